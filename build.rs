@@ -0,0 +1,27 @@
+//! Embed the Git commit of the build, so the running bot can tell which version it is.
+//!
+//! The commit can be overridden with the `TOGGLEBOT_COMMIT` environment variable, for builds that
+//! don't have access to the repository, like the container image.
+
+use std::{env, process::Command};
+
+fn main() {
+    println!("cargo::rerun-if-env-changed=TOGGLEBOT_COMMIT");
+    println!("cargo::rerun-if-changed=.git/HEAD");
+    println!("cargo::rerun-if-changed=.git/refs/heads");
+
+    let commit = env::var("TOGGLEBOT_COMMIT")
+        .ok()
+        .or_else(|| {
+            Command::new("git")
+                .args(["rev-parse", "--short", "HEAD"])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .and_then(|output| String::from_utf8(output.stdout).ok())
+                .map(|commit| commit.trim().to_owned())
+        })
+        .unwrap_or_else(|| "unknown".to_owned());
+
+    println!("cargo::rustc-env=TOGGLEBOT_COMMIT={commit}");
+}
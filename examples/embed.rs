@@ -0,0 +1,40 @@
+//! Minimal embedding of the command handling into another application. Every line from the
+//! standard input is handled as a Twitch chat message, and the replies are printed as plain text.
+
+use std::io::{self, BufRead};
+
+use anyhow::Result;
+use togglebot::{
+    api::{AuthorId, Source},
+    bot::Bot,
+    render,
+    settings::Commands,
+};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let settings = Commands {
+        streamer: "togglebit".to_owned(),
+        ..Commands::default()
+    };
+    let bot = Bot::builder(settings).in_memory().build()?;
+
+    bot.state().add_custom_command(
+        Source::Twitch,
+        "hello",
+        "Hello from the embedded bot!",
+        &AuthorId::Console.user_ref(),
+        bot.clock().now(),
+    )?;
+
+    for line in io::stdin().lock().lines() {
+        let author = AuthorId::Twitch("viewer".to_owned()).into();
+        let resp = bot.handle_text(Source::Twitch, author, &line?).await?;
+
+        if let Some(reply) = resp.and_then(render::plain::response) {
+            println!("{reply}");
+        }
+    }
+
+    Ok(())
+}
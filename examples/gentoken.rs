@@ -1,54 +1,19 @@
-use std::collections::HashMap;
-
 use anyhow::{bail, Result};
-use reqwest::Url;
-use togglebot::settings;
-use twitch_api::{
-    helix::Scope,
-    twitch_oauth2::{TwitchToken, UserToken},
-};
+use togglebot::{settings, twitch::auth};
+use twitch_api::twitch_oauth2::TwitchToken;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let settings = settings::load()?;
-    let url = "http://localhost".parse()?;
-
-    let mut builder = UserToken::builder(
-        settings.twitch.client_id.into(),
-        settings.twitch.client_secret.into(),
-        url,
-    )
-    .force_verify(true)
-    .set_scopes(vec![
-        Scope::ChannelBot,
-        Scope::UserReadChat,
-        Scope::UserWriteChat,
-    ]);
-
-    let (url, _) = builder.generate_url();
-    println!("visit this page: {url}\n");
-
-    println!("paste result url:");
-    let mut url = String::new();
-    std::io::stdin().read_line(&mut url)?;
-    let url = Url::parse(&url)?;
-
-    let pairs = url.query_pairs().collect::<HashMap<_, _>>();
+    let Some(twitch) = settings::load()?.twitch else {
+        bail!("missing Twitch settings");
+    };
 
-    if let Some((state, code)) = pairs.get("state").zip(pairs.get("code")) {
-        let token = builder
-            .get_user_token(&reqwest::Client::new(), state, code)
-            .await?;
+    let token = auth::authorize(twitch.client_id, twitch.client_secret).await?;
 
-        println!("scopes: {}", token.scopes().join(", "));
-        println!("access token: {}", token.access_token.as_str());
-        println!("refresh token: {}", token.refresh_token.unwrap().as_str());
-    } else if let Some((error, description)) =
-        pairs.get("error").zip(pairs.get("error_description"))
-    {
-        bail!("got error from twitch:\n{error}: {description}");
-    } else {
-        bail!("invalid url");
+    println!("scopes: {}", token.scopes().join(", "));
+    println!("access token: {}", token.access_token.as_str());
+    if let Some(refresh_token) = &token.refresh_token {
+        println!("refresh token: {}", refresh_token.as_str());
     }
 
     Ok(())
@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use togglebot::{api::Source, textparse};
+
+fuzz_target!(|data: &str| {
+    for source in Source::ALL {
+        let _ = textparse::parse(data, source, None);
+    }
+});
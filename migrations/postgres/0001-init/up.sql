@@ -0,0 +1,11 @@
+CREATE TABLE admins (
+    id BIGINT PRIMARY KEY
+);
+
+CREATE TABLE custom_commands (
+    id      BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    source  TEXT NOT NULL,
+    name    TEXT NOT NULL,
+    content TEXT NOT NULL,
+    UNIQUE(name, source)
+);
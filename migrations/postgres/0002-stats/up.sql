@@ -0,0 +1,9 @@
+CREATE TABLE command_usage (
+    id    BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    year  BIGINT NOT NULL,
+    month BIGINT NOT NULL,
+    kind  TEXT NOT NULL,
+    name  TEXT NOT NULL,
+    count BIGINT NOT NULL,
+    UNIQUE(year, month, kind, name)
+);
\ No newline at end of file
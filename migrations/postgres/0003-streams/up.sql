@@ -0,0 +1,18 @@
+CREATE TABLE streams (
+    id         BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    stream_id  TEXT NOT NULL UNIQUE,
+    started_at BIGINT NOT NULL,
+    ended_at   BIGINT,
+    title      TEXT NOT NULL,
+    category   TEXT NOT NULL,
+    tag        TEXT
+);
+
+CREATE TABLE stream_usage (
+    id     BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    stream BIGINT NOT NULL REFERENCES streams(id) ON DELETE CASCADE,
+    kind   TEXT NOT NULL,
+    name   TEXT NOT NULL,
+    count  BIGINT NOT NULL,
+    UNIQUE(stream, kind, name)
+);
@@ -0,0 +1,18 @@
+CREATE TABLE user_notes (
+    id         BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    source     TEXT NOT NULL,
+    user_id    TEXT NOT NULL,
+    content    TEXT NOT NULL,
+    author     BIGINT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX user_notes_user ON user_notes (source, user_id);
+
+CREATE TABLE audit_log (
+    id      BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    at      BIGINT NOT NULL,
+    actor   BIGINT NOT NULL,
+    action  TEXT NOT NULL,
+    details TEXT NOT NULL
+);
\ No newline at end of file
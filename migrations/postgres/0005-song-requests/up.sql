@@ -0,0 +1,10 @@
+CREATE TABLE song_requests (
+    id           BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    platform     TEXT NOT NULL,
+    url          TEXT NOT NULL UNIQUE,
+    source       TEXT NOT NULL,
+    user_id      TEXT NOT NULL,
+    requested_at BIGINT NOT NULL
+);
+
+CREATE INDEX song_requests_user ON song_requests (source, user_id);
\ No newline at end of file
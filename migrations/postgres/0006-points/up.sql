@@ -0,0 +1,14 @@
+CREATE TABLE points (
+    id        BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    source    TEXT NOT NULL,
+    user_id   TEXT NOT NULL,
+    balance   BIGINT NOT NULL,
+    earned_at BIGINT NOT NULL,
+    UNIQUE(source, user_id)
+);
+
+CREATE TABLE command_costs (
+    id   BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    cost BIGINT NOT NULL
+);
\ No newline at end of file
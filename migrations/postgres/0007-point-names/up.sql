@@ -0,0 +1,3 @@
+ALTER TABLE points ADD COLUMN name TEXT;
+
+CREATE INDEX points_name ON points (source, name);
\ No newline at end of file
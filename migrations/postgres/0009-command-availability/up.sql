@@ -0,0 +1,5 @@
+CREATE TABLE command_availability (
+    id           BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    name         TEXT NOT NULL UNIQUE,
+    availability TEXT NOT NULL
+);
\ No newline at end of file
@@ -0,0 +1,5 @@
+CREATE TABLE toggles (
+    id      BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    name    TEXT   NOT NULL UNIQUE,
+    enabled BIGINT NOT NULL
+);
\ No newline at end of file
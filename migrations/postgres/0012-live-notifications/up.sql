@@ -0,0 +1,8 @@
+CREATE TABLE live_notifications (
+    id         BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    source     TEXT   NOT NULL,
+    user_id    TEXT   NOT NULL,
+    failures   BIGINT NOT NULL DEFAULT 0,
+    created_at BIGINT NOT NULL,
+    UNIQUE (source, user_id)
+);
\ No newline at end of file
@@ -0,0 +1,13 @@
+CREATE TABLE triggers (
+    id       BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    word     TEXT   NOT NULL UNIQUE,
+    content  TEXT   NOT NULL,
+    cooldown BIGINT NOT NULL
+);
+
+CREATE TABLE disabled_triggers (
+    id     BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    word   TEXT   NOT NULL,
+    source TEXT   NOT NULL,
+    UNIQUE (word, source)
+);
\ No newline at end of file
@@ -0,0 +1,3 @@
+CREATE TABLE admin_roles (
+    id BIGINT PRIMARY KEY
+);
\ No newline at end of file
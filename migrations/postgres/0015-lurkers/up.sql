@@ -0,0 +1,8 @@
+CREATE TABLE lurkers (
+    id      BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    source  TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    message TEXT,
+    since   BIGINT NOT NULL,
+    UNIQUE(source, user_id)
+);
\ No newline at end of file
@@ -0,0 +1,9 @@
+CREATE TABLE birthdays (
+    id       BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    source   TEXT   NOT NULL,
+    user_id  TEXT   NOT NULL,
+    month    BIGINT NOT NULL,
+    day      BIGINT NOT NULL,
+    announce BIGINT NOT NULL DEFAULT 1,
+    UNIQUE (source, user_id)
+);
\ No newline at end of file
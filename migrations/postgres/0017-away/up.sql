@@ -0,0 +1,5 @@
+CREATE TABLE away (
+    id      BIGINT PRIMARY KEY CHECK (id = 1),
+    message TEXT   NOT NULL,
+    since   BIGINT NOT NULL
+);
\ No newline at end of file
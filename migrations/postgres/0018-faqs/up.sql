@@ -0,0 +1,7 @@
+CREATE TABLE faqs (
+    id      BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    name    TEXT   NOT NULL UNIQUE,
+    pattern TEXT   NOT NULL,
+    regex   BIGINT NOT NULL,
+    answer  TEXT   NOT NULL
+);
\ No newline at end of file
@@ -0,0 +1,10 @@
+CREATE TABLE highlights (
+    id         BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    guild_id   BIGINT,
+    channel_id BIGINT NOT NULL,
+    message_id BIGINT NOT NULL UNIQUE,
+    author     TEXT   NOT NULL,
+    content    TEXT   NOT NULL,
+    pinned_by  BIGINT NOT NULL,
+    pinned_at  BIGINT NOT NULL
+);
\ No newline at end of file
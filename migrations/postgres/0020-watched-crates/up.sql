@@ -0,0 +1,6 @@
+CREATE TABLE watched_crates (
+    id      BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    name    TEXT NOT NULL UNIQUE,
+    version TEXT NOT NULL,
+    etag    TEXT
+);
\ No newline at end of file
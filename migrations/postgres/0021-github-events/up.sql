@@ -0,0 +1,7 @@
+CREATE TABLE github_events (
+    id   BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    repo TEXT   NOT NULL,
+    kind TEXT   NOT NULL,
+    item BIGINT NOT NULL,
+    UNIQUE (repo, kind, item)
+);
\ No newline at end of file
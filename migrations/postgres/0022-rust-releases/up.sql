@@ -0,0 +1,4 @@
+CREATE TABLE rust_releases (
+    id      BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    version TEXT   NOT NULL UNIQUE
+);
\ No newline at end of file
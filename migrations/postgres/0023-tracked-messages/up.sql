@@ -0,0 +1,10 @@
+CREATE TABLE tracked_messages (
+    id         BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    message_id BIGINT NOT NULL,
+    kind       TEXT   NOT NULL,
+    record     BIGINT NOT NULL,
+    UNIQUE (message_id, kind, record)
+);
+
+INSERT INTO tracked_messages (message_id, kind, record)
+SELECT message_id, 'highlight', id FROM highlights;
\ No newline at end of file
@@ -0,0 +1,6 @@
+CREATE TABLE command_channels (
+    id         BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    guild_id   BIGINT NOT NULL,
+    channel_id BIGINT NOT NULL UNIQUE,
+    rule       TEXT   NOT NULL
+);
\ No newline at end of file
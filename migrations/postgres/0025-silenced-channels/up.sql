@@ -0,0 +1,5 @@
+CREATE TABLE silenced_channels (
+    id         BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    channel_id BIGINT NOT NULL UNIQUE,
+    until      BIGINT NOT NULL
+);
\ No newline at end of file
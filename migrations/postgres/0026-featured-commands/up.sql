@@ -0,0 +1,5 @@
+CREATE TABLE featured_commands (
+    id          BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    name        TEXT   NOT NULL,
+    featured_at BIGINT NOT NULL
+);
\ No newline at end of file
@@ -0,0 +1,5 @@
+ALTER TABLE custom_commands
+    ADD COLUMN created_by TEXT,
+    ADD COLUMN created_at BIGINT,
+    ADD COLUMN updated_by TEXT,
+    ADD COLUMN updated_at BIGINT;
\ No newline at end of file
@@ -0,0 +1,15 @@
+CREATE TABLE daily_usage (
+    id    BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    day   BIGINT NOT NULL,
+    kind  TEXT NOT NULL,
+    name  TEXT NOT NULL,
+    count BIGINT NOT NULL,
+    UNIQUE(day, kind, name)
+);
+
+CREATE TABLE usage_alerts (
+    id        BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    name      TEXT NOT NULL UNIQUE,
+    threshold BIGINT NOT NULL,
+    tripped   BIGINT
+);
\ No newline at end of file
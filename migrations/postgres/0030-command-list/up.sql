@@ -0,0 +1,5 @@
+CREATE TABLE command_list (
+    id         BIGINT PRIMARY KEY CHECK (id = 1),
+    channel_id BIGINT NOT NULL,
+    message_id BIGINT
+);
\ No newline at end of file
@@ -0,0 +1,18 @@
+CREATE TABLE watch_time (
+    id      BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    year    BIGINT NOT NULL,
+    month   BIGINT NOT NULL,
+    source  TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    name    TEXT NOT NULL,
+    seconds BIGINT NOT NULL,
+    UNIQUE(year, month, source, user_id)
+);
+
+CREATE TABLE watch_time_opt_outs (
+    id         BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    source     TEXT NOT NULL,
+    user_id    TEXT NOT NULL,
+    created_at BIGINT NOT NULL,
+    UNIQUE(source, user_id)
+);
\ No newline at end of file
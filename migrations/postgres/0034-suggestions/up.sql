@@ -0,0 +1,13 @@
+CREATE TABLE suggestions (
+    id          BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    source      TEXT NOT NULL,
+    user_id     TEXT NOT NULL,
+    name        TEXT NOT NULL,
+    content     TEXT NOT NULL,
+    status      TEXT NOT NULL,
+    reason      TEXT,
+    created_at  BIGINT NOT NULL,
+    reviewed_at BIGINT
+);
+
+CREATE INDEX suggestions_status ON suggestions (status);
\ No newline at end of file
@@ -0,0 +1,12 @@
+CREATE TABLE github_issues (
+    id         BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+    repo       TEXT NOT NULL,
+    number     BIGINT NOT NULL,
+    url        TEXT NOT NULL,
+    title      TEXT NOT NULL,
+    suggestion BIGINT UNIQUE REFERENCES suggestions(id) ON DELETE SET NULL,
+    message    TEXT,
+    source     TEXT NOT NULL,
+    user_id    TEXT NOT NULL,
+    created_at BIGINT NOT NULL
+);
\ No newline at end of file
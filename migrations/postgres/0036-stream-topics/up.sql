@@ -0,0 +1,7 @@
+ALTER TABLE streams ADD COLUMN topic TEXT;
+
+CREATE TABLE upcoming_topic (
+    id      BIGINT PRIMARY KEY CHECK (id = 1),
+    content TEXT   NOT NULL,
+    set_at  BIGINT NOT NULL
+);
\ No newline at end of file
@@ -0,0 +1,2 @@
+DROP TABLE stream_usage;
+DROP TABLE streams;
\ No newline at end of file
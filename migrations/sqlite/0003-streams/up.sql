@@ -0,0 +1,18 @@
+CREATE TABLE streams (
+    id         INTEGER PRIMARY KEY,
+    stream_id  TEXT NOT NULL UNIQUE,
+    started_at INTEGER NOT NULL,
+    ended_at   INTEGER,
+    title      TEXT NOT NULL,
+    category   TEXT NOT NULL,
+    tag        TEXT
+) STRICT;
+
+CREATE TABLE stream_usage (
+    id     INTEGER PRIMARY KEY,
+    stream INTEGER NOT NULL REFERENCES streams(id) ON DELETE CASCADE,
+    kind   TEXT NOT NULL,
+    name   TEXT NOT NULL,
+    count  INTEGER NOT NULL,
+    UNIQUE(stream, kind, name)
+) STRICT;
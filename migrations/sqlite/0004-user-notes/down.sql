@@ -0,0 +1,2 @@
+DROP TABLE audit_log;
+DROP TABLE user_notes;
\ No newline at end of file
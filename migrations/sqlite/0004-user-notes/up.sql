@@ -0,0 +1,18 @@
+CREATE TABLE user_notes (
+    id         INTEGER PRIMARY KEY,
+    source     TEXT NOT NULL,
+    user_id    TEXT NOT NULL,
+    content    TEXT NOT NULL,
+    author     INTEGER NOT NULL,
+    created_at INTEGER NOT NULL
+) STRICT;
+
+CREATE INDEX user_notes_user ON user_notes (source, user_id);
+
+CREATE TABLE audit_log (
+    id      INTEGER PRIMARY KEY,
+    at      INTEGER NOT NULL,
+    actor   INTEGER NOT NULL,
+    action  TEXT NOT NULL,
+    details TEXT NOT NULL
+) STRICT;
\ No newline at end of file
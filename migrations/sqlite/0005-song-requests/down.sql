@@ -0,0 +1 @@
+DROP TABLE song_requests;
\ No newline at end of file
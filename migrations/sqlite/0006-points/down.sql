@@ -0,0 +1,2 @@
+DROP TABLE command_costs;
+DROP TABLE points;
\ No newline at end of file
@@ -0,0 +1,14 @@
+CREATE TABLE points (
+    id        INTEGER PRIMARY KEY,
+    source    TEXT NOT NULL,
+    user_id   TEXT NOT NULL,
+    balance   INTEGER NOT NULL,
+    earned_at INTEGER NOT NULL,
+    UNIQUE(source, user_id)
+) STRICT;
+
+CREATE TABLE command_costs (
+    id   INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    cost INTEGER NOT NULL
+) STRICT;
\ No newline at end of file
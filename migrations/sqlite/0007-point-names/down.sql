@@ -0,0 +1,3 @@
+DROP INDEX points_name;
+
+ALTER TABLE points DROP COLUMN name;
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE pronouns;
\ No newline at end of file
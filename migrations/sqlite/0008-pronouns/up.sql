@@ -0,0 +1,7 @@
+CREATE TABLE pronouns (
+    id       INTEGER PRIMARY KEY,
+    source   TEXT NOT NULL,
+    user_id  TEXT NOT NULL,
+    pronouns TEXT NOT NULL,
+    UNIQUE(source, user_id)
+) STRICT;
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE command_availability;
\ No newline at end of file
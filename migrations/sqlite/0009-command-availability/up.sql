@@ -0,0 +1,5 @@
+CREATE TABLE command_availability (
+    id           INTEGER PRIMARY KEY,
+    name         TEXT NOT NULL UNIQUE,
+    availability TEXT NOT NULL
+) STRICT;
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE custom_command_history;
\ No newline at end of file
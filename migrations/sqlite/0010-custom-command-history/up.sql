@@ -0,0 +1,10 @@
+CREATE TABLE custom_command_history (
+    id         INTEGER PRIMARY KEY,
+    source     TEXT    NOT NULL,
+    name       TEXT    NOT NULL,
+    content    TEXT,
+    author     TEXT    NOT NULL,
+    changed_at INTEGER NOT NULL
+) STRICT;
+
+CREATE INDEX custom_command_history_name ON custom_command_history (name, source);
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE toggles;
\ No newline at end of file
@@ -0,0 +1,5 @@
+CREATE TABLE toggles (
+    id      INTEGER PRIMARY KEY,
+    name    TEXT    NOT NULL UNIQUE,
+    enabled INTEGER NOT NULL
+) STRICT;
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE live_notifications;
\ No newline at end of file
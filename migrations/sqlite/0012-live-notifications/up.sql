@@ -0,0 +1,8 @@
+CREATE TABLE live_notifications (
+    id         INTEGER PRIMARY KEY,
+    source     TEXT    NOT NULL,
+    user_id    TEXT    NOT NULL,
+    failures   INTEGER NOT NULL DEFAULT 0,
+    created_at INTEGER NOT NULL,
+    UNIQUE (source, user_id)
+) STRICT;
\ No newline at end of file
@@ -0,0 +1,2 @@
+DROP TABLE disabled_triggers;
+DROP TABLE triggers;
\ No newline at end of file
@@ -0,0 +1,13 @@
+CREATE TABLE triggers (
+    id       INTEGER PRIMARY KEY,
+    word     TEXT    NOT NULL UNIQUE,
+    content  TEXT    NOT NULL,
+    cooldown INTEGER NOT NULL
+) STRICT;
+
+CREATE TABLE disabled_triggers (
+    id     INTEGER PRIMARY KEY,
+    word   TEXT    NOT NULL,
+    source TEXT    NOT NULL,
+    UNIQUE (word, source)
+) STRICT;
\ No newline at end of file
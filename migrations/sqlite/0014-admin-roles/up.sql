@@ -0,0 +1,3 @@
+CREATE TABLE admin_roles (
+    id INTEGER PRIMARY KEY
+) STRICT;
\ No newline at end of file
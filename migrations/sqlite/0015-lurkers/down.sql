@@ -0,0 +1 @@
+DROP TABLE lurkers;
\ No newline at end of file
@@ -0,0 +1,8 @@
+CREATE TABLE lurkers (
+    id      INTEGER PRIMARY KEY,
+    source  TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    message TEXT,
+    since   INTEGER NOT NULL,
+    UNIQUE(source, user_id)
+) STRICT;
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE birthdays;
\ No newline at end of file
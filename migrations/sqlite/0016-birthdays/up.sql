@@ -0,0 +1,9 @@
+CREATE TABLE birthdays (
+    id       INTEGER PRIMARY KEY,
+    source   TEXT NOT NULL,
+    user_id  TEXT NOT NULL,
+    month    INTEGER NOT NULL,
+    day      INTEGER NOT NULL,
+    announce INTEGER NOT NULL DEFAULT 1,
+    UNIQUE(source, user_id)
+) STRICT;
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE away;
\ No newline at end of file
@@ -0,0 +1,5 @@
+CREATE TABLE away (
+    id      INTEGER PRIMARY KEY CHECK (id = 1),
+    message TEXT    NOT NULL,
+    since   INTEGER NOT NULL
+) STRICT;
\ No newline at end of file
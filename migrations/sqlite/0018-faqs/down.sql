@@ -0,0 +1 @@
+DROP TABLE faqs;
\ No newline at end of file
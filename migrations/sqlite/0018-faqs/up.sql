@@ -0,0 +1,7 @@
+CREATE TABLE faqs (
+    id      INTEGER PRIMARY KEY,
+    name    TEXT    NOT NULL UNIQUE,
+    pattern TEXT    NOT NULL,
+    regex   INTEGER NOT NULL,
+    answer  TEXT    NOT NULL
+) STRICT;
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE highlights;
\ No newline at end of file
@@ -0,0 +1,10 @@
+CREATE TABLE highlights (
+    id         INTEGER PRIMARY KEY,
+    guild_id   INTEGER,
+    channel_id INTEGER NOT NULL,
+    message_id INTEGER NOT NULL UNIQUE,
+    author     TEXT    NOT NULL,
+    content    TEXT    NOT NULL,
+    pinned_by  INTEGER NOT NULL,
+    pinned_at  INTEGER NOT NULL
+) STRICT;
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE watched_crates;
\ No newline at end of file
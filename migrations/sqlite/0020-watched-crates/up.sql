@@ -0,0 +1,6 @@
+CREATE TABLE watched_crates (
+    id      INTEGER PRIMARY KEY,
+    name    TEXT NOT NULL UNIQUE,
+    version TEXT NOT NULL,
+    etag    TEXT
+) STRICT;
\ No newline at end of file
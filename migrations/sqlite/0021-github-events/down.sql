@@ -0,0 +1 @@
+DROP TABLE github_events;
\ No newline at end of file
@@ -0,0 +1,7 @@
+CREATE TABLE github_events (
+    id   INTEGER PRIMARY KEY,
+    repo TEXT    NOT NULL,
+    kind TEXT    NOT NULL,
+    item INTEGER NOT NULL,
+    UNIQUE (repo, kind, item)
+) STRICT;
\ No newline at end of file
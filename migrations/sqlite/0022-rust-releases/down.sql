@@ -0,0 +1 @@
+DROP TABLE rust_releases;
\ No newline at end of file
@@ -0,0 +1,4 @@
+CREATE TABLE rust_releases (
+    id      INTEGER PRIMARY KEY,
+    version TEXT    NOT NULL UNIQUE
+) STRICT;
\ No newline at end of file
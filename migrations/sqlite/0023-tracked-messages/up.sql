@@ -0,0 +1,10 @@
+CREATE TABLE tracked_messages (
+    id         INTEGER PRIMARY KEY,
+    message_id INTEGER NOT NULL,
+    kind       TEXT    NOT NULL,
+    record     INTEGER NOT NULL,
+    UNIQUE (message_id, kind, record)
+) STRICT;
+
+INSERT INTO tracked_messages (message_id, kind, record)
+SELECT message_id, 'highlight', id FROM highlights;
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE command_channels;
\ No newline at end of file
@@ -0,0 +1,6 @@
+CREATE TABLE command_channels (
+    id         INTEGER PRIMARY KEY,
+    guild_id   INTEGER NOT NULL,
+    channel_id INTEGER NOT NULL UNIQUE,
+    rule       TEXT    NOT NULL
+) STRICT;
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE silenced_channels;
\ No newline at end of file
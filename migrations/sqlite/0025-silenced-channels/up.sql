@@ -0,0 +1,5 @@
+CREATE TABLE silenced_channels (
+    id         INTEGER PRIMARY KEY,
+    channel_id INTEGER NOT NULL UNIQUE,
+    until      INTEGER NOT NULL
+) STRICT;
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE featured_commands;
\ No newline at end of file
@@ -0,0 +1,5 @@
+CREATE TABLE featured_commands (
+    id          INTEGER PRIMARY KEY,
+    name        TEXT    NOT NULL,
+    featured_at INTEGER NOT NULL
+) STRICT;
\ No newline at end of file
@@ -0,0 +1,8 @@
+CREATE TABLE user_prefs (
+    id      INTEGER PRIMARY KEY,
+    source  TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    key     TEXT NOT NULL,
+    value   TEXT NOT NULL,
+    UNIQUE(source, user_id, key)
+) STRICT;
\ No newline at end of file
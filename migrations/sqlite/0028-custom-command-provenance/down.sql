@@ -0,0 +1,4 @@
+ALTER TABLE custom_commands DROP COLUMN updated_at;
+ALTER TABLE custom_commands DROP COLUMN updated_by;
+ALTER TABLE custom_commands DROP COLUMN created_at;
+ALTER TABLE custom_commands DROP COLUMN created_by;
\ No newline at end of file
@@ -0,0 +1,4 @@
+ALTER TABLE custom_commands ADD COLUMN created_by TEXT;
+ALTER TABLE custom_commands ADD COLUMN created_at INTEGER;
+ALTER TABLE custom_commands ADD COLUMN updated_by TEXT;
+ALTER TABLE custom_commands ADD COLUMN updated_at INTEGER;
\ No newline at end of file
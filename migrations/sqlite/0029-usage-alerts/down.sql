@@ -0,0 +1,2 @@
+DROP TABLE usage_alerts;
+DROP TABLE daily_usage;
\ No newline at end of file
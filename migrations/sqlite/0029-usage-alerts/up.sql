@@ -0,0 +1,15 @@
+CREATE TABLE daily_usage (
+    id    INTEGER PRIMARY KEY,
+    day   INTEGER NOT NULL,
+    kind  TEXT NOT NULL,
+    name  TEXT NOT NULL,
+    count INTEGER NOT NULL,
+    UNIQUE(day, kind, name)
+) STRICT;
+
+CREATE TABLE usage_alerts (
+    id        INTEGER PRIMARY KEY,
+    name      TEXT NOT NULL UNIQUE,
+    threshold INTEGER NOT NULL,
+    tripped   INTEGER
+) STRICT;
\ No newline at end of file
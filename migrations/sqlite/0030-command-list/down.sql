@@ -0,0 +1 @@
+DROP TABLE command_list;
\ No newline at end of file
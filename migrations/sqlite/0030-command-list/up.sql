@@ -0,0 +1,5 @@
+CREATE TABLE command_list (
+    id         INTEGER PRIMARY KEY CHECK (id = 1),
+    channel_id INTEGER NOT NULL,
+    message_id INTEGER
+) STRICT;
\ No newline at end of file
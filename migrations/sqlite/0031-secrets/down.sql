@@ -0,0 +1 @@
+DROP TABLE secrets;
\ No newline at end of file
@@ -0,0 +1,4 @@
+CREATE TABLE secrets (
+    name  TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+) STRICT;
\ No newline at end of file
@@ -0,0 +1,2 @@
+DROP TABLE watch_time_opt_outs;
+DROP TABLE watch_time;
\ No newline at end of file
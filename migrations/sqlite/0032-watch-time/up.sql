@@ -0,0 +1,18 @@
+CREATE TABLE watch_time (
+    id      INTEGER PRIMARY KEY,
+    year    INTEGER NOT NULL,
+    month   INTEGER NOT NULL,
+    source  TEXT NOT NULL,
+    user_id TEXT NOT NULL,
+    name    TEXT NOT NULL,
+    seconds INTEGER NOT NULL,
+    UNIQUE(year, month, source, user_id)
+) STRICT;
+
+CREATE TABLE watch_time_opt_outs (
+    id         INTEGER PRIMARY KEY,
+    source     TEXT NOT NULL,
+    user_id    TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    UNIQUE(source, user_id)
+) STRICT;
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE stream_titles;
\ No newline at end of file
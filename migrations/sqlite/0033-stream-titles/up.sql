@@ -0,0 +1,12 @@
+CREATE TABLE stream_titles (
+    id         INTEGER PRIMARY KEY,
+    stream     INTEGER NOT NULL REFERENCES streams(id) ON DELETE CASCADE,
+    title      TEXT NOT NULL,
+    category   TEXT NOT NULL,
+    changed_at INTEGER NOT NULL
+) STRICT;
+
+CREATE INDEX stream_titles_stream ON stream_titles (stream);
+
+INSERT INTO stream_titles (stream, title, category, changed_at)
+SELECT id, title, category, started_at FROM streams;
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE suggestions;
\ No newline at end of file
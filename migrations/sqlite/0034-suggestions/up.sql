@@ -0,0 +1,13 @@
+CREATE TABLE suggestions (
+    id          INTEGER PRIMARY KEY,
+    source      TEXT NOT NULL,
+    user_id     TEXT NOT NULL,
+    name        TEXT NOT NULL,
+    content     TEXT NOT NULL,
+    status      TEXT NOT NULL,
+    reason      TEXT,
+    created_at  INTEGER NOT NULL,
+    reviewed_at INTEGER
+) STRICT;
+
+CREATE INDEX suggestions_status ON suggestions (status);
\ No newline at end of file
@@ -0,0 +1 @@
+DROP TABLE github_issues;
\ No newline at end of file
@@ -0,0 +1,12 @@
+CREATE TABLE github_issues (
+    id         INTEGER PRIMARY KEY,
+    repo       TEXT NOT NULL,
+    number     INTEGER NOT NULL,
+    url        TEXT NOT NULL,
+    title      TEXT NOT NULL,
+    suggestion INTEGER UNIQUE REFERENCES suggestions(id) ON DELETE SET NULL,
+    message    TEXT,
+    source     TEXT NOT NULL,
+    user_id    TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+) STRICT;
\ No newline at end of file
@@ -0,0 +1,2 @@
+DROP TABLE upcoming_topic;
+ALTER TABLE streams DROP COLUMN topic;
\ No newline at end of file
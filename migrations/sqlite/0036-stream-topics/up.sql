@@ -0,0 +1,7 @@
+ALTER TABLE streams ADD COLUMN topic TEXT;
+
+CREATE TABLE upcoming_topic (
+    id      INTEGER PRIMARY KEY CHECK (id = 1),
+    content TEXT    NOT NULL,
+    set_at  INTEGER NOT NULL
+) STRICT;
\ No newline at end of file
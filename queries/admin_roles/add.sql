@@ -0,0 +1 @@
+INSERT INTO admin_roles (id) VALUES (?) ON CONFLICT DO NOTHING;
\ No newline at end of file
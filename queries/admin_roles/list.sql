@@ -0,0 +1 @@
+SELECT id FROM admin_roles;
\ No newline at end of file
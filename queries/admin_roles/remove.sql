@@ -0,0 +1 @@
+DELETE FROM admin_roles WHERE id = ?;
\ No newline at end of file
@@ -0,0 +1 @@
+INSERT INTO audit_log (at, actor, action, details) VALUES (?, ?, ?, ?);
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM away WHERE id = 1 RETURNING since;
\ No newline at end of file
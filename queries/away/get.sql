@@ -0,0 +1 @@
+SELECT message, since FROM away WHERE id = 1;
\ No newline at end of file
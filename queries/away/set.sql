@@ -0,0 +1,2 @@
+INSERT INTO away (id, message, since) VALUES (1, ?, ?)
+ON CONFLICT (id) DO UPDATE SET message = excluded.message, since = excluded.since;
\ No newline at end of file
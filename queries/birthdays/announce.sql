@@ -0,0 +1 @@
+UPDATE birthdays SET announce = ? WHERE source = ? AND user_id = ? RETURNING id;
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT month, day, announce FROM birthdays WHERE source = ? AND user_id = ?;
\ No newline at end of file
@@ -0,0 +1,2 @@
+SELECT user_id FROM birthdays WHERE source = ? AND month = ? AND day = ? AND announce = 1
+ORDER BY id;
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM birthdays WHERE source = ? AND user_id = ? RETURNING id;
\ No newline at end of file
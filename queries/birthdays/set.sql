@@ -0,0 +1,2 @@
+INSERT INTO birthdays (source, user_id, month, day) VALUES (?, ?, ?, ?)
+ON CONFLICT (source, user_id) DO UPDATE SET month = excluded.month, day = excluded.day;
\ No newline at end of file
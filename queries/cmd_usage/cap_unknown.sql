@@ -0,0 +1,6 @@
+DELETE FROM command_usage WHERE id IN (
+    SELECT id FROM (
+        SELECT id, ROW_NUMBER() OVER (PARTITION BY year, month ORDER BY count DESC, name) AS rank
+        FROM command_usage WHERE kind = 'unknown'
+    ) AS ranked WHERE rank > ?
+);
\ No newline at end of file
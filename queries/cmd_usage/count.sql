@@ -0,0 +1 @@
+SELECT COUNT(*) FROM command_usage;
\ No newline at end of file
@@ -0,0 +1,2 @@
+SELECT CAST(COALESCE(SUM(count), 0) AS BIGINT) FROM daily_usage
+WHERE day = ? AND kind IN ('builtin', 'custom') AND name = ?;
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT CAST(COALESCE(SUM(count), 0) AS BIGINT) FROM daily_usage WHERE day = ? AND kind = ?;
\ No newline at end of file
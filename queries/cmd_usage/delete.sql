@@ -1 +1 @@
-DELETE FROM command_usage WHERE kind = "custom" AND name = ?;
\ No newline at end of file
+DELETE FROM command_usage WHERE kind = 'custom' AND name = ?;
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM command_usage WHERE year * 12 + month < ?;
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM daily_usage WHERE kind = 'custom' AND name = ?;
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM daily_usage WHERE day < ?;
\ No newline at end of file
@@ -1,2 +1,2 @@
-INSERT INTO command_usage (year, month, kind, name, count) VALUES (?, ?, ?, ?, 1)
-ON CONFLICT (year, month, kind, name) DO UPDATE SET count = count + 1;
\ No newline at end of file
+INSERT INTO command_usage (year, month, kind, name, count) VALUES (?, ?, ?, ?, ?)
+ON CONFLICT (year, month, kind, name) DO UPDATE SET count = command_usage.count + excluded.count;
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO daily_usage (day, kind, name, count) VALUES (?, ?, ?, ?)
+ON CONFLICT (day, kind, name) DO UPDATE SET count = daily_usage.count + excluded.count;
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO stream_usage (stream, kind, name, count) VALUES (?, ?, ?, ?)
+ON CONFLICT (stream, kind, name) DO UPDATE SET count = stream_usage.count + excluded.count;
\ No newline at end of file
@@ -1,2 +1,2 @@
-SELECT kind, name, count FROM command_usage WHERE year = ? AND month = ?
+SELECT name, count FROM command_usage WHERE year = ? AND month = ? AND kind = ?
 ORDER BY count DESC;
\ No newline at end of file
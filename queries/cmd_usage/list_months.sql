@@ -0,0 +1,2 @@
+SELECT DISTINCT year, month FROM command_usage
+ORDER BY year DESC, month DESC;
\ No newline at end of file
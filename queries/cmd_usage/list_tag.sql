@@ -0,0 +1,5 @@
+SELECT u.name, CAST(SUM(u.count) AS BIGINT) AS count FROM stream_usage u
+JOIN streams s ON s.id = u.stream
+WHERE s.tag = ? AND u.kind = ?
+GROUP BY u.kind, u.name
+ORDER BY SUM(u.count) DESC;
\ No newline at end of file
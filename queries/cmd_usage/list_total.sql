@@ -1,3 +1,3 @@
-SELECT kind, name, SUM(count) AS count FROM command_usage
+SELECT name, CAST(SUM(count) AS BIGINT) AS count FROM command_usage WHERE kind = ?
 GROUP BY year, month, kind, name
 ORDER BY SUM(count) DESC;
\ No newline at end of file
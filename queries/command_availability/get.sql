@@ -0,0 +1 @@
+SELECT availability FROM command_availability WHERE name = ?;
\ No newline at end of file
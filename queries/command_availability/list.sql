@@ -0,0 +1 @@
+SELECT name, availability FROM command_availability ORDER BY name;
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM command_availability WHERE name = ?;
\ No newline at end of file
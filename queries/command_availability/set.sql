@@ -0,0 +1,2 @@
+INSERT INTO command_availability (name, availability) VALUES (?, ?)
+ON CONFLICT (name) DO UPDATE SET availability = excluded.availability;
\ No newline at end of file
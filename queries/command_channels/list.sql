@@ -0,0 +1 @@
+SELECT channel_id, rule FROM command_channels WHERE guild_id = ? ORDER BY channel_id;
\ No newline at end of file
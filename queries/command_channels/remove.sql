@@ -0,0 +1 @@
+DELETE FROM command_channels WHERE channel_id = ?;
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO command_channels (guild_id, channel_id, rule) VALUES (?, ?, ?)
+ON CONFLICT (channel_id) DO UPDATE SET guild_id = excluded.guild_id, rule = excluded.rule;
\ No newline at end of file
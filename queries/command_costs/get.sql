@@ -0,0 +1 @@
+SELECT cost FROM command_costs WHERE name = ?;
\ No newline at end of file
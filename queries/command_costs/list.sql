@@ -0,0 +1 @@
+SELECT name, cost FROM command_costs ORDER BY name;
\ No newline at end of file
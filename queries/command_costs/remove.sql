@@ -0,0 +1 @@
+DELETE FROM command_costs WHERE name = ?;
\ No newline at end of file
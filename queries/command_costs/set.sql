@@ -0,0 +1,2 @@
+INSERT INTO command_costs (name, cost) VALUES (?, ?)
+ON CONFLICT (name) DO UPDATE SET cost = excluded.cost;
\ No newline at end of file
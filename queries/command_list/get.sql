@@ -0,0 +1 @@
+SELECT channel_id, message_id FROM command_list WHERE id = 1;
\ No newline at end of file
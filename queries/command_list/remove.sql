@@ -0,0 +1 @@
+DELETE FROM command_list WHERE id = 1;
\ No newline at end of file
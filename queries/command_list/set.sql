@@ -0,0 +1,6 @@
+INSERT INTO command_list (id, channel_id, message_id) VALUES (1, ?, NULL)
+ON CONFLICT (id) DO UPDATE SET
+    channel_id = excluded.channel_id,
+    message_id = CASE
+        WHEN command_list.channel_id = excluded.channel_id THEN command_list.message_id
+    END;
\ No newline at end of file
@@ -0,0 +1 @@
+UPDATE command_list SET message_id = ? WHERE id = 1 AND channel_id = ?;
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO custom_command_history (source, name, content, author, changed_at)
+VALUES (?, ?, ?, ?, ?);
\ No newline at end of file
@@ -0,0 +1,3 @@
+SELECT id, source, content, author, changed_at FROM custom_command_history
+WHERE name = ?
+ORDER BY changed_at DESC, id DESC;
\ No newline at end of file
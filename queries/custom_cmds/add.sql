@@ -1,2 +1,5 @@
-INSERT INTO custom_commands (source, name, content) VALUES (?, ?, ?)
-ON CONFLICT (source, name) DO UPDATE SET content = excluded.content;
\ No newline at end of file
+INSERT INTO custom_commands (source, name, content, created_by, created_at) VALUES (?, ?, ?, ?, ?)
+ON CONFLICT (source, name) DO UPDATE SET
+    content = excluded.content,
+    updated_by = excluded.created_by,
+    updated_at = excluded.created_at;
\ No newline at end of file
@@ -0,0 +1,3 @@
+SELECT name, source, content, created_by, created_at, updated_by, updated_at
+FROM custom_commands
+WHERE name = ?;
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO faqs (name, pattern, regex, answer) VALUES (?, ?, ?, ?)
+ON CONFLICT (name) DO UPDATE SET pattern = excluded.pattern, regex = excluded.regex, answer = excluded.answer;
\ No newline at end of file
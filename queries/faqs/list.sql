@@ -0,0 +1 @@
+SELECT name, pattern, regex, answer FROM faqs ORDER BY name;
\ No newline at end of file
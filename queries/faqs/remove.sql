@@ -0,0 +1 @@
+DELETE FROM faqs WHERE name = ? RETURNING id;
\ No newline at end of file
@@ -0,0 +1 @@
+INSERT INTO featured_commands (name, featured_at) VALUES (?, ?);
\ No newline at end of file
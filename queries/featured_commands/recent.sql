@@ -0,0 +1 @@
+SELECT name FROM featured_commands ORDER BY id DESC LIMIT ?;
\ No newline at end of file
@@ -0,0 +1,2 @@
+DELETE FROM featured_commands
+WHERE id NOT IN (SELECT id FROM featured_commands ORDER BY id DESC LIMIT ?);
\ No newline at end of file
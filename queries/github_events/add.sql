@@ -0,0 +1,2 @@
+INSERT INTO github_events (repo, kind, item) VALUES (?, ?, ?)
+ON CONFLICT (repo, kind, item) DO NOTHING RETURNING id;
\ No newline at end of file
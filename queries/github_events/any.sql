@@ -0,0 +1 @@
+SELECT 1 FROM github_events WHERE repo = ? AND kind = ? LIMIT 1;
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO github_issues (repo, number, url, title, suggestion, message, source, user_id, created_at)
+VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?);
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT number, url, title FROM github_issues WHERE suggestion = ?;
\ No newline at end of file
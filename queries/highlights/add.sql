@@ -0,0 +1,4 @@
+INSERT INTO highlights (guild_id, channel_id, message_id, author, content, pinned_by, pinned_at)
+VALUES (?, ?, ?, ?, ?, ?, ?)
+ON CONFLICT (message_id) DO UPDATE SET pinned_by = excluded.pinned_by, pinned_at = excluded.pinned_at
+RETURNING id;
\ No newline at end of file
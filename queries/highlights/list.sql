@@ -0,0 +1,2 @@
+SELECT guild_id AS guild, channel_id AS channel, message_id AS message, author, content
+FROM highlights ORDER BY pinned_at DESC, id DESC LIMIT ?;
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM highlights WHERE id = ?;
\ No newline at end of file
@@ -0,0 +1 @@
+UPDATE highlights SET content = ? WHERE id = ?;
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO live_notifications (source, user_id, created_at) VALUES (?, ?, ?)
+ON CONFLICT (source, user_id) DO NOTHING;
\ No newline at end of file
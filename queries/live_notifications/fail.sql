@@ -0,0 +1,2 @@
+UPDATE live_notifications SET failures = failures + 1 WHERE source = ? AND user_id = ?
+RETURNING failures;
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT id FROM live_notifications WHERE source = ? AND user_id = ?;
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT user_id FROM live_notifications WHERE source = ? ORDER BY id;
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM live_notifications WHERE source = ? AND user_id = ?;
\ No newline at end of file
@@ -0,0 +1 @@
+UPDATE live_notifications SET failures = 0 WHERE source = ? AND user_id = ?;
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM lurkers;
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM lurkers WHERE source = ? AND user_id = ? RETURNING since;
\ No newline at end of file
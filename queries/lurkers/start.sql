@@ -0,0 +1,2 @@
+INSERT INTO lurkers (source, user_id, message, since) VALUES (?, ?, ?, ?)
+ON CONFLICT (source, user_id) DO UPDATE SET message = excluded.message;
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT balance FROM points WHERE source = ? AND user_id = ?;
\ No newline at end of file
@@ -0,0 +1,7 @@
+INSERT INTO points (source, user_id, name, balance, earned_at) VALUES (?1, ?2, ?3, ?4, ?5)
+ON CONFLICT (source, user_id) DO UPDATE SET
+    name = excluded.name,
+    balance = points.balance + excluded.balance,
+    earned_at = excluded.earned_at
+WHERE points.earned_at <= ?6
+RETURNING balance;
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT source, user_id AS id FROM points WHERE source = ? AND name = ?;
\ No newline at end of file
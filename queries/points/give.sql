@@ -0,0 +1,2 @@
+INSERT INTO points (source, user_id, balance, earned_at) VALUES (?, ?, ?, 0)
+ON CONFLICT (source, user_id) DO UPDATE SET balance = points.balance + excluded.balance;
\ No newline at end of file
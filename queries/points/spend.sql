@@ -0,0 +1,3 @@
+UPDATE points SET balance = balance - ?3
+WHERE source = ?1 AND user_id = ?2 AND balance >= ?3
+RETURNING balance;
\ No newline at end of file
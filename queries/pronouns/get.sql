@@ -0,0 +1 @@
+SELECT pronouns FROM pronouns WHERE source = ? AND user_id = ?;
\ No newline at end of file
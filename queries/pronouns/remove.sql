@@ -0,0 +1 @@
+DELETE FROM pronouns WHERE source = ? AND user_id = ? RETURNING pronouns;
\ No newline at end of file
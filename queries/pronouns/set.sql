@@ -0,0 +1,2 @@
+INSERT INTO pronouns (source, user_id, pronouns) VALUES (?, ?, ?)
+ON CONFLICT (source, user_id) DO UPDATE SET pronouns = excluded.pronouns;
\ No newline at end of file
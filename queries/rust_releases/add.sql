@@ -0,0 +1,2 @@
+INSERT INTO rust_releases (version) VALUES (?)
+ON CONFLICT (version) DO NOTHING RETURNING id;
\ No newline at end of file
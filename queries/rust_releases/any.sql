@@ -0,0 +1 @@
+SELECT 1 FROM rust_releases LIMIT 1;
\ No newline at end of file
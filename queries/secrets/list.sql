@@ -0,0 +1 @@
+SELECT name, value FROM secrets ORDER BY name;
\ No newline at end of file
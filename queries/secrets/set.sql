@@ -0,0 +1,2 @@
+INSERT INTO secrets (name, value) VALUES (?, ?)
+ON CONFLICT (name) DO UPDATE SET value = excluded.value;
\ No newline at end of file
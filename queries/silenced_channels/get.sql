@@ -0,0 +1 @@
+SELECT until FROM silenced_channels WHERE channel_id = ?;
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO silenced_channels (channel_id, until) VALUES (?, ?)
+ON CONFLICT (channel_id) DO UPDATE SET until = excluded.until;
\ No newline at end of file
@@ -0,0 +1 @@
+INSERT INTO song_requests (platform, url, source, user_id, requested_at) VALUES (?, ?, ?, ?, ?);
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT COUNT(*) FROM song_requests;
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT COUNT(*) FROM song_requests WHERE source = ? AND user_id = ?;
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT COUNT(*) FROM song_requests WHERE url = ?;
\ No newline at end of file
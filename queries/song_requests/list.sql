@@ -0,0 +1 @@
+SELECT id, platform, url, source, user_id, requested_at FROM song_requests ORDER BY id;
\ No newline at end of file
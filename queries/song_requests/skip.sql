@@ -0,0 +1,2 @@
+DELETE FROM song_requests WHERE id = (SELECT MIN(id) FROM song_requests)
+RETURNING id, platform, url, source, user_id, requested_at;
\ No newline at end of file
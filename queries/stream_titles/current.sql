@@ -0,0 +1,5 @@
+SELECT t.title, t.category, t.changed_at FROM stream_titles t
+JOIN streams s ON s.id = t.stream
+WHERE s.ended_at IS NULL
+ORDER BY t.changed_at DESC, t.id DESC
+LIMIT 1;
\ No newline at end of file
@@ -0,0 +1,4 @@
+SELECT title, category, changed_at FROM stream_titles
+WHERE LOWER(category) = LOWER(?)
+ORDER BY changed_at DESC, id DESC
+LIMIT 1;
\ No newline at end of file
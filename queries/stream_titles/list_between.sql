@@ -0,0 +1,4 @@
+SELECT t.title, t.category, t.changed_at FROM stream_titles t
+JOIN streams s ON s.id = t.stream
+WHERE s.started_at < ? AND (s.ended_at IS NULL OR s.ended_at >= ?)
+ORDER BY t.changed_at, t.id;
\ No newline at end of file
@@ -0,0 +1,4 @@
+SELECT s.id AS stream, s.started_at, t.title, t.category, t.changed_at FROM stream_titles t
+JOIN streams s ON s.id = t.stream
+WHERE s.id = (SELECT id FROM streams ORDER BY started_at DESC LIMIT 1)
+ORDER BY t.changed_at, t.id;
\ No newline at end of file
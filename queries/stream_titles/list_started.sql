@@ -0,0 +1,4 @@
+SELECT s.id AS stream, s.started_at, t.title, t.category, t.changed_at FROM stream_titles t
+JOIN streams s ON s.id = t.stream
+WHERE s.started_at >= ? AND s.started_at < ?
+ORDER BY s.started_at, s.id, t.changed_at, t.id;
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT stream_id AS id, started_at, title, category FROM streams WHERE ended_at IS NULL ORDER BY started_at DESC LIMIT 1;
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT id, topic FROM streams WHERE ended_at IS NULL ORDER BY started_at DESC LIMIT 1;
\ No newline at end of file
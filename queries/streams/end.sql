@@ -0,0 +1 @@
+UPDATE streams SET ended_at = ? WHERE ended_at IS NULL;
\ No newline at end of file
@@ -0,0 +1 @@
+UPDATE streams SET tag = ? WHERE id = ?;
\ No newline at end of file
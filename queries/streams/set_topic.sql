@@ -0,0 +1 @@
+UPDATE streams SET topic = ? WHERE id = ?;
\ No newline at end of file
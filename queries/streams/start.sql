@@ -0,0 +1,5 @@
+INSERT INTO streams (stream_id, started_at, title, category) VALUES (?, ?, ?, ?)
+ON CONFLICT (stream_id) DO UPDATE SET
+    title = excluded.title,
+    category = excluded.category,
+    ended_at = NULL;
\ No newline at end of file
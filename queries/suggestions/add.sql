@@ -0,0 +1,3 @@
+INSERT INTO suggestions (source, user_id, name, content, status, created_at)
+VALUES (?, ?, ?, ?, 'open', ?)
+RETURNING id;
\ No newline at end of file
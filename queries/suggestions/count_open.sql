@@ -0,0 +1 @@
+SELECT COUNT(*) FROM suggestions WHERE source = ? AND user_id = ? AND status = 'open';
\ No newline at end of file
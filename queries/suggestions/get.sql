@@ -0,0 +1,2 @@
+SELECT id, source, user_id, name, content, status, reason, created_at FROM suggestions
+WHERE id = ?;
\ No newline at end of file
@@ -0,0 +1,3 @@
+SELECT id, source, user_id, name, content, status, reason, created_at FROM suggestions
+WHERE status = 'open'
+ORDER BY created_at, id;
\ No newline at end of file
@@ -0,0 +1,3 @@
+UPDATE suggestions SET status = ?, reason = ?, reviewed_at = ?
+WHERE id = ? AND status = 'open'
+RETURNING id, source, user_id, name, content, status, reason, created_at;
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT enabled FROM toggles WHERE name = ?;
\ No newline at end of file
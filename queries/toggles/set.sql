@@ -0,0 +1,2 @@
+INSERT INTO toggles (name, enabled) VALUES (?, ?)
+ON CONFLICT (name) DO UPDATE SET enabled = excluded.enabled;
\ No newline at end of file
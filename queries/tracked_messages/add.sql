@@ -0,0 +1,2 @@
+INSERT INTO tracked_messages (message_id, kind, record) VALUES (?, ?, ?)
+ON CONFLICT (message_id, kind, record) DO NOTHING;
\ No newline at end of file
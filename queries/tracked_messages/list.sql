@@ -0,0 +1 @@
+SELECT kind, record FROM tracked_messages WHERE message_id = ?;
\ No newline at end of file
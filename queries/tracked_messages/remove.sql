@@ -0,0 +1 @@
+DELETE FROM tracked_messages WHERE message_id = ?;
\ No newline at end of file
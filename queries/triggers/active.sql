@@ -0,0 +1,2 @@
+SELECT word, content, cooldown FROM triggers
+WHERE word NOT IN (SELECT word FROM disabled_triggers WHERE source = ?);
\ No newline at end of file
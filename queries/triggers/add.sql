@@ -0,0 +1,2 @@
+INSERT INTO triggers (word, content, cooldown) VALUES (?, ?, ?)
+ON CONFLICT (word) DO UPDATE SET content = excluded.content;
\ No newline at end of file
@@ -0,0 +1 @@
+UPDATE triggers SET cooldown = ? WHERE word = ? RETURNING id;
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO disabled_triggers (word, source) VALUES (?, ?)
+ON CONFLICT (word, source) DO NOTHING;
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM disabled_triggers WHERE word = ? AND source = ?;
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT word, content, cooldown FROM triggers ORDER BY word;
\ No newline at end of file
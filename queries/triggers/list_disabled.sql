@@ -0,0 +1 @@
+SELECT word, source FROM disabled_triggers ORDER BY source;
\ No newline at end of file
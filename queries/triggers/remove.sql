@@ -0,0 +1 @@
+DELETE FROM triggers WHERE word = ? RETURNING id;
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM upcoming_topic WHERE id = 1;
\ No newline at end of file
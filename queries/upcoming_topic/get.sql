@@ -0,0 +1 @@
+SELECT content FROM upcoming_topic WHERE id = 1;
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO upcoming_topic (id, content, set_at) VALUES (1, ?, ?)
+ON CONFLICT (id) DO UPDATE SET content = excluded.content, set_at = excluded.set_at;
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT name, threshold FROM usage_alerts ORDER BY name;
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM usage_alerts WHERE name = ?;
\ No newline at end of file
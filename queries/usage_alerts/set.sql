@@ -0,0 +1,2 @@
+INSERT INTO usage_alerts (name, threshold) VALUES (?, ?)
+ON CONFLICT (name) DO UPDATE SET threshold = excluded.threshold, tripped = NULL;
\ No newline at end of file
@@ -0,0 +1,2 @@
+UPDATE usage_alerts SET tripped = ?
+WHERE name = ? AND (tripped IS NULL OR tripped < ?) RETURNING id;
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO user_notes (source, user_id, content, author, created_at) VALUES (?, ?, ?, ?, ?)
+RETURNING id;
\ No newline at end of file
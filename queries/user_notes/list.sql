@@ -0,0 +1,2 @@
+SELECT id, content, author, created_at FROM user_notes WHERE source = ? AND user_id = ?
+ORDER BY created_at, id;
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM user_notes WHERE id = ? RETURNING source, user_id AS id;
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT key, value FROM user_prefs WHERE source = ? AND user_id = ? ORDER BY key;
\ No newline at end of file
@@ -0,0 +1 @@
+DELETE FROM user_prefs WHERE source = ? AND user_id = ? AND key = ? RETURNING value;
\ No newline at end of file
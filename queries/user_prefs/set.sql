@@ -0,0 +1,2 @@
+INSERT INTO user_prefs (source, user_id, key, value) VALUES (?, ?, ?, ?)
+ON CONFLICT (source, user_id, key) DO UPDATE SET value = excluded.value;
\ No newline at end of file
@@ -0,0 +1,3 @@
+INSERT INTO watch_time (year, month, source, user_id, name, seconds) VALUES (?, ?, ?, ?, ?, ?)
+ON CONFLICT (year, month, source, user_id) DO UPDATE
+SET seconds = watch_time.seconds + excluded.seconds, name = excluded.name;
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT COUNT(*) FROM watch_time;
\ No newline at end of file
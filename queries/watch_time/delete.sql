@@ -0,0 +1 @@
+DELETE FROM watch_time WHERE source = ? AND user_id = ?;
\ No newline at end of file
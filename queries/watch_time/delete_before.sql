@@ -0,0 +1 @@
+DELETE FROM watch_time WHERE year * 12 + month < ?;
\ No newline at end of file
@@ -0,0 +1,2 @@
+SELECT CAST(COALESCE(SUM(seconds), 0) AS BIGINT) FROM watch_time
+WHERE source = ? AND user_id = ?;
\ No newline at end of file
@@ -0,0 +1,3 @@
+SELECT name, seconds AS count FROM watch_time WHERE year = ? AND month = ?
+ORDER BY seconds DESC
+LIMIT ?;
\ No newline at end of file
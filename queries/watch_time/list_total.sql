@@ -0,0 +1,4 @@
+SELECT MAX(name) AS name, CAST(SUM(seconds) AS BIGINT) AS count FROM watch_time
+GROUP BY source, user_id
+ORDER BY SUM(seconds) DESC
+LIMIT ?;
\ No newline at end of file
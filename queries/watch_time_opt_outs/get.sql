@@ -0,0 +1 @@
+SELECT id FROM watch_time_opt_outs WHERE source = ? AND user_id = ?;
\ No newline at end of file
@@ -0,0 +1 @@
+SELECT user_id FROM watch_time_opt_outs WHERE source = ? ORDER BY id;
\ No newline at end of file
@@ -0,0 +1,2 @@
+INSERT INTO watched_crates (name, version, etag) VALUES (?, ?, ?)
+ON CONFLICT (name) DO NOTHING RETURNING id;
\ No newline at end of file
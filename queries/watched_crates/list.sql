@@ -0,0 +1 @@
+SELECT name, version, etag FROM watched_crates ORDER BY name;
\ No newline at end of file
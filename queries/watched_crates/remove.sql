@@ -0,0 +1 @@
+DELETE FROM watched_crates WHERE name = ? RETURNING id;
\ No newline at end of file
@@ -0,0 +1 @@
+UPDATE watched_crates SET version = ?, etag = ? WHERE name = ?;
\ No newline at end of file
@@ -1,20 +1,168 @@
 use std::{
     fmt::{self, Display},
     num::NonZero,
+    str::FromStr,
+    time::{Duration, Instant},
 };
 
+use anyhow::{anyhow, bail, ensure, Context};
 use serde::{Deserialize, Serialize};
+use time::{OffsetDateTime, UtcOffset};
 use tokio::sync::{mpsc, oneshot};
 use tracing::Span;
 
 use self::{request::Request, response::Response};
+use crate::locale::Language;
 
 pub mod request;
 pub mod response;
 
 /// A queue that service connecters can use to send received messages to the handler and get back a
-/// reply to render to the user.
-pub type Queue = mpsc::Sender<(Message, oneshot::Sender<Response>)>;
+/// reply to render to the user. Each message is accompanied by the point in time it was queued.
+pub type Queue = mpsc::Sender<(Message, Instant, oneshot::Sender<Response>)>;
+
+/// A queue that service connectors can use to send events, that aren't related to any chat message,
+/// to the handler.
+pub type Events = mpsc::Sender<Event>;
+
+/// A message that a service connector should post on its own.
+#[derive(Debug)]
+pub struct Post {
+    /// Channel to post the message in. Only used by **Discord**, as Twitch always posts in the
+    /// streamer's chat.
+    pub channel: Option<NonZero<u64>>,
+    /// Content of the message.
+    pub content: String,
+}
+
+/// A custom command, for services that offer them next to the built-in commands.
+#[derive(Clone, Debug)]
+pub struct CustomCommand {
+    /// Name of the command, without any prefix.
+    pub name: String,
+    /// Fixed content that the command replies with.
+    pub content: String,
+}
+
+/// An event that was observed by a service connector.
+#[derive(Clone)]
+pub enum Event {
+    /// The streamer started streaming.
+    StreamOnline(StreamInfo),
+    /// The streamer stopped streaming.
+    StreamOffline,
+    /// The streamer changed the title or category of the channel.
+    ChannelUpdate {
+        /// New title of the stream.
+        title: String,
+        /// New category (game) of the stream.
+        category: String,
+    },
+    /// Someone new followed the channel.
+    Follow {
+        /// Service the follow happened on.
+        source: Source,
+        /// Display name of the new follower.
+        user: String,
+    },
+    /// Someone subscribed to the channel, or was gifted a subscription.
+    Subscribe {
+        /// Service the subscription happened on.
+        source: Source,
+        /// Display name of the subscriber.
+        user: String,
+        /// Tier of the subscription, like `1` or `Prime`.
+        tier: String,
+    },
+    /// Someone cheered in the chat.
+    Cheer {
+        /// Service the cheer happened on.
+        source: Source,
+        /// Display name of the user, or nothing if they cheered anonymously.
+        user: Option<String>,
+        /// Amount of bits that were cheered.
+        bits: u64,
+    },
+    /// Another channel raided the stream.
+    Raid {
+        /// Service the raid happened on.
+        source: Source,
+        /// Display name of the raiding channel.
+        user: String,
+        /// Login name of the raiding channel, as used in links.
+        login: String,
+        /// Amount of viewers that came along.
+        viewers: u64,
+        /// Category that the raiding channel streamed last, if known.
+        category: Option<String>,
+    },
+    /// A poll ended, with the votes for each option.
+    PollEnded {
+        /// Service the poll ran on.
+        source: Source,
+        /// Question of the poll.
+        question: String,
+        /// Options of the poll, with their votes.
+        results: Vec<(String, u64)>,
+    },
+    /// A user wrote a chat message, regardless of whether it was a command.
+    Chat {
+        /// The author of the message.
+        user: UserRef,
+        /// Current name of the author, that others use to refer to them.
+        name: String,
+    },
+    /// A user posted a code block in a Discord channel.
+    CodeShared {
+        /// Channel that the code was posted in.
+        channel: NonZero<u64>,
+        /// Display name of the author.
+        user: String,
+        /// Language tag of the code block, if given.
+        language: Option<String>,
+        /// Content of the code block.
+        code: String,
+    },
+    /// A Discord message was edited, so anything created from it needs updating.
+    MessageEdited {
+        /// ID of the message.
+        message: NonZero<u64>,
+        /// New content of the message.
+        content: String,
+    },
+    /// Discord messages were deleted, so anything created from them needs to be removed.
+    MessagesDeleted(Vec<NonZero<u64>>),
+    /// Users that are currently in the chat, sampled in a regular interval.
+    Watching {
+        /// Users in the chat, without the streamer and the bot itself.
+        viewers: Vec<Viewer>,
+        /// Time since the last sample, that each of the users is credited with.
+        duration: Duration,
+    },
+}
+
+/// A user that is present in the chat.
+#[derive(Clone, Debug)]
+pub struct Viewer {
+    /// Reference to the user.
+    pub user: UserRef,
+    /// Current display name of the user.
+    pub name: String,
+}
+
+/// Details about a single stream session.
+#[derive(Clone, Debug, Deserialize)]
+pub struct StreamInfo {
+    /// Unique identifier of the stream.
+    pub id: String,
+    /// Point in time when the stream started.
+    #[serde(with = "time::serde::timestamp")]
+    pub started_at: OffsetDateTime,
+    /// Title of the stream.
+    pub title: String,
+    /// Category (game) that is streamed.
+    pub category: String,
+}
 
 /// A message that was received by a service connector. It contains all information needed by the
 /// handler to parse and act upon the message.
@@ -25,10 +173,22 @@ pub struct Message {
     pub source: Source,
     /// The whole message content.
     pub content: Request,
-    /// Whether this message is considered an admin command.
-    pub author: AuthorId,
+    /// Who wrote the message.
+    pub author: Author,
     /// ID of a mentioned user contained in the content. Currently specific to **Discord**.
     pub mention: Option<NonZero<u64>>,
+    /// Server channel that the message was written in, or nothing for direct messages. Currently
+    /// specific to **Discord**.
+    pub channel: Option<GuildChannel>,
+}
+
+/// A channel of a Discord server.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GuildChannel {
+    /// Server that the channel belongs to.
+    pub guild: NonZero<u64>,
+    /// ID of the channel itself.
+    pub channel: NonZero<u64>,
 }
 
 /// Possible sources that a message came from.
@@ -39,6 +199,47 @@ pub enum Source {
     Discord,
     /// Twitch source <https://twitch.tv>.
     Twitch,
+    /// Matrix source <https://matrix.org>.
+    Matrix,
+    /// YouTube source <https://youtube.com>.
+    #[serde(rename = "youtube")]
+    YouTube,
+    /// Local console, reading commands from the terminal.
+    Console,
+}
+
+impl Source {
+    /// All available sources, for example to target every service with a custom command.
+    pub const ALL: [Self; 5] = [
+        Self::Discord,
+        Self::Twitch,
+        Self::Matrix,
+        Self::YouTube,
+        Self::Console,
+    ];
+
+    /// Lowercase identifier of the source, as used in commands.
+    #[must_use]
+    pub const fn key(self) -> &'static str {
+        match self {
+            Self::Discord => "discord",
+            Self::Twitch => "twitch",
+            Self::Matrix => "matrix",
+            Self::YouTube => "youtube",
+            Self::Console => "console",
+        }
+    }
+}
+
+impl FromStr for Source {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|source| source.key() == s)
+            .ok_or_else(|| anyhow!("unknown source `{s}`"))
+    }
 }
 
 impl Display for Source {
@@ -46,17 +247,466 @@ impl Display for Source {
         f.write_str(match self {
             Self::Discord => "Discord",
             Self::Twitch => "Twitch",
+            Self::Matrix => "Matrix",
+            Self::YouTube => "YouTube",
+            Self::Console => "Console",
+        })
+    }
+}
+
+/// A Discord message that an admin pinned, to keep it around as highlight.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct Highlight {
+    /// Server that the message was posted in, or nothing for direct messages.
+    pub guild: Option<NonZero<u64>>,
+    /// Channel that the message was posted in.
+    pub channel: NonZero<u64>,
+    /// ID of the message itself.
+    pub message: NonZero<u64>,
+    /// Name of the user who wrote the message.
+    pub author: String,
+    /// Content of the message.
+    pub content: String,
+}
+
+impl Highlight {
+    /// Link that jumps right to the message in Discord.
+    #[must_use]
+    pub fn link(&self) -> String {
+        match self.guild {
+            Some(guild) => format!(
+                "https://discord.com/channels/{guild}/{}/{}",
+                self.channel, self.message
+            ),
+            None => format!(
+                "https://discord.com/channels/@me/{}/{}",
+                self.channel, self.message
+            ),
+        }
+    }
+}
+
+/// A poll that moderators start from the chat, which runs on Twitch and is mirrored to Discord.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Poll {
+    /// Whether it's a normal poll, or a prediction.
+    pub kind: PollKind,
+    /// Question that viewers vote on.
+    pub question: String,
+    /// Possible answers.
+    pub options: Vec<String>,
+    /// Time that viewers have to vote.
+    pub duration: Duration,
+}
+
+/// The kind of a [`Poll`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PollKind {
+    /// Viewers vote for their favorite answer.
+    Poll,
+    /// Viewers bet channel points on the outcome. On Discord this is a normal poll.
+    Prediction,
+}
+
+impl PollKind {
+    /// Lowercase name of the kind, as used in messages.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Poll => "poll",
+            Self::Prediction => "prediction",
+        }
+    }
+}
+
+/// When a command can be used, depending on whether the stream is live.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Availability {
+    /// Regardless of the stream.
+    #[default]
+    Always,
+    /// Only while the stream is live.
+    Live,
+    /// Only while the stream is offline.
+    Offline,
+}
+
+impl Availability {
+    /// Whether the command can be used, given the current stream state.
+    #[must_use]
+    pub fn allows(self, live: bool) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Live => live,
+            Self::Offline => !live,
+        }
+    }
+}
+
+impl FromStr for Availability {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "always" => Self::Always,
+            "live" => Self::Live,
+            "offline" => Self::Offline,
+            _ => bail!("unknown availability `{s}`, must be one of `always`, `live` or `offline`"),
         })
     }
 }
 
+/// Where a user's suggestion stands in the review by the admins.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionStatus {
+    /// Waiting for a review.
+    Open,
+    /// The admins picked up the suggestion.
+    Accepted,
+    /// The admins decided against the suggestion.
+    Rejected,
+}
+
+impl Display for Availability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Always => "always",
+            Self::Live => "live only",
+            Self::Offline => "offline only",
+        })
+    }
+}
+
+/// Whether user commands are answered in a Discord channel. Once any channel of a server is
+/// allowed, all others are denied.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelRule {
+    /// Commands are answered in the channel.
+    Allow,
+    /// Commands are ignored in the channel.
+    Deny,
+}
+
+impl FromStr for ChannelRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "allow" => Self::Allow,
+            "deny" => Self::Deny,
+            _ => bail!("unknown channel rule `{s}`, must be either `allow` or `deny`"),
+        })
+    }
+}
+
+impl Display for ChannelRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Allow => "allowed",
+            Self::Deny => "denied",
+        })
+    }
+}
+
+/// Unit that a user prefers for temperatures.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TemperatureUnit {
+    /// Degrees Celsius.
+    Celsius,
+    /// Degrees Fahrenheit.
+    Fahrenheit,
+}
+
+impl FromStr for TemperatureUnit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_lowercase().as_str() {
+            "c" | "celsius" => Self::Celsius,
+            "f" | "fahrenheit" => Self::Fahrenheit,
+            _ => bail!("unknown unit `{s}`, must be either `celsius` or `fahrenheit`"),
+        })
+    }
+}
+
+impl Display for TemperatureUnit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Celsius => "celsius",
+            Self::Fahrenheit => "fahrenheit",
+        })
+    }
+}
+
+/// Single personal setting of a user, that commands apply automatically.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum UserPref {
+    /// Unit that temperatures are converted into.
+    Unit(TemperatureUnit),
+    /// Time zone for dates, as fixed offset from UTC.
+    Timezone(UtcOffset),
+    /// Language of the responses.
+    Locale(Language),
+}
+
+impl UserPref {
+    /// Names of all settings, as they're written in chat.
+    pub const KEYS: &[&str] = &["unit", "timezone", "locale"];
+
+    /// Parse the setting with the given name from its value.
+    pub fn parse(key: &str, value: &str) -> anyhow::Result<Self> {
+        Ok(match key {
+            "unit" => Self::Unit(value.parse()?),
+            "timezone" => Self::Timezone(parse_offset(value)?),
+            "locale" => Self::Locale(value.parse()?),
+            _ => bail!(
+                "unknown setting `{key}`, must be one of `{}`",
+                Self::KEYS.join("`, `")
+            ),
+        })
+    }
+
+    /// Name of the setting.
+    #[must_use]
+    pub fn key(&self) -> &'static str {
+        match self {
+            Self::Unit(_) => "unit",
+            Self::Timezone(_) => "timezone",
+            Self::Locale(_) => "locale",
+        }
+    }
+
+    /// Value of the setting, in the same form that [`Self::parse`] accepts.
+    #[must_use]
+    pub fn value(&self) -> String {
+        match self {
+            Self::Unit(unit) => unit.to_string(),
+            Self::Timezone(offset) => format_offset(*offset),
+            Self::Locale(language) => language.code().to_owned(),
+        }
+    }
+}
+
+/// All personal settings of a user, where each one is unset until the user changes it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct UserPrefs {
+    /// Unit that temperatures are converted into.
+    pub unit: Option<TemperatureUnit>,
+    /// Time zone for dates.
+    pub timezone: Option<UtcOffset>,
+    /// Language of the responses.
+    pub locale: Option<Language>,
+}
+
+impl UserPrefs {
+    /// Whether none of the settings was changed.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply a single setting, replacing any previous value.
+    pub fn set(&mut self, pref: UserPref) {
+        match pref {
+            UserPref::Unit(unit) => self.unit = Some(unit),
+            UserPref::Timezone(offset) => self.timezone = Some(offset),
+            UserPref::Locale(language) => self.locale = Some(language),
+        }
+    }
+
+    /// All settings that were changed, in the order of [`UserPref::KEYS`].
+    pub fn iter(&self) -> impl Iterator<Item = UserPref> {
+        [
+            self.unit.map(UserPref::Unit),
+            self.timezone.map(UserPref::Timezone),
+            self.locale.map(UserPref::Locale),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+/// Parse a fixed offset from UTC, like `UTC+2`, `+02:00` or `-5:30`.
+fn parse_offset(value: &str) -> anyhow::Result<UtcOffset> {
+    let invalid = || anyhow!("invalid time zone `{value}`, must be an offset like `UTC+2`");
+
+    let offset = value.trim();
+    let offset = offset
+        .strip_prefix("UTC")
+        .or_else(|| offset.strip_prefix("utc"))
+        .unwrap_or(offset);
+    if offset.is_empty() {
+        return Ok(UtcOffset::UTC);
+    }
+
+    let (sign, offset) = match offset.split_at_checked(1) {
+        Some(("+", rest)) => (1, rest),
+        Some(("-", rest)) => (-1, rest),
+        _ => return Err(invalid()),
+    };
+    let (hours, minutes) = offset.split_once(':').unwrap_or((offset, "0"));
+    let hours = hours.parse::<i8>().map_err(|_| invalid())?;
+    let minutes = minutes.parse::<i8>().map_err(|_| invalid())?;
+    ensure!(
+        (0..=14).contains(&hours) && (0..60).contains(&minutes),
+        invalid()
+    );
+
+    UtcOffset::from_hms(sign * hours, sign * minutes, 0).map_err(|_| invalid())
+}
+
+/// Format an offset from UTC like `UTC+02:00`, or just `UTC` if there is no offset.
+#[must_use]
+pub fn format_offset(offset: UtcOffset) -> String {
+    if offset.is_utc() {
+        return "UTC".to_owned();
+    }
+
+    let sign = if offset.is_negative() { '-' } else { '+' };
+    format!(
+        "UTC{sign}{:02}:{:02}",
+        offset.whole_hours().unsigned_abs(),
+        offset.minutes_past_hour().unsigned_abs()
+    )
+}
+
+/// Reference to a user on any of the services, written as `<service>:<name>` (like
+/// `twitch:somebody`) or as plain Discord mention.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, Deserialize, Serialize)]
+pub struct UserRef {
+    /// Service that the user belongs to.
+    pub source: Source,
+    /// Identifier of the user within the service.
+    pub id: String,
+}
+
+impl FromStr for UserRef {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (source, id) = if let Some(id) = s.strip_prefix("<@").and_then(|s| s.strip_suffix('>'))
+        {
+            (Source::Discord, id.trim_start_matches('!'))
+        } else {
+            let (source, id) = s
+                .split_once(':')
+                .context("users must be a Discord mention or look like `<service>:<name>`")?;
+            (source.parse()?, id)
+        };
+
+        ensure!(!id.is_empty(), "missing user name");
+
+        let id = match source {
+            Source::Discord => {
+                ensure!(
+                    id.parse::<NonZero<u64>>().is_ok(),
+                    "invalid Discord user ID `{id}`"
+                );
+                id.to_owned()
+            }
+            // Twitch logins are case-insensitive and often written with a leading `@`.
+            Source::Twitch => id.trim_start_matches('@').to_lowercase(),
+            Source::Matrix | Source::YouTube | Source::Console => id.to_owned(),
+        };
+
+        Ok(Self { source, id })
+    }
+}
+
+impl Display for UserRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.source.key(), self.id)
+    }
+}
+
 /// Unique identifier of the message author, one variant for each service the message might come
 /// from.
+#[derive(Clone, Debug, Serialize)]
+#[serde(untagged)]
 pub enum AuthorId {
     /// Discord author ID.
     Discord(NonZero<u64>),
     /// Twitch author ID.
     Twitch(String),
+    /// Matrix author ID, the fully qualified user ID like `@user:example.org`.
+    Matrix(String),
+    /// YouTube author ID, the channel ID of the user.
+    YouTube(String),
+    /// The local user, typing into the console.
+    Console,
+    /// An external service, calling the control API.
+    Control {
+        /// Name of the client from the settings.
+        client: String,
+        /// Whether the client may run owner commands, or only admin commands.
+        owner: bool,
+    },
+}
+
+impl AuthorId {
+    /// Reference to the author, to keep track of them across messages.
+    #[must_use]
+    pub fn user_ref(&self) -> UserRef {
+        let (source, id) = match self {
+            Self::Discord(id) => (Source::Discord, id.to_string()),
+            Self::Twitch(id) => (Source::Twitch, id.clone()),
+            Self::Matrix(id) => (Source::Matrix, id.clone()),
+            Self::YouTube(id) => (Source::YouTube, id.clone()),
+            Self::Console => (Source::Console, "console".to_owned()),
+            Self::Control { client, .. } => (Source::Console, format!("control:{client}")),
+        };
+
+        UserRef { source, id }
+    }
+}
+
+/// Roles that let a user moderate the chat, as named by the services.
+const MODERATOR_ROLES: &[&str] = &["broadcaster", "lead_moderator", "moderator"];
+
+/// Details about the author of a message, as far as the service tells them.
+#[derive(Clone, Debug)]
+pub struct Author {
+    /// Unique identifier of the author.
+    pub id: AuthorId,
+    /// Name that is shown in the chat.
+    pub name: String,
+    /// Service specific roles, like Twitch badges (`moderator`, `vip`, ...) or Discord role IDs.
+    pub roles: Vec<String>,
+    /// Whether the author supports the channel, like a Twitch subscriber, a YouTube member or a
+    /// Discord server booster.
+    pub subscriber: bool,
+}
+
+impl Author {
+    /// Whether the author is the streamer.
+    #[must_use]
+    pub fn is_broadcaster(&self) -> bool {
+        self.roles.iter().any(|role| role == "broadcaster")
+    }
+
+    /// Whether the author can moderate the chat, which includes the streamer.
+    #[must_use]
+    pub fn is_moderator(&self) -> bool {
+        self.roles
+            .iter()
+            .any(|role| MODERATOR_ROLES.contains(&role.as_str()))
+    }
+}
+
+impl From<AuthorId> for Author {
+    /// Author that is only known by its ID, which is used as name as well.
+    fn from(id: AuthorId) -> Self {
+        Self {
+            name: id.user_ref().id,
+            id,
+            roles: Vec::new(),
+            subscriber: false,
+        }
+    }
 }
 
 impl AsRef<str> for Source {
@@ -64,6 +714,9 @@ impl AsRef<str> for Source {
         match self {
             Self::Discord => "Discord",
             Self::Twitch => "Twitch",
+            Self::Matrix => "Matrix",
+            Self::YouTube => "YouTube",
+            Self::Console => "Console",
         }
     }
 }
@@ -87,7 +740,11 @@ impl AdminId {
     pub fn from_author(id: &AuthorId) -> Option<Self> {
         match id {
             AuthorId::Discord(id) => Some(Self(*id)),
-            AuthorId::Twitch(_) => None,
+            AuthorId::Twitch(_)
+            | AuthorId::Matrix(_)
+            | AuthorId::YouTube(_)
+            | AuthorId::Console
+            | AuthorId::Control { .. } => None,
         }
     }
 }
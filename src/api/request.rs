@@ -1,5 +1,15 @@
-use super::{AdminId, Source};
+use std::{num::NonZero, time::Duration};
 
+use serde::Serialize;
+use time::Date;
+
+use super::{
+    AdminId, Availability, ChannelRule, Highlight, Poll, Source, SuggestionStatus, UserPref,
+    UserRef,
+};
+use crate::fun::Kind;
+
+#[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum Request {
     User(User),
@@ -7,26 +17,349 @@ pub enum Request {
     Owner(Owner),
 }
 
+impl Request {
+    /// Name of the command that created this request, without the `!` prefix.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        match self {
+            Self::User(user) => match user {
+                User::Help | User::CommandHelp(_) => "help",
+                User::Commands(_) => "commands",
+                User::Links => "links",
+                User::Ban(_) => "ban",
+                User::Crate(_)
+                | User::CrateCompare(_, _)
+                | User::CrateVersion { .. }
+                | User::CrateFeatures { .. } => "crate",
+                User::RustVersions => "rustversion",
+                User::Eval(_) => "eval",
+                User::Today => "today",
+                User::Status => "status",
+                User::BotStat => "botstat",
+                User::Ftoc(_) => "ftoc",
+                User::Ctof(_) => "ctof",
+                User::Convert(_) => "convert",
+                User::SongRequest(_) => "sr",
+                User::SongQueue => "queue",
+                User::TimeoutMe => "timeout",
+                User::FollowAge => "followage",
+                User::AccountAge => "accountage",
+                User::Points => "points",
+                User::Gamble(_) => "gamble",
+                User::Duel { .. } => "duel",
+                User::Accept => "accept",
+                User::Pronouns(_) => "pronouns",
+                User::Prefs(_) => "set",
+                User::Poll(poll) => poll.kind.name(),
+                User::NotifyMe(_) => "notifyme",
+                User::WatchTime(_) => "watchtime",
+                User::Birthday(_) => "birthday",
+                User::Fun(kind) => kind.name(),
+                User::Lurk(_) => "lurk",
+                User::Unlurk => "unlurk",
+                User::Highlights => "highlights",
+                User::Yesterday => "yesterday",
+                User::LastCategory(_) => "lastcategory",
+                User::Suggest(_) => "suggest",
+                User::Topic => "topic",
+                User::Trigger(_) => "trigger",
+                User::Custom(name) | User::Plugin { name, .. } => name,
+            },
+            Self::Admin(admin) => match admin {
+                Admin::Help => "ahelp",
+                Admin::CustomCommands(_) => "custom_commands",
+                Admin::Statistics(_) | Admin::StreamTag(_) | Admin::Timing => "stats",
+                Admin::UserNotes(_) => "usernote",
+                Admin::SkipSong => "skip",
+                Admin::CommandCosts(_) => "cost",
+                Admin::CommandAvailability(_) => "availability",
+                Admin::UsageAlerts(_) => "alert",
+                Admin::Shoutout(_) => "shoutout",
+                Admin::Triggers(_) => "trigger",
+                Admin::Faqs(_) => "faq",
+                Admin::WatchCrates(_) => "watchcrate",
+                Admin::Channels(_) => "channels",
+                Admin::Silence { .. } => "silence",
+                Admin::CommandList(_) => "commandlist",
+                Admin::Pin(_) => "pin",
+                Admin::Away(_) => "away",
+                Admin::Back => "back",
+                Admin::Confirm => "confirm",
+                Admin::LinkPreview(_) => "linkpreview",
+                Admin::CheckLinks => "checklinks",
+                Admin::Chapters(_) => "chapters",
+                Admin::Suggestions(_) => "suggestions",
+                Admin::Issue(_) => "issue",
+                Admin::Topic(_) => "topic",
+            },
+            Self::Owner(owner) => match owner {
+                Owner::Help => "ohelp",
+                Owner::Admins(_) => "admins",
+                Owner::Plugins(_) => "plugins",
+                Owner::DebugParse { .. } => "debug",
+                Owner::ResyncCommands => "resync_commands",
+                Owner::Cleanup => "cleanup",
+                Owner::Broadcast(_) => "broadcast",
+                #[cfg(feature = "chaos")]
+                Owner::Chaos(_) => "chaos",
+            },
+        }
+    }
+
+    /// Name of the command for metrics. Custom and plugin commands are grouped under a single name
+    /// each, so the amount of names stays bounded.
+    #[must_use]
+    pub fn metric_name(&self) -> &str {
+        match self {
+            Self::User(User::Custom(_)) => "custom",
+            Self::User(User::Plugin { .. }) => "plugin",
+            _ => self.name(),
+        }
+    }
+}
+
+#[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum User {
     Help,
+    CommandHelp(String),
     Commands(Source),
     Links,
     Ban(String),
     Crate(String),
+    CrateCompare(String, String),
+    CrateVersion {
+        name: String,
+        version: String,
+    },
+    CrateFeatures {
+        name: String,
+        version: Option<String>,
+    },
+    RustVersions,
+    Eval(String),
     Today,
+    Status,
+    BotStat,
     Ftoc(f64),
     Ctof(f64),
+    Convert(f64),
+    SongRequest(String),
+    SongQueue,
+    TimeoutMe,
+    FollowAge,
+    AccountAge,
+    Points,
+    Gamble(u64),
+    Duel {
+        target: String,
+        amount: u64,
+    },
+    Accept,
+    Pronouns(Pronouns),
+    Prefs(Prefs),
+    Poll(Poll),
+    NotifyMe(Option<bool>),
+    WatchTime(Option<bool>),
+    Birthday(Birthday),
+    Fun(Kind),
+    Lurk(Option<String>),
+    Unlurk,
+    Highlights,
+    Yesterday,
+    LastCategory(String),
+    Suggest(String),
+    Topic,
+    Trigger(String),
     Custom(String),
+    Plugin {
+        name: String,
+        args: String,
+    },
 }
 
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum Pronouns {
+    Show(String),
+    Set(String),
+    Unset,
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum Prefs {
+    Show,
+    Set(UserPref),
+    Reset(String),
+}
+
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum Birthday {
+    Show,
+    Set { month: time::Month, day: u8 },
+    Remove,
+    Announce(bool),
+}
+
+#[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum Admin {
     Help,
     CustomCommands(CustomCommands),
     Statistics(StatisticsDate),
+    StreamTag(String),
+    Timing,
+    UserNotes(UserNotes),
+    SkipSong,
+    CommandCosts(CommandCosts),
+    CommandAvailability(CommandAvailability),
+    UsageAlerts(UsageAlerts),
+    Shoutout(Option<bool>),
+    Triggers(Triggers),
+    Faqs(Faqs),
+    WatchCrates(WatchCrates),
+    Channels(Channels),
+    Silence {
+        channel: Option<NonZero<u64>>,
+        duration: Option<Duration>,
+    },
+    CommandList(CommandList),
+    Pin(Highlight),
+    Away(String),
+    Back,
+    Confirm,
+    LinkPreview(Source),
+    CheckLinks,
+    /// Chapters of the streams that started on the day, or of the latest stream.
+    Chapters(Option<Date>),
+    Suggestions(Suggestions),
+    Issue(Issue),
+    /// New agenda of the stream, or nothing to clear it.
+    Topic(Option<String>),
 }
 
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum Issue {
+    /// A new issue with a title and an optional body.
+    New { title: String, body: Option<String> },
+    /// Turn a suggestion into an issue.
+    Suggestion(i64),
+    /// Turn a chat message into an issue, with a link back to it.
+    Message {
+        author: String,
+        content: String,
+        link: String,
+    },
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum Suggestions {
+    /// All suggestions that wait for a review, oldest first.
+    List,
+    /// Accept or reject a suggestion, with an optional reason for the user.
+    Review {
+        id: i64,
+        status: SuggestionStatus,
+        reason: Option<String>,
+    },
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum Triggers {
+    List,
+    Add {
+        word: String,
+        content: String,
+    },
+    Remove(String),
+    Cooldown {
+        word: String,
+        cooldown: u64,
+    },
+    Enable {
+        word: String,
+        source: Source,
+        enabled: bool,
+    },
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum WatchCrates {
+    List,
+    Add(String),
+    Remove(String),
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum Channels {
+    List,
+    Set {
+        channel: NonZero<u64>,
+        rule: Option<ChannelRule>,
+    },
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum CommandList {
+    /// Keep the list pinned in the channel, or the current one if missing.
+    Pin(Option<NonZero<u64>>),
+    Off,
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum Faqs {
+    List,
+    Add {
+        name: String,
+        pattern: String,
+        regex: bool,
+        answer: String,
+    },
+    Remove(String),
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum CommandCosts {
+    List,
+    Set { name: String, cost: u64 },
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum CommandAvailability {
+    List,
+    Set {
+        name: String,
+        availability: Availability,
+    },
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum UsageAlerts {
+    List,
+    Set { name: String, limit: u64 },
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum UserNotes {
+    List(UserRef),
+    Add { user: UserRef, content: String },
+    Remove(i64),
+}
+
+#[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum CustomCommands {
     List,
@@ -39,24 +372,65 @@ pub enum CustomCommands {
         source: Option<Source>,
         name: String,
     },
+    Show(String),
+    History(String),
+    Revert(String),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum StatisticsDate {
     Total,
     Current,
+    Month { year: i32, month: time::Month },
+    Tag(String),
 }
 
+#[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum Owner {
     Help,
     Admins(Admins),
+    Plugins(Plugins),
+    DebugParse {
+        text: String,
+        source: Source,
+        mention: Option<NonZero<u64>>,
+    },
+    ResyncCommands,
+    Cleanup,
+    Broadcast(String),
+    #[cfg(feature = "chaos")]
+    Chaos(Chaos),
 }
 
+/// Control over the injected faults, with rates in percent.
+#[cfg(feature = "chaos")]
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum Chaos {
+    Status,
+    Off,
+    Delay { rate: u8, max: Duration },
+    SendFailure(u8),
+    EventDrop(u8),
+}
+
+#[derive(Debug)]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum Admins {
     List,
     Add(AdminId),
     Remove(AdminId),
+    AddRole(NonZero<u64>),
+    RemoveRole(NonZero<u64>),
+}
+
+#[derive(Debug)]
+#[cfg_attr(test, derive(PartialEq))]
+pub enum Plugins {
+    List,
+    Load(String),
+    Unload(String),
 }
@@ -1,14 +1,26 @@
-use std::{
-    collections::{BTreeMap, BTreeSet, HashMap},
-    sync::Arc,
-};
+use std::{collections::BTreeMap, num::NonZero, sync::Arc, time::Duration};
 
 use anyhow::Result;
-use serde::Deserialize;
-use time::OffsetDateTime;
+use serde::{
+    de::{self, IgnoredAny},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use time::{Month, OffsetDateTime};
 
-use super::{AdminId, Source};
-use crate::statistics::Statistics;
+use super::{
+    request::StatisticsDate, AdminId, Availability, ChannelRule, Highlight, Poll, Source,
+    StreamInfo, SuggestionStatus, UserPref, UserPrefs, UserRef,
+};
+use crate::{
+    commands::CommandInfo,
+    fun::Kind,
+    handler::Access,
+    health::ConnectorHealth,
+    plugins::{PluginAction, PluginInfo},
+    runtime::RuntimeInfo,
+    statistics::{Cleanup, Statistics},
+    timing::{Histogram, Stage},
+};
 
 /// The response for a command sent by a user.
 pub enum Response {
@@ -18,6 +30,91 @@ pub enum Response {
     Admin(Admin),
     /// Response for an owner command.
     Owner(Owner),
+    /// The author tried a command that requires a higher access level.
+    Denied(Access),
+    /// The command is destructive and only runs once the author confirms it.
+    Confirm(Confirm),
+}
+
+/// Destructive command, that waits for its author to confirm it.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub enum Confirm {
+    /// Remove a custom command from all services.
+    RemoveCustomCommand(String),
+    /// Remove a user from the admin list.
+    RemoveAdmin(AdminId),
+    /// Remove a role from the admin roles.
+    RemoveAdminRole(NonZero<u64>),
+}
+
+/// Links to the platforms where the streamer is present, as shown on a single service.
+#[derive(Serialize)]
+#[cfg_attr(test, derive(Debug))]
+pub struct Links {
+    /// The links, in the order they're shown.
+    pub links: Vec<Link>,
+    /// Custom format of a single link, instead of the service's default one.
+    pub template: Option<Arc<str>>,
+    /// Text between the links, instead of the service's default one.
+    pub separator: Option<Arc<str>>,
+}
+
+/// A single entry of the [`Links`].
+#[derive(Serialize)]
+#[cfg_attr(test, derive(Debug))]
+pub struct Link {
+    /// Name of the platform.
+    pub name: String,
+    /// Address of the streamer's page on the platform.
+    pub url: String,
+    /// Emoji or other decoration in front of the link.
+    pub icon: Option<String>,
+}
+
+/// A link that doesn't resolve anymore, found by the link health check.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DeadLink {
+    /// Place that the link is used in.
+    pub origin: LinkOrigin,
+    /// The address itself.
+    pub url: String,
+    /// Why the link counts as dead, like the status code of the response.
+    pub reason: String,
+}
+
+/// Place that a checked link is used in.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkOrigin {
+    /// One of the `links` from the settings, with its name.
+    Links(String),
+    /// Content of a custom command.
+    Command {
+        /// Name of the command.
+        name: String,
+        /// Service that the command exists on.
+        source: Source,
+    },
+}
+
+/// Details about a single built-in or custom command.
+#[cfg_attr(test, derive(Debug))]
+pub struct CommandHelp {
+    /// Main name of the command.
+    pub name: String,
+    /// Description of a built-in command, or nothing for custom commands.
+    pub info: Option<&'static CommandInfo>,
+    /// Services the command can be used on.
+    pub sources: Vec<Source>,
+    /// Points that the command costs, if any.
+    pub cost: Option<u64>,
+    /// Stream states, in which the command can be used.
+    pub availability: Availability,
+    /// Amount of identical uses within the time frame, before further ones are ignored. Only
+    /// applies to commands that are available to everyone.
+    pub cooldown: Option<(usize, Duration)>,
 }
 
 /// Response for a normal user command.
@@ -26,25 +123,403 @@ pub enum User {
     /// Command was not recognized and should be ignored.
     Unknown,
     /// Print a help message showing how to use the bot.
-    Help,
+    Help(String),
+    /// Details about a single command, or nothing if there is no command with that name.
+    CommandHelp(Result<Option<CommandHelp>>),
     /// List all available commands to the user.
-    Commands(Result<Vec<String>>),
+    Commands {
+        /// Built-in commands that are available on the service.
+        builtin: Vec<&'static CommandInfo>,
+        /// Names of the custom commands on the service.
+        custom: Result<Vec<String>>,
+    },
     /// Show a list of links to various platforms where the streamer is present.
-    Links(Arc<HashMap<String, String>>),
-    /// Fake ban anybody or anything.
+    Links(Links),
+    /// Fake ban anybody or anything, with the full message.
     Ban(String),
     /// Lookup details about a single Rust crate.
     Crate(Result<CrateSearch>),
+    /// Compare the details of two Rust crates.
+    CrateCompare(Result<(CrateSearch, CrateSearch)>),
+    /// Lookup details about a single version of a Rust crate.
+    CrateVersion(Result<VersionSearch>),
+    /// List the feature flags of a Rust crate.
+    CrateFeatures(Result<VersionSearch>),
+    /// Current versions of the Rust toolchain in each release channel.
+    RustVersions(Result<RustVersions>),
+    /// Output of a Rust snippet, that ran on the playground.
+    Eval(Result<Eval>),
     /// Get the current date, with unneeded level of detail (in UTC).
     Today(String),
+    /// Show the bot's status, including details about the current stream if it's live.
+    Status(Status),
+    /// Version, uptime and other details about the bot itself.
+    BotStat(BotStat),
     /// Convert Fahrenheit degrees to Celsius degrees.
     FahrenheitToCelsius(String),
     /// Convert Celsius degrees to Fahrenheit degrees.
     CelsiusToFahrenheit(String),
-    /// Execute a custom command.
+    /// Add a song to the song request queue.
+    SongRequest(Result<SongRequest>),
+    /// List the songs in the song request queue.
+    SongQueue(Result<Vec<QueuedSong>>),
+    /// Take a chance at being timed out. Only the Twitch connector can carry it out, as it knows
+    /// the author and their roles in the chat.
+    TimeoutMe,
+    /// Tell the author how long they've been following the channel. Only the Twitch connector can
+    /// look it up.
+    FollowAge,
+    /// Tell the author how old their account is. Only the Twitch connector can look it up.
+    AccountAge,
+    /// Marker for the Twitch connector, to time out the author for the given seconds because
+    /// they repeated a command too often.
+    TimeoutSpammer(u32),
+    /// Show the points that the user collected so far.
+    Points(Result<u64>),
+    /// Bet points, with the chance to double them.
+    Gamble(Result<Gamble>),
+    /// Challenge another user, where the winner takes the points of the loser.
+    Duel(Result<Duel>),
+    /// Look up or change the pronouns of a user.
+    Pronouns(Result<Pronouns>),
+    /// Show or change the author's personal settings.
+    Prefs(Result<Prefs>),
+    /// A user started or stopped lurking.
+    Lurk(Result<Lurk>),
+    /// The most recently pinned highlights, newest first.
+    Highlights(Result<Vec<Highlight>>),
+    /// A poll that was started, or the reason why it couldn't be started. Connectors that support
+    /// polls start them on their service.
+    Poll(Result<Poll>),
+    /// Whether the user now gets a direct message whenever the stream goes live.
+    NotifyMe(Result<bool>),
+    /// Look up or change the author's own birthday.
+    Birthday(Result<Birthday>),
+    /// Look up the author's watch time, or opt in or out of the tracking.
+    WatchTime(Result<WatchTime>),
+    /// Titles and categories of the streams that ran yesterday, in the author's time zone.
+    Yesterday(Result<Vec<StreamTitle>>),
+    /// The last time that a category was streamed.
+    LastCategory(Result<LastCategory>),
+    /// Outcome of sending in a suggestion.
+    Suggest(Result<Suggest>),
+    /// Agenda of the current or next stream, if the admins set one.
+    Topic(Result<Option<Topic>>),
+    /// Automatic reply to a message for the streamer, while they're away.
+    Away {
+        /// Name of the streamer.
+        streamer: String,
+        /// Message that the admins left.
+        message: String,
+    },
+    /// A random piece of advice, fact or joke.
+    Fun {
+        /// Kind of content that was asked for.
+        kind: Kind,
+        /// The content, or why none could be found.
+        content: Result<String>,
+    },
+    /// Response of a trigger word, or nothing if the message contained no trigger words.
+    Trigger(Option<String>),
+    /// Answer to a frequently asked question, that the message looked like.
+    Faq(String),
+    /// The user can't afford a custom command, as it costs more points than they have.
+    NotEnoughPoints {
+        /// Points that the command costs.
+        cost: u64,
+        /// Points that the user has.
+        balance: u64,
+    },
+    /// Execute a custom command, or a command provided by a plugin.
     Custom(Result<String>),
 }
 
+/// Result of a song request, either it was added to the queue at the given position, or it was
+/// refused with the reason why.
+#[cfg_attr(test, derive(Debug))]
+pub enum SongRequest {
+    /// Song was added to the queue.
+    Added {
+        /// Position in the queue, starting at 1 for the next song to play.
+        position: u64,
+    },
+    /// Song was refused, like for an invalid link or a full queue.
+    Refused(String),
+}
+
+/// Outcome of starting or stopping a lurk.
+#[cfg_attr(test, derive(Debug))]
+pub enum Lurk {
+    /// The user is lurking now.
+    Started {
+        /// Display name of the user.
+        name: String,
+        /// Message that the user left, like what they're up to.
+        message: Option<String>,
+    },
+    /// The user is back from lurking.
+    Ended {
+        /// Display name of the user.
+        name: String,
+        /// How long the user was lurking.
+        duration: Duration,
+    },
+    /// The user wasn't lurking, with their display name.
+    NotLurking(String),
+    /// The lurk couldn't be started, with the reason why.
+    Refused(String),
+}
+
+/// Result of a pronoun lookup or change.
+#[cfg_attr(test, derive(Debug))]
+pub enum Pronouns {
+    /// The user shared their pronouns.
+    Found {
+        /// The user as they were asked for.
+        user: String,
+        /// The pronouns, like `she/her`.
+        pronouns: String,
+    },
+    /// The user didn't share any pronouns yet.
+    NotSet(String),
+    /// The author's own pronouns were saved.
+    Saved,
+    /// The author's own pronouns were removed.
+    Removed,
+    /// The request couldn't be handled, with the reason why.
+    Refused(String),
+}
+
+/// Result of a lookup or change of the author's personal settings.
+#[cfg_attr(test, derive(Debug))]
+pub enum Prefs {
+    /// All settings that the author changed.
+    Current(UserPrefs),
+    /// A setting was saved.
+    Saved(UserPref),
+    /// A setting was reset to its default, with its name.
+    Reset(String),
+    /// The setting wasn't changed before, with its name.
+    NotSet(String),
+}
+
+/// Result of a birthday lookup or change.
+#[cfg_attr(test, derive(Debug))]
+pub enum Birthday {
+    /// The author registered their birthday.
+    Registered {
+        /// Month of the birthday.
+        month: Month,
+        /// Day of the month.
+        day: u8,
+        /// Whether the birthday is announced.
+        announce: bool,
+    },
+    /// The author didn't register a birthday yet.
+    NotRegistered,
+    /// The author's birthday was saved.
+    Saved {
+        /// Month of the birthday.
+        month: Month,
+        /// Day of the month.
+        day: u8,
+    },
+    /// The author's birthday was removed.
+    Removed,
+    /// Whether the author's birthday is announced now.
+    Announce(bool),
+}
+
+/// A title and category of the stream, from the point in time that they were set.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StreamTitle {
+    /// Title of the stream.
+    pub title: String,
+    /// Category (game) that was streamed.
+    pub category: String,
+    /// Time when the title and category were set.
+    #[serde(with = "time::serde::timestamp")]
+    pub changed_at: OffsetDateTime,
+}
+
+/// Result of looking up when a category was streamed last.
+#[cfg_attr(test, derive(Debug))]
+pub enum LastCategory {
+    /// The category is streamed right now.
+    Live(StreamTitle),
+    /// The category was streamed last with this title.
+    Past(StreamTitle),
+    /// The category wasn't streamed, as far as the history goes back.
+    Never(String),
+}
+
+/// Agenda of a stream, that admins set with `!topic set`.
+#[cfg_attr(test, derive(Debug))]
+pub struct Topic {
+    /// What the stream is about.
+    pub content: String,
+    /// Whether it belongs to the live stream, or to the next one.
+    pub live: bool,
+}
+
+/// Result of sending in a suggestion, either it was saved for the admins to review, or it was
+/// refused with the reason why.
+#[cfg_attr(test, derive(Debug))]
+pub enum Suggest {
+    /// Suggestion was saved, with its ID.
+    Added(i64),
+    /// Suggestion was refused, like for being too long or having too many open ones.
+    Refused(String),
+}
+
+/// Chapters of a single stream, that can be pasted into the description of its VOD.
+#[derive(Serialize)]
+#[cfg_attr(test, derive(Debug))]
+pub struct StreamChapters {
+    /// Time when the stream started.
+    #[serde(with = "time::serde::rfc3339")]
+    pub started_at: OffsetDateTime,
+    /// Chapters of the stream, each starting with a change of the title or category.
+    pub chapters: Vec<Chapter>,
+}
+
+/// A single chapter within a stream.
+#[derive(Serialize)]
+#[cfg_attr(test, derive(Debug))]
+pub struct Chapter {
+    /// Offset from the start of the stream.
+    pub offset: Duration,
+    /// Title of the stream during the chapter.
+    pub title: String,
+    /// Category (game) that was streamed during the chapter.
+    pub category: String,
+}
+
+/// Result of a watch time lookup or change.
+#[cfg_attr(test, derive(Debug))]
+pub enum WatchTime {
+    /// The author's watch time.
+    Tracked {
+        /// Time watched in the current month.
+        month: Duration,
+        /// Time watched in total, as far back as the statistics are kept.
+        total: Duration,
+    },
+    /// The author opted out of the tracking.
+    OptedOut,
+    /// Whether the author's watch time is tracked now. Opting out erases the tracked time.
+    Changed(bool),
+}
+
+/// Outcome of a gamble.
+#[cfg_attr(test, derive(Debug))]
+pub enum Gamble {
+    /// The user won the amount on top of their bet.
+    Won {
+        /// Points that were won.
+        amount: u64,
+        /// Points that the user has now.
+        balance: u64,
+    },
+    /// The user lost their bet.
+    Lost {
+        /// Points that were lost.
+        amount: u64,
+        /// Points that the user has now.
+        balance: u64,
+    },
+    /// The game didn't happen, with the reason why.
+    Refused(String),
+}
+
+/// Outcome of a duel, from the view of the user that sent the command.
+#[cfg_attr(test, derive(Debug))]
+pub enum Duel {
+    /// The challenge was sent, and waits for the opponent to accept it.
+    Challenged {
+        /// The challenged user, as the challenger named them.
+        opponent: String,
+        /// Points that the winner takes.
+        amount: u64,
+    },
+    /// The user won and took the points of the opponent.
+    Won {
+        /// The other side of the duel, as shown in the chat.
+        opponent: String,
+        /// Points that changed hands.
+        amount: u64,
+    },
+    /// The opponent won and took the points of the user.
+    Lost {
+        /// The other side of the duel, as shown in the chat.
+        opponent: String,
+        /// Points that changed hands.
+        amount: u64,
+    },
+    /// The duel didn't happen, with the reason why.
+    Refused(String),
+}
+
+/// Service that a requested song is played from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SongPlatform {
+    /// YouTube video <https://youtube.com>.
+    #[serde(rename = "youtube")]
+    YouTube,
+    /// Spotify track <https://spotify.com>.
+    Spotify,
+}
+
+impl AsRef<str> for SongPlatform {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::YouTube => "YouTube",
+            Self::Spotify => "Spotify",
+        }
+    }
+}
+
+/// A song waiting in the request queue.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct QueuedSong {
+    /// Unique ID, that also defines the order of the queue.
+    pub id: i64,
+    /// Service to play the song from.
+    pub platform: SongPlatform,
+    /// Normalized link to the song.
+    pub url: String,
+    /// Service of the user that requested the song.
+    pub source: Source,
+    /// ID of the user that requested the song, within the service.
+    pub user_id: String,
+    /// Time when the song was requested.
+    #[serde(with = "time::serde::timestamp")]
+    pub requested_at: OffsetDateTime,
+}
+
+/// Current status of the bot.
+#[cfg_attr(test, derive(Debug))]
+pub struct Status {
+    /// The currently live stream, if any.
+    pub stream: Result<Option<StreamInfo>>,
+    /// Health of each service connector.
+    pub connectors: Vec<ConnectorHealth>,
+}
+
+/// Details about the bot itself.
+#[cfg_attr(test, derive(Debug))]
+pub struct BotStat {
+    /// Version, uptime and message throughput.
+    pub runtime: RuntimeInfo,
+    /// Services that the bot is currently connected to.
+    pub connectors: Vec<Source>,
+    /// Size of the database in bytes.
+    pub db_size: Result<u64>,
+    /// Time from receiving commands until their replies were sent.
+    pub latency: Histogram,
+}
+
 /// Result of a crate search, either it was found, providing the details, or it wasn't giving some
 /// generic reply message (possibly with reason why).
 #[cfg_attr(test, derive(Debug))]
@@ -53,10 +528,53 @@ pub enum CrateSearch {
     Found(CrateInfo),
     /// Request crate couldn't be found.
     NotFound(String),
+    /// Crates.io couldn't be reached, and docs.rs only knows the latest version.
+    Fallback { name: String, version: String },
+}
+
+/// Outcome of running a Rust snippet on the playground.
+#[cfg_attr(test, derive(Debug))]
+pub enum Eval {
+    /// The snippet was built and ran.
+    Ran {
+        /// Whether it compiled and exited successfully.
+        success: bool,
+        /// Printed output of the program, or the compiler errors if it failed.
+        output: String,
+    },
+    /// Refused, as the snippet is longer than the limit.
+    TooLong {
+        /// Maximum length of a snippet in bytes.
+        max: usize,
+    },
+    /// Refused, as another snippet ran too recently, with the time until the next one can run.
+    Cooldown(Duration),
+}
+
+/// Latest versions of the Rust toolchain, one for each release channel.
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug))]
+pub struct RustVersions {
+    /// The stable channel.
+    pub stable: RustVersion,
+    /// The beta channel.
+    pub beta: RustVersion,
+    /// The nightly channel.
+    pub nightly: RustVersion,
+}
+
+/// A single release of the Rust toolchain.
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug, PartialEq))]
+pub struct RustVersion {
+    /// Version number, like `1.82.0` or `1.84.0-nightly`.
+    pub version: String,
+    /// Day that the release was built, like `2024-10-17`.
+    pub date: String,
 }
 
 /// Information about a single Rust crate.
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize)]
 #[cfg_attr(test, derive(Debug))]
 pub struct CrateInfo {
     /// Name of the crate.
@@ -74,51 +592,545 @@ pub struct CrateInfo {
     pub documentation: Option<String>,
     /// Link the the source code repository.
     pub repository: String,
+    /// Amount of published versions.
+    #[serde(default, rename = "versions", deserialize_with = "count")]
+    pub version_count: usize,
+    /// Crates that depend on this one, only looked up where there is room to show them.
+    #[serde(skip)]
+    pub dependents: Option<Dependents>,
+}
+
+/// Reverse dependencies of a crate.
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug))]
+pub struct Dependents {
+    /// Amount of crates that depend on the crate.
+    pub total: u64,
+    /// Names of the most downloaded dependents.
+    pub top: Vec<String>,
+}
+
+impl CrateInfo {
+    /// Link to the documentation, falling back to `docs.rs` if the crate doesn't define any.
+    #[must_use]
+    pub fn docs_link(&self) -> String {
+        self.documentation.clone().unwrap_or_else(|| {
+            format!(
+                "https://docs.rs/{0}/{1}/{0}",
+                self.name, self.newest_version
+            )
+        })
+    }
+}
+
+/// Deserialize a list, only counting its elements.
+fn count<'de, D>(deserializer: D) -> Result<usize, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Vec::<IgnoredAny>::deserialize(deserializer).map(|list| list.len())
+}
+
+/// Result of looking up a single version of a crate, either the version was found, or a message
+/// explains why it wasn't.
+#[cfg_attr(test, derive(Debug))]
+pub enum VersionSearch {
+    /// Found the requested version.
+    Found(VersionInfo),
+    /// Crate or version couldn't be found.
+    NotFound(String),
+}
+
+/// Information about a single published version of a Rust crate.
+#[derive(Clone, Deserialize)]
+#[cfg_attr(test, derive(Debug))]
+pub struct VersionInfo {
+    /// Name of the crate.
+    #[serde(rename = "crate")]
+    pub name: String,
+    /// The version itself.
+    pub num: semver::Version,
+    /// Time when the version was published.
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    /// Downloads of this version.
+    pub downloads: u64,
+    /// Whether the version was yanked, so it's not used for new lockfiles anymore.
+    pub yanked: bool,
+    /// License expression, like `MIT OR Apache-2.0`.
+    pub license: Option<String>,
+    /// Minimum supported Rust version.
+    pub rust_version: Option<String>,
+    /// Feature flags, with the features and dependencies each of them enables.
+    #[serde(default)]
+    pub features: BTreeMap<String, Vec<String>>,
+}
+
+impl VersionInfo {
+    /// Link to the version on crates.io.
+    #[must_use]
+    pub fn link(&self) -> String {
+        format!("https://crates.io/crates/{}/{}", self.name, self.num)
+    }
+
+    /// Link to the list of feature flags on docs.rs.
+    #[must_use]
+    pub fn features_link(&self) -> String {
+        format!("https://docs.rs/crate/{}/{}/features", self.name, self.num)
+    }
 }
 
 /// Response for an admin command.
+#[allow(clippy::large_enum_variant)]
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
 #[cfg_attr(test, derive(Debug))]
 pub enum Admin {
     /// Print a help message with all available admin control commands.
     Help,
     /// Configure custom user commands.
     CustomCommands(CustomCommands),
-    /// Show statistics about user commands.
-    Statistics(Result<(bool, Statistics)>),
+    /// Show statistics about user commands, where each section may have failed loading on its
+    /// own.
+    Statistics(StatisticsDate, Statistics),
+    /// Tag the current stream with a topic.
+    StreamTag(#[serde(serialize_with = "result")] Result<()>),
+    /// Time spent in each stage of the message handling.
+    Timing(Vec<(Stage, Histogram)>),
+    /// Manage private moderator notes about users.
+    UserNotes(UserNotes),
+    /// Remove the next song from the song request queue, if there is any.
+    SkipSong(#[serde(serialize_with = "result")] Result<Option<QueuedSong>>),
+    /// Configure the points that custom commands cost.
+    CommandCosts(CommandCosts),
+    /// Configure whether commands depend on the stream being live.
+    CommandAvailability(CommandAvailability),
+    /// Configure the daily command usage that admins get alerted about.
+    UsageAlerts(UsageAlerts),
+    /// Whether raiding channels get a shoutout, after optionally changing it.
+    Shoutout(#[serde(serialize_with = "result")] Result<bool>),
+    /// Configure trigger words.
+    Triggers(Triggers),
+    /// Configure answers to frequently asked questions.
+    Faqs(Faqs),
+    /// Configure the crates that new versions are announced for.
+    WatchCrates(WatchCrates),
+    /// Configure the Discord channels that user commands are answered in.
+    Channels(Channels),
+    /// Time that replies in a channel are suppressed for, or nothing if the silence was lifted.
+    Silence(#[serde(serialize_with = "result")] Result<Option<Duration>>),
+    /// Channel that the command list is kept pinned in now, or nothing if it isn't updated anymore.
+    CommandList(#[serde(serialize_with = "result")] Result<Option<NonZero<u64>>>),
+    /// A message that was saved as highlight. Connectors that support pinning pin it on their
+    /// service.
+    Pin(#[serde(serialize_with = "result")] Result<Highlight>),
+    /// The streamer is marked as away now.
+    Away(#[serde(serialize_with = "result")] Result<()>),
+    /// The streamer is back, with how long they were away, if they were away at all.
+    Back(#[serde(serialize_with = "result")] Result<Option<Duration>>),
+    /// There is no pending command of the author to confirm, or it already expired.
+    NothingToConfirm,
+    /// The `links` command, as it's shown on another service.
+    LinkPreview {
+        /// Service that the links are shown on.
+        source: Source,
+        /// The links, formatted by the service's profile.
+        links: Links,
+    },
+    /// Links of the settings and custom commands that don't resolve anymore.
+    CheckLinks(#[serde(serialize_with = "result")] Result<Vec<DeadLink>>),
+    /// Chapters of the requested streams, to export them for the VOD uploads.
+    Chapters(#[serde(serialize_with = "result")] Result<Vec<StreamChapters>>),
+    /// Review the suggestions of users.
+    Suggestions(Suggestions),
+    /// A GitHub issue that was created from the chat.
+    Issue(#[serde(serialize_with = "result")] Result<Issue>),
+    /// The agenda was changed.
+    Topic(#[serde(serialize_with = "result")] Result<TopicUpdate>),
+}
+
+/// Stream that a changed agenda belongs to.
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(test, derive(Debug))]
+pub enum TopicUpdate {
+    /// Agenda of the live stream was set.
+    Live,
+    /// The stream is offline, so the agenda is kept for the next one.
+    Next,
+    /// Agenda was removed.
+    Cleared,
+}
+
+/// Result of creating a GitHub issue.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(test, derive(Debug))]
+pub enum Issue {
+    /// The issue was created just now.
+    Created(GitHubIssue),
+    /// The suggestion was turned into this issue before already.
+    Existing(GitHubIssue),
+}
+
+/// An issue in the configured GitHub repository.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GitHubIssue {
+    /// Number of the issue within the repository.
+    pub number: u64,
+    /// Link to the issue on GitHub.
+    pub url: String,
+    /// Title of the issue.
+    pub title: String,
+}
+
+/// Response for suggestion related commands.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(test, derive(Debug))]
+pub enum Suggestions {
+    /// All suggestions that wait for a review.
+    List(#[serde(serialize_with = "result")] Result<Vec<Suggestion>>),
+    /// The reviewed suggestion, which its author gets notified about.
+    Review(#[serde(serialize_with = "result")] Result<Suggestion>),
+}
+
+/// A suggestion that a user sent in.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Suggestion {
+    /// Unique ID, used to review the suggestion.
+    pub id: i64,
+    /// Service of the user that sent in the suggestion.
+    pub source: Source,
+    /// ID of the user that sent in the suggestion, within the service.
+    pub user_id: String,
+    /// Display name of the user, at the time of the suggestion.
+    pub name: String,
+    /// The suggestion itself.
+    pub content: String,
+    /// Where the suggestion stands in the review.
+    pub status: SuggestionStatus,
+    /// Reason that the admins gave with their review.
+    pub reason: Option<String>,
+    /// Time when the suggestion was sent in.
+    #[serde(with = "time::serde::timestamp")]
+    pub created_at: OffsetDateTime,
+}
+
+/// Response for trigger word related commands.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(test, derive(Debug))]
+pub enum Triggers {
+    /// All trigger words.
+    List(#[serde(serialize_with = "result")] Result<Vec<Trigger>>),
+    /// Result of changing a trigger word.
+    Edit(#[serde(serialize_with = "result")] Result<()>),
+}
+
+/// Response for watched crates related commands.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(test, derive(Debug))]
+pub enum WatchCrates {
+    /// All watched crates.
+    List(#[serde(serialize_with = "result")] Result<Vec<WatchedCrate>>),
+    /// A crate that is watched now, with its current version.
+    Add(#[serde(serialize_with = "result")] Result<WatchedCrate>),
+    /// Result of no longer watching a crate.
+    Remove(#[serde(serialize_with = "result")] Result<()>),
+}
+
+/// Response for channel rule related commands.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(test, derive(Debug))]
+pub enum Channels {
+    /// All channels of the server that have a rule.
+    List(#[serde(serialize_with = "result")] Result<Vec<(NonZero<u64>, ChannelRule)>>),
+    /// Result of changing the rule of a channel.
+    Edit(#[serde(serialize_with = "result")] Result<()>),
+}
+
+/// Response for FAQ related commands.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(test, derive(Debug))]
+pub enum Faqs {
+    /// All FAQ entries.
+    List(#[serde(serialize_with = "result")] Result<Vec<Faq>>),
+    /// Result of changing a FAQ entry.
+    Edit(#[serde(serialize_with = "result")] Result<()>),
+}
+
+/// Response for command cost related commands.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(test, derive(Debug))]
+pub enum CommandCosts {
+    /// All commands that cost points, with their cost.
+    List(#[serde(serialize_with = "result")] Result<Vec<(String, u64)>>),
+    /// Result of changing the cost of a command.
+    Edit(#[serde(serialize_with = "result")] Result<()>),
+}
+
+/// Response for command availability related commands.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(test, derive(Debug))]
+pub enum CommandAvailability {
+    /// All commands that aren't always available.
+    List(#[serde(serialize_with = "result")] Result<Vec<(String, Availability)>>),
+    /// Result of changing the availability of a command.
+    Edit(#[serde(serialize_with = "result")] Result<()>),
+}
+
+/// Response for usage alert related commands.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(test, derive(Debug))]
+pub enum UsageAlerts {
+    /// All commands that have an alert, with their daily limit.
+    List(#[serde(serialize_with = "result")] Result<Vec<(String, u64)>>),
+    /// Result of changing the limit of an alert.
+    Edit(#[serde(serialize_with = "result")] Result<()>),
+}
+
+/// Response for user note related commands.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(test, derive(Debug))]
+pub enum UserNotes {
+    /// All notes about a single user.
+    List(
+        UserRef,
+        #[serde(serialize_with = "result")] Result<Vec<UserNote>>,
+    ),
+    /// Result of adding a new note, with the ID of the note.
+    Add(#[serde(serialize_with = "result")] Result<i64>),
+    /// Result of deleting a note.
+    Remove(#[serde(serialize_with = "result")] Result<()>),
+}
+
+/// A private note that an admin attached to a user.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct UserNote {
+    /// Unique ID, used to delete the note.
+    pub id: i64,
+    /// The note itself.
+    pub content: String,
+    /// Admin who wrote the note.
+    pub author: AdminId,
+    /// Time when the note was written.
+    #[serde(with = "time::serde::timestamp")]
+    pub created_at: OffsetDateTime,
+}
+
+/// A crate that is checked for new versions, which are announced once they're released.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WatchedCrate {
+    /// Name of the crate.
+    pub name: String,
+    /// Latest version that was seen.
+    pub version: String,
+    /// Entity tag of the last index response, to skip the download if nothing changed.
+    pub etag: Option<String>,
+}
+
+/// A word that fires a response, whenever it appears anywhere in a chat message.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Trigger {
+    /// The word that fires the trigger, in lowercase.
+    pub word: String,
+    /// Response to the word.
+    pub content: String,
+    /// Time in seconds until the trigger fires again on the same service.
+    pub cooldown: u64,
+    /// Services where the trigger is turned off.
+    #[serde(skip_deserializing)]
+    pub disabled: Vec<Source>,
+}
+
+/// Answer to a frequently asked question, that is given whenever a message looks like the question.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Faq {
+    /// Unique name, to manage the entry.
+    pub name: String,
+    /// Comma separated keywords, or a regular expression, that the question is recognized by.
+    pub pattern: String,
+    /// Whether the pattern is a regular expression.
+    pub regex: bool,
+    /// Answer to the question.
+    pub answer: String,
+}
+
+/// A single change to a custom command.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CommandRevision {
+    /// Unique ID of the change.
+    pub id: i64,
+    /// Service that the change applied to.
+    pub source: Source,
+    /// New content of the command, or nothing if it was removed.
+    pub content: Option<String>,
+    /// User that made the change.
+    #[serde(deserialize_with = "user_ref")]
+    pub author: UserRef,
+    /// Time when the change was made.
+    #[serde(with = "time::serde::timestamp")]
+    pub changed_at: OffsetDateTime,
+}
+
+/// A custom command on a single service, with who created and last changed it. Commands that
+/// were created before this was recorded have no creator.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CustomCommandEntry {
+    /// Name of the command.
+    pub name: String,
+    /// Service that the command exists on.
+    pub source: Source,
+    /// Fixed content that the command replies with.
+    pub content: String,
+    /// User that created the command.
+    #[serde(deserialize_with = "opt_user_ref")]
+    pub created_by: Option<UserRef>,
+    /// Time when the command was created.
+    #[serde(with = "time::serde::timestamp::option")]
+    pub created_at: Option<OffsetDateTime>,
+    /// User that changed the command last, if it was changed at all.
+    #[serde(deserialize_with = "opt_user_ref")]
+    pub updated_by: Option<UserRef>,
+    /// Time of the last change.
+    #[serde(with = "time::serde::timestamp::option")]
+    pub updated_at: Option<OffsetDateTime>,
+}
+
+/// Serialize the outcome of a command as `{"ok": value}` or `{"error": "message"}`, as the errors
+/// themselves can't be serialized.
+pub(crate) fn result<T, S>(res: &Result<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    match res {
+        Ok(value) => serializer.serialize_newtype_variant("Result", 0, "ok", value),
+        Err(e) => serializer.serialize_newtype_variant("Result", 1, "error", &format!("{e:#}")),
+    }
+}
+
+fn user_ref<'de, D>(deserializer: D) -> Result<UserRef, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    String::deserialize(deserializer)?
+        .parse()
+        .map_err(de::Error::custom)
+}
+
+fn opt_user_ref<'de, D>(deserializer: D) -> Result<Option<UserRef>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| value.parse())
+        .transpose()
+        .map_err(de::Error::custom)
 }
 
 /// Response for custom command administration related commands.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
 #[cfg_attr(test, derive(Debug))]
 pub enum CustomCommands {
-    /// List the available custom commands, split by service.
-    List(Result<BTreeMap<String, BTreeSet<Source>>>),
-    /// Add/change/delete custom commands.
-    Edit(Result<()>),
+    /// List the available custom commands, with an entry for each service.
+    List(#[serde(serialize_with = "result")] Result<BTreeMap<String, Vec<CustomCommandEntry>>>),
+    /// Content and provenance of a single custom command, on each service it exists on.
+    Show(
+        String,
+        #[serde(serialize_with = "result")] Result<Vec<CustomCommandEntry>>,
+    ),
+    /// Recent changes to a single custom command.
+    History(
+        String,
+        #[serde(serialize_with = "result")] Result<Vec<CommandRevision>>,
+    ),
+    /// Add/change/delete custom commands, or revert the last change. Added content comes with
+    /// the mentions in it, that would notify many users at once.
+    Edit(#[serde(serialize_with = "result")] Result<Vec<MassMention>>),
+}
+
+/// Mention in the content of a custom command, that notifies many users at once on Discord.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MassMention {
+    /// Everyone on the server, with `@everyone`.
+    Everyone,
+    /// Everyone that is online, with `@here`.
+    Here,
+    /// All members of a role, with `<@&id>`.
+    Role,
 }
 
 /// Response for an owner command.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
 #[cfg_attr(test, derive(Debug))]
 pub enum Owner {
     /// Show the help message for owners.
     Help,
     /// Admin users related commands.
     Admins(Admins),
+    /// Plugin management related commands.
+    Plugins(Plugins),
+    /// Debug representation of a parsed message, or the parse error.
+    DebugParse(String),
+    /// Slash commands are being registered again, in the background.
+    ResyncCommands,
+    /// Outcome of cleaning up old statistics.
+    Cleanup(#[serde(serialize_with = "result")] Result<Cleanup>),
+    /// Message that is posted on all services, in the background.
+    Broadcast(String),
+    /// Current configuration of the injected faults.
+    #[cfg(feature = "chaos")]
+    Chaos(crate::chaos::Faults),
 }
 
 /// Response for admin user management commands.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
 #[cfg_attr(test, derive(Debug))]
 pub enum Admins {
-    /// List the current admins.
-    List(Vec<AdminId>),
+    /// List the current admins, and the roles whose members are admins.
+    List {
+        users: Vec<AdminId>,
+        roles: Vec<NonZero<u64>>,
+    },
     /// Edit the current admin list.
-    Edit(Result<AdminAction>),
+    Edit(#[serde(serialize_with = "result")] Result<AdminAction>),
 }
 
 /// Possible actions for admin list edits.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
 #[cfg_attr(test, derive(Debug))]
 pub enum AdminAction {
     /// Account was added to the admin list.
     Added,
     /// Account was removed from the admin list.
     Removed,
+    /// Role was added to the admin roles.
+    RoleAdded,
+    /// Role was removed from the admin roles.
+    RoleRemoved,
+}
+
+/// Response for plugin management commands.
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(test, derive(Debug))]
+pub enum Plugins {
+    /// List all registered plugins.
+    List(Vec<PluginInfo>),
+    /// Load or unload a plugin.
+    Edit(#[serde(serialize_with = "result")] Result<PluginAction>),
 }
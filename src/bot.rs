@@ -0,0 +1,381 @@
+//! The command handling of the bot, detached from any chat service, so it can be embedded into
+//! other applications.
+//!
+//! A [`Bot`] owns the settings and databases, and turns incoming messages into responses. The
+//! responses can then be rendered for any service with the [`render`](crate::render) module:
+//!
+//! ```
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() -> anyhow::Result<()> {
+//! use togglebot::{
+//!     api::{AuthorId, Source},
+//!     bot::Bot,
+//!     render,
+//!     settings::Commands,
+//! };
+//!
+//! let bot = Bot::builder(Commands::default()).in_memory().build()?;
+//! let resp = bot
+//!     .handle_text(
+//!         Source::Twitch,
+//!         AuthorId::Twitch("viewer".to_owned()).into(),
+//!         "!ban everyone",
+//!     )
+//!     .await?;
+//!
+//! assert_eq!(
+//!     Some("everyone, YOU SHALL NOT PASS!!".to_owned()),
+//!     resp.and_then(render::plain::response),
+//! );
+//! # Ok(())
+//! # }
+//! ```
+
+use std::{
+    process,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+
+use anyhow::Result;
+use tracing::Span;
+
+use crate::{
+    api::{response::Response, Author, Event, Message, Source},
+    clock::{self, SharedClock},
+    db::connection::{Connection, MigrationStatus},
+    fun::Fun,
+    handler::{self, Access, AsyncCommandSettings, Memory},
+    health::Health,
+    plugins::Plugins,
+    settings::{
+        Commands as CommandSettings, Database as DatabaseSettings, Discord as DiscordSettings,
+    },
+    state::{self, State},
+    statistics::{self, Stats},
+    textparse,
+    timing::{Stage, Timings},
+};
+
+/// Command handling with all the data it needs, created through a [`BotBuilder`].
+pub struct Bot {
+    settings: AsyncCommandSettings,
+    discord: Option<DiscordSettings>,
+    state: State,
+    statistics: Stats,
+    plugins: Plugins,
+    fun: Fun,
+    health: Health,
+    timings: Timings,
+    clock: SharedClock,
+    memory: Memory,
+}
+
+impl Bot {
+    /// Start configuring a new bot with the given settings for built-in commands.
+    #[must_use]
+    pub fn builder(settings: CommandSettings) -> BotBuilder {
+        BotBuilder {
+            settings,
+            discord: None,
+            storage: Storage::Database(DatabaseSettings::default()),
+            plugins: Plugins::default(),
+            fun: None,
+            health: Health::default(),
+            timings: Timings::default(),
+            clock: clock::system(),
+        }
+    }
+
+    /// Determine the access level of a message's author.
+    #[must_use]
+    pub fn access(&self, author: &Author) -> Access {
+        let start = Instant::now();
+        let access = handler::access(self.discord.as_ref(), &self.state, author);
+        self.timings.record(Stage::Access, start.elapsed());
+
+        access
+    }
+
+    /// Handle an already parsed message, or nothing if the bot doesn't reply to it.
+    pub async fn handle(&self, message: Message) -> Option<Result<Response>> {
+        let access = self.access(&message.author);
+        let resp = handler::message(
+            &self.memory,
+            &self.settings,
+            &self.state,
+            &self.statistics,
+            &self.plugins,
+            &self.fun,
+            &self.health,
+            &self.timings,
+            &*self.clock,
+            access,
+            message,
+        );
+
+        self.timings.measure(Stage::Handler, resp).await
+    }
+
+    /// Parse a chat message and handle it, or nothing if it isn't a command or the bot doesn't
+    /// reply to it.
+    pub async fn handle_text(
+        &self,
+        source: Source,
+        author: Author,
+        text: &str,
+    ) -> Result<Option<Response>> {
+        let Some(content) = textparse::parse(text, source, None)? else {
+            return Ok(None);
+        };
+
+        let message = Message {
+            span: Span::current(),
+            source,
+            content,
+            author,
+            mention: None,
+            channel: None,
+        };
+
+        self.handle(message).await.transpose()
+    }
+
+    /// Update the state according to an event that isn't related to any message.
+    pub fn event(&self, event: Event) {
+        handler::event(
+            &self.settings,
+            &self.state,
+            &self.statistics,
+            &*self.clock,
+            event,
+        );
+    }
+
+    /// Settings for the built-in commands.
+    #[must_use]
+    pub fn settings(&self) -> &AsyncCommandSettings {
+        &self.settings
+    }
+
+    /// Access to the state, for example to set up admins or custom commands.
+    #[must_use]
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// Access to the collected statistics.
+    #[must_use]
+    pub fn statistics(&self) -> &Stats {
+        &self.statistics
+    }
+
+    /// Providers of the fun commands, for example to replace one of them.
+    #[must_use]
+    pub fn fun(&self) -> &Fun {
+        &self.fun
+    }
+
+    /// Health of the bot, which is updated with every handled message.
+    #[must_use]
+    pub fn health(&self) -> &Health {
+        &self.health
+    }
+
+    /// Latencies of the message handling.
+    #[must_use]
+    pub fn timings(&self) -> &Timings {
+        &self.timings
+    }
+
+    /// Source of the current time for all time dependent commands.
+    #[must_use]
+    pub fn clock(&self) -> &SharedClock {
+        &self.clock
+    }
+
+    /// Cooldowns and other short-lived data of the command handling, for example to give raiding
+    /// channels a shoutout.
+    #[must_use]
+    pub fn memory(&self) -> &Memory {
+        &self.memory
+    }
+}
+
+/// Where the bot keeps its data.
+enum Storage {
+    /// The configured database, which is by default the same SQLite file that the `togglebot`
+    /// binary uses.
+    Database(DatabaseSettings),
+    /// A fresh in-memory database, that is gone once the bot is dropped.
+    InMemory,
+    /// Databases that were already opened.
+    Existing(State, Stats),
+}
+
+/// Configuration of a [`Bot`], with defaults for anything not set explicitly.
+pub struct BotBuilder {
+    settings: CommandSettings,
+    discord: Option<DiscordSettings>,
+    storage: Storage,
+    plugins: Plugins,
+    fun: Option<Fun>,
+    health: Health,
+    timings: Timings,
+    clock: SharedClock,
+}
+
+impl BotBuilder {
+    /// Discord settings, which define the owners and admin roles. Without them, only Discord admins
+    /// from the state have elevated access.
+    #[must_use]
+    pub fn discord(mut self, settings: DiscordSettings) -> Self {
+        self.discord = Some(settings);
+        self
+    }
+
+    /// Store all data in the given database, instead of the local SQLite file.
+    #[must_use]
+    pub fn database(mut self, settings: DatabaseSettings) -> Self {
+        self.storage = Storage::Database(settings);
+        self
+    }
+
+    /// Keep all data in memory only, instead of the database file.
+    #[must_use]
+    pub fn in_memory(mut self) -> Self {
+        self.storage = Storage::InMemory;
+        self
+    }
+
+    /// Use already opened databases, for example to share them with other parts of the
+    /// application.
+    #[must_use]
+    pub fn databases(mut self, state: State, statistics: Stats) -> Self {
+        self.storage = Storage::Existing(state, statistics);
+        self
+    }
+
+    /// Plugins that provide additional commands.
+    #[must_use]
+    pub fn plugins(mut self, plugins: Plugins) -> Self {
+        self.plugins = plugins;
+        self
+    }
+
+    /// Providers of the fun commands, instead of the ones described in the settings.
+    #[must_use]
+    pub fn fun(mut self, fun: Fun) -> Self {
+        self.fun = Some(fun);
+        self
+    }
+
+    /// Health tracking, if it's shared with other parts of the application.
+    #[must_use]
+    pub fn health(mut self, health: Health) -> Self {
+        self.health = health;
+        self
+    }
+
+    /// Latency tracking, if it's shared with other parts of the application.
+    #[must_use]
+    pub fn timings(mut self, timings: Timings) -> Self {
+        self.timings = timings;
+        self
+    }
+
+    /// Clock for all time dependent commands and the statistics, instead of the system clock. It
+    /// doesn't apply to already opened databases.
+    #[must_use]
+    pub fn clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Open the databases and create the bot.
+    pub fn build(self) -> Result<Bot> {
+        let (state, statistics) = match self.storage {
+            Storage::Database(settings) => open(&settings, Arc::clone(&self.clock))?,
+            Storage::InMemory => in_memory(Arc::clone(&self.clock))?,
+            Storage::Existing(state, statistics) => (state, statistics),
+        };
+
+        let fun = match self.fun {
+            Some(fun) => fun,
+            None => Fun::from_settings(&self.settings.fun)?,
+        };
+
+        Ok(Bot {
+            settings: Arc::new(self.settings),
+            discord: self.discord,
+            state,
+            statistics,
+            plugins: self.plugins,
+            fun,
+            health: self.health,
+            timings: self.timings,
+            clock: self.clock,
+            memory: Memory::default(),
+        })
+    }
+}
+
+/// Open the state and statistics databases, migrating any data of the former file storage.
+pub fn open_databases(settings: &DatabaseSettings) -> Result<(State, Stats)> {
+    open(settings, clock::system())
+}
+
+/// Everything that [`open_databases`] would migrate on the next start.
+pub struct PendingMigrations {
+    /// Schema migrations of the database.
+    pub schema: MigrationStatus,
+    /// Entries of the former state file, if it still exists.
+    pub state_file: Option<usize>,
+    /// Entries of the former statistics file, if it still exists.
+    pub statistics_file: Option<usize>,
+}
+
+/// Check what [`open_databases`] would migrate, without changing anything. On a dry run, the
+/// schema migrations are run and rolled back again, to find any errors in them.
+pub fn check_databases(settings: &DatabaseSettings, dry_run: bool) -> Result<PendingMigrations> {
+    Ok(PendingMigrations {
+        schema: Connection::status(settings, dry_run)?,
+        state_file: state::pending_import()?,
+        statistics_file: statistics::pending_import()?,
+    })
+}
+
+/// Open a fresh in-memory database for the state and statistics. Both get their own connection to
+/// the same database, so the statistics see the streams that the state records.
+fn in_memory(clock: SharedClock) -> Result<(State, Stats)> {
+    static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+    let name = format!(
+        "togglebot-{}-{}",
+        process::id(),
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+    );
+    let state = State::new(Connection::in_memory_shared(&name)?);
+    let statistics = Stats::with_clock(Connection::in_memory_shared(&name)?, clock);
+
+    Ok((state, statistics))
+}
+
+fn open(settings: &DatabaseSettings, clock: SharedClock) -> Result<(State, Stats)> {
+    let state = {
+        let conn = Connection::new(settings)?;
+        state::migrate(&conn)?;
+        State::new(conn)
+    };
+
+    let statistics = {
+        let conn = Connection::new(settings)?;
+        statistics::migrate(&conn)?;
+        Stats::with_clock(conn, clock)
+    };
+
+    Ok((state, statistics))
+}
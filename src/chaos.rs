@@ -0,0 +1,121 @@
+//! Fault injection for staging deployments, to see how the bot behaves when things go wrong.
+//!
+//! Only available with the `chaos` feature. All faults are disabled on startup and can be turned on
+//! at runtime through the `!chaos` owner command. Each fault triggers randomly with its configured
+//! rate, given in percent:
+//!
+//! - **Delays** hold back the handling of a message for a random time up to a maximum.
+//! - **Send failures** make connectors fail sending a message, before it reaches the service.
+//! - **Dropped events** make the Twitch connector ignore `EventSub` notifications.
+
+use std::{
+    fmt::{self, Display},
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
+
+use anyhow::{bail, Result};
+use rand::Rng;
+use serde::Serialize;
+use tracing::warn;
+
+static FAULTS: Mutex<Faults> = Mutex::new(Faults::NONE);
+
+/// Current configuration of all faults.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct Faults {
+    /// Chance to delay the handling of a message, in percent.
+    pub delay_rate: u8,
+    /// Upper limit for the random delays.
+    pub delay_max: Duration,
+    /// Chance that sending a message fails, in percent.
+    pub send_failure_rate: u8,
+    /// Chance that an incoming event is dropped, in percent.
+    pub event_drop_rate: u8,
+}
+
+impl Faults {
+    /// All faults disabled.
+    pub const NONE: Self = Self {
+        delay_rate: 0,
+        delay_max: Duration::from_secs(5),
+        send_failure_rate: 0,
+        event_drop_rate: 0,
+    };
+}
+
+impl Display for Faults {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "delays: {}% (up to {}ms), send failures: {}%, dropped events: {}%",
+            self.delay_rate,
+            self.delay_max.as_millis(),
+            self.send_failure_rate,
+            self.event_drop_rate,
+        )
+    }
+}
+
+/// Get the current fault configuration.
+#[must_use]
+pub fn faults() -> Faults {
+    *FAULTS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Change the fault configuration, returning the new state.
+pub fn configure(f: impl FnOnce(&mut Faults)) -> Faults {
+    let mut faults = FAULTS.lock().unwrap_or_else(PoisonError::into_inner);
+    f(&mut faults);
+    warn!(faults = %*faults, "fault injection changed");
+    *faults
+}
+
+fn triggers(rate: u8) -> bool {
+    rate > 0 && rand::thread_rng().gen_range(0..100) < rate
+}
+
+/// Wait for a random time, if the delay fault triggers.
+pub async fn delay() {
+    let Faults {
+        delay_rate,
+        delay_max,
+        ..
+    } = faults();
+
+    if triggers(delay_rate) {
+        let delay = rand::thread_rng().gen_range(Duration::ZERO..=delay_max);
+        warn!(?delay, "injecting handler delay");
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Fail with an error, if the send failure fault triggers.
+pub fn send() -> Result<()> {
+    if triggers(faults().send_failure_rate) {
+        bail!("injected send failure");
+    }
+
+    Ok(())
+}
+
+/// Whether the next incoming event should be dropped.
+#[must_use]
+pub fn drop_event() -> bool {
+    let drop = triggers(faults().event_drop_rate);
+    if drop {
+        warn!("injecting dropped event");
+    }
+    drop
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trigger_rates() {
+        assert!(!triggers(0));
+        assert!(triggers(100));
+    }
+}
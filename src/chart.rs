@@ -0,0 +1,135 @@
+//! Drawing of simple charts as PNG images, to attach them to messages on services that can show
+//! images.
+//!
+//! The font is embedded into the binary, so charts look the same everywhere and don't depend on the
+//! fonts that happen to be installed on the system.
+
+use std::sync::LazyLock;
+
+use anyhow::{anyhow, ensure, Result};
+use plotters::{
+    prelude::*,
+    style::{register_font, FontStyle},
+};
+
+/// Font for all text in the charts.
+static FONT: &[u8] = include_bytes!("../assets/fonts/DejaVuSans.ttf");
+
+/// Width of the charts in pixels.
+const WIDTH: u32 = 800;
+/// Height of a single bar in pixels, including the gap to the next one.
+const BAR_HEIGHT: u32 = 28;
+/// Color of the bars.
+const BAR_COLOR: RGBColor = RGBColor(222, 165, 132);
+
+/// Draw a horizontal bar chart, with one bar per label, in the given order from top to bottom.
+pub fn bars(title: &str, bars: &[(String, u64)]) -> Result<Vec<u8>> {
+    ensure!(!bars.is_empty(), "there is nothing to draw");
+    register()?;
+
+    let count = u32::try_from(bars.len())?;
+    let height = 90 + count * BAR_HEIGHT;
+    let max = bars
+        .iter()
+        .map(|(_, value)| *value)
+        .max()
+        .unwrap_or_default();
+    let label_width = bars
+        .iter()
+        .map(|(label, _)| label.chars().count())
+        .max()
+        .unwrap_or_default();
+
+    // The y axis goes upwards, so the rows are counted from the bottom.
+    let row = |i: usize| bars.len() - 1 - i;
+
+    let mut pixels = vec![0; usize::try_from(WIDTH * height * 3)?];
+    {
+        let root = BitMapBackend::with_buffer(&mut pixels, (WIDTH, height)).into_drawing_area();
+        root.fill(&WHITE).map_err(|e| anyhow!("{e}"))?;
+
+        let mut chart = ChartBuilder::on(&root)
+            .caption(title, ("sans-serif", 24))
+            .margin(12)
+            .x_label_area_size(30)
+            .y_label_area_size(u32::try_from(label_width.clamp(4, 30))? * 9 + 10)
+            .build_cartesian_2d(0..max + max / 10 + 1, (0..bars.len()).into_segmented())
+            .map_err(|e| anyhow!("{e}"))?;
+
+        chart
+            .configure_mesh()
+            .disable_y_mesh()
+            .light_line_style(TRANSPARENT)
+            .y_labels(bars.len())
+            .y_label_formatter(&|value| match value {
+                SegmentValue::CenterOf(i) if *i < bars.len() => bars[row(*i)].0.clone(),
+                _ => String::new(),
+            })
+            .label_style(("sans-serif", 15))
+            .draw()
+            .map_err(|e| anyhow!("{e}"))?;
+
+        chart
+            .draw_series(bars.iter().enumerate().map(|(i, (_, value))| {
+                let mut bar = Rectangle::new(
+                    [
+                        (0, SegmentValue::Exact(row(i))),
+                        (*value, SegmentValue::Exact(row(i) + 1)),
+                    ],
+                    BAR_COLOR.filled(),
+                );
+                bar.set_margin(4, 4, 0, 0);
+                bar
+            }))
+            .map_err(|e| anyhow!("{e}"))?;
+
+        root.present().map_err(|e| anyhow!("{e}"))?;
+    }
+
+    encode(&pixels, WIDTH, height)
+}
+
+/// Make the embedded font available to the charts, which only happens once.
+fn register() -> Result<()> {
+    static REGISTERED: LazyLock<bool> =
+        LazyLock::new(|| register_font("sans-serif", FontStyle::Normal, FONT).is_ok());
+
+    ensure!(*REGISTERED, "the chart font is invalid");
+    Ok(())
+}
+
+/// Encode raw RGB pixels as PNG image.
+fn encode(pixels: &[u8], width: u32, height: u32) -> Result<Vec<u8>> {
+    let mut image = Vec::new();
+
+    let mut encoder = png::Encoder::new(&mut image, width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(pixels)?;
+    writer.finish()?;
+
+    Ok(image)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draw_png() {
+        let image = bars(
+            "Command usage",
+            &[("!crate".to_owned(), 42), ("!hello".to_owned(), 7)],
+        )
+        .unwrap();
+
+        assert!(image.starts_with(b"\x89PNG\r\n\x1a\n"));
+    }
+
+    #[test]
+    fn nothing_to_draw() {
+        assert!(bars("Command usage", &[]).is_err());
+    }
+}
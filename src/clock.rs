@@ -0,0 +1,65 @@
+//! Source of the current time, so that time dependent behavior can be tested with a fixed or
+//! advancing time instead of the system clock.
+
+use std::sync::Arc;
+#[cfg(any(test, feature = "testing"))]
+use std::sync::{Mutex, PoisonError};
+
+use time::OffsetDateTime;
+
+/// Convenience type alias for a [`Clock`] that is shared between the handler and statistics.
+pub type SharedClock = Arc<dyn Clock>;
+
+/// Provider of the current date and time.
+pub trait Clock: Send + Sync {
+    /// Current date and time in UTC.
+    fn now(&self) -> OffsetDateTime;
+}
+
+/// The system clock, which is used everywhere outside of tests.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> OffsetDateTime {
+        OffsetDateTime::now_utc()
+    }
+}
+
+/// Shorthand for a shared [`SystemClock`].
+#[must_use]
+pub fn system() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+/// Clock that stands still at a set time, until it's moved on explicitly. Only available in tests
+/// and with the `testing` feature.
+#[cfg(any(test, feature = "testing"))]
+#[derive(Debug)]
+pub struct MockClock(Mutex<OffsetDateTime>);
+
+#[cfg(any(test, feature = "testing"))]
+impl MockClock {
+    /// Create a clock that stands at the given time.
+    #[must_use]
+    pub fn new(now: OffsetDateTime) -> Self {
+        Self(Mutex::new(now))
+    }
+
+    /// Move the clock to the given time.
+    pub fn set(&self, now: OffsetDateTime) {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = now;
+    }
+
+    /// Move the clock forward by the given duration.
+    pub fn advance(&self, duration: time::Duration) {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) += duration;
+    }
+}
+
+#[cfg(any(test, feature = "testing"))]
+impl Clock for MockClock {
+    fn now(&self) -> OffsetDateTime {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -0,0 +1,169 @@
+//! Single place that describes all built-in commands. The parser resolves aliases with it, help
+//! texts and command lists are rendered from it, custom commands can't take any of its names, and
+//! the usage statistics take their command names from it.
+
+use crate::{api::Source, handler::Access, statistics::BuiltinCommand};
+
+pub mod registry;
+
+/// Metadata about a single built-in command.
+#[derive(Debug)]
+pub struct CommandInfo {
+    /// Main name of the command, without the `!` prefix.
+    pub name: &'static str,
+    /// Alternative names that trigger the same command.
+    pub aliases: &'static [&'static str],
+    /// Access level that is required to use the command, which is also the category it's listed
+    /// under.
+    pub access: Access,
+    /// Services the command is limited to, or empty if it's available everywhere.
+    pub sources: &'static [Source],
+    /// All forms of the command with their arguments. Commands that can't be written in chat, like
+    /// Discord context menus, have none.
+    pub usage: &'static [Usage],
+    /// What the command does, in a single sentence.
+    pub description: &'static str,
+    /// Full invocations, showing typical uses of the command.
+    pub examples: &'static [&'static str],
+    /// Counter of the command in the usage statistics, if it's counted at all.
+    pub statistic: Option<BuiltinCommand>,
+}
+
+/// Single form of a command, like `!crate <name>` or `!crate compare <first> <second>`.
+#[derive(Debug)]
+pub struct Usage {
+    /// Arguments after the command name, empty if the form takes none.
+    pub args: &'static str,
+    /// What this form of the command does.
+    pub description: &'static str,
+}
+
+impl CommandInfo {
+    /// Whether the command can be used on the given service.
+    #[must_use]
+    pub fn available_on(&self, source: Source) -> bool {
+        self.sources.is_empty() || self.sources.contains(&source)
+    }
+
+    /// Whether any form of the command takes arguments.
+    #[must_use]
+    pub fn takes_args(&self) -> bool {
+        self.usage.iter().any(|usage| !usage.args.is_empty())
+    }
+
+    /// All forms of the command as written in chat, like `!crate <name>`.
+    pub fn forms(&self) -> impl Iterator<Item = (String, &'static str)> + '_ {
+        self.usage.iter().map(|usage| {
+            let form = if usage.args.is_empty() {
+                format!("!{}", self.name)
+            } else {
+                format!("!{} {}", self.name, usage.args)
+            };
+            (form, usage.description)
+        })
+    }
+
+    fn matches(&self, name: &str) -> bool {
+        self.name == name || self.aliases.contains(&name)
+    }
+}
+
+/// Find a built-in command by its name or any of its aliases, ignoring case and a leading `!`.
+#[must_use]
+pub fn find(name: &str) -> Option<&'static CommandInfo> {
+    let name = name.strip_prefix('!').unwrap_or(name).to_lowercase();
+    registry::COMMANDS.iter().find(|cmd| cmd.matches(&name))
+}
+
+/// Whether the name belongs to any of the built-in commands, so it can't be used for custom
+/// commands.
+///
+/// As custom commands are checked last, there is no chance of accidentally hiding the other
+/// commands, but refusing these names helps to avoid confusion about commands not being triggered.
+#[must_use]
+pub fn is_reserved(name: &str) -> bool {
+    registry::COMMANDS.iter().any(|cmd| cmd.matches(name))
+}
+
+/// Whether the name belongs to a user command that doesn't take any arguments.
+#[must_use]
+pub fn without_args(name: &str) -> bool {
+    registry::COMMANDS
+        .iter()
+        .any(|cmd| cmd.access == Access::Standard && !cmd.takes_args() && cmd.matches(name))
+}
+
+/// All commands of the access level, in the order they're declared.
+pub fn listed(access: Access) -> impl Iterator<Item = &'static CommandInfo> {
+    registry::COMMANDS
+        .iter()
+        .filter(move |cmd| cmd.access == access)
+}
+
+/// Message that explains how to use a command, listing all its forms.
+#[must_use]
+pub fn usage(name: &str) -> String {
+    let Some(cmd) = find(name) else {
+        return format!("usage: !{name}");
+    };
+
+    let forms = cmd.forms().map(|(form, _)| form).collect::<Vec<_>>();
+    match forms.split_last() {
+        Some((last, [])) => format!("usage: {last}"),
+        Some((last, rest)) => format!("usage: {} or {last}", rest.join(", ")),
+        None => format!("usage: !{}", cmd.name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_by_alias() {
+        assert_eq!("crate", find("crates").unwrap().name);
+        assert_eq!("help", find("!BOT").unwrap().name);
+        assert_eq!("ahelp", find("admin-help").unwrap().name);
+        assert_eq!("rustversion", find("rustversion").unwrap().name);
+        assert!(find("hello").is_none());
+    }
+
+    #[test]
+    fn unique_names() {
+        let mut names = registry::COMMANDS
+            .iter()
+            .flat_map(|cmd| std::iter::once(cmd.name).chain(cmd.aliases.iter().copied()))
+            .collect::<Vec<_>>();
+        let count = names.len();
+        names.sort_unstable();
+        names.dedup();
+
+        assert_eq!(count, names.len());
+    }
+
+    #[test]
+    fn only_user_commands_without_args() {
+        assert!(without_args("links"));
+        assert!(without_args("rustversions"));
+        assert!(!without_args("help"));
+        assert!(!without_args("skip"));
+    }
+
+    #[test]
+    fn usage_of_all_forms() {
+        assert_eq!("usage: !eval <code>", usage("eval"));
+        assert_eq!(
+            "usage: !pronouns <user>, !pronouns set <pronouns> or !pronouns unset",
+            usage("pronouns")
+        );
+    }
+
+    #[test]
+    fn only_user_commands_counted() {
+        for cmd in registry::COMMANDS {
+            if cmd.statistic.is_some() {
+                assert_eq!(Access::Standard, cmd.access, "{}", cmd.name);
+            }
+        }
+    }
+}
@@ -0,0 +1,1181 @@
+//! Declaration of every built-in command, grouped by access level and in the order they're listed
+//! in help texts.
+
+use super::{CommandInfo, Usage};
+use crate::{api::Source, handler::Access, statistics::BuiltinCommand};
+
+/// All built-in commands.
+pub static COMMANDS: &[CommandInfo] = &[
+    // user commands
+    CommandInfo {
+        name: "help",
+        aliases: &["bot"],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[
+            Usage {
+                args: "",
+                description: "Give a short info about this bot.",
+            },
+            Usage {
+                args: "<command>",
+                description: "Show the details of a single command.",
+            },
+        ],
+        description: "Show a short introduction of the bot, or the details of a single command.",
+        examples: &["!help", "!help crate"],
+        statistic: Some(BuiltinCommand::Help),
+    },
+    CommandInfo {
+        name: "commands",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "List all commands that are available here.",
+        }],
+        description: "List all commands that are available on this service.",
+        examples: &["!commands"],
+        statistic: Some(BuiltinCommand::Commands),
+    },
+    CommandInfo {
+        name: "links",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Give a list of links to sites where the streamer is present.",
+        }],
+        description: "List the places where the streamer can be found.",
+        examples: &["!links"],
+        statistic: Some(BuiltinCommand::Links),
+    },
+    CommandInfo {
+        name: "ban",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "<target>",
+            description: "Refuse anything with the power of Gandalf.",
+        }],
+        description: "Ban anybody or anything, but only for fun.",
+        examples: &["!ban the borrow checker"],
+        statistic: Some(BuiltinCommand::Ban),
+    },
+    CommandInfo {
+        name: "crate",
+        aliases: &["crates"],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[
+            Usage {
+                args: "<name>",
+                description: "Get the link for any existing crate.",
+            },
+            Usage {
+                args: "<name>@<version>",
+                description: "Show details about a version of a crate, like `1.38` or `1.38.0`.",
+            },
+            Usage {
+                args: "<name> features",
+                description: "List the feature flags of a crate, optionally of a version.",
+            },
+            Usage {
+                args: "compare <first> <second>",
+                description: "Compare two crates side by side.",
+            },
+        ],
+        description: "Look up a Rust crate, its versions and feature flags, or compare two crates.",
+        examples: &[
+            "!crate serde",
+            "!crate tokio@1.38",
+            "!crate tokio features",
+            "!crate compare tokio smol",
+        ],
+        statistic: Some(BuiltinCommand::Crate),
+    },
+    CommandInfo {
+        name: "rustversion",
+        aliases: &["rustversions"],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Show the current stable, beta and nightly versions of Rust.",
+        }],
+        description: "Show the current Rust versions of each release channel.",
+        examples: &["!rustversion"],
+        statistic: Some(BuiltinCommand::RustVersions),
+    },
+    CommandInfo {
+        name: "eval",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "<code>",
+            description: "Run a Rust snippet on the playground and show its output.",
+        }],
+        description: "Run a Rust snippet on the playground and show its output.",
+        examples: &["!eval 1 + 2"],
+        statistic: Some(BuiltinCommand::Eval),
+    },
+    CommandInfo {
+        name: "today",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Get details about the current day.",
+        }],
+        description: "Tell the current date, in far too much detail.",
+        examples: &["!today"],
+        statistic: Some(BuiltinCommand::Today),
+    },
+    CommandInfo {
+        name: "advice",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Get a random piece of advice.",
+        }],
+        description: "Get a random piece of advice.",
+        examples: &["!advice"],
+        statistic: Some(BuiltinCommand::Advice),
+    },
+    CommandInfo {
+        name: "fact",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Get a random fact.",
+        }],
+        description: "Get a random fact.",
+        examples: &["!fact"],
+        statistic: Some(BuiltinCommand::Fact),
+    },
+    CommandInfo {
+        name: "joke",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Get a random joke.",
+        }],
+        description: "Get a random joke.",
+        examples: &["!joke"],
+        statistic: Some(BuiltinCommand::Joke),
+    },
+    CommandInfo {
+        name: "status",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Show whether the bot is up and the streamer is live.",
+        }],
+        description: "Show whether the stream is live and what it's about.",
+        examples: &["!status"],
+        statistic: Some(BuiltinCommand::Status),
+    },
+    CommandInfo {
+        name: "botstat",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Show the bot's version, uptime and connected services.",
+        }],
+        description: "Show the version, uptime and other details of the bot itself.",
+        examples: &["!botstat"],
+        statistic: Some(BuiltinCommand::BotStat),
+    },
+    CommandInfo {
+        name: "sr",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "<link>",
+            description: "Request a song by its YouTube or Spotify link.",
+        }],
+        description: "Add a song to the song request queue.",
+        examples: &["!sr https://youtu.be/dQw4w9WgXcQ"],
+        statistic: Some(BuiltinCommand::SongRequest),
+    },
+    CommandInfo {
+        name: "queue",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Show the next songs in the song request queue.",
+        }],
+        description: "Show the next songs in the song request queue.",
+        examples: &["!queue"],
+        statistic: Some(BuiltinCommand::SongQueue),
+    },
+    CommandInfo {
+        name: "points",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Show how many points you collected by chatting.",
+        }],
+        description: "Show the points you collected by chatting.",
+        examples: &["!points"],
+        statistic: Some(BuiltinCommand::Points),
+    },
+    CommandInfo {
+        name: "gamble",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "<amount>",
+            description: "Bet your points, with the chance to double them.",
+        }],
+        description: "Bet points, with the chance to double them.",
+        examples: &["!gamble 50"],
+        statistic: Some(BuiltinCommand::Gamble),
+    },
+    CommandInfo {
+        name: "duel",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "<user> <amount>",
+            description: "Challenge somebody, the winner takes the points.",
+        }],
+        description: "Challenge another user, where the winner takes the points.",
+        examples: &["!duel somebody 100"],
+        statistic: Some(BuiltinCommand::Duel),
+    },
+    CommandInfo {
+        name: "accept",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Take on the duel that somebody challenged you to.",
+        }],
+        description: "Accept a duel, before any points are at stake.",
+        examples: &["!accept"],
+        // Counted as `!duel`, when the challenge is sent.
+        statistic: None,
+    },
+    CommandInfo {
+        name: "poll",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "\"<question>\" <options...> <duration>",
+            description: "Start a poll (moderators only).",
+        }],
+        description: "Start a poll about the given options.",
+        examples: &["!poll \"Best crate?\" serde tokio 3m"],
+        statistic: Some(BuiltinCommand::Poll),
+    },
+    CommandInfo {
+        name: "prediction",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "\"<question>\" <outcomes...> <duration>",
+            description: "Start a prediction (moderators only).",
+        }],
+        description: "Start a prediction about the given outcomes.",
+        examples: &["!prediction \"Does it compile?\" yes no 2m"],
+        statistic: Some(BuiltinCommand::Poll),
+    },
+    CommandInfo {
+        name: "notifyme",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[Source::Discord],
+        usage: &[Usage {
+            args: "[on|off]",
+            description: "Get a direct message whenever the stream goes live, or stop getting \
+                          them.",
+        }],
+        description: "Get a direct message whenever the stream goes live.",
+        examples: &["!notifyme on"],
+        statistic: Some(BuiltinCommand::NotifyMe),
+    },
+    CommandInfo {
+        name: "watchtime",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[Source::Twitch],
+        usage: &[
+            Usage {
+                args: "",
+                description: "Show how long you watched the stream this month and in total.",
+            },
+            Usage {
+                args: "on|off",
+                description: "Stop tracking your watch time and delete it, or track it again.",
+            },
+        ],
+        description: "Show how long you watched the stream.",
+        examples: &["!watchtime", "!watchtime off"],
+        statistic: Some(BuiltinCommand::WatchTime),
+    },
+    CommandInfo {
+        name: "birthday",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[Source::Discord],
+        usage: &[
+            Usage {
+                args: "",
+                description: "Show your registered birthday.",
+            },
+            Usage {
+                args: "set <MM-DD>",
+                description: "Register your birthday for birthday wishes.",
+            },
+            Usage {
+                args: "remove",
+                description: "Forget your birthday again.",
+            },
+            Usage {
+                args: "announce [on|off]",
+                description: "Choose whether your birthday is announced on the server.",
+            },
+        ],
+        description: "Look up or change your birthday, and whether it's announced.",
+        examples: &["!birthday set 04-23", "!birthday announce on"],
+        statistic: Some(BuiltinCommand::Birthday),
+    },
+    CommandInfo {
+        name: "pronouns",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[
+            Usage {
+                args: "<user>",
+                description: "Show somebody's pronouns.",
+            },
+            Usage {
+                args: "set <pronouns>",
+                description: "Share your own pronouns.",
+            },
+            Usage {
+                args: "unset",
+                description: "Stop sharing your pronouns.",
+            },
+        ],
+        description: "Look up the pronouns of a user, or share your own.",
+        examples: &["!pronouns somebody", "!pronouns set they/them"],
+        statistic: Some(BuiltinCommand::Pronouns),
+    },
+    CommandInfo {
+        name: "lurk",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "[message]",
+            description: "Let the chat know you're lurking.",
+        }],
+        description: "Let the streamer know that you're lurking.",
+        examples: &["!lurk", "!lurk making dinner"],
+        statistic: Some(BuiltinCommand::Lurk),
+    },
+    CommandInfo {
+        name: "unlurk",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Let the chat know you're back from lurking.",
+        }],
+        description: "Let the streamer know that you're back.",
+        examples: &["!unlurk"],
+        statistic: Some(BuiltinCommand::Unlurk),
+    },
+    CommandInfo {
+        name: "highlights",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Show the latest messages that were pinned as highlights.",
+        }],
+        description: "Show the most recently pinned highlights.",
+        examples: &["!highlights"],
+        statistic: Some(BuiltinCommand::Highlights),
+    },
+    CommandInfo {
+        name: "yesterday",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Show the titles and categories of the streams from yesterday.",
+        }],
+        description: "Show what was streamed yesterday.",
+        examples: &["!yesterday"],
+        statistic: Some(BuiltinCommand::Yesterday),
+    },
+    CommandInfo {
+        name: "lastcategory",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "<category>",
+            description: "Show when the category was streamed the last time, and with which title.",
+        }],
+        description: "Show when a category was streamed the last time.",
+        examples: &["!lastcategory Minecraft"],
+        statistic: Some(BuiltinCommand::LastCategory),
+    },
+    CommandInfo {
+        name: "suggest",
+        aliases: &["suggestion"],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "<text>",
+            description: "Send in a suggestion for the stream or the community, that the admins \
+                          review. You get a message once it was accepted or rejected.",
+        }],
+        description: "Send in a suggestion for the admins to review.",
+        examples: &["!suggest a Rust quiz night"],
+        statistic: Some(BuiltinCommand::Suggest),
+    },
+    CommandInfo {
+        name: "topic",
+        aliases: &["agenda"],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[
+            Usage {
+                args: "",
+                description: "Show the agenda of the current stream, or of the next one while \
+                              the stream is offline.",
+            },
+            Usage {
+                args: "set <text>",
+                description: "Set the agenda (admins only). It belongs to the current stream and \
+                              is cleared once the stream goes offline.",
+            },
+            Usage {
+                args: "clear",
+                description: "Remove the agenda (admins only).",
+            },
+        ],
+        description: "Show what today's stream is about.",
+        examples: &["!topic", "!topic set Rust quiz, then bug hunting"],
+        statistic: Some(BuiltinCommand::Topic),
+    },
+    CommandInfo {
+        name: "followage",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[Source::Twitch],
+        usage: &[Usage {
+            args: "",
+            description: "Tell how long you've been following the channel.",
+        }],
+        description: "Tell how long you've been following the channel.",
+        examples: &["!followage"],
+        statistic: Some(BuiltinCommand::FollowAge),
+    },
+    CommandInfo {
+        name: "accountage",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[Source::Twitch],
+        usage: &[Usage {
+            args: "",
+            description: "Tell how old your account is.",
+        }],
+        description: "Tell how old your account is.",
+        examples: &["!accountage"],
+        statistic: Some(BuiltinCommand::AccountAge),
+    },
+    CommandInfo {
+        name: "timeout",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[Source::Twitch],
+        usage: &[Usage {
+            args: "me",
+            description: "Take a chance at being timed out.",
+        }],
+        description: "Take a chance at being timed out.",
+        examples: &["!timeout me"],
+        statistic: Some(BuiltinCommand::TimeoutMe),
+    },
+    CommandInfo {
+        name: "ftoc",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "<fahrenheit>",
+            description: "Convert Fahrenheit to Celsius.",
+        }],
+        description: "Convert degrees Fahrenheit to Celsius.",
+        examples: &["!ftoc 72"],
+        statistic: Some(BuiltinCommand::FahrenheitToCelsius),
+    },
+    CommandInfo {
+        name: "ctof",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "<celsius>",
+            description: "Convert Celsius to Fahrenheit.",
+        }],
+        description: "Convert degrees Celsius to Fahrenheit.",
+        examples: &["!ctof 21.5"],
+        statistic: Some(BuiltinCommand::CelsiusToFahrenheit),
+    },
+    CommandInfo {
+        name: "convert",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[Usage {
+            args: "<degrees>",
+            description: "Convert a temperature into your preferred unit.",
+        }],
+        description: "Convert degrees into your preferred unit, set with `!set unit`.",
+        examples: &["!convert 72"],
+        // Counted as either `!ftoc` or `!ctof`, depending on the direction.
+        statistic: None,
+    },
+    CommandInfo {
+        name: "set",
+        aliases: &[],
+        access: Access::Standard,
+        sources: &[],
+        usage: &[
+            Usage {
+                args: "",
+                description: "Show your personal settings.",
+            },
+            Usage {
+                args: "<setting> <value>",
+                description: "Change the `unit`, `timezone` or `locale` setting.",
+            },
+            Usage {
+                args: "reset <setting>",
+                description: "Go back to the default for a setting.",
+            },
+        ],
+        description: "Keep personal settings, that other commands apply automatically.",
+        examples: &["!set unit celsius", "!set timezone UTC+2", "!set locale de"],
+        statistic: Some(BuiltinCommand::Settings),
+    },
+    // admin commands
+    CommandInfo {
+        name: "ahelp",
+        aliases: &["admin_help", "admin-help", "adminhelp"],
+        access: Access::Admin,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Give a list of admin commands.",
+        }],
+        description: "List all admin commands.",
+        examples: &["!ahelp"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "custom_commands",
+        aliases: &["custom_command"],
+        access: Access::Admin,
+        sources: &[],
+        usage: &[
+            Usage {
+                args: "[add|remove] [all|discord|twitch|matrix|youtube|console] <name> <content>",
+                description: "Add or remove a custom command that has fixed content and can be \
+                              anything. The command can be modified for all sources or \
+                              individually. Command names must start with a lowercase letter, \
+                              only consist of lowercase letters, numbers and underscores and must \
+                              not start with the `!`.",
+            },
+            Usage {
+                args: "list",
+                description: "List all currently available custom commands.",
+            },
+            Usage {
+                args: "show <name>",
+                description: "Show the content of a custom command, including who created it and \
+                              when.",
+            },
+            Usage {
+                args: "history <name>",
+                description: "Show the recent changes to a custom command, including who made \
+                              them and when.",
+            },
+            Usage {
+                args: "revert <name>",
+                description: "Undo the last change to a custom command, restoring its previous \
+                              content.",
+            },
+        ],
+        description: "Manage the custom commands and look into their history.",
+        examples: &["!custom_commands add all hello Hello there!"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "stats",
+        aliases: &[],
+        access: Access::Admin,
+        sources: &[],
+        usage: &[
+            Usage {
+                args: "[current|total|<month>]",
+                description: "Get statistics about command usage, either for the **current \
+                              month**, the overall counters for **all time** or a past month \
+                              like `2024-09`.",
+            },
+            Usage {
+                args: "tag <name> [total]",
+                description: "Tag the current stream with a topic, or get the overall statistics \
+                              of all streams with that tag.",
+            },
+            Usage {
+                args: "timing",
+                description: "Show how long messages spend in each stage of the handling: \
+                              waiting in the queue, checking access, running the command and \
+                              sending the reply.",
+            },
+        ],
+        description: "Show usage statistics, tag the current stream or show handling times.",
+        examples: &["!stats 2024-09", "!stats tag coding"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "usernote",
+        aliases: &["usernotes", "user_note", "user_notes"],
+        access: Access::Admin,
+        sources: &[],
+        usage: &[Usage {
+            args: "[add <user> <text>|list <user>|remove <id>]",
+            description: "Manage private notes about users, to keep track of context across \
+                          platforms. Users are either a Discord mention or written as \
+                          `<service>:<name>` (like `twitch:somebody`). Notes are only ever sent \
+                          as direct message.",
+        }],
+        description: "Manage private notes about users.",
+        examples: &["!usernote add twitch:somebody asked about lifetimes"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "skip",
+        aliases: &[],
+        access: Access::Admin,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Remove the next song from the song request queue, once it was played.",
+        }],
+        description: "Remove the next song from the song request queue.",
+        examples: &["!skip"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "cost",
+        aliases: &[],
+        access: Access::Admin,
+        sources: &[],
+        usage: &[
+            Usage {
+                args: "<name> <points>",
+                description: "Let a custom command cost points, that users collect by chatting. \
+                              A cost of `0` makes the command free again.",
+            },
+            Usage {
+                args: "list",
+                description: "List all custom commands that cost points.",
+            },
+        ],
+        description: "Set the points that a custom command costs, or list all costs.",
+        examples: &["!cost hug 50"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "availability",
+        aliases: &[],
+        access: Access::Admin,
+        sources: &[],
+        usage: &[
+            Usage {
+                args: "<name> [always|live|offline]",
+                description: "Only answer a command while the stream is live, or while it's \
+                              offline.",
+            },
+            Usage {
+                args: "list",
+                description: "List all commands that depend on the stream being live.",
+            },
+        ],
+        description: "Limit a command to the live or offline stream.",
+        examples: &["!availability sr live"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "alert",
+        aliases: &[],
+        access: Access::Admin,
+        sources: &[],
+        usage: &[
+            Usage {
+                args: "<name> <limit>",
+                description: "Get alerted once a command is used more often than the limit in a \
+                              single day. The name `unknown` counts all unknown commands together. \
+                              A limit of `0` removes the alert.",
+            },
+            Usage {
+                args: "list",
+                description: "List all commands that have an alert.",
+            },
+        ],
+        description: "Set the daily usage of a command that admins get alerted about.",
+        examples: &["!alert unknown 100"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "shoutout",
+        aliases: &[],
+        access: Access::Admin,
+        sources: &[],
+        usage: &[Usage {
+            args: "[on|off]",
+            description: "Turn the automatic shoutout for raiding channels on or off, or show \
+                          whether it's on.",
+        }],
+        description: "Show or toggle the automatic shoutouts for raids.",
+        examples: &["!shoutout off"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "away",
+        aliases: &[],
+        access: Access::Admin,
+        sources: &[],
+        usage: &[Usage {
+            args: "<message>",
+            description: "Mark the streamer as away, so messages for them are answered with the \
+                          message, until they're back.",
+        }],
+        description: "Reply to messages for the streamer, while they're away.",
+        examples: &["!away grabbing food, back in 20"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "back",
+        aliases: &[],
+        access: Access::Admin,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Mark the streamer as back, stopping the automatic replies.",
+        }],
+        description: "Stop the automatic replies for the streamer.",
+        examples: &["!back"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "trigger",
+        aliases: &["triggers"],
+        access: Access::Admin,
+        sources: &[],
+        usage: &[
+            Usage {
+                args: "add <word> <response>",
+                description: "Add a trigger word, that fires a response whenever it appears in \
+                              a message.",
+            },
+            Usage {
+                args: "remove <word>",
+                description: "Remove a trigger word.",
+            },
+            Usage {
+                args: "cooldown <word> <secs>",
+                description: "Change the time until a trigger word can fire again, counted \
+                              separately for each service.",
+            },
+            Usage {
+                args: "[on|off] <service> <word>",
+                description: "Turn a trigger word on or off for a single service.",
+            },
+            Usage {
+                args: "list",
+                description: "List all trigger words with their cooldown and response.",
+            },
+        ],
+        description: "Manage the words that the bot replies to in any message.",
+        examples: &["!trigger add rust Rust is great!"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "faq",
+        aliases: &["faqs"],
+        access: Access::Admin,
+        sources: &[],
+        usage: &[
+            Usage {
+                args: "add <name> <keywords> <answer>",
+                description: "Add an answer to a frequently asked question, that is given \
+                              whenever a message contains enough of the comma separated keywords.",
+            },
+            Usage {
+                args: "regex <name> <regex> <answer>",
+                description: "Add an answer to a frequently asked question, that is given \
+                              whenever a message matches the regular expression.",
+            },
+            Usage {
+                args: "remove <name>",
+                description: "Remove an answer to a frequently asked question.",
+            },
+            Usage {
+                args: "list",
+                description: "List all FAQ entries with their pattern and answer.",
+            },
+        ],
+        description: "Manage the answers to frequently asked questions.",
+        examples: &["!faq add keyboard which,keyboard It's a Keychron Q1"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "watchcrate",
+        aliases: &["watchcrates"],
+        access: Access::Admin,
+        sources: &[],
+        usage: &[
+            Usage {
+                args: "[add|remove] <name>",
+                description: "Watch a crate, to announce its new versions once they're released.",
+            },
+            Usage {
+                args: "list",
+                description: "List all watched crates.",
+            },
+        ],
+        description: "Manage the crates whose new versions are announced.",
+        examples: &["!watchcrate add serde"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "channels",
+        aliases: &["channel"],
+        access: Access::Admin,
+        sources: &[Source::Discord],
+        usage: &[
+            Usage {
+                args: "[allow|deny|reset] <channel>",
+                description: "Answer user commands only in allowed channels of this server, or \
+                              never in denied ones. Admin commands work in every channel.",
+            },
+            Usage {
+                args: "list",
+                description: "List all allowed and denied channels.",
+            },
+        ],
+        description: "Control the Discord channels that user commands are answered in.",
+        examples: &["!channels allow #bot-commands"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "silence",
+        aliases: &[],
+        access: Access::Admin,
+        sources: &[Source::Discord],
+        usage: &[Usage {
+            args: "[<channel>|here] [<duration>|off]",
+            description: "Stop replying to user commands in a channel for a while, like `30m` or \
+                          `2h`, but still count them in the statistics.",
+        }],
+        description: "Stop answering user commands in a channel for a while.",
+        examples: &["!silence here 30m"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "commandlist",
+        aliases: &[],
+        access: Access::Admin,
+        sources: &[Source::Discord],
+        usage: &[Usage {
+            args: "<channel>|here|off",
+            description: "Keep the list of commands pinned in a channel, and update it whenever \
+                          the custom commands change.",
+        }],
+        description: "Keep an up-to-date list of commands pinned in a channel.",
+        examples: &["!commandlist #bot-commands"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "confirm",
+        aliases: &[],
+        access: Access::Admin,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Confirm the last destructive command, like removing a custom command \
+                          from all services or removing an admin. Unconfirmed commands expire \
+                          after 30 seconds.",
+        }],
+        description: "Confirm a destructive command.",
+        examples: &["!confirm"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "linkpreview",
+        aliases: &[],
+        access: Access::Admin,
+        sources: &[],
+        usage: &[Usage {
+            args: "<source>",
+            description: "Show the `!links` command as it looks on the given service, with its \
+                          formatting profile from the settings.",
+        }],
+        description: "Preview the links of another service.",
+        examples: &["!linkpreview twitch"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "checklinks",
+        aliases: &[],
+        access: Access::Admin,
+        sources: &[],
+        usage: &[],
+        description: "Check that the links of the settings and custom commands still work, and \
+                      list the ones that don't.",
+        examples: &["!checklinks"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "chapters",
+        aliases: &[],
+        access: Access::Admin,
+        sources: &[],
+        usage: &[Usage {
+            args: "[<day>]",
+            description: "Export the chapters of the latest stream, or of all streams that \
+                          started on a day like `2025-03-01` (UTC), for the VOD uploads.",
+        }],
+        description: "Turn the title and category changes of streams into chapter lists, that \
+                      can be pasted into the description of a YouTube video.",
+        examples: &["!chapters", "!chapters 2025-03-01"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "suggestions",
+        aliases: &[],
+        access: Access::Admin,
+        sources: &[],
+        usage: &[
+            Usage {
+                args: "[list]",
+                description: "List all suggestions of users that wait for a review, oldest first.",
+            },
+            Usage {
+                args: "[accept|reject] <id> [<reason>]",
+                description: "Accept or reject a suggestion. Its author gets a message about it, \
+                              together with the reason if one was given.",
+            },
+        ],
+        description: "Review the suggestions that users sent in.",
+        examples: &[
+            "!suggestions accept 3",
+            "!suggestions reject 4 we did that last week",
+        ],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "issue",
+        aliases: &[],
+        access: Access::Admin,
+        sources: &[],
+        usage: &[
+            Usage {
+                args: "<title> [| <body>]",
+                description: "Create a new issue on the configured GitHub repository, with an \
+                              optional body after a `|`.",
+            },
+            Usage {
+                args: "suggestion <id>",
+                description: "Turn a suggestion into an issue. Each suggestion becomes an issue \
+                              only once, later calls reply with the existing one.",
+            },
+        ],
+        description: "Create GitHub issues from the chat and reply with their link.",
+        examples: &[
+            "!issue Add a !quote command | Viewers asked for it a few times.",
+            "!issue suggestion 3",
+        ],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "pin",
+        aliases: &[],
+        access: Access::Admin,
+        sources: &[Source::Discord],
+        usage: &[],
+        description: "Pin a message from its context menu (Apps › Pin as highlight), and keep it \
+                      as highlight that users can find with `!highlights`.",
+        examples: &[],
+        statistic: None,
+    },
+    // owner commands
+    CommandInfo {
+        name: "ohelp",
+        aliases: &["owner_help", "owner-help", "ownerhelp"],
+        access: Access::Owner,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Give a list of owner commands.",
+        }],
+        description: "List all owner commands.",
+        examples: &["!ohelp"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "admins",
+        aliases: &["admin"],
+        access: Access::Owner,
+        sources: &[Source::Discord],
+        usage: &[
+            Usage {
+                args: "[add|remove] @name",
+                description: "Add or remove a user to/from the admin list. An admin has access \
+                              to most of the bot-controlling commands.",
+            },
+            Usage {
+                args: "role [add|remove] @role",
+                description: "Make all members of a role admins, or stop doing so.",
+            },
+            Usage {
+                args: "list",
+                description: "List all currently configured admin users and roles.",
+            },
+        ],
+        description: "Manage the users and roles with admin access.",
+        examples: &["!admins add @somebody"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "plugins",
+        aliases: &[],
+        access: Access::Owner,
+        sources: &[],
+        usage: &[
+            Usage {
+                args: "[load|unload] <name>",
+                description: "Load or unload a plugin, making its commands available or \
+                              unavailable to users.",
+            },
+            Usage {
+                args: "list",
+                description: "List all registered plugins and whether they're currently loaded.",
+            },
+        ],
+        description: "Manage the plugins that provide extra commands.",
+        examples: &["!plugins load dice"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "debug",
+        aliases: &[],
+        access: Access::Owner,
+        sources: &[],
+        usage: &[Usage {
+            args: "parse <text>",
+            description: "Show how the given text is parsed into a command, to find out why a \
+                          message isn't recognized.",
+        }],
+        description: "Show how a message is parsed into a command.",
+        examples: &["!debug parse !crate serde"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "resync_commands",
+        aliases: &[],
+        access: Access::Owner,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Register all slash commands with Discord again, without restarting the \
+                          bot.",
+        }],
+        description: "Register the slash commands with Discord again.",
+        examples: &["!resync_commands"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "cleanup",
+        aliases: &[],
+        access: Access::Owner,
+        sources: &[],
+        usage: &[Usage {
+            args: "",
+            description: "Delete statistics beyond the configured retention and compact the \
+                          database, showing how much space was freed.",
+        }],
+        description: "Remove data that is older than the retention periods.",
+        examples: &["!cleanup"],
+        statistic: None,
+    },
+    CommandInfo {
+        name: "broadcast",
+        aliases: &[],
+        access: Access::Owner,
+        sources: &[],
+        usage: &[Usage {
+            args: "<text>",
+            description: "Post a message on all services at once, in the Twitch chat and the \
+                          Discord announcement channel.",
+        }],
+        description: "Post a message on all connected services.",
+        examples: &["!broadcast Stream starts in 5 minutes!"],
+        statistic: None,
+    },
+    #[cfg(feature = "chaos")]
+    CommandInfo {
+        name: "chaos",
+        aliases: &[],
+        access: Access::Owner,
+        sources: &[],
+        usage: &[Usage {
+            args: "[status|off|delay <percent> [max_ms]|send <percent>|events <percent>]",
+            description: "Inject random faults, to test how the bot copes with delays, failing \
+                          messages and lost Twitch events. Rates are given in percent and `off` \
+                          disables all faults again.",
+        }],
+        description: "Inject random faults into the bot.",
+        examples: &["!chaos delay 20 500"],
+        statistic: None,
+    },
+];
@@ -0,0 +1,224 @@
+//! Suggestions for partially typed command arguments, for services that offer autocompletion.
+//!
+//! Suggestions are a best effort, so any failure is logged and results in no suggestions at all.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{LazyLock, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use serde::Deserialize;
+use tracing::error;
+
+use crate::{api::Source, state::State, statistics::Stats};
+
+/// Maximum amount of suggestions, as Discord doesn't show more than that.
+const LIMIT: usize = 25;
+/// Time that crate searches are cached, as suggestions are requested on every key stroke.
+const CRATE_CACHE_TTL: Duration = Duration::from_mins(10);
+
+/// Recent crate searches, by the searched text.
+type CrateCache = Mutex<HashMap<String, (Instant, Vec<String>)>>;
+
+static CRATE_CACHE: LazyLock<CrateCache> = LazyLock::new(Mutex::default);
+
+/// Source of suggestions, with read access to the bot's data.
+#[derive(Clone)]
+pub struct Completions {
+    state: State,
+    statistics: Stats,
+}
+
+impl Completions {
+    #[must_use]
+    pub fn new(state: State, statistics: Stats) -> Self {
+        Self { state, statistics }
+    }
+
+    /// Names of custom commands on any service, that start with the partial input.
+    #[must_use]
+    pub fn custom_commands(&self, partial: &str) -> Vec<String> {
+        let commands = match self.state.list_custom_commands() {
+            Ok(commands) => commands,
+            Err(e) => {
+                error!(error = ?e, "failed listing custom commands for suggestions");
+                return Vec::new();
+            }
+        };
+
+        let names = commands
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect::<BTreeSet<_>>();
+
+        matching(names, partial)
+    }
+
+    /// Current content of a custom command, on the given service or the first one that has it.
+    #[must_use]
+    pub fn custom_command(&self, source: Option<Source>, name: &str) -> Option<String> {
+        let sources = source.map_or_else(|| Source::ALL.to_vec(), |source| vec![source]);
+
+        sources.into_iter().find_map(|source| {
+            self.state
+                .get_custom_command(source, name)
+                .inspect_err(|e| error!(error = ?e, "failed loading custom command to edit"))
+                .ok()
+                .flatten()
+        })
+    }
+
+    /// Months with statistics, like `2024-09`, that start with the partial input.
+    #[must_use]
+    pub fn months(&self, partial: &str) -> Vec<String> {
+        let months = match self.statistics.list_months() {
+            Ok(months) => months,
+            Err(e) => {
+                error!(error = ?e, "failed listing statistics months for suggestions");
+                return Vec::new();
+            }
+        };
+
+        matching(
+            months
+                .into_iter()
+                .map(|(year, month)| format!("{year}-{:02}", u8::from(month))),
+            partial,
+        )
+    }
+
+    /// Crates on crates.io, that match the partial input.
+    pub async fn crates(&self, partial: &str) -> Vec<String> {
+        let partial = partial.trim().to_lowercase();
+        if partial.len() < 2 {
+            return Vec::new();
+        }
+
+        match search_crates(&partial).await {
+            Ok(names) => names,
+            Err(e) => {
+                error!(error = ?e, "failed searching crates for suggestions");
+                Vec::new()
+            }
+        }
+    }
+}
+
+fn matching(values: impl IntoIterator<Item = String>, partial: &str) -> Vec<String> {
+    values
+        .into_iter()
+        .filter(|value| value.starts_with(partial))
+        .take(LIMIT)
+        .collect()
+}
+
+async fn search_crates(query: &str) -> Result<Vec<String>> {
+    #[derive(Deserialize)]
+    struct ApiResponse {
+        crates: Vec<ApiCrate>,
+    }
+
+    #[derive(Deserialize)]
+    struct ApiCrate {
+        name: String,
+    }
+
+    let cached = CRATE_CACHE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(query)
+        .filter(|(fetched, _)| fetched.elapsed() < CRATE_CACHE_TTL)
+        .map(|(_, names)| names.clone());
+
+    if let Some(names) = cached {
+        return Ok(names);
+    }
+
+    let names = reqwest::Client::builder()
+        .user_agent("ToggleBot (https://github.com/dnaka91/togglebot)")
+        .build()?
+        .get("https://crates.io/api/v1/crates")
+        .query(&[("q", query), ("per_page", &LIMIT.to_string())])
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<ApiResponse>()
+        .await?
+        .crates
+        .into_iter()
+        .map(|info| info.name)
+        .collect::<Vec<_>>();
+
+    let mut cache = CRATE_CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+    cache.retain(|_, (fetched, _)| fetched.elapsed() < CRATE_CACHE_TTL);
+    cache.insert(query.to_owned(), (Instant::now(), names.clone()));
+
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::OffsetDateTime;
+
+    use super::*;
+    use crate::api::AuthorId;
+
+    #[test]
+    fn custom_commands() {
+        let state = State::in_memory().unwrap();
+        state
+            .add_custom_command(
+                Source::Discord,
+                "hello",
+                "hi",
+                &AuthorId::Console.user_ref(),
+                OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+        state
+            .add_custom_command(
+                Source::Twitch,
+                "hello",
+                "hi",
+                &AuthorId::Console.user_ref(),
+                OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+        state
+            .add_custom_command(
+                Source::Twitch,
+                "hug",
+                "<3",
+                &AuthorId::Console.user_ref(),
+                OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+        state
+            .add_custom_command(
+                Source::Twitch,
+                "lurk",
+                "bye",
+                &AuthorId::Console.user_ref(),
+                OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+
+        let completions = Completions::new(state, Stats::in_memory().unwrap());
+        assert_eq!(vec!["hello", "hug"], completions.custom_commands("h"));
+        assert_eq!(
+            vec!["hello", "hug", "lurk"],
+            completions.custom_commands("")
+        );
+
+        assert_eq!(
+            Some("<3".to_owned()),
+            completions.custom_command(None, "hug")
+        );
+        assert_eq!(
+            None,
+            completions.custom_command(Some(Source::Discord), "hug")
+        );
+    }
+}
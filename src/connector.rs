@@ -0,0 +1,197 @@
+//! Common interface for all service integrations, that receive chat messages from a service and
+//! render the bot's replies back to it.
+
+use std::{num::NonZero, sync::Arc, time::Instant};
+
+use anyhow::{bail, Context as _, Result};
+use futures_util::future::BoxFuture;
+use indexmap::IndexMap;
+use tokio::sync::oneshot;
+use tokio_shutdown::Shutdown;
+use tracing::{error, info_span, Instrument};
+
+use crate::{
+    api::{
+        response::Response, CustomCommand, Events, Message, Poll, Post, Queue, Source, StreamInfo,
+    },
+    clock::SharedClock,
+    completions::Completions,
+    health::Health,
+    settings::Commands as CommandSettings,
+    timing::Timings,
+};
+
+/// Everything a connector needs to interact with the rest of the bot.
+#[derive(Clone)]
+pub struct Context {
+    /// Settings for the built-in commands.
+    pub settings: Arc<CommandSettings>,
+    /// Queue to send received messages to the handler.
+    pub queue: Queue,
+    /// Queue to report events that aren't related to any chat message.
+    pub events: Events,
+    /// Suggestions for partially typed command arguments.
+    pub completions: Completions,
+    /// Health state to report heartbeats to.
+    pub health: Health,
+    /// Timings to report how long replies took to render and send.
+    pub timings: Timings,
+    /// Source of the current time.
+    pub clock: SharedClock,
+    /// Handle to listen for the application shutting down.
+    pub shutdown: Shutdown,
+}
+
+/// A connection to a chat service, like Discord or Twitch.
+pub trait Connector: Send + Sync {
+    /// The service that this connector integrates with.
+    fn source(&self) -> Source;
+
+    /// Connect to the service and start processing messages in the background.
+    fn start(&self, ctx: Context) -> BoxFuture<'_, Result<()>>;
+
+    /// Post a message to the service, that isn't a reply to any received chat message.
+    fn send_reply(&self, post: Post) -> BoxFuture<'_, Result<()>>;
+
+    /// Keep a message pinned in a channel, editing the given message if it still exists or posting
+    /// and pinning a new one otherwise. Returns the ID of the pinned message.
+    fn pin_post(
+        &self,
+        _post: Post,
+        _message: Option<NonZero<u64>>,
+    ) -> BoxFuture<'_, Result<NonZero<u64>>> {
+        Box::pin(async { bail!("pinned messages aren't supported") })
+    }
+
+    /// Send a direct message to a single user of the service, if the service supports it.
+    fn send_direct(&self, _user: String, _content: String) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async { bail!("direct messages aren't supported") })
+    }
+
+    /// Start a poll on the service, that announces its results once it ended. Services without
+    /// any kind of polls simply ignore it.
+    fn start_poll(&self, _poll: Poll) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Register the service's commands again, together with the given custom commands, for
+    /// services that need them registered upfront.
+    fn resync_commands(&self, _custom: Vec<CustomCommand>) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Show whether the stream is live in the status of the bot account, for services that have
+    /// one.
+    fn update_presence(&self, _stream: Option<StreamInfo>) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    /// Gracefully disconnect from the service.
+    fn shutdown(&self) -> BoxFuture<'_, ()>;
+}
+
+/// Collection of all active connectors, at most one per service.
+#[derive(Default)]
+pub struct Connectors(IndexMap<Source, Arc<dyn Connector>>);
+
+impl Connectors {
+    /// Add a new connector, replacing any previous one for the same service.
+    pub fn add(&mut self, connector: impl Connector + 'static) {
+        self.0.insert(connector.source(), Arc::new(connector));
+    }
+
+    /// Start all connectors, one after another, and track their health.
+    pub async fn start(&self, ctx: &Context) -> Result<()> {
+        for (source, connector) in &self.0 {
+            ctx.health.register(*source);
+            connector
+                .start(ctx.clone())
+                .await
+                .with_context(|| format!("failed starting {source} connector"))?;
+        }
+
+        Ok(())
+    }
+
+    /// Post a message through the connector of the given service.
+    pub async fn send_reply(&self, source: Source, post: Post) -> Result<()> {
+        self.0
+            .get(&source)
+            .with_context(|| format!("no connector for {source}"))?
+            .send_reply(post)
+            .await
+    }
+
+    /// Keep a message pinned through the connector of the given service.
+    pub async fn pin_post(
+        &self,
+        source: Source,
+        post: Post,
+        message: Option<NonZero<u64>>,
+    ) -> Result<NonZero<u64>> {
+        self.0
+            .get(&source)
+            .with_context(|| format!("no connector for {source}"))?
+            .pin_post(post, message)
+            .await
+    }
+
+    /// Send a direct message through the connector of the given service.
+    pub async fn send_direct(&self, source: Source, user: String, content: String) -> Result<()> {
+        self.0
+            .get(&source)
+            .with_context(|| format!("no connector for {source}"))?
+            .send_direct(user, content)
+            .await
+    }
+
+    /// Start the poll on all services at once, logging any service that failed to start it.
+    pub async fn start_poll(&self, poll: &Poll) {
+        for (source, connector) in &self.0 {
+            if let Err(e) = connector.start_poll(poll.clone()).await {
+                error!(error = ?e, %source, "failed starting poll");
+            }
+        }
+    }
+
+    /// Services that a connector was added for.
+    pub fn sources(&self) -> impl Iterator<Item = Source> + '_ {
+        self.0.keys().copied()
+    }
+
+    /// Register the commands of all services again, logging any service that failed.
+    pub async fn resync_commands(&self, custom: &[CustomCommand]) {
+        for (source, connector) in &self.0 {
+            if let Err(e) = connector.resync_commands(custom.to_vec()).await {
+                error!(error = ?e, %source, "failed re-registering commands");
+            }
+        }
+    }
+
+    /// Update the status of the bot account on all services, logging any service that failed.
+    pub async fn update_presence(&self, stream: Option<&StreamInfo>) {
+        for (source, connector) in &self.0 {
+            if let Err(e) = connector.update_presence(stream.cloned()).await {
+                error!(error = ?e, %source, "failed updating presence");
+            }
+        }
+    }
+
+    /// Gracefully shut down all connectors.
+    pub async fn shutdown(&self) {
+        for connector in self.0.values() {
+            connector.shutdown().await;
+        }
+    }
+}
+
+/// Hand a received message to the handler and wait for the response, if any.
+pub async fn dispatch(queue: &Queue, message: Message) -> Option<Response> {
+    async {
+        let (tx, rx) = oneshot::channel();
+        queue.send((message, Instant::now(), tx)).await.ok()?;
+        rx.await.ok()
+    }
+    .instrument(info_span!("handle"))
+    .await
+}
@@ -0,0 +1,165 @@
+//! Console connector that reads commands from the terminal and prints the replies, to try out the
+//! whole bot locally without any service accounts.
+//!
+//! The user at the console is treated as owner, so all commands are available.
+
+use std::{
+    io::{self, BufRead},
+    sync::{Mutex, PoisonError},
+    thread,
+    time::Instant,
+};
+
+use anyhow::Result;
+use futures_util::{future, future::BoxFuture};
+use tokio::{select, sync::mpsc, task::JoinHandle};
+use tracing::{error, info, info_span, instrument, Span};
+
+use crate::{
+    api::{AuthorId, Message, Post, Queue, Source},
+    connector::{self, Connector, Context as ConnectorContext},
+    render, textparse,
+    timing::Timings,
+};
+
+/// Connector for the local console, that reads one command per line from the standard input.
+#[derive(Default)]
+pub struct Console {
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl Console {
+    /// Create a new, not yet started, console connector.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read lines from the standard input in a background task, until it's closed.
+    ///
+    /// Once the input is closed (for example with `Ctrl+D`), the connector releases the message
+    /// queue, which lets the bot shut down if no other connectors are running.
+    fn run(&self, ctx: ConnectorContext) {
+        let ConnectorContext {
+            queue,
+            timings,
+            shutdown,
+            ..
+        } = ctx;
+
+        // Reading from the standard input blocks, so it's done on a separate thread, which
+        // doesn't hold up the shutdown while waiting for the next line.
+        let (lines_tx, mut lines_rx) = mpsc::channel(1);
+        thread::spawn(move || {
+            for line in io::stdin().lock().lines() {
+                match line {
+                    Ok(line) => {
+                        if lines_tx.blocking_send(line).is_err() {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        error!(error = ?e, "failed reading from the console");
+                        break;
+                    }
+                }
+            }
+        });
+
+        let task = tokio::spawn(async move {
+            loop {
+                let line = select! {
+                    () = shutdown.handle() => break,
+                    line = lines_rx.recv() => line,
+                };
+
+                let Some(line) = line else {
+                    info!("console input closed");
+                    break;
+                };
+
+                if !line.trim().is_empty() {
+                    handle_message(&queue, &timings, &line).await;
+                }
+            }
+        });
+
+        self.tasks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(task);
+
+        info!("console ready, type a command like `!help`");
+    }
+}
+
+impl Connector for Console {
+    fn source(&self) -> Source {
+        Source::Console
+    }
+
+    fn start(&self, ctx: ConnectorContext) -> BoxFuture<'_, Result<()>> {
+        self.run(ctx);
+        Box::pin(future::ready(Ok(())))
+    }
+
+    fn send_reply(&self, post: Post) -> BoxFuture<'_, Result<()>> {
+        println!("[post] {}", post.content);
+        Box::pin(future::ready(Ok(())))
+    }
+
+    fn shutdown(&self) -> BoxFuture<'_, ()> {
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap_or_else(PoisonError::into_inner));
+
+        Box::pin(async move {
+            for task in tasks {
+                task.abort();
+            }
+            info!("console shutting down");
+        })
+    }
+}
+
+#[instrument(skip_all, name = "console message", fields(source = %Source::Console))]
+async fn handle_message(queue: &Queue, timings: &Timings, line: &str) {
+    let received = Instant::now();
+    let content = match textparse::parse(line, Source::Console, None) {
+        Ok(Some(content)) => content,
+        Ok(None) => {
+            println!("(not a command)");
+            return;
+        }
+        Err(e) => {
+            println!("(invalid command: {e})");
+            return;
+        }
+    };
+
+    let message = Message {
+        span: Span::current(),
+        source: Source::Console,
+        content,
+        author: AuthorId::Console.into(),
+        mention: None,
+        channel: None,
+    };
+
+    let command = message.content.metric_name().to_owned();
+    let Some(resp) = connector::dispatch(queue, message).await else {
+        println!("(no reply)");
+        return;
+    };
+
+    let reply = async {
+        info_span!("reply").in_scope(|| {
+            println!(
+                "{}",
+                render::plain::response(resp)
+                    .as_deref()
+                    .unwrap_or("(no reply)")
+            );
+        });
+    };
+
+    timings.reply(&command, received, reply).await;
+}
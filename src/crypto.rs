@@ -0,0 +1,140 @@
+//! Encryption of secrets that are kept in the database, so they don't leak through backups.
+//!
+//! Values are encrypted with XChaCha20-Poly1305 and a random nonce, that is stored in front of
+//! the ciphertext. Both are hex encoded, to be kept in a plain text column.
+
+use std::fmt::{self, Write as _};
+
+use anyhow::{anyhow, ensure, Context, Result};
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    XChaCha20Poly1305, XNonce,
+};
+use serde::{de, Deserialize, Deserializer};
+
+/// Length of the nonce in bytes, that is prepended to each encrypted value.
+const NONCE_LEN: usize = 24;
+
+/// Secret key to encrypt and decrypt values with. It's written as 64 hex digits in the config.
+#[derive(Clone)]
+pub struct Key(chacha20poly1305::Key);
+
+impl Key {
+    /// Create a new random key.
+    #[must_use]
+    pub fn generate() -> Self {
+        Self(XChaCha20Poly1305::generate_key(&mut OsRng))
+    }
+
+    /// Encrypt the value with a fresh nonce.
+    pub fn encrypt(&self, value: &str) -> Result<String> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = XChaCha20Poly1305::new(&self.0)
+            .encrypt(&nonce, value.as_bytes())
+            .map_err(|_| anyhow!("failed encrypting value"))?;
+
+        Ok(encode(nonce.iter().chain(&ciphertext)))
+    }
+
+    /// Decrypt a value that was previously encrypted with this key.
+    pub fn decrypt(&self, value: &str) -> Result<String> {
+        let data = decode(value)?;
+        ensure!(data.len() >= NONCE_LEN, "encrypted value is too short");
+
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let plaintext = XChaCha20Poly1305::new(&self.0)
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow!("failed decrypting value, the key may be wrong"))?;
+
+        String::from_utf8(plaintext).context("decrypted value isn't valid UTF-8")
+    }
+}
+
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Key(..)")
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&encode(&self.0))
+    }
+}
+
+impl std::str::FromStr for Key {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let data = decode(s)?;
+        ensure!(data.len() == 32, "key must be 64 hex digits long");
+
+        Ok(Self(*chacha20poly1305::Key::from_slice(&data)))
+    }
+}
+
+impl<'de> Deserialize<'de> for Key {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(de::Error::custom)
+    }
+}
+
+fn encode<'a>(data: impl IntoIterator<Item = &'a u8>) -> String {
+    data.into_iter().fold(String::new(), |mut s, b| {
+        write!(s, "{b:02x}").ok();
+        s
+    })
+}
+
+fn decode(value: &str) -> Result<Vec<u8>> {
+    ensure!(value.len().is_multiple_of(2), "hex value has an odd length");
+
+    (0..value.len())
+        .step_by(2)
+        .map(|i| {
+            value
+                .get(i..i + 2)
+                .and_then(|b| u8::from_str_radix(b, 16).ok())
+                .context("invalid hex digit")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let key = Key::generate();
+        let encrypted = key.encrypt("secret token").unwrap();
+
+        assert!(!encrypted.contains("secret"));
+        assert_ne!(encrypted, key.encrypt("secret token").unwrap());
+        assert_eq!("secret token", key.decrypt(&encrypted).unwrap());
+    }
+
+    #[test]
+    fn wrong_key() {
+        let encrypted = Key::generate().encrypt("secret token").unwrap();
+
+        assert!(Key::generate().decrypt(&encrypted).is_err());
+        assert!(Key::generate().decrypt("abcd").is_err());
+    }
+
+    #[test]
+    fn parse_key() {
+        let key = Key::generate();
+        let parsed = key.to_string().parse::<Key>().unwrap();
+
+        assert_eq!(key.0, parsed.0);
+        assert!("abcd".parse::<Key>().is_err());
+        assert!("zz".repeat(32).parse::<Key>().is_err());
+    }
+}
@@ -1,22 +1,65 @@
-use std::{
-    ops::{Deref, DerefMut},
-    sync::LazyLock,
-};
+use std::sync::LazyLock;
 
-use anyhow::{Context, Result};
+use anyhow::{ensure, Context, Result};
 use include_dir::{include_dir, Dir};
+use rusqlite::OpenFlags;
 use rusqlite_migration::Migrations;
 
-use crate::dirs::DIRS;
+use super::postgres::Client;
+use crate::{dirs::DIRS, settings::Database as DatabaseSettings};
 
-static MIGRATIONS_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/migrations");
+static MIGRATIONS_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/migrations/sqlite");
 static MIGRATIONS: LazyLock<Migrations<'_>> =
     LazyLock::new(|| Migrations::from_directory(&MIGRATIONS_DIR).unwrap());
 
-pub struct Connection(rusqlite::Connection);
+/// Connection to either of the supported database backends, with all migrations applied.
+pub struct Connection(pub(super) Backend);
+
+pub(super) enum Backend {
+    Sqlite(rusqlite::Connection),
+    Postgres(Client),
+}
+
+/// Migrations of the database schema, split by whether they were applied already.
+pub struct MigrationStatus {
+    /// Amount of migrations that were applied.
+    pub applied: usize,
+    /// Names of the migrations that run on the next start, in order.
+    pub pending: Vec<String>,
+}
+
+impl MigrationStatus {
+    /// Split the migrations, after the given amount of them was applied.
+    pub(super) fn new(dir: &Dir<'_>, applied: usize) -> Result<Self> {
+        let mut names = dir
+            .dirs()
+            .map(|dir| dir.path().display().to_string())
+            .collect::<Vec<_>>();
+        names.sort();
+
+        ensure!(
+            applied <= names.len(),
+            "the database was migrated by a newer version ({applied} of {} migrations applied)",
+            names.len()
+        );
+
+        Ok(Self {
+            applied,
+            pending: names.split_off(applied),
+        })
+    }
+}
 
 impl Connection {
-    pub fn new() -> Result<Self> {
+    /// Connect to the configured PostgreSQL server, or open the local SQLite file otherwise.
+    pub fn new(settings: &DatabaseSettings) -> Result<Self> {
+        match &settings.postgres {
+            Some(url) => Client::connect(url).map(|client| Self(Backend::Postgres(client))),
+            None => Self::sqlite(),
+        }
+    }
+
+    fn sqlite() -> Result<Self> {
         let mut conn = rusqlite::Connection::open(DIRS.database_file())
             .with_context(|| format!("failed opening database at {:?}", DIRS.database_file()))?;
 
@@ -28,38 +71,131 @@ impl Connection {
         conn.pragma_update(None, "synchronous", "NORMAL")?;
         conn.pragma_update(None, "foreign_keys", "ON")?;
 
-        Ok(Self(conn))
+        Ok(Self(Backend::Sqlite(conn)))
+    }
+
+    /// Check which migrations the configured database is missing, without applying them.
+    ///
+    /// On a dry run, the missing migrations are run in a transaction that is rolled back
+    /// afterwards, so any errors in them show up before the bot is upgraded.
+    pub fn status(settings: &DatabaseSettings, dry_run: bool) -> Result<MigrationStatus> {
+        match &settings.postgres {
+            Some(url) => super::postgres::status(url, dry_run),
+            None => Self::sqlite_status(dry_run),
+        }
+    }
+
+    fn sqlite_status(dry_run: bool) -> Result<MigrationStatus> {
+        let path = DIRS.database_file();
+        let conn = if path.exists() {
+            let flags = if dry_run {
+                OpenFlags::SQLITE_OPEN_READ_WRITE
+            } else {
+                OpenFlags::SQLITE_OPEN_READ_ONLY
+            };
+            rusqlite::Connection::open_with_flags(path, flags)
+                .with_context(|| format!("failed opening database at {path:?}"))?
+        } else {
+            rusqlite::Connection::open_in_memory()?
+        };
+
+        let version =
+            conn.pragma_query_value(None, "user_version", |row| row.get::<_, usize>(0))?;
+        let status = MigrationStatus::new(&MIGRATIONS_DIR, version)?;
+
+        if dry_run {
+            dry_run_sqlite(&conn, &status.pending)?;
+        }
+
+        Ok(status)
     }
 
-    #[cfg(test)]
     pub fn in_memory() -> Result<Self> {
-        let mut conn = rusqlite::Connection::open_in_memory()?;
+        Self::in_memory_with(rusqlite::Connection::open_in_memory()?)
+    }
+
+    /// Open another connection to the named in-memory database, which is created by the first
+    /// connection and kept until the last one is closed. All connections with the same name see
+    /// the same data, like they would with a database file.
+    pub fn in_memory_shared(name: &str) -> Result<Self> {
+        let conn = rusqlite::Connection::open_with_flags(
+            format!("file:/{name}?vfs=memdb"),
+            OpenFlags::default() | OpenFlags::SQLITE_OPEN_URI,
+        )
+        .with_context(|| format!("failed opening in-memory database `{name}`"))?;
+
+        Self::in_memory_with(conn)
+    }
 
+    fn in_memory_with(mut conn: rusqlite::Connection) -> Result<Self> {
         MIGRATIONS.to_latest(&mut conn)?;
 
         conn.pragma_update(None, "foreign_keys", "ON")?;
 
-        Ok(Self(conn))
+        Ok(Self(Backend::Sqlite(conn)))
     }
-}
 
-impl Deref for Connection {
-    type Target = rusqlite::Connection;
+    /// Run all statements of the closure in a single transaction, that is only committed if the
+    /// closure succeeds.
+    pub fn transaction<T>(&self, f: impl FnOnce(&Self) -> Result<T>) -> Result<T> {
+        super::exec(self, "BEGIN;", super::NO_PARAMS)?;
+
+        match f(self) {
+            Ok(value) => {
+                super::exec(self, "COMMIT;", super::NO_PARAMS)?;
+                Ok(value)
+            }
+            Err(e) => {
+                super::exec(self, "ROLLBACK;", super::NO_PARAMS).ok();
+                Err(e)
+            }
+        }
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+    /// Size of the database, in bytes.
+    pub fn size(&self) -> Result<u64> {
+        match &self.0 {
+            Backend::Sqlite(conn) => {
+                let pages =
+                    conn.pragma_query_value(None, "page_count", |row| row.get::<_, u64>(0))?;
+                let page_size =
+                    conn.pragma_query_value(None, "page_size", |row| row.get::<_, u64>(0))?;
+
+                Ok(pages * page_size)
+            }
+            Backend::Postgres(_) => super::query_one(
+                self,
+                "SELECT pg_database_size(current_database());",
+                super::NO_PARAMS,
+            )
+            .map(Option::unwrap_or_default),
+        }
     }
 }
 
-impl DerefMut for Connection {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
-    }
+/// Run the pending migrations in a transaction, that is always rolled back.
+fn dry_run_sqlite(conn: &rusqlite::Connection, pending: &[String]) -> Result<()> {
+    conn.execute_batch("BEGIN;")?;
+
+    let res = pending.iter().try_for_each(|name| {
+        let up = MIGRATIONS_DIR
+            .get_file(format!("{name}/up.sql"))
+            .and_then(|file| file.contents_utf8())
+            .with_context(|| format!("missing up.sql in {name}"))?;
+
+        conn.execute_batch(up)
+            .with_context(|| format!("failed running migration {name}"))
+    });
+
+    conn.execute_batch("ROLLBACK;")?;
+    res
 }
 
 #[cfg(test)]
 mod tests {
-    use super::MIGRATIONS;
+    use similar_asserts::assert_eq;
+
+    use super::*;
 
     #[test]
     fn run_migrations() {
@@ -67,4 +203,27 @@ mod tests {
         MIGRATIONS.to_latest(&mut conn).unwrap();
         MIGRATIONS.to_version(&mut conn, 0).unwrap();
     }
+
+    #[test]
+    fn pending_migrations() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        let total = MIGRATIONS_DIR.dirs().count();
+
+        let status = MigrationStatus::new(&MIGRATIONS_DIR, total - 2).unwrap();
+        assert_eq!(total - 2, status.applied);
+        assert_eq!(2, status.pending.len());
+        assert!(status.pending[0] < status.pending[1]);
+        assert!(MigrationStatus::new(&MIGRATIONS_DIR, total + 1).is_err());
+
+        let status = MigrationStatus::new(&MIGRATIONS_DIR, 0).unwrap();
+        assert_eq!("0001-init", status.pending[0]);
+        dry_run_sqlite(&conn, &status.pending).unwrap();
+
+        let tables = conn
+            .query_row("SELECT COUNT(*) FROM sqlite_master;", [], |row| {
+                row.get::<_, u64>(0)
+            })
+            .unwrap();
+        assert_eq!(0, tables);
+    }
 }
@@ -0,0 +1,237 @@
+//! Deserialization of rows that were already read into SQLite values, behaving the same as
+//! [`serde_rusqlite`] does for rows of a SQLite database.
+
+use rusqlite::types::Value;
+use serde::{
+    de::{
+        value::{Error, MapDeserializer, SeqDeserializer},
+        DeserializeOwned, IntoDeserializer, Visitor,
+    },
+    forward_to_deserialize_any, Deserializer,
+};
+
+/// Deserialize a row, either as a whole (structs, tuples) or from its first column (single
+/// values).
+pub fn from_row<T: DeserializeOwned>(columns: &[String], values: Vec<Value>) -> Result<T, Error> {
+    T::deserialize(RowDeserializer { columns, values })
+}
+
+struct RowDeserializer<'a> {
+    columns: &'a [String],
+    values: Vec<Value>,
+}
+
+impl RowDeserializer<'_> {
+    fn first(self) -> ValueDeserializer {
+        ValueDeserializer(self.values.into_iter().next().unwrap_or(Value::Null))
+    }
+}
+
+macro_rules! forward_to_first_column {
+    ($($fun:ident)*) => {
+        $(
+            fn $fun<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                self.first().$fun(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for RowDeserializer<'_> {
+    type Error = Error;
+
+    forward_to_first_column! {
+        deserialize_any deserialize_bool deserialize_option deserialize_unit
+        deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_f32 deserialize_f64 deserialize_char deserialize_str deserialize_string
+        deserialize_bytes deserialize_byte_buf deserialize_seq deserialize_identifier
+        deserialize_ignored_any
+    }
+
+    forward_to_deserialize_any! {
+        tuple_struct
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self.first())
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.first().deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let mut seq = SeqDeserializer::new(self.values.into_iter().map(ValueDeserializer));
+        let value = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let mut map = MapDeserializer::new(
+            self.columns
+                .iter()
+                .map(String::as_str)
+                .zip(self.values.into_iter().map(ValueDeserializer)),
+        );
+        let value = visitor.visit_map(&mut map)?;
+        map.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.first().deserialize_enum(name, variants, visitor)
+    }
+}
+
+struct ValueDeserializer(Value);
+
+impl IntoDeserializer<'_, Error> for ValueDeserializer {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+impl<'de> Deserializer<'de> for ValueDeserializer {
+    type Error = Error;
+
+    forward_to_deserialize_any! {
+        i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes byte_buf
+        unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            Value::Integer(value) => visitor.visit_i64(value),
+            Value::Real(value) => visitor.visit_f64(value),
+            Value::Text(value) => visitor.visit_string(value),
+            Value::Blob(value) => visitor.visit_byte_buf(value),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Integer(value) => visitor.visit_bool(value != 0),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Null => visitor.visit_unit(),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.0 {
+            Value::Text(value) => visitor.visit_enum(value.into_deserializer()),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZero;
+
+    use super::*;
+    use crate::api::Source;
+
+    fn columns(names: &[&str]) -> Vec<String> {
+        names.iter().map(|&name| name.to_owned()).collect()
+    }
+
+    #[test]
+    fn single_values() {
+        let columns = columns(&["value"]);
+
+        assert!(from_row::<bool>(&columns, vec![Value::Integer(1)]).unwrap());
+        assert_eq!(
+            NonZero::new(5_u64),
+            from_row(&columns, vec![Value::Integer(5)]).unwrap()
+        );
+        assert_eq!(
+            None::<String>,
+            from_row(&columns, vec![Value::Null]).unwrap()
+        );
+        assert_eq!(
+            Source::Twitch,
+            from_row(&columns, vec![Value::Text("twitch".to_owned())]).unwrap()
+        );
+        assert!(from_row::<u64>(&columns, vec![Value::Integer(-1)]).is_err());
+    }
+
+    #[test]
+    fn whole_rows() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Row {
+            name: String,
+            count: u64,
+        }
+
+        let values = vec![Value::Text("help".to_owned()), Value::Integer(3)];
+
+        assert_eq!(
+            ("help".to_owned(), 3),
+            from_row::<(String, u64)>(&columns(&["a", "b"]), values.clone()).unwrap()
+        );
+        assert_eq!(
+            Row {
+                name: "help".to_owned(),
+                count: 3
+            },
+            from_row(&columns(&["name", "count"]), values).unwrap()
+        );
+    }
+}
@@ -1,9 +1,11 @@
 use anyhow::{Context, Result};
 use serde::{de::DeserializeOwned, Serialize};
 
-use self::connection::Connection;
+use self::connection::{Backend, Connection};
 
 pub mod connection;
+mod de;
+mod postgres;
 
 /// Shorthand to pass zero parameters to helper functions.
 ///
@@ -16,10 +18,17 @@ pub fn exec<P>(conn: &Connection, query: &str, params: P) -> Result<()>
 where
     P: Serialize,
 {
-    conn.prepare_cached(query)
-        .context("failed preparing query")?
-        .execute(serde_rusqlite::to_params(params).context("failed converting params")?)
-        .context("failed executing statement")?;
+    match &conn.0 {
+        Backend::Sqlite(conn) => {
+            conn.prepare_cached(query)
+                .context("failed preparing query")?
+                .execute(serde_rusqlite::to_params(params).context("failed converting params")?)
+                .context("failed executing statement")?;
+        }
+        Backend::Postgres(client) => {
+            client.query(query, postgres::params(params)?)?;
+        }
+    }
 
     Ok(())
 }
@@ -30,16 +39,28 @@ where
     P: Serialize,
     T: DeserializeOwned,
 {
-    let mut stmt = conn
-        .prepare_cached(query)
-        .context("failed preparing query")?;
-    let mut rows = stmt
-        .query(serde_rusqlite::to_params(params).context("failed converting params")?)
-        .context("failed executing statement")?;
-
-    serde_rusqlite::from_rows_ref(&mut rows)
-        .map(|r| r.context("failed reading row"))
+    let client = match &conn.0 {
+        Backend::Sqlite(conn) => {
+            let mut stmt = conn
+                .prepare_cached(query)
+                .context("failed preparing query")?;
+            let mut rows = stmt
+                .query(serde_rusqlite::to_params(params).context("failed converting params")?)
+                .context("failed executing statement")?;
+
+            return serde_rusqlite::from_rows_ref(&mut rows)
+                .map(|r| r.context("failed reading row"))
+                .next()
+                .transpose();
+        }
+        Backend::Postgres(client) => client,
+    };
+
+    let rows = client.query(query, postgres::params(params)?)?;
+    rows.values
+        .into_iter()
         .next()
+        .map(|row| de::from_row(&rows.columns, row).context("failed reading row"))
         .transpose()
 }
 
@@ -49,14 +70,25 @@ where
     P: Serialize,
     T: DeserializeOwned,
 {
-    let mut stmt = conn
-        .prepare_cached(query)
-        .context("failed preparing query")?;
-    let rows = stmt
-        .query(serde_rusqlite::to_params(params).context("failed converting params")?)
-        .context("failed executing statement")?;
-
-    serde_rusqlite::from_rows(rows)
-        .map(|r| r.context("failed reading row"))
+    let client = match &conn.0 {
+        Backend::Sqlite(conn) => {
+            let mut stmt = conn
+                .prepare_cached(query)
+                .context("failed preparing query")?;
+            let rows = stmt
+                .query(serde_rusqlite::to_params(params).context("failed converting params")?)
+                .context("failed executing statement")?;
+
+            return serde_rusqlite::from_rows(rows)
+                .map(|r| r.context("failed reading row"))
+                .collect();
+        }
+        Backend::Postgres(client) => client,
+    };
+
+    let rows = client.query(query, postgres::params(params)?)?;
+    rows.values
+        .into_iter()
+        .map(|row| de::from_row(&rows.columns, row).context("failed reading row"))
         .collect()
 }
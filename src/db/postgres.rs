@@ -0,0 +1,469 @@
+//! PostgreSQL backend, running the same queries as SQLite.
+//!
+//! The queries are written for SQLite, so their placeholders are rewritten before running them,
+//! and values are converted from and to SQLite's types. That way the rest of the bot doesn't need
+//! to know which backend is used.
+
+use std::{
+    collections::HashMap,
+    sync::mpsc::{self, Sender},
+    thread,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use include_dir::{include_dir, Dir};
+use postgres::{
+    types::{ToSql, Type},
+    NoTls, Row, Statement, Transaction,
+};
+use rusqlite::types::{ToSqlOutput, Value};
+use serde::Serialize;
+use serde_rusqlite::PositionalSliceSerializer;
+
+use super::connection::MigrationStatus;
+
+static MIGRATIONS_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/migrations/postgres");
+
+/// A query to run, with the channel that receives its result.
+type Job = (String, Vec<Value>, Sender<Result<Rows>>);
+
+/// Parameter for a query, converted to the type that the server expects.
+type Param = Box<dyn ToSql + Sync>;
+
+/// Result of a query, with all rows already read.
+pub struct Rows {
+    pub columns: Vec<String>,
+    pub values: Vec<Vec<Value>>,
+}
+
+/// Client for a PostgreSQL server.
+///
+/// The blocking client can't be used from within the async runtime, so it lives on its own thread
+/// and queries are sent to it.
+pub struct Client(Sender<Job>);
+
+impl Client {
+    /// Connect to the server and run all pending migrations.
+    pub fn connect(url: &str) -> Result<Self> {
+        let (jobs_tx, jobs_rx) = mpsc::channel::<Job>();
+        let (ready_tx, ready_rx) = mpsc::channel();
+        let url = url.to_owned();
+
+        thread::Builder::new()
+            .name("postgres".to_owned())
+            .spawn(move || {
+                let mut worker = match Worker::connect(&url) {
+                    Ok(worker) => worker,
+                    Err(e) => {
+                        ready_tx.send(Err(e)).ok();
+                        return;
+                    }
+                };
+                ready_tx.send(Ok(())).ok();
+
+                for (query, params, reply) in jobs_rx {
+                    reply.send(worker.run(&query, params)).ok();
+                }
+            })
+            .context("failed starting database thread")?;
+
+        ready_rx.recv().context("database thread stopped")??;
+
+        Ok(Self(jobs_tx))
+    }
+
+    /// Run a single query, returning all its rows.
+    pub fn query(&self, query: &str, params: Vec<Value>) -> Result<Rows> {
+        let (reply_tx, reply_rx) = mpsc::channel();
+        self.0
+            .send((query.to_owned(), params, reply_tx))
+            .context("database thread stopped")?;
+
+        reply_rx.recv().context("database thread stopped")?
+    }
+}
+
+struct Worker {
+    client: postgres::Client,
+    statements: HashMap<String, Statement>,
+}
+
+impl Worker {
+    fn connect(url: &str) -> Result<Self> {
+        let mut client = postgres::Client::connect(url, NoTls).context("failed connecting")?;
+        migrate(&mut client).context("failed running migrations")?;
+
+        Ok(Self {
+            client,
+            statements: HashMap::new(),
+        })
+    }
+
+    fn run(&mut self, query: &str, params: Vec<Value>) -> Result<Rows> {
+        let statement = if let Some(statement) = self.statements.get(query) {
+            statement.clone()
+        } else {
+            let statement = self
+                .client
+                .prepare(&placeholders(query))
+                .context("failed preparing query")?;
+            self.statements.insert(query.to_owned(), statement.clone());
+            statement
+        };
+
+        let params = params
+            .into_iter()
+            .zip(statement.params())
+            .map(|(value, ty)| param(value, ty))
+            .collect::<Result<Vec<_>>>()
+            .context("failed converting params")?;
+        let params = params
+            .iter()
+            .map(|param| &**param as &(dyn ToSql + Sync))
+            .collect::<Vec<_>>();
+
+        let rows = self
+            .client
+            .query(&statement, &params)
+            .context("failed executing statement")?;
+
+        Ok(Rows {
+            columns: statement
+                .columns()
+                .iter()
+                .map(|column| column.name().to_owned())
+                .collect(),
+            values: rows
+                .iter()
+                .map(row_values)
+                .collect::<Result<_>>()
+                .context("failed reading row")?,
+        })
+    }
+}
+
+/// Apply all migrations that weren't run yet. The version table is locked while doing so, in
+/// case several instances of the bot start at the same time.
+fn migrate(client: &mut postgres::Client) -> Result<()> {
+    client.batch_execute("CREATE TABLE IF NOT EXISTS schema_version (version BIGINT NOT NULL);")?;
+
+    let mut tx = client.transaction()?;
+    tx.batch_execute("LOCK TABLE schema_version IN EXCLUSIVE MODE;")?;
+
+    let status = MigrationStatus::new(&MIGRATIONS_DIR, version(&mut tx)?)?;
+    run_migrations(&mut tx, &status.pending)?;
+
+    tx.execute("DELETE FROM schema_version;", &[])?;
+    tx.execute(
+        "INSERT INTO schema_version (version) VALUES ($1);",
+        &[&i64::try_from(status.applied + status.pending.len())?],
+    )?;
+
+    tx.commit().map_err(Into::into)
+}
+
+/// Check which migrations weren't run yet, without applying them. On a dry run, they're run in a
+/// transaction that is rolled back afterwards.
+pub fn status(url: &str, dry_run: bool) -> Result<MigrationStatus> {
+    let url = url.to_owned();
+
+    // The blocking client can't be used from within the async runtime.
+    thread::spawn(move || {
+        let mut client = postgres::Client::connect(&url, NoTls).context("failed connecting")?;
+        let mut tx = client.transaction()?;
+
+        tx.batch_execute("CREATE TABLE IF NOT EXISTS schema_version (version BIGINT NOT NULL);")?;
+        let status = MigrationStatus::new(&MIGRATIONS_DIR, version(&mut tx)?)?;
+        if dry_run {
+            run_migrations(&mut tx, &status.pending)?;
+        }
+
+        tx.rollback()?;
+        Ok(status)
+    })
+    .join()
+    .map_err(|_| anyhow!("database thread panicked"))?
+}
+
+/// Amount of migrations that were applied already.
+fn version(tx: &mut Transaction<'_>) -> Result<usize> {
+    let version = tx
+        .query_opt("SELECT version FROM schema_version;", &[])?
+        .map_or(0, |row| row.get::<_, i64>(0));
+
+    usize::try_from(version).map_err(Into::into)
+}
+
+fn run_migrations(tx: &mut Transaction<'_>, pending: &[String]) -> Result<()> {
+    for name in pending {
+        let up = MIGRATIONS_DIR
+            .get_file(format!("{name}/up.sql"))
+            .and_then(|file| file.contents_utf8())
+            .with_context(|| format!("missing up.sql in {name}"))?;
+
+        tx.batch_execute(up)
+            .with_context(|| format!("failed running migration {name}"))?;
+    }
+
+    Ok(())
+}
+
+/// Convert the parameters for a query the same way as for SQLite.
+pub fn params(params: impl Serialize) -> Result<Vec<Value>> {
+    params
+        .serialize(PositionalSliceSerializer::default())
+        .context("failed converting params")?
+        .iter()
+        .map(|param| match param.to_sql()? {
+            ToSqlOutput::Borrowed(value) => Ok(value.into()),
+            ToSqlOutput::Owned(value) => Ok(value),
+            _ => bail!("unsupported parameter"),
+        })
+        .collect()
+}
+
+/// Rewrite SQLite's placeholders (`?` and `?N`) to PostgreSQL's (`$N`).
+fn placeholders(query: &str) -> String {
+    let mut result = String::with_capacity(query.len());
+    let mut chars = query.chars().peekable();
+    let (mut quoted, mut last) = (false, 0_u32);
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                quoted = !quoted;
+                result.push(c);
+            }
+            '?' if !quoted => {
+                let mut index = 0;
+                let mut numbered = false;
+                while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                    index = index * 10 + digit.to_digit(10).unwrap_or_default();
+                    numbered = true;
+                }
+
+                let index = if numbered { index } else { last + 1 };
+                last = last.max(index);
+
+                result.push('$');
+                result.push_str(&index.to_string());
+            }
+            _ => result.push(c),
+        }
+    }
+
+    result
+}
+
+/// Convert a SQLite value to the type of the query parameter.
+fn param(value: Value, ty: &Type) -> Result<Param> {
+    Ok(match (value, ty.name()) {
+        (Value::Null, "int2") => Box::new(None::<i16>),
+        (Value::Null, "int4") => Box::new(None::<i32>),
+        (Value::Null, "int8") => Box::new(None::<i64>),
+        (Value::Null, "bool") => Box::new(None::<bool>),
+        (Value::Null, "float8") => Box::new(None::<f64>),
+        (Value::Null, "text" | "varchar") => Box::new(None::<String>),
+        (Value::Null, "bytea") => Box::new(None::<Vec<u8>>),
+        (Value::Integer(value), "int2") => Box::new(i16::try_from(value)?),
+        (Value::Integer(value), "int4") => Box::new(i32::try_from(value)?),
+        (Value::Integer(value), "int8") => Box::new(value),
+        (Value::Integer(value), "bool") => Box::new(value != 0),
+        (Value::Real(value), "float8") => Box::new(value),
+        (Value::Text(value), "text" | "varchar") => Box::new(value),
+        (Value::Blob(value), "bytea") => Box::new(value),
+        (value, ty) => bail!("can't use {:?} as {ty} parameter", value.data_type()),
+    })
+}
+
+/// Read all columns of a row as SQLite values.
+fn row_values(row: &Row) -> Result<Vec<Value>> {
+    row.columns()
+        .iter()
+        .enumerate()
+        .map(|(idx, column)| {
+            Ok(match column.type_().name() {
+                "int2" => row.try_get::<_, Option<i16>>(idx)?.map(i64::from).into(),
+                "int4" => row.try_get::<_, Option<i32>>(idx)?.map(i64::from).into(),
+                "int8" => row.try_get::<_, Option<i64>>(idx)?.into(),
+                "bool" => row.try_get::<_, Option<bool>>(idx)?.map(i64::from).into(),
+                "float8" => row.try_get::<_, Option<f64>>(idx)?.into(),
+                "text" | "varchar" | "name" => row.try_get::<_, Option<String>>(idx)?.into(),
+                "bytea" => row.try_get::<_, Option<Vec<u8>>>(idx)?.into(),
+                ty => bail!("unsupported column type {ty} of {}", column.name()),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZero;
+
+    use time::Month;
+
+    use super::*;
+    use crate::{
+        api::{
+            response::{Faq, WatchedCrate},
+            AdminId, AuthorId, Highlight, Source, UserRef,
+        },
+        db::connection::{Backend, Connection},
+        settings::Retention,
+        state::State,
+        statistics::Stats,
+    };
+
+    /// Only runs against a real server, if one is given in `TOGGLEBOT_TEST_POSTGRES`.
+    #[test]
+    fn roundtrip() {
+        let Ok(url) = std::env::var("TOGGLEBOT_TEST_POSTGRES") else {
+            return;
+        };
+
+        let conn = Connection(Backend::Postgres(Client::connect(&url).unwrap()));
+        let state = State::new(conn);
+
+        state
+            .add_custom_command(
+                Source::Twitch,
+                "pg_roundtrip",
+                "hello",
+                &AuthorId::Console.user_ref(),
+                time::OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+        assert_eq!(
+            Some("hello".to_owned()),
+            state
+                .get_custom_command(Source::Twitch, "pg_roundtrip")
+                .unwrap()
+        );
+
+        state
+            .remove_custom_command(Source::Twitch, "pg_roundtrip")
+            .unwrap();
+        assert_eq!(
+            None,
+            state
+                .get_custom_command(Source::Twitch, "pg_roundtrip")
+                .unwrap()
+        );
+        assert!(state.size().unwrap() > 0);
+
+        let user = UserRef {
+            source: Source::Discord,
+            id: "pg_roundtrip".to_owned(),
+        };
+        state.set_birthday(&user, Month::April, 2).unwrap();
+        assert!(state.announce_birthday(&user, false).unwrap());
+        assert_eq!(
+            Some((Month::April, 2, false)),
+            state.birthday(&user).unwrap()
+        );
+        assert!(state.remove_birthday(&user).unwrap());
+
+        let faq = Faq {
+            name: "pgroundtrip".to_owned(),
+            pattern: "postgres".to_owned(),
+            regex: true,
+            answer: "hello".to_owned(),
+        };
+        state.add_faq(&faq).unwrap();
+        assert!(state
+            .list_faqs()
+            .unwrap()
+            .iter()
+            .any(|faq| faq.name == "pgroundtrip" && faq.regex));
+        assert!(state.remove_faq("pgroundtrip").unwrap());
+
+        let highlight = Highlight {
+            guild: None,
+            channel: NonZero::new(1).unwrap(),
+            message: NonZero::new(u64::from(u32::MAX)).unwrap(),
+            author: "pg_roundtrip".to_owned(),
+            content: "hello".to_owned(),
+        };
+        state
+            .add_highlight(
+                &highlight,
+                AdminId::new(1).unwrap(),
+                time::OffsetDateTime::now_utc(),
+            )
+            .unwrap();
+        assert_eq!(highlight, state.recent_highlights(1).unwrap()[0]);
+        assert_eq!(
+            1,
+            state
+                .edit_tracked_message(highlight.message, "edited")
+                .unwrap()
+        );
+        assert_eq!(1, state.remove_tracked_message(highlight.message).unwrap());
+
+        releases_roundtrip(&state);
+
+        state
+            .set_away("pg_roundtrip", time::OffsetDateTime::UNIX_EPOCH)
+            .unwrap();
+        assert_eq!(
+            Some(time::OffsetDateTime::UNIX_EPOCH),
+            state.clear_away().unwrap()
+        );
+
+        let statistics = Stats::new(Connection(Backend::Postgres(
+            Client::connect(&url).unwrap(),
+        )));
+        statistics.cleanup(Retention::default()).unwrap();
+    }
+
+    /// Tracking of releases and other news, that are announced once.
+    fn releases_roundtrip(state: &State) {
+        let watched = WatchedCrate {
+            name: "pg-roundtrip".to_owned(),
+            version: "1.0.0".to_owned(),
+            etag: None,
+        };
+        assert!(state.watch_crate(&watched).unwrap());
+        assert!(!state.watch_crate(&watched).unwrap());
+        let watched = WatchedCrate {
+            version: "1.1.0".to_owned(),
+            etag: Some("\"pg\"".to_owned()),
+            ..watched
+        };
+        state.update_watched_crate(&watched).unwrap();
+        assert!(state
+            .watched_crates()
+            .unwrap()
+            .iter()
+            .any(|c| c.name == "pg-roundtrip" && c.version == "1.1.0" && c.etag.is_some()));
+        assert!(state.unwatch_crate("pg-roundtrip").unwrap());
+
+        state
+            .add_github_event("pg/roundtrip", "release", 1)
+            .unwrap();
+        assert!(!state
+            .add_github_event("pg/roundtrip", "release", 1)
+            .unwrap());
+        assert!(state.has_github_events("pg/roundtrip", "release").unwrap());
+
+        state.add_rust_release("0.0.0-pg-roundtrip").unwrap();
+        assert!(!state.add_rust_release("0.0.0-pg-roundtrip").unwrap());
+        assert!(state.has_rust_releases().unwrap());
+    }
+
+    #[test]
+    fn rewrite_placeholders() {
+        assert_eq!(
+            "SELECT * FROM t WHERE a = $1 AND b = $2;",
+            placeholders("SELECT * FROM t WHERE a = ? AND b = ?;")
+        );
+        assert_eq!(
+            "UPDATE t SET a = a - $3 WHERE b = $1 AND c = $2 AND a >= $3;",
+            placeholders("UPDATE t SET a = a - ?3 WHERE b = ?1 AND c = ?2 AND a >= ?3;")
+        );
+        assert_eq!(
+            "SELECT '?' FROM t WHERE a = $1;",
+            placeholders("SELECT '?' FROM t WHERE a = ?;")
+        );
+    }
+}
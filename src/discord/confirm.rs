@@ -0,0 +1,120 @@
+//! Button to confirm a destructive command, as alternative to typing `!confirm`.
+
+use std::time::Instant;
+
+use anyhow::Result;
+use poise::serenity_prelude::{
+    self as serenity, ButtonStyle, ComponentInteraction, CreateActionRow, CreateButton,
+    CreateInteractionResponse, CreateInteractionResponseMessage,
+};
+use tracing::{info_span, instrument, Instrument, Span};
+
+use super::{event_author, State};
+use crate::{
+    api::{
+        request::{self, Request},
+        response::{self, Response},
+        Message, Source,
+    },
+    connector,
+    render::discord::{self as render, Reply},
+    settings::Mentions,
+};
+
+/// Identifier of the confirmation button.
+const CUSTOM_ID: &str = "confirm";
+
+/// Row with the button, that is attached to the request for a confirmation.
+pub fn button() -> CreateActionRow {
+    CreateActionRow::Buttons(vec![CreateButton::new(CUSTOM_ID)
+        .label("Confirm")
+        .style(ButtonStyle::Danger)])
+}
+
+/// Confirm the pending command of whoever clicked the button.
+#[instrument(skip_all, name = "discord confirmation", fields(source = %Source::Discord))]
+pub async fn run(
+    ctx: &serenity::Context,
+    event: &serenity::FullEvent,
+    state: &State,
+) -> Result<()> {
+    let received = Instant::now();
+    let serenity::FullEvent::InteractionCreate { interaction } = event else {
+        return Ok(());
+    };
+    let Some(component) = interaction.as_message_component() else {
+        return Ok(());
+    };
+    if component.data.custom_id != CUSTOM_ID {
+        return Ok(());
+    }
+
+    let member = component.member.as_ref();
+    let message = Message {
+        span: Span::current(),
+        source: Source::Discord,
+        content: Request::Admin(request::Admin::Confirm),
+        author: event_author(
+            ctx,
+            component.guild_id,
+            &component.user,
+            member.map_or(&[], |member| &member.roles),
+            member.is_some_and(|member| member.premium_since.is_some()),
+        ),
+        mention: None,
+        channel: super::guild_channel(component.guild_id, component.channel_id),
+    };
+
+    if let Some(resp) = connector::dispatch(&state.queue, message).await {
+        let reply = async {
+            // Only the author of the command can confirm it, so anybody else gets a reply that
+            // only they see, and the button stays in place.
+            let (reply, replace) = match resp {
+                Response::User(resp) => match render::user(resp, &state.settings.streamer)? {
+                    Some(reply) => (reply, true),
+                    None => return Ok(()),
+                },
+                Response::Admin(resp @ response::Admin::NothingToConfirm) => {
+                    (render::admin(resp), false)
+                }
+                Response::Admin(resp) => (render::admin(resp), true),
+                Response::Owner(resp) => (render::owner(resp), true),
+                Response::Denied(required) => (render::denied(required), false),
+                Response::Confirm(action) => (render::confirm(action), false),
+            };
+
+            respond(ctx, component, reply, replace, state.mentions).await
+        }
+        .instrument(info_span!("reply"));
+
+        state.timings.reply("confirm", received, reply).await?;
+    }
+
+    Ok(())
+}
+
+/// Answer the click, either replacing the confirmation request with the outcome of the command,
+/// or with a separate message that only the clicking user sees.
+async fn respond(
+    ctx: &serenity::Context,
+    component: &ComponentInteraction,
+    reply: Reply,
+    replace: bool,
+    mentions: Mentions,
+) -> Result<()> {
+    let mut message = CreateInteractionResponseMessage::new().content(reply.content);
+    if let Some(embed) = reply.embed {
+        message = message.embed(embed);
+    }
+    message = message.allowed_mentions(super::allowed_mentions(mentions, reply.notify));
+
+    let response = if replace {
+        CreateInteractionResponse::UpdateMessage(message.components(Vec::new()))
+    } else {
+        CreateInteractionResponse::Message(message.ephemeral(true))
+    };
+
+    component.create_response(&ctx.http, response).await?;
+
+    Ok(())
+}
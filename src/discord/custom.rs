@@ -0,0 +1,193 @@
+//! Custom commands as slash commands, so they show up in Discord's command picker like the
+//! built-in ones.
+
+use std::{collections::HashSet, time::Instant};
+
+use anyhow::Result;
+use poise::serenity_prelude::{
+    self as serenity, CommandInteraction, CreateCommand, CreateInteractionResponse,
+    CreateInteractionResponseMessage,
+};
+use tracing::{info_span, instrument, warn, Instrument, Span};
+
+use super::{event_author, State};
+use crate::{
+    api::{
+        request::{self, Request},
+        response::Response,
+        CustomCommand, Message, Source,
+    },
+    connector,
+    render::discord::{self as render, Delivery},
+    settings::Mentions,
+};
+
+/// Maximum amount of slash commands that Discord allows per application.
+const MAX_COMMANDS: usize = 100;
+/// Maximum length of a slash command's name.
+const MAX_NAME_LEN: usize = 32;
+/// Maximum length of a slash command's description.
+const MAX_DESCRIPTION_LEN: usize = 100;
+
+/// Create the slash commands for all custom commands, that don't clash with any of the built-in
+/// ones and fit within Discord's limits.
+pub fn create(
+    builtin: &[CreateCommand],
+    names: &HashSet<&str>,
+    custom: &[CustomCommand],
+) -> Vec<CreateCommand> {
+    let commands = custom
+        .iter()
+        .filter(|command| {
+            command.name.len() <= MAX_NAME_LEN && !names.contains(command.name.as_str())
+        })
+        .map(|command| CreateCommand::new(&command.name).description(description(&command.content)))
+        .collect::<Vec<_>>();
+
+    let space = MAX_COMMANDS.saturating_sub(builtin.len());
+    if commands.len() > space {
+        warn!(
+            count = commands.len() - space,
+            "too many custom commands, not all of them are registered"
+        );
+    }
+
+    commands.into_iter().take(space).collect()
+}
+
+/// Shorten the content to the first line, within the description limit.
+fn description(content: &str) -> String {
+    let line = content.lines().next().unwrap_or_default().trim();
+    if line.is_empty() {
+        return "Custom command".to_owned();
+    }
+
+    if line.chars().count() <= MAX_DESCRIPTION_LEN {
+        line.to_owned()
+    } else {
+        let mut short = line
+            .chars()
+            .take(MAX_DESCRIPTION_LEN - 1)
+            .collect::<String>();
+        short.push('…');
+        short
+    }
+}
+
+/// Answer the invocation of a custom slash command, which the framework doesn't know about.
+#[instrument(skip_all, name = "discord custom command", fields(source = %Source::Discord))]
+pub async fn run(
+    ctx: &serenity::Context,
+    event: &serenity::FullEvent,
+    state: &State,
+) -> Result<()> {
+    let received = Instant::now();
+    let serenity::FullEvent::InteractionCreate { interaction } = event else {
+        return Ok(());
+    };
+    let Some(command) = interaction.as_command() else {
+        return Ok(());
+    };
+    if !state.is_custom(&command.data.name) {
+        return Ok(());
+    }
+
+    let member = command.member.as_deref();
+    let message = Message {
+        span: Span::current(),
+        source: Source::Discord,
+        content: Request::User(request::User::Custom(command.data.name.clone())),
+        author: event_author(
+            ctx,
+            command.guild_id,
+            &command.user,
+            member.map_or(&[], |member| &member.roles),
+            member.is_some_and(|member| member.premium_since.is_some()),
+        ),
+        mention: None,
+        channel: super::guild_channel(command.guild_id, command.channel_id),
+    };
+
+    if let Some(Response::User(resp)) = connector::dispatch(&state.queue, message).await {
+        let reply = async {
+            match render::user(resp, &state.settings.streamer)? {
+                Some(reply) => respond(ctx, command, reply, state.mentions).await,
+                None => Ok(()),
+            }
+        }
+        .instrument(info_span!("reply"));
+
+        state.timings.reply("custom", received, reply).await?;
+    }
+
+    Ok(())
+}
+
+async fn respond(
+    ctx: &serenity::Context,
+    command: &CommandInteraction,
+    reply: render::Reply,
+    mentions: Mentions,
+) -> Result<()> {
+    let mut message = CreateInteractionResponseMessage::new()
+        .content(reply.content)
+        .ephemeral(reply.delivery != Delivery::Channel);
+    if let Some(embed) = reply.embed {
+        message = message.embed(embed);
+    }
+    if let Some(attachment) = reply.attachment {
+        message = message.add_file(serenity::CreateAttachment::bytes(
+            attachment.data,
+            attachment.name,
+        ));
+    }
+    message = message.allowed_mentions(super::allowed_mentions(mentions, reply.notify));
+
+    command
+        .create_response(&ctx.http, CreateInteractionResponse::Message(message))
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom(name: &str, content: &str) -> CustomCommand {
+        CustomCommand {
+            name: name.to_owned(),
+            content: content.to_owned(),
+        }
+    }
+
+    #[test]
+    fn skip_clashing_names() {
+        let builtin = vec![CreateCommand::new("help")];
+        let names = HashSet::from(["help"]);
+        let custom = [
+            custom("help", "Not the real help"),
+            custom("hug", "Sending hugs"),
+            custom(&"a".repeat(33), "Too long"),
+        ];
+
+        assert_eq!(1, create(&builtin, &names, &custom).len());
+    }
+
+    #[test]
+    fn limit_commands() {
+        let builtin = vec![CreateCommand::new("help"); 98];
+        let custom = (0..5)
+            .map(|i| custom(&format!("cmd{i}"), "content"))
+            .collect::<Vec<_>>();
+
+        assert_eq!(2, create(&builtin, &HashSet::new(), &custom).len());
+    }
+
+    #[test]
+    fn shorten_description() {
+        assert_eq!("Hello there", description("Hello there\nsecond line"));
+        assert_eq!("Custom command", description(""));
+        assert_eq!(100, description(&"a".repeat(150)).chars().count());
+    }
+}
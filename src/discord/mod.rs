@@ -2,27 +2,46 @@
 
 use std::{
     fmt::{self, Display},
-    sync::Arc,
+    num::NonZero,
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+    time::{Duration, Instant},
 };
 
-use anyhow::Result;
-use poise::serenity_prelude::{self as serenity, UserId};
-use tokio::sync::oneshot;
-use tokio_shutdown::Shutdown;
-use tracing::{error, info, info_span, instrument, Instrument, Span};
+use anyhow::{anyhow, Context as _, Result};
+use futures_util::future::BoxFuture;
+use poise::{
+    serenity_prelude::{
+        self as serenity, ChannelId, CreateAllowedMentions, CreateAttachment, CreateMessage,
+        EditMessage, GuildId, MessageId, RoleId, UserId,
+    },
+    CreateReply, Modal,
+};
+use tracing::{error, info, info_span, instrument, warn, Instrument, Span};
 
 use crate::{
     api::{
         request::{self, Request, StatisticsDate},
         response::{self, Response},
-        AuthorId, Message, Queue, Source,
+        Author, AuthorId, Availability, ChannelRule, CustomCommand, Event, Events, GuildChannel,
+        Highlight, Message, Poll, PollKind, Post, Queue, Source, StreamInfo, SuggestionStatus,
     },
-    settings::{Commands as CommandSettings, Discord as DiscordSettings},
+    completions::Completions,
+    connector::{self, Connector, Context as ConnectorContext},
+    emojis,
+    fun::Kind,
+    handler,
+    health::Health,
+    render::discord::{self as render, Delivery, Reply},
+    settings::{Commands as CommandSettings, Discord as DiscordSettings, DmRouting, Mentions},
+    textparse,
+    timing::Timings,
 };
 
-mod admin;
-mod owner;
-mod user;
+mod confirm;
+mod custom;
+mod poll;
+mod presence;
+mod retry;
 
 type Context<'a> = poise::ApplicationContext<'a, State, anyhow::Error>;
 
@@ -49,7 +68,13 @@ async fn ohelp(ctx: Context<'_>) -> Result<()> {
     slash_command,
     owners_only,
     category = "Owner",
-    subcommands("admins_add", "admins_remove", "admins_list")
+    subcommands(
+        "admins_add",
+        "admins_remove",
+        "admins_add_role",
+        "admins_remove_role",
+        "admins_list"
+    )
 )]
 async fn admins(_: Context<'_>) -> Result<()> {
     Ok(())
@@ -87,7 +112,39 @@ async fn admins_remove(ctx: Context<'_>, user: UserId) -> Result<()> {
     .await
 }
 
-/// List all currently configured admin users.
+/// Make all members of a role admins.
+#[poise::command(slash_command, owners_only, category = "Owner", rename = "add_role")]
+async fn admins_add_role(ctx: Context<'_>, role: RoleId) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Owner(request::Owner::Admins(request::Admins::AddRole(
+                role.into(),
+            ))),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Stop making members of a role admins.
+#[poise::command(slash_command, owners_only, category = "Owner", rename = "remove_role")]
+async fn admins_remove_role(ctx: Context<'_>, role: RoleId) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Owner(request::Owner::Admins(request::Admins::RemoveRole(
+                role.into(),
+            ))),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// List all currently configured admin users and roles.
 #[poise::command(slash_command, owners_only, category = "Owner", rename = "list")]
 async fn admins_list(ctx: Context<'_>) -> Result<()> {
     handle_message(
@@ -101,6 +158,219 @@ async fn admins_list(ctx: Context<'_>) -> Result<()> {
     .await
 }
 
+#[allow(clippy::unused_async)]
+#[poise::command(
+    slash_command,
+    owners_only,
+    category = "Owner",
+    subcommands("plugins_list", "plugins_load", "plugins_unload")
+)]
+async fn plugins(_: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// List all registered plugins and whether they're loaded.
+#[poise::command(slash_command, owners_only, category = "Owner", rename = "list")]
+async fn plugins_list(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Owner(request::Owner::Plugins(request::Plugins::List)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Load a plugin, making its commands available.
+#[poise::command(slash_command, owners_only, category = "Owner", rename = "load")]
+async fn plugins_load(ctx: Context<'_>, name: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Owner(request::Owner::Plugins(request::Plugins::Load(name))),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Unload a plugin, making its commands unavailable.
+#[poise::command(slash_command, owners_only, category = "Owner", rename = "unload")]
+async fn plugins_unload(ctx: Context<'_>, name: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Owner(request::Owner::Plugins(request::Plugins::Unload(name))),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+#[allow(clippy::unused_async)]
+#[poise::command(
+    slash_command,
+    owners_only,
+    category = "Owner",
+    subcommands("debug_parse")
+)]
+async fn debug(_: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Show how a message is parsed into a command.
+#[poise::command(slash_command, owners_only, category = "Owner", rename = "parse")]
+async fn debug_parse(ctx: Context<'_>, text: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Owner(request::Owner::DebugParse {
+                text,
+                source: Source::Discord,
+                mention: None,
+            }),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Register all slash commands with Discord again.
+#[poise::command(slash_command, owners_only, category = "Owner")]
+async fn resync_commands(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Owner(request::Owner::ResyncCommands),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Post a message on all services at once.
+#[poise::command(slash_command, owners_only, category = "Owner")]
+async fn broadcast(ctx: Context<'_>, text: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Owner(request::Owner::Broadcast(text)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Delete outdated statistics and compact the database.
+#[poise::command(slash_command, owners_only, category = "Owner")]
+async fn cleanup(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Owner(request::Owner::Cleanup),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+#[cfg(feature = "chaos")]
+#[allow(clippy::unused_async)]
+#[poise::command(
+    slash_command,
+    owners_only,
+    category = "Owner",
+    subcommands(
+        "chaos_status",
+        "chaos_off",
+        "chaos_delay",
+        "chaos_send",
+        "chaos_events"
+    )
+)]
+async fn chaos(_: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Show the currently injected faults.
+#[cfg(feature = "chaos")]
+#[poise::command(slash_command, owners_only, category = "Owner", rename = "status")]
+async fn chaos_status(ctx: Context<'_>) -> Result<()> {
+    handle_chaos(ctx, request::Chaos::Status).await
+}
+
+/// Disable all injected faults.
+#[cfg(feature = "chaos")]
+#[poise::command(slash_command, owners_only, category = "Owner", rename = "off")]
+async fn chaos_off(ctx: Context<'_>) -> Result<()> {
+    handle_chaos(ctx, request::Chaos::Off).await
+}
+
+/// Randomly delay the handling of messages.
+#[cfg(feature = "chaos")]
+#[poise::command(slash_command, owners_only, category = "Owner", rename = "delay")]
+async fn chaos_delay(
+    ctx: Context<'_>,
+    #[description = "Chance in percent"]
+    #[max = 100]
+    rate: u8,
+    #[description = "Maximum delay in milliseconds"] max_ms: Option<u64>,
+) -> Result<()> {
+    handle_chaos(
+        ctx,
+        request::Chaos::Delay {
+            rate,
+            max: std::time::Duration::from_millis(max_ms.unwrap_or(5000)),
+        },
+    )
+    .await
+}
+
+/// Randomly fail sending messages.
+#[cfg(feature = "chaos")]
+#[poise::command(slash_command, owners_only, category = "Owner", rename = "send")]
+async fn chaos_send(
+    ctx: Context<'_>,
+    #[description = "Chance in percent"]
+    #[max = 100]
+    rate: u8,
+) -> Result<()> {
+    handle_chaos(ctx, request::Chaos::SendFailure(rate)).await
+}
+
+/// Randomly drop incoming Twitch events.
+#[cfg(feature = "chaos")]
+#[poise::command(slash_command, owners_only, category = "Owner", rename = "events")]
+async fn chaos_events(
+    ctx: Context<'_>,
+    #[description = "Chance in percent"]
+    #[max = 100]
+    rate: u8,
+) -> Result<()> {
+    handle_chaos(ctx, request::Chaos::EventDrop(rate)).await
+}
+
+#[cfg(feature = "chaos")]
+async fn handle_chaos(ctx: Context<'_>, req: request::Chaos) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Owner(request::Owner::Chaos(req)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
 // --------------------------------------------
 // ADMINS
 // --------------------------------------------
@@ -125,8 +395,12 @@ async fn ahelp(ctx: Context<'_>) -> Result<()> {
     category = "Admin",
     subcommands(
         "custom_commands_add",
+        "custom_commands_edit",
         "custom_commands_remove",
-        "custom_commands_list"
+        "custom_commands_list",
+        "custom_commands_show",
+        "custom_commands_history",
+        "custom_commands_revert"
     )
 )]
 async fn custom_commands(_: Context<'_>) -> Result<()> {
@@ -135,12 +409,29 @@ async fn custom_commands(_: Context<'_>) -> Result<()> {
 
 #[derive(poise::ChoiceParameter)]
 enum Target {
-    /// Everywhere (Discord and Twitch).
+    /// Everywhere (Discord, Twitch, Matrix and YouTube).
     All,
     /// Only Discord.
     Discord,
     /// Only Twitch.
     Twitch,
+    /// Only Matrix.
+    Matrix,
+    /// Only YouTube.
+    YouTube,
+}
+
+impl Target {
+    /// The single service to target, or `None` for all of them.
+    fn source(&self) -> Option<Source> {
+        match self {
+            Self::All => None,
+            Self::Discord => Some(Source::Discord),
+            Self::Twitch => Some(Source::Twitch),
+            Self::Matrix => Some(Source::Matrix),
+            Self::YouTube => Some(Source::YouTube),
+        }
+    }
 }
 
 impl Display for Target {
@@ -149,6 +440,8 @@ impl Display for Target {
             Self::All => "all",
             Self::Discord => "discord",
             Self::Twitch => "twitch",
+            Self::Matrix => "matrix",
+            Self::YouTube => "youtube",
         })
     }
 }
@@ -157,12 +450,84 @@ impl Display for Target {
 ///
 /// The command can be modified for all sources or individually. Command names must start with a
 /// lowercase letter, only consist of lowercase letters, numbers and underscores and must not start
-/// with the `!`.
+/// with the `!`. Without any content, an editor opens to write longer content over multiple lines.
 #[poise::command(slash_command, category = "Admin", rename = "add")]
 async fn custom_commands_add(
     ctx: Context<'_>,
     target: Target,
     name: String,
+    content: Option<String>,
+) -> Result<()> {
+    let content = match content {
+        Some(content) => content,
+        None => match edit_command(ctx, String::new()).await? {
+            Some(content) => content,
+            None => return Ok(()),
+        },
+    };
+
+    save_command(ctx, &target, name, content).await
+}
+
+/// Edit the content of a custom command in a multi-line editor.
+#[poise::command(slash_command, category = "Admin", rename = "edit")]
+async fn custom_commands_edit(
+    ctx: Context<'_>,
+    target: Target,
+    #[autocomplete = "complete_custom_commands"] name: String,
+) -> Result<()> {
+    let current = ctx
+        .data()
+        .completions
+        .custom_command(target.source(), &name)
+        .unwrap_or_default();
+
+    match edit_command(ctx, current).await? {
+        Some(content) => save_command(ctx, &target, name, content).await,
+        None => Ok(()),
+    }
+}
+
+/// Multi-line editor for the content of a custom command.
+#[derive(poise::Modal)]
+#[name = "Custom command"]
+struct CommandEditor {
+    #[name = "Content"]
+    #[placeholder = "What the command replies with"]
+    #[paragraph]
+    #[min_length = 1]
+    #[max_length = 2000]
+    content: String,
+}
+
+/// Open the editor with the given content, and get the new content once submitted. Gives `None`
+/// if the editor was closed, or the content is blank.
+async fn edit_command(ctx: Context<'_>, content: String) -> Result<Option<String>> {
+    let Some(editor) = CommandEditor::execute_with_defaults(ctx, CommandEditor { content }).await?
+    else {
+        return Ok(None);
+    };
+
+    let content = editor.content.trim();
+    if content.is_empty() {
+        reply_private(
+            ctx,
+            format!(
+                "{} the command content must not be blank",
+                emojis::COLLISION
+            ),
+        )
+        .await?;
+        return Ok(None);
+    }
+
+    Ok(Some(content.to_owned()))
+}
+
+async fn save_command(
+    ctx: Context<'_>,
+    target: &Target,
+    name: String,
     content: String,
 ) -> Result<()> {
     handle_message(
@@ -170,11 +535,7 @@ async fn custom_commands_add(
         SerenityMessage {
             content: Request::Admin(request::Admin::CustomCommands(
                 request::CustomCommands::Add {
-                    source: match target {
-                        Target::All => None,
-                        Target::Discord => Some(Source::Discord),
-                        Target::Twitch => Some(Source::Twitch),
-                    },
+                    source: target.source(),
                     name,
                     content,
                 },
@@ -192,17 +553,17 @@ async fn custom_commands_add(
 /// lowercase letter, only consist of lowercase letters, numbers and underscores and must not start
 /// with the `!`.
 #[poise::command(slash_command, category = "Admin", rename = "remove")]
-async fn custom_commands_remove(ctx: Context<'_>, target: Target, name: String) -> Result<()> {
+async fn custom_commands_remove(
+    ctx: Context<'_>,
+    target: Target,
+    #[autocomplete = "complete_custom_commands"] name: String,
+) -> Result<()> {
     handle_message(
         ctx,
         SerenityMessage {
             content: Request::Admin(request::Admin::CustomCommands(
                 request::CustomCommands::Remove {
-                    source: match target {
-                        Target::All => None,
-                        Target::Discord => Some(Source::Discord),
-                        Target::Twitch => Some(Source::Twitch),
-                    },
+                    source: target.source(),
                     name,
                 },
             )),
@@ -229,6 +590,57 @@ async fn custom_commands_list(ctx: Context<'_>) -> Result<()> {
     .await
 }
 
+/// Show the content of a custom command, including who created it and when.
+#[poise::command(slash_command, category = "Admin", rename = "show")]
+async fn custom_commands_show(
+    ctx: Context<'_>,
+    #[autocomplete = "complete_custom_commands"] name: String,
+) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::CustomCommands(
+                request::CustomCommands::Show(name),
+            )),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Show the recent changes to a custom command, including who made them and when.
+#[poise::command(slash_command, category = "Admin", rename = "history")]
+async fn custom_commands_history(ctx: Context<'_>, name: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::CustomCommands(
+                request::CustomCommands::History(name),
+            )),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Undo the last change to a custom command, restoring its previous content.
+#[poise::command(slash_command, category = "Admin", rename = "revert")]
+async fn custom_commands_revert(ctx: Context<'_>, name: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::CustomCommands(
+                request::CustomCommands::Revert(name),
+            )),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
 #[derive(poise::ChoiceParameter)]
 enum Time {
     Current,
@@ -246,15 +658,24 @@ impl Display for Time {
 
 /// Get statistics about command usage.
 ///
-/// Either for the **current month** or the overall counters for **all time**.
+/// Either for the **current month** or the overall counters for **all time**. If a tag is given,
+/// the overall counters of all streams with that tag are shown, or if a month is given, the
+/// counters of that month.
 #[poise::command(slash_command, category = "Admin")]
-async fn stats(ctx: Context<'_>, time: Time) -> Result<()> {
+async fn stats(
+    ctx: Context<'_>,
+    time: Time,
+    tag: Option<String>,
+    #[autocomplete = "complete_months"] month: Option<String>,
+) -> Result<()> {
     handle_message(
         ctx,
         SerenityMessage {
-            content: Request::Admin(request::Admin::Statistics(match time {
-                Time::Current => StatisticsDate::Current,
-                Time::Total => StatisticsDate::Total,
+            content: Request::Admin(request::Admin::Statistics(match (time, tag, month) {
+                (_, Some(tag), _) => StatisticsDate::Tag(tag),
+                (_, None, Some(month)) => textparse::month(&month)?,
+                (Time::Current, None, None) => StatisticsDate::Current,
+                (Time::Total, None, None) => StatisticsDate::Total,
             })),
             author: ctx.author().id,
             mention: None,
@@ -263,17 +684,13 @@ async fn stats(ctx: Context<'_>, time: Time) -> Result<()> {
     .await
 }
 
-// --------------------------------------------
-// USERS
-// --------------------------------------------
-
-/// Gives a short info about this bot.
-#[poise::command(slash_command, aliases("bot"), category = "User")]
-async fn help(ctx: Context<'_>) -> Result<()> {
+/// Show how long messages spend in each stage of the handling.
+#[poise::command(slash_command, category = "Admin")]
+async fn timing(ctx: Context<'_>) -> Result<()> {
     handle_message(
         ctx,
         SerenityMessage {
-            content: Request::User(request::User::Help),
+            content: Request::Admin(request::Admin::Timing),
             author: ctx.author().id,
             mention: None,
         },
@@ -281,25 +698,13 @@ async fn help(ctx: Context<'_>) -> Result<()> {
     .await
 }
 
-/// List all available commands of the bot.
-#[poise::command(slash_command, category = "User")]
-async fn commands(ctx: Context<'_>, command: Option<String>) -> Result<()> {
-    poise::builtins::help(
-        ctx.into(),
-        command.as_deref(),
-        poise::builtins::HelpConfiguration::default(),
-    )
-    .await
-    .map_err(Into::into)
-}
-
-/// Gives you a list of links to sites where the streamer is present.
-#[poise::command(slash_command, category = "User")]
-async fn links(ctx: Context<'_>) -> Result<()> {
+/// Remove the next song from the song request queue.
+#[poise::command(slash_command, category = "Admin")]
+async fn skip(ctx: Context<'_>) -> Result<()> {
     handle_message(
         ctx,
         SerenityMessage {
-            content: Request::User(request::User::Links),
+            content: Request::Admin(request::Admin::SkipSong),
             author: ctx.author().id,
             mention: None,
         },
@@ -307,13 +712,13 @@ async fn links(ctx: Context<'_>) -> Result<()> {
     .await
 }
 
-/// Refuse anything with the power of Gandalf.
-#[poise::command(slash_command, category = "User")]
-async fn ban(ctx: Context<'_>, target: String) -> Result<()> {
+/// Mark the streamer as away, answering messages for them with the message until they're back.
+#[poise::command(slash_command, category = "Admin")]
+async fn away(ctx: Context<'_>, message: String) -> Result<()> {
     handle_message(
         ctx,
         SerenityMessage {
-            content: Request::User(request::User::Ban(target)),
+            content: Request::Admin(request::Admin::Away(message)),
             author: ctx.author().id,
             mention: None,
         },
@@ -321,13 +726,13 @@ async fn ban(ctx: Context<'_>, target: String) -> Result<()> {
     .await
 }
 
-/// Get the link for any existing crate.
-#[poise::command(slash_command, category = "User")]
-async fn crates(ctx: Context<'_>, name: String) -> Result<()> {
+/// Mark the streamer as back, ending the automatic replies.
+#[poise::command(slash_command, category = "Admin")]
+async fn back(ctx: Context<'_>) -> Result<()> {
     handle_message(
         ctx,
         SerenityMessage {
-            content: Request::User(request::User::Ban(name)),
+            content: Request::Admin(request::Admin::Back),
             author: ctx.author().id,
             mention: None,
         },
@@ -335,13 +740,1415 @@ async fn crates(ctx: Context<'_>, name: String) -> Result<()> {
     .await
 }
 
-/// Get details about the current day.
+/// Turn the automatic shoutout for raiding channels on or off, or show whether it's on.
+#[poise::command(slash_command, category = "Admin")]
+async fn shoutout(ctx: Context<'_>, enabled: Option<bool>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::Shoutout(enabled)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Configure trigger words, that fire a response anywhere in a chat message.
+#[allow(clippy::unused_async)]
+#[poise::command(
+    slash_command,
+    category = "Admin",
+    subcommands(
+        "trigger_add",
+        "trigger_remove",
+        "trigger_cooldown",
+        "trigger_enable",
+        "trigger_list"
+    )
+)]
+async fn trigger(_: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+#[derive(poise::ChoiceParameter)]
+enum Service {
+    Discord,
+    Twitch,
+}
+
+impl From<Service> for Source {
+    fn from(value: Service) -> Self {
+        match value {
+            Service::Discord => Self::Discord,
+            Service::Twitch => Self::Twitch,
+        }
+    }
+}
+
+/// Add a new trigger word, or change the response of an existing one.
+#[poise::command(slash_command, category = "Admin", rename = "add")]
+async fn trigger_add(ctx: Context<'_>, word: String, response: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::Triggers(request::Triggers::Add {
+                word,
+                content: response,
+            })),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Remove a trigger word.
+#[poise::command(slash_command, category = "Admin", rename = "remove")]
+async fn trigger_remove(ctx: Context<'_>, word: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::Triggers(request::Triggers::Remove(word))),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Change the time in seconds until a trigger word fires again.
+#[poise::command(slash_command, category = "Admin", rename = "cooldown")]
+async fn trigger_cooldown(ctx: Context<'_>, word: String, seconds: u64) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::Triggers(request::Triggers::Cooldown {
+                word,
+                cooldown: seconds,
+            })),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Turn a trigger word on or off for a single service.
+#[poise::command(slash_command, category = "Admin", rename = "enable")]
+async fn trigger_enable(
+    ctx: Context<'_>,
+    service: Service,
+    word: String,
+    enabled: bool,
+) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::Triggers(request::Triggers::Enable {
+                word,
+                source: service.into(),
+                enabled,
+            })),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// List all trigger words.
+#[poise::command(slash_command, category = "Admin", rename = "list")]
+async fn trigger_list(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::Triggers(request::Triggers::List)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Configure answers to frequently asked questions, given whenever a message looks like one.
+#[allow(clippy::unused_async)]
+#[poise::command(
+    slash_command,
+    category = "Admin",
+    subcommands("faq_add", "faq_regex", "faq_remove", "faq_list")
+)]
+async fn faq(_: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Add an answer to a question, recognized by comma separated keywords.
+#[poise::command(slash_command, category = "Admin", rename = "add")]
+async fn faq_add(ctx: Context<'_>, name: String, keywords: String, answer: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::Faqs(request::Faqs::Add {
+                name,
+                pattern: keywords,
+                regex: false,
+                answer,
+            })),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Add an answer to a question, recognized by a regular expression.
+#[poise::command(slash_command, category = "Admin", rename = "regex")]
+async fn faq_regex(ctx: Context<'_>, name: String, regex: String, answer: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::Faqs(request::Faqs::Add {
+                name,
+                pattern: regex,
+                regex: true,
+                answer,
+            })),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Remove an answer to a question.
+#[poise::command(slash_command, category = "Admin", rename = "remove")]
+async fn faq_remove(ctx: Context<'_>, name: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::Faqs(request::Faqs::Remove(name))),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// List all answers to frequently asked questions.
+#[poise::command(slash_command, category = "Admin", rename = "list")]
+async fn faq_list(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::Faqs(request::Faqs::List)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Watch crates on crates.io, and announce their new versions.
+#[allow(clippy::unused_async)]
+#[poise::command(
+    slash_command,
+    category = "Admin",
+    subcommands("watchcrate_add", "watchcrate_remove", "watchcrate_list")
+)]
+async fn watchcrate(_: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Start watching a crate for new versions.
+#[poise::command(slash_command, category = "Admin", rename = "add")]
+async fn watchcrate_add(ctx: Context<'_>, name: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::WatchCrates(request::WatchCrates::Add(name))),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Stop watching a crate for new versions.
+#[poise::command(slash_command, category = "Admin", rename = "remove")]
+async fn watchcrate_remove(ctx: Context<'_>, name: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::WatchCrates(request::WatchCrates::Remove(
+                name,
+            ))),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// List all watched crates, with their latest known version.
+#[poise::command(slash_command, category = "Admin", rename = "list")]
+async fn watchcrate_list(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::WatchCrates(request::WatchCrates::List)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Restrict user commands to some channels of this server.
+#[allow(clippy::unused_async)]
+#[poise::command(
+    slash_command,
+    category = "Admin",
+    guild_only,
+    subcommands("channels_allow", "channels_deny", "channels_reset", "channels_list")
+)]
+async fn channels(_: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Answer user commands in a channel. Once any channel is allowed, all others are denied.
+#[poise::command(slash_command, category = "Admin", rename = "allow")]
+async fn channels_allow(ctx: Context<'_>, channel: ChannelId) -> Result<()> {
+    handle_channel_rule(ctx, channel, Some(ChannelRule::Allow)).await
+}
+
+/// Ignore user commands in a channel.
+#[poise::command(slash_command, category = "Admin", rename = "deny")]
+async fn channels_deny(ctx: Context<'_>, channel: ChannelId) -> Result<()> {
+    handle_channel_rule(ctx, channel, Some(ChannelRule::Deny)).await
+}
+
+/// Remove the rule of a channel.
+#[poise::command(slash_command, category = "Admin", rename = "reset")]
+async fn channels_reset(ctx: Context<'_>, channel: ChannelId) -> Result<()> {
+    handle_channel_rule(ctx, channel, None).await
+}
+
+/// List all channels of this server that have a rule.
+#[poise::command(slash_command, category = "Admin", rename = "list")]
+async fn channels_list(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::Channels(request::Channels::List)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+async fn handle_channel_rule(
+    ctx: Context<'_>,
+    channel: ChannelId,
+    rule: Option<ChannelRule>,
+) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::Channels(request::Channels::Set {
+                channel: channel.into(),
+                rule,
+            })),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Stop replying to user commands in a channel for some minutes, or reply again without minutes.
+#[poise::command(slash_command, category = "Admin", guild_only)]
+async fn silence(ctx: Context<'_>, minutes: Option<u64>, channel: Option<ChannelId>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::Silence {
+                channel: channel.map(Into::into),
+                duration: minutes.map(Duration::from_mins),
+            }),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Keep the list of commands pinned in a channel, or stop updating it.
+#[allow(clippy::unused_async)]
+#[poise::command(
+    slash_command,
+    category = "Admin",
+    guild_only,
+    subcommands("commandlist_pin", "commandlist_off")
+)]
+async fn commandlist(_: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Keep the list of commands pinned in a channel, the current one if none is given.
+#[poise::command(slash_command, category = "Admin", rename = "pin")]
+async fn commandlist_pin(ctx: Context<'_>, channel: Option<ChannelId>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::CommandList(request::CommandList::Pin(
+                channel.map(Into::into),
+            ))),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Stop updating the pinned list of commands.
+#[poise::command(slash_command, category = "Admin", rename = "off")]
+async fn commandlist_off(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::CommandList(request::CommandList::Off)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+#[derive(poise::ChoiceParameter)]
+enum Platform {
+    Discord,
+    Twitch,
+    Matrix,
+    YouTube,
+}
+
+impl From<Platform> for Source {
+    fn from(value: Platform) -> Self {
+        match value {
+            Platform::Discord => Self::Discord,
+            Platform::Twitch => Self::Twitch,
+            Platform::Matrix => Self::Matrix,
+            Platform::YouTube => Self::YouTube,
+        }
+    }
+}
+
+/// Show the links command as it looks on another service.
+#[poise::command(slash_command, category = "Admin")]
+async fn linkpreview(ctx: Context<'_>, service: Platform) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::LinkPreview(service.into())),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Check that all links still work.
+#[poise::command(slash_command, category = "Admin")]
+async fn checklinks(ctx: Context<'_>) -> Result<()> {
+    ctx.defer().await?;
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::CheckLinks),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Export the chapters of streams for their VODs.
+///
+/// Either of the latest stream, or of all streams that started on a day like `2025-03-01` (UTC).
+#[poise::command(slash_command, category = "Admin")]
+async fn chapters(ctx: Context<'_>, day: Option<String>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::Chapters(
+                day.as_deref().map(textparse::day).transpose()?,
+            )),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Review the suggestions that users sent in.
+#[allow(clippy::unused_async)]
+#[poise::command(
+    slash_command,
+    category = "Admin",
+    subcommands("suggestions_list", "suggestions_accept", "suggestions_reject")
+)]
+async fn suggestions(_: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// List all suggestions that wait for a review.
+#[poise::command(slash_command, category = "Admin", rename = "list")]
+async fn suggestions_list(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::Suggestions(request::Suggestions::List)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Accept a suggestion, and let its author know.
+#[poise::command(slash_command, category = "Admin", rename = "accept")]
+async fn suggestions_accept(ctx: Context<'_>, id: i64, reason: Option<String>) -> Result<()> {
+    review_suggestion(ctx, id, SuggestionStatus::Accepted, reason).await
+}
+
+/// Reject a suggestion, and let its author know.
+#[poise::command(slash_command, category = "Admin", rename = "reject")]
+async fn suggestions_reject(ctx: Context<'_>, id: i64, reason: Option<String>) -> Result<()> {
+    review_suggestion(ctx, id, SuggestionStatus::Rejected, reason).await
+}
+
+async fn review_suggestion(
+    ctx: Context<'_>,
+    id: i64,
+    status: SuggestionStatus,
+    reason: Option<String>,
+) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::Suggestions(request::Suggestions::Review {
+                id,
+                status,
+                reason,
+            })),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Create an issue on the configured GitHub repository.
+#[poise::command(slash_command, category = "Admin")]
+async fn issue(ctx: Context<'_>, title: String, body: Option<String>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::Issue(request::Issue::New { title, body })),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Turn the message into an issue on the configured GitHub repository.
+#[poise::command(
+    context_menu_command = "Create GitHub issue",
+    category = "Admin",
+    guild_only
+)]
+async fn issue_from_message(ctx: Context<'_>, message: serenity::Message) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::Issue(request::Issue::Message {
+                author: message.author.name.clone(),
+                content: message.content.clone(),
+                link: message.link(),
+            })),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Pin the message, and keep it as highlight.
+#[poise::command(
+    context_menu_command = "Pin as highlight",
+    category = "Admin",
+    guild_only
+)]
+async fn pin(ctx: Context<'_>, message: serenity::Message) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::Pin(Highlight {
+                guild: ctx.guild_id().map(Into::into),
+                channel: message.channel_id.into(),
+                message: message.id.into(),
+                author: message.author.name.clone(),
+                content: message.content.clone(),
+            })),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Configure the points that custom commands cost.
+#[allow(clippy::unused_async)]
+#[poise::command(
+    slash_command,
+    category = "Admin",
+    subcommands("cost_set", "cost_list")
+)]
+async fn cost(_: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Let a custom command cost points, or make it free again with a cost of zero.
+#[poise::command(slash_command, category = "Admin", rename = "set")]
+async fn cost_set(ctx: Context<'_>, name: String, points: u64) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::CommandCosts(request::CommandCosts::Set {
+                name,
+                cost: points,
+            })),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// List all custom commands that cost points.
+#[poise::command(slash_command, category = "Admin", rename = "list")]
+async fn cost_list(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::CommandCosts(request::CommandCosts::List)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Configure whether commands depend on the stream being live.
+#[allow(clippy::unused_async)]
+#[poise::command(
+    slash_command,
+    category = "Admin",
+    subcommands("availability_set", "availability_list")
+)]
+async fn availability(_: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+#[derive(poise::ChoiceParameter)]
+enum StreamState {
+    /// Regardless of the stream.
+    Always,
+    /// Only while the stream is live.
+    Live,
+    /// Only while the stream is offline.
+    Offline,
+}
+
+impl From<StreamState> for Availability {
+    fn from(value: StreamState) -> Self {
+        match value {
+            StreamState::Always => Self::Always,
+            StreamState::Live => Self::Live,
+            StreamState::Offline => Self::Offline,
+        }
+    }
+}
+
+/// Only answer a command while the stream is live, or while it's offline.
+#[poise::command(slash_command, category = "Admin", rename = "set")]
+async fn availability_set(ctx: Context<'_>, name: String, when: StreamState) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::CommandAvailability(
+                request::CommandAvailability::Set {
+                    name,
+                    availability: when.into(),
+                },
+            )),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// List all commands that depend on the stream being live.
+#[poise::command(slash_command, category = "Admin", rename = "list")]
+async fn availability_list(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::CommandAvailability(
+                request::CommandAvailability::List,
+            )),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Configure the daily command usage that admins get alerted about.
+#[allow(clippy::unused_async)]
+#[poise::command(
+    slash_command,
+    category = "Admin",
+    subcommands("alert_set", "alert_list")
+)]
+async fn alert(_: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Get alerted once a command is used too often in a day, or remove the alert with a zero limit.
+#[poise::command(slash_command, category = "Admin", rename = "set")]
+async fn alert_set(ctx: Context<'_>, name: String, limit: u64) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::UsageAlerts(request::UsageAlerts::Set {
+                name,
+                limit,
+            })),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// List all commands that have a usage alert.
+#[poise::command(slash_command, category = "Admin", rename = "list")]
+async fn alert_list(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::UsageAlerts(request::UsageAlerts::List)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Tag the current stream with a topic, to compare statistics across content types.
+#[poise::command(slash_command, category = "Admin")]
+async fn stream_tag(ctx: Context<'_>, tag: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::StreamTag(tag)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+#[allow(clippy::unused_async)]
+#[poise::command(
+    slash_command,
+    category = "Admin",
+    subcommands("usernote_add", "usernote_list", "usernote_remove")
+)]
+async fn usernote(_: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Attach a private note to a user.
+///
+/// The user is either a Discord mention or written as `<service>:<name>`, like `twitch:somebody`.
+#[poise::command(slash_command, category = "Admin", rename = "add")]
+async fn usernote_add(ctx: Context<'_>, user: String, text: String) -> Result<()> {
+    let user = match user.parse() {
+        Ok(user) => user,
+        Err(e) => return reply_private(ctx, format!("invalid user: {e}")).await,
+    };
+
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::UserNotes(request::UserNotes::Add {
+                user,
+                content: text,
+            })),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Get all notes about a user as direct message.
+///
+/// The user is either a Discord mention or written as `<service>:<name>`, like `twitch:somebody`.
+#[poise::command(slash_command, category = "Admin", rename = "list")]
+async fn usernote_list(ctx: Context<'_>, user: String) -> Result<()> {
+    let user = match user.parse() {
+        Ok(user) => user,
+        Err(e) => return reply_private(ctx, format!("invalid user: {e}")).await,
+    };
+
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::UserNotes(request::UserNotes::List(user))),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Delete a note by its ID.
+#[poise::command(slash_command, category = "Admin", rename = "remove")]
+async fn usernote_remove(ctx: Context<'_>, id: i64) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::Admin(request::Admin::UserNotes(request::UserNotes::Remove(id))),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+// --------------------------------------------
+// USERS
+// --------------------------------------------
+
+/// Gives a short info about this bot, or the details of a single command.
+#[poise::command(slash_command, aliases("bot"), category = "User")]
+async fn help(
+    ctx: Context<'_>,
+    #[description = "Name of the command to show the details of"] command: Option<String>,
+) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(command.map_or(request::User::Help, request::User::CommandHelp)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// List all available commands of the bot.
+#[poise::command(slash_command, category = "User")]
+async fn commands(ctx: Context<'_>, command: Option<String>) -> Result<()> {
+    poise::builtins::help(
+        ctx.into(),
+        command.as_deref(),
+        poise::builtins::HelpConfiguration::default(),
+    )
+    .await
+    .map_err(Into::into)
+}
+
+/// Gives you a list of links to sites where the streamer is present.
+#[poise::command(slash_command, category = "User")]
+async fn links(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Links),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Refuse anything with the power of Gandalf.
+#[poise::command(slash_command, category = "User")]
+async fn ban(ctx: Context<'_>, target: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Ban(target)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+async fn complete_crates(ctx: Context<'_>, partial: &str) -> Vec<String> {
+    ctx.data().completions.crates(partial).await
+}
+
+#[allow(clippy::unused_async)]
+async fn complete_custom_commands(ctx: Context<'_>, partial: &str) -> Vec<String> {
+    ctx.data().completions.custom_commands(partial)
+}
+
+#[allow(clippy::unused_async)]
+async fn complete_months(ctx: Context<'_>, partial: &str) -> Vec<String> {
+    ctx.data().completions.months(partial)
+}
+
+/// Get the link for any existing crate, or details about one of its versions.
+#[poise::command(slash_command, category = "User")]
+async fn crates(
+    ctx: Context<'_>,
+    #[autocomplete = "complete_crates"] name: String,
+    #[description = "Version like 1.38 or 1.38.0"] version: Option<String>,
+) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(match version {
+                Some(version) => request::User::CrateVersion { name, version },
+                None => request::User::Crate(name),
+            }),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Show the current stable, beta and nightly versions of Rust.
+#[poise::command(slash_command, category = "User")]
+async fn rustversion(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::RustVersions),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Run a Rust snippet on the playground and show its output.
+#[poise::command(slash_command, category = "User")]
+async fn eval(ctx: Context<'_>, code: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Eval(code)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Run the Rust code of a message on the playground and show its output.
+#[poise::command(context_menu_command = "Run as Rust", category = "User")]
+async fn eval_message(ctx: Context<'_>, message: serenity::Message) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Eval(message.content)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Compare two crates side by side.
+#[poise::command(slash_command, category = "User")]
+async fn compare_crates(
+    ctx: Context<'_>,
+    #[autocomplete = "complete_crates"] first: String,
+    #[autocomplete = "complete_crates"] second: String,
+) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::CrateCompare(first, second)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Get details about the current day.
+#[poise::command(slash_command, category = "User")]
+async fn today(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Today),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Get a random piece of advice.
+#[poise::command(slash_command, category = "User")]
+async fn advice(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Fun(Kind::Advice)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Learn a random fact.
+#[poise::command(slash_command, category = "User")]
+async fn fact(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Fun(Kind::Fact)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Hear a random joke.
+#[poise::command(slash_command, category = "User")]
+async fn joke(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Fun(Kind::Joke)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Show whether the bot is up and the stream is live.
+#[poise::command(slash_command, category = "User")]
+async fn status(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Status),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Show the bot's version, uptime and connected services.
+#[poise::command(slash_command, category = "User")]
+async fn botstat(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::BotStat),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Request a song by its YouTube or Spotify link.
+#[poise::command(slash_command, category = "User")]
+async fn sr(ctx: Context<'_>, link: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::SongRequest(link)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Show the songs in the song request queue.
+#[poise::command(slash_command, rename = "queue", category = "User")]
+async fn song_queue(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::SongQueue),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Show how many points you collected by chatting.
+#[poise::command(slash_command, category = "User")]
+async fn points(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Points),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Bet your points, with the chance to double them.
+#[poise::command(slash_command, category = "User")]
+async fn gamble(ctx: Context<'_>, amount: u64) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Gamble(amount)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Challenge somebody to a duel, the winner takes the points.
+#[poise::command(slash_command, category = "User")]
+async fn duel(ctx: Context<'_>, opponent: serenity::User, amount: u64) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Duel {
+                target: format!("<@{}>", opponent.id),
+                amount,
+            }),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Accept the duel that somebody challenged you to.
+#[poise::command(slash_command, category = "User")]
+async fn accept(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Accept),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Start a poll on Twitch, mirrored as reaction poll on Discord. Separate options by commas.
+#[poise::command(slash_command, category = "User")]
+async fn poll(ctx: Context<'_>, question: String, options: String, seconds: u64) -> Result<()> {
+    start_poll(ctx, PollKind::Poll, question, &options, seconds).await
+}
+
+/// Start a prediction on Twitch, mirrored as reaction poll on Discord. Separate outcomes by commas.
+#[poise::command(slash_command, category = "User")]
+async fn prediction(
+    ctx: Context<'_>,
+    question: String,
+    outcomes: String,
+    seconds: u64,
+) -> Result<()> {
+    start_poll(ctx, PollKind::Prediction, question, &outcomes, seconds).await
+}
+
+async fn start_poll(
+    ctx: Context<'_>,
+    kind: PollKind,
+    question: String,
+    options: &str,
+    seconds: u64,
+) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Poll(Poll {
+                kind,
+                question,
+                options: options
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|option| !option.is_empty())
+                    .map(ToOwned::to_owned)
+                    .collect(),
+                duration: Duration::from_secs(seconds),
+            })),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Get a direct message whenever the stream goes live. Toggles the current choice if left out.
+#[poise::command(slash_command, category = "User")]
+async fn notifyme(ctx: Context<'_>, enabled: Option<bool>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::NotifyMe(enabled)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Show the latest messages that were pinned as highlights.
+#[poise::command(slash_command, category = "User")]
+async fn highlights(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Highlights),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Register your birthday, to get birthday wishes on the day.
+#[allow(clippy::unused_async)]
+#[poise::command(
+    slash_command,
+    category = "User",
+    subcommands(
+        "birthday_show",
+        "birthday_set",
+        "birthday_remove",
+        "birthday_announce"
+    )
+)]
+async fn birthday(_: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Show the birthday that you registered.
+#[poise::command(slash_command, category = "User", rename = "show")]
+async fn birthday_show(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Birthday(request::Birthday::Show)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Register your birthday as month and day, like `04-02`.
+#[poise::command(slash_command, category = "User", rename = "set")]
+async fn birthday_set(ctx: Context<'_>, date: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Birthday(textparse::birthday(&date)?)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Remove your birthday again.
+#[poise::command(slash_command, category = "User", rename = "remove")]
+async fn birthday_remove(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Birthday(request::Birthday::Remove)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Choose whether your birthday is announced on the day.
+#[poise::command(slash_command, category = "User", rename = "announce")]
+async fn birthday_announce(ctx: Context<'_>, enabled: bool) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Birthday(request::Birthday::Announce(
+                enabled,
+            ))),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Show what was streamed yesterday.
+#[poise::command(slash_command, category = "User")]
+async fn yesterday(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Yesterday),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Show when a category was streamed the last time.
+#[poise::command(slash_command, category = "User")]
+async fn lastcategory(ctx: Context<'_>, category: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::LastCategory(category)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Show what today's stream is about.
+#[poise::command(slash_command, category = "User")]
+async fn topic(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Topic),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Send in a suggestion for the admins to review.
+#[poise::command(slash_command, category = "User")]
+async fn suggest(ctx: Context<'_>, text: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Suggest(text)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Let the chat know that you're lurking, with an optional message.
+#[poise::command(slash_command, category = "User")]
+async fn lurk(ctx: Context<'_>, message: Option<String>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Lurk(message)),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Let the chat know that you're back from lurking.
 #[poise::command(slash_command, category = "User")]
-async fn today(ctx: Context<'_>) -> Result<()> {
+async fn unlurk(ctx: Context<'_>) -> Result<()> {
     handle_message(
         ctx,
         SerenityMessage {
-            content: Request::User(request::User::Today),
+            content: Request::User(request::User::Unlurk),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Look up or share pronouns.
+#[allow(clippy::unused_async)]
+#[poise::command(
+    slash_command,
+    category = "User",
+    subcommands("pronouns_show", "pronouns_set", "pronouns_unset")
+)]
+async fn pronouns(_: Context<'_>) -> Result<()> {
+    Ok(())
+}
+
+/// Show the pronouns that somebody shared.
+#[poise::command(slash_command, category = "User", rename = "show")]
+async fn pronouns_show(ctx: Context<'_>, user: serenity::User) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Pronouns(request::Pronouns::Show(format!(
+                "<@{}>",
+                user.id
+            )))),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Share your own pronouns, like `she/her`.
+#[poise::command(slash_command, category = "User", rename = "set")]
+async fn pronouns_set(ctx: Context<'_>, pronouns: String) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Pronouns(request::Pronouns::Set(pronouns))),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// Remove your own pronouns again.
+#[poise::command(slash_command, category = "User", rename = "unset")]
+async fn pronouns_unset(ctx: Context<'_>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(request::User::Pronouns(request::Pronouns::Unset)),
             author: ctx.author().id,
             mention: None,
         },
@@ -377,52 +2184,252 @@ async fn ctof(ctx: Context<'_>, celsius: f64) -> Result<()> {
     .await
 }
 
-/// Initiate and run the Discord bot connection in a background task.
-///
-/// It pushes messages into the given queue for processing, each message accompanied by a oneshot
-/// channel, that allows to listen for the generated reply (if any). The shutdown handler is used
-/// to gracefully shut down the connection before fully quitting the application.
-pub async fn start(
-    config: &DiscordSettings,
-    settings: Arc<CommandSettings>,
-    queue: Queue,
-    shutdown: Shutdown,
+/// Run a command that is provided by a plugin.
+#[poise::command(slash_command, category = "User")]
+async fn plugin(ctx: Context<'_>, name: String, args: Option<String>) -> Result<()> {
+    handle_message(
+        ctx,
+        SerenityMessage {
+            content: Request::User(match args {
+                Some(args) => request::User::Plugin { name, args },
+                None => request::User::Custom(name),
+            }),
+            author: ctx.author().id,
+            mention: None,
+        },
+    )
+    .await
+}
+
+/// All slash commands, that are registered with Discord.
+fn all_commands() -> Vec<poise::Command<State, anyhow::Error>> {
+    vec![
+        // owners
+        ohelp(),
+        admins(),
+        plugins(),
+        debug(),
+        resync_commands(),
+        broadcast(),
+        cleanup(),
+        #[cfg(feature = "chaos")]
+        chaos(),
+        // admins
+        ahelp(),
+        custom_commands(),
+        stats(),
+        timing(),
+        stream_tag(),
+        usernote(),
+        skip(),
+        shoutout(),
+        away(),
+        back(),
+        trigger(),
+        faq(),
+        watchcrate(),
+        channels(),
+        silence(),
+        commandlist(),
+        linkpreview(),
+        checklinks(),
+        chapters(),
+        suggestions(),
+        issue(),
+        issue_from_message(),
+        pin(),
+        cost(),
+        availability(),
+        alert(),
+        // users
+        help(),
+        commands(),
+        links(),
+        ban(),
+        crates(),
+        compare_crates(),
+        rustversion(),
+        eval(),
+        eval_message(),
+        today(),
+        advice(),
+        fact(),
+        joke(),
+        status(),
+        botstat(),
+        sr(),
+        song_queue(),
+        points(),
+        gamble(),
+        duel(),
+        accept(),
+        poll(),
+        prediction(),
+        notifyme(),
+        birthday(),
+        lurk(),
+        unlurk(),
+        highlights(),
+        yesterday(),
+        lastcategory(),
+        suggest(),
+        topic(),
+        pronouns(),
+        ftoc(),
+        ctof(),
+        plugin(),
+    ]
+}
+
+/// Register the slash commands, together with the custom commands, in the given servers, where
+/// changes show up instantly, or globally if there are none.
+async fn register_commands(
+    http: &serenity::Http,
+    guilds: &[GuildId],
+    builtin: &[poise::Command<State, anyhow::Error>],
+    custom: &[CustomCommand],
 ) -> Result<()> {
-    let token = config.token.clone();
-    let framework = poise::Framework::builder()
-        .options(poise::FrameworkOptions {
-            commands: vec![
-                // owners
-                ohelp(),
-                admins(),
-                // admins
-                ahelp(),
-                custom_commands(),
-                stats(),
-                // users
-                help(),
-                commands(),
-                links(),
-                ban(),
-                crates(),
-                today(),
-                ftoc(),
-                ctof(),
-            ],
-            ..Default::default()
-        })
-        .setup(|ctx, _ready, framework| {
-            Box::pin(async move {
-                poise::builtins::register_globally(ctx, &framework.options().commands).await?;
-                anyhow::Ok(State { settings, queue })
+    let names = builtin
+        .iter()
+        .map(|command| command.name.as_str())
+        .collect();
+    let mut commands = poise::builtins::create_application_commands(builtin);
+    commands.extend(custom::create(&commands, &names, custom));
+
+    if guilds.is_empty() {
+        serenity::Command::set_global_commands(http, commands).await?;
+    } else {
+        for guild in guilds {
+            guild.set_commands(http, commands.clone()).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Connector for Discord, that receives slash commands and renders their replies.
+pub struct Discord {
+    token: String,
+    poll_channel: Option<ChannelId>,
+    prefix_commands: bool,
+    mentions: Mentions,
+    dm_routing: DmRouting,
+    guilds: Vec<GuildId>,
+    custom: Arc<Mutex<Vec<CustomCommand>>>,
+    presence: Arc<presence::Presence>,
+    client: OnceLock<Running>,
+}
+
+struct Running {
+    http: Arc<serenity::Http>,
+    shard_manager: Arc<serenity::ShardManager>,
+}
+
+impl Discord {
+    /// Create a new, not yet started, Discord connector.
+    #[must_use]
+    pub fn new(config: &DiscordSettings) -> Self {
+        Self {
+            token: config.token.clone(),
+            poll_channel: config.poll_channel.map(ChannelId::from),
+            prefix_commands: config.prefix_commands,
+            mentions: config.mentions,
+            dm_routing: config.dm_routing.clone(),
+            guilds: config.guilds.iter().copied().map(GuildId::from).collect(),
+            custom: Arc::default(),
+            presence: Arc::new(presence::Presence::new(config.presence.clone())),
+            client: OnceLock::new(),
+        }
+    }
+
+    /// Message for a post that doesn't answer anyone, like announcements. Mentions in it only
+    /// notify the kinds that the settings allow, the same as in replies.
+    fn post_message(&self, content: String) -> CreateMessage {
+        CreateMessage::new()
+            .content(content)
+            .allowed_mentions(allowed_mentions(self.mentions, true))
+    }
+
+    /// Initiate and run the Discord bot connection in a background task.
+    ///
+    /// It pushes messages into the context's queue for processing, each message accompanied by a
+    /// oneshot channel, that allows to listen for the generated reply (if any). Every gateway
+    /// event is reported as heartbeat to the health state.
+    async fn run(&self, ctx: ConnectorContext) -> Result<()> {
+        let ConnectorContext {
+            settings,
+            queue,
+            events,
+            completions,
+            health,
+            timings,
+            ..
+        } = ctx;
+
+        let (prefix_commands, mentions) = (self.prefix_commands, self.mentions);
+        let dm_routing = self.dm_routing.clone();
+        let guilds = self.guilds.clone();
+        let custom = Arc::clone(&self.custom);
+        let presence = Arc::clone(&self.presence);
+        presence.set_streamer(&settings.streamer);
+        let framework = poise::Framework::builder()
+            .options(poise::FrameworkOptions {
+                commands: all_commands(),
+                on_error: |error| {
+                    Box::pin(async move {
+                        // Custom commands are registered as slash commands, but answered outside
+                        // of the framework.
+                        if matches!(error, poise::FrameworkError::UnknownInteraction { .. }) {
+                            return;
+                        }
+                        if let Err(e) = poise::builtins::on_error(error).await {
+                            error!(error = ?e, "failed handling framework error");
+                        }
+                    })
+                },
+                event_handler: |ctx, event, _, state| {
+                    state.health.beat(Source::Discord);
+                    Box::pin(async move {
+                        report_chat(event, &state.events).await?;
+                        report_changes(event, &state.events).await;
+                        forward_message(ctx, event, state).await?;
+                        confirm::run(ctx, event, state).await?;
+                        custom::run(ctx, event, state).await
+                    })
+                },
+                ..Default::default()
             })
-        })
-        .build();
+            .setup(move |ctx, _ready, framework| {
+                Box::pin(async move {
+                    let list = custom
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .clone();
+                    register_commands(&ctx.http, &guilds, &framework.options().commands, &list)
+                        .await?;
+                    ctx.set_activity(presence.activity());
+                    anyhow::Ok(State {
+                        settings,
+                        queue,
+                        events,
+                        completions,
+                        health,
+                        timings,
+                        prefix_commands,
+                        mentions,
+                        dm_routing,
+                        custom,
+                    })
+                })
+            })
+            .build();
 
-    let mut client =
-        match serenity::ClientBuilder::new(token, serenity::GatewayIntents::non_privileged())
-            .framework(framework)
-            .await
+        let mut client = match serenity::ClientBuilder::new(
+            &self.token,
+            serenity::GatewayIntents::non_privileged() | serenity::GatewayIntents::MESSAGE_CONTENT,
+        )
+        .framework(framework)
+        .await
         {
             Ok(client) => client,
             Err(e) => {
@@ -431,28 +2438,485 @@ pub async fn start(
             }
         };
 
-    info!("discord connection ready, listening for events");
+        self.client
+            .set(Running {
+                http: Arc::clone(&client.http),
+                shard_manager: Arc::clone(&client.shard_manager),
+            })
+            .map_err(|_| anyhow!("discord connector already started"))?;
 
-    tokio::spawn(async move {
-        tokio::select! {
-            () = shutdown.handle() => {}
-            res = client.start() => {
-                if let Err(e) = res {
-                    error!(error = ?e, "failed running discord client");
-                }
+        info!("discord connection ready, listening for events");
+
+        tokio::spawn(async move {
+            if let Err(e) = client.start().await {
+                error!(error = ?e, "failed running discord client");
+            }
+        });
+
+        Ok(())
+    }
+
+    async fn post(&self, post: Post) -> Result<()> {
+        let running = self.client.get().context("discord connector not started")?;
+        let channel = post.channel.context("missing target channel")?;
+
+        #[cfg(feature = "chaos")]
+        crate::chaos::send()?;
+
+        ChannelId::from(channel)
+            .send_message(&running.http, self.post_message(post.content))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn pin(&self, post: Post, message: Option<NonZero<u64>>) -> Result<NonZero<u64>> {
+        let running = self.client.get().context("discord connector not started")?;
+        let channel = ChannelId::from(post.channel.context("missing target channel")?);
+
+        if let Some(message) = message {
+            let edit = EditMessage::new()
+                .content(&post.content)
+                .allowed_mentions(allowed_mentions(self.mentions, true));
+            match channel
+                .edit_message(&running.http, MessageId::from(message), edit)
+                .await
+            {
+                Ok(_) => return Ok(message),
+                Err(e) => warn!(error = ?e, "failed editing pinned message, posting a new one"),
             }
         }
 
-        client.shard_manager.shutdown_all().await;
-        info!("discord connection shutting down");
-    });
+        let message = channel
+            .send_message(&running.http, self.post_message(post.content))
+            .await?;
+        message.pin(&running.http).await?;
 
-    Ok(())
+        Ok(message.id.into())
+    }
+
+    async fn direct(&self, user: String, content: String) -> Result<()> {
+        let running = self.client.get().context("discord connector not started")?;
+        let user = UserId::from(user.parse::<NonZero<u64>>()?);
+
+        user.create_dm_channel(&running.http)
+            .await?
+            .send_message(
+                &running.http,
+                CreateMessage::new()
+                    .content(content)
+                    .allowed_mentions(CreateAllowedMentions::new()),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn resync(&self, custom: Vec<CustomCommand>) -> Result<()> {
+        self.custom
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone_from(&custom);
+
+        // Not connected yet, the commands are registered once the connection is ready.
+        let Some(running) = self.client.get() else {
+            return Ok(());
+        };
+        register_commands(&running.http, &self.guilds, &all_commands(), &custom).await?;
+
+        info!("slash commands registered again");
+        Ok(())
+    }
+
+    async fn set_presence(&self, stream: Option<StreamInfo>) -> Result<()> {
+        let activity = self.presence.update(stream);
+
+        // Not connected yet, the status is set once the connection is ready.
+        let Some(running) = self.client.get() else {
+            return Ok(());
+        };
+        for runner in running.shard_manager.runners.lock().await.values() {
+            runner.runner_tx.set_activity(activity.clone());
+        }
+
+        Ok(())
+    }
+
+    async fn create_poll(&self, poll: Poll) -> Result<()> {
+        let Some(channel) = self.poll_channel else {
+            return Ok(());
+        };
+        let running = self.client.get().context("discord connector not started")?;
+
+        poll::start(Arc::clone(&running.http), channel, poll).await
+    }
+}
+
+impl Connector for Discord {
+    fn source(&self) -> Source {
+        Source::Discord
+    }
+
+    fn start(&self, ctx: ConnectorContext) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.run(ctx))
+    }
+
+    fn send_reply(&self, post: Post) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.post(post))
+    }
+
+    fn pin_post(
+        &self,
+        post: Post,
+        message: Option<NonZero<u64>>,
+    ) -> BoxFuture<'_, Result<NonZero<u64>>> {
+        Box::pin(self.pin(post, message))
+    }
+
+    fn send_direct(&self, user: String, content: String) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.direct(user, content))
+    }
+
+    fn start_poll(&self, poll: Poll) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.create_poll(poll))
+    }
+
+    fn resync_commands(&self, custom: Vec<CustomCommand>) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.resync(custom))
+    }
+
+    fn update_presence(&self, stream: Option<StreamInfo>) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.set_presence(stream))
+    }
+
+    fn shutdown(&self) -> BoxFuture<'_, ()> {
+        Box::pin(async {
+            if let Some(running) = self.client.get() {
+                running.shard_manager.shutdown_all().await;
+                info!("discord connection shutting down");
+            }
+        })
+    }
 }
 
 struct State {
     settings: Arc<CommandSettings>,
     queue: Queue,
+    events: Events,
+    completions: Completions,
+    health: Health,
+    timings: Timings,
+    prefix_commands: bool,
+    mentions: Mentions,
+    dm_routing: DmRouting,
+    custom: Arc<Mutex<Vec<CustomCommand>>>,
+}
+
+impl State {
+    /// Whether the name belongs to a custom command, that is registered as slash command.
+    fn is_custom(&self, name: &str) -> bool {
+        self.custom
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .any(|command| command.name == name)
+    }
+}
+
+/// Report chat messages of users, so they earn points for chatting, and any code blocks in them,
+/// so they can be relayed.
+async fn report_chat(event: &serenity::FullEvent, events: &Events) -> Result<()> {
+    let serenity::FullEvent::Message { new_message } = event else {
+        return Ok(());
+    };
+    if new_message.author.bot {
+        return Ok(());
+    }
+
+    let author = AuthorId::Discord(new_message.author.id.into());
+    events
+        .send(Event::Chat {
+            user: author.user_ref(),
+            name: new_message.author.name.to_lowercase(),
+        })
+        .await
+        .ok();
+
+    for (language, code) in handler::code_blocks(&new_message.content) {
+        events
+            .send(Event::CodeShared {
+                channel: new_message.channel_id.into(),
+                user: new_message
+                    .author
+                    .global_name
+                    .clone()
+                    .unwrap_or_else(|| new_message.author.name.clone()),
+                language: language.map(ToOwned::to_owned),
+                code: code.to_owned(),
+            })
+            .await
+            .ok();
+    }
+
+    Ok(())
+}
+
+/// Report edited and deleted messages, so anything that was created from them follows along, like
+/// highlights.
+async fn report_changes(event: &serenity::FullEvent, events: &Events) {
+    let event = match event {
+        serenity::FullEvent::MessageUpdate { event, .. } => match &event.content {
+            Some(content) => Event::MessageEdited {
+                message: event.id.into(),
+                content: content.clone(),
+            },
+            // Only embeds were updated, like a link preview being added.
+            None => return,
+        },
+        serenity::FullEvent::MessageDelete {
+            deleted_message_id, ..
+        } => Event::MessagesDeleted(vec![(*deleted_message_id).into()]),
+        serenity::FullEvent::MessageDeleteBulk {
+            multiple_deleted_messages_ids,
+            ..
+        } => Event::MessagesDeleted(
+            multiple_deleted_messages_ids
+                .iter()
+                .map(|&id| id.into())
+                .collect(),
+        ),
+        _ => return,
+    };
+
+    events.send(event).await.ok();
+}
+
+/// Forward regular chat messages, so they can fire trigger words or, if enabled, run `!` prefixed
+/// commands. Reading the content needs the privileged message content intent.
+#[instrument(skip_all, name = "discord chat message", fields(source = %Source::Discord))]
+async fn forward_message(
+    ctx: &serenity::Context,
+    event: &serenity::FullEvent,
+    state: &State,
+) -> Result<()> {
+    let received = Instant::now();
+    let serenity::FullEvent::Message { new_message } = event else {
+        return Ok(());
+    };
+    if new_message.author.bot || new_message.content.is_empty() {
+        return Ok(());
+    }
+
+    let mention = new_message.mentions.first().map(|user| user.id);
+    let parsed = if state.prefix_commands {
+        textparse::parse(
+            &new_message.content,
+            Source::Discord,
+            mention.map(Into::into),
+        )
+    } else {
+        Ok(None)
+    };
+    let content = match parsed {
+        Ok(Some(content)) => content,
+        Ok(None) => Request::User(request::User::Trigger(new_message.content.clone())),
+        Err(_) => return Ok(()),
+    };
+
+    let message = Message {
+        span: Span::current(),
+        source: Source::Discord,
+        content,
+        author: message_author(ctx, new_message),
+        mention: mention.map(Into::into),
+        channel: guild_channel(new_message.guild_id, new_message.channel_id),
+    };
+
+    let command = message.content.metric_name().to_owned();
+    if let Some(resp) = connector::dispatch(&state.queue, message).await {
+        let reply = async {
+            let route = routed_channel(&state.dm_routing, new_message.guild_id, &command, &resp);
+            let rendered = match resp {
+                Response::User(resp) => render::user(resp, &state.settings.streamer)?,
+                Response::Admin(resp) => Some(render::admin(resp)),
+                Response::Owner(resp) => Some(render::owner(resp)),
+                Response::Denied(required) => Some(render::denied(required)),
+                Response::Confirm(action) => Some(render::confirm(action)),
+            };
+
+            match (rendered, route) {
+                (Some(reply), Some(channel)) if reply.delivery == Delivery::Channel => {
+                    let note =
+                        deliver_routed(&ctx.http, &state.timings, state.mentions, channel, reply)
+                            .await;
+                    let builder = CreateMessage::new()
+                        .content(note)
+                        .allowed_mentions(CreateAllowedMentions::new());
+                    retry::send_or_drop(&state.timings, || {
+                        new_message
+                            .channel_id
+                            .send_message(&ctx.http, builder.clone())
+                    })
+                    .await
+                }
+                (Some(reply), _) => Box::pin(deliver_message(ctx, state, new_message, reply)).await,
+                (None, _) => Ok(()),
+            }
+        }
+        .instrument(info_span!("reply"));
+
+        state.timings.reply(&command, received, reply).await?;
+    }
+
+    Ok(())
+}
+
+/// Server channel of a message, or nothing for direct messages.
+fn guild_channel(guild: Option<GuildId>, channel: ChannelId) -> Option<GuildChannel> {
+    guild.map(|guild| GuildChannel {
+        guild: guild.into(),
+        channel: channel.into(),
+    })
+}
+
+/// Author of a regular chat message.
+fn message_author(ctx: &serenity::Context, message: &serenity::Message) -> Author {
+    let member = message.member.as_deref();
+
+    event_author(
+        ctx,
+        message.guild_id,
+        &message.author,
+        member.map_or(&[], |member| &member.roles),
+        member.is_some_and(|member| member.premium_since.is_some()),
+    )
+}
+
+/// Author of a message that didn't come in through the framework, the same way as [`author`] does
+/// for slash commands.
+fn event_author(
+    ctx: &serenity::Context,
+    guild: Option<GuildId>,
+    user: &serenity::User,
+    roles: &[serenity::RoleId],
+    booster: bool,
+) -> Author {
+    let owner = guild
+        .and_then(|guild| {
+            guild
+                .to_guild_cached(&ctx.cache)
+                .map(|guild| guild.owner_id)
+        })
+        .is_some_and(|owner| owner == user.id);
+
+    let mut roles = roles.iter().map(ToString::to_string).collect::<Vec<_>>();
+    if owner {
+        roles.push("broadcaster".to_owned());
+    }
+
+    Author {
+        id: AuthorId::Discord(user.id.into()),
+        name: user.name.clone(),
+        roles,
+        subscriber: booster,
+    }
+}
+
+/// Mentions that notify anyone in a reply, which are none if the reply doesn't notify at all, and
+/// otherwise the ones the settings allow.
+fn allowed_mentions(mentions: Mentions, notify: bool) -> CreateAllowedMentions {
+    if !notify {
+        return CreateAllowedMentions::new();
+    }
+
+    CreateAllowedMentions::new()
+        .all_users(mentions.users)
+        .all_roles(mentions.roles)
+        .everyone(mentions.everyone)
+}
+
+/// Channel that the reply is posted in instead of the conversation, if it answers an admin command
+/// that was sent as direct message and the settings route the command's replies.
+fn routed_channel(
+    routing: &DmRouting,
+    guild: Option<GuildId>,
+    command: &str,
+    resp: &Response,
+) -> Option<ChannelId> {
+    if guild.is_some() || !matches!(resp, Response::Admin(_)) {
+        return None;
+    }
+
+    routing.channel(command).map(ChannelId::from)
+}
+
+/// Post a routed reply in its channel, and give back a note for the author about where it went.
+async fn deliver_routed(
+    http: &serenity::Http,
+    timings: &Timings,
+    mentions: Mentions,
+    channel: ChannelId,
+    reply: Reply,
+) -> String {
+    let builder = channel_message(reply, mentions);
+
+    match retry::send(timings, || channel.send_message(http, builder.clone())).await {
+        Ok(_) => format!("{} posted in <#{channel}>", emojis::OK_HAND),
+        Err(e) => {
+            error!(error = ?e, %channel, "failed posting routed reply");
+            format!("{} failed posting in <#{channel}>: {e}", emojis::COLLISION)
+        }
+    }
+}
+
+/// Message with all parts of a rendered reply, for posting it in a channel.
+fn channel_message(reply: Reply, mentions: Mentions) -> CreateMessage {
+    let mut builder = CreateMessage::new().content(reply.content);
+    if let Some(embed) = reply.embed {
+        builder = builder.embed(embed);
+    }
+    if let Some(attachment) = reply.attachment {
+        builder = builder.add_file(CreateAttachment::bytes(attachment.data, attachment.name));
+    }
+    builder = builder.allowed_mentions(allowed_mentions(mentions, reply.notify));
+    if reply.confirm {
+        builder = builder.components(vec![confirm::button()]);
+    }
+
+    builder
+}
+
+/// Send a rendered reply to a regular chat message. Plain messages can't be answered privately, so
+/// private replies go out as direct message instead.
+async fn deliver_message(
+    ctx: &serenity::Context,
+    state: &State,
+    message: &serenity::Message,
+    reply: Reply,
+) -> Result<()> {
+    match reply.delivery {
+        Delivery::Channel => {
+            let builder = channel_message(reply, state.mentions).reference_message(message);
+
+            retry::send_or_drop(&state.timings, || {
+                message.channel_id.send_message(&ctx.http, builder.clone())
+            })
+            .await?;
+        }
+        Delivery::Private | Delivery::Direct => {
+            for chunk in chunks(&reply.content) {
+                let builder = CreateMessage::new()
+                    .content(chunk)
+                    .allowed_mentions(CreateAllowedMentions::new());
+                retry::send_or_drop(&state.timings, || {
+                    message.author.direct_message(ctx, builder.clone())
+                })
+                .await?;
+            }
+        }
+    }
+
+    Ok(())
 }
 
 struct SerenityMessage {
@@ -461,82 +2925,166 @@ struct SerenityMessage {
     mention: Option<UserId>,
 }
 
+/// Author of a message, with the server roles and boosters counting as subscribers. The owner of
+/// the server is considered the broadcaster.
+async fn author(ctx: Context<'_>, id: UserId) -> Author {
+    let owner = ctx.guild().is_some_and(|guild| guild.owner_id == id);
+    let member = ctx.author_member().await;
+
+    let mut roles = member.as_ref().map_or_else(Vec::new, |member| {
+        member.roles.iter().map(ToString::to_string).collect()
+    });
+    if owner {
+        roles.push("broadcaster".to_owned());
+    }
+
+    Author {
+        id: AuthorId::Discord(id.into()),
+        name: ctx.author().name.clone(),
+        roles,
+        subscriber: member.is_some_and(|member| member.premium_since.is_some()),
+    }
+}
+
 #[instrument(skip_all, name = "discord message", fields(source = %Source::Discord))]
 async fn handle_message(ctx: Context<'_>, msg: SerenityMessage) -> Result<()> {
+    let received = Instant::now();
     if ctx.author().bot {
         // Ignore bots and our own messages.
         return Ok(());
     }
 
-    let queue = ctx.data().queue.clone();
-
-    let response = async {
-        let message = Message {
-            span: Span::current(),
-            source: Source::Discord,
-            content: msg.content,
-            author: AuthorId::Discord(msg.author.into()),
-            mention: msg.mention.map(Into::into),
-        };
+    let message = Message {
+        span: Span::current(),
+        source: Source::Discord,
+        content: msg.content,
+        author: author(ctx, msg.author).await,
+        mention: msg.mention.map(Into::into),
+        channel: guild_channel(ctx.guild_id(), ctx.channel_id()),
+    };
 
-        let (tx, rx) = oneshot::channel();
+    let command = message.content.metric_name().to_owned();
+    if let Some(resp) = connector::dispatch(&ctx.data().queue, message).await {
+        let reply = async {
+            #[cfg(feature = "chaos")]
+            crate::chaos::send()?;
 
-        if queue.send((message, tx)).await.is_ok() {
-            Some(rx.await)
-        } else {
-            None
-        }
-    }
-    .instrument(info_span!("handle"))
-    .await;
+            let resp = pin_highlight(ctx, resp).await;
+            let route = routed_channel(&ctx.data().dm_routing, ctx.guild_id(), &command, &resp);
+            let rendered = match resp {
+                Response::User(resp) => render::user(resp, &ctx.data().settings.streamer)?,
+                Response::Admin(resp) => Some(render::admin(resp)),
+                Response::Owner(resp) => Some(render::owner(resp)),
+                Response::Denied(required) => Some(render::denied(required)),
+                Response::Confirm(action) => Some(render::confirm(action)),
+            };
 
-    if let Some(Ok(resp)) = response {
-        async {
-            match resp {
-                Response::User(user_resp) => handle_user_message(user_resp, ctx).await,
-                Response::Admin(admin_resp) => handle_admin_message(admin_resp, ctx).await,
-                Response::Owner(owner_resp) => handle_owner_message(owner_resp, ctx).await,
+            match (rendered, route) {
+                (Some(reply), Some(channel)) if reply.delivery == Delivery::Channel => {
+                    let data = ctx.data();
+                    let note =
+                        deliver_routed(ctx.http(), &data.timings, data.mentions, channel, reply)
+                            .await;
+                    reply_private(ctx, note).await
+                }
+                (Some(reply), _) => Box::pin(deliver(ctx, reply)).await,
+                (None, _) => Ok(()),
             }
         }
-        .instrument(info_span!("reply"))
-        .await?;
+        .instrument(info_span!("reply"));
+
+        ctx.data().timings.reply(&command, received, reply).await?;
     }
 
     Ok(())
 }
 
-async fn handle_user_message(resp: response::User, ctx: Context<'_>) -> Result<()> {
-    match resp {
-        response::User::Help => user::help(ctx).await,
-        response::User::Commands(res) => user::commands(ctx, res).await,
-        response::User::Links(links) => user::links(ctx, links).await,
-        response::User::Ban(target) => user::ban(ctx, target).await,
-        response::User::Crate(res) => user::crate_(ctx, res).await,
-        response::User::Today(content)
-        | response::User::FahrenheitToCelsius(content)
-        | response::User::CelsiusToFahrenheit(content) => user::string_reply(ctx, content).await,
-        response::User::Custom(content) => user::custom_reply(ctx, content).await,
-        response::User::Unknown => Ok(()),
-    }
+/// Pin the message on Discord, once it was saved as highlight.
+async fn pin_highlight(ctx: Context<'_>, resp: Response) -> Response {
+    let Response::Admin(response::Admin::Pin(Ok(highlight))) = resp else {
+        return resp;
+    };
+
+    let res = ChannelId::from(highlight.channel)
+        .pin(ctx.http(), MessageId::from(highlight.message))
+        .await
+        .context("failed pinning the message");
+
+    Response::Admin(response::Admin::Pin(res.map(|()| highlight)))
 }
 
-async fn handle_admin_message(resp: response::Admin, ctx: Context<'_>) -> Result<()> {
-    match resp {
-        response::Admin::Help => admin::help(ctx).await,
-        response::Admin::CustomCommands(resp) => match resp {
-            response::CustomCommands::List(res) => admin::custom_commands_list(ctx, res).await,
-            response::CustomCommands::Edit(res) => admin::custom_commands_edit(ctx, res).await,
-        },
-        response::Admin::Statistics(res) => admin::stats(ctx, res).await,
+/// Send a rendered reply to the author of the command, in the way the reply asks for.
+async fn deliver(ctx: Context<'_>, reply: Reply) -> Result<()> {
+    match reply.delivery {
+        Delivery::Channel => {
+            let mut builder = CreateReply::default().reply(true).content(reply.content);
+            if let Some(embed) = reply.embed {
+                builder = builder.embed(embed);
+            }
+            if let Some(attachment) = reply.attachment {
+                builder =
+                    builder.attachment(CreateAttachment::bytes(attachment.data, attachment.name));
+            }
+            builder = builder.allowed_mentions(allowed_mentions(ctx.data().mentions, reply.notify));
+            if reply.confirm {
+                builder = builder.components(vec![confirm::button()]);
+            }
+
+            retry::send_or_drop(&ctx.data().timings, || ctx.send(builder.clone())).await
+        }
+        Delivery::Private => reply_private(ctx, reply.content).await,
+        Delivery::Direct => {
+            let delivered = async {
+                for chunk in chunks(&reply.content) {
+                    let builder = CreateMessage::new()
+                        .content(chunk)
+                        .allowed_mentions(CreateAllowedMentions::new());
+                    retry::send(&ctx.data().timings, || {
+                        ctx.author()
+                            .direct_message(ctx.serenity_context(), builder.clone())
+                    })
+                    .await?;
+                }
+                anyhow::Ok(())
+            }
+            .await;
+
+            reply_private(
+                ctx,
+                match delivered {
+                    Ok(()) => format!("{} sent you the notes as direct message", emojis::OK_HAND),
+                    Err(e) => format!(
+                        "{} failed sending you a direct message: {e}",
+                        emojis::COLLISION
+                    ),
+                },
+            )
+            .await
+        }
     }
 }
 
-async fn handle_owner_message(resp: response::Owner, ctx: Context<'_>) -> Result<()> {
-    match resp {
-        response::Owner::Help => owner::help(ctx).await,
-        response::Owner::Admins(resp) => match resp {
-            response::Admins::List(res) => owner::admins_list(ctx, res).await,
-            response::Admins::Edit(res) => owner::admins_edit(ctx, res).await,
-        },
-    }
+/// Reply with a message that only the author of the command can see.
+async fn reply_private(ctx: Context<'_>, message: String) -> Result<()> {
+    let builder = CreateReply::default().content(message).ephemeral(true);
+    retry::send_or_drop(&ctx.data().timings, || ctx.send(builder.clone())).await
+}
+
+/// Split a message by lines into chunks that fit into a single Discord message.
+fn chunks(message: &str) -> Vec<String> {
+    /// Maximum length of a single Discord message.
+    const MAX_MESSAGE_LEN: usize = 2000;
+
+    message
+        .lines()
+        .fold(Vec::<String>::new(), |mut chunks, line| {
+            match chunks.last_mut() {
+                Some(chunk) if chunk.len() + 1 + line.len() <= MAX_MESSAGE_LEN => {
+                    chunk.push('\n');
+                    chunk.push_str(line);
+                }
+                _ => chunks.push(line.to_owned()),
+            }
+            chunks
+        })
 }
@@ -0,0 +1,107 @@
+//! Lightweight polls, that mirror Twitch polls and predictions with reactions on a message.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use poise::serenity_prelude::{
+    self as serenity, ChannelId, CreateAllowedMentions, CreateMessage, MessageId, ReactionType,
+};
+use tracing::{error, info};
+
+use crate::{api::Poll, render};
+
+/// Reactions to vote for an option, in the order of the options.
+const KEYCAPS: [&str; 10] = [
+    "1\u{fe0f}\u{20e3}",
+    "2\u{fe0f}\u{20e3}",
+    "3\u{fe0f}\u{20e3}",
+    "4\u{fe0f}\u{20e3}",
+    "5\u{fe0f}\u{20e3}",
+    "6\u{fe0f}\u{20e3}",
+    "7\u{fe0f}\u{20e3}",
+    "8\u{fe0f}\u{20e3}",
+    "9\u{fe0f}\u{20e3}",
+    "\u{1f51f}",
+];
+
+/// Message that doesn't notify anyone, as the poll question comes from another service.
+fn silent_message(content: String) -> CreateMessage {
+    CreateMessage::new()
+        .content(content)
+        .allowed_mentions(CreateAllowedMentions::new())
+}
+
+/// Post the poll to the channel and announce the results in the background, once the poll ended.
+pub async fn start(http: Arc<serenity::Http>, channel: ChannelId, poll: Poll) -> Result<()> {
+    let message = channel
+        .send_message(&http, silent_message(content(&poll)))
+        .await?;
+
+    for keycap in KEYCAPS.iter().take(poll.options.len()) {
+        message
+            .react(&http, ReactionType::Unicode((*keycap).to_owned()))
+            .await?;
+    }
+
+    info!(message = %message.id, "started poll");
+
+    tokio::spawn(async move {
+        tokio::time::sleep(poll.duration).await;
+
+        if let Err(e) = finish(&http, channel, message.id, &poll).await {
+            error!(error = ?e, "failed announcing poll results");
+        }
+    });
+
+    Ok(())
+}
+
+/// Count the votes of the poll and post the results.
+async fn finish(
+    http: &serenity::Http,
+    channel: ChannelId,
+    message: MessageId,
+    poll: &Poll,
+) -> Result<()> {
+    let message = channel.message(http, message).await?;
+    let results = poll
+        .options
+        .iter()
+        .zip(KEYCAPS)
+        .map(|(option, keycap)| {
+            let votes = message
+                .reactions
+                .iter()
+                .find(|reaction| {
+                    matches!(&reaction.reaction_type, ReactionType::Unicode(emoji) if emoji == keycap)
+                })
+                // The bot's own reaction is only there to make voting easier.
+                .map_or(0, |reaction| reaction.count - u64::from(reaction.me));
+
+            (option.clone(), votes)
+        })
+        .collect::<Vec<_>>();
+
+    channel
+        .send_message(
+            http,
+            silent_message(render::poll_results(&poll.question, &results)),
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Message that presents the question, and which reaction votes for which option.
+fn content(poll: &Poll) -> String {
+    poll.options.iter().zip(KEYCAPS).fold(
+        format!("**{}** ({})", poll.question, poll.kind.name()),
+        |mut content, (option, keycap)| {
+            content.push('\n');
+            content.push_str(keycap);
+            content.push(' ');
+            content.push_str(option);
+            content
+        },
+    )
+}
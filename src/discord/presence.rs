@@ -0,0 +1,106 @@
+//! Status of the bot account, that shows whether the stream is live and what it's about.
+
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+use poise::serenity_prelude::ActivityData;
+
+use crate::{api::StreamInfo, locale, settings::Presence as PresenceSettings};
+
+/// Current stream state, kept around so the status can be set again after reconnecting.
+pub struct Presence {
+    settings: PresenceSettings,
+    streamer: OnceLock<String>,
+    stream: Mutex<Option<StreamInfo>>,
+}
+
+impl Presence {
+    pub fn new(settings: PresenceSettings) -> Self {
+        Self {
+            settings,
+            streamer: OnceLock::new(),
+            stream: Mutex::default(),
+        }
+    }
+
+    /// Set the name of the streamer, which is only known once the connector starts.
+    pub fn set_streamer(&self, streamer: &str) {
+        self.streamer.set(streamer.to_owned()).ok();
+    }
+
+    /// Remember the new stream state, returning the activity to show for it.
+    pub fn update(&self, stream: Option<StreamInfo>) -> Option<ActivityData> {
+        let mut current = self.stream.lock().unwrap_or_else(PoisonError::into_inner);
+        *current = stream;
+
+        self.activity_for(current.as_ref())
+    }
+
+    /// Activity to show for the current stream state.
+    pub fn activity(&self) -> Option<ActivityData> {
+        self.activity_for(
+            self.stream
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .as_ref(),
+        )
+    }
+
+    fn activity_for(&self, stream: Option<&StreamInfo>) -> Option<ActivityData> {
+        match stream {
+            Some(stream) => Some(ActivityData::watching(locale::fill(
+                &self.settings.live,
+                &[
+                    ("streamer", &self.streamer.get().map_or("", String::as_str)),
+                    ("title", &stream.title),
+                    ("category", &stream.category),
+                ],
+            ))),
+            None => self.settings.idle.clone().map(ActivityData::custom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use poise::serenity_prelude::ActivityType;
+    use similar_asserts::assert_eq;
+    use time::macros::datetime;
+
+    use super::*;
+
+    fn stream() -> StreamInfo {
+        StreamInfo {
+            id: "1".to_owned(),
+            started_at: datetime!(2025-03-01 12:00 UTC),
+            title: "Writing a bot".to_owned(),
+            category: "Software and Game Development".to_owned(),
+        }
+    }
+
+    #[test]
+    fn live_and_idle() {
+        let presence = Presence::new(PresenceSettings {
+            idle: Some("Type /help".to_owned()),
+            ..PresenceSettings::default()
+        });
+        presence.set_streamer("togglebit");
+
+        let live = presence.update(Some(stream())).unwrap();
+        assert_eq!(ActivityType::Watching, live.kind);
+        assert_eq!("togglebit live: Writing a bot", live.name);
+        assert_eq!(
+            Some("togglebit live: Writing a bot"),
+            presence.activity().map(|activity| activity.name).as_deref()
+        );
+
+        let idle = presence.update(None).unwrap();
+        assert_eq!(ActivityType::Custom, idle.kind);
+        assert_eq!(Some("Type /help"), idle.state.as_deref());
+    }
+
+    #[test]
+    fn no_idle_status() {
+        let presence = Presence::new(PresenceSettings::default());
+        assert!(presence.activity().is_none());
+    }
+}
@@ -0,0 +1,172 @@
+//! Retries for sending replies to Discord, so a hiccup of the API doesn't lose a response.
+//!
+//! Temporary failures, like rate limits, server errors or lost connections, are tried again with
+//! an increasing delay. Anything else, like missing permissions or a deleted channel, won't succeed
+//! on a second try and is given up right away.
+
+use std::{future::Future, time::Duration};
+
+use anyhow::Result;
+use poise::serenity_prelude::{self as serenity, HttpError, StatusCode};
+use tracing::warn;
+
+use crate::timing::{ReplyOutcome, Timings};
+
+/// Maximum attempts to send a single reply.
+const MAX_ATTEMPTS: u32 = 3;
+/// Delay before the first retry, which doubles with each further one.
+const INITIAL_DELAY: Duration = Duration::from_millis(500);
+
+/// Send a reply, trying again on temporary failures. The error of the last attempt is returned,
+/// if none of them succeeded.
+pub async fn send<T, F, Fut>(timings: &Timings, op: F) -> serenity::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = serenity::Result<T>>,
+{
+    retry(timings, INITIAL_DELAY, transient, op).await
+}
+
+/// Send a reply like [`send`], but only log failures that won't go away and carry on as if the
+/// reply was sent. These are usually caused by the server setup, and there's nobody to tell about
+/// them in the channel anyway.
+pub async fn send_or_drop<T, F, Fut>(timings: &Timings, op: F) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = serenity::Result<T>>,
+{
+    match send(timings, op).await {
+        Ok(_) => Ok(()),
+        Err(e) if !transient(&e) => {
+            warn!(error = ?e, "dropped Discord reply");
+            Ok(())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn retry<T, E, F, Fut>(
+    timings: &Timings,
+    mut delay: Duration,
+    transient: fn(&E) -> bool,
+    mut op: F,
+) -> Result<T, E>
+where
+    E: std::fmt::Debug,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let mut attempt = 1;
+
+    loop {
+        let e = match op().await {
+            Ok(value) => {
+                timings.record_reply(ReplyOutcome::Sent);
+                return Ok(value);
+            }
+            Err(e) => e,
+        };
+
+        if !transient(&e) {
+            timings.record_reply(ReplyOutcome::Dropped);
+            return Err(e);
+        }
+        if attempt == MAX_ATTEMPTS {
+            timings.record_reply(ReplyOutcome::Failed);
+            return Err(e);
+        }
+
+        warn!(error = ?e, attempt, "failed sending Discord reply, trying again");
+        timings.record_reply(ReplyOutcome::Retried);
+
+        tokio::time::sleep(delay).await;
+        delay *= 2;
+        attempt += 1;
+    }
+}
+
+/// Whether the error is only temporary, and the same request may succeed later.
+fn transient(e: &serenity::Error) -> bool {
+    match e {
+        serenity::Error::Http(HttpError::Request(_)) => true,
+        serenity::Error::Http(e) => e.status_code().is_some_and(transient_status),
+        _ => false,
+    }
+}
+
+fn transient_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use similar_asserts::assert_eq;
+
+    use super::*;
+
+    #[derive(Clone, Debug)]
+    enum Failure {
+        Temporary,
+        Permanent,
+    }
+
+    fn temporary(e: &Failure) -> bool {
+        matches!(e, Failure::Temporary)
+    }
+
+    async fn run(
+        results: Vec<Result<(), Failure>>,
+    ) -> (Result<(), Failure>, Vec<(ReplyOutcome, u64)>) {
+        let timings = Timings::default();
+        let calls = Cell::new(0);
+
+        let res = retry(&timings, Duration::ZERO, temporary, || {
+            let index = calls.get();
+            calls.set(index + 1);
+            let res = results[index].clone();
+            async move { res }
+        })
+        .await;
+
+        (res, timings.replies())
+    }
+
+    #[tokio::test]
+    async fn retry_temporary() {
+        let (res, outcomes) = run(vec![Err(Failure::Temporary), Ok(())]).await;
+        assert!(res.is_ok());
+        assert_eq!(
+            vec![
+                (ReplyOutcome::Sent, 1),
+                (ReplyOutcome::Retried, 1),
+                (ReplyOutcome::Dropped, 0),
+                (ReplyOutcome::Failed, 0),
+            ],
+            outcomes
+        );
+    }
+
+    #[tokio::test]
+    async fn give_up() {
+        let (res, outcomes) = run(vec![Err(Failure::Temporary); 3]).await;
+        assert!(matches!(res, Err(Failure::Temporary)));
+        assert_eq!(2, outcomes[1].1);
+        assert_eq!(1, outcomes[3].1);
+
+        let (res, outcomes) = run(vec![Err(Failure::Temporary), Err(Failure::Permanent)]).await;
+        assert!(matches!(res, Err(Failure::Permanent)));
+        assert_eq!(1, outcomes[1].1);
+        assert_eq!(1, outcomes[2].1);
+    }
+
+    #[test]
+    fn classify() {
+        assert!(transient_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(transient_status(StatusCode::BAD_GATEWAY));
+        assert!(!transient_status(StatusCode::FORBIDDEN));
+        assert!(!transient_status(StatusCode::NOT_FOUND));
+        assert!(!transient(&serenity::Error::Other("too long")));
+    }
+}
@@ -4,3 +4,7 @@
 pub const COLLISION: char = '💥';
 /// The OK hand 👌 emoji.
 pub const OK_HAND: char = '👌';
+/// The pushpin 📌 emoji.
+pub const PUSHPIN: char = '📌';
+/// The warning ⚠ emoji.
+pub const WARNING: char = '⚠';
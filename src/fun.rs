@@ -0,0 +1,303 @@
+//! Light-hearted content for the `!advice`, `!fact` and `!joke` commands.
+//!
+//! Each kind of content comes from a [`Provider`], which are by default the word lists that ship
+//! with the bot, or external APIs configured in the settings. Any custom type implementing the
+//! trait can be set as well, when embedding the bot.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::{self, Display},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use futures_util::future::BoxFuture;
+use rand::seq::SliceRandom;
+use tracing::warn;
+
+use crate::settings::{Fun as FunSettings, FunProvider};
+
+/// Kinds of content, each with their own command.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Kind {
+    /// A piece of advice.
+    Advice,
+    /// A random fact.
+    Fact,
+    /// A joke.
+    Joke,
+}
+
+impl Kind {
+    /// Name of the command, without the `!` prefix.
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Advice => "advice",
+            Self::Fact => "fact",
+            Self::Joke => "joke",
+        }
+    }
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A provider creates a new random entry whenever asked.
+pub trait Provider: Send + Sync {
+    /// Get a random entry.
+    fn fetch(&self) -> BoxFuture<'_, Result<String>>;
+}
+
+/// Fixed list of entries, that one is picked from at random.
+pub struct WordList(Vec<String>);
+
+impl WordList {
+    /// Create a list from the given entries.
+    #[must_use]
+    pub fn new(entries: Vec<String>) -> Self {
+        Self(entries)
+    }
+
+    /// The list that ships with the bot, with one entry per line.
+    #[must_use]
+    pub fn builtin(kind: Kind) -> Self {
+        let list = match kind {
+            Kind::Advice => include_str!("../assets/fun/advice.txt"),
+            Kind::Fact => include_str!("../assets/fun/facts.txt"),
+            Kind::Joke => include_str!("../assets/fun/jokes.txt"),
+        };
+
+        Self(
+            list.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(ToOwned::to_owned)
+                .collect(),
+        )
+    }
+}
+
+impl Provider for WordList {
+    fn fetch(&self) -> BoxFuture<'_, Result<String>> {
+        let entry = self
+            .0
+            .choose(&mut rand::thread_rng())
+            .cloned()
+            .context("the word list is empty");
+
+        Box::pin(async move { entry })
+    }
+}
+
+/// External API that answers with JSON, where the entry is found at a JSON pointer.
+pub struct Api {
+    client: reqwest::Client,
+    url: String,
+    pointer: String,
+}
+
+impl Api {
+    /// Create a provider for the API at `url`, where `pointer` locates the text in the response,
+    /// like `/slip/advice`.
+    pub fn new(url: String, pointer: String) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .user_agent("ToggleBot (https://github.com/dnaka91/togglebot)")
+            .timeout(Duration::from_secs(5))
+            .build()?;
+
+        Ok(Self {
+            client,
+            url,
+            pointer,
+        })
+    }
+}
+
+impl Provider for Api {
+    fn fetch(&self) -> BoxFuture<'_, Result<String>> {
+        Box::pin(async move {
+            let value = self
+                .client
+                .get(&self.url)
+                .header(reqwest::header::ACCEPT, "application/json")
+                .send()
+                .await?
+                .error_for_status()?
+                .json::<serde_json::Value>()
+                .await?;
+
+            value
+                .pointer(&self.pointer)
+                .and_then(serde_json::Value::as_str)
+                .map(|text| text.trim().to_owned())
+                .with_context(|| format!("no text at `{}` in the response", self.pointer))
+        })
+    }
+}
+
+/// Time after a fetch, in which entries are taken from the cache instead of asking the provider
+/// again. This keeps external APIs from being flooded when the commands are spammed.
+const MIN_INTERVAL: Duration = Duration::from_secs(3);
+/// Amount of recent entries that are kept for each kind.
+const CACHE_SIZE: usize = 50;
+
+struct Entry {
+    provider: Arc<dyn Provider>,
+    recent: VecDeque<String>,
+    fetched: Option<Instant>,
+}
+
+impl Entry {
+    fn new(provider: Arc<dyn Provider>) -> Self {
+        Self {
+            provider,
+            recent: VecDeque::new(),
+            fetched: None,
+        }
+    }
+
+    fn cached(&self) -> Option<String> {
+        let index = rand::random::<usize>() % self.recent.len().max(1);
+        self.recent.get(index).cloned()
+    }
+}
+
+/// Providers for all kinds of content, with a cache of recent entries.
+///
+/// The cache answers while a provider was asked very recently, and whenever a provider fails, so
+/// a flaky API still gives an answer most of the time.
+pub struct Fun(Mutex<HashMap<Kind, Entry>>);
+
+impl Default for Fun {
+    /// Only the word lists that ship with the bot.
+    fn default() -> Self {
+        Self(Mutex::new(
+            [Kind::Advice, Kind::Fact, Kind::Joke]
+                .into_iter()
+                .map(|kind| (kind, Entry::new(Arc::new(WordList::builtin(kind)))))
+                .collect(),
+        ))
+    }
+}
+
+impl Fun {
+    /// Create the providers as described in the settings.
+    pub fn from_settings(settings: &FunSettings) -> Result<Self> {
+        let fun = Self::default();
+
+        for (kind, provider) in [
+            (Kind::Advice, &settings.advice),
+            (Kind::Fact, &settings.fact),
+            (Kind::Joke, &settings.joke),
+        ] {
+            if let FunProvider::Api { url, pointer } = provider {
+                fun.set(kind, Api::new(url.clone(), pointer.clone())?);
+            }
+        }
+
+        Ok(fun)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<Kind, Entry>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Replace the provider for a kind of content, dropping any cached entries.
+    pub fn set(&self, kind: Kind, provider: impl Provider + 'static) {
+        self.lock().insert(kind, Entry::new(Arc::new(provider)));
+    }
+
+    /// Get a random entry of the given kind.
+    pub async fn fetch(&self, kind: Kind) -> Result<String> {
+        let provider = {
+            let mut entries = self.lock();
+            let entry = entries.get_mut(&kind).context("no provider available")?;
+
+            if entry
+                .fetched
+                .is_some_and(|fetched| fetched.elapsed() < MIN_INTERVAL)
+            {
+                if let Some(text) = entry.cached() {
+                    return Ok(text);
+                }
+            }
+
+            entry.fetched = Some(Instant::now());
+            Arc::clone(&entry.provider)
+        };
+
+        let res = provider.fetch().await;
+
+        let mut entries = self.lock();
+        let entry = entries.get_mut(&kind).context("no provider available")?;
+
+        match res {
+            Ok(text) => {
+                if !entry.recent.contains(&text) {
+                    if entry.recent.len() >= CACHE_SIZE {
+                        entry.recent.pop_front();
+                    }
+                    entry.recent.push_back(text.clone());
+                }
+                Ok(text)
+            }
+            Err(e) => match entry.cached() {
+                Some(text) => {
+                    warn!(error = ?e, %kind, "provider failed, answering from the cache");
+                    Ok(text)
+                }
+                None => Err(e),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use anyhow::bail;
+
+    use super::*;
+
+    /// Answers once, and fails afterwards.
+    struct Flaky(AtomicBool);
+
+    impl Provider for Flaky {
+        fn fetch(&self) -> BoxFuture<'_, Result<String>> {
+            Box::pin(async move {
+                if self.0.swap(true, Ordering::SeqCst) {
+                    bail!("service unavailable");
+                }
+                Ok("first".to_owned())
+            })
+        }
+    }
+
+    #[test]
+    fn builtin_lists() {
+        for kind in [Kind::Advice, Kind::Fact, Kind::Joke] {
+            let list = WordList::builtin(kind);
+            assert!(list.0.len() >= 10, "{kind}");
+            assert!(list.0.iter().all(|entry| entry.len() <= 200), "{kind}");
+        }
+    }
+
+    #[tokio::test]
+    async fn fall_back_to_cache() {
+        let fun = Fun::default();
+        fun.set(Kind::Joke, Flaky(AtomicBool::new(false)));
+
+        assert_eq!("first", fun.fetch(Kind::Joke).await.unwrap());
+        fun.lock().get_mut(&Kind::Joke).unwrap().fetched = None;
+        assert_eq!("first", fun.fetch(Kind::Joke).await.unwrap());
+
+        fun.set(Kind::Joke, Flaky(AtomicBool::new(true)));
+        assert!(fun.fetch(Kind::Joke).await.is_err());
+    }
+}
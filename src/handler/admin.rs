@@ -1,12 +1,19 @@
 use std::collections::{BTreeMap, BTreeSet};
 
-use anyhow::{ensure, Result};
-use tracing::{info, instrument};
+use anyhow::{ensure, Context, Result};
+use time::OffsetDateTime;
+use tracing::{info, instrument, warn};
 
 use crate::{
-    api::{request::StatisticsDate, response, Source},
+    api::{
+        request::{self, StatisticsDate},
+        response::{self, CustomCommandEntry, MassMention},
+        AdminId, AuthorId, Availability, Highlight, Source, UserRef,
+    },
+    commands,
     state::State,
     statistics::Stats,
+    timing::Timings,
 };
 
 #[instrument(skip_all)]
@@ -15,7 +22,7 @@ pub fn help() -> response::Admin {
     response::Admin::Help
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub(super) enum Action {
     Add,
     Remove,
@@ -28,73 +35,175 @@ pub fn custom_commands_list(state: &State) -> response::Admin {
     response::Admin::CustomCommands(response::CustomCommands::List(list_commands(state)))
 }
 
-fn list_commands(state: &State) -> Result<BTreeMap<String, BTreeSet<Source>>> {
-    Ok(state.list_custom_commands()?.into_iter().fold(
-        BTreeMap::new(),
-        |mut acc, (name, source)| {
-            acc.entry(name).or_default().insert(source);
+fn list_commands(state: &State) -> Result<BTreeMap<String, Vec<CustomCommandEntry>>> {
+    let mut list = state.list_custom_command_details()?.into_iter().fold(
+        BTreeMap::<_, Vec<_>>::new(),
+        |mut acc, entry| {
+            acc.entry(entry.name.clone()).or_default().push(entry);
             acc
         },
-    ))
+    );
+
+    for entries in list.values_mut() {
+        entries.sort_by_key(|entry| entry.source);
+    }
+
+    Ok(list)
+}
+
+#[instrument(skip(state))]
+pub fn custom_commands_show(state: &State, name: String) -> response::Admin {
+    info!("received `custom_commands show` command");
+
+    let res = state.custom_command_details(&name).map(|mut entries| {
+        entries.sort_by_key(|entry| entry.source);
+        entries
+    });
+
+    response::Admin::CustomCommands(response::CustomCommands::Show(name, res))
 }
 
+#[allow(clippy::too_many_arguments)]
 #[instrument(skip_all)]
 pub async fn custom_commands(
     state: &State,
     statistics: &Stats,
+    author: &AuthorId,
     content: &str,
     action: Action,
     source: Option<Source>,
     name: &str,
+    now: OffsetDateTime,
 ) -> response::Admin {
     info!("received `custom_commands` command");
 
+    let res = update_commands(
+        state,
+        statistics,
+        &author.user_ref(),
+        action,
+        source,
+        name,
+        content,
+        now,
+    )
+    .await
+    .map(|()| match action {
+        Action::Add => mass_mentions(content),
+        Action::Remove => Vec::new(),
+    });
+
+    if let Ok(mentions) = &res {
+        if !mentions.is_empty() {
+            warn!(
+                name,
+                ?mentions,
+                "custom command content contains mass mentions"
+            );
+        }
+    }
+
+    response::Admin::CustomCommands(response::CustomCommands::Edit(res))
+}
+
+/// Mentions in the content that notify many users at once on Discord, if the settings allow them.
+fn mass_mentions(content: &str) -> Vec<MassMention> {
+    let role = content.match_indices("<@&").any(|(i, _)| {
+        content[i + 3..]
+            .split_once('>')
+            .is_some_and(|(id, _)| !id.is_empty() && id.bytes().all(|b| b.is_ascii_digit()))
+    });
+
+    [
+        (content.contains("@everyone"), MassMention::Everyone),
+        (content.contains("@here"), MassMention::Here),
+        (role, MassMention::Role),
+    ]
+    .into_iter()
+    .filter_map(|(found, mention)| found.then_some(mention))
+    .collect()
+}
+
+/// Maximum amount of changes shown in the history of a custom command.
+const HISTORY_LEN: usize = 10;
+
+#[instrument(skip(state))]
+pub fn custom_commands_history(state: &State, name: String) -> response::Admin {
+    info!("received `custom_commands history` command");
+
+    let res = state
+        .list_custom_command_revisions(&name)
+        .map(|mut revisions| {
+            revisions.truncate(HISTORY_LEN);
+            revisions
+        });
+
+    response::Admin::CustomCommands(response::CustomCommands::History(name, res))
+}
+
+#[instrument(skip(state, author))]
+pub fn custom_commands_revert(
+    state: &State,
+    author: &AuthorId,
+    name: &str,
+    now: OffsetDateTime,
+) -> response::Admin {
+    info!("received `custom_commands revert` command");
+
     response::Admin::CustomCommands(response::CustomCommands::Edit(
-        update_commands(state, statistics, action, source, name, content).await,
+        revert_command(state, &author.user_ref(), name, now).map(|()| Vec::new()),
     ))
 }
 
-/// List of all pre-defined commands that can not be defined as name for custom commands.
+/// Undo the most recent change to a custom command, restoring the previous content in every
+/// source that the change applied to. A command that didn't exist before is removed again.
 ///
-/// As custom commands are checked last, there is no chance of accidentally hiding the other
-/// commands, but refusing these names helps to avoid confusion about commands not being triggered.
-const RESERVED_COMMANDS: &[&str] = &[
-    // user commands
-    "help",
-    "bot",
-    "commands",
-    "links",
-    "crate",
-    "crates",
-    "ban",
-    "today",
-    "ftoc",
-    "ctof",
-    // admin commands
-    "admin_help",
-    "admin-help",
-    "adminhelp",
-    "ahelp",
-    "custom_commands",
-    "custom_command",
-    "stats",
-    // owner commands
-    "owner_help",
-    "owner-help",
-    "ownerhelp",
-    "ohelp",
-    "admins",
-    "admin",
-];
+/// A single change can cover several sources, which shows as a run of revisions with the same
+/// author, time and content at the top of the history. The revert is recorded as a change itself,
+/// so reverting twice restores the original change.
+fn revert_command(state: &State, author: &UserRef, name: &str, now: OffsetDateTime) -> Result<()> {
+    let revisions = state.list_custom_command_revisions(name)?;
+    let latest = revisions
+        .first()
+        .with_context(|| format!("no changes recorded for the command `{name}`"))?;
+    let sources = revisions
+        .iter()
+        .take_while(|rev| {
+            rev.changed_at == latest.changed_at
+                && rev.author == latest.author
+                && rev.content == latest.content
+        })
+        .map(|rev| rev.source)
+        .collect::<BTreeSet<_>>();
+
+    for source in sources {
+        let previous = revisions
+            .iter()
+            .filter(|rev| rev.source == source)
+            .nth(1)
+            .and_then(|rev| rev.content.as_deref());
+
+        match previous {
+            Some(content) => state.add_custom_command(source, name, content, author, now)?,
+            None => state.remove_custom_command(source, name)?,
+        }
+        state.add_custom_command_revision(source, name, previous, author, now)?;
+    }
+
+    Ok(())
+}
 
+#[allow(clippy::too_many_arguments)]
 #[instrument(skip(state, statistics))]
 async fn update_commands(
     state: &State,
     statistics: &Stats,
+    author: &UserRef,
     action: Action,
     source: Option<Source>,
     name: &str,
     content: &str,
+    now: OffsetDateTime,
 ) -> Result<()> {
     ensure!(
         !name.starts_with('!'),
@@ -110,32 +219,36 @@ async fn update_commands(
         "command names must consist of only letters, numbers and underscores",
     );
     ensure!(
-        !RESERVED_COMMANDS.contains(&name),
+        !commands::is_reserved(name),
         "the command name `{name}` is reserved",
     );
 
+    let sources = source.map_or_else(|| Source::ALL.to_vec(), |source| vec![source]);
+
     match action {
         Action::Add => {
             ensure!(!content.is_empty(), "no content for the command provided");
 
-            if let Some(source) = source {
-                state.add_custom_command(source, name, content)?;
-            } else {
-                for source in [Source::Discord, Source::Twitch] {
-                    state.add_custom_command(source, name, content)?;
-                }
+            for source in sources {
+                state.add_custom_command(source, name, content, author, now)?;
+                state.add_custom_command_revision(source, name, Some(content), author, now)?;
             }
         }
         Action::Remove => {
-            match source {
-                Some(source) => {
-                    state.remove_custom_command(source, name)?;
-                }
-                None => {
-                    state.remove_custom_command_by_name(name)?;
+            for source in sources {
+                if state.get_custom_command(source, name)?.is_some() {
+                    state.add_custom_command_revision(source, name, None, author, now)?;
                 }
             }
 
+            if let Some(source) = source {
+                state.remove_custom_command(source, name)?;
+            } else {
+                state.remove_custom_command_by_name(name)?;
+                state.set_command_cost(name, 0)?;
+                state.set_command_availability(name, Availability::Always)?;
+            }
+
             statistics.erase_custom(name)?;
         }
     }
@@ -144,15 +257,308 @@ async fn update_commands(
 }
 
 #[instrument(skip(stats))]
-pub async fn stats(stats: &Stats, date: StatisticsDate) -> response::Admin {
-    let res = || async {
-        let total = match date {
-            StatisticsDate::Total => true,
-            StatisticsDate::Current => false,
-        };
-
-        Ok((total, stats.get(total)?))
+pub fn stats(stats: &Stats, date: StatisticsDate) -> response::Admin {
+    let statistics = match &date {
+        StatisticsDate::Total => stats.get(true),
+        StatisticsDate::Current => stats.get(false),
+        StatisticsDate::Month { year, month } => stats.get_month(*year, *month),
+        StatisticsDate::Tag(tag) => stats.get_tag(tag),
+    };
+
+    response::Admin::Statistics(date, statistics)
+}
+
+#[instrument(skip(state))]
+pub fn stream_tag(state: &State, tag: &str) -> response::Admin {
+    info!("received `stats tag` command");
+
+    let res = || {
+        ensure!(
+            tag.len() <= 50,
+            "tags must not be longer than 50 characters"
+        );
+        ensure!(
+            tag.chars()
+                .all(|c| c == '-' || c == '_' || c.is_ascii_lowercase() || c.is_ascii_digit()),
+            "tags must consist of only lowercase letters, numbers, dashes and underscores",
+        );
+
+        state.tag_stream(tag)
     };
 
-    response::Admin::Statistics(res().await)
+    response::Admin::StreamTag(res())
+}
+
+#[instrument(skip_all)]
+pub fn timing(timings: &Timings) -> response::Admin {
+    info!("received `stats timing` command");
+    response::Admin::Timing(timings.report())
+}
+
+#[instrument(skip_all)]
+pub fn skip_song(state: &State) -> response::Admin {
+    info!("received `skip` command");
+    response::Admin::SkipSong(state.skip_song_request())
+}
+
+#[instrument(skip(state))]
+pub fn command_costs(state: &State, req: request::CommandCosts) -> response::Admin {
+    info!("received `cost` command");
+
+    response::Admin::CommandCosts(match req {
+        request::CommandCosts::List => response::CommandCosts::List(state.list_command_costs()),
+        request::CommandCosts::Set { name, cost } => {
+            response::CommandCosts::Edit(set_cost(state, &name, cost))
+        }
+    })
+}
+
+fn set_cost(state: &State, name: &str, cost: u64) -> Result<()> {
+    if cost > 0 {
+        ensure!(
+            state
+                .list_custom_commands()?
+                .iter()
+                .any(|(cmd, _)| cmd == name),
+            "there is no custom command named `{name}`",
+        );
+    }
+
+    state.set_command_cost(name, cost)
+}
+
+#[instrument(skip(state))]
+pub fn command_availability(state: &State, req: request::CommandAvailability) -> response::Admin {
+    info!("received `availability` command");
+
+    response::Admin::CommandAvailability(match req {
+        request::CommandAvailability::List => {
+            response::CommandAvailability::List(state.list_command_availability())
+        }
+        request::CommandAvailability::Set { name, availability } => {
+            response::CommandAvailability::Edit(set_availability(state, &name, availability))
+        }
+    })
+}
+
+fn set_availability(state: &State, name: &str, availability: Availability) -> Result<()> {
+    if availability != Availability::Always {
+        ensure!(
+            commands::is_reserved(name)
+                || state
+                    .list_custom_commands()?
+                    .iter()
+                    .any(|(cmd, _)| cmd == name),
+            "there is no command named `{name}`",
+        );
+    }
+
+    state.set_command_availability(name, availability)
+}
+
+#[instrument(skip(state))]
+pub fn shoutout(state: &State, enabled: Option<bool>) -> response::Admin {
+    info!("received `shoutout` command");
+
+    response::Admin::Shoutout(match enabled {
+        Some(enabled) => state
+            .set_toggle(super::raid::TOGGLE, enabled)
+            .map(|()| enabled),
+        None => state.toggle(super::raid::TOGGLE, true),
+    })
+}
+
+#[instrument(skip(state))]
+pub fn triggers(state: &State, req: request::Triggers) -> response::Admin {
+    info!("received `trigger` command");
+
+    response::Admin::Triggers(match req {
+        request::Triggers::List => response::Triggers::List(state.list_triggers()),
+        request::Triggers::Add { word, content } => {
+            response::Triggers::Edit(add_trigger(state, &word.to_lowercase(), &content))
+        }
+        request::Triggers::Remove(word) => response::Triggers::Edit(
+            state
+                .remove_trigger(&word.to_lowercase())
+                .and_then(|removed| exists(removed, &word)),
+        ),
+        request::Triggers::Cooldown { word, cooldown } => response::Triggers::Edit(
+            state
+                .set_trigger_cooldown(&word.to_lowercase(), cooldown)
+                .and_then(|found| exists(found, &word)),
+        ),
+        request::Triggers::Enable {
+            word,
+            source,
+            enabled,
+        } => response::Triggers::Edit(state.set_trigger_enabled(
+            &word.to_lowercase(),
+            source,
+            enabled,
+        )),
+    })
+}
+
+fn add_trigger(state: &State, word: &str, content: &str) -> Result<()> {
+    ensure!(
+        super::triggers::is_valid(word),
+        "trigger words can only contain letters and digits"
+    );
+
+    state.add_trigger(word, content, super::triggers::DEFAULT_COOLDOWN)
+}
+
+#[instrument(skip(state))]
+pub fn faqs(state: &State, req: request::Faqs) -> response::Admin {
+    info!("received `faq` command");
+
+    response::Admin::Faqs(match req {
+        request::Faqs::List => response::Faqs::List(state.list_faqs()),
+        request::Faqs::Add {
+            name,
+            pattern,
+            regex,
+            answer,
+        } => response::Faqs::Edit(add_faq(
+            state,
+            &response::Faq {
+                name: name.to_lowercase(),
+                pattern: if regex {
+                    pattern
+                } else {
+                    pattern.to_lowercase()
+                },
+                regex,
+                answer,
+            },
+        )),
+        request::Faqs::Remove(name) => {
+            response::Faqs::Edit(state.remove_faq(&name.to_lowercase()).and_then(|removed| {
+                ensure!(removed, "there is no FAQ entry `{name}`");
+                Ok(())
+            }))
+        }
+    })
+}
+
+fn add_faq(state: &State, faq: &response::Faq) -> Result<()> {
+    super::faq::validate(faq)?;
+    state.add_faq(faq)
+}
+
+#[instrument(skip_all)]
+pub fn pin(
+    state: &State,
+    author: &AuthorId,
+    highlight: Highlight,
+    now: OffsetDateTime,
+) -> response::Admin {
+    info!("received `pin` command");
+
+    response::Admin::Pin(
+        AdminId::from_author(author)
+            .context("messages can only be pinned from Discord")
+            .and_then(|admin| state.add_highlight(&highlight, admin, now))
+            .map(|()| highlight),
+    )
+}
+
+fn exists(found: bool, word: &str) -> Result<()> {
+    ensure!(found, "there is no trigger word `{word}`");
+    Ok(())
+}
+
+#[instrument(skip(state, author))]
+pub fn user_notes(
+    state: &State,
+    author: &AuthorId,
+    req: request::UserNotes,
+    now: OffsetDateTime,
+) -> response::Admin {
+    info!("received `usernote` command");
+
+    response::Admin::UserNotes(match req {
+        request::UserNotes::List(user) => {
+            let res = list_notes(state, author, &user, now);
+            response::UserNotes::List(user, res)
+        }
+        request::UserNotes::Add { user, content } => {
+            response::UserNotes::Add(add_note(state, author, &user, &content, now))
+        }
+        request::UserNotes::Remove(id) => {
+            response::UserNotes::Remove(remove_note(state, author, id, now))
+        }
+    })
+}
+
+/// Maximum length of a single user note, so a list of notes stays readable.
+const MAX_NOTE_LEN: usize = 1000;
+
+/// Get the admin behind a command, as notes can only be managed by Discord admins.
+fn actor(author: &AuthorId) -> Result<AdminId> {
+    AdminId::from_author(author).context("user notes can only be managed from Discord")
+}
+
+/// Record a note related action in the audit log, as well as the application logs.
+fn audit(
+    state: &State,
+    actor: AdminId,
+    action: &str,
+    details: &str,
+    now: OffsetDateTime,
+) -> Result<()> {
+    info!(target: "audit", %actor, action, details);
+    state.audit(actor, action, details, now)
+}
+
+fn list_notes(
+    state: &State,
+    author: &AuthorId,
+    user: &UserRef,
+    now: OffsetDateTime,
+) -> Result<Vec<response::UserNote>> {
+    let actor = actor(author)?;
+    let notes = state.list_user_notes(user)?;
+
+    audit(state, actor, "usernote list", &user.to_string(), now)?;
+    Ok(notes)
+}
+
+fn add_note(
+    state: &State,
+    author: &AuthorId,
+    user: &UserRef,
+    content: &str,
+    now: OffsetDateTime,
+) -> Result<i64> {
+    let actor = actor(author)?;
+    ensure!(
+        content.chars().count() <= MAX_NOTE_LEN,
+        "notes must be at most {MAX_NOTE_LEN} characters long",
+    );
+    let id = state.add_user_note(user, content, actor, now)?;
+
+    audit(
+        state,
+        actor,
+        "usernote add",
+        &format!("#{id} for {user}"),
+        now,
+    )?;
+    Ok(id)
+}
+
+fn remove_note(state: &State, author: &AuthorId, id: i64, now: OffsetDateTime) -> Result<()> {
+    let actor = actor(author)?;
+    let user = state
+        .remove_user_note(id)?
+        .with_context(|| format!("no note with ID #{id} exists"))?;
+
+    audit(
+        state,
+        actor,
+        "usernote remove",
+        &format!("#{id} for {user}"),
+        now,
+    )
 }
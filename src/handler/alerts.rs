@@ -0,0 +1,259 @@
+//! Alerts about command usage, for the daily limits that admins set with `!alert`.
+//!
+//! The usage counts of the current day are checked regularly, and each alert fires at most once a
+//! day, so a command that keeps being spammed doesn't flood the admins with messages.
+
+use std::num::NonZero;
+
+use anyhow::{ensure, Result};
+use time::Date;
+use tracing::{error, info, instrument, warn};
+
+use crate::{
+    api::{request, response, AdminId, Post, Source},
+    commands::registry,
+    connector::Connectors,
+    outbox::Outbox,
+    settings::UsageAlerts as UsageAlertsSettings,
+    state::State,
+    statistics::Stats,
+};
+
+/// Name of the alert that counts all unrecognized commands together, instead of a single command.
+pub const UNKNOWN: &str = "unknown";
+
+/// An alert whose limit was exceeded.
+struct Tripped {
+    /// Name of the command, or [`UNKNOWN`].
+    name: String,
+    /// Daily limit of the alert.
+    limit: u64,
+    /// Usage count of the day.
+    count: u64,
+}
+
+#[instrument(skip(state))]
+pub fn usage_alerts(state: &State, req: request::UsageAlerts) -> response::Admin {
+    info!("received `alert` command");
+
+    response::Admin::UsageAlerts(match req {
+        request::UsageAlerts::List => response::UsageAlerts::List(state.list_usage_alerts()),
+        request::UsageAlerts::Set { name, limit } => {
+            response::UsageAlerts::Edit(set_alert(state, &name, limit))
+        }
+    })
+}
+
+fn set_alert(state: &State, name: &str, limit: u64) -> Result<()> {
+    if limit > 0 && name != UNKNOWN {
+        let builtin = registry::COMMANDS
+            .iter()
+            .filter_map(|cmd| cmd.statistic)
+            .any(|statistic| statistic.name() == name);
+        ensure!(
+            builtin
+                || state
+                    .list_custom_commands()?
+                    .iter()
+                    .any(|(cmd, _)| cmd == name),
+            "there is no command named `{name}`",
+        );
+    }
+
+    state.set_usage_alert(name, limit)
+}
+
+/// Check all alerts against the usage of the day, and send the ones that tripped to the
+/// configured places.
+pub async fn check_usage_alerts(
+    connectors: &Connectors,
+    outbox: &Outbox,
+    state: &State,
+    statistics: &Stats,
+    settings: &UsageAlertsSettings,
+    owners: &[NonZero<u64>],
+    day: Date,
+) {
+    let tripped = match tripped(state, statistics, day) {
+        Ok(tripped) => tripped,
+        Err(e) => {
+            error!(error = ?e, "failed checking usage alerts");
+            return;
+        }
+    };
+
+    for alert in tripped {
+        info!(alert.name, alert.count, "usage alert tripped");
+        notify(
+            connectors,
+            outbox,
+            state,
+            settings,
+            owners,
+            &message(&alert),
+        )
+        .await;
+    }
+}
+
+/// Send an alert to the places that are configured for usage alerts, which are also used for
+/// other notices to the admins.
+pub(super) async fn notify(
+    connectors: &Connectors,
+    outbox: &Outbox,
+    state: &State,
+    settings: &UsageAlertsSettings,
+    owners: &[NonZero<u64>],
+    content: &str,
+) {
+    if let Some(channel) = settings.discord_channel {
+        let post = Post {
+            channel: Some(channel),
+            content: content.to_owned(),
+        };
+        if let Err(e) = outbox.publish(Source::Discord, post) {
+            error!(error = ?e, "failed posting alert");
+        }
+    }
+
+    if settings.direct_messages {
+        for id in recipients(state, owners) {
+            if let Err(e) = connectors
+                .send_direct(Source::Discord, id.to_string(), content.to_owned())
+                .await
+            {
+                warn!(error = ?e, user = id, "failed sending alert");
+            }
+        }
+    }
+}
+
+/// Alerts whose limit was exceeded on the given day, and that didn't trip on it yet.
+fn tripped(state: &State, statistics: &Stats, day: Date) -> Result<Vec<Tripped>> {
+    let mut tripped = Vec::new();
+
+    for (name, limit) in state.list_usage_alerts()? {
+        let count = if name == UNKNOWN {
+            statistics.daily_unknown(day)?
+        } else {
+            statistics.daily_usage(day, &name)?
+        };
+
+        if count > limit && state.trip_usage_alert(&name, day)? {
+            tripped.push(Tripped { name, limit, count });
+        }
+    }
+
+    Ok(tripped)
+}
+
+fn message(alert: &Tripped) -> String {
+    let Tripped { name, limit, count } = alert;
+
+    if name == UNKNOWN {
+        format!("🚨 Unknown commands were used {count} times today, more than the limit of {limit}")
+    } else {
+        format!("🚨 !{name} was used {count} times today, more than the limit of {limit}")
+    }
+}
+
+/// Discord users that get the alerts as direct message, which are the owners and all admins that
+/// were added by them.
+fn recipients(state: &State, owners: &[NonZero<u64>]) -> Vec<u64> {
+    let mut recipients = owners.iter().map(|id| id.get()).collect::<Vec<_>>();
+
+    match state.list_admins() {
+        Ok(admins) => recipients.extend(admins.iter().map(AdminId::get)),
+        Err(e) => error!(error = ?e, "failed listing admins for alerts"),
+    }
+
+    recipients.sort_unstable();
+    recipients.dedup();
+    recipients
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use similar_asserts::assert_eq;
+    use time::{
+        macros::{date, datetime},
+        OffsetDateTime,
+    };
+
+    use super::*;
+    use crate::{
+        api::AuthorId,
+        clock::MockClock,
+        db::connection::Connection,
+        statistics::{BuiltinCommand, Command},
+    };
+
+    #[test]
+    fn trip_once_a_day() {
+        let state = State::in_memory().unwrap();
+        let clock = Arc::new(MockClock::new(datetime!(2025-03-01 12:00 UTC)));
+        let statistics = Stats::with_clock(Connection::in_memory().unwrap(), clock.clone());
+        let day = date!(2025 - 03 - 01);
+
+        state.set_usage_alert("help", 2).unwrap();
+        state.set_usage_alert(UNKNOWN, 1).unwrap();
+
+        for _ in 0..2 {
+            statistics.increment(BuiltinCommand::Help.into()).unwrap();
+            statistics.increment(Command::Unknown("halp")).unwrap();
+        }
+
+        let names = |tripped: Vec<Tripped>| {
+            tripped
+                .into_iter()
+                .map(|alert| (alert.name, alert.count))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            vec![(UNKNOWN.to_owned(), 2)],
+            names(tripped(&state, &statistics, day).unwrap())
+        );
+
+        statistics.increment(BuiltinCommand::Help.into()).unwrap();
+        statistics.increment(Command::Unknown("halp")).unwrap();
+
+        assert_eq!(
+            vec![("help".to_owned(), 3)],
+            names(tripped(&state, &statistics, day).unwrap())
+        );
+        assert!(tripped(&state, &statistics, day).unwrap().is_empty());
+    }
+
+    #[test]
+    fn only_known_commands() {
+        let state = State::in_memory().unwrap();
+        state
+            .add_custom_command(
+                Source::Twitch,
+                "hug",
+                "*hugs*",
+                &AuthorId::Console.user_ref(),
+                OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+
+        assert!(set_alert(&state, "ftoc", 10).is_ok());
+        assert!(set_alert(&state, "hug", 10).is_ok());
+        assert!(set_alert(&state, UNKNOWN, 10).is_ok());
+        assert!(set_alert(&state, "nothing", 10).is_err());
+        assert!(set_alert(&state, "nothing", 0).is_ok());
+    }
+
+    #[test]
+    fn owners_and_admins() {
+        let state = State::in_memory().unwrap();
+        state.add_admin(AdminId::new(2).unwrap()).unwrap();
+        state.add_admin(AdminId::new(3).unwrap()).unwrap();
+
+        let owners = [NonZero::new(2).unwrap(), NonZero::new(1).unwrap()];
+        assert_eq!(vec![1, 2, 3], recipients(&state, &owners));
+    }
+}
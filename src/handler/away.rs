@@ -0,0 +1,212 @@
+//! Automatic replies to messages for the streamer, while they're away.
+//!
+//! On Discord, messages that mention the streamer's account are answered, and on Twitch messages
+//! that address the streamer with `@name`. Replies have a cooldown per service, so a busy chat
+//! doesn't get flooded with the same message.
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use time::OffsetDateTime;
+use tracing::{debug, error, info, instrument};
+
+use crate::{
+    api::{response, Author, AuthorId, Source},
+    settings::Commands as CommandSettings,
+    state::State,
+};
+
+/// Last time an automatic reply was sent, per service.
+pub(super) type Replied = Mutex<HashMap<Source, Instant>>;
+
+#[instrument(skip(state))]
+pub fn away(state: &State, message: &str, now: OffsetDateTime) -> response::Admin {
+    info!("received `away` command");
+    response::Admin::Away(state.set_away(message, now))
+}
+
+#[instrument(skip_all)]
+pub fn back(state: &State, now: OffsetDateTime) -> response::Admin {
+    info!("received `back` command");
+
+    response::Admin::Back(
+        state
+            .clear_away()
+            .map(|since| since.map(|since| Duration::try_from(now - since).unwrap_or_default())),
+    )
+}
+
+/// Reply to a message that addresses the streamer, if they're away and the cooldown passed.
+pub fn reply(
+    replied: &Replied,
+    state: &State,
+    settings: &CommandSettings,
+    source: Source,
+    author: &Author,
+    text: &str,
+) -> Option<response::User> {
+    if !addresses(settings, source, author, text) {
+        return None;
+    }
+
+    let (message, _) = state
+        .away()
+        .inspect_err(|e| error!(error = ?e, "failed loading away message"))
+        .ok()??;
+
+    let cooldown = Duration::from_secs(settings.away.cooldown);
+    if !cooled_down(replied, source, cooldown, Instant::now()) {
+        debug!("away reply is on cooldown");
+        return None;
+    }
+
+    Some(response::User::Away {
+        streamer: settings.streamer.clone(),
+        message,
+    })
+}
+
+/// Whether the message is meant for the streamer, and not written by the streamer themselves.
+fn addresses(settings: &CommandSettings, source: Source, author: &Author, text: &str) -> bool {
+    match (source, &author.id) {
+        (Source::Discord, AuthorId::Discord(id)) => {
+            settings.away.discord_user.is_some_and(|user| {
+                user != *id
+                    && (text.contains(&format!("<@{user}>"))
+                        || text.contains(&format!("<@!{user}>")))
+            })
+        }
+        (Source::Twitch, _) => {
+            !settings.streamer.is_empty()
+                && !author.is_broadcaster()
+                && text
+                    .split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '@'))
+                    .filter_map(|word| word.strip_prefix('@'))
+                    .any(|name| name.eq_ignore_ascii_case(&settings.streamer))
+        }
+        _ => false,
+    }
+}
+
+/// Check whether the cooldown passed on the service, and remember this time if so.
+fn cooled_down(last: &Replied, source: Source, cooldown: Duration, now: Instant) -> bool {
+    let mut last = last.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if last
+        .get(&source)
+        .is_some_and(|replied| now.duration_since(*replied) < cooldown)
+    {
+        return false;
+    }
+
+    last.insert(source, now);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZero;
+
+    use super::*;
+    use crate::settings::Away as AwaySettings;
+
+    fn settings() -> CommandSettings {
+        CommandSettings {
+            streamer: "togglebit".to_owned(),
+            away: AwaySettings {
+                discord_user: NonZero::new(5),
+                ..AwaySettings::default()
+            },
+            ..CommandSettings::default()
+        }
+    }
+
+    #[test]
+    fn addressed_messages() {
+        let settings = settings();
+        let viewer = Author::from(AuthorId::Twitch("1".to_owned()));
+        let streamer = Author {
+            roles: vec!["broadcaster".to_owned()],
+            ..AuthorId::Twitch("2".to_owned()).into()
+        };
+        let member = Author::from(AuthorId::Discord(NonZero::new(1).unwrap()));
+
+        assert!(addresses(
+            &settings,
+            Source::Twitch,
+            &viewer,
+            "hey @ToggleBit!"
+        ));
+        assert!(!addresses(
+            &settings,
+            Source::Twitch,
+            &viewer,
+            "hey @togglebit_fan"
+        ));
+        assert!(!addresses(
+            &settings,
+            Source::Twitch,
+            &viewer,
+            "togglebit is cool"
+        ));
+        assert!(!addresses(
+            &settings,
+            Source::Twitch,
+            &streamer,
+            "@togglebit"
+        ));
+        assert!(addresses(
+            &settings,
+            Source::Discord,
+            &member,
+            "<@5> are you there?"
+        ));
+        assert!(!addresses(&settings, Source::Discord, &member, "<@6> hi"));
+    }
+
+    #[test]
+    fn reply_while_away() {
+        let settings = settings();
+        let state = State::in_memory().unwrap();
+        let author = Author::from(AuthorId::Discord(NonZero::new(1).unwrap()));
+        let replied = Replied::default();
+
+        assert!(reply(
+            &replied,
+            &state,
+            &settings,
+            Source::Discord,
+            &author,
+            "<@5>"
+        )
+        .is_none());
+
+        state
+            .set_away("grabbing food", OffsetDateTime::now_utc())
+            .unwrap();
+        assert!(matches!(
+            reply(&replied, &state, &settings, Source::Discord, &author, "<@5>"),
+            Some(response::User::Away { message, .. }) if message == "grabbing food"
+        ));
+    }
+
+    #[test]
+    fn cooldown() {
+        let last = Replied::default();
+        let now = Instant::now();
+        let cooldown = Duration::from_mins(5);
+
+        assert!(cooled_down(&last, Source::Twitch, cooldown, now));
+        assert!(!cooled_down(
+            &last,
+            Source::Twitch,
+            cooldown,
+            now + Duration::from_mins(4)
+        ));
+        assert!(cooled_down(&last, Source::Discord, cooldown, now));
+        assert!(cooled_down(&last, Source::Twitch, cooldown, now + cooldown));
+    }
+}
@@ -0,0 +1,214 @@
+//! Birthdays that users registered, which are announced in a Discord channel once a day.
+
+use anyhow::Result;
+use time::{util, Date, Month, OffsetDateTime, Time};
+use tracing::{error, info, instrument};
+
+use crate::{
+    api::{
+        request,
+        response::{self, Birthday},
+        AuthorId, Post, Source, UserRef,
+    },
+    outbox::Outbox,
+    settings::Birthdays as BirthdaysSettings,
+    state::State,
+};
+
+#[instrument(skip_all)]
+pub fn birthday(state: &State, author: &AuthorId, req: request::Birthday) -> response::User {
+    info!("received `birthday` command");
+    response::User::Birthday(handle(state, &author.user_ref(), req))
+}
+
+fn handle(state: &State, user: &UserRef, req: request::Birthday) -> Result<Birthday> {
+    Ok(match req {
+        request::Birthday::Show => match state.birthday(user)? {
+            Some((month, day, announce)) => Birthday::Registered {
+                month,
+                day,
+                announce,
+            },
+            None => Birthday::NotRegistered,
+        },
+        request::Birthday::Set { month, day } => {
+            state.set_birthday(user, month, day)?;
+            Birthday::Saved { month, day }
+        }
+        request::Birthday::Remove => {
+            if state.remove_birthday(user)? {
+                Birthday::Removed
+            } else {
+                Birthday::NotRegistered
+            }
+        }
+        request::Birthday::Announce(announce) => {
+            if state.announce_birthday(user, announce)? {
+                Birthday::Announce(announce)
+            } else {
+                Birthday::NotRegistered
+            }
+        }
+    })
+}
+
+/// Wish everyone a happy birthday in the configured channel, whose birthday is on the given day.
+pub fn announce_birthdays(
+    outbox: &Outbox,
+    state: &State,
+    settings: &BirthdaysSettings,
+    today: Date,
+) {
+    let users = match birthdays(state, today) {
+        Ok(users) => users,
+        Err(e) => {
+            error!(error = ?e, "failed listing birthdays");
+            return;
+        }
+    };
+
+    if users.is_empty() {
+        return;
+    }
+
+    info!(count = users.len(), "announcing birthdays");
+
+    let post = Post {
+        channel: Some(settings.channel),
+        content: wishes(&users),
+    };
+    if let Err(e) = outbox.publish(Source::Discord, post) {
+        error!(error = ?e, "failed announcing birthdays");
+    }
+}
+
+/// Next moment after `now`, at which the birthdays are announced.
+#[must_use]
+pub fn next_birthdays_announcement(
+    settings: &BirthdaysSettings,
+    now: OffsetDateTime,
+) -> OffsetDateTime {
+    let now = now.to_offset(settings.utc_offset);
+    let today = now.replace_time(Time::from_hms(settings.hour, 0, 0).unwrap_or(Time::MIDNIGHT));
+
+    if today > now {
+        today
+    } else {
+        today + time::Duration::DAY
+    }
+}
+
+/// IDs of all Discord users, whose birthday is on the given day. Birthdays on February 29 are
+/// celebrated on February 28 outside of leap years.
+fn birthdays(state: &State, today: Date) -> Result<Vec<String>> {
+    let mut users = state.list_birthdays(Source::Discord, today.month(), today.day())?;
+
+    if (today.month(), today.day()) == (Month::February, 28) && !util::is_leap_year(today.year()) {
+        users.extend(state.list_birthdays(Source::Discord, Month::February, 29)?);
+    }
+
+    Ok(users)
+}
+
+fn wishes(users: &[String]) -> String {
+    let mentions = users
+        .iter()
+        .map(|id| format!("<@{id}>"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!("Happy birthday {mentions}! 🎂")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZero;
+
+    use time::{macros::datetime, UtcOffset};
+
+    use super::*;
+
+    fn set(state: &State, id: &str, month: Month, day: u8) {
+        let user = AuthorId::Discord(id.parse().unwrap()).user_ref();
+        handle(state, &user, request::Birthday::Set { month, day }).unwrap();
+    }
+
+    #[test]
+    fn leap_day_birthdays() {
+        let state = State::in_memory().unwrap();
+        set(&state, "1", Month::February, 28);
+        set(&state, "2", Month::February, 29);
+
+        assert_eq!(
+            vec!["1".to_owned(), "2".to_owned()],
+            birthdays(
+                &state,
+                Date::from_calendar_date(2025, Month::February, 28).unwrap()
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            vec!["1".to_owned()],
+            birthdays(
+                &state,
+                Date::from_calendar_date(2024, Month::February, 28).unwrap()
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            "Happy birthday <@1>, <@2>! 🎂",
+            wishes(&["1".into(), "2".into()])
+        );
+    }
+
+    #[test]
+    fn opt_out_and_removal() {
+        let state = State::in_memory().unwrap();
+        let user = AuthorId::Discord(NonZero::new(1).unwrap()).user_ref();
+
+        assert!(matches!(
+            handle(&state, &user, request::Birthday::Remove).unwrap(),
+            Birthday::NotRegistered
+        ));
+
+        set(&state, "1", Month::April, 2);
+        handle(&state, &user, request::Birthday::Announce(false)).unwrap();
+        assert!(matches!(
+            handle(&state, &user, request::Birthday::Show).unwrap(),
+            Birthday::Registered {
+                month: Month::April,
+                day: 2,
+                announce: false
+            }
+        ));
+        assert!(birthdays(
+            &state,
+            Date::from_calendar_date(2025, Month::April, 2).unwrap()
+        )
+        .unwrap()
+        .is_empty());
+
+        assert!(matches!(
+            handle(&state, &user, request::Birthday::Remove).unwrap(),
+            Birthday::Removed
+        ));
+    }
+
+    #[test]
+    fn next_announcement() {
+        let settings = BirthdaysSettings {
+            channel: NonZero::new(1).unwrap(),
+            hour: 9,
+            utc_offset: UtcOffset::from_hms(2, 0, 0).unwrap(),
+        };
+
+        assert_eq!(
+            datetime!(2025-04-02 09:00 +2),
+            next_birthdays_announcement(&settings, datetime!(2025-04-02 06:59 UTC))
+        );
+        assert_eq!(
+            datetime!(2025-04-03 09:00 +2),
+            next_birthdays_announcement(&settings, datetime!(2025-04-02 07:00 UTC))
+        );
+    }
+}
@@ -0,0 +1,79 @@
+//! Restriction of user commands to some channels of a Discord server.
+//!
+//! Channels are either allowed or denied. Once any channel of a server is allowed, user commands
+//! are only answered in the allowed channels. Admin commands are never restricted, so admins can
+//! always fix the rules, wherever they are.
+
+use std::num::NonZero;
+
+use anyhow::Context;
+use tracing::{error, info, instrument};
+
+use crate::{
+    api::{request, response, ChannelRule, GuildChannel},
+    state::State,
+};
+
+#[instrument(skip(state))]
+pub fn channels(
+    state: &State,
+    location: Option<GuildChannel>,
+    req: request::Channels,
+) -> response::Admin {
+    info!("received `channels` command");
+
+    let guild = location
+        .map(|location| location.guild)
+        .context("channels can only be configured from within a Discord server");
+
+    response::Admin::Channels(match req {
+        request::Channels::List => {
+            response::Channels::List(guild.and_then(|guild| state.channel_rules(guild)))
+        }
+        request::Channels::Set { channel, rule } => response::Channels::Edit(
+            guild.and_then(|guild| state.set_channel_rule(GuildChannel { guild, channel }, rule)),
+        ),
+    })
+}
+
+/// Whether user commands are answered in the channel. Commands stay allowed, if the rules can't be
+/// loaded.
+pub fn allowed(state: &State, channel: GuildChannel) -> bool {
+    match state.channel_rules(channel.guild) {
+        Ok(rules) => is_allowed(&rules, channel.channel),
+        Err(e) => {
+            error!(error = ?e, "failed loading channel rules");
+            true
+        }
+    }
+}
+
+fn is_allowed(rules: &[(NonZero<u64>, ChannelRule)], channel: NonZero<u64>) -> bool {
+    match rules.iter().find(|(id, _)| *id == channel) {
+        Some((_, rule)) => *rule == ChannelRule::Allow,
+        None => !rules.iter().any(|(_, rule)| *rule == ChannelRule::Allow),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(value: u64) -> NonZero<u64> {
+        NonZero::new(value).unwrap()
+    }
+
+    #[test]
+    fn channel_rules() {
+        assert!(is_allowed(&[], id(1)));
+
+        let denied = [(id(1), ChannelRule::Deny)];
+        assert!(!is_allowed(&denied, id(1)));
+        assert!(is_allowed(&denied, id(2)));
+
+        let allowed = [(id(1), ChannelRule::Allow), (id(2), ChannelRule::Deny)];
+        assert!(is_allowed(&allowed, id(1)));
+        assert!(!is_allowed(&allowed, id(2)));
+        assert!(!is_allowed(&allowed, id(3)));
+    }
+}
@@ -0,0 +1,72 @@
+//! List of commands that is kept pinned in a Discord channel, so users find the commands without
+//! asking for `!commands`.
+//!
+//! The list lives in a single message, which is edited whenever the custom commands change. If the
+//! message is gone, like after somebody deleted it, a new one is posted and pinned instead.
+
+use anyhow::Context;
+use tracing::{error, info, instrument};
+
+use super::user;
+use crate::{
+    api::{request, response, GuildChannel, Post, Source},
+    connector::Connectors,
+    state::State,
+};
+
+#[instrument(skip(state))]
+pub fn command_list(
+    state: &State,
+    location: Option<GuildChannel>,
+    req: request::CommandList,
+) -> response::Admin {
+    info!("received `commandlist` command");
+
+    response::Admin::CommandList(match req {
+        request::CommandList::Pin(channel) => channel
+            .or_else(|| location.map(|location| location.channel))
+            .context("the command list can only be kept in a channel of a Discord server")
+            .and_then(|channel| {
+                state
+                    .set_command_list(Some(channel))
+                    .map(|()| Some(channel))
+            }),
+        request::CommandList::Off => state.set_command_list(None).map(|()| None),
+    })
+}
+
+/// Post or update the pinned command list, if it's enabled. The list is rendered from the same
+/// response as `!commands` on Discord.
+pub async fn pin_command_list(
+    connectors: &Connectors,
+    state: &State,
+    render: impl FnOnce(response::User) -> Option<String>,
+) {
+    let (channel, message) = match state.command_list() {
+        Ok(Some(list)) => list,
+        Ok(None) => return,
+        Err(e) => {
+            error!(error = ?e, "failed loading the command list settings");
+            return;
+        }
+    };
+
+    let Some(content) = render(user::commands(state, Source::Discord)) else {
+        return;
+    };
+    let post = Post {
+        channel: Some(channel),
+        content,
+    };
+
+    match connectors.pin_post(Source::Discord, post, message).await {
+        Ok(pinned) if Some(pinned) != message => {
+            info!(%channel, message = pinned, "posted new command list");
+            if let Err(e) = state.set_command_list_message(channel, pinned) {
+                error!(error = ?e, "failed saving the command list message");
+            }
+        }
+        Ok(_) => {}
+        Err(e) => error!(error = ?e, "failed updating the command list"),
+    }
+}
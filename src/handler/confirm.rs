@@ -0,0 +1,233 @@
+//! Confirmation of destructive commands, that only run once their author confirms them.
+//!
+//! Commands like removing a custom command from all services or removing an admin are put aside
+//! as pending action, and the author has a short time to confirm them with `!confirm`. Every
+//! author has at most one pending action, a newer one replaces the older.
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use tracing::info;
+
+use super::Access;
+use crate::api::{
+    request::{self, Request},
+    response, AuthorId, UserRef,
+};
+
+/// Time that the author has to confirm a command.
+pub const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Pending commands of each author, with the time they were sent.
+pub(super) type Pending = Mutex<HashMap<UserRef, (Instant, Request)>>;
+
+/// Decide whether the request can run right away, or has to wait for a confirmation first. A
+/// confirmation is replaced with the pending request of the author, if there is any.
+///
+/// Requests that the author isn't allowed to use are left alone, so they're denied as usual. The
+/// same goes for pending requests, which are dropped if the author lacks the access to run them.
+pub fn check(
+    pending: &Pending,
+    access: Access,
+    author: &AuthorId,
+    req: Request,
+) -> Result<Request, response::Confirm> {
+    if !permitted(access, &req) {
+        return Ok(req);
+    }
+
+    let user = author.user_ref();
+    let now = Instant::now();
+
+    if matches!(req, Request::Admin(request::Admin::Confirm)) {
+        return Ok(take_at(pending, &user, now)
+            .filter(|pending| permitted(access, pending))
+            .unwrap_or(req));
+    }
+
+    match action(&req) {
+        Some(action) => {
+            info!(?user, name = req.name(), "waiting for confirmation");
+            defer_at(pending, user, req, now);
+            Err(action)
+        }
+        None => Ok(req),
+    }
+}
+
+fn permitted(access: Access, req: &Request) -> bool {
+    match req {
+        Request::User(_) => true,
+        Request::Admin(_) => access != Access::Standard,
+        Request::Owner(_) => access == Access::Owner,
+    }
+}
+
+/// The destructive action of the request, if it is one.
+fn action(req: &Request) -> Option<response::Confirm> {
+    Some(match req {
+        Request::Admin(request::Admin::CustomCommands(request::CustomCommands::Remove {
+            source: None,
+            name,
+        })) => response::Confirm::RemoveCustomCommand(name.clone()),
+        Request::Owner(request::Owner::Admins(request::Admins::Remove(id))) => {
+            response::Confirm::RemoveAdmin(*id)
+        }
+        Request::Owner(request::Owner::Admins(request::Admins::RemoveRole(role))) => {
+            response::Confirm::RemoveAdminRole(*role)
+        }
+        _ => return None,
+    })
+}
+
+fn defer_at(pending: &Pending, user: UserRef, req: Request, now: Instant) {
+    let mut pending = pending.lock().unwrap_or_else(PoisonError::into_inner);
+    pending.retain(|_, (sent, _)| now.duration_since(*sent) < TIMEOUT);
+    pending.insert(user, (now, req));
+}
+
+fn take_at(pending: &Pending, user: &UserRef, now: Instant) -> Option<Request> {
+    let (sent, req) = pending
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(user)?;
+
+    (now.duration_since(sent) < TIMEOUT).then_some(req)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZero;
+
+    use similar_asserts::assert_eq;
+
+    use super::*;
+    use crate::api::{AdminId, Source};
+
+    fn user(id: &str) -> UserRef {
+        UserRef {
+            source: Source::Discord,
+            id: id.to_owned(),
+        }
+    }
+
+    fn remove(name: &str) -> Request {
+        Request::Admin(request::Admin::CustomCommands(
+            request::CustomCommands::Remove {
+                source: None,
+                name: name.to_owned(),
+            },
+        ))
+    }
+
+    #[test]
+    fn destructive_requests() {
+        assert_eq!(
+            Some(response::Confirm::RemoveCustomCommand("hello".to_owned())),
+            action(&remove("hello"))
+        );
+        assert_eq!(
+            None,
+            action(&Request::Admin(request::Admin::CustomCommands(
+                request::CustomCommands::Remove {
+                    source: Some(Source::Twitch),
+                    name: "hello".to_owned(),
+                },
+            )))
+        );
+        assert_eq!(
+            Some(response::Confirm::RemoveAdminRole(NonZero::new(7).unwrap())),
+            action(&Request::Owner(request::Owner::Admins(
+                request::Admins::RemoveRole(NonZero::new(7).unwrap())
+            )))
+        );
+        assert_eq!(None, action(&Request::Admin(request::Admin::Help)));
+    }
+
+    #[test]
+    fn confirm_in_time() {
+        let pending = Pending::default();
+        let now = Instant::now();
+
+        defer_at(&pending, user("1"), remove("first"), now);
+        defer_at(&pending, user("1"), remove("second"), now);
+        defer_at(&pending, user("2"), remove("other"), now);
+
+        assert_eq!(None, take_at(&pending, &user("3"), now));
+        assert_eq!(
+            Some(remove("second")),
+            take_at(&pending, &user("1"), now + Duration::from_secs(10))
+        );
+        assert_eq!(None, take_at(&pending, &user("1"), now));
+        assert_eq!(None, take_at(&pending, &user("2"), now + TIMEOUT));
+    }
+
+    #[test]
+    fn denied_requests_untouched() {
+        let pending = Pending::default();
+        let author = AuthorId::Discord(NonZero::new(99).unwrap());
+
+        assert!(matches!(
+            check(&pending, Access::Standard, &author, remove("hello")),
+            Ok(Request::Admin(_))
+        ));
+        assert!(matches!(
+            check(&pending, Access::Admin, &author, remove("hello")),
+            Err(response::Confirm::RemoveCustomCommand(_))
+        ));
+        assert_eq!(
+            Ok(remove("hello")),
+            check(
+                &pending,
+                Access::Admin,
+                &author,
+                Request::Admin(request::Admin::Confirm)
+            )
+        );
+    }
+
+    #[test]
+    fn control_clients_apart() {
+        let pending = Pending::default();
+        let client = |client: &str, owner| AuthorId::Control {
+            client: client.to_owned(),
+            owner,
+        };
+        let confirm = || Request::Admin(request::Admin::Confirm);
+        let remove_admin = || {
+            Request::Owner(request::Owner::Admins(request::Admins::Remove(
+                AdminId::new(5).unwrap(),
+            )))
+        };
+
+        assert!(check(
+            &pending,
+            Access::Owner,
+            &client("owner", true),
+            remove_admin()
+        )
+        .is_err());
+        assert_eq!(
+            Ok(confirm()),
+            check(&pending, Access::Admin, &client("admin", false), confirm())
+        );
+        assert_eq!(
+            Ok(confirm()),
+            check(&pending, Access::Admin, &client("owner", true), confirm())
+        );
+        assert!(check(
+            &pending,
+            Access::Owner,
+            &client("owner", true),
+            remove_admin()
+        )
+        .is_err());
+        assert_eq!(
+            Ok(remove_admin()),
+            check(&pending, Access::Owner, &client("owner", true), confirm())
+        );
+    }
+}
@@ -0,0 +1,266 @@
+//! Evaluation of Rust snippets on the official playground, for the `!eval` command.
+//!
+//! Snippets without a `main` function are treated as an expression, whose value is printed with
+//! its debug representation. The playground is a shared service, so snippets have a size limit
+//! and there's a cooldown per user and across all users.
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::{info, instrument};
+
+use crate::{
+    api::{
+        response::{self, Eval},
+        AuthorId, UserRef,
+    },
+    settings::Eval as EvalSettings,
+};
+
+/// Endpoint of the playground that compiles and runs a program.
+const EXECUTE_URL: &str = "https://play.rust-lang.org/execute";
+
+/// Last time that each user ran a snippet, and the last time that anybody did.
+#[derive(Default)]
+pub(super) struct LastRun {
+    users: HashMap<UserRef, Instant>,
+    any: Option<Instant>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Request<'a> {
+    channel: &'static str,
+    mode: &'static str,
+    edition: &'static str,
+    crate_type: &'static str,
+    tests: bool,
+    backtrace: bool,
+    code: &'a str,
+}
+
+#[derive(Deserialize)]
+struct Response {
+    success: bool,
+    stdout: String,
+    stderr: String,
+}
+
+#[instrument(skip_all)]
+pub async fn eval(
+    last_run: &Mutex<LastRun>,
+    settings: &EvalSettings,
+    author: &AuthorId,
+    code: &str,
+) -> response::User {
+    info!("received `eval` command");
+    response::User::Eval(run(last_run, settings, &author.user_ref(), code).await)
+}
+
+async fn run(
+    last_run: &Mutex<LastRun>,
+    settings: &EvalSettings,
+    user: &UserRef,
+    code: &str,
+) -> Result<Eval> {
+    let code = strip_code_block(code);
+    if code.len() > settings.max_code {
+        return Ok(Eval::TooLong {
+            max: settings.max_code,
+        });
+    }
+
+    if let Err(left) = cooldown(last_run, settings, user, Instant::now()) {
+        return Ok(Eval::Cooldown(left));
+    }
+
+    let resp = reqwest::Client::builder()
+        .user_agent("ToggleBot (https://github.com/dnaka91/togglebot)")
+        .timeout(Duration::from_secs(30))
+        .build()?
+        .post(EXECUTE_URL)
+        .json(&Request {
+            channel: "stable",
+            mode: "debug",
+            edition: "2021",
+            crate_type: "bin",
+            tests: false,
+            backtrace: false,
+            code: &program(code),
+        })
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<Response>()
+        .await?;
+
+    Ok(Eval::Ran {
+        success: resp.success,
+        output: if resp.success {
+            resp.stdout.trim_end().to_owned()
+        } else {
+            compiler_output(&resp.stderr)
+        },
+    })
+}
+
+/// Take the code out of a Markdown code block, if it's wrapped in one.
+fn strip_code_block(code: &str) -> &str {
+    let code = code.trim();
+
+    if let Some(block) = code
+        .strip_prefix("```")
+        .and_then(|code| code.strip_suffix("```"))
+    {
+        // The language tag is only allowed right after the opening fence, on the same line.
+        return match block.split_once('\n') {
+            Some((lang, code)) if !lang.contains(char::is_whitespace) => code.trim(),
+            _ => block.trim(),
+        };
+    }
+
+    code.strip_prefix('`')
+        .and_then(|code| code.strip_suffix('`'))
+        .unwrap_or(code)
+        .trim()
+}
+
+/// Complete program for the snippet, printing the value of the snippet unless it's a whole program
+/// already.
+fn program(code: &str) -> String {
+    if code.contains("fn main") {
+        code.to_owned()
+    } else {
+        format!("fn main() {{\n    println!(\"{{:?}}\", {{\n{code}\n    }});\n}}")
+    }
+}
+
+/// Compiler errors or the panic message, without the build progress that's always printed.
+fn compiler_output(stderr: &str) -> String {
+    stderr
+        .lines()
+        .filter(|line| {
+            let line = line.trim_start();
+            !["Compiling ", "Finished ", "Running "]
+                .iter()
+                .any(|prefix| line.starts_with(prefix))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_owned()
+}
+
+/// Start the cooldowns for the user, or get the remaining time if any of them is still active.
+fn cooldown(
+    last: &Mutex<LastRun>,
+    settings: &EvalSettings,
+    user: &UserRef,
+    now: Instant,
+) -> Result<(), Duration> {
+    let mut last = last.lock().unwrap_or_else(PoisonError::into_inner);
+    let user_cooldown = Duration::from_secs(settings.cooldown);
+    let global_cooldown = Duration::from_secs(settings.global_cooldown);
+
+    last.users
+        .retain(|_, ran| now.duration_since(*ran) < user_cooldown);
+
+    let left = [
+        last.users.get(user).map(|ran| (*ran, user_cooldown)),
+        last.any.map(|ran| (ran, global_cooldown)),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|(ran, cooldown)| cooldown.saturating_sub(now.duration_since(ran)))
+    .max()
+    .unwrap_or_default();
+
+    if !left.is_zero() {
+        return Err(left);
+    }
+
+    last.users.insert(user.clone(), now);
+    last.any = Some(now);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::Source;
+
+    fn user(id: &str) -> UserRef {
+        UserRef {
+            source: Source::Twitch,
+            id: id.to_owned(),
+        }
+    }
+
+    #[test]
+    fn code_blocks() {
+        assert_eq!("1 + 1", strip_code_block("1 + 1"));
+        assert_eq!("1 + 1", strip_code_block("`1 + 1`"));
+        assert_eq!("1 + 1", strip_code_block("```1 + 1```"));
+        assert_eq!(
+            "let a = 1;\na",
+            strip_code_block("```rust\nlet a = 1;\na\n```")
+        );
+        assert_eq!("let a = 1;\na", strip_code_block("```\nlet a = 1;\na\n```"));
+    }
+
+    #[test]
+    fn wrap_expressions() {
+        assert_eq!(
+            "fn main() {\n    println!(\"{:?}\", {\n1 + 1\n    });\n}",
+            program("1 + 1")
+        );
+        assert_eq!(
+            "fn main() { println!(\"hi\"); }",
+            program("fn main() { println!(\"hi\"); }")
+        );
+    }
+
+    #[test]
+    fn strip_build_progress() {
+        let stderr = "   Compiling playground v0.0.1 (/playground)\nerror[E0308]: mismatched \
+                      types\n --> src/main.rs:3:1\n";
+        assert_eq!(
+            "error[E0308]: mismatched types\n --> src/main.rs:3:1",
+            compiler_output(stderr)
+        );
+    }
+
+    #[test]
+    fn cooldowns() {
+        let last = Mutex::default();
+        let settings = EvalSettings {
+            cooldown: 60,
+            global_cooldown: 5,
+            ..EvalSettings::default()
+        };
+        let now = Instant::now();
+
+        assert_eq!(Ok(()), cooldown(&last, &settings, &user("1"), now));
+        assert_eq!(
+            Err(Duration::from_secs(5)),
+            cooldown(&last, &settings, &user("2"), now)
+        );
+        assert_eq!(
+            Ok(()),
+            cooldown(&last, &settings, &user("2"), now + Duration::from_secs(5))
+        );
+        assert_eq!(
+            Err(Duration::from_secs(50)),
+            cooldown(&last, &settings, &user("1"), now + Duration::from_secs(10))
+        );
+        assert_eq!(
+            Ok(()),
+            cooldown(&last, &settings, &user("1"), now + Duration::from_mins(1))
+        );
+    }
+}
@@ -0,0 +1,256 @@
+//! Answers to frequently asked questions, that fire whenever a chat message looks like one.
+//!
+//! Entries recognize their question either by keywords or by a regular expression. Keywords score
+//! by the share of them that appear in a message, as whole words and ignoring case, and the best
+//! entry that reaches the configured threshold is answered. Regular expressions either match or
+//! they don't. Like trigger words, each entry has a cooldown per service.
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use anyhow::{ensure, Result};
+use regex::{Regex, RegexBuilder};
+use tracing::{debug, error};
+
+use crate::{
+    api::{response::Faq, Source},
+    settings::Faq as FaqSettings,
+    state::State,
+};
+
+/// Last time each entry was answered, per service.
+type Answered = Mutex<HashMap<(Source, String), Instant>>;
+
+/// Compiled regular expressions, so they aren't built again for every message.
+type Regexes = Mutex<HashMap<String, Regex>>;
+
+/// Cooldowns and compiled patterns of the entries.
+#[derive(Default)]
+pub(super) struct Cache {
+    answered: Answered,
+    regexes: Regexes,
+}
+
+/// Upper limit for the size of compiled regular expressions, to keep matching cheap.
+const REGEX_SIZE_LIMIT: usize = 1 << 16;
+
+/// Find the entry that fits the message best and get its answer, unless it's on cooldown.
+pub fn answer(
+    cache: &Cache,
+    state: &State,
+    settings: &FaqSettings,
+    source: Source,
+    text: &str,
+) -> Option<String> {
+    let faqs = state
+        .list_faqs()
+        .inspect_err(|e| error!(error = ?e, "failed loading FAQ entries"))
+        .ok()?;
+
+    let faq = best(&cache.regexes, &faqs, text, settings.threshold)?;
+    let cooldown = Duration::from_secs(settings.cooldown);
+    if !cooled_down(&cache.answered, source, &faq.name, cooldown, Instant::now()) {
+        debug!(faq.name, "FAQ entry is on cooldown");
+        return None;
+    }
+
+    Some(faq.answer.clone())
+}
+
+/// Check that the entry can be saved, with a valid name and pattern.
+pub fn validate(faq: &Faq) -> Result<()> {
+    ensure!(
+        super::triggers::is_valid(&faq.name),
+        "FAQ names can only contain letters and digits"
+    );
+
+    if faq.regex {
+        build(&faq.pattern)?;
+    } else {
+        ensure!(
+            keywords(&faq.pattern).all(super::triggers::is_valid),
+            "keywords are separated by commas, and can only contain letters and digits"
+        );
+    }
+
+    Ok(())
+}
+
+/// Pick the entry with the highest score, where earlier entries win ties.
+fn best<'a>(regexes: &Regexes, faqs: &'a [Faq], text: &str, threshold: u8) -> Option<&'a Faq> {
+    faqs.iter()
+        .map(|faq| (score(regexes, faq, text), faq))
+        .filter(|(score, _)| *score >= threshold)
+        .fold(None, |best: Option<(u8, &Faq)>, (score, faq)| match best {
+            Some((best_score, _)) if best_score >= score => best,
+            _ => Some((score, faq)),
+        })
+        .map(|(_, faq)| faq)
+}
+
+/// How well the message fits the entry, in percent.
+fn score(regexes: &Regexes, faq: &Faq, text: &str) -> u8 {
+    if faq.regex {
+        return match regex(regexes, &faq.pattern) {
+            Ok(regex) if regex.is_match(text) => 100,
+            Ok(_) => 0,
+            Err(e) => {
+                error!(error = ?e, faq.name, "invalid FAQ pattern");
+                0
+            }
+        };
+    }
+
+    let words = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>();
+
+    let (total, found) = keywords(&faq.pattern).fold((0_usize, 0_usize), |(total, found), kw| {
+        let contained = words.iter().any(|word| word.eq_ignore_ascii_case(kw));
+        (total + 1, found + usize::from(contained))
+    });
+
+    if total == 0 {
+        return 0;
+    }
+
+    u8::try_from(found * 100 / total).unwrap_or(100)
+}
+
+fn keywords(pattern: &str) -> impl Iterator<Item = &str> {
+    pattern.split(',').map(str::trim)
+}
+
+/// Get the compiled regular expression, building it on first use.
+fn regex(regexes: &Regexes, pattern: &str) -> Result<Regex> {
+    let mut regexes = regexes.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if let Some(regex) = regexes.get(pattern) {
+        return Ok(regex.clone());
+    }
+
+    let regex = build(pattern)?;
+    regexes.insert(pattern.to_owned(), regex.clone());
+
+    Ok(regex)
+}
+
+fn build(pattern: &str) -> Result<Regex> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(Into::into)
+}
+
+/// Check whether the entry's cooldown passed on the service, and remember this time if so.
+fn cooled_down(
+    last: &Answered,
+    source: Source,
+    name: &str,
+    cooldown: Duration,
+    now: Instant,
+) -> bool {
+    let mut last = last.lock().unwrap_or_else(PoisonError::into_inner);
+    let key = (source, name.to_owned());
+
+    if last
+        .get(&key)
+        .is_some_and(|answered| now.duration_since(*answered) < cooldown)
+    {
+        return false;
+    }
+
+    last.insert(key, now);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn faq(name: &str, pattern: &str, regex: bool) -> Faq {
+        Faq {
+            name: name.to_owned(),
+            pattern: pattern.to_owned(),
+            regex,
+            answer: format!("{name}!"),
+        }
+    }
+
+    #[test]
+    fn scoring() {
+        let keyboard = faq("keyboard", "which,keyboard", false);
+        let editor = faq("editor", r"what (editor|ide)", true);
+        let regexes = Regexes::default();
+        let score = |faq, text| score(&regexes, faq, text);
+
+        assert_eq!(100, score(&keyboard, "Which keyboard is that?"));
+        assert_eq!(50, score(&keyboard, "nice keyboard"));
+        assert_eq!(0, score(&keyboard, "keyboards are cool"));
+        assert_eq!(100, score(&editor, "What IDE do you use?"));
+        assert_eq!(0, score(&editor, "editor wars"));
+    }
+
+    #[test]
+    fn best_match() {
+        let faqs = [
+            faq("keyboard", "which,keyboard", false),
+            faq("switches", "keyboard,switches", false),
+            faq("editor", "editor", false),
+        ];
+        let regexes = Regexes::default();
+        let best =
+            |text, threshold| best(&regexes, &faqs, text, threshold).map(|faq| faq.name.as_str());
+
+        assert_eq!(Some("switches"), best("what keyboard switches?", 60));
+        assert_eq!(Some("keyboard"), best("keyboard?", 50));
+        assert_eq!(None, best("keyboard?", 60));
+        assert_eq!(Some("editor"), best("your editor looks great", 60));
+        assert_eq!(None, best("hello there", 1));
+    }
+
+    #[test]
+    fn cooldown() {
+        let last = Answered::default();
+        let now = Instant::now();
+        let cooldown = Duration::from_mins(2);
+
+        assert!(cooled_down(&last, Source::Twitch, "editor", cooldown, now));
+        assert!(cooled_down(
+            &last,
+            Source::Twitch,
+            "keyboard",
+            cooldown,
+            now
+        ));
+        assert!(cooled_down(&last, Source::Discord, "editor", cooldown, now));
+        assert!(!cooled_down(
+            &last,
+            Source::Twitch,
+            "editor",
+            cooldown,
+            now + Duration::from_mins(1)
+        ));
+        assert!(cooled_down(
+            &last,
+            Source::Twitch,
+            "editor",
+            cooldown,
+            now + cooldown
+        ));
+    }
+
+    #[test]
+    fn validation() {
+        assert!(validate(&faq("keyboard", "which, keyboard", false)).is_ok());
+        assert!(validate(&faq("editor", r"what (editor|ide)\b", true)).is_ok());
+        assert!(validate(&faq("two words", "keyboard", false)).is_err());
+        assert!(validate(&faq("keyboard", "which,,keyboard", false)).is_err());
+        assert!(validate(&faq("editor", "what (editor", true)).is_err());
+    }
+}
@@ -0,0 +1,198 @@
+//! Command of the day, that features a random custom command once a day, so people learn about
+//! the ones they didn't know yet.
+
+use std::num::NonZero;
+
+use anyhow::Result;
+use rand::seq::SliceRandom;
+use time::{OffsetDateTime, Time};
+use tracing::{error, info};
+
+use crate::{
+    api::{Post, Source},
+    outbox::Outbox,
+    settings::CommandOfTheDay as CommandOfTheDaySettings,
+    state::State,
+};
+
+/// Pick a random custom command that wasn't featured recently, and post it in the configured
+/// places. Commands are only posted on the services they're available on.
+pub fn feature_command(
+    outbox: &Outbox,
+    state: &State,
+    settings: &CommandOfTheDaySettings,
+    now: OffsetDateTime,
+) {
+    let targets = [
+        (Source::Discord, settings.discord_channel.map(Some)),
+        (Source::Twitch, settings.twitch.then_some(None)),
+    ];
+    let targets = targets
+        .into_iter()
+        .filter_map(|(source, channel)| Some((source, channel?)))
+        .collect::<Vec<_>>();
+
+    let (name, sources) = match pick(state, settings, &targets) {
+        Ok(Some(picked)) => picked,
+        Ok(None) => return,
+        Err(e) => {
+            error!(error = ?e, "failed picking the command of the day");
+            return;
+        }
+    };
+
+    info!(%name, "featuring command of the day");
+
+    if let Err(e) = state.add_featured_command(&name, now, settings.history) {
+        error!(error = ?e, "failed saving the command of the day");
+    }
+
+    for (source, channel) in targets {
+        if !sources.contains(&source) {
+            continue;
+        }
+
+        let post = Post {
+            channel,
+            content: format!("💡 Did you know about !{name}?"),
+        };
+
+        if let Err(e) = outbox.publish(source, post) {
+            error!(error = ?e, %source, "failed posting the command of the day");
+        }
+    }
+}
+
+/// Next moment after `now`, at which the command of the day is posted.
+#[must_use]
+pub fn next_featured_command(
+    settings: &CommandOfTheDaySettings,
+    now: OffsetDateTime,
+) -> OffsetDateTime {
+    let now = now.to_offset(settings.utc_offset);
+    let today = now.replace_time(Time::from_hms(settings.hour, 0, 0).unwrap_or(Time::MIDNIGHT));
+
+    if today > now {
+        today
+    } else {
+        today + time::Duration::DAY
+    }
+}
+
+/// Random custom command for any of the targets, together with the services it's available on.
+fn pick(
+    state: &State,
+    settings: &CommandOfTheDaySettings,
+    targets: &[(Source, Option<NonZero<u64>>)],
+) -> Result<Option<(String, Vec<Source>)>> {
+    let mut commands = Vec::<(String, Vec<Source>)>::new();
+    for (name, source) in state.list_custom_commands()? {
+        if !targets.iter().any(|(target, _)| *target == source) {
+            continue;
+        }
+
+        match commands.iter_mut().find(|(existing, _)| *existing == name) {
+            Some((_, sources)) => sources.push(source),
+            None => commands.push((name, vec![source])),
+        }
+    }
+
+    let recent = state.recent_featured_commands(settings.history)?;
+    Ok(choose(commands, &recent))
+}
+
+/// Choose randomly among the commands that weren't featured recently. If all of them were, the
+/// recent ones are considered again, so there is always something to post.
+fn choose<T>(commands: Vec<(String, T)>, recent: &[String]) -> Option<(String, T)> {
+    let (mut fresh, mut stale) = commands
+        .into_iter()
+        .partition::<Vec<_>, _>(|(name, _)| !recent.contains(name));
+    let candidates = if fresh.is_empty() {
+        &mut stale
+    } else {
+        &mut fresh
+    };
+
+    candidates.shuffle(&mut rand::thread_rng());
+    candidates.pop()
+}
+
+#[cfg(test)]
+mod tests {
+    use time::{macros::datetime, UtcOffset};
+
+    use super::*;
+    use crate::api::AuthorId;
+
+    #[test]
+    fn skip_recent_commands() {
+        let commands = || vec![("a".to_owned(), ()), ("b".to_owned(), ())];
+
+        for _ in 0..10 {
+            assert_eq!(
+                Some("b".to_owned()),
+                choose(commands(), &["a".to_owned()]).map(|(name, ())| name)
+            );
+        }
+        assert!(choose(commands(), &["a".to_owned(), "b".to_owned()]).is_some());
+        assert!(choose(Vec::<(String, ())>::new(), &[]).is_none());
+    }
+
+    #[test]
+    fn only_available_commands() {
+        let state = State::in_memory().unwrap();
+        state
+            .add_custom_command(
+                Source::Twitch,
+                "a",
+                "A",
+                &AuthorId::Console.user_ref(),
+                OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+        state
+            .add_custom_command(
+                Source::Discord,
+                "b",
+                "B",
+                &AuthorId::Console.user_ref(),
+                OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+        state
+            .add_custom_command(
+                Source::Twitch,
+                "b",
+                "B",
+                &AuthorId::Console.user_ref(),
+                OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+
+        let settings = CommandOfTheDaySettings::default();
+        let targets = [(Source::Discord, NonZero::new(1))];
+
+        assert_eq!(
+            Some(("b".to_owned(), vec![Source::Discord])),
+            pick(&state, &settings, &targets).unwrap()
+        );
+    }
+
+    #[test]
+    fn next_post() {
+        let settings = CommandOfTheDaySettings {
+            hour: 18,
+            utc_offset: UtcOffset::from_hms(2, 0, 0).unwrap(),
+            ..CommandOfTheDaySettings::default()
+        };
+
+        assert_eq!(
+            datetime!(2025-03-01 18:00 +2),
+            next_featured_command(&settings, datetime!(2025-03-01 10:00 UTC))
+        );
+        assert_eq!(
+            datetime!(2025-03-02 18:00 +2),
+            next_featured_command(&settings, datetime!(2025-03-01 16:00 UTC))
+        );
+    }
+}
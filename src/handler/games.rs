@@ -0,0 +1,459 @@
+//! Mini-games, where users bet the points they collected by chatting.
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+use rand::Rng;
+use tracing::{info, instrument};
+
+use crate::{
+    api::{
+        response::{self, Duel, Gamble},
+        Author, AuthorId, Source, UserRef,
+    },
+    settings::Games as GameSettings,
+    state::State,
+};
+
+/// Time that the challenged user has to accept a duel.
+pub const ACCEPT_TIMEOUT: Duration = Duration::from_mins(1);
+
+/// Last time that each user played any of the games, to enforce a break between two games.
+type LastPlayed = Mutex<HashMap<UserRef, Instant>>;
+
+/// Open challenges of each challenged user. Every user has at most one, a newer one replaces the
+/// older.
+type Challenges = Mutex<HashMap<UserRef, Challenge>>;
+
+/// Cooldowns and open challenges of the games.
+#[derive(Default)]
+pub(super) struct Games {
+    last_played: LastPlayed,
+    challenges: Challenges,
+}
+
+/// Duel that waits for the opponent to accept it, before any points are at stake.
+struct Challenge {
+    /// The user that sent the challenge.
+    challenger: UserRef,
+    /// Name of the challenger, as shown in the chat.
+    name: String,
+    /// Points that the winner takes from the loser.
+    amount: u64,
+    /// Time that the challenge was sent.
+    sent: Instant,
+}
+
+#[instrument(skip_all)]
+pub fn gamble(
+    games: &Games,
+    state: &State,
+    settings: &GameSettings,
+    author: &AuthorId,
+    amount: u64,
+) -> response::User {
+    info!("received `gamble` command");
+
+    let won = rand::thread_rng().gen_range(0..100) < settings.win_chance;
+    response::User::Gamble(play_gamble(
+        state,
+        &Cooldown::new(&games.last_played, settings.cooldown),
+        &author.user_ref(),
+        amount,
+        won,
+    ))
+}
+
+fn play_gamble(
+    state: &State,
+    cooldown: &Cooldown<'_>,
+    user: &UserRef,
+    amount: u64,
+    won: bool,
+) -> Result<Gamble> {
+    if amount == 0 {
+        return Ok(Gamble::Refused(
+            "You have to bet at least 1 point".to_owned(),
+        ));
+    }
+
+    let balance = state.points(user)?;
+    if balance < amount {
+        return Ok(Gamble::Refused(format!(
+            "You don't have enough points, you only have {balance}"
+        )));
+    }
+
+    if let Err(left) = cooldown.start(user, Instant::now()) {
+        return Ok(Gamble::Refused(slow_down(left)));
+    }
+
+    Ok(match state.wager_points(user, amount, won)? {
+        Some(balance) if won => Gamble::Won { amount, balance },
+        Some(balance) => Gamble::Lost { amount, balance },
+        None => Gamble::Refused("You spent your points in the meantime".to_owned()),
+    })
+}
+
+#[instrument(skip_all)]
+pub fn duel(
+    games: &Games,
+    state: &State,
+    settings: &GameSettings,
+    source: Source,
+    author: &Author,
+    target: &str,
+    amount: u64,
+) -> response::User {
+    info!("received `duel` command");
+
+    response::User::Duel(play_duel(
+        state,
+        &Cooldown::new(&games.last_played, settings.cooldown),
+        &games.challenges,
+        source,
+        Challenge {
+            challenger: author.id.user_ref(),
+            name: author.name.clone(),
+            amount,
+            sent: Instant::now(),
+        },
+        target,
+    ))
+}
+
+/// Check that the duel can happen, and leave it to the opponent to accept it.
+fn play_duel(
+    state: &State,
+    cooldown: &Cooldown<'_>,
+    challenges: &Challenges,
+    source: Source,
+    challenge: Challenge,
+    target: &str,
+) -> Result<Duel> {
+    let amount = challenge.amount;
+    if amount == 0 {
+        return Ok(Duel::Refused("You have to bet at least 1 point".to_owned()));
+    }
+
+    let Some(opponent) = find_opponent(state, source, target)? else {
+        return Ok(Duel::Refused(format!(
+            "I don't know {target} yet, they have to chat first"
+        )));
+    };
+
+    if opponent == challenge.challenger {
+        return Ok(Duel::Refused("You can't duel yourself".to_owned()));
+    }
+
+    let balance = state.points(&challenge.challenger)?;
+    if balance < amount {
+        return Ok(Duel::Refused(format!(
+            "You don't have enough points, you only have {balance}"
+        )));
+    }
+
+    if state.points(&opponent)? < amount {
+        return Ok(Duel::Refused(format!(
+            "{target} doesn't have enough points for that"
+        )));
+    }
+
+    if let Err(left) = cooldown.start(&challenge.challenger, challenge.sent) {
+        return Ok(Duel::Refused(slow_down(left)));
+    }
+
+    let mut challenges = challenges.lock().unwrap_or_else(PoisonError::into_inner);
+    challenges.retain(|_, open| challenge.sent.duration_since(open.sent) < ACCEPT_TIMEOUT);
+    challenges.insert(opponent, challenge);
+
+    Ok(Duel::Challenged {
+        opponent: target.to_owned(),
+        amount,
+    })
+}
+
+#[instrument(skip_all)]
+pub fn accept(
+    games: &Games,
+    state: &State,
+    settings: &GameSettings,
+    author: &AuthorId,
+) -> response::User {
+    info!("received `accept` command");
+
+    let won = duel_won(&mut rand::thread_rng(), settings.duel_win_chance);
+    response::User::Duel(play_accept(
+        state,
+        &games.challenges,
+        &author.user_ref(),
+        Instant::now(),
+        won,
+    ))
+}
+
+/// Decide whether the challenged user wins the duel, with a chance between 0.0 and 1.0 that is
+/// checked when loading the settings.
+fn duel_won(rng: &mut impl Rng, chance: f64) -> bool {
+    rng.gen_bool(chance)
+}
+
+/// Fight out the open challenge of the opponent, where `won` is the outcome from the view of the
+/// opponent.
+fn play_accept(
+    state: &State,
+    challenges: &Challenges,
+    opponent: &UserRef,
+    now: Instant,
+    won: bool,
+) -> Result<Duel> {
+    let challenge = challenges
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .remove(opponent)
+        .filter(|challenge| now.duration_since(challenge.sent) < ACCEPT_TIMEOUT);
+    let Some(Challenge {
+        challenger,
+        name,
+        amount,
+        ..
+    }) = challenge
+    else {
+        return Ok(Duel::Refused(
+            "Nobody challenged you to a duel, or it expired already".to_owned(),
+        ));
+    };
+
+    let (winner, loser) = if won {
+        (opponent, &challenger)
+    } else {
+        (&challenger, opponent)
+    };
+
+    if !state.duel_points(winner, loser, amount)? {
+        return Ok(Duel::Refused(
+            "Somebody spent their points in the meantime, try again".to_owned(),
+        ));
+    }
+
+    let opponent = name;
+    Ok(if won {
+        Duel::Won { opponent, amount }
+    } else {
+        Duel::Lost { opponent, amount }
+    })
+}
+
+/// Find the challenged user, either by Discord mention or by the name they're chatting with.
+fn find_opponent(state: &State, source: Source, target: &str) -> Result<Option<UserRef>> {
+    if source == Source::Discord {
+        if let Ok(user) = target.parse::<UserRef>() {
+            return Ok(Some(user));
+        }
+    }
+
+    state.find_points_user(source, &target.trim_start_matches('@').to_lowercase())
+}
+
+fn slow_down(left: Duration) -> String {
+    format!(
+        "Slow down, you can play again in {}s",
+        left.as_secs().max(1)
+    )
+}
+
+/// Break between two games of the same user.
+struct Cooldown<'a> {
+    last_played: &'a LastPlayed,
+    duration: Duration,
+}
+
+impl<'a> Cooldown<'a> {
+    fn new(last_played: &'a LastPlayed, secs: u64) -> Self {
+        Self {
+            last_played,
+            duration: Duration::from_secs(secs),
+        }
+    }
+
+    /// Start the cooldown for the user, or get the remaining time if it's still active.
+    fn start(&self, user: &UserRef, now: Instant) -> Result<(), Duration> {
+        let mut last_played = self
+            .last_played
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        last_played.retain(|_, played| now.duration_since(*played) < self.duration);
+
+        if let Some(played) = last_played.get(user) {
+            return Err(self.duration.saturating_sub(now.duration_since(*played)));
+        }
+
+        last_played.insert(user.clone(), now);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+    use time::OffsetDateTime;
+
+    use super::*;
+
+    fn user(id: &str) -> UserRef {
+        UserRef {
+            source: Source::Twitch,
+            id: id.to_owned(),
+        }
+    }
+
+    fn state() -> State {
+        let state = State::in_memory().unwrap();
+        let now = OffsetDateTime::now_utc();
+        for (id, name, points) in [("1", "first", 10), ("2", "second", 5)] {
+            state
+                .earn_points(&user(id), name, points, now, Duration::ZERO)
+                .unwrap();
+        }
+        state
+    }
+
+    #[test]
+    fn gamble_outcomes() {
+        let state = state();
+        let last_played = Mutex::default();
+        let cooldown = Cooldown::new(&last_played, 0);
+
+        assert!(matches!(
+            play_gamble(&state, &cooldown, &user("1"), 4, true).unwrap(),
+            Gamble::Won {
+                amount: 4,
+                balance: 14
+            }
+        ));
+        assert!(matches!(
+            play_gamble(&state, &cooldown, &user("1"), 10, false).unwrap(),
+            Gamble::Lost {
+                amount: 10,
+                balance: 4
+            }
+        ));
+        assert!(matches!(
+            play_gamble(&state, &cooldown, &user("1"), 5, true).unwrap(),
+            Gamble::Refused(_)
+        ));
+    }
+
+    #[test]
+    fn gamble_cooldown() {
+        let state = state();
+        let last_played = Mutex::default();
+        let cooldown = Cooldown::new(&last_played, 60);
+
+        assert!(matches!(
+            play_gamble(&state, &cooldown, &user("1"), 1, true).unwrap(),
+            Gamble::Won { .. }
+        ));
+        assert!(matches!(
+            play_gamble(&state, &cooldown, &user("1"), 1, true).unwrap(),
+            Gamble::Refused(_)
+        ));
+        assert_eq!(11, state.points(&user("1")).unwrap());
+    }
+
+    #[test]
+    fn duel_outcomes() {
+        let state = state();
+        let last_played = Mutex::default();
+        let challenges = Challenges::default();
+        let cooldown = Cooldown::new(&last_played, 0);
+        let now = Instant::now();
+        let duel = |target: &str, amount| {
+            play_duel(
+                &state,
+                &cooldown,
+                &challenges,
+                Source::Twitch,
+                Challenge {
+                    challenger: user("1"),
+                    name: "first".to_owned(),
+                    amount,
+                    sent: now,
+                },
+                target,
+            )
+            .unwrap()
+        };
+        let accept = |id, won| play_accept(&state, &challenges, &user(id), now, won).unwrap();
+
+        assert!(matches!(duel("@nobody", 1), Duel::Refused(_)));
+        assert!(matches!(duel("@first", 1), Duel::Refused(_)));
+        assert!(matches!(duel("@second", 6), Duel::Refused(_)));
+        assert!(matches!(
+            duel("@Second", 5),
+            Duel::Challenged { amount: 5, .. }
+        ));
+        assert_eq!(5, state.points(&user("2")).unwrap());
+
+        assert!(matches!(accept("1", true), Duel::Refused(_)));
+        assert!(matches!(
+            accept("2", false),
+            Duel::Lost { amount: 5, ref opponent } if opponent == "first"
+        ));
+        assert!(matches!(accept("2", true), Duel::Refused(_)));
+        assert_eq!(
+            (15, 0),
+            (
+                state.points(&user("1")).unwrap(),
+                state.points(&user("2")).unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn duel_win_chance() {
+        let mut rng = StdRng::seed_from_u64(1597);
+        let mut wins = |chance| (0..1000).filter(|_| duel_won(&mut rng, chance)).count();
+
+        assert_eq!(0, wins(0.0));
+        assert_eq!(1000, wins(1.0));
+        assert!((750..850).contains(&wins(0.8)));
+        assert!((150..250).contains(&wins(0.2)));
+    }
+
+    #[test]
+    fn duel_expired() {
+        let state = state();
+        let last_played = Mutex::default();
+        let challenges = Challenges::default();
+        let now = Instant::now();
+
+        assert!(matches!(
+            play_duel(
+                &state,
+                &Cooldown::new(&last_played, 0),
+                &challenges,
+                Source::Twitch,
+                Challenge {
+                    challenger: user("1"),
+                    name: "first".to_owned(),
+                    amount: 5,
+                    sent: now,
+                },
+                "second",
+            )
+            .unwrap(),
+            Duel::Challenged { .. }
+        ));
+        assert!(matches!(
+            play_accept(&state, &challenges, &user("2"), now + ACCEPT_TIMEOUT, true).unwrap(),
+            Duel::Refused(_)
+        ));
+        assert_eq!(10, state.points(&user("1")).unwrap());
+    }
+}
@@ -0,0 +1,330 @@
+//! Notifications about releases, issues and pull requests of GitHub repositories, posted to
+//! Discord.
+//!
+//! Repositories are polled through the REST API, and every item that was seen is remembered in the
+//! database, so nothing is announced twice, not even across restarts. The first time a repository
+//! is checked, its existing items are only remembered, so adding a repository doesn't flood the
+//! channel with its whole history.
+
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use reqwest::header;
+use serde::{de::IgnoredAny, Deserialize};
+use tracing::{error, info};
+
+use crate::{
+    api::{response::GitHubIssue, Post, Source},
+    outbox::Outbox,
+    settings::{GitHub as GitHubSettings, GitHubRepo, Issues as IssuesSettings},
+    state::State,
+};
+
+/// Location of the GitHub REST API.
+const API_URL: &str = "https://api.github.com";
+/// Amount of items to look at in each check, which only needs to cover what happens in between.
+const PER_PAGE: u8 = 30;
+
+/// IDs of items, with their announcement unless they shouldn't be announced.
+type Items = Vec<(i64, Option<String>)>;
+
+/// Kind of items, that are remembered separately.
+#[derive(Clone, Copy)]
+enum Kind {
+    Release,
+    /// Issues and pull requests, which share their numbers and the API to list them.
+    Issue,
+}
+
+impl Kind {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Release => "release",
+            Self::Issue => "issue",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Release {
+    id: i64,
+    tag_name: String,
+    name: Option<String>,
+    html_url: String,
+    draft: bool,
+}
+
+#[derive(Deserialize)]
+struct Issue {
+    id: i64,
+    number: u64,
+    title: String,
+    html_url: String,
+    user: User,
+    pull_request: Option<IgnoredAny>,
+}
+
+#[derive(Deserialize)]
+struct User {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct CreatedIssue {
+    number: u64,
+    html_url: String,
+}
+
+/// Check all configured repositories for news, and post them in each repository's channel.
+pub async fn announce_github(outbox: &Outbox, state: &State, settings: &GitHubSettings) {
+    let client = match client(settings.token.as_deref()) {
+        Ok(client) => client,
+        Err(e) => {
+            error!(error = ?e, "failed creating HTTP client");
+            return;
+        }
+    };
+
+    for repo in &settings.repos {
+        let kinds = [
+            (Kind::Release, repo.releases),
+            (Kind::Issue, repo.issues || repo.pulls),
+        ];
+
+        for (kind, enabled) in kinds {
+            if !enabled {
+                continue;
+            }
+
+            if let Err(e) = check(outbox, state, &client, repo, kind).await {
+                error!(error = ?e, repo = repo.name, "failed checking repository");
+            }
+        }
+    }
+}
+
+async fn check(
+    outbox: &Outbox,
+    state: &State,
+    client: &reqwest::Client,
+    repo: &GitHubRepo,
+    kind: Kind,
+) -> Result<()> {
+    let items = match kind {
+        Kind::Release => releases(client, repo).await?,
+        Kind::Issue => issues(client, repo).await?,
+    };
+    let known = state.has_github_events(&repo.name, kind.as_str())?;
+
+    // The API lists the newest items first, but they're announced in the order they happened.
+    for (id, content) in items.into_iter().rev() {
+        if !state.add_github_event(&repo.name, kind.as_str(), id)? || !known {
+            continue;
+        }
+        let Some(content) = content else { continue };
+
+        info!(
+            repo = repo.name,
+            kind = kind.as_str(),
+            id,
+            "announcing GitHub news"
+        );
+
+        let post = Post {
+            channel: Some(repo.channel),
+            content,
+        };
+        if let Err(e) = outbox.publish(Source::Discord, post) {
+            error!(error = ?e, repo = repo.name, "failed announcing GitHub news");
+        }
+    }
+
+    Ok(())
+}
+
+/// Published releases, with their announcement. Drafts are left out, so they're announced once
+/// they're published.
+async fn releases(client: &reqwest::Client, repo: &GitHubRepo) -> Result<Items> {
+    let releases = fetch::<Release>(client, &format!("repos/{}/releases", repo.name)).await?;
+
+    Ok(releases
+        .into_iter()
+        .filter(|release| !release.draft)
+        .map(|release| (release.id, Some(release_text(&repo.name, &release))))
+        .collect())
+}
+
+/// Recently opened issues and pull requests, with their announcement if it's enabled for their
+/// kind.
+async fn issues(client: &reqwest::Client, repo: &GitHubRepo) -> Result<Items> {
+    let issues = fetch::<Issue>(
+        client,
+        &format!(
+            "repos/{}/issues?state=all&sort=created&direction=desc",
+            repo.name
+        ),
+    )
+    .await?;
+
+    Ok(issues
+        .into_iter()
+        .map(|issue| {
+            let pull = issue.pull_request.is_some();
+            let enabled = if pull { repo.pulls } else { repo.issues };
+            (issue.id, enabled.then(|| issue_text(&repo.name, &issue)))
+        })
+        .collect())
+}
+
+fn release_text(repo: &str, release: &Release) -> String {
+    let name = release
+        .name
+        .as_deref()
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or(&release.tag_name);
+
+    format!("🚀 **{repo}** released **{name}**\n{}", release.html_url)
+}
+
+fn issue_text(repo: &str, issue: &Issue) -> String {
+    let (icon, kind) = if issue.pull_request.is_some() {
+        ('🔀', "pull request")
+    } else {
+        ('🐛', "issue")
+    };
+
+    format!(
+        "{icon} New {kind} #{} in **{repo}** by {}: {}\n{}",
+        issue.number, issue.user.login, issue.title, issue.html_url
+    )
+}
+
+/// Create an issue in the configured repository.
+pub(super) async fn create_issue(
+    settings: &IssuesSettings,
+    title: &str,
+    body: &str,
+) -> Result<GitHubIssue> {
+    let created = client(Some(&settings.token))?
+        .post(format!("{API_URL}/repos/{}/issues", settings.repo))
+        .json(&serde_json::json!({ "title": title, "body": body }))
+        .send()
+        .await?
+        .error_for_status()?
+        .json::<CreatedIssue>()
+        .await?;
+
+    Ok(GitHubIssue {
+        number: created.number,
+        url: created.html_url,
+        title: title.to_owned(),
+    })
+}
+
+fn client(token: Option<&str>) -> Result<reqwest::Client> {
+    let mut headers = header::HeaderMap::new();
+    headers.insert(
+        header::ACCEPT,
+        header::HeaderValue::from_static("application/vnd.github+json"),
+    );
+    headers.insert(
+        "X-GitHub-Api-Version",
+        header::HeaderValue::from_static("2022-11-28"),
+    );
+    if let Some(token) = token {
+        let mut value = header::HeaderValue::from_str(&format!("Bearer {token}"))
+            .context("invalid GitHub token")?;
+        value.set_sensitive(true);
+        headers.insert(header::AUTHORIZATION, value);
+    }
+
+    reqwest::Client::builder()
+        .user_agent("ToggleBot (https://github.com/dnaka91/togglebot)")
+        .default_headers(headers)
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(Into::into)
+}
+
+async fn fetch<T: for<'de> Deserialize<'de>>(
+    client: &reqwest::Client,
+    path: &str,
+) -> Result<Vec<T>> {
+    let separator = if path.contains('?') { '&' } else { '?' };
+
+    client
+        .get(format!("{API_URL}/{path}{separator}per_page={PER_PAGE}"))
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await
+        .map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_issues() {
+        let issues = serde_json::from_str::<Vec<Issue>>(
+            r#"[
+                {
+                    "id": 2,
+                    "number": 12,
+                    "title": "Add !watchtime",
+                    "html_url": "https://github.com/togglebit/togglebot/pull/12",
+                    "user": { "login": "dnaka91" },
+                    "pull_request": { "url": "https://api.github.com/repos/togglebit/togglebot/pulls/12" }
+                },
+                {
+                    "id": 1,
+                    "number": 11,
+                    "title": "Bot crashes on start",
+                    "html_url": "https://github.com/togglebit/togglebot/issues/11",
+                    "user": { "login": "togglebit" }
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            "🔀 New pull request #12 in **togglebit/togglebot** by dnaka91: Add !watchtime\n\
+             https://github.com/togglebit/togglebot/pull/12",
+            issue_text("togglebit/togglebot", &issues[0])
+        );
+        assert_eq!(
+            "🐛 New issue #11 in **togglebit/togglebot** by togglebit: Bot crashes on start\n\
+             https://github.com/togglebit/togglebot/issues/11",
+            issue_text("togglebit/togglebot", &issues[1])
+        );
+    }
+
+    #[test]
+    fn release_names() {
+        let release = |name: Option<&str>| Release {
+            id: 1,
+            tag_name: "v1.0.0".to_owned(),
+            name: name.map(ToOwned::to_owned),
+            html_url: "https://github.com/togglebit/togglebot/releases/tag/v1.0.0".to_owned(),
+            draft: false,
+        };
+
+        assert_eq!(
+            "🚀 **togglebit/togglebot** released **First release**\n\
+             https://github.com/togglebit/togglebot/releases/tag/v1.0.0",
+            release_text("togglebit/togglebot", &release(Some("First release")))
+        );
+        assert_eq!(
+            "🚀 **togglebit/togglebot** released **v1.0.0**\n\
+             https://github.com/togglebit/togglebot/releases/tag/v1.0.0",
+            release_text("togglebit/togglebot", &release(Some(" ")))
+        );
+        assert_eq!(
+            "🚀 **togglebit/togglebot** released **v1.0.0**\n\
+             https://github.com/togglebit/togglebot/releases/tag/v1.0.0",
+            release_text("togglebit/togglebot", &release(None))
+        );
+    }
+}
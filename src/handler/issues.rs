@@ -0,0 +1,187 @@
+//! GitHub issues, that admins create from the chat, suggestions or single chat messages.
+
+use anyhow::{Context, Result};
+use time::OffsetDateTime;
+use tracing::{info, instrument};
+
+use super::github;
+use crate::{
+    api::{
+        request,
+        response::{self, Issue, Suggestion},
+        AuthorId,
+    },
+    settings::Commands as CommandSettings,
+    state::State,
+};
+
+/// Longest title of an issue that is made from a suggestion or chat message. The rest of the text
+/// is still part of the body.
+const MAX_TITLE_LEN: usize = 80;
+
+#[instrument(skip_all)]
+pub async fn issue(
+    settings: &CommandSettings,
+    state: &State,
+    author: &AuthorId,
+    req: request::Issue,
+    now: OffsetDateTime,
+) -> response::Admin {
+    info!("received `issue` command");
+    response::Admin::Issue(create(settings, state, author, req, now).await)
+}
+
+async fn create(
+    settings: &CommandSettings,
+    state: &State,
+    author: &AuthorId,
+    req: request::Issue,
+    now: OffsetDateTime,
+) -> Result<Issue> {
+    let settings = settings
+        .issues
+        .as_ref()
+        .context("no GitHub repository is configured for issues")?;
+
+    let (title, body, suggestion, message) = match req {
+        request::Issue::New { title, body } => (title, body.unwrap_or_default(), None, None),
+        request::Issue::Suggestion(id) => {
+            if let Some(issue) = state.suggestion_issue(id)? {
+                return Ok(Issue::Existing(issue));
+            }
+            let suggestion = state
+                .suggestion(id)?
+                .with_context(|| format!("no suggestion with ID #{id} exists"))?;
+            let (title, body) = from_suggestion(&suggestion);
+            (title, body, Some(id), None)
+        }
+        request::Issue::Message {
+            author,
+            content,
+            link,
+        } => {
+            let (title, body) = from_message(&author, &content, &link);
+            (title, body, None, Some(link))
+        }
+    };
+
+    let issue = github::create_issue(settings, &title, &body).await?;
+    info!(
+        repo = settings.repo,
+        number = issue.number,
+        "created GitHub issue"
+    );
+
+    state.add_github_issue(
+        &settings.repo,
+        &issue,
+        suggestion,
+        message.as_deref(),
+        &author.user_ref(),
+        now,
+    )?;
+
+    Ok(Issue::Created(issue))
+}
+
+fn from_suggestion(suggestion: &Suggestion) -> (String, String) {
+    (
+        format!("Suggestion: {}", title(&suggestion.content)),
+        format!(
+            "{}\n\n---\nSuggested by {} on {} at {}, as suggestion #{}.",
+            suggestion.content,
+            suggestion.name,
+            suggestion.source,
+            suggestion.created_at.date(),
+            suggestion.id
+        ),
+    )
+}
+
+fn from_message(author: &str, content: &str, link: &str) -> (String, String) {
+    let quote = content
+        .lines()
+        .map(|line| format!("> {line}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    (
+        title(content),
+        format!("{quote}\n\n---\nPosted by {author} in [the chat]({link})."),
+    )
+}
+
+/// Title from the first line of the text, cut off if it's too long.
+fn title(text: &str) -> String {
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+
+    if line.chars().count() <= MAX_TITLE_LEN {
+        line.to_owned()
+    } else {
+        let mut short = line.chars().take(MAX_TITLE_LEN - 1).collect::<String>();
+        short.push('…');
+        short
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::api::{Source, SuggestionStatus};
+
+    #[test]
+    fn issue_texts() {
+        let suggestion = Suggestion {
+            id: 3,
+            source: Source::Twitch,
+            user_id: "1".to_owned(),
+            name: "viewer".to_owned(),
+            content: "A Rust quiz night\nwith prizes".to_owned(),
+            status: SuggestionStatus::Open,
+            reason: None,
+            created_at: datetime!(2025-03-01 18:00 UTC),
+        };
+        assert_eq!(
+            (
+                "Suggestion: A Rust quiz night".to_owned(),
+                "A Rust quiz night\nwith prizes\n\n---\nSuggested by viewer on Twitch at \
+                 2025-03-01, as suggestion #3."
+                    .to_owned()
+            ),
+            from_suggestion(&suggestion)
+        );
+
+        let (title, body) = from_message(
+            "someone",
+            &format!("{}\nsecond line", "x".repeat(100)),
+            "https://discord.com/channels/1/2/3",
+        );
+        assert_eq!(MAX_TITLE_LEN, title.chars().count());
+        assert!(title.ends_with('…'));
+        assert!(body.starts_with(&format!("> {}\n> second line\n\n", "x".repeat(100))));
+        assert!(
+            body.ends_with("Posted by someone in [the chat](https://discord.com/channels/1/2/3).")
+        );
+    }
+
+    #[tokio::test]
+    async fn unconfigured() {
+        let state = State::in_memory().unwrap();
+        let res = create(
+            &CommandSettings::default(),
+            &state,
+            &AuthorId::Twitch("1".to_owned()),
+            request::Issue::Suggestion(1),
+            OffsetDateTime::UNIX_EPOCH,
+        )
+        .await;
+
+        assert!(res.is_err());
+    }
+}
@@ -0,0 +1,285 @@
+//! Health checks for the links of the settings and custom commands, on demand with `!checklinks`
+//! and regularly in the background.
+//!
+//! Each address is requested with `HEAD`, following redirects, and counts as dead if it fails or
+//! ends in an error status. Servers that don't support `HEAD` get a plain `GET` instead.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    fmt::Write,
+    num::NonZero,
+    time::Duration,
+};
+
+use anyhow::Result;
+use futures_util::{stream, StreamExt};
+use reqwest::{redirect, StatusCode};
+use tracing::{error, info, instrument, warn};
+
+use super::alerts;
+use crate::{
+    api::response::{self, DeadLink, LinkOrigin},
+    connector::Connectors,
+    outbox::Outbox,
+    settings::Commands as CommandSettings,
+    state::State,
+};
+
+/// Amount of links that are requested at the same time.
+const CONCURRENCY: usize = 8;
+
+/// Characters around a link in a message, that aren't part of the address.
+const TRAILING: &[char] = &[
+    '.', ',', ';', ':', '!', '?', ')', ']', '>', '"', '\'', '*', '_', '`',
+];
+
+#[instrument(skip_all)]
+pub async fn check_links(settings: &CommandSettings, state: &State) -> response::Admin {
+    info!("received `checklinks` command");
+
+    response::Admin::CheckLinks(dead_links(&settings.links, state).await)
+}
+
+/// Dead links that were already sent to the admins, and aren't sent again until they work again.
+#[derive(Default)]
+pub struct ReportedLinks(HashSet<String>);
+
+/// Check all links, and send the ones that died since the last check to the places of the usage
+/// alerts.
+pub async fn alert_dead_links(
+    connectors: &Connectors,
+    outbox: &Outbox,
+    state: &State,
+    settings: &CommandSettings,
+    owners: &[NonZero<u64>],
+    reported: &mut ReportedLinks,
+) {
+    let dead = match dead_links(&settings.links, state).await {
+        Ok(dead) => dead,
+        Err(e) => {
+            error!(error = ?e, "failed checking links");
+            return;
+        }
+    };
+
+    let fresh = fresh(&dead, &reported.0);
+    reported.0 = dead.into_iter().map(|link| link.url).collect();
+
+    if fresh.is_empty() {
+        return;
+    }
+
+    warn!(count = fresh.len(), "found dead links");
+    let content = message(&fresh);
+    alerts::notify(
+        connectors,
+        outbox,
+        state,
+        &settings.usage_alerts,
+        owners,
+        &content,
+    )
+    .await;
+}
+
+/// Check the links of the settings and all custom commands, returning the ones that are dead.
+async fn dead_links(links: &HashMap<String, String>, state: &State) -> Result<Vec<DeadLink>> {
+    let targets = targets(links, state)?;
+    let client = client()?;
+
+    let unique = targets
+        .iter()
+        .map(|(_, url)| url.clone())
+        .collect::<BTreeSet<_>>();
+    let failures = stream::iter(unique)
+        .map(|url| {
+            let client = client.clone();
+            async move {
+                let reason = probe(&client, &url).await;
+                (url, reason)
+            }
+        })
+        .buffer_unordered(CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .filter_map(|(url, reason)| reason.map(|reason| (url, reason)))
+        .collect::<HashMap<_, _>>();
+
+    Ok(targets
+        .iter()
+        .filter_map(|(origin, url)| {
+            failures.get(url).map(|reason| DeadLink {
+                origin: origin.clone(),
+                url: url.clone(),
+                reason: reason.clone(),
+            })
+        })
+        .collect())
+}
+
+/// All links to check, with the place that they are used in. The settings come first, ordered by
+/// name, followed by the custom commands.
+fn targets(links: &HashMap<String, String>, state: &State) -> Result<Vec<(LinkOrigin, String)>> {
+    let mut targets = links
+        .iter()
+        .collect::<BTreeMap<_, _>>()
+        .into_iter()
+        .map(|(name, url)| (LinkOrigin::Links(name.clone()), url.clone()))
+        .collect::<Vec<_>>();
+
+    for cmd in state.list_custom_command_details()? {
+        targets.extend(urls(&cmd.content).map(|url| {
+            let origin = LinkOrigin::Command {
+                name: cmd.name.clone(),
+                source: cmd.source,
+            };
+            (origin, url.to_owned())
+        }));
+    }
+
+    Ok(targets)
+}
+
+/// Find all web addresses in the text, without any punctuation that surrounds them.
+fn urls(text: &str) -> impl Iterator<Item = &str> {
+    text.split_whitespace().filter_map(|word| {
+        let start = word.find("https://").or_else(|| word.find("http://"))?;
+        let url = word[start..].trim_end_matches(TRAILING);
+        url.split_once("://")
+            .is_some_and(|(_, rest)| !rest.is_empty())
+            .then_some(url)
+    })
+}
+
+/// Request the address, returning why it counts as dead, or nothing if it works.
+async fn probe(client: &reqwest::Client, url: &str) -> Option<String> {
+    let resp = match client.head(url).send().await {
+        Ok(resp)
+            if matches!(
+                resp.status(),
+                StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+            ) =>
+        {
+            client.get(url).send().await
+        }
+        resp => resp,
+    };
+
+    match resp {
+        // Rate limits say nothing about the link itself.
+        Ok(resp) if resp.status() == StatusCode::TOO_MANY_REQUESTS => None,
+        Ok(resp) if resp.status().is_client_error() || resp.status().is_server_error() => {
+            Some(format!("status {}", resp.status()))
+        }
+        Ok(_) => None,
+        Err(e) if e.is_timeout() => Some("timed out".to_owned()),
+        Err(e) if e.is_redirect() => Some("too many redirects".to_owned()),
+        Err(e) if e.is_connect() => Some("connection failed".to_owned()),
+        Err(e) => Some(e.without_url().to_string()),
+    }
+}
+
+/// Dead links that weren't reported in the last check.
+fn fresh(dead: &[DeadLink], reported: &HashSet<String>) -> Vec<DeadLink> {
+    dead.iter()
+        .filter(|link| !reported.contains(&link.url))
+        .cloned()
+        .collect()
+}
+
+fn message(dead: &[DeadLink]) -> String {
+    dead.iter().fold(
+        String::from("🔗 Some links don't work anymore:"),
+        |mut message, link| {
+            let origin = match &link.origin {
+                LinkOrigin::Links(name) => format!("link {name}"),
+                LinkOrigin::Command { name, source } => format!("!{name} on {source}"),
+            };
+            write!(&mut message, "\n{origin}: <{}> ({})", link.url, link.reason).ok();
+            message
+        },
+    )
+}
+
+fn client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent("ToggleBot (https://github.com/dnaka91/togglebot)")
+        .timeout(Duration::from_secs(10))
+        .redirect(redirect::Policy::limited(10))
+        .build()
+        .map_err(Into::into)
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+    use time::OffsetDateTime;
+
+    use super::*;
+    use crate::api::{AuthorId, Source};
+
+    #[test]
+    fn find_urls() {
+        assert_eq!(
+            vec!["https://github.com/dnaka91", "http://example.com/a?b=c"],
+            urls("Code at <https://github.com/dnaka91>, or (http://example.com/a?b=c).")
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(0, urls("no links, only https:// and example.com").count());
+    }
+
+    #[test]
+    fn settings_and_commands() {
+        let state = State::in_memory().unwrap();
+        state
+            .add_custom_command(
+                Source::Twitch,
+                "discord",
+                "Join at https://discord.gg/abc!",
+                &AuthorId::Console.user_ref(),
+                OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+        let links = HashMap::from([
+            ("Twitter".to_owned(), "https://x.com/dnaka91".to_owned()),
+            ("GitHub".to_owned(), "https://github.com/dnaka91".to_owned()),
+        ]);
+
+        assert_eq!(
+            vec![
+                (
+                    LinkOrigin::Links("GitHub".to_owned()),
+                    "https://github.com/dnaka91".to_owned()
+                ),
+                (
+                    LinkOrigin::Links("Twitter".to_owned()),
+                    "https://x.com/dnaka91".to_owned()
+                ),
+                (
+                    LinkOrigin::Command {
+                        name: "discord".to_owned(),
+                        source: Source::Twitch,
+                    },
+                    "https://discord.gg/abc".to_owned()
+                ),
+            ],
+            targets(&links, &state).unwrap()
+        );
+    }
+
+    #[test]
+    fn report_once() {
+        let dead = |url: &str| DeadLink {
+            origin: LinkOrigin::Links("GitHub".to_owned()),
+            url: url.to_owned(),
+            reason: "status 404 Not Found".to_owned(),
+        };
+        let reported = HashSet::from(["https://a.com".to_owned()]);
+
+        assert_eq!(
+            vec![dead("https://b.com")],
+            fresh(&[dead("https://a.com"), dead("https://b.com")], &reported)
+        );
+    }
+}
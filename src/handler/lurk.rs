@@ -0,0 +1,101 @@
+//! Lurkers, that let the chat know they're watching without taking part for a while.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use time::OffsetDateTime;
+use tracing::{info, instrument};
+
+use crate::{
+    api::{
+        response::{self, Lurk},
+        Author,
+    },
+    state::State,
+};
+
+/// Longest message that users can leave when they start lurking.
+const MAX_MESSAGE_LENGTH: usize = 100;
+
+#[instrument(skip_all)]
+pub fn lurk(
+    state: &State,
+    author: &Author,
+    message: Option<String>,
+    now: OffsetDateTime,
+) -> response::User {
+    info!("received `lurk` command");
+    response::User::Lurk(start(state, author, message, now))
+}
+
+#[instrument(skip_all)]
+pub fn unlurk(state: &State, author: &Author, now: OffsetDateTime) -> response::User {
+    info!("received `unlurk` command");
+    response::User::Lurk(end(state, author, now))
+}
+
+fn start(
+    state: &State,
+    author: &Author,
+    message: Option<String>,
+    now: OffsetDateTime,
+) -> Result<Lurk> {
+    if message
+        .as_ref()
+        .is_some_and(|message| message.chars().count() > MAX_MESSAGE_LENGTH)
+    {
+        return Ok(Lurk::Refused(format!(
+            "Lurk messages can be at most {MAX_MESSAGE_LENGTH} characters long"
+        )));
+    }
+
+    state.start_lurk(&author.id.user_ref(), message.as_deref(), now)?;
+
+    Ok(Lurk::Started {
+        name: author.name.clone(),
+        message,
+    })
+}
+
+fn end(state: &State, author: &Author, now: OffsetDateTime) -> Result<Lurk> {
+    Ok(match state.end_lurk(&author.id.user_ref())? {
+        Some(since) => Lurk::Ended {
+            name: author.name.clone(),
+            duration: Duration::try_from(now - since).unwrap_or_default(),
+        },
+        None => Lurk::NotLurking(author.name.clone()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::AuthorId;
+
+    #[test]
+    fn lurk_and_return() {
+        let state = State::in_memory().unwrap();
+        let author = Author {
+            name: "viewer".to_owned(),
+            ..AuthorId::Twitch("1".to_owned()).into()
+        };
+        let now = OffsetDateTime::from_unix_timestamp(1_730_054_700).unwrap();
+
+        assert!(matches!(
+            end(&state, &author, now).unwrap(),
+            Lurk::NotLurking(name) if name == "viewer"
+        ));
+        assert!(matches!(
+            start(&state, &author, Some("x".repeat(101)), now).unwrap(),
+            Lurk::Refused(_)
+        ));
+        assert!(matches!(
+            start(&state, &author, Some("brb".to_owned()), now).unwrap(),
+            Lurk::Started { message: Some(message), .. } if message == "brb"
+        ));
+        assert!(matches!(
+            end(&state, &author, now + Duration::from_mins(90)).unwrap(),
+            Lurk::Ended { duration, .. } if duration == Duration::from_mins(90)
+        ));
+    }
+}
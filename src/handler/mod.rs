@@ -1,26 +1,103 @@
 //! Main handling logic for all supported bot commands.
 
-use std::sync::Arc;
+use std::{
+    num::NonZero,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use anyhow::Result;
-use tracing::Span;
+use time::UtcOffset;
+use tracing::{error, info, trace, Span};
 
 use crate::{
-    api::{request, response, AuthorId, Source},
-    settings::{Commands as CommandSettings, Discord as DiscordSettings},
+    api::{
+        request, request::Request, response, Author, AuthorId, Availability, Event, GuildChannel,
+        Message, Source, TemperatureUnit,
+    },
+    clock::Clock,
+    fun::Fun,
+    health::Health,
+    locale,
+    plugins::Plugins,
+    settings::{Commands as CommandSettings, Discord as DiscordSettings, Thanks as ThanksSettings},
     state::State,
     statistics::{BuiltinCommand, Command, Stats},
+    timing::Timings,
 };
 
 mod admin;
+mod alerts;
+mod away;
+mod birthdays;
+mod channels;
+mod command_list;
+mod confirm;
+mod eval;
+mod faq;
+mod featured;
+mod games;
+mod github;
+mod issues;
+mod linkcheck;
+mod lurk;
+mod notify;
 mod owner;
+mod paste;
+mod polls;
+mod prefs;
+mod pronouns;
+mod raid;
+mod releases;
+mod rust;
+mod silence;
+mod spam;
+mod suggestions;
+mod titles;
+mod topic;
+mod triggers;
 mod user;
+mod watch_time;
+
+pub use self::{
+    alerts::check_usage_alerts,
+    birthdays::{announce_birthdays, next_birthdays_announcement},
+    command_list::pin_command_list,
+    confirm::TIMEOUT as CONFIRM_TIMEOUT,
+    featured::{feature_command, next_featured_command},
+    games::ACCEPT_TIMEOUT as DUEL_TIMEOUT,
+    github::announce_github,
+    linkcheck::{alert_dead_links, ReportedLinks},
+    notify::go_live,
+    paste::{code_blocks, relay_code},
+    raid::shoutout,
+    releases::announce_releases,
+    rust::announce_rust_release,
+    topic::post_topic,
+};
 
 /// Convenience type alias for [`CommandSettings`] wrapped in an [`Arc`].
 pub type AsyncCommandSettings = Arc<CommandSettings>;
 
+/// Short-lived data of the command handling, like cooldowns, pending confirmations and looked up
+/// crates. Every [`Bot`](crate::bot::Bot) keeps its own, so bots in the same process don't affect
+/// each other.
+#[derive(Default)]
+pub struct Memory {
+    confirmations: confirm::Pending,
+    spam: spam::History,
+    games: games::Games,
+    faqs: faq::Cache,
+    shoutouts: raid::Shoutouts,
+    away: away::Replied,
+    triggers: triggers::Fired,
+    eval: Mutex<eval::LastRun>,
+    crates: Arc<user::Crates>,
+    pronouns: pronouns::Known,
+}
+
 /// Possible access levels for users, controlling access over accessible bot commands.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Access {
     /// Default user level, only granting access to the user commands.
     Standard,
@@ -38,38 +115,252 @@ pub enum Access {
 /// Determine the access level for the author of a chat message.
 ///
 /// - In **Discord** all possible access levels exist, owners defined in a pre-defined static list
-///   and admins defined in a dynamic list controlled by owners at runtime.
-/// - In **Twitch** only standard users exist, regardless of any settings.
+///   and admins defined in a dynamic list controlled by owners at runtime. Members of an admin
+///   role, either from the settings or added by owners, are admins as well.
+/// - In **Twitch**, **Matrix** and **YouTube** only standard users exist, regardless of any
+///   settings.
+/// - In the local **Console** the user is always an owner, as it's only available to whoever runs
+///   the bot.
+/// - **Control** clients get the access that they're configured with.
 #[must_use]
-pub fn access(settings: &DiscordSettings, state: &State, author: &AuthorId) -> Access {
-    match author {
+pub fn access(settings: Option<&DiscordSettings>, state: &State, author: &Author) -> Access {
+    match &author.id {
         AuthorId::Discord(id) => {
-            if settings.owners.contains(id) {
+            if settings.is_some_and(|settings| settings.owners.contains(id)) {
                 Access::Owner
-            } else if state.is_admin((*id).into()).unwrap_or(false) {
+            } else if state.is_admin((*id).into()).unwrap_or(false)
+                || has_admin_role(settings, state, &author.roles)
+            {
                 Access::Admin
             } else {
                 Access::Standard
             }
         }
-        AuthorId::Twitch(_) => Access::Standard,
+        AuthorId::Twitch(_) | AuthorId::Matrix(_) | AuthorId::YouTube(_) => Access::Standard,
+        AuthorId::Console | AuthorId::Control { owner: true, .. } => Access::Owner,
+        AuthorId::Control { owner: false, .. } => Access::Admin,
+    }
+}
+
+/// Whether any of the author's Discord roles makes them an admin.
+fn has_admin_role(settings: Option<&DiscordSettings>, state: &State, roles: &[String]) -> bool {
+    let roles = roles
+        .iter()
+        .filter_map(|role| role.parse::<NonZero<u64>>().ok())
+        .collect::<Vec<_>>();
+    if roles.is_empty() {
+        return false;
+    }
+
+    if settings.is_some_and(|settings| roles.iter().any(|role| settings.admin_roles.contains(role)))
+    {
+        return true;
+    }
+
+    state
+        .list_admin_roles()
+        .inspect_err(|e| error!(error = ?e, "failed listing admin roles"))
+        .is_ok_and(|admin_roles| roles.iter().any(|role| admin_roles.contains(role)))
+}
+
+/// Handle a message according to the author's access level, or nothing if the author isn't
+/// allowed to use the command.
+#[allow(clippy::too_many_arguments)]
+pub async fn message(
+    memory: &Memory,
+    settings: &AsyncCommandSettings,
+    state: &State,
+    statistics: &Stats,
+    plugins: &Plugins,
+    fun: &Fun,
+    health: &Health,
+    timings: &Timings,
+    clock: &dyn Clock,
+    access: Access,
+    message: Message,
+) -> Option<Result<response::Response>> {
+    #[cfg(feature = "chaos")]
+    crate::chaos::delay().await;
+
+    health.runtime().record_message();
+
+    // Admins can use their commands anywhere, so they can always change the channel rules.
+    if let (Some(channel), Request::User(_)) = (message.channel, &message.content) {
+        if !channels::allowed(state, channel) {
+            trace!("user commands are not allowed in the channel");
+            return None;
+        }
+    }
+
+    if matches!(message.content, Request::User(_)) && !available(state, message.content.name()) {
+        trace!("command is not available in the current stream state");
+        return None;
+    }
+
+    // Trigger words have their own cooldowns, and repeated chat messages aren't spam by themselves.
+    let trigger = matches!(message.content, Request::User(request::User::Trigger(_)));
+    if let (Access::Standard, Request::User(request), false) = (access, &message.content, trigger) {
+        match spam::check(&memory.spam, &settings.spam, &message.author, request) {
+            spam::Verdict::Allow => {}
+            spam::Verdict::Drop => {
+                statistics.try_increment(Command::Spam(message.content.name()));
+                return None;
+            }
+            spam::Verdict::Timeout(secs) => {
+                statistics.try_increment(Command::Spam(message.content.name()));
+                return Some(Ok(response::Response::User(
+                    response::User::TimeoutSpammer(secs),
+                )));
+            }
+        }
+    }
+
+    // Destructive commands wait for a confirmation, and then run like any other command.
+    let content = match confirm::check(
+        &memory.confirmations,
+        access,
+        &message.author.id,
+        message.content,
+    ) {
+        Ok(content) => content,
+        Err(action) => return Some(Ok(response::Response::Confirm(action))),
+    };
+
+    Some(match (access, content) {
+        (Access::Owner, Request::Owner(request)) => {
+            owner_message(message.span, settings, state, statistics, plugins, request)
+                .await
+                .map(response::Response::Owner)
+        }
+        (_, Request::Owner(_)) => {
+            trace!("non-owner tried using a owner-only request");
+            return denied(settings, message.source, Access::Owner);
+        }
+        (Access::Owner | Access::Admin, Request::Admin(request)) => admin_message(
+            message.span,
+            settings,
+            state,
+            statistics,
+            timings,
+            clock,
+            request,
+            &message.author.id,
+            message.channel,
+        )
+        .await
+        .map(response::Response::Admin),
+        (_, Request::Admin(_)) => {
+            trace!("non-admin tried using a admin-only request");
+            return denied(settings, message.source, Access::Admin);
+        }
+        (_, Request::User(request)) => {
+            let response = user_message(
+                message.span,
+                memory,
+                Arc::clone(settings),
+                state,
+                statistics,
+                plugins,
+                fun,
+                health,
+                timings,
+                clock,
+                request,
+                message.source,
+                &message.author,
+            )
+            .await;
+
+            // The command still counts towards the statistics, only the reply is dropped.
+            if message
+                .channel
+                .is_some_and(|channel| silence::silenced(state, channel, clock.now()))
+            {
+                trace!("replies are silenced in the channel");
+                return None;
+            }
+
+            response.map(response::Response::User)
+        }
+    })
+}
+
+/// Tell the author that the command requires a higher access level, if denials are replied to on
+/// the service.
+fn denied(
+    settings: &CommandSettings,
+    source: Source,
+    required: Access,
+) -> Option<Result<response::Response>> {
+    settings
+        .denials
+        .enabled(source)
+        .then_some(Ok(response::Response::Denied(required)))
+}
+
+/// Whether the command can be used in the current stream state. Commands stay available, if
+/// either state can't be loaded.
+fn available(state: &State, name: &str) -> bool {
+    let res = state.command_availability(name).and_then(|availability| {
+        Ok(availability == Availability::Always || availability.allows(state.is_live()?))
+    });
+
+    res.unwrap_or_else(|e| {
+        error!(error = ?e, "failed checking command availability");
+        true
+    })
+}
+
+/// Usage statistics that ignore the commands of the streamer, who tries out commands far more
+/// often than anyone else.
+struct Counter<'a> {
+    stats: &'a Stats,
+    ignore: bool,
+}
+
+impl Counter<'_> {
+    fn try_increment(&self, cmd: Command<'_>) {
+        if !self.ignore {
+            self.stats.try_increment(cmd);
+        }
     }
 }
 
 /// Handle any user facing message and prepare a response.
+#[allow(clippy::too_many_arguments)]
 #[tracing::instrument(parent = span, skip_all, name = "user")]
 pub async fn user_message(
     span: Span,
+    memory: &Memory,
     settings: AsyncCommandSettings,
     state: &State,
     statistics: &Stats,
+    plugins: &Plugins,
+    fun: &Fun,
+    health: &Health,
+    timings: &Timings,
+    clock: &dyn Clock,
     content: request::User,
     source: Source,
+    author: &Author,
 ) -> Result<response::User> {
+    let prefs = prefs::resolve(state, &author.id);
+    let language = prefs
+        .locale
+        .unwrap_or_else(|| settings.locale.language(source));
+    let statistics = &Counter {
+        stats: statistics,
+        ignore: author.is_broadcaster(),
+    };
+
     Ok(match content {
         request::User::Help => {
             statistics.try_increment(BuiltinCommand::Help.into());
-            user::help()
+            user::help(&settings, language, author)
+        }
+        request::User::CommandHelp(name) => {
+            statistics.try_increment(BuiltinCommand::Help.into());
+            user::command_help(state, &settings.spam, &name)
         }
         request::User::Commands(source) => {
             statistics.try_increment(BuiltinCommand::Commands.into());
@@ -77,30 +368,212 @@ pub async fn user_message(
         }
         request::User::Links => {
             statistics.try_increment(BuiltinCommand::Links.into());
-            user::links(&settings)
+            user::links(&settings, source)
         }
         request::User::Crate(name) => {
             statistics.try_increment(BuiltinCommand::Crate.into());
-            user::crate_(&name).await
+            user::crate_(&memory.crates, &name, source).await
+        }
+        request::User::CrateCompare(first, second) => {
+            statistics.try_increment(BuiltinCommand::Crate.into());
+            user::crate_compare(&memory.crates, &first, &second).await
+        }
+        request::User::CrateVersion { name, version } => {
+            statistics.try_increment(BuiltinCommand::Crate.into());
+            user::crate_version(&name, &version).await
+        }
+        request::User::CrateFeatures { name, version } => {
+            statistics.try_increment(BuiltinCommand::Crate.into());
+            user::crate_features(&name, version.as_deref()).await
+        }
+        request::User::RustVersions => {
+            statistics.try_increment(BuiltinCommand::RustVersions.into());
+            rust::versions().await
+        }
+        request::User::Eval(code) => {
+            statistics.try_increment(BuiltinCommand::Eval.into());
+            eval::eval(&memory.eval, &settings.eval, &author.id, &code).await
         }
         request::User::Ban(target) => {
             statistics.try_increment(BuiltinCommand::Ban.into());
-            user::ban(&target)
+            user::ban(&settings, language, author, &target)
         }
         request::User::Today => {
             statistics.try_increment(BuiltinCommand::Today.into());
-            user::today()
+            user::today(
+                language,
+                clock
+                    .now()
+                    .to_offset(prefs.timezone.unwrap_or(UtcOffset::UTC)),
+            )
+        }
+        request::User::Status => {
+            statistics.try_increment(BuiltinCommand::Status.into());
+            user::status(state, health)
+        }
+        request::User::BotStat => {
+            statistics.try_increment(BuiltinCommand::BotStat.into());
+            user::botstat(state, health, timings)
         }
         request::User::Ftoc(fahrenheit) => {
             statistics.try_increment(BuiltinCommand::FahrenheitToCelsius.into());
-            user::ftoc(fahrenheit)
+            user::ftoc(language, fahrenheit)
         }
         request::User::Ctof(celsius) => {
             statistics.try_increment(BuiltinCommand::CelsiusToFahrenheit.into());
-            user::ctof(celsius)
+            user::ctof(language, celsius)
+        }
+        request::User::Convert(degrees) => match prefs.unit.unwrap_or(TemperatureUnit::Celsius) {
+            TemperatureUnit::Celsius => {
+                statistics.try_increment(BuiltinCommand::FahrenheitToCelsius.into());
+                user::ftoc(language, degrees)
+            }
+            TemperatureUnit::Fahrenheit => {
+                statistics.try_increment(BuiltinCommand::CelsiusToFahrenheit.into());
+                user::ctof(language, degrees)
+            }
+        },
+        request::User::SongRequest(link) => {
+            statistics.try_increment(BuiltinCommand::SongRequest.into());
+            user::song_request(
+                state,
+                &settings.song_requests,
+                &author.id,
+                &link,
+                clock.now(),
+            )
+        }
+        request::User::SongQueue => {
+            statistics.try_increment(BuiltinCommand::SongQueue.into());
+            user::song_queue(state)
+        }
+        request::User::TimeoutMe => {
+            statistics.try_increment(BuiltinCommand::TimeoutMe.into());
+            user::timeout_me()
+        }
+        request::User::FollowAge => {
+            statistics.try_increment(BuiltinCommand::FollowAge.into());
+            user::follow_age()
+        }
+        request::User::AccountAge => {
+            statistics.try_increment(BuiltinCommand::AccountAge.into());
+            user::account_age()
+        }
+        request::User::Points => {
+            statistics.try_increment(BuiltinCommand::Points.into());
+            user::points(state, &author.id)
+        }
+        request::User::Gamble(amount) => {
+            statistics.try_increment(BuiltinCommand::Gamble.into());
+            games::gamble(&memory.games, state, &settings.games, &author.id, amount)
+        }
+        request::User::Duel { target, amount } => {
+            statistics.try_increment(BuiltinCommand::Duel.into());
+            games::duel(
+                &memory.games,
+                state,
+                &settings.games,
+                source,
+                author,
+                &target,
+                amount,
+            )
+        }
+        request::User::Accept => games::accept(&memory.games, state, &settings.games, &author.id),
+        request::User::Pronouns(req) => {
+            statistics.try_increment(BuiltinCommand::Pronouns.into());
+            pronouns::pronouns(&memory.pronouns, state, source, &author.id, req).await
+        }
+        request::User::Prefs(req) => {
+            statistics.try_increment(BuiltinCommand::Settings.into());
+            prefs::prefs(state, &author.id, req)
+        }
+        request::User::NotifyMe(enabled) => {
+            statistics.try_increment(BuiltinCommand::NotifyMe.into());
+            notify::notify_me(state, &author.id, enabled, clock.now())
+        }
+        request::User::WatchTime(enabled) => {
+            statistics.try_increment(BuiltinCommand::WatchTime.into());
+            watch_time::watch_time(state, statistics.stats, &author.id, enabled, clock.now())
+        }
+        request::User::Fun(kind) => {
+            statistics.try_increment(BuiltinCommand::from(kind).into());
+            user::fun(fun, kind).await
+        }
+        request::User::Birthday(req) => {
+            statistics.try_increment(BuiltinCommand::Birthday.into());
+            birthdays::birthday(state, &author.id, req)
+        }
+        request::User::Lurk(message) => {
+            statistics.try_increment(BuiltinCommand::Lurk.into());
+            lurk::lurk(state, author, message, clock.now())
+        }
+        request::User::Unlurk => {
+            statistics.try_increment(BuiltinCommand::Unlurk.into());
+            lurk::unlurk(state, author, clock.now())
+        }
+        request::User::Yesterday => {
+            statistics.try_increment(BuiltinCommand::Yesterday.into());
+            titles::yesterday(
+                state,
+                clock
+                    .now()
+                    .to_offset(prefs.timezone.unwrap_or(UtcOffset::UTC)),
+            )
+        }
+        request::User::LastCategory(category) => {
+            statistics.try_increment(BuiltinCommand::LastCategory.into());
+            titles::last_category(state, &category, prefs.timezone.unwrap_or(UtcOffset::UTC))
+        }
+        request::User::Suggest(content) => {
+            statistics.try_increment(BuiltinCommand::Suggest.into());
+            suggestions::suggest(state, author, &content, clock.now())
+        }
+        request::User::Topic => {
+            statistics.try_increment(BuiltinCommand::Topic.into());
+            topic::topic(state)
+        }
+        request::User::Highlights => {
+            statistics.try_increment(BuiltinCommand::Highlights.into());
+            user::highlights(state)
+        }
+        request::User::Trigger(text) => {
+            if let Some(resp) = away::reply(&memory.away, state, &settings, source, author, &text) {
+                return Ok(resp);
+            }
+
+            if let Some(content) = triggers::fire(&memory.triggers, state, source, &text) {
+                statistics.try_increment(BuiltinCommand::Trigger.into());
+                response::User::Trigger(Some(content))
+            } else if let Some(answer) =
+                faq::answer(&memory.faqs, state, &settings.faq, source, &text)
+            {
+                statistics.try_increment(BuiltinCommand::Faq.into());
+                response::User::Faq(answer)
+            } else {
+                response::User::Trigger(None)
+            }
+        }
+        request::User::Poll(poll) => {
+            statistics.try_increment(BuiltinCommand::Poll.into());
+            polls::start(author, poll)
         }
         request::User::Custom(name) => {
-            let response = user::custom(state, source, &name);
+            let response = match user::custom(state, source, &name) {
+                Some(response) => Some(user::charge(state, &author.id, &name, response)),
+                None => user::plugin(plugins, &name, None, &author.id, source).await,
+            };
+
+            let name = match response {
+                Some(_) => Command::Custom(&name),
+                None => Command::Unknown(&name),
+            };
+            statistics.try_increment(name);
+
+            response.unwrap_or(response::User::Unknown)
+        }
+        request::User::Plugin { name, args } => {
+            let response = user::plugin(plugins, &name, Some(&args), &author.id, source).await;
 
             let name = match response {
                 Some(_) => Command::Custom(&name),
@@ -114,12 +587,18 @@ pub async fn user_message(
 }
 
 /// Handle admin facing messages to control the bot and prepare a response.
+#[allow(clippy::too_many_arguments)]
 #[tracing::instrument(parent = span, skip_all, name = "admin")]
 pub async fn admin_message(
     span: Span,
+    settings: &CommandSettings,
     state: &State,
     statistics: &Stats,
+    timings: &Timings,
+    clock: &dyn Clock,
     content: request::Admin,
+    author: &AuthorId,
+    channel: Option<GuildChannel>,
 ) -> Result<response::Admin> {
     Ok(match content {
         request::Admin::Help => admin::help(),
@@ -134,30 +613,193 @@ pub async fn admin_message(
             admin::custom_commands(
                 state,
                 statistics,
+                author,
                 &content,
                 admin::Action::Add,
                 source,
                 &name,
+                clock.now(),
             )
             .await
         }
         request::Admin::CustomCommands(request::CustomCommands::Remove { source, name }) => {
-            admin::custom_commands(state, statistics, "", admin::Action::Remove, source, &name)
-                .await
+            admin::custom_commands(
+                state,
+                statistics,
+                author,
+                "",
+                admin::Action::Remove,
+                source,
+                &name,
+                clock.now(),
+            )
+            .await
+        }
+        request::Admin::CustomCommands(request::CustomCommands::Show(name)) => {
+            admin::custom_commands_show(state, name)
         }
-        request::Admin::Statistics(date) => admin::stats(statistics, date).await,
+        request::Admin::CustomCommands(request::CustomCommands::History(name)) => {
+            admin::custom_commands_history(state, name)
+        }
+        request::Admin::CustomCommands(request::CustomCommands::Revert(name)) => {
+            admin::custom_commands_revert(state, author, &name, clock.now())
+        }
+        request::Admin::Statistics(date) => admin::stats(statistics, date),
+        request::Admin::StreamTag(tag) => admin::stream_tag(state, &tag),
+        request::Admin::Timing => admin::timing(timings),
+        request::Admin::UserNotes(req) => admin::user_notes(state, author, req, clock.now()),
+        request::Admin::SkipSong => admin::skip_song(state),
+        request::Admin::CommandCosts(req) => admin::command_costs(state, req),
+        request::Admin::CommandAvailability(req) => admin::command_availability(state, req),
+        request::Admin::UsageAlerts(req) => alerts::usage_alerts(state, req),
+        request::Admin::Shoutout(enabled) => admin::shoutout(state, enabled),
+        request::Admin::Triggers(req) => admin::triggers(state, req),
+        request::Admin::Faqs(req) => admin::faqs(state, req),
+        request::Admin::WatchCrates(req) => releases::watch_crates(state, req).await,
+        request::Admin::Channels(req) => channels::channels(state, channel, req),
+        request::Admin::Silence {
+            channel: target,
+            duration,
+        } => silence::silence(state, channel, target, duration, clock.now()),
+        request::Admin::CommandList(req) => command_list::command_list(state, channel, req),
+        request::Admin::Pin(highlight) => admin::pin(state, author, highlight, clock.now()),
+        request::Admin::Away(message) => away::away(state, &message, clock.now()),
+        request::Admin::Back => away::back(state, clock.now()),
+        request::Admin::Confirm => response::Admin::NothingToConfirm,
+        request::Admin::LinkPreview(source) => response::Admin::LinkPreview {
+            source,
+            links: user::links_for(settings, source),
+        },
+        request::Admin::CheckLinks => linkcheck::check_links(settings, state).await,
+        request::Admin::Chapters(day) => titles::chapters(state, day),
+        request::Admin::Suggestions(req) => suggestions::suggestions(state, req, clock.now()),
+        request::Admin::Issue(req) => {
+            issues::issue(settings, state, author, req, clock.now()).await
+        }
+        request::Admin::Topic(content) => topic::set_topic(state, content.as_deref(), clock.now()),
     })
 }
 
+/// Handle events that are not related to any chat message.
+pub fn event(
+    settings: &CommandSettings,
+    state: &State,
+    statistics: &Stats,
+    clock: &dyn Clock,
+    event: Event,
+) {
+    let res = match event {
+        Event::StreamOnline(info) => {
+            info!(info.id, "recording stream start");
+            state
+                .start_stream(&info)
+                .and_then(|()| statistics.stream_online())
+                .and_then(|()| state.start_topic())
+                .and_then(|()| titles::stream_online(state, &info, clock.now()))
+        }
+        Event::StreamOffline => {
+            info!("recording stream end");
+            statistics.stream_offline();
+            state
+                .end_stream(clock.now())
+                .and_then(|()| state.clear_lurkers())
+        }
+        Event::ChannelUpdate { title, category } => {
+            info!(title, category, "recording title change");
+            state.record_title(&title, &category, clock.now())
+        }
+        Event::Follow { .. }
+        | Event::Subscribe { .. }
+        | Event::Cheer { .. }
+        | Event::Raid { .. }
+        | Event::PollEnded { .. }
+        | Event::CodeShared { .. } => return,
+        Event::Chat { user, name } => {
+            let points = &settings.points;
+            if points.per_message == 0 {
+                return;
+            }
+
+            state
+                .earn_points(
+                    &user,
+                    &name,
+                    points.per_message,
+                    clock.now(),
+                    Duration::from_secs(points.interval),
+                )
+                .map(|_| ())
+        }
+        Event::MessageEdited { message, content } => state
+            .edit_tracked_message(message, &content)
+            .map(|updated| {
+                if updated > 0 {
+                    info!(%message, updated, "updated records of edited message");
+                }
+            }),
+        Event::Watching { viewers, duration } => {
+            watch_time::record(state, statistics, viewers, duration)
+        }
+        Event::MessagesDeleted(messages) => messages.into_iter().try_for_each(|message| {
+            state.remove_tracked_message(message).map(|removed| {
+                if removed > 0 {
+                    info!(%message, removed, "removed records of deleted message");
+                }
+            })
+        }),
+    };
+
+    if let Err(e) = res {
+        error!(error = ?e, "failed handling event");
+    }
+}
+
+/// Create the thank-you message for a subscription or cheer, or nothing for any other event.
+#[must_use]
+pub fn thanks(settings: &ThanksSettings, event: &Event) -> Option<String> {
+    match event {
+        Event::Subscribe { user, tier, .. } => Some(locale::fill(
+            &settings.subscribe,
+            &[("user", user), ("tier", tier)],
+        )),
+        Event::Cheer { user, bits, .. } => Some(locale::fill(
+            &settings.cheer,
+            &[
+                ("user", &user.as_deref().unwrap_or("Anonymous")),
+                ("bits", bits),
+            ],
+        )),
+        _ => None,
+    }
+}
+
 /// Handle messages only accessible to owners defined in the settings and prepare a response.
 #[tracing::instrument(parent = span, skip_all, name = "owner")]
 pub async fn owner_message(
     span: Span,
+    settings: &CommandSettings,
     state: &State,
+    statistics: &Stats,
+    plugins: &Plugins,
     content: request::Owner,
 ) -> Result<response::Owner> {
     Ok(match content {
         request::Owner::Help => owner::help(),
+        request::Owner::Plugins(request::Plugins::List) => owner::plugins_list(plugins),
+        request::Owner::Plugins(request::Plugins::Load(name)) => {
+            owner::plugins_edit(plugins, &name, true)
+        }
+        request::Owner::Plugins(request::Plugins::Unload(name)) => {
+            owner::plugins_edit(plugins, &name, false)
+        }
+        request::Owner::DebugParse {
+            text,
+            source,
+            mention,
+        } => owner::debug_parse(&text, source, mention),
+        request::Owner::ResyncCommands => owner::resync_commands(),
+        request::Owner::Cleanup => owner::cleanup(settings, statistics),
+        request::Owner::Broadcast(text) => owner::broadcast(text),
         request::Owner::Admins(request::Admins::List) => owner::admins_list(state)?,
         request::Owner::Admins(request::Admins::Add(id)) => {
             owner::admins_edit(state, owner::Action::Add, id)?
@@ -165,16 +807,31 @@ pub async fn owner_message(
         request::Owner::Admins(request::Admins::Remove(id)) => {
             owner::admins_edit(state, owner::Action::Remove, id)?
         }
+        request::Owner::Admins(request::Admins::AddRole(id)) => {
+            owner::admin_roles_edit(state, owner::Action::Add, id)
+        }
+        request::Owner::Admins(request::Admins::RemoveRole(id)) => {
+            owner::admin_roles_edit(state, owner::Action::Remove, id)
+        }
+        #[cfg(feature = "chaos")]
+        request::Owner::Chaos(req) => owner::chaos(req),
     })
 }
 
 #[cfg(test)]
 mod tests {
+    use std::num::NonZero;
+
     use similar_asserts::assert_eq;
+    use time::OffsetDateTime;
 
-    use self::response::AdminAction;
+    use self::response::{AdminAction, CrateSearch, MassMention, VersionSearch};
     use super::*;
-    use crate::api::{request::StatisticsDate, AdminId};
+    use crate::{
+        api::{request::StatisticsDate, AdminId, UserRef},
+        clock::SystemClock,
+        settings::{DmRouting, Mentions, Presence},
+    };
 
     fn defaults() -> (AsyncCommandSettings, State, Stats, Source) {
         (
@@ -185,30 +842,70 @@ mod tests {
         )
     }
 
+    fn author() -> Author {
+        AuthorId::Discord(NonZero::new(1).unwrap()).into()
+    }
+
     async fn run_user_message(content: request::User) -> Result<response::User> {
         tracing_subscriber::fmt::try_init().ok();
         let (settings, state, statistics, source) = defaults();
         user_message(
             Span::current(),
+            &Memory::default(),
             settings,
             &state,
             &statistics,
+            &Plugins::default(),
+            &Fun::default(),
+            &Health::default(),
+            &Timings::default(),
+            &SystemClock,
             content,
             source,
+            &author(),
         )
         .await
     }
 
     async fn run_admin_message(content: request::Admin) -> Result<response::Admin> {
+        let (_, state, _, _) = defaults();
+        run_admin_message_with(&state, content).await
+    }
+
+    /// Run an admin command on an existing state, so later commands see the changes of earlier
+    /// ones.
+    async fn run_admin_message_with(
+        state: &State,
+        content: request::Admin,
+    ) -> Result<response::Admin> {
         tracing_subscriber::fmt::try_init().ok();
-        let (_, state, statistics, _) = defaults();
-        admin_message(Span::current(), &state, &statistics, content).await
+        let (settings, _, statistics, _) = defaults();
+        admin_message(
+            Span::current(),
+            &settings,
+            state,
+            &statistics,
+            &Timings::default(),
+            &SystemClock,
+            content,
+            &author().id,
+            None,
+        )
+        .await
     }
 
     async fn run_owner_message(content: request::Owner) -> Result<response::Owner> {
         tracing_subscriber::fmt::try_init().ok();
-        let (_, state, _, _) = defaults();
-        owner_message(Span::current(), &state, content).await
+        let (settings, state, statistics, _) = defaults();
+        owner_message(
+            Span::current(),
+            &settings,
+            &state,
+            &statistics,
+            &Plugins::default(),
+            content,
+        )
+        .await
     }
 
     // #[tokio::test]
@@ -219,22 +916,83 @@ mod tests {
     //     ));
     // }
 
+    #[test]
+    fn access_by_role() {
+        let state = State::in_memory().unwrap();
+        let settings = DiscordSettings {
+            token: String::new(),
+            owners: [NonZero::new(9).unwrap()].into(),
+            admin_roles: [NonZero::new(100).unwrap()].into(),
+            announcement_channel: None,
+            poll_channel: None,
+            prefix_commands: false,
+            guilds: Vec::new(),
+            birthdays: None,
+            github: None,
+            presence: Presence::default(),
+            mentions: Mentions::default(),
+            dm_routing: DmRouting::default(),
+        };
+        let author = |roles: &[&str]| Author {
+            roles: roles.iter().map(ToString::to_string).collect(),
+            ..author()
+        };
+
+        let access = |author: &Author| super::access(Some(&settings), &state, author);
+        assert!(matches!(access(&author(&[])), Access::Standard));
+        assert!(matches!(access(&author(&["100"])), Access::Admin));
+        assert!(matches!(
+            access(&author(&["200", "broadcaster"])),
+            Access::Standard
+        ));
+
+        state.add_admin_role(NonZero::new(200).unwrap()).unwrap();
+        assert!(matches!(access(&author(&["200"])), Access::Admin));
+
+        let client = |owner| {
+            Author::from(AuthorId::Control {
+                client: "dashboard".to_owned(),
+                owner,
+            })
+        };
+        assert!(matches!(access(&client(false)), Access::Admin));
+        assert!(matches!(access(&client(true)), Access::Owner));
+    }
+
     #[tokio::test]
     async fn user_cmd_help() {
         assert!(matches!(
             run_user_message(request::User::Help).await,
-            Ok(response::User::Help)
+            Ok(response::User::Help(_))
         ));
     }
 
+    #[tokio::test]
+    async fn user_cmd_command_help() {
+        match run_user_message(request::User::CommandHelp("crates".to_owned()))
+            .await
+            .unwrap()
+        {
+            response::User::CommandHelp(Ok(Some(help))) => assert_eq!("crate", help.name),
+            res => panic!("unexpected response: {res:?}"),
+        }
+    }
+
     #[tokio::test]
     async fn user_cmd_commands() {
         match run_user_message(request::User::Commands(Source::Twitch))
             .await
             .unwrap()
         {
-            response::User::Commands(Ok(cmds)) => assert!(cmds.is_empty()),
-            response::User::Commands(Err(e)) => panic!("{e:?}"),
+            response::User::Commands {
+                builtin,
+                custom: Ok(custom),
+            } => {
+                assert!(builtin.iter().any(|cmd| cmd.name == "followage"));
+                assert!(!builtin.iter().any(|cmd| cmd.name == "notifyme"));
+                assert!(custom.is_empty());
+            }
+            response::User::Commands { custom: Err(e), .. } => panic!("{e:?}"),
             res => panic!("unexpected response: {res:?}"),
         }
     }
@@ -247,13 +1005,287 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn live_only_while_offline() {
+        let (settings, state, statistics, source) = defaults();
+        state
+            .set_command_availability("links", Availability::Live)
+            .unwrap();
+
+        let resp = message(
+            &Memory::default(),
+            &settings,
+            &state,
+            &statistics,
+            &Plugins::default(),
+            &Fun::default(),
+            &Health::default(),
+            &Timings::default(),
+            &SystemClock,
+            Access::Standard,
+            Message {
+                span: Span::current(),
+                source,
+                content: Request::User(request::User::Links),
+                author: author(),
+                mention: None,
+                channel: None,
+            },
+        )
+        .await;
+
+        assert!(resp.is_none());
+    }
+
+    #[tokio::test]
+    async fn admin_cmd_denied() {
+        let (settings, state, statistics, _) = defaults();
+
+        for source in [Source::Discord, Source::Twitch] {
+            let resp = message(
+                &Memory::default(),
+                &settings,
+                &state,
+                &statistics,
+                &Plugins::default(),
+                &Fun::default(),
+                &Health::default(),
+                &Timings::default(),
+                &SystemClock,
+                Access::Standard,
+                Message {
+                    span: Span::current(),
+                    source,
+                    content: Request::Admin(request::Admin::Help),
+                    author: author(),
+                    mention: None,
+                    channel: None,
+                },
+            )
+            .await;
+
+            match source {
+                Source::Twitch => assert!(resp.is_none()),
+                _ => assert!(matches!(
+                    resp,
+                    Some(Ok(response::Response::Denied(Access::Admin)))
+                )),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn confirm_admin_removal() {
+        let (settings, state, statistics, source) = defaults();
+        let admin = AdminId::new(2).unwrap();
+        state.add_admin(admin).unwrap();
+        let (memory, plugins, fun, health, timings) = (
+            Memory::default(),
+            Plugins::default(),
+            Fun::default(),
+            Health::default(),
+            Timings::default(),
+        );
+
+        let send = |content| {
+            message(
+                &memory,
+                &settings,
+                &state,
+                &statistics,
+                &plugins,
+                &fun,
+                &health,
+                &timings,
+                &SystemClock,
+                Access::Owner,
+                Message {
+                    span: Span::current(),
+                    source,
+                    content,
+                    author: author(),
+                    mention: None,
+                    channel: None,
+                },
+            )
+        };
+
+        let resp = send(Request::Owner(request::Owner::Admins(
+            request::Admins::Remove(admin),
+        )))
+        .await;
+        assert!(matches!(
+            resp,
+            Some(Ok(response::Response::Confirm(
+                response::Confirm::RemoveAdmin(_)
+            )))
+        ));
+        assert!(state.is_admin(admin).unwrap());
+
+        let resp = send(Request::Admin(request::Admin::Confirm)).await;
+        assert!(matches!(
+            resp,
+            Some(Ok(response::Response::Owner(response::Owner::Admins(
+                response::Admins::Edit(Ok(AdminAction::Removed))
+            ))))
+        ));
+        assert!(!state.is_admin(admin).unwrap());
+
+        let resp = send(Request::Admin(request::Admin::Confirm)).await;
+        assert!(matches!(
+            resp,
+            Some(Ok(response::Response::Admin(
+                response::Admin::NothingToConfirm
+            )))
+        ));
+    }
+
+    #[tokio::test]
+    async fn broadcaster_not_counted() {
+        let (settings, state, statistics, source) = defaults();
+        let (plugins, health) = (Plugins::default(), Health::default());
+        let fun = Fun::default();
+        let broadcaster = Author {
+            roles: vec!["broadcaster".to_owned()],
+            ..author()
+        };
+
+        for author in [broadcaster, author()] {
+            user_message(
+                Span::current(),
+                &Memory::default(),
+                Arc::clone(&settings),
+                &state,
+                &statistics,
+                &plugins,
+                &fun,
+                &health,
+                &Timings::default(),
+                &SystemClock,
+                request::User::Links,
+                source,
+                &author,
+            )
+            .await
+            .unwrap();
+        }
+
+        let usage = statistics.get(true).command_usage.builtin.unwrap();
+        assert_eq!(Some(&1), usage.get(&BuiltinCommand::Links));
+    }
+
+    #[tokio::test]
+    async fn user_cmd_song_request() {
+        tracing_subscriber::fmt::try_init().ok();
+        let (settings, state, statistics, source) = defaults();
+        let (plugins, health, author) = (Plugins::default(), Health::default(), author());
+        let (memory, fun, timings) = (Memory::default(), Fun::default(), Timings::default());
+        let request = |link: &str| {
+            user_message(
+                Span::current(),
+                &memory,
+                Arc::clone(&settings),
+                &state,
+                &statistics,
+                &plugins,
+                &fun,
+                &health,
+                &timings,
+                &SystemClock,
+                request::User::SongRequest(link.to_owned()),
+                source,
+                &author,
+            )
+        };
+
+        for (i, id) in ["aaaaaaaaaaa", "bbbbbbbbbbb", "ccccccccccc"]
+            .iter()
+            .enumerate()
+        {
+            match request(&format!("https://youtu.be/{id}")).await.unwrap() {
+                response::User::SongRequest(Ok(response::SongRequest::Added { position })) => {
+                    assert_eq!(i as u64 + 1, position);
+                }
+                res => panic!("unexpected response: {res:?}"),
+            }
+        }
+
+        for link in [
+            "https://youtu.be/aaaaaaaaaaa",
+            "https://youtu.be/ddddddddddd",
+            "https://example.com",
+        ] {
+            match request(link).await.unwrap() {
+                response::User::SongRequest(Ok(response::SongRequest::Refused(_))) => {}
+                res => panic!("unexpected response: {res:?}"),
+            }
+        }
+
+        match admin_message(
+            Span::current(),
+            &settings,
+            &state,
+            &statistics,
+            &Timings::default(),
+            &SystemClock,
+            request::Admin::SkipSong,
+            &author.id,
+            None,
+        )
+        .await
+        .unwrap()
+        {
+            response::Admin::SkipSong(Ok(Some(song))) => {
+                assert_eq!("https://youtu.be/aaaaaaaaaaa", song.url);
+            }
+            res => panic!("unexpected response: {res:?}"),
+        }
+
+        assert_eq!(2, state.list_song_requests().unwrap().len());
+    }
+
     #[tokio::test]
     async fn user_cmd_ban() {
         match run_user_message(request::User::Ban("me".to_owned()))
             .await
             .unwrap()
         {
-            response::User::Ban(target) => assert_eq!("me", target),
+            response::User::Ban(message) => assert_eq!("me, YOU SHALL NOT PASS!!", message),
+            res => panic!("unexpected response: {res:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn user_cmd_crate_compare() {
+        match run_user_message(request::User::CrateCompare(
+            "anyhow".to_owned(),
+            "anyhow".to_owned(),
+        ))
+        .await
+        .unwrap()
+        {
+            response::User::CrateCompare(Ok((
+                CrateSearch::Found(first),
+                CrateSearch::Found(second),
+            ))) => {
+                assert_eq!(3, first.version_count);
+                assert_eq!(first.name, second.name);
+            }
+            res => panic!("unexpected response: {res:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn user_cmd_status() {
+        match run_user_message(request::User::Status).await.unwrap() {
+            response::User::Status(response::Status {
+                stream: Ok(stream),
+                connectors,
+            }) => {
+                assert!(stream.is_none());
+                assert!(connectors.iter().all(|c| c.stale));
+            }
+            response::User::Status(response::Status { stream: Err(e), .. }) => panic!("{e:?}"),
             res => panic!("unexpected response: {res:?}"),
         }
     }
@@ -270,6 +1302,52 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn user_cmd_crate_version() {
+        match run_user_message(request::User::CrateVersion {
+            name: "anyhow".to_owned(),
+            version: "1.0.90".to_owned(),
+        })
+        .await
+        .unwrap()
+        {
+            response::User::CrateVersion(Ok(VersionSearch::Found(info))) => {
+                assert_eq!("1.0.90", info.num.to_string());
+            }
+            res => panic!("unexpected response: {res:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn user_cmd_crate_features() {
+        match run_user_message(request::User::CrateFeatures {
+            name: "anyhow".to_owned(),
+            version: None,
+        })
+        .await
+        .unwrap()
+        {
+            response::User::CrateFeatures(Ok(VersionSearch::Found(info))) => {
+                assert_eq!("1.0.91", info.num.to_string());
+                assert_eq!(3, info.features.len());
+            }
+            res => panic!("unexpected response: {res:?}"),
+        }
+
+        match run_user_message(request::User::CrateFeatures {
+            name: "anyhow".to_owned(),
+            version: Some("2".to_owned()),
+        })
+        .await
+        .unwrap()
+        {
+            response::User::CrateFeatures(Ok(VersionSearch::NotFound(message))) => {
+                assert_eq!("Crate `anyhow` has no version `2`", message);
+            }
+            res => panic!("unexpected response: {res:?}"),
+        }
+    }
+
     #[tokio::test]
     async fn user_cmd_ftoc() {
         match run_user_message(request::User::Ftoc(350.0)).await.unwrap() {
@@ -296,6 +1374,48 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn user_cmd_convert_preferred_unit() {
+        let (settings, state, statistics, source) = defaults();
+
+        for (unit, expect) in [
+            (None, "20.0°F => -6.7°C"),
+            (Some("fahrenheit"), "20.0°C => 68.0°F"),
+        ] {
+            if let Some(unit) = unit {
+                state
+                    .set_user_pref(&author().id.user_ref(), "unit", unit)
+                    .unwrap();
+            }
+
+            let res = user_message(
+                Span::current(),
+                &Memory::default(),
+                Arc::clone(&settings),
+                &state,
+                &statistics,
+                &Plugins::default(),
+                &Fun::default(),
+                &Health::default(),
+                &Timings::default(),
+                &SystemClock,
+                request::User::Convert(20.0),
+                source,
+                &author(),
+            )
+            .await
+            .unwrap();
+
+            match res {
+                response::User::FahrenheitToCelsius(msg)
+                | response::User::CelsiusToFahrenheit(msg) => {
+                    assert_eq!(expect, msg);
+                }
+                res => panic!("unexpected response: {res:?}"),
+            }
+        }
+    }
+
     // #[tokio::test]
     // async fn user_cmd_ctof_invalid() {
     //     match run_user_message("!ctof test").await.unwrap() {
@@ -312,16 +1432,29 @@ mod tests {
 
         let (settings, state, statistics, source) = defaults();
         state
-            .add_custom_command(Source::Discord, "hi", "hello")
+            .add_custom_command(
+                Source::Discord,
+                "hi",
+                "hello",
+                &AuthorId::Console.user_ref(),
+                OffsetDateTime::UNIX_EPOCH,
+            )
             .unwrap();
 
         match user_message(
             Span::current(),
+            &Memory::default(),
             settings,
             &state,
             &statistics,
+            &Plugins::default(),
+            &Fun::default(),
+            &Health::default(),
+            &Timings::default(),
+            &SystemClock,
             request::User::Custom("hi".to_owned()),
             source,
+            &author(),
         )
         .await
         .unwrap()
@@ -331,6 +1464,113 @@ mod tests {
         }
     }
 
+    #[test]
+    fn thanks_messages() {
+        let settings = ThanksSettings {
+            subscribe: "Thanks {user} for the tier {tier} sub".to_owned(),
+            cheer: "{user} cheered {bits} bits".to_owned(),
+            discord_channel: None,
+        };
+
+        assert_eq!(
+            Some("Thanks someone for the tier 2 sub".to_owned()),
+            thanks(
+                &settings,
+                &Event::Subscribe {
+                    source: Source::Twitch,
+                    user: "someone".to_owned(),
+                    tier: "2".to_owned(),
+                }
+            )
+        );
+        assert_eq!(
+            Some("Anonymous cheered 100 bits".to_owned()),
+            thanks(
+                &settings,
+                &Event::Cheer {
+                    source: Source::Twitch,
+                    user: None,
+                    bits: 100,
+                }
+            )
+        );
+        assert_eq!(None, thanks(&settings, &Event::StreamOffline));
+    }
+
+    #[tokio::test]
+    async fn user_cmd_custom_cost() {
+        tracing_subscriber::fmt::try_init().ok();
+
+        let (settings, state, statistics, source) = defaults();
+        let (plugins, health, author) = (Plugins::default(), Health::default(), author());
+        let (fun, timings) = (Fun::default(), Timings::default());
+        state
+            .add_custom_command(
+                Source::Discord,
+                "hug",
+                "*hugs*",
+                &AuthorId::Console.user_ref(),
+                OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+        state.set_command_cost("hug", 1).unwrap();
+
+        let memory = Memory::default();
+        let request = || {
+            user_message(
+                Span::current(),
+                &memory,
+                Arc::clone(&settings),
+                &state,
+                &statistics,
+                &plugins,
+                &fun,
+                &health,
+                &timings,
+                &SystemClock,
+                request::User::Custom("hug".to_owned()),
+                source,
+                &author,
+            )
+        };
+
+        match request().await.unwrap() {
+            response::User::NotEnoughPoints { cost, balance } => {
+                assert_eq!((1, 0), (cost, balance));
+            }
+            res => panic!("unexpected response: {res:?}"),
+        }
+
+        event(
+            &settings,
+            &state,
+            &statistics,
+            &SystemClock,
+            Event::Chat {
+                user: author.id.user_ref(),
+                name: "someone".to_owned(),
+            },
+        );
+
+        match request().await.unwrap() {
+            response::User::Custom(message) => assert_eq!("*hugs*", message.unwrap()),
+            res => panic!("unexpected response: {res:?}"),
+        }
+        assert_eq!(0, state.points(&author.id.user_ref()).unwrap());
+    }
+
+    #[tokio::test]
+    async fn user_cmd_plugin_unknown() {
+        assert!(matches!(
+            run_user_message(request::User::Plugin {
+                name: "weather".to_owned(),
+                args: "berlin".to_owned(),
+            })
+            .await,
+            Ok(response::User::Unknown)
+        ));
+    }
+
     // #[tokio::test]
     // async fn admin_cmd_unknown() {
     //     assert!(matches!(
@@ -377,7 +1617,9 @@ mod tests {
         .await
         .unwrap()
         {
-            response::Admin::CustomCommands(response::CustomCommands::Edit(Ok(()))) => {}
+            response::Admin::CustomCommands(response::CustomCommands::Edit(Ok(mentions))) => {
+                assert!(mentions.is_empty());
+            }
             response::Admin::CustomCommands(response::CustomCommands::Edit(Err(e))) => {
                 panic!("{e:?}")
             }
@@ -385,11 +1627,172 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn admin_cmd_custom_commands_add_mass_mentions() {
+        let add = |content: &str| {
+            run_admin_message(request::Admin::CustomCommands(
+                request::CustomCommands::Add {
+                    source: None,
+                    name: "test".to_owned(),
+                    content: content.to_owned(),
+                },
+            ))
+        };
+
+        match add("hey @everyone, <@&123> and <@456>").await.unwrap() {
+            response::Admin::CustomCommands(response::CustomCommands::Edit(Ok(mentions))) => {
+                assert_eq!(vec![MassMention::Everyone, MassMention::Role], mentions);
+            }
+            res => panic!("unexpected response: {res:?}"),
+        }
+        match add("mail me @ here, or <@&abc>").await.unwrap() {
+            response::Admin::CustomCommands(response::CustomCommands::Edit(Ok(mentions))) => {
+                assert!(mentions.is_empty());
+            }
+            res => panic!("unexpected response: {res:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn admin_cmd_custom_commands_revert() {
+        let (settings, state, statistics, _) = defaults();
+        let (settings, state, statistics, timings, author) = (
+            &*settings,
+            &state,
+            &statistics,
+            &Timings::default(),
+            &author().id,
+        );
+        let run = move |req| async move {
+            match admin_message(
+                Span::current(),
+                settings,
+                state,
+                statistics,
+                timings,
+                &SystemClock,
+                request::Admin::CustomCommands(req),
+                author,
+                None,
+            )
+            .await
+            .unwrap()
+            {
+                response::Admin::CustomCommands(response::CustomCommands::Edit(res)) => res,
+                res => panic!("unexpected response: {res:?}"),
+            }
+        };
+
+        run(request::CustomCommands::Add {
+            source: None,
+            name: "hi".to_owned(),
+            content: "one".to_owned(),
+        })
+        .await
+        .unwrap();
+        run(request::CustomCommands::Add {
+            source: Some(Source::Discord),
+            name: "hi".to_owned(),
+            content: "two".to_owned(),
+        })
+        .await
+        .unwrap();
+
+        run(request::CustomCommands::Revert("hi".to_owned()))
+            .await
+            .unwrap();
+        assert_eq!(
+            Some("one".to_owned()),
+            state.get_custom_command(Source::Discord, "hi").unwrap()
+        );
+
+        run(request::CustomCommands::Revert("hi".to_owned()))
+            .await
+            .unwrap();
+        assert_eq!(
+            Some("two".to_owned()),
+            state.get_custom_command(Source::Discord, "hi").unwrap()
+        );
+        assert_eq!(
+            Some("one".to_owned()),
+            state.get_custom_command(Source::Twitch, "hi").unwrap()
+        );
+        assert_eq!(8, state.list_custom_command_revisions("hi").unwrap().len());
+
+        assert!(run(request::CustomCommands::Revert("bye".to_owned()))
+            .await
+            .is_err());
+    }
+
     #[tokio::test]
     async fn admin_cmd_statistics() {
         assert!(matches!(
             run_admin_message(request::Admin::Statistics(StatisticsDate::Current)).await,
-            Ok(response::Admin::Statistics(Ok((false, _))))
+            Ok(response::Admin::Statistics(StatisticsDate::Current, _))
+        ));
+    }
+
+    #[tokio::test]
+    async fn admin_cmd_user_notes() {
+        let (_, state, _, _) = defaults();
+        let user = UserRef {
+            source: Source::Twitch,
+            id: "someone".to_owned(),
+        };
+
+        assert!(matches!(
+            run_admin_message_with(
+                &state,
+                request::Admin::UserNotes(request::UserNotes::Add {
+                    user: user.clone(),
+                    content: "note".to_owned(),
+                })
+            )
+            .await,
+            Ok(response::Admin::UserNotes(response::UserNotes::Add(Ok(1))))
+        ));
+        assert!(matches!(
+            run_admin_message_with(
+                &state,
+                request::Admin::UserNotes(request::UserNotes::List(user.clone()))
+            )
+            .await,
+            Ok(response::Admin::UserNotes(response::UserNotes::List(_, Ok(notes))))
+                if notes.len() == 1 && notes[0].content == "note"
+        ));
+        assert!(matches!(
+            run_admin_message_with(
+                &state,
+                request::Admin::UserNotes(request::UserNotes::Remove(1))
+            )
+            .await,
+            Ok(response::Admin::UserNotes(response::UserNotes::Remove(Ok(
+                ()
+            ))))
+        ));
+        assert!(matches!(
+            run_admin_message_with(
+                &state,
+                request::Admin::UserNotes(request::UserNotes::List(user))
+            )
+            .await,
+            Ok(response::Admin::UserNotes(response::UserNotes::List(_, Ok(notes)))) if notes.is_empty()
+        ));
+    }
+
+    #[tokio::test]
+    async fn admin_cmd_stats_timing() {
+        assert!(matches!(
+            run_admin_message(request::Admin::Timing).await,
+            Ok(response::Admin::Timing(report)) if report.len() == 4
+        ));
+    }
+
+    #[tokio::test]
+    async fn admin_cmd_stream_tag() {
+        assert!(matches!(
+            run_admin_message(request::Admin::StreamTag("rust".to_owned())).await,
+            Ok(response::Admin::StreamTag(Err(_)))
         ));
     }
 
@@ -407,7 +1810,10 @@ mod tests {
             .await
             .unwrap()
         {
-            response::Owner::Admins(response::Admins::List(list)) => assert!(list.is_empty()),
+            response::Owner::Admins(response::Admins::List { users, roles }) => {
+                assert!(users.is_empty());
+                assert!(roles.is_empty());
+            }
             res => panic!("unexpected response: {res:?}"),
         }
     }
@@ -425,4 +1831,30 @@ mod tests {
             res => panic!("unexpected response: {res:?}"),
         }
     }
+
+    #[tokio::test]
+    async fn owner_cmd_plugins_list() {
+        match run_owner_message(request::Owner::Plugins(request::Plugins::List))
+            .await
+            .unwrap()
+        {
+            response::Owner::Plugins(response::Plugins::List(list)) => assert!(list.is_empty()),
+            res => panic!("unexpected response: {res:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn owner_cmd_debug_parse() {
+        match run_owner_message(request::Owner::DebugParse {
+            text: "!ftoc abc".to_owned(),
+            source: Source::Discord,
+            mention: None,
+        })
+        .await
+        .unwrap()
+        {
+            response::Owner::DebugParse(text) => assert!(text.starts_with("Error:")),
+            res => panic!("unexpected response: {res:?}"),
+        }
+    }
 }
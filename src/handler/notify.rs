@@ -0,0 +1,176 @@
+//! Direct messages to Discord users that opted in, whenever the stream goes live.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use time::OffsetDateTime;
+use tracing::{error, info, instrument, warn};
+
+use crate::{
+    api::{response, AuthorId, Source, StreamInfo, UserRef},
+    connector::Connectors,
+    state::State,
+};
+
+/// Consecutive failed deliveries, after which a user is unsubscribed. This happens for example if
+/// they left the server or don't accept direct messages anymore.
+const MAX_FAILURES: u32 = 3;
+/// Pause between two direct messages, to stay clear of Discord's rate limits.
+const INTERVAL: Duration = Duration::from_secs(1);
+
+#[instrument(skip_all)]
+pub fn notify_me(
+    state: &State,
+    author: &AuthorId,
+    enabled: Option<bool>,
+    now: OffsetDateTime,
+) -> response::User {
+    info!("received `notifyme` command");
+
+    let user = author.user_ref();
+    let res = || -> Result<bool> {
+        let enabled = match enabled {
+            Some(enabled) => enabled,
+            None => !state.live_notification(&user)?,
+        };
+        state.set_live_notification(&user, enabled, now)?;
+        Ok(enabled)
+    };
+
+    response::User::NotifyMe(res())
+}
+
+/// Send a direct message to every user that opted in, one after another.
+pub async fn go_live(connectors: &Connectors, state: &State, streamer: &str, info: &StreamInfo) {
+    let content = if info.title.is_empty() {
+        format!("{streamer} is live now! https://twitch.tv/{streamer}")
+    } else {
+        format!(
+            "{streamer} is live now, streaming {} ({})! https://twitch.tv/{streamer}",
+            info.title, info.category
+        )
+    };
+
+    fan_out(connectors, state, &content, INTERVAL).await;
+}
+
+async fn fan_out(connectors: &Connectors, state: &State, content: &str, interval: Duration) {
+    let users = match state.list_live_notifications(Source::Discord) {
+        Ok(users) => users,
+        Err(e) => {
+            error!(error = ?e, "failed listing live notifications");
+            return;
+        }
+    };
+
+    info!(count = users.len(), "sending live notifications");
+
+    for (i, id) in users.into_iter().enumerate() {
+        if i > 0 {
+            tokio::time::sleep(interval).await;
+        }
+
+        let delivered = connectors
+            .send_direct(Source::Discord, id.clone(), content.to_owned())
+            .await;
+        if let Err(e) = &delivered {
+            warn!(error = ?e, user = id, "failed sending live notification");
+        }
+
+        let user = UserRef {
+            source: Source::Discord,
+            id,
+        };
+        match state.record_live_notification(&user, delivered.is_ok()) {
+            Ok(failures) if failures >= MAX_FAILURES => {
+                info!(?user, "removing live notification after repeated failures");
+                if let Err(e) = state.remove_live_notification(&user) {
+                    error!(error = ?e, "failed removing live notification");
+                }
+            }
+            Ok(_) => {}
+            Err(e) => error!(error = ?e, "failed recording live notification"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        num::NonZero,
+        sync::{Arc, Mutex},
+    };
+
+    use anyhow::bail;
+    use futures_util::future::BoxFuture;
+
+    use super::*;
+    use crate::{
+        api::Post,
+        connector::{Connector, Context},
+    };
+
+    /// Connector that can deliver direct messages to all users except `2`.
+    #[derive(Default)]
+    struct Fake(Arc<Mutex<Vec<String>>>);
+
+    impl Connector for Fake {
+        fn source(&self) -> Source {
+            Source::Discord
+        }
+
+        fn start(&self, _: Context) -> BoxFuture<'_, Result<()>> {
+            Box::pin(async { Ok(()) })
+        }
+
+        fn send_reply(&self, _: Post) -> BoxFuture<'_, Result<()>> {
+            Box::pin(async { Ok(()) })
+        }
+
+        fn send_direct(&self, user: String, _: String) -> BoxFuture<'_, Result<()>> {
+            Box::pin(async move {
+                if user == "2" {
+                    bail!("direct messages disabled");
+                }
+                self.0.lock().unwrap().push(user);
+                Ok(())
+            })
+        }
+
+        fn shutdown(&self) -> BoxFuture<'_, ()> {
+            Box::pin(async {})
+        }
+    }
+
+    #[tokio::test]
+    async fn remove_failing_users() {
+        let state = State::in_memory().unwrap();
+        let delivered = Arc::default();
+        let mut connectors = Connectors::default();
+        connectors.add(Fake(Arc::clone(&delivered)));
+
+        for id in [1, 2] {
+            let author = AuthorId::Discord(NonZero::new(id).unwrap());
+            let resp = notify_me(&state, &author, None, OffsetDateTime::UNIX_EPOCH);
+            assert!(matches!(resp, response::User::NotifyMe(Ok(true))));
+        }
+
+        for _ in 0..MAX_FAILURES {
+            fan_out(&connectors, &state, "live", Duration::ZERO).await;
+        }
+
+        assert_eq!(vec!["1"; 3], *delivered.lock().unwrap());
+        assert_eq!(
+            vec!["1".to_owned()],
+            state.list_live_notifications(Source::Discord).unwrap()
+        );
+
+        let resp = notify_me(
+            &state,
+            &AuthorId::Discord(NonZero::<u64>::MIN),
+            None,
+            OffsetDateTime::UNIX_EPOCH,
+        );
+        assert!(matches!(resp, response::User::NotifyMe(Ok(false))));
+    }
+}
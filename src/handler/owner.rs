@@ -1,12 +1,18 @@
+use std::num::NonZero;
+
 use anyhow::Result;
 use tracing::{info, instrument};
 
 use crate::{
     api::{
         response::{self, AdminAction},
-        AdminId,
+        AdminId, Source,
     },
+    plugins::Plugins,
+    settings::Commands as CommandSettings,
     state::State,
+    statistics::Stats,
+    textparse,
 };
 
 #[instrument(skip_all)]
@@ -18,9 +24,10 @@ pub fn help() -> response::Owner {
 #[instrument(skip_all)]
 pub fn admins_list(state: &State) -> Result<response::Owner> {
     info!("received `admins list` command");
-    let list = state.list_admins()?;
-
-    Ok(response::Owner::Admins(response::Admins::List(list)))
+    Ok(response::Owner::Admins(response::Admins::List {
+        users: state.list_admins()?,
+        roles: state.list_admin_roles()?,
+    }))
 }
 
 #[instrument(skip_all)]
@@ -32,6 +39,82 @@ pub fn admins_edit(state: &State, action: Action, id: AdminId) -> Result<respons
     )))
 }
 
+#[instrument(skip(state))]
+pub fn admin_roles_edit(state: &State, action: Action, id: NonZero<u64>) -> response::Owner {
+    info!("received `admins role` command");
+
+    let res = match action {
+        Action::Add => state.add_admin_role(id).map(|()| AdminAction::RoleAdded),
+        Action::Remove => state
+            .remove_admin_role(id)
+            .map(|()| AdminAction::RoleRemoved),
+    };
+
+    response::Owner::Admins(response::Admins::Edit(res))
+}
+
+#[instrument(skip_all)]
+pub fn plugins_list(plugins: &Plugins) -> response::Owner {
+    info!("received `plugins list` command");
+    response::Owner::Plugins(response::Plugins::List(plugins.list()))
+}
+
+#[instrument(skip(plugins))]
+pub fn plugins_edit(plugins: &Plugins, name: &str, load: bool) -> response::Owner {
+    info!("received `plugins` command");
+    response::Owner::Plugins(response::Plugins::Edit(plugins.set_loaded(name, load)))
+}
+
+#[instrument(skip_all)]
+pub fn debug_parse(text: &str, source: Source, mention: Option<NonZero<u64>>) -> response::Owner {
+    info!("received `debug parse` command");
+    response::Owner::DebugParse(match textparse::parse(text, source, mention) {
+        Ok(req) => format!("{req:#?}"),
+        Err(e) => format!("Error: {e:?}"),
+    })
+}
+
+#[instrument(skip_all)]
+pub fn cleanup(settings: &CommandSettings, statistics: &Stats) -> response::Owner {
+    info!("received `cleanup` command");
+    response::Owner::Cleanup(statistics.cleanup(settings.retention))
+}
+
+#[instrument(skip_all)]
+pub fn resync_commands() -> response::Owner {
+    info!("received `resync_commands` command");
+    response::Owner::ResyncCommands
+}
+
+#[instrument(skip_all)]
+pub fn broadcast(text: String) -> response::Owner {
+    info!("received `broadcast` command");
+    response::Owner::Broadcast(text)
+}
+
+#[cfg(feature = "chaos")]
+#[instrument(skip_all)]
+pub fn chaos(req: crate::api::request::Chaos) -> response::Owner {
+    use crate::{
+        api::request,
+        chaos::{self, Faults},
+    };
+
+    info!("received `chaos` command");
+    response::Owner::Chaos(match req {
+        request::Chaos::Status => chaos::faults(),
+        request::Chaos::Off => chaos::configure(|faults| *faults = Faults::NONE),
+        request::Chaos::Delay { rate, max } => chaos::configure(|faults| {
+            faults.delay_rate = rate;
+            faults.delay_max = max;
+        }),
+        request::Chaos::SendFailure(rate) => {
+            chaos::configure(|faults| faults.send_failure_rate = rate)
+        }
+        request::Chaos::EventDrop(rate) => chaos::configure(|faults| faults.event_drop_rate = rate),
+    })
+}
+
 #[derive(Clone, Copy, Debug)]
 pub(super) enum Action {
     Add,
@@ -0,0 +1,171 @@
+//! Relay of code blocks from Discord to the Twitch chat.
+//!
+//! Twitch can't show code blocks, so the code is uploaded to a paste service instead, and only a
+//! short link to it is posted. Services like `paste.rs` highlight the code, when the link ends
+//! with the file extension of its language.
+
+use std::time::Duration;
+
+use anyhow::{ensure, Result};
+use tracing::{error, info};
+
+use crate::{
+    api::{Event, Post, Source},
+    outbox::Outbox,
+    settings::CodeRelay as CodeRelaySettings,
+};
+
+/// Fenced code blocks of a Markdown message, with their language tag if given.
+#[must_use]
+pub fn code_blocks(content: &str) -> Vec<(Option<&str>, &str)> {
+    let parts = content.split("```").collect::<Vec<_>>();
+
+    // Every second part is inside a block, but the last one only if the block is closed.
+    parts
+        .iter()
+        .copied()
+        .skip(1)
+        .step_by(2)
+        .take((parts.len() - 1) / 2)
+        .filter_map(|block| {
+            let (lang, code) = match block.split_once('\n') {
+                Some((lang, code)) if !lang.contains(char::is_whitespace) => (lang, code),
+                _ => ("", block),
+            };
+            let code = code.trim_matches('\n');
+
+            (!code.trim().is_empty()).then_some(((!lang.is_empty()).then_some(lang), code))
+        })
+        .collect()
+}
+
+/// Upload the code block of the event, and post the link to it in the Twitch chat, if it was
+/// shared in one of the relayed channels.
+pub async fn relay_code(outbox: &Outbox, settings: &CodeRelaySettings, event: &Event) {
+    let Event::CodeShared {
+        channel,
+        user,
+        language,
+        code,
+    } = event
+    else {
+        return;
+    };
+
+    if !settings.channels.contains(channel) {
+        return;
+    }
+
+    info!(%channel, user, "relaying code block to Twitch");
+
+    let link = match upload(&settings.paste_url, code).await {
+        Ok(link) => link,
+        Err(e) => {
+            error!(error = ?e, "failed uploading code block");
+            return;
+        }
+    };
+
+    let post = Post {
+        channel: None,
+        content: announcement(user, language.as_deref(), &link),
+    };
+    if let Err(e) = outbox.publish(Source::Twitch, post) {
+        error!(error = ?e, "failed relaying code block");
+    }
+}
+
+/// Upload the code to the paste service, returning the link to it.
+async fn upload(url: &str, code: &str) -> Result<String> {
+    let link = reqwest::Client::builder()
+        .user_agent("ToggleBot (https://github.com/dnaka91/togglebot)")
+        .timeout(Duration::from_secs(10))
+        .build()?
+        .post(url)
+        .body(code.to_owned())
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let link = link.trim();
+
+    ensure!(
+        link.starts_with("https://") || link.starts_with("http://"),
+        "paste service didn't reply with a link"
+    );
+
+    Ok(link.to_owned())
+}
+
+fn announcement(user: &str, language: Option<&str>, link: &str) -> String {
+    match language.and_then(extension) {
+        Some(ext) => format!("{user} shared some code on Discord: {link}.{ext}"),
+        None => format!("{user} shared some code on Discord: {link}"),
+    }
+}
+
+/// File extension for the language tag of a code block, as paste services expect it for
+/// highlighting.
+fn extension(language: &str) -> Option<&str> {
+    let language = language.trim();
+    if language.is_empty() || !language.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+
+    Some(match language.to_ascii_lowercase().as_str() {
+        "rust" => "rs",
+        "python" => "py",
+        "javascript" => "js",
+        "typescript" => "ts",
+        "bash" | "shell" | "zsh" => "sh",
+        "golang" => "go",
+        "ruby" => "rb",
+        "kotlin" => "kt",
+        "csharp" => "cs",
+        "markdown" => "md",
+        "yml" => "yaml",
+        _ => language,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_code_blocks() {
+        assert_eq!(
+            vec![(Some("rust"), "fn main() {}")],
+            code_blocks("look at this:\n```rust\nfn main() {}\n```")
+        );
+        assert_eq!(
+            vec![(None, "let a = 1;"), (Some("toml"), "[a]\nb = 1")],
+            code_blocks("```\nlet a = 1;\n``` and ```toml\n[a]\nb = 1\n```")
+        );
+        assert_eq!(vec![(None, "a + b")], code_blocks("```a + b```"));
+        assert!(code_blocks("```\n\n```").is_empty());
+        assert!(code_blocks("no `code` here").is_empty());
+        assert!(code_blocks("```rust\nunclosed").is_empty());
+    }
+
+    #[test]
+    fn announcement_links() {
+        assert_eq!(
+            "togglebit shared some code on Discord: https://paste.rs/Abc.rs",
+            announcement("togglebit", Some("rust"), "https://paste.rs/Abc")
+        );
+        assert_eq!(
+            "togglebit shared some code on Discord: https://paste.rs/Abc.toml",
+            announcement("togglebit", Some("toml"), "https://paste.rs/Abc")
+        );
+        assert_eq!(
+            "togglebit shared some code on Discord: https://paste.rs/Abc",
+            announcement("togglebit", Some("c++"), "https://paste.rs/Abc")
+        );
+        assert_eq!(
+            "togglebit shared some code on Discord: https://paste.rs/Abc",
+            announcement("togglebit", None, "https://paste.rs/Abc")
+        );
+    }
+}
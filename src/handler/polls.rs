@@ -0,0 +1,118 @@
+//! Polls and predictions, that moderators start from the chat.
+//!
+//! The limits follow the ones of Twitch, as the Discord mirror is much more lenient.
+
+use std::{ops::RangeInclusive, time::Duration};
+
+use anyhow::{ensure, Result};
+use tracing::{info, instrument};
+
+use crate::api::{response, Author, Poll, PollKind};
+
+/// Limits for a kind of poll.
+struct Limits {
+    question: usize,
+    option: usize,
+    options: RangeInclusive<usize>,
+    duration: RangeInclusive<Duration>,
+}
+
+impl Limits {
+    const fn of(kind: PollKind) -> Self {
+        match kind {
+            PollKind::Poll => Self {
+                question: 60,
+                option: 25,
+                options: 2..=5,
+                duration: Duration::from_secs(15)..=Duration::from_mins(30),
+            },
+            PollKind::Prediction => Self {
+                question: 45,
+                option: 25,
+                options: 2..=10,
+                duration: Duration::from_secs(30)..=Duration::from_mins(30),
+            },
+        }
+    }
+}
+
+#[instrument(skip_all)]
+pub fn start(author: &Author, poll: Poll) -> response::User {
+    info!("received `{}` command", poll.kind.name());
+    response::User::Poll(validate(author, poll))
+}
+
+fn validate(author: &Author, poll: Poll) -> Result<Poll> {
+    let kind = poll.kind.name();
+    let limits = Limits::of(poll.kind);
+
+    ensure!(author.is_moderator(), "only moderators can start a {kind}");
+    ensure!(
+        (1..=limits.question).contains(&poll.question.chars().count()),
+        "the question must have between 1 and {} characters",
+        limits.question,
+    );
+    ensure!(
+        limits.options.contains(&poll.options.len()),
+        "a {kind} needs between {} and {} options",
+        limits.options.start(),
+        limits.options.end(),
+    );
+    ensure!(
+        poll.options
+            .iter()
+            .all(|option| option.chars().count() <= limits.option),
+        "options must have at most {} characters",
+        limits.option,
+    );
+    ensure!(
+        limits.duration.contains(&poll.duration),
+        "a {kind} must run between {}s and {}m",
+        limits.duration.start().as_secs(),
+        limits.duration.end().as_secs() / 60,
+    );
+
+    Ok(poll)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::AuthorId;
+
+    fn moderator() -> Author {
+        Author {
+            roles: vec!["moderator".to_owned()],
+            ..Author::from(AuthorId::Twitch("1".to_owned()))
+        }
+    }
+
+    fn poll(kind: PollKind, options: &[&str], secs: u64) -> Poll {
+        Poll {
+            kind,
+            question: "Which one?".to_owned(),
+            options: options.iter().map(|&o| o.to_owned()).collect(),
+            duration: Duration::from_secs(secs),
+        }
+    }
+
+    #[test]
+    fn limits() {
+        let valid = poll(PollKind::Poll, &["a", "b"], 60);
+        assert!(validate(&moderator(), valid.clone()).is_ok());
+        assert!(validate(&AuthorId::Twitch("2".to_owned()).into(), valid).is_err());
+
+        assert!(validate(&moderator(), poll(PollKind::Poll, &["a"], 60)).is_err());
+        assert!(validate(&moderator(), poll(PollKind::Poll, &["a", "b"], 5)).is_err());
+        assert!(validate(
+            &moderator(),
+            poll(PollKind::Poll, &["a", "b", "c", "d", "e", "f"], 60)
+        )
+        .is_err());
+        assert!(validate(
+            &moderator(),
+            poll(PollKind::Prediction, &["a", "b", "c", "d", "e", "f"], 60)
+        )
+        .is_ok());
+    }
+}
@@ -0,0 +1,146 @@
+//! Personal settings of users, that commands like `!today` or `!convert` apply automatically.
+
+use anyhow::Result;
+use tracing::{info, instrument, warn};
+
+use crate::{
+    api::{
+        request,
+        response::{self, Prefs},
+        AuthorId, UserPref, UserPrefs,
+    },
+    state::State,
+};
+
+#[instrument(skip_all)]
+pub fn prefs(state: &State, author: &AuthorId, req: request::Prefs) -> response::User {
+    info!("received `set` command");
+
+    response::User::Prefs(match req {
+        request::Prefs::Show => load(state, author).map(Prefs::Current),
+        request::Prefs::Set(pref) => set(state, author, pref),
+        request::Prefs::Reset(key) => reset(state, author, key),
+    })
+}
+
+fn set(state: &State, author: &AuthorId, pref: UserPref) -> Result<Prefs> {
+    state.set_user_pref(&author.user_ref(), pref.key(), &pref.value())?;
+    Ok(Prefs::Saved(pref))
+}
+
+fn reset(state: &State, author: &AuthorId, key: String) -> Result<Prefs> {
+    Ok(if state.remove_user_pref(&author.user_ref(), &key)? {
+        Prefs::Reset(key)
+    } else {
+        Prefs::NotSet(key)
+    })
+}
+
+/// Settings of the author, to apply to the command they sent.
+///
+/// A failure to load them only means that the command falls back to its defaults, so it's logged
+/// instead of failing the whole command.
+pub fn resolve(state: &State, author: &AuthorId) -> UserPrefs {
+    load(state, author).unwrap_or_else(|e| {
+        warn!(error = ?e, "failed loading user settings");
+        UserPrefs::default()
+    })
+}
+
+/// Load all settings of the author. Saved values that aren't valid anymore are skipped.
+fn load(state: &State, author: &AuthorId) -> Result<UserPrefs> {
+    let mut prefs = UserPrefs::default();
+
+    for (key, value) in state.user_prefs(&author.user_ref())? {
+        match UserPref::parse(&key, &value) {
+            Ok(pref) => prefs.set(pref),
+            Err(e) => warn!(error = ?e, key, "skipping invalid user setting"),
+        }
+    }
+
+    Ok(prefs)
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+    use time::UtcOffset;
+
+    use super::*;
+    use crate::{api::TemperatureUnit, locale::Language};
+
+    #[test]
+    fn set_and_resolve() {
+        let state = State::in_memory().unwrap();
+        let author = AuthorId::Twitch("1".to_owned());
+
+        assert!(resolve(&state, &author).is_empty());
+
+        set(&state, &author, UserPref::Unit(TemperatureUnit::Fahrenheit)).unwrap();
+        set(
+            &state,
+            &author,
+            UserPref::Timezone(UtcOffset::from_hms(5, 30, 0).unwrap()),
+        )
+        .unwrap();
+        set(&state, &author, UserPref::Locale(Language::German)).unwrap();
+
+        assert_eq!(
+            UserPrefs {
+                unit: Some(TemperatureUnit::Fahrenheit),
+                timezone: Some(UtcOffset::from_hms(5, 30, 0).unwrap()),
+                locale: Some(Language::German),
+            },
+            resolve(&state, &author)
+        );
+
+        assert!(matches!(
+            reset(&state, &author, "locale".to_owned()).unwrap(),
+            Prefs::Reset(_)
+        ));
+        assert!(matches!(
+            reset(&state, &author, "locale".to_owned()).unwrap(),
+            Prefs::NotSet(_)
+        ));
+        assert_eq!(None, resolve(&state, &author).locale);
+    }
+
+    #[test]
+    fn skip_invalid() {
+        let state = State::in_memory().unwrap();
+        let author = AuthorId::Console;
+
+        state
+            .set_user_pref(&author.user_ref(), "unit", "kelvin")
+            .unwrap();
+        state
+            .set_user_pref(&author.user_ref(), "locale", "de")
+            .unwrap();
+
+        assert_eq!(
+            UserPrefs {
+                locale: Some(Language::German),
+                ..UserPrefs::default()
+            },
+            resolve(&state, &author)
+        );
+    }
+
+    #[test]
+    fn offsets() {
+        let offset = |value| match UserPref::parse("timezone", value).unwrap() {
+            UserPref::Timezone(offset) => offset,
+            pref => panic!("unexpected setting {pref:?}"),
+        };
+
+        assert_eq!(UtcOffset::UTC, offset("UTC"));
+        assert_eq!(UtcOffset::from_hms(2, 0, 0).unwrap(), offset("+2"));
+        assert_eq!(
+            UtcOffset::from_hms(-9, -30, 0).unwrap(),
+            offset("utc-09:30")
+        );
+        assert_eq!("UTC-09:30", UserPref::Timezone(offset("-9:30")).value());
+        assert!(UserPref::parse("timezone", "2").is_err());
+        assert!(UserPref::parse("timezone", "+2:60").is_err());
+    }
+}
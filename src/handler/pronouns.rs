@@ -0,0 +1,299 @@
+//! Pronoun lookup, from the [alejo.io](https://pronouns.alejo.io) service for Twitch users and from
+//! the bot's own database for everybody else.
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
+
+use anyhow::{bail, Result};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use tracing::{info, instrument};
+
+use crate::{
+    api::{
+        request,
+        response::{self, Pronouns},
+        AuthorId, Source, UserRef,
+    },
+    state::State,
+};
+
+/// Base URL of the pronoun API for Twitch users.
+const API_URL: &str = "https://api.pronouns.alejo.io/v1";
+
+/// Longest pronouns that users can set for themselves.
+const MAX_LENGTH: usize = 32;
+
+/// Longest login name that Twitch allows.
+const MAX_LOGIN_LENGTH: usize = 25;
+
+/// All pronouns known to the API, which practically never change and are only fetched once.
+#[derive(Default)]
+pub(super) struct Known(Mutex<HashMap<String, Pronoun>>);
+
+#[derive(Deserialize)]
+struct ApiUser {
+    pronoun_id: String,
+    alt_pronoun_id: Option<String>,
+}
+
+#[derive(Clone, Deserialize)]
+struct Pronoun {
+    subject: String,
+    object: String,
+    singular: bool,
+}
+
+#[instrument(skip_all)]
+pub async fn pronouns(
+    known: &Known,
+    state: &State,
+    source: Source,
+    author: &AuthorId,
+    req: request::Pronouns,
+) -> response::User {
+    info!("received `pronouns` command");
+
+    response::User::Pronouns(match req {
+        request::Pronouns::Show(target) => show(known, state, source, &target).await,
+        request::Pronouns::Set(pronouns) => set(state, author, &pronouns),
+        request::Pronouns::Unset => unset(state, author),
+    })
+}
+
+async fn show(known: &Known, state: &State, source: Source, target: &str) -> Result<Pronouns> {
+    let Some(user) = resolve(source, target)
+        .filter(|user| user.source != Source::Twitch || valid_login(&user.id))
+    else {
+        return Ok(Pronouns::Refused(format!(
+            "I don't know who {target} is, try to mention them"
+        )));
+    };
+
+    let pronouns = if user.source == Source::Twitch {
+        lookup_twitch(known, &user.id).await?
+    } else {
+        state.pronouns(&user)?
+    };
+
+    Ok(match pronouns {
+        Some(pronouns) => Pronouns::Found {
+            user: target.to_owned(),
+            pronouns,
+        },
+        None => Pronouns::NotSet(target.to_owned()),
+    })
+}
+
+fn set(state: &State, author: &AuthorId, pronouns: &str) -> Result<Pronouns> {
+    let user = author.user_ref();
+    if user.source == Source::Twitch {
+        return Ok(twitch_refusal());
+    }
+
+    if pronouns.is_empty() || pronouns.chars().count() > MAX_LENGTH {
+        return Ok(Pronouns::Refused(format!(
+            "Pronouns must be between 1 and {MAX_LENGTH} characters long"
+        )));
+    }
+
+    state.set_pronouns(&user, pronouns)?;
+    Ok(Pronouns::Saved)
+}
+
+fn unset(state: &State, author: &AuthorId) -> Result<Pronouns> {
+    let user = author.user_ref();
+    if user.source == Source::Twitch {
+        return Ok(twitch_refusal());
+    }
+
+    Ok(if state.remove_pronouns(&user)? {
+        Pronouns::Removed
+    } else {
+        Pronouns::Refused("You didn't share any pronouns yet".to_owned())
+    })
+}
+
+fn twitch_refusal() -> Pronouns {
+    Pronouns::Refused(
+        "On Twitch, you can set your pronouns at https://pronouns.alejo.io".to_owned(),
+    )
+}
+
+/// Find the user, either as mention or `<service>:<name>` reference, or by plain name on Twitch.
+fn resolve(source: Source, target: &str) -> Option<UserRef> {
+    target.parse().ok().or_else(|| {
+        (source == Source::Twitch).then(|| UserRef {
+            source: Source::Twitch,
+            id: target.trim_start_matches('@').to_lowercase(),
+        })
+    })
+}
+
+/// Whether the name is a possible Twitch login, which is safe to put into the API's URL.
+fn valid_login(login: &str) -> bool {
+    (1..=MAX_LOGIN_LENGTH).contains(&login.len())
+        && login
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'_')
+}
+
+async fn lookup_twitch(known: &Known, login: &str) -> Result<Option<String>> {
+    let client = reqwest::Client::builder()
+        .user_agent("ToggleBot (https://github.com/dnaka91/togglebot)")
+        .timeout(Duration::from_secs(10))
+        .build()?;
+
+    let resp = client
+        .get(format!("{API_URL}/users/{login}"))
+        .send()
+        .await?;
+    let user = match resp.status() {
+        StatusCode::OK => resp.json::<ApiUser>().await?,
+        StatusCode::NOT_FOUND => return Ok(None),
+        s => bail!("unexpected status code {s:?}"),
+    };
+
+    let missing = known
+        .0
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .is_empty();
+
+    if missing {
+        let fetched = client
+            .get(format!("{API_URL}/pronouns"))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<HashMap<String, Pronoun>>()
+            .await?;
+
+        *known.0.lock().unwrap_or_else(PoisonError::into_inner) = fetched;
+    }
+
+    Ok(Some(format_pronouns(
+        &user,
+        &known.0.lock().unwrap_or_else(PoisonError::into_inner),
+    )))
+}
+
+/// Format the pronouns like `she/her`, or like `she/they` if the user has alternative pronouns.
+fn format_pronouns(user: &ApiUser, known: &HashMap<String, Pronoun>) -> String {
+    let Some(primary) = known.get(&user.pronoun_id) else {
+        return user.pronoun_id.clone();
+    };
+
+    match user.alt_pronoun_id.as_ref().and_then(|id| known.get(id)) {
+        Some(alt) => format!("{}/{}", primary.subject, alt.subject),
+        None if primary.singular => primary.subject.clone(),
+        None => format!("{}/{}", primary.subject, primary.object),
+    }
+    .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZero;
+
+    use similar_asserts::assert_eq;
+
+    use super::*;
+
+    fn known() -> HashMap<String, Pronoun> {
+        [
+            ("sheher", "She", "Her", false),
+            ("theythem", "They", "Them", false),
+            ("any", "Any", "Any", true),
+        ]
+        .into_iter()
+        .map(|(id, subject, object, singular)| {
+            (
+                id.to_owned(),
+                Pronoun {
+                    subject: subject.to_owned(),
+                    object: object.to_owned(),
+                    singular,
+                },
+            )
+        })
+        .collect()
+    }
+
+    fn user(primary: &str, alt: Option<&str>) -> ApiUser {
+        ApiUser {
+            pronoun_id: primary.to_owned(),
+            alt_pronoun_id: alt.map(ToOwned::to_owned),
+        }
+    }
+
+    #[test]
+    fn format() {
+        let known = known();
+
+        assert_eq!("she/her", format_pronouns(&user("sheher", None), &known));
+        assert_eq!(
+            "she/they",
+            format_pronouns(&user("sheher", Some("theythem")), &known)
+        );
+        assert_eq!("any", format_pronouns(&user("any", None), &known));
+        assert_eq!("unknown", format_pronouns(&user("unknown", None), &known));
+    }
+
+    #[test]
+    fn resolve_users() {
+        assert_eq!(
+            Some(UserRef {
+                source: Source::Twitch,
+                id: "someone".to_owned()
+            }),
+            resolve(Source::Twitch, "@SomeOne")
+        );
+        assert_eq!(
+            Some(UserRef {
+                source: Source::Discord,
+                id: "123".to_owned()
+            }),
+            resolve(Source::Discord, "<@123>")
+        );
+        assert_eq!(None, resolve(Source::Discord, "someone"));
+    }
+
+    #[test]
+    fn valid_logins() {
+        assert!(valid_login("some_one123"));
+        assert!(valid_login(&"a".repeat(25)));
+        assert!(!valid_login(""));
+        assert!(!valid_login(&"a".repeat(26)));
+        assert!(!valid_login("../pronouns"));
+        assert!(!valid_login("some one"));
+        assert!(!valid_login("someone?x=1"));
+    }
+
+    #[test]
+    fn set_and_show() {
+        let state = State::in_memory().unwrap();
+        let author = AuthorId::Discord(NonZero::new(123).unwrap());
+
+        assert!(matches!(
+            set(&state, &author, "they/them").unwrap(),
+            Pronouns::Saved
+        ));
+        assert_eq!(
+            Some("they/them".to_owned()),
+            state.pronouns(&author.user_ref()).unwrap()
+        );
+        assert!(matches!(unset(&state, &author).unwrap(), Pronouns::Removed));
+        assert!(matches!(
+            unset(&state, &author).unwrap(),
+            Pronouns::Refused(_)
+        ));
+        assert!(matches!(
+            set(&state, &AuthorId::Twitch("1".to_owned()), "she/her").unwrap(),
+            Pronouns::Refused(_)
+        ));
+    }
+}
@@ -0,0 +1,136 @@
+//! Automatic shoutouts for channels that raid the stream.
+//!
+//! Shoutouts can be turned off by admins, and every channel only gets one shoutout within the
+//! configured cooldown, so repeated raids don't flood the chat.
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use tracing::{error, info};
+
+use super::Memory;
+use crate::{api::Event, locale, settings::Shoutout as ShoutoutSettings, state::State};
+
+/// Name of the toggle, that admins use to turn shoutouts on and off.
+pub const TOGGLE: &str = "shoutout";
+
+/// Last shoutout for each raiding channel.
+pub(super) type Shoutouts = Mutex<HashMap<String, Instant>>;
+
+/// Create the shoutout message for a raid, or nothing for any other event or if no shoutout should
+/// be posted right now.
+#[must_use]
+pub fn shoutout(
+    memory: &Memory,
+    settings: &ShoutoutSettings,
+    state: &State,
+    event: &Event,
+) -> Option<String> {
+    let Event::Raid {
+        user,
+        login,
+        viewers,
+        category,
+        ..
+    } = event
+    else {
+        return None;
+    };
+
+    info!(%login, viewers, "channel raided the stream");
+
+    match state.toggle(TOGGLE, true) {
+        Ok(true) => {}
+        Ok(false) => return None,
+        Err(e) => {
+            error!(error = ?e, "failed checking whether shoutouts are enabled");
+            return None;
+        }
+    }
+
+    let cooldown = Duration::from_secs(settings.cooldown);
+    if !cooled_down(&memory.shoutouts, login, cooldown, Instant::now()) {
+        info!(%login, "skipping shoutout, channel raided recently");
+        return None;
+    }
+
+    Some(locale::fill(
+        &settings.template,
+        &[
+            ("user", user),
+            ("login", login),
+            ("viewers", viewers),
+            ("category", &category.as_deref().unwrap_or("something")),
+        ],
+    ))
+}
+
+/// Check whether the channel's last shoutout is longer ago than the cooldown, and remember this
+/// one if so.
+fn cooled_down(last: &Shoutouts, login: &str, cooldown: Duration, now: Instant) -> bool {
+    let mut last = last.lock().unwrap_or_else(PoisonError::into_inner);
+    last.retain(|_, sent| now.duration_since(*sent) < cooldown);
+
+    if last.contains_key(login) {
+        return false;
+    }
+
+    last.insert(login.to_owned(), now);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::Source;
+
+    #[test]
+    fn cooldown() {
+        let last = Mutex::default();
+        let cooldown = Duration::from_mins(10);
+        let now = Instant::now();
+
+        assert!(cooled_down(&last, "somebody", cooldown, now));
+        assert!(!cooled_down(
+            &last,
+            "somebody",
+            cooldown,
+            now + Duration::from_mins(5)
+        ));
+        assert!(cooled_down(&last, "other", cooldown, now));
+        assert!(cooled_down(
+            &last,
+            "somebody",
+            cooldown,
+            now + Duration::from_mins(10)
+        ));
+    }
+
+    #[test]
+    fn disabled() {
+        let memory = Memory::default();
+        let state = State::in_memory().unwrap();
+        let settings = ShoutoutSettings {
+            template: "{user} raided with {viewers} viewers from {category}".to_owned(),
+            cooldown: 0,
+        };
+        let event = Event::Raid {
+            source: Source::Twitch,
+            user: "Somebody".to_owned(),
+            login: "somebody".to_owned(),
+            viewers: 5,
+            category: None,
+        };
+
+        assert_eq!(
+            Some("Somebody raided with 5 viewers from something".to_owned()),
+            shoutout(&memory, &settings, &state, &event)
+        );
+
+        state.set_toggle(TOGGLE, false).unwrap();
+        assert_eq!(None, shoutout(&memory, &settings, &state, &event));
+    }
+}
@@ -0,0 +1,277 @@
+//! Announcements of new crate versions, for the crates that admins watch with `!watchcrate`.
+//!
+//! Versions are looked up in the sparse index of crates.io, which is meant to be polled. The entity
+//! tag of each response is kept, so checking a crate without any new versions is a tiny request.
+
+use std::time::Duration;
+
+use anyhow::{bail, ensure, Result};
+use reqwest::{header, StatusCode};
+use serde::Deserialize;
+use tracing::{error, info, instrument, warn};
+
+use crate::{
+    api::{
+        request,
+        response::{self, WatchedCrate},
+        Post, Source,
+    },
+    outbox::Outbox,
+    settings::CrateReleases as CrateReleasesSettings,
+    state::State,
+};
+
+/// Location of the sparse crates.io index.
+const INDEX_URL: &str = "https://index.crates.io";
+
+/// Outcome of looking up a crate in the index.
+enum Index {
+    /// Nothing changed since the last lookup.
+    Unchanged,
+    /// The crate doesn't exist.
+    Missing,
+    /// Latest version of the crate, with the entity tag of the response.
+    Found {
+        version: String,
+        etag: Option<String>,
+    },
+}
+
+#[instrument(skip(state))]
+pub async fn watch_crates(state: &State, req: request::WatchCrates) -> response::Admin {
+    info!("received `watchcrate` command");
+
+    response::Admin::WatchCrates(match req {
+        request::WatchCrates::List => response::WatchCrates::List(state.watched_crates()),
+        request::WatchCrates::Add(name) => {
+            response::WatchCrates::Add(watch(state, &name.to_lowercase()).await)
+        }
+        request::WatchCrates::Remove(name) => response::WatchCrates::Remove(
+            state.unwatch_crate(&name.to_lowercase()).and_then(|found| {
+                ensure!(found, "the crate `{name}` isn't watched");
+                Ok(())
+            }),
+        ),
+    })
+}
+
+async fn watch(state: &State, name: &str) -> Result<WatchedCrate> {
+    ensure!(is_valid(name), "`{name}` isn't a valid crate name");
+
+    let watched = match fetch(&client()?, name, None).await? {
+        Index::Found { version, etag } => WatchedCrate {
+            name: name.to_owned(),
+            version,
+            etag,
+        },
+        Index::Missing => bail!("the crate `{name}` doesn't exist"),
+        Index::Unchanged => bail!("unexpected unchanged response for `{name}`"),
+    };
+
+    ensure!(
+        state.watch_crate(&watched)?,
+        "the crate `{name}` is already watched"
+    );
+    Ok(watched)
+}
+
+/// Check all watched crates for new versions, and announce them in the configured places.
+pub async fn announce_releases(outbox: &Outbox, state: &State, settings: &CrateReleasesSettings) {
+    let watched = match state.watched_crates() {
+        Ok(watched) => watched,
+        Err(e) => {
+            error!(error = ?e, "failed listing watched crates");
+            return;
+        }
+    };
+    let client = match client() {
+        Ok(client) => client,
+        Err(e) => {
+            error!(error = ?e, "failed creating HTTP client");
+            return;
+        }
+    };
+
+    for watched in watched {
+        let (version, etag) = match fetch(&client, &watched.name, watched.etag.as_deref()).await {
+            Ok(Index::Found { version, etag }) => (version, etag),
+            Ok(Index::Unchanged) => continue,
+            Ok(Index::Missing) => {
+                warn!(name = watched.name, "watched crate doesn't exist anymore");
+                continue;
+            }
+            Err(e) => {
+                error!(error = ?e, name = watched.name, "failed checking for new versions");
+                continue;
+            }
+        };
+
+        let released = version != watched.version;
+        let updated = WatchedCrate {
+            version,
+            etag,
+            ..watched
+        };
+        if let Err(e) = state.update_watched_crate(&updated) {
+            error!(error = ?e, name = updated.name, "failed saving the latest version");
+            continue;
+        }
+
+        if released {
+            info!(
+                name = updated.name,
+                version = updated.version,
+                "announcing new version"
+            );
+            announce(outbox, settings, &announcement(&updated));
+        }
+    }
+}
+
+fn announce(outbox: &Outbox, settings: &CrateReleasesSettings, content: &str) {
+    let targets = [
+        (Source::Discord, settings.discord_channel.map(Some)),
+        (Source::Twitch, settings.twitch.then_some(None)),
+    ];
+
+    for (source, channel) in targets {
+        let Some(channel) = channel else { continue };
+        let post = Post {
+            channel,
+            content: content.to_owned(),
+        };
+
+        if let Err(e) = outbox.publish(source, post) {
+            error!(error = ?e, %source, "failed announcing new crate version");
+        }
+    }
+}
+
+fn announcement(watched: &WatchedCrate) -> String {
+    format!(
+        "📦 {0} {1} was just released: https://crates.io/crates/{0}/{1}",
+        watched.name, watched.version
+    )
+}
+
+fn client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent("ToggleBot (https://github.com/dnaka91/togglebot)")
+        .timeout(Duration::from_secs(10))
+        .build()
+        .map_err(Into::into)
+}
+
+/// Look up the latest version of a crate, unless the index didn't change since the `etag`.
+async fn fetch(client: &reqwest::Client, name: &str, etag: Option<&str>) -> Result<Index> {
+    let mut req = client.get(format!("{INDEX_URL}/{}", index_path(name)));
+    if let Some(etag) = etag {
+        req = req.header(header::IF_NONE_MATCH, etag);
+    }
+
+    let resp = req.send().await?;
+
+    Ok(match resp.status() {
+        StatusCode::OK => {
+            let etag = resp
+                .headers()
+                .get(header::ETAG)
+                .and_then(|etag| etag.to_str().ok())
+                .map(ToOwned::to_owned);
+
+            match latest(&resp.text().await?) {
+                Some(version) => Index::Found { version, etag },
+                None => Index::Missing,
+            }
+        }
+        StatusCode::NOT_MODIFIED => Index::Unchanged,
+        StatusCode::NOT_FOUND => Index::Missing,
+        s => bail!("unexpected status code {s:?}"),
+    })
+}
+
+/// Whether the name follows the rules of crates.io, which also makes it safe to use in the path.
+fn is_valid(name: &str) -> bool {
+    (1..=64).contains(&name.len())
+        && name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Path of a crate's file in the index, which depends on the length of its name.
+fn index_path(name: &str) -> String {
+    match name.len() {
+        1 => format!("1/{name}"),
+        2 => format!("2/{name}"),
+        3 => format!("3/{}/{name}", &name[..1]),
+        _ => format!("{}/{}/{name}", &name[..2], &name[2..4]),
+    }
+}
+
+/// Most recently published version that wasn't yanked. The index lists one version per line, in
+/// the order they were published.
+fn latest(index: &str) -> Option<String> {
+    #[derive(Deserialize)]
+    struct Entry {
+        vers: String,
+        #[serde(default)]
+        yanked: bool,
+    }
+
+    index
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Entry>(line).ok())
+        .rfind(|entry| !entry.yanked)
+        .map(|entry| entry.vers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn index_paths() {
+        assert_eq!("1/a", index_path("a"));
+        assert_eq!("2/io", index_path("io"));
+        assert_eq!("3/s/syn", index_path("syn"));
+        assert_eq!("to/ki/tokio", index_path("tokio"));
+    }
+
+    #[test]
+    fn crate_names() {
+        assert!(is_valid("tokio"));
+        assert!(is_valid("serde_json"));
+        assert!(is_valid("tracing-subscriber"));
+        assert!(!is_valid("../etc/passwd"));
+        assert!(!is_valid("1password"));
+        assert!(!is_valid(""));
+    }
+
+    #[test]
+    fn latest_version() {
+        let index = concat!(
+            r#"{"name":"demo","vers":"1.0.0","deps":[],"yanked":false}"#,
+            "\n",
+            r#"{"name":"demo","vers":"1.1.0","deps":[],"yanked":false}"#,
+            "\n",
+            r#"{"name":"demo","vers":"1.2.0","deps":[],"yanked":true}"#,
+            "\n",
+        );
+
+        assert_eq!(Some("1.1.0".to_owned()), latest(index));
+        assert_eq!(None, latest(""));
+    }
+
+    #[test]
+    fn announcement_text() {
+        assert_eq!(
+            "📦 tokio 1.42.0 was just released: https://crates.io/crates/tokio/1.42.0",
+            announcement(&WatchedCrate {
+                name: "tokio".to_owned(),
+                version: "1.42.0".to_owned(),
+                etag: None,
+            })
+        );
+    }
+}
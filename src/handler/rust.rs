@@ -0,0 +1,221 @@
+//! Versions of the Rust toolchain, for the `!rustversion` command and announcements of new stable
+//! releases.
+//!
+//! Versions come from the release manifests that rustup installs toolchains from, so they're
+//! available the moment a release goes out. The manifests are rather large, so the versions are
+//! cached for a while.
+
+use std::{
+    sync::{LazyLock, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use tracing::{error, info, instrument};
+
+use crate::{
+    api::{
+        response::{self, RustVersion, RustVersions},
+        Post, Source,
+    },
+    outbox::Outbox,
+    settings::RustReleases as RustReleasesSettings,
+    state::State,
+};
+
+/// Time that the versions are cached, before they're fetched again.
+const CACHE_TTL: Duration = Duration::from_mins(30);
+
+/// Most recently fetched versions.
+static CACHE: LazyLock<Mutex<Option<(Instant, RustVersions)>>> = LazyLock::new(Mutex::default);
+
+#[instrument(skip_all)]
+pub async fn versions() -> response::User {
+    info!("received `rustversion` command");
+    response::User::RustVersions(cached_versions().await)
+}
+
+async fn cached_versions() -> Result<RustVersions> {
+    let cached = CACHE
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+        .filter(|(fetched, _)| fetched.elapsed() < CACHE_TTL)
+        .map(|(_, versions)| versions.clone());
+
+    if let Some(versions) = cached {
+        return Ok(versions);
+    }
+
+    let client = client()?;
+    let (stable, beta, nightly) = tokio::try_join!(
+        fetch(&client, "stable"),
+        fetch(&client, "beta"),
+        fetch(&client, "nightly"),
+    )?;
+    let versions = RustVersions {
+        stable,
+        beta,
+        nightly,
+    };
+
+    *CACHE.lock().unwrap_or_else(PoisonError::into_inner) =
+        Some((Instant::now(), versions.clone()));
+
+    Ok(versions)
+}
+
+/// Check for a new stable release, and announce it in the configured places.
+///
+/// The first check only remembers the current release, so a fresh setup doesn't announce a
+/// release that happened long ago.
+pub async fn announce_rust_release(
+    outbox: &Outbox,
+    state: &State,
+    settings: &RustReleasesSettings,
+) {
+    let release = match check(state).await {
+        Ok(Some(release)) => release,
+        Ok(None) => return,
+        Err(e) => {
+            error!(error = ?e, "failed checking for a new Rust release");
+            return;
+        }
+    };
+
+    info!(version = release.version, "announcing new Rust release");
+
+    let targets = [
+        (Source::Discord, settings.discord_channel.map(Some)),
+        (Source::Twitch, settings.twitch.then_some(None)),
+    ];
+
+    for (source, channel) in targets {
+        let Some(channel) = channel else { continue };
+        let post = Post {
+            channel,
+            content: announcement(&release),
+        };
+
+        if let Err(e) = outbox.publish(source, post) {
+            error!(error = ?e, %source, "failed announcing new Rust release");
+        }
+    }
+}
+
+/// Get the current stable release, if it wasn't seen before.
+async fn check(state: &State) -> Result<Option<RustVersion>> {
+    let release = fetch(&client()?, "stable").await?;
+    let known = state.has_rust_releases()?;
+
+    Ok((state.add_rust_release(&release.version)? && known).then_some(release))
+}
+
+fn announcement(release: &RustVersion) -> String {
+    format!(
+        "🦀 Rust {0} was just released: https://github.com/rust-lang/rust/releases/tag/{0}",
+        release.version
+    )
+}
+
+fn client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent("ToggleBot (https://github.com/dnaka91/togglebot)")
+        .timeout(Duration::from_secs(30))
+        .build()
+        .map_err(Into::into)
+}
+
+/// Fetch the latest release of a channel, from its manifest.
+async fn fetch(client: &reqwest::Client, channel: &str) -> Result<RustVersion> {
+    let manifest = client
+        .get(format!(
+            "https://static.rust-lang.org/dist/channel-rust-{channel}.toml"
+        ))
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+
+    parse(&manifest).with_context(|| format!("invalid manifest for the {channel} channel"))
+}
+
+/// Extract the version from a channel's manifest. Only the small part that's needed is read.
+fn parse(manifest: &str) -> Result<RustVersion> {
+    #[derive(Deserialize)]
+    struct Manifest {
+        date: String,
+        pkg: Packages,
+    }
+
+    #[derive(Deserialize)]
+    struct Packages {
+        rust: Package,
+    }
+
+    #[derive(Deserialize)]
+    struct Package {
+        version: String,
+    }
+
+    let manifest = toml::from_str::<Manifest>(manifest)?;
+
+    // The version is followed by the commit and date, like `1.82.0 (f6e511eec 2024-10-15)`.
+    let version = manifest
+        .pkg
+        .rust
+        .version
+        .split_whitespace()
+        .next()
+        .context("empty version")?
+        .to_owned();
+
+    Ok(RustVersion {
+        version,
+        date: manifest.date,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_manifest() {
+        let manifest = indoc::indoc! {r#"
+            manifest-version = "2"
+            date = "2024-10-17"
+
+            [pkg.cargo]
+            version = "0.83.0 (8f40fc59f 2024-08-21)"
+
+            [pkg.rust]
+            version = "1.82.0 (f6e511eec 2024-10-15)"
+
+            [pkg.rust.target.x86_64-unknown-linux-gnu]
+            available = true
+        "#};
+
+        assert_eq!(
+            RustVersion {
+                version: "1.82.0".to_owned(),
+                date: "2024-10-17".to_owned(),
+            },
+            parse(manifest).unwrap()
+        );
+        assert!(parse("date = \"2024-10-17\"").is_err());
+    }
+
+    #[test]
+    fn announcement_text() {
+        assert_eq!(
+            "🦀 Rust 1.82.0 was just released: https://github.com/rust-lang/rust/releases/tag/1.82.0",
+            announcement(&RustVersion {
+                version: "1.82.0".to_owned(),
+                date: "2024-10-17".to_owned(),
+            })
+        );
+    }
+}
@@ -0,0 +1,49 @@
+//! Temporary silence of the bot in a Discord channel.
+//!
+//! While a channel is silenced, user commands are still handled and counted in the statistics, but
+//! their replies are dropped. The silence ends by itself once its duration passed. Admin commands
+//! are still answered, so admins can lift the silence early.
+
+use std::{num::NonZero, time::Duration};
+
+use anyhow::Context;
+use time::OffsetDateTime;
+use tracing::{error, info, instrument};
+
+use crate::{
+    api::{response, GuildChannel},
+    state::State,
+};
+
+#[instrument(skip(state))]
+pub fn silence(
+    state: &State,
+    location: Option<GuildChannel>,
+    channel: Option<NonZero<u64>>,
+    duration: Option<Duration>,
+    now: OffsetDateTime,
+) -> response::Admin {
+    info!("received `silence` command");
+
+    let channel = channel
+        .or_else(|| location.map(|location| location.channel))
+        .context("only channels of a Discord server can be silenced");
+
+    response::Admin::Silence(channel.and_then(|channel| {
+        state
+            .set_silence(channel, duration.map(|d| now + d))
+            .map(|()| duration)
+    }))
+}
+
+/// Whether replies in the channel are currently suppressed. Replies are sent, if the silence
+/// can't be loaded.
+pub fn silenced(state: &State, channel: GuildChannel, now: OffsetDateTime) -> bool {
+    match state.silenced_until(channel.channel) {
+        Ok(until) => until.is_some_and(|until| until > now),
+        Err(e) => {
+            error!(error = ?e, "failed loading channel silence");
+            false
+        }
+    }
+}
@@ -0,0 +1,159 @@
+//! Detection of users that send the same command over and over again.
+//!
+//! Every author has a sliding window of their recent commands. Commands are identical if they
+//! parse into the same request, so `!crate serde` and `!crates serde` count as the same command.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use tracing::{debug, info};
+
+use crate::{
+    api::{request, Author, Source, UserRef},
+    settings::Spam as SpamSettings,
+};
+
+/// Recent commands of each user, with the time they were sent.
+pub(super) type History = Mutex<HashMap<UserRef, VecDeque<(Instant, String)>>>;
+
+/// What to do with a command, after checking it for spam.
+#[derive(Debug, Eq, PartialEq)]
+pub enum Verdict {
+    /// The command is fine and can be handled.
+    Allow,
+    /// The command was repeated too often and is ignored.
+    Drop,
+    /// The command was repeated so often, that the author is timed out for the given seconds.
+    Timeout(u32),
+}
+
+/// Check whether the author repeated the command too often. Moderators are never considered
+/// spammers.
+pub fn check(
+    recent: &History,
+    settings: &SpamSettings,
+    author: &Author,
+    req: &request::User,
+) -> Verdict {
+    if author.is_moderator() {
+        return Verdict::Allow;
+    }
+
+    let user = author.id.user_ref();
+    let verdict = check_at(recent, settings, &user, format!("{req:?}"), Instant::now());
+
+    match verdict {
+        Verdict::Allow => {}
+        Verdict::Drop => debug!(?user, "dropping repeated command"),
+        Verdict::Timeout(_) => info!(?user, "escalating repeated command to a timeout"),
+    }
+
+    verdict
+}
+
+fn check_at(
+    recent: &History,
+    settings: &SpamSettings,
+    user: &UserRef,
+    fingerprint: String,
+    now: Instant,
+) -> Verdict {
+    let window = Duration::from_secs(settings.window);
+    let mut recent = recent.lock().unwrap_or_else(PoisonError::into_inner);
+
+    recent.retain(|_, commands| {
+        while commands
+            .front()
+            .is_some_and(|(sent, _)| now.duration_since(*sent) >= window)
+        {
+            commands.pop_front();
+        }
+        !commands.is_empty()
+    });
+
+    let commands = recent.entry(user.clone()).or_default();
+    let repeats = commands.iter().filter(|(_, f)| *f == fingerprint).count() + 1;
+    commands.push_back((now, fingerprint));
+
+    if repeats <= settings.threshold {
+        return Verdict::Allow;
+    }
+
+    match settings.timeout {
+        Some(timeout) if user.source == Source::Twitch && repeats == settings.threshold * 2 => {
+            Verdict::Timeout(timeout)
+        }
+        _ => Verdict::Drop,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use super::*;
+
+    fn user(source: Source) -> UserRef {
+        UserRef {
+            source,
+            id: "1".to_owned(),
+        }
+    }
+
+    fn settings(timeout: Option<u32>) -> SpamSettings {
+        SpamSettings {
+            window: 30,
+            threshold: 2,
+            timeout,
+        }
+    }
+
+    #[test]
+    fn drop_repeats() {
+        let recent = Mutex::default();
+        let settings = settings(None);
+        let now = Instant::now();
+        let check = |fingerprint: &str, secs| {
+            check_at(
+                &recent,
+                &settings,
+                &user(Source::Discord),
+                fingerprint.to_owned(),
+                now + Duration::from_secs(secs),
+            )
+        };
+
+        assert_eq!(Verdict::Allow, check("links", 0));
+        assert_eq!(Verdict::Allow, check("links", 1));
+        assert_eq!(Verdict::Allow, check("today", 2));
+        assert_eq!(Verdict::Drop, check("links", 3));
+        assert_eq!(Verdict::Drop, check("links", 4));
+        assert_eq!(Verdict::Allow, check("links", 40));
+    }
+
+    #[test]
+    fn escalate_on_twitch() {
+        let recent = Mutex::default();
+        let settings = settings(Some(60));
+        let now = Instant::now();
+        let check = |source| check_at(&recent, &settings, &user(source), "links".to_owned(), now);
+
+        let twitch = (0..5).map(|_| check(Source::Twitch)).collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                Verdict::Allow,
+                Verdict::Allow,
+                Verdict::Drop,
+                Verdict::Timeout(60),
+                Verdict::Drop
+            ],
+            twitch
+        );
+
+        let discord = (0..4).map(|_| check(Source::Discord)).collect::<Vec<_>>();
+        assert_eq!(Verdict::Drop, discord[3]);
+    }
+}
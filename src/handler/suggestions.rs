@@ -0,0 +1,137 @@
+//! Suggestions that users send in, and the admins accept or reject.
+
+use anyhow::{Context, Result};
+use time::OffsetDateTime;
+use tracing::{info, instrument};
+
+use crate::{
+    api::{
+        request,
+        response::{self, Suggest},
+        Author,
+    },
+    state::State,
+};
+
+/// Longest suggestion that users can send in.
+const MAX_SUGGESTION_LEN: usize = 500;
+/// Suggestions of a single user that can wait for a review at the same time, so nobody floods the
+/// admins with them.
+const MAX_OPEN: u64 = 3;
+
+#[instrument(skip_all)]
+pub fn suggest(
+    state: &State,
+    author: &Author,
+    content: &str,
+    now: OffsetDateTime,
+) -> response::User {
+    info!("received `suggest` command");
+    response::User::Suggest(add(state, author, content, now))
+}
+
+#[instrument(skip_all)]
+pub fn suggestions(
+    state: &State,
+    req: request::Suggestions,
+    now: OffsetDateTime,
+) -> response::Admin {
+    info!("received `suggestions` command");
+
+    response::Admin::Suggestions(match req {
+        request::Suggestions::List => response::Suggestions::List(state.list_open_suggestions()),
+        request::Suggestions::Review { id, status, reason } => response::Suggestions::Review(
+            state
+                .review_suggestion(id, status, reason.as_deref(), now)
+                .and_then(|suggestion| {
+                    suggestion.with_context(|| format!("no open suggestion with ID #{id} exists"))
+                }),
+        ),
+    })
+}
+
+fn add(state: &State, author: &Author, content: &str, now: OffsetDateTime) -> Result<Suggest> {
+    if content.chars().count() > MAX_SUGGESTION_LEN {
+        return Ok(Suggest::Refused(format!(
+            "Suggestions can be at most {MAX_SUGGESTION_LEN} characters long"
+        )));
+    }
+
+    let user = author.id.user_ref();
+    if state.count_open_suggestions(&user)? >= MAX_OPEN {
+        return Ok(Suggest::Refused(format!(
+            "You already have {MAX_OPEN} suggestions waiting for a review"
+        )));
+    }
+
+    state
+        .add_suggestion(&user, &author.name, content, now)
+        .map(Suggest::Added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{AuthorId, SuggestionStatus};
+
+    fn review(state: &State, id: i64, status: SuggestionStatus) -> Result<response::Suggestion> {
+        let req = request::Suggestions::Review {
+            id,
+            status,
+            reason: None,
+        };
+        match suggestions(state, req, OffsetDateTime::UNIX_EPOCH) {
+            response::Admin::Suggestions(response::Suggestions::Review(res)) => res,
+            resp => panic!("unexpected response: {resp:?}"),
+        }
+    }
+
+    #[test]
+    fn suggest_and_review() {
+        let state = State::in_memory().unwrap();
+        let author = Author {
+            name: "viewer".to_owned(),
+            ..AuthorId::Twitch("1".to_owned()).into()
+        };
+        let now = OffsetDateTime::from_unix_timestamp(1_730_054_700).unwrap();
+
+        assert!(matches!(
+            add(&state, &author, &"x".repeat(501), now).unwrap(),
+            Suggest::Refused(_)
+        ));
+
+        let ids = (0..MAX_OPEN)
+            .map(
+                |i| match add(&state, &author, &format!("idea {i}"), now).unwrap() {
+                    Suggest::Added(id) => id,
+                    Suggest::Refused(reason) => panic!("refused: {reason}"),
+                },
+            )
+            .collect::<Vec<_>>();
+        assert!(matches!(
+            add(&state, &author, "one more", now).unwrap(),
+            Suggest::Refused(_)
+        ));
+
+        let accepted = review(&state, ids[0], SuggestionStatus::Accepted).unwrap();
+        assert_eq!("viewer", accepted.name);
+        assert_eq!("idea 0", accepted.content);
+        assert_eq!(SuggestionStatus::Accepted, accepted.status);
+        // Reviewed suggestions can't be reviewed again, and free up a slot for a new one.
+        assert!(review(&state, ids[0], SuggestionStatus::Rejected).is_err());
+        assert!(matches!(
+            add(&state, &author, "one more", now).unwrap(),
+            Suggest::Added(_)
+        ));
+
+        assert_eq!(
+            vec!["idea 1", "idea 2", "one more"],
+            state
+                .list_open_suggestions()
+                .unwrap()
+                .into_iter()
+                .map(|suggestion| suggestion.content)
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,265 @@
+//! History of the stream titles and categories, to look up what was streamed when.
+
+use anyhow::Result;
+use time::{Date, Duration, OffsetDateTime, Time, UtcOffset};
+use tracing::{info, instrument};
+
+use crate::{
+    api::{
+        response::{self, Chapter, LastCategory, StreamChapters, StreamTitle},
+        StreamInfo,
+    },
+    state::State,
+};
+
+/// Shortest chapter that YouTube accepts. Shorter ones are replaced by the chapter that follows.
+const MIN_CHAPTER: std::time::Duration = std::time::Duration::from_secs(10);
+
+#[instrument(skip_all)]
+pub fn yesterday(state: &State, now: OffsetDateTime) -> response::User {
+    info!("received `yesterday` command");
+
+    let res = || -> Result<Vec<StreamTitle>> {
+        let today = now.replace_time(Time::MIDNIGHT);
+        let titles = state.list_titles(today - Duration::DAY, today)?;
+
+        Ok(titles
+            .into_iter()
+            .map(|title| local(title, now.offset()))
+            .collect())
+    };
+
+    response::User::Yesterday(res())
+}
+
+#[instrument(skip_all)]
+pub fn last_category(state: &State, category: &str, offset: UtcOffset) -> response::User {
+    info!("received `lastcategory` command");
+
+    let res = || -> Result<LastCategory> {
+        if let Some(current) = state
+            .current_title()?
+            .filter(|current| current.category.eq_ignore_ascii_case(category))
+        {
+            return Ok(LastCategory::Live(local(current, offset)));
+        }
+
+        Ok(match state.last_category(category)? {
+            Some(title) => LastCategory::Past(local(title, offset)),
+            None => LastCategory::Never(category.to_owned()),
+        })
+    };
+
+    response::User::LastCategory(res())
+}
+
+#[instrument(skip_all)]
+pub fn chapters(state: &State, day: Option<Date>) -> response::Admin {
+    info!("received `chapters` command");
+
+    let res = || -> Result<Vec<StreamChapters>> {
+        let range = day.map(|day| {
+            let start = day.midnight().assume_utc();
+            (start, start + Duration::DAY)
+        });
+
+        Ok(state
+            .stream_titles(range)?
+            .into_iter()
+            .map(|(started_at, titles)| StreamChapters {
+                started_at,
+                chapters: stream_chapters(started_at, titles),
+            })
+            .collect())
+    };
+
+    response::Admin::Chapters(res())
+}
+
+/// Turn the title changes of a stream into chapters, where the first one always starts at the
+/// beginning of the stream, as YouTube requires it.
+fn stream_chapters(started_at: OffsetDateTime, titles: Vec<StreamTitle>) -> Vec<Chapter> {
+    let mut chapters = Vec::<Chapter>::with_capacity(titles.len());
+
+    for title in titles {
+        // Changes from before the stream went online belong to the start.
+        let offset: std::time::Duration = (title.changed_at - started_at)
+            .try_into()
+            .unwrap_or_default();
+
+        if chapters
+            .last()
+            .is_some_and(|last| offset.saturating_sub(last.offset) < MIN_CHAPTER)
+        {
+            chapters.pop();
+        }
+
+        chapters.push(Chapter {
+            offset,
+            title: title.title,
+            category: title.category,
+        });
+    }
+
+    if let Some(first) = chapters.first_mut() {
+        first.offset = std::time::Duration::ZERO;
+    }
+
+    chapters
+}
+
+/// Record the title and category that the stream started with. If the stream was already known,
+/// like after a reconnect, any change since then happened just now.
+pub(super) fn stream_online(state: &State, info: &StreamInfo, now: OffsetDateTime) -> Result<()> {
+    if info.title.is_empty() && info.category.is_empty() {
+        return Ok(());
+    }
+
+    let changed_at = if state.current_title()?.is_some() {
+        now
+    } else {
+        info.started_at
+    };
+
+    state.record_title(&info.title, &info.category, changed_at)
+}
+
+fn local(title: StreamTitle, offset: UtcOffset) -> StreamTitle {
+    StreamTitle {
+        changed_at: title.changed_at.to_offset(offset),
+        ..title
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime, offset};
+
+    use super::*;
+
+    fn go_live(state: &State, id: &str, started_at: OffsetDateTime, category: &str) {
+        let info = StreamInfo {
+            id: id.to_owned(),
+            started_at,
+            title: format!("Playing {category}"),
+            category: category.to_owned(),
+        };
+        state.start_stream(&info).unwrap();
+        stream_online(state, &info, started_at).unwrap();
+    }
+
+    fn categories(resp: response::User) -> Vec<String> {
+        match resp {
+            response::User::Yesterday(Ok(titles)) => {
+                titles.into_iter().map(|title| title.category).collect()
+            }
+            resp => panic!("unexpected response: {resp:?}"),
+        }
+    }
+
+    #[test]
+    fn streamed_yesterday() {
+        let state = State::in_memory().unwrap();
+
+        go_live(&state, "1", datetime!(2025-03-01 18:00 UTC), "Rust");
+        state
+            .record_title("Still Rust", "Rust", datetime!(2025-03-01 19:00 UTC))
+            .unwrap();
+        state
+            .record_title("Games", "Minecraft", datetime!(2025-03-01 20:00 UTC))
+            .unwrap();
+        state.end_stream(datetime!(2025-03-01 23:00 UTC)).unwrap();
+        // Offline changes aren't part of any stream.
+        state
+            .record_title("Tomorrow", "Art", datetime!(2025-03-02 08:00 UTC))
+            .unwrap();
+        go_live(&state, "2", datetime!(2025-03-02 18:00 UTC), "Art");
+
+        assert_eq!(
+            vec!["Rust", "Rust", "Minecraft"],
+            categories(yesterday(&state, datetime!(2025-03-02 12:00 UTC)))
+        );
+        assert_eq!(
+            vec!["Art"],
+            categories(yesterday(&state, datetime!(2025-03-03 02:00 UTC)))
+        );
+        // At the same time, six hours behind UTC, it's still the day of the second stream.
+        assert_eq!(
+            vec!["Rust", "Rust", "Minecraft"],
+            categories(yesterday(&state, datetime!(2025-03-02 20:00 -6)))
+        );
+        state.end_stream(datetime!(2025-03-02 23:00 UTC)).unwrap();
+        assert!(categories(yesterday(&state, datetime!(2025-03-10 12:00 UTC))).is_empty());
+    }
+
+    #[test]
+    fn chapters_of_streams() {
+        let state = State::in_memory().unwrap();
+
+        go_live(&state, "1", datetime!(2025-03-01 18:00 UTC), "Rust");
+        // Changed right after going live, so the first title never really showed.
+        state
+            .record_title("Rust again", "Rust", datetime!(2025-03-01 18:00:05 UTC))
+            .unwrap();
+        state
+            .record_title("Games", "Minecraft", datetime!(2025-03-01 19:30 UTC))
+            .unwrap();
+        state.end_stream(datetime!(2025-03-01 21:00 UTC)).unwrap();
+        go_live(&state, "2", datetime!(2025-03-02 18:00 UTC), "Art");
+
+        let offsets = |resp| match resp {
+            response::Admin::Chapters(Ok(streams)) => streams
+                .into_iter()
+                .map(|stream: StreamChapters| {
+                    stream
+                        .chapters
+                        .into_iter()
+                        .map(|chapter| (chapter.offset.as_secs(), chapter.title))
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>(),
+            resp => panic!("unexpected response: {resp:?}"),
+        };
+
+        assert_eq!(
+            vec![vec![(0, "Playing Art".to_owned())]],
+            offsets(chapters(&state, None))
+        );
+        assert_eq!(
+            vec![vec![
+                (0, "Rust again".to_owned()),
+                (5400, "Games".to_owned())
+            ]],
+            offsets(chapters(&state, Some(date!(2025 - 03 - 01))))
+        );
+        assert!(offsets(chapters(&state, Some(date!(2025 - 03 - 03)))).is_empty());
+    }
+
+    #[test]
+    fn last_streamed() {
+        let state = State::in_memory().unwrap();
+
+        go_live(&state, "1", datetime!(2025-03-01 18:00 UTC), "Rust");
+        state
+            .record_title("Games", "Minecraft", datetime!(2025-03-01 20:00 UTC))
+            .unwrap();
+        state.end_stream(datetime!(2025-03-01 23:00 UTC)).unwrap();
+        go_live(&state, "2", datetime!(2025-03-02 18:00 UTC), "Art");
+
+        match last_category(&state, "minecraft", offset!(+2)) {
+            response::User::LastCategory(Ok(LastCategory::Past(title))) => {
+                assert_eq!("Minecraft", title.category);
+                assert_eq!(datetime!(2025-03-01 22:00 +2), title.changed_at);
+            }
+            resp => panic!("unexpected response: {resp:?}"),
+        }
+        assert!(matches!(
+            last_category(&state, "ART", UtcOffset::UTC),
+            response::User::LastCategory(Ok(LastCategory::Live(_)))
+        ));
+        assert!(matches!(
+            last_category(&state, "Chess", UtcOffset::UTC),
+            response::User::LastCategory(Ok(LastCategory::Never(_)))
+        ));
+    }
+}
@@ -0,0 +1,61 @@
+//! Agenda of the stream, that admins set and users look up with `!topic`.
+
+use time::OffsetDateTime;
+use tracing::{error, info, instrument};
+
+use crate::{
+    api::{response, Post, Source},
+    outbox::Outbox,
+    settings::Topic as TopicSettings,
+    state::State,
+};
+
+#[instrument(skip_all)]
+pub fn topic(state: &State) -> response::User {
+    info!("received `topic` command");
+    response::User::Topic(state.topic())
+}
+
+#[instrument(skip_all)]
+pub fn set_topic(state: &State, content: Option<&str>, now: OffsetDateTime) -> response::Admin {
+    info!("received `topic` command");
+
+    response::Admin::Topic(
+        state
+            .set_topic(content, now)
+            .map(|live| match (content, live) {
+                (None, _) => response::TopicUpdate::Cleared,
+                (Some(_), true) => response::TopicUpdate::Live,
+                (Some(_), false) => response::TopicUpdate::Next,
+            }),
+    )
+}
+
+/// Post the agenda in the configured places, once the stream went live.
+pub fn post_topic(outbox: &Outbox, state: &State, settings: &TopicSettings) {
+    let targets = [
+        (Source::Discord, settings.discord_channel.map(Some)),
+        (Source::Twitch, settings.twitch.then_some(None)),
+    ];
+
+    let topic = match state.topic() {
+        Ok(Some(topic)) => topic,
+        Ok(None) => return,
+        Err(e) => {
+            error!(error = ?e, "failed loading the stream agenda");
+            return;
+        }
+    };
+
+    for (source, channel) in targets {
+        let Some(channel) = channel else { continue };
+        let post = Post {
+            channel,
+            content: format!("📋 Today's agenda: {}", topic.content),
+        };
+
+        if let Err(e) = outbox.publish(source, post) {
+            error!(error = ?e, %source, "failed posting the stream agenda");
+        }
+    }
+}
@@ -0,0 +1,125 @@
+//! Trigger words, that fire a response whenever they appear anywhere in a chat message.
+//!
+//! Words are matched case-insensitive and only as a whole, so `rust` fires for `I love Rust!` but
+//! not for `rusty`. Each trigger has its own cooldown per service, to not flood the chat.
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use tracing::{debug, error};
+
+use crate::{
+    api::{response::Trigger, Source},
+    state::State,
+};
+
+/// Cooldown in seconds of new triggers, until it's changed by an admin.
+pub const DEFAULT_COOLDOWN: u64 = 60;
+
+/// Last time each trigger fired, per service.
+pub(super) type Fired = Mutex<HashMap<(Source, String), Instant>>;
+
+/// Find the first trigger word in the message and create its response, unless it's on cooldown.
+pub fn fire(fired: &Fired, state: &State, source: Source, text: &str) -> Option<String> {
+    let triggers = state
+        .active_triggers(source)
+        .inspect_err(|e| error!(error = ?e, "failed loading triggers"))
+        .ok()?;
+
+    let trigger = find(&triggers, text)?;
+    if !cooled_down(fired, source, trigger, Instant::now()) {
+        debug!(trigger.word, "trigger is on cooldown");
+        return None;
+    }
+
+    Some(trigger.content.clone())
+}
+
+/// Whether the word is a valid trigger, made up of only letters and digits.
+pub fn is_valid(word: &str) -> bool {
+    !word.is_empty() && word.chars().all(char::is_alphanumeric)
+}
+
+fn find<'a>(triggers: &'a [Trigger], text: &str) -> Option<&'a Trigger> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .find_map(|word| {
+            triggers
+                .iter()
+                .find(|trigger| trigger.word.eq_ignore_ascii_case(word))
+        })
+}
+
+/// Check whether the trigger's cooldown passed on the service, and remember this time if so.
+fn cooled_down(last: &Fired, source: Source, trigger: &Trigger, now: Instant) -> bool {
+    let mut last = last.lock().unwrap_or_else(PoisonError::into_inner);
+    let key = (source, trigger.word.clone());
+
+    if last
+        .get(&key)
+        .is_some_and(|fired| now.duration_since(*fired) < Duration::from_secs(trigger.cooldown))
+    {
+        return false;
+    }
+
+    last.insert(key, now);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trigger(word: &str, cooldown: u64) -> Trigger {
+        Trigger {
+            word: word.to_owned(),
+            content: format!("{word}!"),
+            cooldown,
+            disabled: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn whole_words() {
+        let triggers = [trigger("rust", 0), trigger("o7", 0)];
+        let find = |text| find(&triggers, text).map(|trigger| trigger.word.as_str());
+
+        assert_eq!(Some("rust"), find("I love Rust!"));
+        assert_eq!(Some("o7"), find("o7 everybody"));
+        assert_eq!(None, find("rusty trombone"));
+        assert_eq!(None, find(""));
+    }
+
+    #[test]
+    fn cooldown() {
+        let last = Mutex::default();
+        let trigger = trigger("rust", 30);
+        let now = Instant::now();
+
+        assert!(cooled_down(&last, Source::Twitch, &trigger, now));
+        assert!(cooled_down(&last, Source::Discord, &trigger, now));
+        assert!(!cooled_down(
+            &last,
+            Source::Twitch,
+            &trigger,
+            now + Duration::from_secs(10)
+        ));
+        assert!(cooled_down(
+            &last,
+            Source::Twitch,
+            &trigger,
+            now + Duration::from_secs(30)
+        ));
+    }
+
+    #[test]
+    fn valid_words() {
+        assert!(is_valid("rust"));
+        assert!(is_valid("o7"));
+        assert!(!is_valid("two words"));
+        assert!(!is_valid(""));
+    }
+}
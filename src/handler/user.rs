@@ -1,81 +1,794 @@
-use std::sync::Arc;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
 
-use anyhow::bail;
-use reqwest::StatusCode;
+use anyhow::{bail, Result};
+use reqwest::{StatusCode, Url};
+use semver::VersionReq;
 use serde::Deserialize;
 use time::OffsetDateTime;
-use tracing::{info, instrument};
+use tracing::{info, instrument, warn};
 
-use super::AsyncCommandSettings;
+use super::{Access, AsyncCommandSettings};
 use crate::{
     api::{
-        response::{self, CrateInfo, CrateSearch},
-        Source,
+        self,
+        response::{
+            self, CommandHelp, CrateInfo, CrateSearch, Dependents, SongPlatform, SongRequest,
+            VersionInfo, VersionSearch,
+        },
+        Author, AuthorId, Source,
+    },
+    commands,
+    fun::{Fun, Kind},
+    health::Health,
+    locale::Language,
+    plugins::{self, Plugins},
+    settings::{
+        Commands as CommandSettings, SongRequests as SongRequestSettings, Spam as SpamSettings,
     },
     state::State,
+    timing::Timings,
 };
 
 #[instrument(skip_all)]
-pub fn help() -> response::User {
+pub fn help(
+    settings: &AsyncCommandSettings,
+    language: Language,
+    author: &Author,
+) -> response::User {
     info!("received `help` command");
-    response::User::Help
+    response::User::Help(settings.templates.translate(
+        language,
+        "help",
+        &[("author", &author.name)],
+    ))
+}
+
+#[instrument(skip(state, spam))]
+pub fn command_help(state: &State, spam: &SpamSettings, name: &str) -> response::User {
+    info!("received `help` command for a single command");
+    response::User::CommandHelp(command_details(state, spam, name))
+}
+
+/// Collect the details of a built-in or custom command. Built-in commands are found by any of
+/// their aliases as well.
+fn command_details(state: &State, spam: &SpamSettings, name: &str) -> Result<Option<CommandHelp>> {
+    let name = name.strip_prefix('!').unwrap_or(name).to_lowercase();
+
+    let (name, info, sources) = if let Some(info) = commands::find(&name) {
+        let sources = Source::ALL
+            .into_iter()
+            .filter(|source| info.available_on(*source))
+            .collect();
+        (info.name.to_owned(), Some(info), sources)
+    } else {
+        let sources = state
+            .list_custom_commands()?
+            .into_iter()
+            .filter_map(|(cmd, source)| (cmd == name).then_some(source))
+            .collect::<Vec<_>>();
+        if sources.is_empty() {
+            return Ok(None);
+        }
+        (name, None, sources)
+    };
+
+    Ok(Some(CommandHelp {
+        cost: if info.is_none() {
+            state.command_cost(&name)?
+        } else {
+            None
+        },
+        availability: state.command_availability(&name)?,
+        cooldown: info
+            .is_none_or(|info| info.access == Access::Standard)
+            .then(|| (spam.threshold, Duration::from_secs(spam.window))),
+        name,
+        info,
+        sources,
+    }))
 }
 
 #[instrument(skip_all)]
 pub fn commands(state: &State, source: Source) -> response::User {
     info!("received `commands` command");
-    response::User::Commands(state.list_custom_command_names(source))
+    response::User::Commands {
+        builtin: commands::listed(Access::Standard)
+            .filter(|cmd| cmd.available_on(source))
+            .collect(),
+        custom: state.list_custom_command_names(source),
+    }
 }
 
 #[instrument(skip_all)]
-pub fn links(settings: &AsyncCommandSettings) -> response::User {
+pub fn links(settings: &AsyncCommandSettings, source: Source) -> response::User {
     info!("received `links` command");
-    response::User::Links(Arc::clone(&settings.links))
+    response::User::Links(links_for(settings, source))
+}
+
+/// The links as they're shown on the service, following its profile if there is one. Without an
+/// explicit order, all links are shown sorted by name.
+pub fn links_for(settings: &CommandSettings, source: Source) -> response::Links {
+    let profile = settings.link_profiles.get(&source);
+    let entry = |name: &String, url: &String| response::Link {
+        name: name.clone(),
+        url: url.clone(),
+        icon: profile.and_then(|p| p.icons.get(name).cloned()),
+    };
+
+    let links = match profile.filter(|p| !p.order.is_empty()) {
+        Some(profile) => profile
+            .order
+            .iter()
+            .filter_map(|name| Some(entry(name, settings.links.get(name)?)))
+            .collect(),
+        None => settings
+            .links
+            .iter()
+            .collect::<BTreeMap<_, _>>()
+            .into_iter()
+            .map(|(name, url)| entry(name, url))
+            .collect(),
+    };
+
+    response::Links {
+        links,
+        template: profile
+            .and_then(|p| p.template.clone())
+            .or_else(|| settings.templates.link.clone()),
+        separator: profile.and_then(|p| p.separator.clone()),
+    }
 }
 
 #[instrument(skip_all)]
-pub fn ban(target: &str) -> response::User {
+pub fn ban(
+    settings: &AsyncCommandSettings,
+    language: Language,
+    author: &Author,
+    target: &str,
+) -> response::User {
     info!("received `ban` command");
-    response::User::Ban(target.to_owned())
+    response::User::Ban(settings.templates.translate(
+        language,
+        "ban",
+        &[("target", &target), ("author", &author.name)],
+    ))
+}
+
+#[instrument(skip_all)]
+pub fn timeout_me() -> response::User {
+    info!("received `timeout me` command");
+    response::User::TimeoutMe
+}
+
+#[instrument(skip_all)]
+pub fn follow_age() -> response::User {
+    info!("received `followage` command");
+    response::User::FollowAge
+}
+
+#[instrument(skip_all)]
+pub fn account_age() -> response::User {
+    info!("received `accountage` command");
+    response::User::AccountAge
+}
+
+#[instrument(skip_all)]
+pub async fn fun(fun: &Fun, kind: Kind) -> response::User {
+    info!("received `{kind}` command");
+    response::User::Fun {
+        kind,
+        content: fun.fetch(kind).await,
+    }
 }
 
 #[instrument(skip_all, name = "crate")]
-pub async fn crate_(name: &str) -> response::User {
+pub async fn crate_(crates: &Arc<Crates>, name: &str, source: Source) -> response::User {
+    info!("received `crate` command");
+
+    let mut res = lookup_crate(crates, name).await;
+    // Only Discord has room to show the dependents.
+    if source == Source::Discord {
+        if let Ok(CrateSearch::Found(info)) = &mut res {
+            info.dependents = lookup_dependents(crates, name).await;
+        }
+    }
+
+    response::User::Crate(res)
+}
+
+#[instrument(skip_all, name = "crate compare")]
+pub async fn crate_compare(crates: &Arc<Crates>, first: &str, second: &str) -> response::User {
+    info!("received `crate compare` command");
+
+    let (first, second) = tokio::join!(lookup_crate(crates, first), lookup_crate(crates, second));
+    response::User::CrateCompare(first.and_then(|first| Ok((first, second?))))
+}
+
+#[instrument(skip_all, name = "crate version")]
+pub async fn crate_version(name: &str, version: &str) -> response::User {
+    info!("received `crate version` command");
+    response::User::CrateVersion(lookup_version(name, Some(version)).await)
+}
+
+#[instrument(skip_all, name = "crate features")]
+pub async fn crate_features(name: &str, version: Option<&str>) -> response::User {
+    info!("received `crate features` command");
+    response::User::CrateFeatures(lookup_version(name, version).await)
+}
+
+/// Time that crate details are cached, before they're fetched again.
+const CRATE_CACHE_TTL: Duration = Duration::from_mins(10);
+/// Time that expired crate details are still answered with right away, while they're fetched again
+/// in the background.
+const CRATE_CACHE_STALE: Duration = Duration::from_hours(24);
+
+/// Recently looked up crates and their dependents, to avoid hitting the crates.io API repeatedly
+/// for popular crates.
+#[derive(Default)]
+pub(super) struct Crates {
+    /// Details of each crate. Expired entries are kept, so there is something to answer with while
+    /// crates.io is down.
+    infos: Mutex<HashMap<String, (Instant, CrateInfo)>>,
+    /// Crates that are currently fetched again in the background.
+    revalidations: Mutex<HashSet<String>>,
+    /// Dependents of each crate.
+    dependents: Mutex<HashMap<String, (Instant, Dependents)>>,
+}
+
+/// Look up a crate, preferring cached details. Stale details are answered right away and refreshed
+/// in the background. If crates.io fails, any cached copy is used, and docs.rs is asked as last
+/// resort, which only knows the latest version.
+async fn lookup_crate(crates: &Arc<Crates>, name: &str) -> Result<CrateSearch> {
+    let cached = crates
+        .infos
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(name)
+        .map(|(fetched, info)| (fetched.elapsed(), info.clone()));
+
+    match cached {
+        Some((age, info)) if age < CRATE_CACHE_TTL => return Ok(CrateSearch::Found(info)),
+        Some((age, info)) if age < CRATE_CACHE_STALE => {
+            revalidate_crate(crates, name);
+            return Ok(CrateSearch::Found(info));
+        }
+        _ => {}
+    }
+
+    match fetch_crate(crates, name).await {
+        Ok(search) => Ok(search),
+        Err(e) => {
+            warn!(error = ?e, name, "failed fetching crate from crates.io");
+            match cached {
+                Some((_, info)) => Ok(CrateSearch::Found(info)),
+                None => docs_rs_crate(name).await.or(Err(e)),
+            }
+        }
+    }
+}
+
+/// Fetch the crate again in the background, unless that already happens.
+fn revalidate_crate(crates: &Arc<Crates>, name: &str) {
+    if !crates
+        .revalidations
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(name.to_owned())
+    {
+        return;
+    }
+
+    let (crates, name) = (Arc::clone(crates), name.to_owned());
+    tokio::spawn(async move {
+        if let Err(e) = fetch_crate(&crates, &name).await {
+            warn!(error = ?e, name, "failed refreshing crate from crates.io");
+        }
+
+        crates
+            .revalidations
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&name);
+    });
+}
+
+/// Time that crates.io and docs.rs get to answer, before the cache or fallback is used instead.
+#[cfg(not(test))]
+const CRATE_TIMEOUT: Duration = Duration::from_secs(5);
+#[cfg(test)]
+const CRATE_TIMEOUT: Duration = Duration::from_millis(100);
+
+fn client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent("ToggleBot (https://github.com/dnaka91/togglebot)")
+        .timeout(CRATE_TIMEOUT)
+        .build()
+        .map_err(Into::into)
+}
+
+/// Fetch the crate details from crates.io and put them in the cache.
+async fn fetch_crate(crates: &Crates, name: &str) -> Result<CrateSearch> {
     #[derive(Deserialize)]
     struct ApiResponse {
         #[serde(rename = "crate")]
         crate_: CrateInfo,
     }
 
-    info!("received `crate` command");
+    #[cfg(test)]
+    let resp = crate_test_response(name).await?;
+    #[cfg(not(test))]
+    let resp = {
+        let link = format!("https://crates.io/api/v1/crates/{name}");
+        client()?.get(&link).send().await?
+    };
 
-    let res = async {
-        #[cfg(test)]
-        let resp = crate_test_response();
-        #[cfg(not(test))]
-        let resp = {
-            let link = format!("https://crates.io/api/v1/crates/{name}");
-            reqwest::Client::builder()
-                .user_agent("ToggleBot (https://github.com/dnaka91/togglebot)")
-                .build()?
-                .get(&link)
-                .send()
-                .await?
-        };
+    Ok(match resp.status() {
+        StatusCode::OK => {
+            let info = resp.json::<ApiResponse>().await?.crate_;
+            crates
+                .infos
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(name.to_owned(), (Instant::now(), info.clone()));
+
+            CrateSearch::Found(info)
+        }
+        StatusCode::NOT_FOUND => {
+            crates
+                .infos
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .remove(name);
+
+            CrateSearch::NotFound(format!("Crate `{name}` doesn't exist"))
+        }
+        s => bail!("unexpected status code {s:?}"),
+    })
+}
+
+/// Ask docs.rs for the latest version of the crate, which is all it knows about it.
+async fn docs_rs_crate(name: &str) -> Result<CrateSearch> {
+    #[derive(Deserialize)]
+    struct ApiResponse {
+        version: String,
+    }
+
+    #[cfg(test)]
+    let resp = docs_rs_test_response();
+    #[cfg(not(test))]
+    let resp = {
+        let link = format!("https://docs.rs/crate/{name}/latest/status.json");
+        client()?.get(&link).send().await?
+    };
+
+    Ok(match resp.status() {
+        StatusCode::OK => CrateSearch::Fallback {
+            name: name.to_owned(),
+            version: resp.json::<ApiResponse>().await?.version,
+        },
+        StatusCode::NOT_FOUND => CrateSearch::NotFound(format!("Crate `{name}` doesn't exist")),
+        s => bail!("unexpected status code {s:?} from docs.rs"),
+    })
+}
+
+/// Time that the dependents of a crate are cached, which change much slower than the crate itself.
+const DEPENDENTS_CACHE_TTL: Duration = Duration::from_hours(12);
+/// Amount of top dependents that are looked up.
+const TOP_DEPENDENTS: usize = 5;
+
+/// Look up the crates that depend on the given one. As these are only extra details, failures are
+/// logged and result in nothing.
+async fn lookup_dependents(crates: &Crates, name: &str) -> Option<Dependents> {
+    let cached = crates
+        .dependents
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(name)
+        .filter(|(fetched, _)| fetched.elapsed() < DEPENDENTS_CACHE_TTL)
+        .map(|(_, dependents)| dependents.clone());
+
+    if cached.is_some() {
+        return cached;
+    }
+
+    match fetch_dependents(name).await {
+        Ok(dependents) => {
+            crates
+                .dependents
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .insert(name.to_owned(), (Instant::now(), dependents.clone()));
+            Some(dependents)
+        }
+        Err(e) => {
+            warn!(error = ?e, name, "failed fetching crate dependents");
+            None
+        }
+    }
+}
+
+async fn fetch_dependents(name: &str) -> Result<Dependents> {
+    #[derive(Deserialize)]
+    struct ApiResponse {
+        dependencies: Vec<Dependency>,
+        versions: Vec<Version>,
+        meta: Meta,
+    }
+
+    #[derive(Deserialize)]
+    struct Dependency {
+        version_id: u64,
+    }
+
+    #[derive(Deserialize)]
+    struct Version {
+        id: u64,
+        #[serde(rename = "crate")]
+        crate_: String,
+    }
 
-        Ok(match resp.status() {
-            StatusCode::OK => CrateSearch::Found(resp.json::<ApiResponse>().await?.crate_),
-            StatusCode::NOT_FOUND => CrateSearch::NotFound(format!("Crate `{name}` doesn't exist")),
-            s => bail!("unexpected status code {s:?}"),
+    #[derive(Deserialize)]
+    struct Meta {
+        total: u64,
+    }
+
+    #[cfg(test)]
+    let resp = dependents_test_response(name);
+    #[cfg(not(test))]
+    let resp = {
+        let link = format!(
+            "https://crates.io/api/v1/crates/{name}/reverse_dependencies?per_page={TOP_DEPENDENTS}"
+        );
+        client()?.get(&link).send().await?.error_for_status()?
+    };
+
+    let resp = resp.json::<ApiResponse>().await?;
+
+    // Dependencies are sorted by downloads, but refer to the dependent's version by its ID only.
+    let top = resp
+        .dependencies
+        .iter()
+        .filter_map(|dependency| {
+            resp.versions
+                .iter()
+                .find(|version| version.id == dependency.version_id)
+                .map(|version| version.crate_.clone())
         })
+        .take(TOP_DEPENDENTS)
+        .collect();
+
+    Ok(Dependents {
+        total: resp.meta.total,
+        top,
+    })
+}
+
+/// Look up a single version of a crate, where a partial version like `1.38` picks the latest
+/// matching release. Without any version, the latest stable release is picked.
+async fn lookup_version(name: &str, version: Option<&str>) -> Result<VersionSearch> {
+    #[derive(Deserialize)]
+    struct ApiResponse {
+        versions: Vec<VersionInfo>,
+    }
+
+    let req = match version.map(|version| VersionReq::parse(&format!("={version}"))) {
+        Some(Ok(req)) => req,
+        Some(Err(_)) => {
+            return Ok(VersionSearch::NotFound(format!(
+                "`{}` isn't a valid version",
+                version.unwrap_or_default()
+            )))
+        }
+        None => VersionReq::STAR,
+    };
+
+    #[cfg(test)]
+    let resp = versions_test_response();
+    #[cfg(not(test))]
+    let resp = {
+        let link = format!("https://crates.io/api/v1/crates/{name}/versions");
+        client()?.get(&link).send().await?
     };
 
-    response::User::Crate(res.await)
+    Ok(match resp.status() {
+        StatusCode::OK => {
+            let versions = resp.json::<ApiResponse>().await?.versions;
+            match (pick_version(versions, &req), version) {
+                (Some(info), _) => VersionSearch::Found(info),
+                (None, Some(version)) => {
+                    VersionSearch::NotFound(format!("Crate `{name}` has no version `{version}`"))
+                }
+                (None, None) => {
+                    VersionSearch::NotFound(format!("Crate `{name}` has no stable version yet"))
+                }
+            }
+        }
+        StatusCode::NOT_FOUND => VersionSearch::NotFound(format!("Crate `{name}` doesn't exist")),
+        s => bail!("unexpected status code {s:?}"),
+    })
+}
+
+/// Latest version that matches the requirement, preferring the ones that weren't yanked.
+fn pick_version(versions: Vec<VersionInfo>, req: &VersionReq) -> Option<VersionInfo> {
+    versions
+        .into_iter()
+        .filter(|info| req.matches(&info.num))
+        .max_by(|a, b| (!a.yanked, &a.num).cmp(&(!b.yanked, &b.num)))
 }
 
 #[cfg(test)]
-fn crate_test_response() -> reqwest::Response {
-    http::Response::new(
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use similar_asserts::assert_eq;
+    use test_case::test_case;
+    use time::macros::{datetime, offset};
+
+    use super::*;
+    use crate::settings::LinkProfile;
+
+    #[test_case("https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=42s" ; "watch")]
+    #[test_case("https://youtu.be/dQw4w9WgXcQ?si=abc" ; "short link")]
+    #[test_case("http://music.youtube.com/watch?v=dQw4w9WgXcQ" ; "music")]
+    #[test_case("https://youtube.com/shorts/dQw4w9WgXcQ" ; "shorts")]
+    fn song_link_youtube(link: &str) {
+        assert_eq!(
+            Some((
+                SongPlatform::YouTube,
+                "https://youtu.be/dQw4w9WgXcQ".to_owned()
+            )),
+            song_link(link)
+        );
+    }
+
+    #[test]
+    fn song_link_spotify() {
+        assert_eq!(
+            Some((
+                SongPlatform::Spotify,
+                "https://open.spotify.com/track/4cOdK2wGLETKBW3PvgPWqT".to_owned()
+            )),
+            song_link("https://open.spotify.com/intl-de/track/4cOdK2wGLETKBW3PvgPWqT?si=1")
+        );
+    }
+
+    #[test_case("dQw4w9WgXcQ" ; "no url")]
+    #[test_case("ftp://youtu.be/dQw4w9WgXcQ" ; "scheme")]
+    #[test_case("https://youtu.be/short" ; "youtube id")]
+    #[test_case("https://www.youtube.com/channel/dQw4w9WgXcQ" ; "youtube channel")]
+    #[test_case("https://open.spotify.com/album/4cOdK2wGLETKBW3PvgPWqT" ; "spotify album")]
+    #[test_case("https://example.com/watch?v=dQw4w9WgXcQ" ; "host")]
+    fn song_link_invalid(link: &str) {
+        assert_eq!(None, song_link(link));
+    }
+
+    #[test]
+    fn links_profile() {
+        fn names(links: &response::Links) -> Vec<(&str, Option<&str>)> {
+            links
+                .links
+                .iter()
+                .map(|link| (link.name.as_str(), link.icon.as_deref()))
+                .collect()
+        }
+
+        let settings = CommandSettings {
+            links: Arc::new(HashMap::from([
+                ("Twitch".to_owned(), "https://twitch.tv/x".to_owned()),
+                ("GitHub".to_owned(), "https://github.com/x".to_owned()),
+                ("YouTube".to_owned(), "https://youtube.com/@x".to_owned()),
+            ])),
+            link_profiles: HashMap::from([(
+                Source::Twitch,
+                LinkProfile {
+                    order: vec!["YouTube".to_owned(), "GitHub".to_owned()],
+                    icons: HashMap::from([("GitHub".to_owned(), "🐙".to_owned())]),
+                    separator: Some(" ~ ".into()),
+                    ..LinkProfile::default()
+                },
+            )]),
+            ..CommandSettings::default()
+        };
+
+        let links = links_for(&settings, Source::Twitch);
+        assert_eq!(
+            vec![("YouTube", None), ("GitHub", Some("🐙"))],
+            names(&links)
+        );
+        assert_eq!(Some(" ~ "), links.separator.as_deref());
+
+        let links = links_for(&settings, Source::Discord);
+        assert_eq!(
+            vec![("GitHub", None), ("Twitch", None), ("YouTube", None)],
+            names(&links)
+        );
+        assert_eq!(None, links.separator);
+    }
+
+    #[test]
+    fn command_details_custom() {
+        let state = State::in_memory().unwrap();
+        state
+            .add_custom_command(
+                Source::Twitch,
+                "hug",
+                "🤗",
+                &AuthorId::Console.user_ref(),
+                OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+        state.set_command_cost("hug", 50).unwrap();
+
+        let help = command_details(&state, &SpamSettings::default(), "!Hug")
+            .unwrap()
+            .unwrap();
+        assert_eq!("hug", help.name);
+        assert!(help.info.is_none());
+        assert_eq!(vec![Source::Twitch], help.sources);
+        assert_eq!(Some(50), help.cost);
+
+        assert!(command_details(&state, &SpamSettings::default(), "missing")
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn command_details_builtin() {
+        let state = State::in_memory().unwrap();
+        let help = command_details(&state, &SpamSettings::default(), "ohelp")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!("ohelp", help.name);
+        assert!(help.info.is_some());
+        assert_eq!(None, help.cooldown);
+    }
+
+    #[tokio::test]
+    async fn crate_dependents_on_discord() {
+        let crates = Arc::default();
+        match crate_(&crates, "anyhow", Source::Discord).await {
+            response::User::Crate(Ok(CrateSearch::Found(info))) => {
+                let dependents = info.dependents.unwrap();
+                assert_eq!(21_573, dependents.total);
+                assert_eq!(vec!["wasm-bindgen-cli", "cargo"], dependents.top);
+            }
+            res => panic!("unexpected response: {res:?}"),
+        }
+
+        match crate_(&crates, "anyhow", Source::Twitch).await {
+            response::User::Crate(Ok(CrateSearch::Found(info))) => {
+                assert!(info.dependents.is_none());
+            }
+            res => panic!("unexpected response: {res:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn crate_fallback_to_docs_rs() {
+        match docs_rs_crate("anyhow").await.unwrap() {
+            CrateSearch::Fallback { name, version } => {
+                assert_eq!("anyhow", name);
+                assert_eq!("1.0.91", version);
+            }
+            res => panic!("unexpected response: {res:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn crate_timeout_falls_back_to_docs_rs() {
+        let crates = Arc::default();
+        match lookup_crate(&crates, "hanging").await.unwrap() {
+            CrateSearch::Fallback { name, version } => {
+                assert_eq!("hanging", name);
+                assert_eq!("1.0.91", version);
+            }
+            res => panic!("unexpected response: {res:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn crate_stale_while_revalidate() {
+        let crates = Arc::default();
+        let mut info = match fetch_crate(&crates, "anyhow").await.unwrap() {
+            CrateSearch::Found(info) => info,
+            res => panic!("unexpected response: {res:?}"),
+        };
+        info.name = "stale".to_owned();
+        let expired = Instant::now()
+            .checked_sub(CRATE_CACHE_TTL + Duration::from_secs(1))
+            .unwrap();
+        crates
+            .infos
+            .lock()
+            .unwrap()
+            .insert("stale".to_owned(), (expired, info));
+
+        match lookup_crate(&crates, "stale").await.unwrap() {
+            CrateSearch::Found(info) => assert_eq!("stale", info.name),
+            res => panic!("unexpected response: {res:?}"),
+        }
+    }
+
+    #[test]
+    fn pick_latest_matching_version() {
+        let versions = || {
+            [
+                "1.37.0",
+                "1.38.0",
+                "1.38.1",
+                "1.38.2",
+                "1.39.0-rc.1",
+                "2.0.0",
+            ]
+            .into_iter()
+            .map(|num| VersionInfo {
+                name: "tokio".to_owned(),
+                num: num.parse().unwrap(),
+                created_at: datetime!(2024-06-01 12:00 UTC),
+                downloads: 0,
+                yanked: num == "1.38.2",
+                license: None,
+                rust_version: None,
+                features: BTreeMap::new(),
+            })
+            .collect::<Vec<_>>()
+        };
+        let pick = |req: &str| {
+            pick_version(versions(), &VersionReq::parse(req).unwrap())
+                .map(|info| info.num.to_string())
+        };
+
+        assert_eq!(Some("1.38.1".to_owned()), pick("=1.38"));
+        assert_eq!(Some("1.38.2".to_owned()), pick("=1.38.2"));
+        assert_eq!(Some("1.38.1".to_owned()), pick("=1"));
+        assert_eq!(Some("2.0.0".to_owned()), pick("*"));
+        assert_eq!(Some("1.39.0-rc.1".to_owned()), pick("=1.39.0-rc.1"));
+        assert_eq!(None, pick("=1.40"));
+    }
+
+    #[test]
+    fn today_fixed_date() {
+        let response::User::Today(text) = today(Language::English, datetime!(2025-03-01 10:00 UTC))
+        else {
+            panic!("unexpected response");
+        };
+
+        assert_eq!(
+            "Today is Saturday, March the 1st of 2025 in the UTC time zone. Did you know, this is the \
+             60th day of the year and we're in the 9th week of the year. Amazing, isn't it?!",
+            text
+        );
+    }
+
+    #[test]
+    fn today_with_offset() {
+        let date = datetime!(2025-03-01 22:00 UTC).to_offset(offset!(+5:30));
+        let response::User::Today(text) = today(Language::German, date) else {
+            panic!("unexpected response");
+        };
+
+        assert!(
+            text.starts_with("Heute ist Sonntag, der 2. März 2025 in der Zeitzone UTC+05:30."),
+            "{text}"
+        );
+    }
+}
+
+#[cfg(test)]
+async fn crate_test_response(name: &str) -> Result<reqwest::Response> {
+    // A server that accepts the connection but never answers, to run into the timeout.
+    if name == "hanging" {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let link = format!("http://{}", listener.local_addr()?);
+        return client()?.get(&link).send().await.map_err(Into::into);
+    }
+
+    Ok(http::Response::new(
         serde_json::json! {{
             "crate": {
                 "name": "anyhow",
@@ -85,58 +798,231 @@ fn crate_test_response() -> reqwest::Response {
                 "description": "Flexible concrete Error type built on std::error::Error",
                 "documentation": "https://docs.rs/anyhow",
                 "repository": "https://github.com/dtolnay/anyhow",
+                "versions": [1, 2, 3],
             }
         }}
         .to_string(),
     )
-    .into()
+    .into())
 }
 
 #[instrument(skip_all)]
-pub fn today() -> response::User {
-    fn th(value: impl Into<u16>) -> &'static str {
-        match value.into() % 10 {
-            1 => "st",
-            2 => "nd",
-            3 => "rd",
-            _ => "th",
-        }
-    }
-
+pub fn today(language: Language, date: OffsetDateTime) -> response::User {
     info!("received `today` command");
 
-    let date = OffsetDateTime::now_utc();
-    let weekday = date.weekday();
-    let month = date.month();
-    let day = date.day();
-    let day_th = th(day);
-    let year = date.year();
-    let day_of_year = date.ordinal();
-    let day_of_year_th = th(day_of_year);
-    let week_of_year = date.iso_week();
-    let week_of_year_th = th(week_of_year);
-
-    response::User::Today(format!(
-        "Today is {weekday}, {month} the {day}{day_th} of {year} in the UTC time zone. Did you \
-         know, this is the {day_of_year}{day_of_year_th} day of the year and we're in the \
-         {week_of_year}{week_of_year_th} week of the year. Amazing, isn't it?!"
+    response::User::Today(language.translate(
+        "today",
+        &[
+            ("weekday", &language.weekday(date.weekday())),
+            ("month", &language.month(date.month())),
+            ("day", &language.ordinal(date.day())),
+            ("year", &date.year()),
+            ("day_of_year", &language.ordinal(date.ordinal())),
+            ("week", &language.ordinal(date.iso_week())),
+            ("zone", &api::format_offset(date.offset())),
+        ],
     ))
 }
 
-pub fn ftoc(fahrenheit: f64) -> response::User {
-    response::User::FahrenheitToCelsius({
-        let celsius = (fahrenheit - 32.0) / 1.8;
-        format!("{fahrenheit:.1}°F => {celsius:.1}°C")
+#[instrument(skip_all)]
+pub fn status(state: &State, health: &Health) -> response::User {
+    info!("received `status` command");
+    response::User::Status(response::Status {
+        stream: state.current_stream(),
+        connectors: health.report(),
     })
 }
 
-pub fn ctof(celsius: f64) -> response::User {
-    response::User::CelsiusToFahrenheit({
-        let fahrenheit = celsius * 1.8 + 32.0;
-        format!("{celsius:.1}°C => {fahrenheit:.1}°F")
+#[instrument(skip_all)]
+pub fn botstat(state: &State, health: &Health, timings: &Timings) -> response::User {
+    info!("received `botstat` command");
+    response::User::BotStat(response::BotStat {
+        runtime: health.runtime().report(),
+        connectors: health
+            .report()
+            .into_iter()
+            .filter(|connector| !connector.stale)
+            .map(|connector| connector.source)
+            .collect(),
+        db_size: state.size(),
+        latency: timings.latency(),
     })
 }
 
+#[instrument(skip_all)]
+pub fn song_request(
+    state: &State,
+    settings: &SongRequestSettings,
+    author: &AuthorId,
+    link: &str,
+    now: OffsetDateTime,
+) -> response::User {
+    info!("received `sr` command");
+    response::User::SongRequest(add_song(state, settings, author, link, now))
+}
+
+fn add_song(
+    state: &State,
+    settings: &SongRequestSettings,
+    author: &AuthorId,
+    link: &str,
+    now: OffsetDateTime,
+) -> Result<SongRequest> {
+    let Some((platform, url)) = song_link(link) else {
+        return Ok(SongRequest::Refused(
+            "Only links to YouTube videos and Spotify tracks can be requested".to_owned(),
+        ));
+    };
+
+    let user = author.user_ref();
+
+    if state.has_song_request(&url)? {
+        return Ok(SongRequest::Refused(
+            "That song is already in the queue".to_owned(),
+        ));
+    }
+
+    if state.count_song_requests(Some(&user))? >= settings.per_user {
+        return Ok(SongRequest::Refused(format!(
+            "You already have {} songs in the queue, wait until one of them was played",
+            settings.per_user
+        )));
+    }
+
+    let position = state.count_song_requests(None)? + 1;
+    if position > settings.max_queue {
+        return Ok(SongRequest::Refused(
+            "The queue is full, try again later".to_owned(),
+        ));
+    }
+
+    state.add_song_request(platform, &url, &user, now)?;
+
+    Ok(SongRequest::Added { position })
+}
+
+/// Validate a song link and normalize it, so the same song is always stored with the same link.
+fn song_link(link: &str) -> Option<(SongPlatform, String)> {
+    fn valid_id(id: &str, len: usize, extra: &[char]) -> bool {
+        id.len() == len
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || extra.contains(&c))
+    }
+
+    let url = Url::parse(link).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+
+    let mut segments = url.path_segments()?.filter(|s| !s.is_empty());
+
+    match url.host_str()? {
+        "youtube.com" | "www.youtube.com" | "m.youtube.com" | "music.youtube.com" => {
+            let id = match segments.next()? {
+                "watch" => url
+                    .query_pairs()
+                    .find_map(|(key, value)| (key == "v").then_some(value))?
+                    .into_owned(),
+                "shorts" | "live" => segments.next()?.to_owned(),
+                _ => return None,
+            };
+
+            valid_id(&id, 11, &['-', '_'])
+                .then(|| (SongPlatform::YouTube, format!("https://youtu.be/{id}")))
+        }
+        "youtu.be" => {
+            let id = segments.next()?;
+            valid_id(id, 11, &['-', '_'])
+                .then(|| (SongPlatform::YouTube, format!("https://youtu.be/{id}")))
+        }
+        "open.spotify.com" => {
+            let mut kind = segments.next()?;
+            if kind.starts_with("intl-") {
+                kind = segments.next()?;
+            }
+
+            let id = segments.next()?;
+            (kind == "track" && valid_id(id, 22, &[])).then(|| {
+                (
+                    SongPlatform::Spotify,
+                    format!("https://open.spotify.com/track/{id}"),
+                )
+            })
+        }
+        _ => None,
+    }
+}
+
+#[instrument(skip_all)]
+pub fn points(state: &State, author: &AuthorId) -> response::User {
+    info!("received `points` command");
+    response::User::Points(state.points(&author.user_ref()))
+}
+
+/// Take the points for a custom command from the author, if the command has a cost. The command's
+/// response is only given if the author can afford it.
+pub fn charge(
+    state: &State,
+    author: &AuthorId,
+    name: &str,
+    resp: response::User,
+) -> response::User {
+    let res = || {
+        let Some(cost) = state.command_cost(name)? else {
+            return Ok(resp);
+        };
+
+        let user = author.user_ref();
+        Ok(match state.spend_points(&user, cost)? {
+            Some(_) => resp,
+            None => response::User::NotEnoughPoints {
+                cost,
+                balance: state.points(&user)?,
+            },
+        })
+    };
+
+    res().unwrap_or_else(|e| response::User::Custom(Err(e)))
+}
+
+#[instrument(skip_all)]
+pub fn song_queue(state: &State) -> response::User {
+    info!("received `queue` command");
+    response::User::SongQueue(state.list_song_requests())
+}
+
+/// Amount of highlights that are listed at once.
+const HIGHLIGHTS_LIMIT: usize = 5;
+
+#[instrument(skip_all)]
+pub fn highlights(state: &State) -> response::User {
+    info!("received `highlights` command");
+    response::User::Highlights(state.recent_highlights(HIGHLIGHTS_LIMIT))
+}
+
+pub fn ftoc(language: Language, fahrenheit: f64) -> response::User {
+    let celsius = (fahrenheit - 32.0) / 1.8;
+    response::User::FahrenheitToCelsius(language.translate(
+        "ftoc",
+        &[
+            ("fahrenheit", &format!("{fahrenheit:.1}")),
+            ("celsius", &format!("{celsius:.1}")),
+        ],
+    ))
+}
+
+pub fn ctof(language: Language, celsius: f64) -> response::User {
+    let fahrenheit = celsius * 1.8 + 32.0;
+    response::User::CelsiusToFahrenheit(language.translate(
+        "ctof",
+        &[
+            ("celsius", &format!("{celsius:.1}")),
+            ("fahrenheit", &format!("{fahrenheit:.1}")),
+        ],
+    ))
+}
+
 #[instrument(skip_all)]
 pub fn custom(state: &State, source: Source, name: &str) -> Option<response::User> {
     state
@@ -149,3 +1035,96 @@ pub fn custom(state: &State, source: Source, name: &str) -> Option<response::Use
             response::User::Custom(res)
         })
 }
+
+#[instrument(skip_all)]
+pub async fn plugin(
+    plugins: &Plugins,
+    name: &str,
+    args: Option<&str>,
+    author: &AuthorId,
+    source: Source,
+) -> Option<response::User> {
+    let ctx = plugins::Context {
+        command: name,
+        args,
+        author,
+        source,
+    };
+
+    plugins.execute(ctx).await.map(|res| {
+        if res.is_ok() {
+            info!("user: received plugin `{name}` command");
+        }
+        response::User::Custom(res)
+    })
+}
+
+#[cfg(test)]
+fn docs_rs_test_response() -> reqwest::Response {
+    http::Response::new(
+        serde_json::json! {{
+            "version": "1.0.91",
+            "doc_status": true,
+        }}
+        .to_string(),
+    )
+    .into()
+}
+
+#[cfg(test)]
+fn dependents_test_response(name: &str) -> reqwest::Response {
+    http::Response::new(
+        serde_json::json! {{
+            "dependencies": [
+                { "id": 1, "version_id": 20, "crate_id": name, "req": "^1" },
+                { "id": 2, "version_id": 10, "crate_id": name, "req": "^1.0.40" },
+            ],
+            "versions": [
+                { "id": 10, "crate": "cargo", "num": "0.83.0" },
+                { "id": 20, "crate": "wasm-bindgen-cli", "num": "0.2.95" },
+            ],
+            "meta": { "total": 21_573 },
+        }}
+        .to_string(),
+    )
+    .into()
+}
+
+#[cfg(test)]
+fn versions_test_response() -> reqwest::Response {
+    http::Response::new(
+        serde_json::json! {{
+            "versions": [
+                {
+                    "crate": "anyhow",
+                    "num": "1.0.91",
+                    "created_at": "2024-10-22T17:51:36.413602+00:00",
+                    "downloads": 1_234_567,
+                    "yanked": false,
+                    "license": "MIT OR Apache-2.0",
+                    "rust_version": "1.39",
+                    "features": {
+                        "backtrace": ["dep:backtrace"],
+                        "default": ["std"],
+                        "std": [],
+                    },
+                },
+                {
+                    "crate": "anyhow",
+                    "num": "1.0.90",
+                    "created_at": "2024-10-01T08:12:00.000000+00:00",
+                    "downloads": 2_345_678,
+                    "yanked": false,
+                    "license": "MIT OR Apache-2.0",
+                    "rust_version": "1.39",
+                    "features": {
+                        "default": ["std"],
+                        "std": [],
+                    },
+                },
+            ],
+        }}
+        .to_string(),
+    )
+    .into()
+}
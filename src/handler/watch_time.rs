@@ -0,0 +1,191 @@
+//! Watch time of chatters, that adds up while they are in the chat of the live stream.
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    time::Duration,
+};
+
+use anyhow::Result;
+use time::OffsetDateTime;
+use tracing::{info, instrument};
+
+use crate::{
+    api::{
+        response::{self, WatchTime},
+        AuthorId, UserRef, Viewer,
+    },
+    state::State,
+    statistics::Stats,
+};
+
+#[instrument(skip_all)]
+pub fn watch_time(
+    state: &State,
+    statistics: &Stats,
+    author: &AuthorId,
+    enabled: Option<bool>,
+    now: OffsetDateTime,
+) -> response::User {
+    info!("received `watchtime` command");
+
+    let user = author.user_ref();
+    let res = || -> Result<WatchTime> {
+        match enabled {
+            None if state.watch_time_opt_out(&user)? => Ok(WatchTime::OptedOut),
+            None => statistics
+                .watch_time(&user)
+                .map(|(month, total)| WatchTime::Tracked { month, total }),
+            Some(enabled) => {
+                state.set_watch_time_opt_out(&user, !enabled, now)?;
+                if !enabled {
+                    statistics.erase_watch_time(&user)?;
+                }
+                Ok(WatchTime::Changed(enabled))
+            }
+        }
+    };
+
+    response::User::WatchTime(res())
+}
+
+/// Credit the viewers with the time since the last sample, but only while the stream is live.
+/// Users that opted out are left out.
+pub(super) fn record(
+    state: &State,
+    statistics: &Stats,
+    viewers: Vec<Viewer>,
+    duration: Duration,
+) -> Result<()> {
+    if viewers.is_empty() || state.current_stream()?.is_none() {
+        return Ok(());
+    }
+
+    let mut opted_out = HashSet::new();
+    for source in viewers
+        .iter()
+        .map(|viewer| viewer.user.source)
+        .collect::<BTreeSet<_>>()
+    {
+        opted_out.extend(
+            state
+                .list_watch_time_opt_outs(source)?
+                .into_iter()
+                .map(|id| UserRef { source, id }),
+        );
+    }
+
+    let viewers = viewers
+        .into_iter()
+        .filter(|viewer| !opted_out.contains(&viewer.user))
+        .collect::<Vec<_>>();
+
+    statistics.add_watch_time(&viewers, duration)
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use super::*;
+    use crate::api::{Source, StreamInfo};
+
+    const NOW: OffsetDateTime = datetime!(2025-03-01 18:00 UTC);
+
+    fn viewer(id: &str) -> Viewer {
+        Viewer {
+            user: AuthorId::Twitch(id.to_owned()).user_ref(),
+            name: id.to_uppercase(),
+        }
+    }
+
+    fn go_live(state: &State) {
+        state
+            .start_stream(&StreamInfo {
+                id: "1".to_owned(),
+                started_at: NOW,
+                title: "Rust".to_owned(),
+                category: "Software and Game Development".to_owned(),
+            })
+            .unwrap();
+    }
+
+    fn tracked(state: &State, statistics: &Stats, id: &str) -> Option<(Duration, Duration)> {
+        match watch_time(
+            state,
+            statistics,
+            &AuthorId::Twitch(id.to_owned()),
+            None,
+            NOW,
+        ) {
+            response::User::WatchTime(Ok(WatchTime::Tracked { month, total })) => {
+                Some((month, total))
+            }
+            response::User::WatchTime(Ok(WatchTime::OptedOut)) => None,
+            resp => panic!("unexpected response: {resp:?}"),
+        }
+    }
+
+    #[test]
+    fn only_while_live() {
+        let (state, statistics) = (State::in_memory().unwrap(), Stats::in_memory().unwrap());
+        let minutes = Duration::from_mins(5);
+
+        record(&state, &statistics, vec![viewer("a")], minutes).unwrap();
+        assert_eq!(
+            Some((Duration::ZERO, Duration::ZERO)),
+            tracked(&state, &statistics, "a")
+        );
+
+        go_live(&state);
+        record(&state, &statistics, vec![viewer("a")], minutes).unwrap();
+        record(&state, &statistics, vec![viewer("a")], minutes).unwrap();
+        assert_eq!(
+            Some((minutes * 2, minutes * 2)),
+            tracked(&state, &statistics, "a")
+        );
+        assert_eq!(
+            Some(&600),
+            statistics.get(false).watch_time.unwrap().get("A")
+        );
+    }
+
+    #[test]
+    fn opt_out() {
+        let (state, statistics) = (State::in_memory().unwrap(), Stats::in_memory().unwrap());
+        let author = AuthorId::Twitch("a".to_owned());
+        go_live(&state);
+
+        let viewers = || vec![viewer("a"), viewer("b")];
+        record(&state, &statistics, viewers(), Duration::from_mins(5)).unwrap();
+
+        assert!(matches!(
+            watch_time(&state, &statistics, &author, Some(false), NOW),
+            response::User::WatchTime(Ok(WatchTime::Changed(false)))
+        ));
+        record(&state, &statistics, viewers(), Duration::from_mins(5)).unwrap();
+        assert_eq!(None, tracked(&state, &statistics, "a"));
+        assert_eq!(
+            Some((Duration::from_mins(10), Duration::from_mins(10))),
+            tracked(&state, &statistics, "b")
+        );
+        assert_eq!(
+            vec![("B".to_owned(), 600)],
+            statistics
+                .get(true)
+                .watch_time
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>()
+        );
+
+        watch_time(&state, &statistics, &author, Some(true), NOW);
+        assert_eq!(
+            Some((Duration::ZERO, Duration::ZERO)),
+            tracked(&state, &statistics, "a")
+        );
+        assert!(state
+            .list_watch_time_opt_outs(Source::Twitch)
+            .unwrap()
+            .is_empty());
+    }
+}
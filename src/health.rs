@@ -0,0 +1,156 @@
+//! Health tracking of the service connectors, to detect connections that are still established
+//! but don't receive any data anymore.
+//!
+//! Connectors report a heartbeat whenever they receive something from their service (gateway
+//! events for Discord, keepalive or notification messages for Twitch, sync responses for Matrix,
+//! API responses for YouTube). A connector is considered stale, if its last heartbeat is older than
+//! the configured threshold.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+use crate::{api::Source, runtime::Runtime, settings::Health as HealthSettings};
+
+struct Connector {
+    threshold: Duration,
+    last_beat: Option<Instant>,
+}
+
+/// Shared handle to the health state of all connectors.
+#[derive(Clone)]
+pub struct Health {
+    thresholds: Arc<BTreeMap<Source, Duration>>,
+    connectors: Arc<Mutex<BTreeMap<Source, Connector>>>,
+    runtime: Runtime,
+}
+
+impl Health {
+    /// Create the health state with the staleness thresholds from the settings.
+    #[must_use]
+    pub fn new(settings: &HealthSettings) -> Self {
+        Self {
+            thresholds: Arc::new(
+                [
+                    (Source::Discord, settings.discord_threshold),
+                    (Source::Twitch, settings.twitch_threshold),
+                    (Source::Matrix, settings.matrix_threshold),
+                    (Source::YouTube, settings.youtube_threshold),
+                ]
+                .into_iter()
+                .map(|(source, threshold)| (source, Duration::from_secs(threshold)))
+                .collect(),
+            ),
+            connectors: Arc::default(),
+            runtime: Runtime::new(),
+        }
+    }
+
+    /// Details about the bot itself, like its uptime, which are tracked since the health state
+    /// was created.
+    #[must_use]
+    pub fn runtime(&self) -> &Runtime {
+        &self.runtime
+    }
+
+    /// Start tracking the given connector, which is considered stale until its first heartbeat.
+    /// Connectors without a threshold, like the local console, aren't tracked.
+    pub fn register(&self, source: Source) {
+        let Some(&threshold) = self.thresholds.get(&source) else {
+            return;
+        };
+
+        self.lock().entry(source).or_insert(Connector {
+            threshold,
+            last_beat: None,
+        });
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<Source, Connector>> {
+        self.connectors
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Record a heartbeat for the given connector.
+    pub fn beat(&self, source: Source) {
+        self.beat_at(source, Instant::now());
+    }
+
+    fn beat_at(&self, source: Source, now: Instant) {
+        if let Some(connector) = self.lock().get_mut(&source) {
+            connector.last_beat = Some(now);
+        }
+    }
+
+    /// Create a report about the current state of all connectors.
+    #[must_use]
+    pub fn report(&self) -> Vec<ConnectorHealth> {
+        self.report_at(Instant::now())
+    }
+
+    fn report_at(&self, now: Instant) -> Vec<ConnectorHealth> {
+        self.lock()
+            .iter()
+            .map(|(&source, connector)| {
+                let silent = connector.last_beat.map(|beat| now.duration_since(beat));
+
+                ConnectorHealth {
+                    source,
+                    silent_secs: silent.map(|silent| silent.as_secs()),
+                    stale: silent.is_none_or(|silent| silent > connector.threshold),
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self::new(&HealthSettings::default())
+    }
+}
+
+/// Health details of a single connector.
+#[derive(Debug, Serialize)]
+pub struct ConnectorHealth {
+    /// The service that the connector integrates with.
+    pub source: Source,
+    /// Seconds since the last heartbeat, or nothing if none was received yet.
+    pub silent_secs: Option<u64>,
+    /// Whether the connector didn't report a heartbeat within its threshold.
+    pub stale: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn staleness() {
+        let health = Health::new(&HealthSettings {
+            discord_threshold: 10,
+            twitch_threshold: 10,
+            matrix_threshold: 10,
+            youtube_threshold: 10,
+        });
+        health.register(Source::Discord);
+        health.register(Source::Twitch);
+        let now = Instant::now();
+
+        assert!(health.report_at(now).iter().all(|c| c.stale));
+
+        health.beat_at(Source::Twitch, now);
+        let report = health.report_at(now + Duration::from_secs(5));
+        assert!(report[0].stale);
+        assert!(!report[1].stale);
+        assert_eq!(Some(5), report[1].silent_secs);
+
+        let report = health.report_at(now + Duration::from_secs(11));
+        assert!(report[1].stale);
+    }
+}
@@ -0,0 +1,351 @@
+//! Control interface for other services, that run admin and owner commands through JSON-RPC 2.0.
+//!
+//! Services authenticate with a bearer token and send a call like
+//! `{"jsonrpc": "2.0", "method": "stats", "params": {"args": "total"}, "id": 1}` to `POST /rpc`.
+//! The method is the name of a command and the arguments are written just like in chat, so every
+//! call goes through the same parser and handler as a chat message. The result is the structured
+//! response of the command, instead of the text that would be posted in chat.
+
+use std::sync::Arc;
+
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{info, warn, Span};
+
+use crate::{
+    api::{request::Request, response, AuthorId, Message, Queue, Source},
+    connector,
+    settings::{ControlAccess, ControlClient},
+    textparse,
+};
+
+/// The received body isn't valid JSON.
+const PARSE_ERROR: i32 = -32700;
+/// The body isn't a valid call.
+const INVALID_REQUEST: i32 = -32600;
+/// The method isn't an admin or owner command.
+const METHOD_NOT_FOUND: i32 = -32601;
+/// The arguments don't fit the command.
+const INVALID_PARAMS: i32 = -32602;
+/// The command didn't produce any response.
+const INTERNAL_ERROR: i32 = -32603;
+/// The client isn't allowed to run the command.
+const FORBIDDEN: i32 = -32001;
+
+struct Control {
+    clients: Vec<ControlClient>,
+    queue: Queue,
+}
+
+pub fn router(clients: Vec<ControlClient>, queue: Queue) -> Router {
+    Router::new()
+        .route("/rpc", post(rpc))
+        .with_state(Arc::new(Control { clients, queue }))
+}
+
+#[derive(Deserialize)]
+struct Call {
+    jsonrpc: String,
+    method: String,
+    #[serde(default)]
+    params: Params,
+    /// Identifier that is sent back with the reply. Calls without one are notifications, which
+    /// don't get any reply.
+    #[serde(default)]
+    id: Option<Value>,
+}
+
+#[derive(Default, Deserialize)]
+struct Params {
+    /// Arguments of the command, as they'd be written in chat.
+    #[serde(default)]
+    args: String,
+}
+
+#[derive(Serialize)]
+struct Reply {
+    jsonrpc: &'static str,
+    #[serde(flatten)]
+    outcome: Outcome,
+    id: Value,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Outcome {
+    Result(Value),
+    Error(Error),
+}
+
+#[derive(Debug, Serialize)]
+struct Error {
+    code: i32,
+    message: String,
+}
+
+impl Error {
+    fn new(code: i32, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+async fn rpc(State(control): State<Arc<Control>>, headers: HeaderMap, body: Bytes) -> Response {
+    let Some(client) = authenticate(&control.clients, &headers) else {
+        warn!("refused control call with invalid token");
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+
+    let call = match serde_json::from_slice::<Call>(&body) {
+        Ok(call) => call,
+        Err(e) if e.is_data() => {
+            return reply(Value::Null, Err(Error::new(INVALID_REQUEST, e.to_string())))
+        }
+        Err(e) => return reply(Value::Null, Err(Error::new(PARSE_ERROR, e.to_string()))),
+    };
+
+    let outcome = if call.jsonrpc == "2.0" {
+        info!(client.name, call.method, "running control call");
+        run(&control.queue, client, &call.method, &call.params.args).await
+    } else {
+        Err(Error::new(
+            INVALID_REQUEST,
+            "only JSON-RPC 2.0 is supported",
+        ))
+    };
+
+    match call.id {
+        Some(id) => reply(id, outcome),
+        None => StatusCode::NO_CONTENT.into_response(),
+    }
+}
+
+fn reply(id: Value, outcome: Result<Value, Error>) -> Response {
+    Json(Reply {
+        jsonrpc: "2.0",
+        outcome: match outcome {
+            Ok(value) => Outcome::Result(value),
+            Err(e) => Outcome::Error(e),
+        },
+        id,
+    })
+    .into_response()
+}
+
+/// Find the client that belongs to the bearer token of the request, if any.
+fn authenticate<'a>(
+    clients: &'a [ControlClient],
+    headers: &HeaderMap,
+) -> Option<&'a ControlClient> {
+    let token = super::bearer_token(headers)?;
+
+    clients
+        .iter()
+        .find(|client| super::constant_time_eq(client.token.as_bytes(), token.as_bytes()))
+}
+
+/// Run the command through the message queue, acting as the client on the local console, and
+/// return its response.
+async fn run(
+    queue: &Queue,
+    client: &ControlClient,
+    method: &str,
+    args: &str,
+) -> Result<Value, Error> {
+    let content = request(client, method, args)?;
+    let message = Message {
+        span: Span::current(),
+        source: Source::Console,
+        content,
+        author: AuthorId::Control {
+            client: client.name.clone(),
+            owner: client.access == ControlAccess::Owner,
+        }
+        .into(),
+        mention: None,
+        channel: None,
+    };
+
+    let value = match connector::dispatch(queue, message).await {
+        Some(response::Response::Admin(resp)) => serde_json::to_value(resp),
+        Some(response::Response::Owner(resp)) => serde_json::to_value(resp),
+        Some(response::Response::Confirm(action)) => {
+            serde_json::to_value(action).map(|action| serde_json::json!({ "confirm": action }))
+        }
+        Some(response::Response::User(_) | response::Response::Denied(_)) | None => {
+            return Err(Error::new(INTERNAL_ERROR, "the command didn't respond"));
+        }
+    };
+
+    value.map_err(|e| Error::new(INTERNAL_ERROR, e.to_string()))
+}
+
+/// Translate the call into a request, as long as the client is allowed to run it.
+fn request(client: &ControlClient, method: &str, args: &str) -> Result<Request, Error> {
+    let not_found = || Error::new(METHOD_NOT_FOUND, format!("unknown method `{method}`"));
+
+    if method.is_empty()
+        || !method
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    {
+        return Err(not_found());
+    }
+
+    let text = format!("!{method} {args}");
+    let req = match textparse::parse(text.trim_end(), Source::Console, None) {
+        Ok(Some(req)) => req,
+        Ok(None) => return Err(not_found()),
+        Err(e) => return Err(Error::new(INVALID_PARAMS, format!("{e:#}"))),
+    };
+
+    match (&req, client.access) {
+        (Request::Admin(_), _) | (Request::Owner(_), ControlAccess::Owner) => Ok(req),
+        (Request::Owner(_), ControlAccess::Admin) => Err(Error::new(
+            FORBIDDEN,
+            format!("`{method}` is an owner command, which this client may not run"),
+        )),
+        (Request::User(_), _) => Err(not_found()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{header::AUTHORIZATION, HeaderValue};
+    use similar_asserts::assert_eq;
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::api::request;
+
+    fn client(access: ControlAccess) -> ControlClient {
+        ControlClient {
+            name: "dashboard".to_owned(),
+            token: "secret".to_owned(),
+            access,
+        }
+    }
+
+    fn headers(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {token}")).unwrap(),
+        );
+        headers
+    }
+
+    async fn call(control: &Arc<Control>, token: &str, body: &str) -> (StatusCode, Value) {
+        let resp = rpc(
+            State(Arc::clone(control)),
+            headers(token),
+            Bytes::from(body.to_owned()),
+        )
+        .await;
+
+        let status = resp.status();
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let value = if body.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_slice(&body).unwrap()
+        };
+
+        (status, value)
+    }
+
+    #[test]
+    fn translate() {
+        let admin = client(ControlAccess::Admin);
+        let owner = client(ControlAccess::Owner);
+
+        assert_eq!(
+            Request::Admin(request::Admin::CommandCosts(request::CommandCosts::List)),
+            request(&admin, "cost", "list").unwrap()
+        );
+        assert_eq!(
+            Request::Owner(request::Owner::Cleanup),
+            request(&owner, "cleanup", "").unwrap()
+        );
+
+        let code = |res: Result<Request, Error>| res.unwrap_err().code;
+        assert_eq!(FORBIDDEN, code(request(&admin, "cleanup", "")));
+        assert_eq!(METHOD_NOT_FOUND, code(request(&owner, "help", "")));
+        assert_eq!(METHOD_NOT_FOUND, code(request(&owner, "!cost", "list")));
+        assert_eq!(INVALID_PARAMS, code(request(&owner, "cost", "hug many")));
+    }
+
+    #[tokio::test]
+    async fn calls() {
+        let (queue, mut rx) = mpsc::channel(1);
+        let control = Arc::new(Control {
+            clients: vec![client(ControlAccess::Admin)],
+            queue,
+        });
+
+        tokio::spawn(async move {
+            while let Some((message, _, reply)) = rx.recv().await {
+                assert!(matches!(message.content, Request::Admin(_)));
+                reply
+                    .send(response::Response::Admin(response::Admin::CommandCosts(
+                        response::CommandCosts::List(Ok(vec![("hug".to_owned(), 50)])),
+                    )))
+                    .ok();
+            }
+        });
+
+        let (status, _) = call(&control, "wrong", "{}").await;
+        assert_eq!(StatusCode::UNAUTHORIZED, status);
+
+        let (status, value) = call(
+            &control,
+            "secret",
+            r#"{"jsonrpc": "2.0", "method": "cost", "params": {"args": "list"}, "id": 1}"#,
+        )
+        .await;
+        assert_eq!(StatusCode::OK, status);
+        assert_eq!(
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "result": {"command_costs": {"list": {"ok": [["hug", 50]]}}},
+                "id": 1,
+            }),
+            value
+        );
+
+        let (_, value) = call(
+            &control,
+            "secret",
+            r#"{"jsonrpc": "2.0", "method": "cleanup", "id": "a"}"#,
+        )
+        .await;
+        assert_eq!(FORBIDDEN, value["error"]["code"]);
+        assert_eq!("a", value["id"]);
+
+        let (_, value) = call(&control, "secret", "{").await;
+        assert_eq!(PARSE_ERROR, value["error"]["code"]);
+
+        let (_, value) = call(&control, "secret", r#"{"jsonrpc": "2.0", "id": 2}"#).await;
+        assert_eq!(INVALID_REQUEST, value["error"]["code"]);
+
+        let (status, _) = call(
+            &control,
+            "secret",
+            r#"{"jsonrpc": "2.0", "method": "cost", "params": {"args": "list"}}"#,
+        )
+        .await;
+        assert_eq!(StatusCode::NO_CONTENT, status);
+    }
+}
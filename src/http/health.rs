@@ -0,0 +1,45 @@
+//! Liveness and readiness probes, for container orchestrators or external monitoring.
+
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+
+use crate::health::{ConnectorHealth, Health};
+
+pub fn router(health: Health) -> Router {
+    Router::new()
+        .route("/healthz", get(|| async { StatusCode::OK }))
+        .route("/readyz", get(ready))
+        .with_state(health)
+}
+
+/// Report the state of all connectors, failing if any of them is stale.
+async fn ready(State(health): State<Health>) -> (StatusCode, Json<Vec<ConnectorHealth>>) {
+    let report = health.report();
+    let status = if report.iter().any(|c| c.stale) {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+
+    (status, Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::Source;
+
+    #[tokio::test]
+    async fn readiness() {
+        let health = Health::default();
+        health.register(Source::Discord);
+        health.register(Source::Twitch);
+        let (status, _) = ready(State(health.clone())).await;
+        assert_eq!(StatusCode::SERVICE_UNAVAILABLE, status);
+
+        health.beat(Source::Discord);
+        health.beat(Source::Twitch);
+        let (status, Json(report)) = ready(State(health)).await;
+        assert_eq!(StatusCode::OK, status);
+        assert_eq!(2, report.len());
+    }
+}
@@ -0,0 +1,230 @@
+//! Inbound webhooks, that allow external tools (like CI pipelines or donation services) to post
+//! messages in chat through the bot.
+//!
+//! Tools authenticate with a bearer token and send a JSON body like
+//! `{"target": "twitch", "message": "Hello!"}` to `POST /hooks/message`.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Json, Router,
+};
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use crate::{
+    api::{Post, Source},
+    outbox::Outbox,
+    settings::InboundHook,
+};
+
+/// Time window for the per-hook rate limit.
+const RATE_WINDOW: Duration = Duration::from_mins(1);
+
+struct Inbound {
+    hooks: Vec<InboundHook>,
+    outbox: Outbox,
+    limiter: RateLimiter,
+}
+
+pub fn router(hooks: Vec<InboundHook>, outbox: Outbox) -> Router {
+    Router::new()
+        .route("/message", post(message))
+        .with_state(Arc::new(Inbound {
+            hooks,
+            outbox,
+            limiter: RateLimiter::default(),
+        }))
+}
+
+#[derive(Deserialize)]
+struct Payload {
+    target: Source,
+    message: String,
+}
+
+async fn message(
+    State(state): State<Arc<Inbound>>,
+    headers: HeaderMap,
+    Json(payload): Json<Payload>,
+) -> StatusCode {
+    let Some(hook) = authenticate(&state.hooks, &headers) else {
+        warn!("refused inbound hook with invalid token");
+        return StatusCode::UNAUTHORIZED;
+    };
+
+    if !hook.targets.is_empty() && !hook.targets.contains(&payload.target) {
+        warn!(hook.name, %payload.target, "refused inbound hook for disallowed target");
+        return StatusCode::FORBIDDEN;
+    }
+
+    let channel = match payload.target {
+        Source::Discord => match hook.discord_channel {
+            Some(channel) => Some(channel),
+            None => return StatusCode::FORBIDDEN,
+        },
+        Source::Twitch | Source::Matrix | Source::YouTube | Source::Console => None,
+    };
+
+    if payload.message.trim().is_empty() {
+        return StatusCode::UNPROCESSABLE_ENTITY;
+    }
+
+    if !state
+        .limiter
+        .check(&hook.name, hook.rate_limit, Instant::now())
+    {
+        warn!(hook.name, "inbound hook exceeded rate limit");
+        return StatusCode::TOO_MANY_REQUESTS;
+    }
+
+    info!(hook.name, %payload.target, "posting message from inbound hook");
+
+    let post = Post {
+        channel,
+        content: payload.message,
+    };
+
+    match state.outbox.publish(payload.target, post) {
+        Ok(()) => StatusCode::ACCEPTED,
+        Err(e) => {
+            warn!(error = ?e, hook.name, "failed posting message from inbound hook");
+            StatusCode::SERVICE_UNAVAILABLE
+        }
+    }
+}
+
+/// Find the hook that belongs to the bearer token of the request, if any.
+fn authenticate<'a>(hooks: &'a [InboundHook], headers: &HeaderMap) -> Option<&'a InboundHook> {
+    let token = super::bearer_token(headers)?;
+
+    hooks
+        .iter()
+        .find(|hook| super::constant_time_eq(hook.token.as_bytes(), token.as_bytes()))
+}
+
+/// Simple fixed-window rate limiter, that tracks the amount of requests per hook.
+#[derive(Default)]
+struct RateLimiter(Mutex<HashMap<String, (Instant, u32)>>);
+
+impl RateLimiter {
+    /// Record a request for the given hook and check whether it's still within the limit.
+    fn check(&self, name: &str, limit: u32, now: Instant) -> bool {
+        let mut windows = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let (start, count) = windows.entry(name.to_owned()).or_insert((now, 0));
+
+        if now.duration_since(*start) >= RATE_WINDOW {
+            *start = now;
+            *count = 0;
+        }
+
+        if *count >= limit {
+            return false;
+        }
+
+        *count += 1;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZero;
+
+    use axum::http::{header::AUTHORIZATION, HeaderValue};
+
+    use super::*;
+
+    fn hook(targets: Vec<Source>) -> InboundHook {
+        InboundHook {
+            name: "ci".to_owned(),
+            token: "secret".to_owned(),
+            targets,
+            discord_channel: NonZero::new(5),
+            rate_limit: 1,
+        }
+    }
+
+    fn headers(token: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_str(&format!("Bearer {token}")).unwrap(),
+        );
+        headers
+    }
+
+    async fn send(state: &Arc<Inbound>, token: &str, target: Source) -> StatusCode {
+        message(
+            State(Arc::clone(state)),
+            headers(token),
+            Json(Payload {
+                target,
+                message: "hello".to_owned(),
+            }),
+        )
+        .await
+    }
+
+    #[test]
+    fn auth() {
+        let hooks = [hook(vec![])];
+
+        assert!(authenticate(&hooks, &headers("secret")).is_some());
+        assert!(authenticate(&hooks, &headers("secret2")).is_none());
+        assert!(authenticate(&hooks, &HeaderMap::new()).is_none());
+    }
+
+    #[test]
+    fn rate_limit() {
+        let limiter = RateLimiter::default();
+        let now = Instant::now();
+
+        assert!(limiter.check("a", 2, now));
+        assert!(limiter.check("a", 2, now));
+        assert!(!limiter.check("a", 2, now));
+        assert!(limiter.check("b", 2, now));
+        assert!(limiter.check("a", 2, now + RATE_WINDOW));
+    }
+
+    #[tokio::test]
+    async fn routing() {
+        let (outbox, mut outbound) = Outbox::new();
+        let state = Arc::new(Inbound {
+            hooks: vec![hook(vec![Source::Discord])],
+            outbox,
+            limiter: RateLimiter::default(),
+        });
+
+        assert_eq!(
+            StatusCode::UNAUTHORIZED,
+            send(&state, "wrong", Source::Discord).await
+        );
+        assert_eq!(
+            StatusCode::FORBIDDEN,
+            send(&state, "secret", Source::Twitch).await
+        );
+        assert_eq!(
+            StatusCode::ACCEPTED,
+            send(&state, "secret", Source::Discord).await
+        );
+        assert_eq!(
+            StatusCode::TOO_MANY_REQUESTS,
+            send(&state, "secret", Source::Discord).await
+        );
+
+        drop(state);
+        let (source, post) = outbound.recv().await.unwrap();
+        assert_eq!(Source::Discord, source);
+        assert_eq!(NonZero::new(5), post.channel);
+        assert_eq!("hello", post.content);
+        assert!(outbound.recv().await.is_none());
+    }
+}
@@ -0,0 +1,158 @@
+//! Metrics about the message handling, in the Prometheus text format.
+
+use std::{fmt::Write, time::Duration};
+
+use axum::{extract::State, http::header, response::IntoResponse, routing::get, Router};
+
+use crate::timing::{Timings, BUCKETS};
+
+pub fn router(timings: Timings) -> Router {
+    Router::new()
+        .route("/metrics", get(metrics))
+        .with_state(timings)
+}
+
+async fn metrics(State(timings): State<Timings>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        render(&timings),
+    )
+}
+
+/// Render the histograms of all stages, with cumulative bucket counts as Prometheus expects them,
+/// followed by the latency percentiles of each command and the outcomes of sending replies.
+fn render(timings: &Timings) -> String {
+    let mut out = String::from(
+        "# HELP togglebot_stage_duration_seconds Time that messages spent in each stage of the \
+         handling.\n# TYPE togglebot_stage_duration_seconds histogram\n",
+    );
+
+    for (stage, histogram) in timings.report() {
+        let mut cumulative = 0;
+        for (count, bound) in histogram.buckets.iter().zip(BUCKETS) {
+            cumulative += count;
+            writeln!(
+                out,
+                "togglebot_stage_duration_seconds_bucket{{stage=\"{stage}\",le=\"{}\"}} \
+                 {cumulative}",
+                Duration::from_millis(bound).as_secs_f64()
+            )
+            .ok();
+        }
+
+        writeln!(
+            out,
+            "togglebot_stage_duration_seconds_bucket{{stage=\"{stage}\",le=\"+Inf\"}} {}",
+            histogram.count
+        )
+        .ok();
+        writeln!(
+            out,
+            "togglebot_stage_duration_seconds_sum{{stage=\"{stage}\"}} {}",
+            histogram.sum.as_secs_f64()
+        )
+        .ok();
+        writeln!(
+            out,
+            "togglebot_stage_duration_seconds_count{{stage=\"{stage}\"}} {}",
+            histogram.count
+        )
+        .ok();
+    }
+
+    out.push_str(
+        "# HELP togglebot_command_latency_seconds Time from receiving a command until its reply \
+         was sent.\n# TYPE togglebot_command_latency_seconds summary\n",
+    );
+
+    for (command, histogram) in timings.commands() {
+        for (quantile, percent) in [("0.5", 50), ("0.95", 95)] {
+            writeln!(
+                out,
+                "togglebot_command_latency_seconds{{command=\"{command}\",quantile=\"{quantile}\"\
+                 }} {}",
+                histogram.percentile(percent).as_secs_f64()
+            )
+            .ok();
+        }
+
+        writeln!(
+            out,
+            "togglebot_command_latency_seconds_sum{{command=\"{command}\"}} {}",
+            histogram.sum.as_secs_f64()
+        )
+        .ok();
+        writeln!(
+            out,
+            "togglebot_command_latency_seconds_count{{command=\"{command}\"}} {}",
+            histogram.count
+        )
+        .ok();
+    }
+
+    out.push_str(
+        "# HELP togglebot_discord_replies_total Attempts to send a reply to Discord, by outcome.\n\
+         # TYPE togglebot_discord_replies_total counter\n",
+    );
+
+    for (outcome, count) in timings.replies() {
+        writeln!(
+            out,
+            "togglebot_discord_replies_total{{outcome=\"{outcome}\"}} {count}"
+        )
+        .ok();
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::timing::{ReplyOutcome, Stage};
+
+    #[test]
+    fn render_histograms() {
+        let timings = Timings::default();
+        timings.record(Stage::Queue, Duration::from_millis(3));
+        timings.record(Stage::Queue, Duration::from_millis(20));
+
+        let out = render(&timings);
+        assert!(out
+            .contains("togglebot_stage_duration_seconds_bucket{stage=\"queue\",le=\"0.001\"} 0\n"));
+        assert!(out
+            .contains("togglebot_stage_duration_seconds_bucket{stage=\"queue\",le=\"0.005\"} 1\n"));
+        assert!(out
+            .contains("togglebot_stage_duration_seconds_bucket{stage=\"queue\",le=\"0.025\"} 2\n"));
+        assert!(out
+            .contains("togglebot_stage_duration_seconds_bucket{stage=\"queue\",le=\"+Inf\"} 2\n"));
+        assert!(out.contains("togglebot_stage_duration_seconds_sum{stage=\"queue\"} 0.023\n"));
+        assert!(out.contains("togglebot_stage_duration_seconds_count{stage=\"reply\"} 0\n"));
+    }
+
+    #[test]
+    fn render_command_latency() {
+        let timings = Timings::default();
+        timings.record_command("help", Duration::from_millis(3));
+        timings.record_command("help", Duration::from_millis(200));
+
+        let out = render(&timings);
+        assert!(out.contains(
+            "togglebot_command_latency_seconds{command=\"help\",quantile=\"0.5\"} 0.005\n"
+        ));
+        assert!(out.contains(
+            "togglebot_command_latency_seconds{command=\"help\",quantile=\"0.95\"} 0.2\n"
+        ));
+        assert!(out.contains("togglebot_command_latency_seconds_count{command=\"help\"} 2\n"));
+    }
+
+    #[test]
+    fn render_replies() {
+        let timings = Timings::default();
+        timings.record_reply(ReplyOutcome::Dropped);
+
+        let out = render(&timings);
+        assert!(out.contains("togglebot_discord_replies_total{outcome=\"dropped\"} 1\n"));
+        assert!(out.contains("togglebot_discord_replies_total{outcome=\"sent\"} 0\n"));
+    }
+}
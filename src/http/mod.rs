@@ -0,0 +1,87 @@
+//! Built-in HTTP server, that exposes endpoints for external tools to interact with the bot.
+
+use anyhow::{Context, Result};
+use axum::{
+    http::{header::AUTHORIZATION, HeaderMap},
+    Router,
+};
+use tokio::net::TcpListener;
+use tokio_shutdown::Shutdown;
+use tracing::{error, info};
+
+use crate::{
+    api::Queue, health::Health, outbox::Outbox, overlay::Overlay, settings::Http as HttpSettings,
+    state::State, timing::Timings,
+};
+
+mod control;
+mod health;
+mod hooks;
+mod metrics;
+mod overlay;
+mod songs;
+
+/// Initialize and run the HTTP server in a background task.
+///
+/// Messages posted by external tools are published on the outbound bus, for the requested target.
+/// Liveness and readiness probes are available at `/healthz` and `/readyz`, the latter
+/// reporting whether all connectors received data recently. Timings of the message handling are
+/// exposed at `/metrics` in the Prometheus text format. Stream overlays can fetch the song request
+/// queue at `/songs` and receive live bot events through the websocket at `/overlay`. Other
+/// services run admin and owner commands through the JSON-RPC interface at `/rpc`, which hands
+/// them to the message queue. The shutdown handle is used to gracefully stop accepting new
+/// connections.
+#[allow(clippy::too_many_arguments)]
+pub async fn start(
+    config: HttpSettings,
+    queue: Queue,
+    outbox: Outbox,
+    health: Health,
+    timings: Timings,
+    state: State,
+    overlay: Overlay,
+    shutdown: Shutdown,
+) -> Result<()> {
+    let listener = TcpListener::bind(config.address)
+        .await
+        .with_context(|| format!("failed binding HTTP server to {}", config.address))?;
+
+    let app = Router::new()
+        .nest("/hooks", hooks::router(config.hooks, outbox))
+        .merge(control::router(config.control, queue))
+        .merge(health::router(health))
+        .merge(metrics::router(timings))
+        .merge(songs::router(state))
+        .merge(overlay::router(overlay, shutdown.clone()));
+
+    info!(address = %config.address, "http server ready, listening for requests");
+
+    tokio::spawn(async move {
+        let res = axum::serve(listener, app)
+            .with_graceful_shutdown(async move { shutdown.handle().await })
+            .await;
+
+        if let Err(e) = res {
+            error!(error = ?e, "failed running http server");
+        }
+
+        info!("http server shutting down");
+    });
+
+    Ok(())
+}
+
+/// Bearer token of the request, if it has any.
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+}
+
+/// Compare two values without exiting early, to not leak information about the token through
+/// response timings.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,61 @@
+//! Websocket feed of bot events, for stream overlays that react to them in real time.
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket},
+        State, WebSocketUpgrade,
+    },
+    response::Response,
+    routing::get,
+    Router,
+};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_shutdown::Shutdown;
+use tracing::{debug, warn};
+
+use crate::overlay::Overlay;
+
+#[derive(Clone)]
+struct OverlayState {
+    overlay: Overlay,
+    shutdown: Shutdown,
+}
+
+pub fn router(overlay: Overlay, shutdown: Shutdown) -> Router {
+    Router::new()
+        .route("/overlay", get(upgrade))
+        .with_state(OverlayState { overlay, shutdown })
+}
+
+async fn upgrade(ws: WebSocketUpgrade, State(state): State<OverlayState>) -> Response {
+    ws.on_upgrade(move |socket| forward(socket, state))
+}
+
+/// Push all events to the overlay, until either side goes away. Anything the overlay sends is
+/// ignored, except for the closing of the connection.
+async fn forward(mut socket: WebSocket, state: OverlayState) {
+    let mut events = state.overlay.subscribe();
+    debug!("overlay connected");
+
+    loop {
+        tokio::select! {
+            () = state.shutdown.handle() => break,
+            event = events.recv() => match event {
+                Ok(payload) => {
+                    if socket.send(Message::Text(payload.to_string())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => warn!(skipped, "overlay is lagging behind"),
+                Err(RecvError::Closed) => break,
+            },
+            msg = socket.recv() => match msg {
+                Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+
+    socket.close().await.ok();
+    debug!("overlay disconnected");
+}
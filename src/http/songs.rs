@@ -0,0 +1,46 @@
+//! The song request queue, for stream overlays that show the upcoming songs.
+
+use axum::{extract::State as AxumState, http::StatusCode, routing::get, Json, Router};
+use tracing::error;
+
+use crate::{api::response::QueuedSong, state::State};
+
+pub fn router(state: State) -> Router {
+    Router::new().route("/songs", get(songs)).with_state(state)
+}
+
+/// List all songs in the queue, next one first.
+async fn songs(AxumState(state): AxumState<State>) -> Result<Json<Vec<QueuedSong>>, StatusCode> {
+    state.list_song_requests().map(Json).map_err(|e| {
+        error!(error = ?e, "failed listing song requests");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use time::OffsetDateTime;
+
+    use super::*;
+    use crate::api::{response::SongPlatform, Source, UserRef};
+
+    #[tokio::test]
+    async fn list_songs() {
+        let state = State::in_memory().unwrap();
+        state
+            .add_song_request(
+                SongPlatform::YouTube,
+                "https://youtu.be/dQw4w9WgXcQ",
+                &UserRef {
+                    source: Source::Twitch,
+                    id: "someone".to_owned(),
+                },
+                OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+
+        let Json(songs) = songs(AxumState(state)).await.unwrap();
+        assert_eq!(1, songs.len());
+        assert_eq!("https://youtu.be/dQw4w9WgXcQ", songs[0].url);
+    }
+}
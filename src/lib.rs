@@ -5,13 +5,39 @@
 #![allow(clippy::missing_errors_doc, missing_docs)]
 
 pub mod api;
+pub mod bot;
+#[cfg(feature = "chaos")]
+pub mod chaos;
+pub mod chart;
+pub mod clock;
+pub mod commands;
+pub mod completions;
+pub mod connector;
+pub mod console;
+pub mod crypto;
 pub mod db;
 mod dirs;
 pub mod discord;
 pub mod emojis;
+pub mod fun;
 pub mod handler;
+pub mod health;
+pub mod http;
+pub mod locale;
+pub mod matrix;
+pub mod outbox;
+pub mod overlay;
+pub mod plugins;
+pub mod render;
+pub mod runtime;
 pub mod settings;
 pub mod state;
 pub mod statistics;
-mod textparse;
+pub mod systemd;
+#[cfg(feature = "testing")]
+pub mod testing;
+pub mod textparse;
+pub mod timing;
 pub mod twitch;
+pub mod webhooks;
+pub mod youtube;
@@ -0,0 +1,240 @@
+//! Translations of bot responses, kept as a simple key to template map per language.
+//!
+//! Templates contain named placeholders like `{target}`, that are replaced with the arguments
+//! passed to [`Language::translate`]. Any key that is missing in a language falls back to the
+//! English template.
+
+use std::{fmt::Display, str::FromStr};
+
+use anyhow::bail;
+use serde::Deserialize;
+use time::{Month, Weekday};
+
+/// Languages that the bot can respond in.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Deserialize)]
+pub enum Language {
+    /// English, the default language.
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    /// German.
+    #[serde(rename = "de")]
+    German,
+}
+
+const ENGLISH: &[(&str, &str)] = &[
+    (
+        "help",
+        "Thanks for asking, I'm a bot to help answer some typical questions. Try out the \
+         `!commands` command to see what I can do. My source code is at \
+         https://github.com/dnaka91/togglebot",
+    ),
+    ("ban", "{target}, YOU SHALL NOT PASS!!"),
+    (
+        "today",
+        "Today is {weekday}, {month} the {day} of {year} in the {zone} time zone. Did you know, \
+         this is the {day_of_year} day of the year and we're in the {week} week of the year. \
+         Amazing, isn't it?!",
+    ),
+    ("ftoc", "{fahrenheit}°F => {celsius}°C"),
+    ("ctof", "{celsius}°C => {fahrenheit}°F"),
+    ("weekday.monday", "Monday"),
+    ("weekday.tuesday", "Tuesday"),
+    ("weekday.wednesday", "Wednesday"),
+    ("weekday.thursday", "Thursday"),
+    ("weekday.friday", "Friday"),
+    ("weekday.saturday", "Saturday"),
+    ("weekday.sunday", "Sunday"),
+    ("month.january", "January"),
+    ("month.february", "February"),
+    ("month.march", "March"),
+    ("month.april", "April"),
+    ("month.may", "May"),
+    ("month.june", "June"),
+    ("month.july", "July"),
+    ("month.august", "August"),
+    ("month.september", "September"),
+    ("month.october", "October"),
+    ("month.november", "November"),
+    ("month.december", "December"),
+];
+
+const GERMAN: &[(&str, &str)] = &[
+    (
+        "help",
+        "Danke der Nachfrage, ich bin ein Bot, der typische Fragen beantwortet. Probier den \
+         Befehl `!commands` aus, um zu sehen, was ich kann. Mein Quellcode liegt unter \
+         https://github.com/dnaka91/togglebot",
+    ),
+    ("ban", "{target}, DU KANNST NICHT VORBEI!!"),
+    (
+        "today",
+        "Heute ist {weekday}, der {day} {month} {year} in der Zeitzone {zone}. Wusstest du, dass \
+         heute der {day_of_year} Tag des Jahres ist und wir in der {week} Woche des Jahres sind? \
+         Erstaunlich, oder?!",
+    ),
+    ("weekday.monday", "Montag"),
+    ("weekday.tuesday", "Dienstag"),
+    ("weekday.wednesday", "Mittwoch"),
+    ("weekday.thursday", "Donnerstag"),
+    ("weekday.friday", "Freitag"),
+    ("weekday.saturday", "Samstag"),
+    ("weekday.sunday", "Sonntag"),
+    ("month.january", "Januar"),
+    ("month.february", "Februar"),
+    ("month.march", "März"),
+    ("month.april", "April"),
+    ("month.may", "Mai"),
+    ("month.june", "Juni"),
+    ("month.july", "Juli"),
+    ("month.august", "August"),
+    ("month.september", "September"),
+    ("month.october", "Oktober"),
+    ("month.november", "November"),
+    ("month.december", "Dezember"),
+];
+
+impl Language {
+    /// Short code of the language, like `en`.
+    #[must_use]
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::German => "de",
+        }
+    }
+
+    fn templates(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::English => ENGLISH,
+            Self::German => GERMAN,
+        }
+    }
+
+    fn template(self, key: &str) -> &'static str {
+        [self.templates(), ENGLISH]
+            .into_iter()
+            .flatten()
+            .find_map(|(k, template)| (*k == key).then_some(*template))
+            .unwrap_or_default()
+    }
+
+    /// Fill the template for the key with the given arguments.
+    #[must_use]
+    pub fn translate(self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        fill(self.template(key), args)
+    }
+
+    /// Ordinal number, like `1st` in English or `1.` in German.
+    #[must_use]
+    pub fn ordinal(self, value: impl Into<u16>) -> String {
+        let value = value.into();
+        match self {
+            Self::English => {
+                let suffix = match (value % 10, value % 100) {
+                    (_, 11..=13) => "th",
+                    (1, _) => "st",
+                    (2, _) => "nd",
+                    (3, _) => "rd",
+                    _ => "th",
+                };
+                format!("{value}{suffix}")
+            }
+            Self::German => format!("{value}."),
+        }
+    }
+
+    /// Name of the day of the week.
+    #[must_use]
+    pub fn weekday(self, weekday: Weekday) -> &'static str {
+        self.template(&format!("weekday.{}", weekday.to_string().to_lowercase()))
+    }
+
+    /// Name of the month.
+    #[must_use]
+    pub fn month(self, month: Month) -> &'static str {
+        self.template(&format!("month.{}", month.to_string().to_lowercase()))
+    }
+}
+
+impl FromStr for Language {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_lowercase().as_str() {
+            "en" => Self::English,
+            "de" => Self::German,
+            _ => bail!("unknown locale `{s}`, must be either `en` or `de`"),
+        })
+    }
+}
+
+/// Replace the placeholders in the template with the given arguments.
+#[must_use]
+pub fn fill(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter()
+        .fold(template.to_owned(), |text, (name, value)| {
+            text.replace(&format!("{{{name}}}"), &value.to_string())
+        })
+}
+
+/// Names of all placeholders in the template, without the surrounding braces.
+pub fn placeholders(template: &str) -> impl Iterator<Item = &str> {
+    template
+        .split('{')
+        .skip(1)
+        .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn translate() {
+        assert_eq!(
+            "me, YOU SHALL NOT PASS!!",
+            Language::English.translate("ban", &[("target", &"me")])
+        );
+        assert_eq!(
+            "me, DU KANNST NICHT VORBEI!!",
+            Language::German.translate("ban", &[("target", &"me")])
+        );
+    }
+
+    #[test]
+    fn fallback() {
+        assert_eq!(
+            "1.0°C => 33.8°F",
+            Language::German.translate("ctof", &[("celsius", &"1.0"), ("fahrenheit", &"33.8")])
+        );
+        assert_eq!("", Language::English.translate("missing", &[]));
+    }
+
+    #[test]
+    fn find_placeholders() {
+        assert_eq!(
+            vec!["name", "url"],
+            placeholders("{name} at {url}").collect::<Vec<_>>()
+        );
+        assert_eq!(0, placeholders("no {placeholders").count());
+    }
+
+    #[test]
+    fn ordinals() {
+        let english = [1_u16, 2, 3, 4, 11, 12, 13, 21, 112]
+            .map(|value| Language::English.ordinal(value))
+            .join(" ");
+        assert_eq!("1st 2nd 3rd 4th 11th 12th 13th 21st 112th", english);
+        assert_eq!("3.", Language::German.ordinal(3_u16));
+    }
+
+    #[test]
+    fn names() {
+        assert_eq!("Mittwoch", Language::German.weekday(Weekday::Wednesday));
+        assert_eq!("März", Language::German.month(Month::March));
+        assert_eq!("March", Language::English.month(Month::March));
+    }
+}
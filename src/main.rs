@@ -1,93 +1,869 @@
 #![deny(rust_2018_idioms, clippy::all, clippy::pedantic)]
 #![allow(clippy::map_err_ignore)]
 
-use std::sync::Arc;
+use std::{num::NonZero, sync::Arc, time::Duration};
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use togglebot::{
-    api::{request::Request, response::Response, Message},
-    db::connection::Connection,
-    discord,
-    handler::{self, Access},
-    settings::{self, Commands as CommandSettings, Levels, LogStyle, Logging},
-    state::{self, State},
+    api::{
+        response::{self, Response},
+        CustomCommand, Event, Message, Post, Source,
+    },
+    bot::{self, Bot},
+    clock::{self, SharedClock},
+    completions::Completions,
+    connector::{self, Connectors},
+    console,
+    crypto::Key,
+    discord, handler,
+    health::Health,
+    http, matrix,
+    outbox::{self, Outbox},
+    overlay::{self, Overlay},
+    plugins::Plugins,
+    render,
+    settings::{self, Levels, LogStyle, Logging, Tracing, Twitch},
+    state::State,
     statistics::{self, Stats},
+    systemd::Systemd,
+    timing::{Stage, Timings},
     twitch,
+    webhooks::{self, Webhooks},
+    youtube,
 };
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
 use tokio_shutdown::Shutdown;
-use tracing::{error, trace, Subscriber};
+use tracing::{error, Subscriber};
 use tracing_subscriber::{filter::Targets, prelude::*, registry::LookupSpan, Layer};
 
 #[tokio::main]
+#[allow(clippy::too_many_lines)]
 async fn main() -> Result<()> {
-    let config = settings::load()?;
-
-    tracing_subscriber::registry()
-        .with(config.tracing.logging.map(init_logging))
-        .with(init_targets(config.tracing.levels))
-        .init();
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    if args.iter().any(|arg| arg == "--migrate-config") {
+        return settings::upgrade_legacy().await;
+    }
+    if args.first().is_some_and(|arg| arg == "migrate") {
+        return migrate(&args[1..]);
+    }
+    if args.first().is_some_and(|arg| arg == "rotate-key") {
+        return rotate_key();
+    }
 
-    let command_settings = Arc::new(config.commands);
+    let repl = args.iter().any(|arg| arg == "--repl");
+    let config = settings::load()?;
 
-    let state = {
-        let mut conn = Connection::new()?;
-        state::migrate(&mut conn)?;
-        State::new(conn)
-    };
+    init_tracing(config.tracing);
 
-    let statistics = {
-        let mut conn = Connection::new()?;
-        statistics::migrate(&mut conn)?;
-        Stats::new(conn)
-    };
+    let webhooks = Webhooks::new(config.webhooks)?;
+    let health = Health::new(&config.health);
+    let timings = Timings::default();
+    let overlay = Overlay::default();
 
+    let clock = clock::system();
+    let (state, statistics) = bot::open_databases(&config.database)?;
     let shutdown = Shutdown::new()?;
 
     let (queue_tx, mut queue_rx) = mpsc::channel(100);
+    let (events_tx, mut events_rx) = mpsc::channel(100);
+    let twitch = config.twitch.clone();
+    let tokens = config
+        .twitch
+        .as_ref()
+        .zip(config.database.key.clone())
+        .map(|(twitch, key)| twitch::TokenStore::new(state.clone(), key, twitch));
+    let announcements = config.discord.as_ref().and_then(|d| d.announcement_channel);
+    let connectors = Arc::new(init_connectors(
+        repl,
+        config.discord.as_ref(),
+        config.twitch.map(|config| (config, tokens)),
+        config.matrix,
+        config.youtube,
+    ));
+    let (outbox, outbound) = Outbox::new();
+    outbox::start(Arc::clone(&connectors), outbound, shutdown.clone());
+    schedule_discord_posts(&outbox, &state, &clock, config.discord.as_ref(), &shutdown);
+    announce_releases(&outbox, &state, &config.commands, &shutdown);
+    feature_commands(&outbox, &state, &clock, &config.commands, &shutdown);
+    alert_usage(
+        &connectors,
+        &outbox,
+        &state,
+        &statistics,
+        &clock,
+        &config.commands,
+        config.discord.as_ref(),
+        &shutdown,
+    );
 
-    discord::start(
-        &config.discord,
-        Arc::clone(&command_settings),
-        queue_tx.clone(),
-        shutdown.clone(),
-    )
-    .await?;
+    let owners = config
+        .discord
+        .as_ref()
+        .map(|d| d.owners.iter().copied().collect())
+        .unwrap_or_default();
+    let mut builder = Bot::builder(config.commands)
+        .databases(state.clone(), statistics.clone())
+        .clock(Arc::clone(&clock))
+        .plugins(Plugins::from_settings(&config.plugins)?)
+        .health(health.clone())
+        .timings(timings.clone());
+    if let Some(discord) = config.discord {
+        builder = builder.discord(discord);
+    }
+    let bot = Arc::new(builder.build()?);
+    let command_settings = bot.settings();
+
+    connectors.resync_commands(&custom_commands(&state)?).await;
+    connectors
+        .update_presence(state.current_stream()?.as_ref())
+        .await;
+    connectors
+        .start(&connector::Context {
+            settings: Arc::clone(command_settings),
+            queue: queue_tx.clone(),
+            events: events_tx,
+            completions: Completions::new(state.clone(), statistics.clone()),
+            health: health.clone(),
+            timings: timings.clone(),
+            clock: Arc::clone(&clock),
+            shutdown: shutdown.clone(),
+        })
+        .await?;
 
-    twitch::start(
-        &config.twitch,
-        Arc::clone(&command_settings),
-        queue_tx,
+    if let Some(http_config) = config.http {
+        http::start(
+            http_config,
+            queue_tx,
+            outbox.clone(),
+            health.clone(),
+            timings.clone(),
+            state.clone(),
+            overlay.clone(),
+            shutdown.clone(),
+        )
+        .await?;
+    }
+
+    maintain_statistics(
+        statistics.clone(),
+        command_settings.retention,
         shutdown.clone(),
-    )
-    .await?;
+    );
+    check_links(
+        &connectors,
+        &outbox,
+        &state,
+        command_settings,
+        owners,
+        &shutdown,
+    );
+
+    let responder = Responder {
+        bot: Arc::clone(&bot),
+        connectors: Arc::clone(&connectors),
+        outbox: outbox.clone(),
+        state: state.clone(),
+        webhooks: webhooks.clone(),
+        overlay: overlay.clone(),
+        announcements,
+    };
+
+    let mut systemd = Systemd::from_env();
+    systemd.ready();
 
     loop {
         tokio::select! {
             () = shutdown.handle() => break,
+            () = systemd.watchdog() => systemd.ping(),
+            Some(event) = events_rx.recv() => {
+                notify(&webhooks, &overlay, &event);
+                react(&outbox, &state, bot.memory(), twitch.as_ref(), &event);
+                go_live(&connectors, &state, &command_settings.streamer, &event);
+                post_topic(&outbox, &state, &command_settings.topic, &event);
+                update_presence(&connectors, &event);
+                bot.event(event);
+            }
             item = queue_rx.recv() => {
-                let Some((message, reply)) = item else { break };
+                let Some((message, queued, reply)) = item else { break };
+                timings.record(Stage::Queue, queued.elapsed());
 
-                let access = handler::access(&config.discord, &state, &message.author);
-                let res = handle_message(&command_settings, &state, &statistics, access, message).await;
+                // Many commands wait on external services or programs, which must neither hold up
+                // other messages nor the watchdog.
+                let responder = responder.clone();
+                tokio::spawn(async move { responder.respond(message, reply).await });
+            }
+        }
+    }
 
-                let Some(res) = res else { continue };
+    systemd.stopping();
+    connectors.shutdown().await;
+    statistics.try_flush();
+
+    Ok(())
+}
 
-                match res {
-                    Ok(resp) => {
-                        reply.send(resp).ok();
-                    }
-                    Err(e) => {
-                        error!(error = ?e, "error during event handling");
-                    }
+/// Everything needed to handle a message and act on its response, so each message can be handled
+/// in the background.
+#[derive(Clone)]
+struct Responder {
+    bot: Arc<Bot>,
+    connectors: Arc<Connectors>,
+    outbox: Outbox,
+    state: State,
+    webhooks: Webhooks,
+    overlay: Overlay,
+    announcements: Option<NonZero<u64>>,
+}
+
+impl Responder {
+    /// Handle the message, and send the response back to the connector it came from.
+    async fn respond(&self, message: Message, reply: oneshot::Sender<Response>) {
+        let hook = webhooks::Event::from_request(message.source, &message.content);
+        let (source, command) = (message.source, message.content.name().to_owned());
+        let Some(res) = self.bot.handle(message).await else {
+            return;
+        };
+
+        match res {
+            Ok(resp) => {
+                if succeeded(&resp) {
+                    self.webhooks.send(hook);
+                    self.overlay.command(source, &command);
                 }
+                follow_up(
+                    &self.connectors,
+                    &self.outbox,
+                    &self.state,
+                    self.announcements,
+                    &resp,
+                );
+                reply.send(resp).ok();
+            }
+            Err(e) => {
+                error!(error = ?e, "error during event handling");
             }
         }
     }
+}
+
+/// Apply all pending database migrations, or only report them with `--status` and `--dry-run`.
+fn migrate(args: &[String]) -> Result<()> {
+    if let Some(arg) = args
+        .iter()
+        .find(|arg| !matches!(arg.as_str(), "--status" | "--dry-run"))
+    {
+        bail!("unknown argument `{arg}`, expected `--status` or `--dry-run`");
+    }
+
+    let dry_run = args.iter().any(|arg| arg == "--dry-run");
+    let status = dry_run || args.iter().any(|arg| arg == "--status");
+    let config = settings::load()?;
+
+    if !status {
+        bot::open_databases(&config.database)?;
+        println!("all migrations applied, the database is up to date");
+        return Ok(());
+    }
+
+    let pending = bot::check_databases(&config.database, dry_run)?;
+    let schema = &pending.schema;
+
+    println!(
+        "schema: {} of {} migrations applied",
+        schema.applied,
+        schema.applied + schema.pending.len()
+    );
+    for name in &schema.pending {
+        println!("  pending: {name}");
+    }
+
+    for (file, entries) in [
+        ("state.json", pending.state_file),
+        ("statistics.json", pending.statistics_file),
+    ] {
+        match entries {
+            Some(entries) => println!("{file}: {entries} entries awaiting import"),
+            None => println!("{file}: nothing to import"),
+        }
+    }
+
+    if dry_run && !schema.pending.is_empty() {
+        println!("dry run: all pending migrations ran without errors, and were rolled back");
+    }
 
     Ok(())
 }
 
+/// Encrypt the secrets in the database with a new key, and save it in the config file. Without a
+/// current key, this creates the first one.
+fn rotate_key() -> Result<()> {
+    let config = settings::load()?;
+    let (state, _) = bot::open_databases(&config.database)?;
+    let key = Key::generate();
+
+    let count = if let Some(old) = &config.database.key {
+        state.rotate_secrets(old, &key, || settings::save_database_key(&key))?
+    } else {
+        settings::save_database_key(&key)?;
+        0
+    };
+
+    println!("saved the new key in the config file, re-encrypted {count} secrets");
+    Ok(())
+}
+
+/// Write the accumulated statistics to the database and clean up outdated ones in the background,
+/// until shutdown.
+fn maintain_statistics(statistics: Stats, retention: settings::Retention, shutdown: Shutdown) {
+    tokio::spawn(async move {
+        let mut flush = tokio::time::interval(statistics::FLUSH_INTERVAL);
+        let mut cleanup = tokio::time::interval(statistics::CLEANUP_INTERVAL);
+        loop {
+            tokio::select! {
+                () = shutdown.handle() => break,
+                _ = flush.tick() => statistics.try_flush(),
+                _ = cleanup.tick() => statistics.try_cleanup(retention),
+            }
+        }
+    });
+}
+
+/// Start the regular posts to Discord in the background, for the features that are configured.
+fn schedule_discord_posts(
+    outbox: &Outbox,
+    state: &State,
+    clock: &SharedClock,
+    discord: Option<&settings::Discord>,
+    shutdown: &Shutdown,
+) {
+    let Some(discord) = discord else { return };
+
+    if let Some(settings) = &discord.birthdays {
+        announce_birthdays(outbox, state, clock, settings, shutdown);
+    }
+    if let Some(settings) = &discord.github {
+        announce_github(outbox, state, settings, shutdown);
+    }
+}
+
+/// Announce the birthdays of each day in the background, at the configured time, until shutdown.
+fn announce_birthdays(
+    outbox: &Outbox,
+    state: &State,
+    clock: &SharedClock,
+    settings: &settings::Birthdays,
+    shutdown: &Shutdown,
+) {
+    let settings = settings.clone();
+    let (outbox, state, shutdown) = (outbox.clone(), state.clone(), shutdown.clone());
+    let clock = Arc::clone(clock);
+
+    tokio::spawn(async move {
+        loop {
+            let now = clock.now();
+            let next = handler::next_birthdays_announcement(&settings, now);
+
+            tokio::select! {
+                () = shutdown.handle() => break,
+                () = tokio::time::sleep((next - now).unsigned_abs()) => {
+                    handler::announce_birthdays(&outbox, &state, &settings, next.date());
+                }
+            }
+        }
+    });
+}
+
+/// Check the configured GitHub repositories for news in the background, until shutdown.
+fn announce_github(
+    outbox: &Outbox,
+    state: &State,
+    settings: &settings::GitHub,
+    shutdown: &Shutdown,
+) {
+    let (outbox, state, shutdown) = (outbox.clone(), state.clone(), shutdown.clone());
+    let settings = settings.clone();
+
+    tokio::spawn(async move {
+        let mut check = tokio::time::interval(Duration::from_secs(settings.interval));
+        loop {
+            tokio::select! {
+                () = shutdown.handle() => break,
+                _ = check.tick() => handler::announce_github(&outbox, &state, &settings).await,
+            }
+        }
+    });
+}
+
+/// Check the watched crates and the Rust toolchain for new versions in the background, until
+/// shutdown. Each check only runs, if it has at least one place to announce in.
+fn announce_releases(
+    outbox: &Outbox,
+    state: &State,
+    commands: &settings::Commands,
+    shutdown: &Shutdown,
+) {
+    let crates = commands.crate_releases.clone();
+    if crates.discord_channel.is_some() || crates.twitch {
+        let (outbox, state, shutdown) = (outbox.clone(), state.clone(), shutdown.clone());
+
+        tokio::spawn(async move {
+            let mut check = tokio::time::interval(Duration::from_secs(crates.interval));
+            loop {
+                tokio::select! {
+                    () = shutdown.handle() => break,
+                    _ = check.tick() => handler::announce_releases(&outbox, &state, &crates).await,
+                }
+            }
+        });
+    }
+
+    let rust = commands.rust_releases.clone();
+    if rust.discord_channel.is_some() || rust.twitch {
+        let (outbox, state, shutdown) = (outbox.clone(), state.clone(), shutdown.clone());
+
+        tokio::spawn(async move {
+            let mut check = tokio::time::interval(Duration::from_secs(rust.interval));
+            loop {
+                tokio::select! {
+                    () = shutdown.handle() => break,
+                    _ = check.tick() => handler::announce_rust_release(&outbox, &state, &rust).await,
+                }
+            }
+        });
+    }
+}
+
+/// Post the command of the day in the background, at the configured time, until shutdown. Nothing
+/// is scheduled without a place to post in.
+fn feature_commands(
+    outbox: &Outbox,
+    state: &State,
+    clock: &SharedClock,
+    commands: &settings::Commands,
+    shutdown: &Shutdown,
+) {
+    let settings = commands.command_of_the_day.clone();
+    if settings.discord_channel.is_none() && !settings.twitch {
+        return;
+    }
+
+    let (outbox, state, shutdown) = (outbox.clone(), state.clone(), shutdown.clone());
+    let clock = Arc::clone(clock);
+
+    tokio::spawn(async move {
+        loop {
+            let now = clock.now();
+            let next = handler::next_featured_command(&settings, now);
+
+            tokio::select! {
+                () = shutdown.handle() => break,
+                () = tokio::time::sleep((next - now).unsigned_abs()) => {
+                    handler::feature_command(&outbox, &state, &settings, next);
+                }
+            }
+        }
+    });
+}
+
+/// Check the command usage against the limits that admins set in the background, until shutdown.
+/// Nothing is checked without a place to send the alerts to.
+#[allow(clippy::too_many_arguments)]
+fn alert_usage(
+    connectors: &Arc<Connectors>,
+    outbox: &Outbox,
+    state: &State,
+    statistics: &Stats,
+    clock: &SharedClock,
+    commands: &settings::Commands,
+    discord: Option<&settings::Discord>,
+    shutdown: &Shutdown,
+) {
+    let settings = commands.usage_alerts.clone();
+    if settings.discord_channel.is_none() && !settings.direct_messages {
+        return;
+    }
+
+    let owners = discord
+        .map(|d| d.owners.iter().copied().collect::<Vec<_>>())
+        .unwrap_or_default();
+    let (connectors, outbox, shutdown) = (Arc::clone(connectors), outbox.clone(), shutdown.clone());
+    let (state, statistics, clock) = (state.clone(), statistics.clone(), Arc::clone(clock));
+
+    tokio::spawn(async move {
+        let mut check = tokio::time::interval(Duration::from_secs(settings.interval));
+        loop {
+            tokio::select! {
+                () = shutdown.handle() => break,
+                _ = check.tick() => {
+                    handler::check_usage_alerts(
+                        &connectors,
+                        &outbox,
+                        &state,
+                        &statistics,
+                        &settings,
+                        &owners,
+                        clock.now().date(),
+                    )
+                    .await;
+                }
+            }
+        }
+    });
+}
+
+/// Check the links of the settings and custom commands in the background, until shutdown. Dead
+/// links are sent to the same places as the usage alerts, so nothing is checked without any.
+fn check_links(
+    connectors: &Arc<Connectors>,
+    outbox: &Outbox,
+    state: &State,
+    commands: &Arc<settings::Commands>,
+    owners: Vec<NonZero<u64>>,
+    shutdown: &Shutdown,
+) {
+    let alerts = &commands.usage_alerts;
+    if alerts.discord_channel.is_none() && !alerts.direct_messages {
+        return;
+    }
+
+    let (connectors, outbox, shutdown) = (Arc::clone(connectors), outbox.clone(), shutdown.clone());
+    let (state, settings) = (state.clone(), Arc::clone(commands));
+
+    tokio::spawn(async move {
+        let mut reported = handler::ReportedLinks::default();
+        let mut check = tokio::time::interval(Duration::from_secs(settings.link_check.interval));
+        loop {
+            tokio::select! {
+                () = shutdown.handle() => break,
+                _ = check.tick() => {
+                    handler::alert_dead_links(
+                        &connectors,
+                        &outbox,
+                        &state,
+                        &settings,
+                        &owners,
+                        &mut reported,
+                    )
+                    .await;
+                }
+            }
+        }
+    });
+}
+
+/// Set up the connectors for all configured services, or only the local console in REPL mode.
+fn init_connectors(
+    repl: bool,
+    discord: Option<&settings::Discord>,
+    twitch: Option<(settings::Twitch, Option<twitch::TokenStore>)>,
+    matrix: Option<settings::Matrix>,
+    youtube: Option<settings::YouTube>,
+) -> Connectors {
+    let mut connectors = Connectors::default();
+    if repl {
+        // Only talk to the local console, so no service accounts are needed.
+        connectors.add(console::Console::new());
+        return connectors;
+    }
+
+    if let Some(discord_config) = discord {
+        connectors.add(discord::Discord::new(discord_config));
+    }
+    if let Some((twitch_config, tokens)) = twitch {
+        connectors.add(twitch::Twitch::new(twitch_config, tokens));
+    }
+    if let Some(matrix_config) = matrix {
+        connectors.add(matrix::Matrix::new(matrix_config));
+    }
+    if let Some(youtube_config) = youtube {
+        connectors.add(youtube::YouTube::new(youtube_config));
+    }
+
+    connectors
+}
+
+/// Inform the webhooks and stream overlays about an event that isn't related to any message.
+fn notify(webhooks: &Webhooks, overlay: &Overlay, event: &Event) {
+    match event {
+        Event::StreamOnline(info) => {
+            webhooks.send(webhooks::Event::stream_online(info));
+            overlay.send(overlay::Event::stream_online(info));
+        }
+        Event::StreamOffline => {
+            webhooks.send(webhooks::Event::StreamOffline);
+            overlay.send(overlay::Event::StreamOffline);
+        }
+        Event::Follow { source, user } => overlay.send(overlay::Event::Follow {
+            source: *source,
+            user: user.clone(),
+        }),
+        Event::Subscribe { .. }
+        | Event::Cheer { .. }
+        | Event::Raid { .. }
+        | Event::PollEnded { .. }
+        | Event::Chat { .. }
+        | Event::CodeShared { .. }
+        | Event::MessageEdited { .. }
+        | Event::MessagesDeleted(_)
+        | Event::Watching { .. }
+        | Event::ChannelUpdate { .. } => {}
+    }
+}
+
+/// Thank supporters, give raiding channels a shoutout, relay code blocks from Discord and announce
+/// poll results in the Twitch chat.
+fn react(
+    outbox: &Outbox,
+    state: &State,
+    memory: &handler::Memory,
+    twitch: Option<&Twitch>,
+    event: &Event,
+) {
+    let Some(twitch) = twitch else { return };
+
+    if let Some(settings) = &twitch.thanks {
+        if let Some(content) = handler::thanks(settings, event) {
+            announce(outbox, content, settings.discord_channel);
+        }
+    }
+
+    if let Some(settings) = &twitch.shoutout {
+        if let Some(content) = handler::shoutout(memory, settings, state, event) {
+            announce(outbox, content, None);
+        }
+    }
+
+    if let Event::PollEnded {
+        source: Source::Twitch,
+        question,
+        results,
+    } = event
+    {
+        announce(outbox, render::poll_results(question, results), None);
+    }
+
+    if let (Some(settings), Event::CodeShared { .. }) = (&twitch.code_relay, event) {
+        let (outbox, settings, event) = (outbox.clone(), settings.clone(), event.clone());
+        tokio::spawn(async move { handler::relay_code(&outbox, &settings, &event).await });
+    }
+}
+
+/// Send direct messages to all users that opted in, once the stream went live.
+fn go_live(connectors: &Arc<Connectors>, state: &State, streamer: &str, event: &Event) {
+    if let Event::StreamOnline(info) = event {
+        let (connectors, state) = (Arc::clone(connectors), state.clone());
+        let (streamer, info) = (streamer.to_owned(), info.clone());
+
+        tokio::spawn(async move { handler::go_live(&connectors, &state, &streamer, &info).await });
+    }
+}
+
+/// Post the agenda in the configured places, once the stream went live. This runs before the event
+/// is recorded, so an agenda that was set while offline is still waiting for the new session.
+fn post_topic(outbox: &Outbox, state: &State, settings: &settings::Topic, event: &Event) {
+    if settings.discord_channel.is_none() && !settings.twitch {
+        return;
+    }
+
+    if let Event::StreamOnline(_) = event {
+        handler::post_topic(outbox, state, settings);
+    }
+}
+
+/// Show the new stream state in the status of the bot accounts in the background.
+fn update_presence(connectors: &Arc<Connectors>, event: &Event) {
+    let stream = match event {
+        Event::StreamOnline(info) => Some(info.clone()),
+        Event::StreamOffline => None,
+        _ => return,
+    };
+    let connectors = Arc::clone(connectors);
+
+    tokio::spawn(async move { connectors.update_presence(stream.as_ref()).await });
+}
+
+/// Start any background work on the services, that a response asks for.
+fn follow_up(
+    connectors: &Arc<Connectors>,
+    outbox: &Outbox,
+    state: &State,
+    announcements: Option<NonZero<u64>>,
+    resp: &Response,
+) {
+    start_poll(connectors, resp);
+    resync_commands(connectors, state, resp);
+    pin_command_list(connectors, state, resp);
+    broadcast(connectors, outbox, announcements, resp);
+    notify_suggester(connectors, outbox, resp);
+}
+
+/// Start a poll on all services in the background, once a moderator successfully requested one.
+fn start_poll(connectors: &Arc<Connectors>, resp: &Response) {
+    if let Response::User(response::User::Poll(Ok(poll))) = resp {
+        let (connectors, poll) = (Arc::clone(connectors), poll.clone());
+        tokio::spawn(async move { connectors.start_poll(&poll).await });
+    }
+}
+
+/// Register all commands again in the background, once an owner asked for it or the custom
+/// commands changed.
+fn resync_commands(connectors: &Arc<Connectors>, state: &State, resp: &Response) {
+    if !matches!(
+        resp,
+        Response::Owner(response::Owner::ResyncCommands)
+            | Response::Admin(response::Admin::CustomCommands(
+                response::CustomCommands::Edit(Ok(_))
+            ))
+    ) {
+        return;
+    }
+
+    let custom = match custom_commands(state) {
+        Ok(custom) => custom,
+        Err(e) => {
+            error!(error = ?e, "failed loading custom commands");
+            return;
+        }
+    };
+
+    let connectors = Arc::clone(connectors);
+    tokio::spawn(async move { connectors.resync_commands(&custom).await });
+}
+
+/// Update the pinned command list in the background, once it was moved or the custom commands
+/// changed.
+fn pin_command_list(connectors: &Arc<Connectors>, state: &State, resp: &Response) {
+    if !matches!(
+        resp,
+        Response::Admin(
+            response::Admin::CommandList(Ok(Some(_)))
+                | response::Admin::CustomCommands(response::CustomCommands::Edit(Ok(_)))
+        )
+    ) {
+        return;
+    }
+
+    let (connectors, state) = (Arc::clone(connectors), state.clone());
+    tokio::spawn(async move {
+        handler::pin_command_list(&connectors, &state, render::discord::command_list).await;
+    });
+}
+
+/// Post an owner's message on all connected services, with the announcement channel as place on
+/// Discord. Discord is left out without one.
+fn broadcast(
+    connectors: &Connectors,
+    outbox: &Outbox,
+    announcements: Option<NonZero<u64>>,
+    resp: &Response,
+) {
+    let Response::Owner(response::Owner::Broadcast(content)) = resp else {
+        return;
+    };
+
+    for source in connectors.sources() {
+        let channel = match (source, announcements) {
+            (Source::Discord, None) => continue,
+            (Source::Discord, channel) => channel,
+            _ => None,
+        };
+        let post = Post {
+            channel,
+            content: content.clone(),
+        };
+
+        if let Err(e) = outbox.publish(source, post) {
+            error!(error = ?e, %source, "failed broadcasting message");
+        }
+    }
+}
+
+/// Tell the author of a suggestion that it was reviewed. Discord users get a direct message, while
+/// on other services they're mentioned in the chat.
+fn notify_suggester(connectors: &Arc<Connectors>, outbox: &Outbox, resp: &Response) {
+    let Response::Admin(response::Admin::Suggestions(response::Suggestions::Review(Ok(
+        suggestion,
+    )))) = resp
+    else {
+        return;
+    };
+
+    let content = render::suggestion_reviewed(suggestion);
+    if suggestion.source == Source::Discord {
+        let (connectors, user) = (Arc::clone(connectors), suggestion.user_id.clone());
+        tokio::spawn(async move {
+            if let Err(e) = connectors
+                .send_direct(Source::Discord, user.clone(), content)
+                .await
+            {
+                error!(error = ?e, user, "failed notifying suggester");
+            }
+        });
+        return;
+    }
+
+    let post = Post {
+        channel: None,
+        content: format!("@{} {content}", suggestion.name),
+    };
+    if let Err(e) = outbox.publish(suggestion.source, post) {
+        error!(error = ?e, source = %suggestion.source, "failed notifying suggester");
+    }
+}
+
+/// Custom commands of Discord, the only service that registers its commands upfront.
+fn custom_commands(state: &State) -> Result<Vec<CustomCommand>> {
+    state
+        .list_custom_command_names(Source::Discord)?
+        .into_iter()
+        .filter_map(|name| {
+            state
+                .get_custom_command(Source::Discord, &name)
+                .transpose()
+                .map(|content| content.map(|content| CustomCommand { name, content }))
+        })
+        .collect()
+}
+
+/// Post a message in the Twitch chat, and mirror it to a Discord channel if given.
+fn announce(outbox: &Outbox, content: String, mirror: Option<NonZero<u64>>) {
+    if let Some(channel) = mirror {
+        let post = Post {
+            channel: Some(channel),
+            content: content.clone(),
+        };
+        if let Err(e) = outbox.publish(Source::Discord, post) {
+            error!(error = ?e, "failed mirroring announcement to Discord");
+        }
+    }
+
+    let post = Post {
+        channel: None,
+        content,
+    };
+    if let Err(e) = outbox.publish(Source::Twitch, post) {
+        error!(error = ?e, "failed posting announcement");
+    }
+}
+
+/// Whether the response describes a successfully executed command.
+fn succeeded(resp: &Response) -> bool {
+    !matches!(
+        resp,
+        Response::User(response::User::Unknown | response::User::Trigger(None))
+            | Response::Admin(response::Admin::CustomCommands(
+                response::CustomCommands::Edit(Err(_))
+            ))
+    )
+}
+
+fn init_tracing(settings: Tracing) {
+    tracing_subscriber::registry()
+        .with(settings.logging.map(init_logging))
+        .with(init_targets(settings.levels))
+        .init();
+}
+
 #[allow(clippy::needless_pass_by_value)]
 fn init_logging<S>(settings: Logging) -> impl Layer<S>
 where
@@ -108,42 +884,3 @@ fn init_targets(settings: Levels) -> Targets {
         .with_target(env!("CARGO_CRATE_NAME"), settings.togglebot)
         .with_targets(settings.targets)
 }
-
-async fn handle_message(
-    settings: &Arc<CommandSettings>,
-    state: &State,
-    statistics: &Stats,
-    access: Access,
-    message: Message,
-) -> Option<Result<Response>> {
-    Some(match (access, message.content) {
-        (Access::Owner, Request::Owner(request)) => {
-            handler::owner_message(message.span, state, request)
-                .await
-                .map(Response::Owner)
-        }
-        (_, Request::Owner(_)) => {
-            trace!("non-owner tried using a owner-only request");
-            return None;
-        }
-        (Access::Owner | Access::Admin, Request::Admin(request)) => {
-            handler::admin_message(message.span, state, statistics, request)
-                .await
-                .map(Response::Admin)
-        }
-        (_, Request::Admin(_)) => {
-            trace!("non-admin tried using a admin-only request");
-            return None;
-        }
-        (_, Request::User(request)) => handler::user_message(
-            message.span,
-            Arc::clone(settings),
-            state,
-            statistics,
-            request,
-            message.source,
-        )
-        .await
-        .map(Response::User),
-    })
-}
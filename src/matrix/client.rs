@@ -0,0 +1,183 @@
+//! Minimal client for the Matrix client-server API, covering only the few endpoints that the bot
+//! needs to follow rooms and reply to messages.
+
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{ensure, Context, Result};
+use reqwest::{Method, RequestBuilder, Url};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// Event filter for the sync endpoint, that skips everything except room messages.
+const FILTER: &str = r#"{"presence":{"types":[]},"account_data":{"types":[]},"room":{"account_data":{"types":[]},"ephemeral":{"types":[]},"state":{"types":[]},"timeline":{"types":["m.room.message"]}}}"#;
+
+pub struct Client {
+    http: reqwest::Client,
+    homeserver: Url,
+    access_token: String,
+    txn_prefix: u128,
+    txn_counter: AtomicU64,
+}
+
+impl Client {
+    pub fn new(homeserver: &str, access_token: String) -> Result<Self> {
+        let homeserver = Url::parse(homeserver).context("invalid homeserver URL")?;
+        ensure!(
+            !homeserver.cannot_be_a_base(),
+            "homeserver URL must be a base URL"
+        );
+
+        Ok(Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_mins(1))
+                .build()?,
+            homeserver,
+            access_token,
+            // Transaction IDs must be unique per access token, also across restarts.
+            txn_prefix: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis(),
+            txn_counter: AtomicU64::new(0),
+        })
+    }
+
+    fn request(&self, method: Method, segments: &[&str]) -> RequestBuilder {
+        let mut url = self.homeserver.clone();
+        url.path_segments_mut()
+            .expect("homeserver URL is a base URL")
+            .pop_if_empty()
+            .extend(["_matrix", "client", "v3"])
+            .extend(segments);
+
+        self.http
+            .request(method, url)
+            .bearer_auth(&self.access_token)
+    }
+
+    /// Get the fully qualified user ID of the bot account.
+    pub async fn whoami(&self) -> Result<String> {
+        #[derive(Deserialize)]
+        struct WhoAmI {
+            user_id: String,
+        }
+
+        Ok(self
+            .request(Method::GET, &["account", "whoami"])
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<WhoAmI>()
+            .await?
+            .user_id)
+    }
+
+    /// Join the room with the given ID or alias, returning the room ID.
+    pub async fn join(&self, room: &str) -> Result<String> {
+        #[derive(Deserialize)]
+        struct Joined {
+            room_id: String,
+        }
+
+        Ok(self
+            .request(Method::POST, &["join", room])
+            .json(&json!({}))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Joined>()
+            .await?
+            .room_id)
+    }
+
+    /// Fetch all new events since the given batch token, waiting up to the timeout for any
+    /// events to arrive.
+    pub async fn sync(&self, since: Option<&str>, timeout: Duration) -> Result<Sync> {
+        let mut query = vec![
+            ("filter", FILTER.to_owned()),
+            ("timeout", timeout.as_millis().to_string()),
+        ];
+        if let Some(since) = since {
+            query.push(("since", since.to_owned()));
+        }
+
+        self.request(Method::GET, &["sync"])
+            .query(&query)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Send a plain text message to a room, optionally as reply to another event.
+    pub async fn send(&self, room_id: &str, reply_to: Option<&str>, body: &str) -> Result<()> {
+        #[cfg(feature = "chaos")]
+        crate::chaos::send()?;
+
+        let txn_id = format!(
+            "togglebot-{}-{}",
+            self.txn_prefix,
+            self.txn_counter.fetch_add(1, Ordering::Relaxed)
+        );
+
+        let mut content = json!({
+            "msgtype": "m.text",
+            "body": body,
+        });
+        if let Some(event_id) = reply_to {
+            content["m.relates_to"] = json!({ "m.in_reply_to": { "event_id": event_id } });
+        }
+
+        self.request(
+            Method::PUT,
+            &["rooms", room_id, "send", "m.room.message", &txn_id],
+        )
+        .json(&content)
+        .send()
+        .await?
+        .error_for_status()?;
+
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+pub struct Sync {
+    pub next_batch: String,
+    #[serde(default)]
+    pub rooms: Rooms,
+}
+
+#[derive(Default, Deserialize)]
+pub struct Rooms {
+    #[serde(default)]
+    pub join: HashMap<String, JoinedRoom>,
+}
+
+#[derive(Deserialize)]
+pub struct JoinedRoom {
+    #[serde(default)]
+    pub timeline: Timeline,
+}
+
+#[derive(Default, Deserialize)]
+pub struct Timeline {
+    #[serde(default)]
+    pub events: Vec<RoomEvent>,
+}
+
+#[derive(Deserialize)]
+pub struct RoomEvent {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub event_id: String,
+    pub sender: String,
+    #[serde(default)]
+    pub content: Value,
+}
@@ -0,0 +1,338 @@
+//! Matrix service connector that allows to receive commands from Matrix rooms.
+//!
+//! Talks to the homeserver through the plain client-server API, long-polling the sync endpoint for
+//! new room messages. End-to-end encrypted rooms aren't supported.
+
+use std::{
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Context, Result};
+use futures_util::future::BoxFuture;
+use tokio::{select, task::JoinHandle, time};
+use tracing::{error, info, info_span, instrument, Instrument, Span};
+
+use self::client::{Client, Sync};
+use crate::{
+    api::{response::Response, Author, AuthorId, Message, Post, Queue, Source},
+    connector::{self, Connector, Context as ConnectorContext},
+    render,
+    settings::Matrix as MatrixSettings,
+    textparse,
+    timing::Timings,
+};
+
+mod client;
+
+/// Time that the homeserver may hold back a sync request, waiting for new events.
+const SYNC_TIMEOUT: Duration = Duration::from_secs(30);
+/// Delay before retrying a failed sync request.
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+struct Running {
+    client: Client,
+    user_id: String,
+    rooms: Vec<String>,
+}
+
+/// Connector for Matrix, that follows the configured rooms and replies in them.
+pub struct Matrix {
+    config: MatrixSettings,
+    running: OnceLock<Arc<Running>>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl Matrix {
+    /// Create a new, not yet started, Matrix connector.
+    #[must_use]
+    pub fn new(config: MatrixSettings) -> Self {
+        Self {
+            config,
+            running: OnceLock::new(),
+            tasks: Mutex::default(),
+        }
+    }
+
+    /// Join the configured rooms and listen for new messages in a background task.
+    ///
+    /// Only messages that arrive after startup are processed. Every sync response is reported as
+    /// heartbeat to the health state.
+    async fn run(&self, ctx: ConnectorContext) -> Result<()> {
+        let ConnectorContext {
+            queue,
+            health,
+            timings,
+            shutdown,
+            ..
+        } = ctx;
+
+        let client = Client::new(&self.config.homeserver, self.config.access_token.clone())?;
+        let user_id = client.whoami().await.context("failed validating token")?;
+
+        let mut rooms = Vec::with_capacity(self.config.rooms.len());
+        for room in &self.config.rooms {
+            rooms.push(
+                client
+                    .join(room)
+                    .await
+                    .with_context(|| format!("failed joining room {room}"))?,
+            );
+        }
+
+        // Skip over any history, so old commands aren't answered again.
+        let mut since = client
+            .sync(None, Duration::ZERO)
+            .await
+            .context("failed initial sync")?
+            .next_batch;
+        health.beat(Source::Matrix);
+
+        let running = Arc::new(Running {
+            client,
+            user_id,
+            rooms,
+        });
+
+        self.running
+            .set(Arc::clone(&running))
+            .map_err(|_| anyhow!("matrix connector already started"))?;
+
+        let task = tokio::spawn(async move {
+            loop {
+                let res = select! {
+                    () = shutdown.handle() => break,
+                    res = running.client.sync(Some(&since), SYNC_TIMEOUT) => res,
+                };
+
+                match res {
+                    Ok(sync) => {
+                        health.beat(Source::Matrix);
+
+                        for message in messages(&sync, &running.rooms, &running.user_id) {
+                            if let Err(e) =
+                                handle_message(&queue, &timings, &running.client, message).await
+                            {
+                                error!(error = ?e, "failed handling matrix message");
+                            }
+                        }
+
+                        since = sync.next_batch;
+                    }
+                    Err(e) => {
+                        error!(error = ?e, "failed syncing with matrix homeserver");
+                        time::sleep(RETRY_DELAY).await;
+                    }
+                }
+            }
+        });
+
+        self.tasks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(task);
+
+        info!("matrix connection ready, listening for events");
+
+        Ok(())
+    }
+
+    /// Post the message in all joined rooms.
+    async fn post(&self, post: Post) -> Result<()> {
+        let running = self.running.get().context("matrix connector not started")?;
+
+        for room in &running.rooms {
+            running.client.send(room, None, &post.content).await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Connector for Matrix {
+    fn source(&self) -> Source {
+        Source::Matrix
+    }
+
+    fn start(&self, ctx: ConnectorContext) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.run(ctx))
+    }
+
+    fn send_reply(&self, post: Post) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.post(post))
+    }
+
+    fn shutdown(&self) -> BoxFuture<'_, ()> {
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap_or_else(PoisonError::into_inner));
+
+        Box::pin(async move {
+            for task in tasks {
+                task.abort();
+            }
+            info!("matrix connection shutting down");
+        })
+    }
+}
+
+/// A plain text message, received in one of the followed rooms.
+#[cfg_attr(test, derive(Debug, PartialEq))]
+struct RoomMessage<'a> {
+    room_id: &'a str,
+    event_id: &'a str,
+    sender: &'a str,
+    body: &'a str,
+}
+
+/// Extract all text messages from the sync response, that were sent by other users in the given
+/// rooms.
+fn messages<'a>(
+    sync: &'a Sync,
+    rooms: &'a [String],
+    user_id: &'a str,
+) -> impl Iterator<Item = RoomMessage<'a>> {
+    sync.rooms
+        .join
+        .iter()
+        .filter(|(room_id, _)| rooms.contains(room_id))
+        .flat_map(|(room_id, room)| {
+            room.timeline
+                .events
+                .iter()
+                .map(move |event| (room_id, event))
+        })
+        .filter(move |(_, event)| event.kind == "m.room.message" && event.sender != user_id)
+        .filter_map(|(room_id, event)| {
+            if event.content["msgtype"] != "m.text" {
+                return None;
+            }
+
+            Some(RoomMessage {
+                room_id,
+                event_id: &event.event_id,
+                sender: &event.sender,
+                body: event.content["body"].as_str()?,
+            })
+        })
+}
+
+#[instrument(skip_all, name = "matrix message", fields(source = %Source::Matrix))]
+async fn handle_message(
+    queue: &Queue,
+    timings: &Timings,
+    client: &Client,
+    msg: RoomMessage<'_>,
+) -> Result<()> {
+    let received = Instant::now();
+    let Ok(Some(content)) = textparse::parse(msg.body, Source::Matrix, None) else {
+        return Ok(());
+    };
+
+    let message = Message {
+        span: Span::current(),
+        source: Source::Matrix,
+        content,
+        author: Author {
+            name: msg
+                .sender
+                .trim_start_matches('@')
+                .split(':')
+                .next()
+                .unwrap_or_default()
+                .to_owned(),
+            ..AuthorId::Matrix(msg.sender.to_owned()).into()
+        },
+        mention: None,
+        channel: None,
+    };
+
+    let command = message.content.metric_name().to_owned();
+    if let Some(resp) = connector::dispatch(queue, message).await {
+        let reply = async {
+            match resp {
+                Response::User(user_resp) => match render::plain::user(user_resp) {
+                    Some(body) => client.send(msg.room_id, Some(msg.event_id), &body).await,
+                    None => Ok(()),
+                },
+                Response::Denied(required) => {
+                    client
+                        .send(msg.room_id, Some(msg.event_id), &render::denied(required))
+                        .await
+                }
+                Response::Admin(_) | Response::Owner(_) | Response::Confirm(_) => Ok(()),
+            }
+        }
+        .instrument(info_span!("reply"));
+
+        timings.reply(&command, received, reply).await?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn room_messages() {
+        let sync = serde_json::from_value::<Sync>(serde_json::json!({
+            "next_batch": "s2",
+            "rooms": {
+                "join": {
+                    "!room:example.org": {
+                        "timeline": {
+                            "events": [
+                                {
+                                    "type": "m.room.message",
+                                    "event_id": "$1",
+                                    "sender": "@user:example.org",
+                                    "content": { "msgtype": "m.text", "body": "!help" }
+                                },
+                                {
+                                    "type": "m.room.message",
+                                    "event_id": "$2",
+                                    "sender": "@bot:example.org",
+                                    "content": { "msgtype": "m.text", "body": "!help" }
+                                },
+                                {
+                                    "type": "m.room.message",
+                                    "event_id": "$3",
+                                    "sender": "@user:example.org",
+                                    "content": { "msgtype": "m.image", "body": "cat.png" }
+                                }
+                            ]
+                        }
+                    },
+                    "!other:example.org": {
+                        "timeline": {
+                            "events": [
+                                {
+                                    "type": "m.room.message",
+                                    "event_id": "$4",
+                                    "sender": "@user:example.org",
+                                    "content": { "msgtype": "m.text", "body": "!help" }
+                                }
+                            ]
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        let rooms = ["!room:example.org".to_owned()];
+        let messages = messages(&sync, &rooms, "@bot:example.org").collect::<Vec<_>>();
+
+        assert_eq!(
+            vec![RoomMessage {
+                room_id: "!room:example.org",
+                event_id: "$1",
+                sender: "@user:example.org",
+                body: "!help",
+            }],
+            messages
+        );
+    }
+}
@@ -0,0 +1,159 @@
+//! Bus for messages that the bot posts on its own, like announcements and scheduled posts, which
+//! aren't a reply to any received chat message.
+//!
+//! Subsystems publish their messages through an [`Outbox`], addressed to a service and optionally a
+//! channel. The messages are delivered through the connectors in the background, and messages to
+//! the same service are spaced out, so a burst of announcements doesn't run into rate limits.
+
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Result};
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio_shutdown::Shutdown;
+use tracing::error;
+
+use crate::{
+    api::{Post, Source},
+    connector::Connectors,
+};
+
+/// Amount of messages that can wait for delivery, before new ones are refused.
+const CAPACITY: usize = 100;
+
+/// Handle to publish messages on the bus, which can be cloned freely.
+#[derive(Clone)]
+pub struct Outbox(mpsc::Sender<(Source, Post)>);
+
+/// Receiving end of the bus, that [`start`] delivers the messages from.
+pub struct Outbound(mpsc::Receiver<(Source, Post)>);
+
+impl Outbox {
+    /// Create a new bus, returning the handle to publish with and the receiving end.
+    #[must_use]
+    pub fn new() -> (Self, Outbound) {
+        let (tx, rx) = mpsc::channel(CAPACITY);
+        (Self(tx), Outbound(rx))
+    }
+
+    /// Queue a message for delivery to the given service. It fails if too many messages are
+    /// waiting already, or the bus was shut down.
+    pub fn publish(&self, source: Source, post: Post) -> Result<()> {
+        match self.0.try_send((source, post)) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => bail!("too many outbound messages are waiting"),
+            Err(TrySendError::Closed(_)) => bail!("outbound messages aren't delivered anymore"),
+        }
+    }
+}
+
+impl Outbound {
+    /// Wait for the next published message, or nothing once all handles are gone.
+    pub async fn recv(&mut self) -> Option<(Source, Post)> {
+        self.0.recv().await
+    }
+}
+
+/// Deliver all published messages through the connectors in the background, until shutdown.
+/// Failed deliveries are only logged, as nobody waits for them.
+pub fn start(connectors: Arc<Connectors>, mut outbound: Outbound, shutdown: Shutdown) {
+    tokio::spawn(async move {
+        let mut spacing = Spacing::default();
+
+        loop {
+            let (source, post) = tokio::select! {
+                () = shutdown.handle() => break,
+                item = outbound.recv() => match item {
+                    Some(item) => item,
+                    None => break,
+                },
+            };
+
+            let at = spacing.reserve(source, Instant::now());
+            let connectors = Arc::clone(&connectors);
+
+            tokio::spawn(async move {
+                tokio::time::sleep_until(at.into()).await;
+                if let Err(e) = connectors.send_reply(source, post).await {
+                    error!(error = ?e, %source, "failed delivering outbound message");
+                }
+            });
+        }
+    });
+}
+
+/// Minimum time between two messages to the same service.
+const fn interval(source: Source) -> Duration {
+    match source {
+        // Regular accounts may send 20 messages within 30 seconds.
+        Source::Twitch => Duration::from_millis(1500),
+        Source::Discord | Source::Matrix | Source::YouTube => Duration::from_secs(1),
+        Source::Console => Duration::ZERO,
+    }
+}
+
+/// Delivery slots per service, that keep the messages apart by the service's interval.
+#[derive(Default)]
+struct Spacing(HashMap<Source, Instant>);
+
+impl Spacing {
+    /// Reserve the next free slot of the service, returning when the message can be sent.
+    fn reserve(&mut self, source: Source, now: Instant) -> Instant {
+        let at = self.0.get(&source).map_or(now, |next| (*next).max(now));
+        self.0.insert(source, at + interval(source));
+        at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spacing() {
+        let mut spacing = Spacing::default();
+        let now = Instant::now();
+
+        assert_eq!(now, spacing.reserve(Source::Discord, now));
+        assert_eq!(
+            now + Duration::from_secs(1),
+            spacing.reserve(Source::Discord, now)
+        );
+        assert_eq!(now, spacing.reserve(Source::Twitch, now));
+
+        let later = now + Duration::from_secs(10);
+        assert_eq!(later, spacing.reserve(Source::Discord, later));
+    }
+
+    #[tokio::test]
+    async fn publish() {
+        let (outbox, mut outbound) = Outbox::new();
+        outbox
+            .publish(
+                Source::Twitch,
+                Post {
+                    channel: None,
+                    content: "hello".to_owned(),
+                },
+            )
+            .unwrap();
+
+        let (source, post) = outbound.recv().await.unwrap();
+        assert_eq!(Source::Twitch, source);
+        assert_eq!("hello", post.content);
+
+        drop(outbound);
+        assert!(outbox
+            .publish(
+                Source::Twitch,
+                Post {
+                    channel: None,
+                    content: "bye".to_owned(),
+                },
+            )
+            .is_err());
+    }
+}
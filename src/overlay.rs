@@ -0,0 +1,168 @@
+//! Live feed of bot events for stream overlays, like OBS browser sources.
+//!
+//! Events are serialized as JSON once and then broadcast to all connected overlays. Overlays that
+//! can't keep up miss the oldest events instead of slowing down the bot.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use serde::Serialize;
+use time::OffsetDateTime;
+use tokio::sync::broadcast;
+use tracing::warn;
+
+use crate::api::{Source, StreamInfo};
+
+/// Amount of events that are kept for overlays that are lagging behind.
+const CAPACITY: usize = 64;
+
+/// Sender for the overlay feed, that can be cloned and shared between tasks.
+#[derive(Clone)]
+pub struct Overlay {
+    tx: broadcast::Sender<Arc<str>>,
+    counters: Arc<Mutex<HashMap<String, u64>>>,
+}
+
+impl Default for Overlay {
+    fn default() -> Self {
+        Self {
+            tx: broadcast::channel(CAPACITY).0,
+            counters: Arc::default(),
+        }
+    }
+}
+
+impl Overlay {
+    /// Start receiving all events that are sent from now on, already serialized as JSON.
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<str>> {
+        self.tx.subscribe()
+    }
+
+    /// Send the event to all connected overlays. Nothing is serialized if no overlay is connected.
+    pub fn send(&self, event: Event) {
+        if self.tx.receiver_count() == 0 {
+            return;
+        }
+
+        match serde_json::to_string(&Payload {
+            timestamp: OffsetDateTime::now_utc(),
+            event,
+        }) {
+            Ok(payload) => {
+                self.tx.send(payload.into()).ok();
+            }
+            Err(e) => warn!(error = ?e, "failed serializing overlay event"),
+        }
+    }
+
+    /// Count the invocation of a command and announce it, together with the updated counter.
+    pub fn command(&self, source: Source, command: &str) {
+        let count = {
+            let mut counters = self.counters.lock().unwrap_or_else(PoisonError::into_inner);
+            let count = counters.entry(command.to_owned()).or_default();
+            *count += 1;
+            *count
+        };
+
+        self.send(Event::CommandExecuted {
+            source,
+            command: command.to_owned(),
+            count,
+        });
+    }
+}
+
+#[derive(Serialize)]
+struct Payload {
+    #[serde(with = "time::serde::rfc3339")]
+    timestamp: OffsetDateTime,
+    #[serde(flatten)]
+    event: Event,
+}
+
+/// An event that is sent to the overlays.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum Event {
+    /// A command was executed.
+    CommandExecuted {
+        /// Service the command came from.
+        source: Source,
+        /// Name of the command, without the `!` prefix.
+        command: String,
+        /// How often the command was executed since the bot started, including this time.
+        count: u64,
+    },
+    /// Someone new followed the channel.
+    Follow {
+        /// Service the follow happened on.
+        source: Source,
+        /// Display name of the new follower.
+        user: String,
+    },
+    /// The stream went online.
+    StreamOnline {
+        /// Title of the stream.
+        title: String,
+        /// Category (game) of the stream.
+        category: String,
+    },
+    /// The stream went offline.
+    StreamOffline,
+}
+
+impl Event {
+    /// Create the event for a started stream.
+    #[must_use]
+    pub fn stream_online(info: &StreamInfo) -> Self {
+        Self::StreamOnline {
+            title: info.title.clone(),
+            category: info.category.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn counts_commands() {
+        let overlay = Overlay::default();
+        let mut rx = overlay.subscribe();
+
+        overlay.command(Source::Twitch, "lurk");
+        overlay.command(Source::Discord, "lurk");
+
+        let mut last = serde_json::Value::Null;
+        while let Ok(payload) = rx.try_recv() {
+            last = serde_json::from_str(&payload).unwrap();
+        }
+
+        assert_eq!("command_executed", last["event"]);
+        assert_eq!("lurk", last["data"]["command"]);
+        assert_eq!(2, last["data"]["count"]);
+    }
+
+    #[test]
+    fn skips_without_overlays() {
+        let overlay = Overlay::default();
+        overlay.send(Event::StreamOffline);
+
+        let mut rx = overlay.subscribe();
+        overlay.send(Event::Follow {
+            source: Source::Twitch,
+            user: "someone".to_owned(),
+        });
+
+        let payload = serde_json::from_str::<serde_json::Value>(&rx.try_recv().unwrap()).unwrap();
+        assert_eq!("follow", payload["event"]);
+        assert_eq!("someone", payload["data"]["user"]);
+        assert!(rx.try_recv().is_err());
+    }
+}
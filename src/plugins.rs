@@ -0,0 +1,535 @@
+//! Plugin subsystem that allows to extend the bot with additional commands, without having to
+//! fork it.
+//!
+//! Plugins are registered dynamically at startup, either as external programs described in the
+//! settings, or as any custom type implementing the [`Plugin`] trait. Owners can load and unload
+//! registered plugins at runtime.
+//!
+//! Plugins are trusted like the bot itself. External programs run under the operating system
+//! limits of a [`Sandbox`], which keeps a faulty program from exhausting the host, writing files or
+//! reaching the network. It still runs as the bot's user and can read anything that the bot can
+//! read, so it isn't meant to contain malicious code.
+
+use std::{
+    fmt::{self, Display},
+    io,
+    path::PathBuf,
+    process::Stdio,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use anyhow::{bail, ensure, Context as _, Result};
+use futures_util::future::BoxFuture;
+use indexmap::IndexMap;
+use serde::Serialize;
+use tokio::{io::AsyncWriteExt, process::Command};
+use tracing::{info, warn};
+
+use crate::{
+    api::{AuthorId, Source},
+    settings,
+};
+
+/// A plugin provides one or more commands, that users can invoke like any other command.
+pub trait Plugin: Send + Sync {
+    /// List of command names (without the `!` prefix) that this plugin handles.
+    fn commands(&self) -> &[String];
+
+    /// Execute one of the plugin's commands and create the reply message.
+    fn execute<'a>(&'a self, ctx: Context<'a>) -> BoxFuture<'a, Result<String>>;
+}
+
+/// Limited context that is handed to a plugin when one of its commands is executed.
+#[derive(Clone, Copy, Serialize)]
+pub struct Context<'a> {
+    /// The name of the invoked command.
+    pub command: &'a str,
+    /// Arguments passed to the command, if any.
+    pub args: Option<&'a str>,
+    /// Author of the message that invoked the command.
+    pub author: &'a AuthorId,
+    /// Service the message came from.
+    pub source: Source,
+}
+
+/// Resource limits that are enforced for each plugin execution.
+#[derive(Clone, Copy, Debug)]
+pub struct Limits {
+    /// Maximum time a plugin may take to create its reply.
+    pub timeout: Duration,
+    /// Maximum length of the reply in bytes. Longer replies are truncated.
+    pub max_output: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(5),
+            max_output: 500,
+        }
+    }
+}
+
+/// Basic information about a registered plugin.
+#[derive(Debug, Serialize)]
+pub struct PluginInfo {
+    /// Unique name of the plugin.
+    pub name: String,
+    /// Commands provided by the plugin.
+    pub commands: Vec<String>,
+    /// Whether the plugin is currently loaded and its commands available.
+    pub loaded: bool,
+}
+
+/// Possible actions for plugin state changes.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginAction {
+    /// Plugin was loaded.
+    Loaded,
+    /// Plugin was unloaded.
+    Unloaded,
+}
+
+struct Entry {
+    plugin: Arc<dyn Plugin>,
+    limits: Limits,
+    loaded: bool,
+}
+
+/// Registry of all known plugins.
+#[derive(Default)]
+pub struct Plugins(Mutex<IndexMap<String, Entry>>);
+
+impl Plugins {
+    /// Create the registry from the plugins described in the settings.
+    pub fn from_settings(settings: &[settings::Plugin]) -> Result<Self> {
+        let plugins = Self::default();
+
+        for plugin in settings {
+            plugins.register(
+                plugin.name.clone(),
+                ProcessPlugin::new(
+                    plugin.path.clone(),
+                    plugin.commands.clone(),
+                    Sandbox {
+                        memory: plugin.memory.saturating_mul(1024 * 1024),
+                        cpu: Duration::from_secs(plugin.timeout),
+                        network: plugin.network,
+                    },
+                ),
+                Limits {
+                    timeout: Duration::from_secs(plugin.timeout),
+                    max_output: plugin.max_output,
+                },
+                plugin.load,
+            )?;
+        }
+
+        Ok(plugins)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, IndexMap<String, Entry>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Register a new plugin under the given name. Names as well as the commands of a plugin must
+    /// be unique among all plugins.
+    pub fn register(
+        &self,
+        name: String,
+        plugin: impl Plugin + 'static,
+        limits: Limits,
+        load: bool,
+    ) -> Result<()> {
+        let mut plugins = self.lock();
+
+        ensure!(
+            !plugins.contains_key(&name),
+            "plugin `{name}` is already registered"
+        );
+
+        for command in plugin.commands() {
+            if let Some(other) = plugins
+                .iter()
+                .find_map(|(other, e)| e.plugin.commands().contains(command).then_some(other))
+            {
+                bail!("command `{command}` of plugin `{name}` is already provided by `{other}`");
+            }
+        }
+
+        info!(%name, commands = ?plugin.commands(), "registered plugin");
+
+        plugins.insert(
+            name,
+            Entry {
+                plugin: Arc::new(plugin),
+                limits,
+                loaded: load,
+            },
+        );
+
+        Ok(())
+    }
+
+    /// Load or unload a plugin, making its commands available or unavailable to users.
+    pub fn set_loaded(&self, name: &str, loaded: bool) -> Result<PluginAction> {
+        let mut plugins = self.lock();
+        let entry = plugins
+            .get_mut(name)
+            .with_context(|| format!("plugin `{name}` doesn't exist"))?;
+
+        entry.loaded = loaded;
+
+        Ok(if loaded {
+            PluginAction::Loaded
+        } else {
+            PluginAction::Unloaded
+        })
+    }
+
+    /// List all registered plugins.
+    pub fn list(&self) -> Vec<PluginInfo> {
+        self.lock()
+            .iter()
+            .map(|(name, entry)| PluginInfo {
+                name: name.clone(),
+                commands: entry.plugin.commands().to_vec(),
+                loaded: entry.loaded,
+            })
+            .collect()
+    }
+
+    /// Execute the command, if any loaded plugin provides it. The execution is bound by the
+    /// plugin's resource limits.
+    pub async fn execute(&self, ctx: Context<'_>) -> Option<Result<String>> {
+        let (plugin, limits) = self.lock().values().find_map(|entry| {
+            (entry.loaded && entry.plugin.commands().iter().any(|c| c == ctx.command))
+                .then(|| (Arc::clone(&entry.plugin), entry.limits))
+        })?;
+
+        let res = tokio::time::timeout(limits.timeout, plugin.execute(ctx)).await;
+
+        Some(match res {
+            Ok(Ok(mut output)) => {
+                truncate(&mut output, limits.max_output);
+                Ok(output)
+            }
+            Ok(Err(e)) => Err(e),
+            Err(_) => {
+                warn!(command = ctx.command, ?limits.timeout, "plugin timed out");
+                Err(anyhow::anyhow!("plugin timed out"))
+            }
+        })
+    }
+}
+
+/// Cut off the string at the given maximum byte length, respecting char boundaries.
+fn truncate(value: &mut String, max: usize) {
+    if value.len() > max {
+        let end = (0..=max)
+            .rev()
+            .find(|&i| value.is_char_boundary(i))
+            .unwrap_or_default();
+        value.truncate(end);
+    }
+}
+
+/// Plugin that runs an external program for each command execution.
+///
+/// The program receives the invoked command as first argument, and the [`Context`] as JSON object
+/// on the standard input. Anything written to the standard output is used as reply.
+pub struct ProcessPlugin {
+    path: PathBuf,
+    commands: Vec<String>,
+    sandbox: Sandbox,
+}
+
+impl ProcessPlugin {
+    /// Create a new plugin for the program at the given path, that runs within the sandbox.
+    #[must_use]
+    pub fn new(path: PathBuf, commands: Vec<String>, sandbox: Sandbox) -> Self {
+        Self {
+            path,
+            commands,
+            sandbox,
+        }
+    }
+
+    async fn run(&self, ctx: Context<'_>) -> Result<String> {
+        let sandbox = self.sandbox;
+        let mut command = Command::new(&self.path);
+        command
+            .arg(ctx.command)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true);
+
+        // SAFETY: The sandbox only issues system calls, without allocating memory or taking locks,
+        // which is safe between forking and running the program.
+        unsafe {
+            command.pre_exec(move || sandbox.apply());
+        }
+
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("failed starting plugin at {}", self.path.display()))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(&serde_json::to_vec(&ctx)?).await?;
+        }
+
+        let output = child.wait_with_output().await?;
+        ensure!(
+            output.status.success(),
+            "plugin exited with {}",
+            output.status
+        );
+
+        Ok(String::from_utf8(output.stdout)
+            .context("plugin output is not valid UTF-8")?
+            .trim()
+            .to_owned())
+    }
+}
+
+impl Plugin for ProcessPlugin {
+    fn commands(&self) -> &[String] {
+        &self.commands
+    }
+
+    fn execute<'a>(&'a self, ctx: Context<'a>) -> BoxFuture<'a, Result<String>> {
+        Box::pin(self.run(ctx))
+    }
+}
+
+/// Operating system limits for the program of a [`ProcessPlugin`], that are set up right before
+/// it starts.
+#[derive(Clone, Copy, Debug)]
+pub struct Sandbox {
+    /// Maximum memory that the program may map, in bytes.
+    pub memory: u64,
+    /// Maximum CPU time of the program, in full seconds.
+    pub cpu: Duration,
+    /// Whether the program may access the network. Without it, the program runs in a network
+    /// namespace of its own, which needs unprivileged user namespaces.
+    pub network: bool,
+}
+
+impl Sandbox {
+    /// Restrict the current process, which is about to run the plugin's program. Any failure
+    /// keeps the program from running.
+    fn apply(self) -> io::Result<()> {
+        #[cfg(target_os = "linux")]
+        if !self.network {
+            // SAFETY: Only affects the current process, which is single-threaded after forking.
+            check(unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) })?;
+        }
+
+        let limits = [
+            (libc::RLIMIT_AS, self.memory),
+            (libc::RLIMIT_CPU, self.cpu.as_secs().max(1)),
+            // Writing to files, starting further processes and dumping the core are off limits.
+            (libc::RLIMIT_FSIZE, 0),
+            (libc::RLIMIT_NPROC, 0),
+            (libc::RLIMIT_CORE, 0),
+        ];
+
+        for (resource, value) in limits {
+            let limit = libc::rlimit {
+                rlim_cur: value,
+                rlim_max: value,
+            };
+            // SAFETY: The limit is a valid value that lives for the whole call.
+            check(unsafe { libc::setrlimit(resource, &raw const limit) })?;
+        }
+
+        #[cfg(target_os = "linux")]
+        deny_writes()?;
+
+        Ok(())
+    }
+}
+
+/// Deny any change to the filesystem through Landlock. Reading stays possible, as the program has
+/// to load its libraries. Kernels without Landlock are skipped, where the file size limit still
+/// keeps the content of files unchanged.
+#[cfg(target_os = "linux")]
+fn deny_writes() -> io::Result<()> {
+    /// Attribute of `landlock_create_ruleset`, with the access rights that the ruleset handles.
+    #[repr(C)]
+    struct RulesetAttr {
+        handled_access_fs: u64,
+    }
+
+    // Writing files, and removing or creating anything from `REMOVE_DIR` to `MAKE_SYM`.
+    const WRITE_ACCESS: u64 = (1 << 1) | (0x1ff << 4);
+
+    let attr = RulesetAttr {
+        handled_access_fs: WRITE_ACCESS,
+    };
+    // SAFETY: The attribute lives for the whole call, and its size is passed along.
+    let fd = unsafe {
+        libc::syscall(
+            libc::SYS_landlock_create_ruleset,
+            &raw const attr,
+            std::mem::size_of::<RulesetAttr>(),
+            0,
+        )
+    };
+    let Ok(fd) = libc::c_int::try_from(fd) else {
+        return Err(io::Error::from_raw_os_error(libc::EBADF));
+    };
+    if fd < 0 {
+        let e = io::Error::last_os_error();
+        return match e.raw_os_error() {
+            Some(libc::ENOSYS | libc::EOPNOTSUPP) => Ok(()),
+            _ => Err(e),
+        };
+    }
+
+    // SAFETY: Only affects the current process.
+    let res = unsafe {
+        match libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) {
+            0 => libc::syscall(libc::SYS_landlock_restrict_self, fd, 0),
+            res => res.into(),
+        }
+    };
+    let res = if res == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    };
+
+    // SAFETY: The ruleset isn't used anymore, once the process is restricted.
+    unsafe { libc::close(fd) };
+    res
+}
+
+/// Turn the result of a system call into an error, if it failed.
+fn check(res: libc::c_int) -> io::Result<()> {
+    if res == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+impl Display for PluginAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Loaded => "loaded",
+            Self::Unloaded => "unloaded",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZero;
+
+    use similar_asserts::assert_eq;
+
+    use super::*;
+
+    struct Echo(Vec<String>);
+
+    impl Plugin for Echo {
+        fn commands(&self) -> &[String] {
+            &self.0
+        }
+
+        fn execute<'a>(&'a self, ctx: Context<'a>) -> BoxFuture<'a, Result<String>> {
+            Box::pin(async move { Ok(ctx.args.unwrap_or_default().to_owned()) })
+        }
+    }
+
+    fn echo() -> Echo {
+        Echo(vec!["echo".to_owned()])
+    }
+
+    fn ctx<'a>(author: &'a AuthorId, args: &'a str) -> Context<'a> {
+        Context {
+            command: "echo",
+            args: Some(args),
+            author,
+            source: Source::Discord,
+        }
+    }
+
+    #[tokio::test]
+    async fn execute_loaded() {
+        let plugins = Plugins::default();
+        plugins
+            .register("echo".to_owned(), echo(), Limits::default(), true)
+            .unwrap();
+
+        let author = AuthorId::Discord(NonZero::new(1).unwrap());
+        let res = plugins.execute(ctx(&author, "hello")).await;
+        assert_eq!("hello", res.unwrap().unwrap());
+
+        plugins.set_loaded("echo", false).unwrap();
+        assert!(plugins.execute(ctx(&author, "hello")).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn truncate_output() {
+        let plugins = Plugins::default();
+        plugins
+            .register(
+                "echo".to_owned(),
+                echo(),
+                Limits {
+                    max_output: 4,
+                    ..Limits::default()
+                },
+                true,
+            )
+            .unwrap();
+
+        let author = AuthorId::Discord(NonZero::new(1).unwrap());
+        let res = plugins.execute(ctx(&author, "abcä")).await;
+        assert_eq!("abc", res.unwrap().unwrap());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn process_limits() {
+        use std::{env, fs, os::unix::fs::PermissionsExt};
+
+        let dir = env::temp_dir().join(format!("togglebot-plugin-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("limits.sh");
+        fs::write(&path, "#!/bin/sh\nread -r _\nulimit -v\nulimit -f\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+
+        let plugin = ProcessPlugin::new(
+            path,
+            vec!["echo".to_owned()],
+            Sandbox {
+                memory: 64 * 1024 * 1024,
+                cpu: Duration::from_secs(1),
+                // Network namespaces need user namespaces, which aren't available everywhere.
+                network: true,
+            },
+        );
+        let res = plugin.execute(ctx(&AuthorId::Console, "")).await;
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!("65536\n0", res.unwrap());
+    }
+
+    #[test]
+    fn duplicate_commands() {
+        let plugins = Plugins::default();
+        plugins
+            .register("a".to_owned(), echo(), Limits::default(), true)
+            .unwrap();
+
+        assert!(plugins
+            .register("b".to_owned(), echo(), Limits::default(), true)
+            .is_err());
+    }
+}
@@ -0,0 +1,1386 @@
+//! Rendering of responses as Discord messages, making use of Markdown, mentions and embeds.
+
+use std::{collections::BTreeMap, fmt::Write, num::NonZero, time::Duration};
+
+use anyhow::Result;
+use indexmap::IndexMap;
+use poise::serenity_prelude::CreateEmbed;
+use serde::Serialize;
+use time::{format_description::FormatItem, macros::format_description, UtcOffset};
+use tracing::{error, warn};
+
+use super::QUEUE_PREVIEW;
+use crate::{
+    api::{
+        request::StatisticsDate,
+        response::{
+            self, AdminAction, CommandHelp, CommandRevision, CrateSearch, CustomCommandEntry,
+            DeadLink, Eval, Faq, LinkOrigin, MassMention, QueuedSong, RustVersions, StreamChapters,
+            Trigger, UserNote, VersionSearch, WatchedCrate,
+        },
+        AdminId, Availability, Highlight, Source, UserRef,
+    },
+    chart,
+    commands::{self, CommandInfo},
+    emojis,
+    handler::{Access, CONFIRM_TIMEOUT},
+    plugins::PluginInfo,
+    statistics::{BuiltinCommand, CommandUsage, Statistics},
+    timing::{Histogram, Stage},
+};
+
+/// Gandalf's famous "You shall not pass!" scene.
+const GANDALF_GIF: &str =
+    "https://tenor.com/view/you-shall-not-pass-lotr-do-not-enter-not-allowed-scream-gif-16729885";
+
+/// Amount of commands that are shown in the statistics chart.
+const CHART_BARS: usize = 20;
+/// Maximum message length in Discord, minus some room for the code block.
+const MAX_CODE_LEN: usize = 1900;
+
+/// A rendered message, together with the details about how it must be sent.
+#[derive(Debug, Serialize)]
+pub struct Reply {
+    /// Text content of the message.
+    pub content: String,
+    /// Rich content, shown below the text.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embed: Option<CreateEmbed>,
+    /// File that is sent along with the message, like an image.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachment: Option<Attachment>,
+    /// Whether mentioned users are notified about the message.
+    pub notify: bool,
+    /// Where the message is sent to.
+    pub delivery: Delivery,
+    /// Whether the message comes with a button to confirm a pending command.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub confirm: bool,
+}
+
+impl Reply {
+    fn new(content: impl Into<String>) -> Self {
+        Self {
+            content: content.into(),
+            embed: None,
+            attachment: None,
+            notify: true,
+            delivery: Delivery::Channel,
+            confirm: false,
+        }
+    }
+
+    fn embed(embed: CreateEmbed) -> Self {
+        Self {
+            embed: Some(embed),
+            ..Self::new(String::new())
+        }
+    }
+
+    fn attachment(self, name: impl Into<String>, data: Vec<u8>) -> Self {
+        Self {
+            attachment: Some(Attachment {
+                name: name.into(),
+                data,
+            }),
+            ..self
+        }
+    }
+
+    fn silent(self) -> Self {
+        Self {
+            notify: false,
+            ..self
+        }
+    }
+
+    fn private(self) -> Self {
+        Self {
+            delivery: Delivery::Private,
+            ..self
+        }
+    }
+}
+
+/// File that is attached to a reply.
+#[derive(Debug, Serialize)]
+pub struct Attachment {
+    /// File name, which also tells Discord how to show the file.
+    pub name: String,
+    /// Content of the file.
+    #[serde(skip)]
+    pub data: Vec<u8>,
+}
+
+/// The ways a reply can be delivered.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub enum Delivery {
+    /// As reply to the command, visible to everyone in the channel.
+    #[default]
+    Channel,
+    /// As reply to the command, that only the author of the command can see.
+    Private,
+    /// As direct message to the author of the command, confirming the delivery with a private
+    /// reply.
+    Direct,
+}
+
+/// Render a user response, or nothing if there is nothing to reply.
+pub fn user(resp: response::User, streamer: &str) -> Result<Option<Reply>> {
+    Ok(Some(match resp {
+        response::User::Help(text) => Reply::new(text),
+        response::User::CommandHelp(res) => Reply::new(command_help(res)),
+        response::User::Commands { builtin, custom } => Reply::new(commands(&builtin, custom)),
+        response::User::Links(links) => Reply::new(links_list(&links)),
+        response::User::Ban(message) => Reply::new(format!("**{message}**\n\n{GANDALF_GIF}")),
+        response::User::Crate(res) => crate_(res)?,
+        response::User::RustVersions(res) => Reply::new(rust_versions(res)),
+        response::User::Eval(res) => Reply::new(eval(res)),
+        response::User::CrateCompare(res) => crate_compare(res)?,
+        response::User::CrateVersion(res) => crate_version(res)?,
+        response::User::CrateFeatures(res) => crate_features(res),
+        response::User::Status(status) => Reply::new(status_(status, streamer)?),
+        response::User::BotStat(stat) => Reply::new(super::botstat(&stat)),
+        response::User::SongRequest(res) => Reply::new(super::song_request(res)),
+        response::User::SongQueue(res) => Reply::new(song_queue(res)).silent(),
+        response::User::Points(res) => Reply::new(super::points(res)),
+        response::User::Gamble(res) => Reply::new(super::gamble(res)),
+        response::User::Duel(res) => Reply::new(super::duel(res)),
+        response::User::Poll(res) => Reply::new(super::poll(res)),
+        response::User::NotifyMe(res) => Reply::new(super::notify_me(res)),
+        response::User::WatchTime(res) => Reply::new(super::watch_time(res)),
+        response::User::Yesterday(res) => Reply::new(super::yesterday(res)),
+        response::User::LastCategory(res) => Reply::new(super::last_category(res)),
+        response::User::Suggest(res) => Reply::new(super::suggest(res)),
+        response::User::Topic(res) => Reply::new(super::topic(res)),
+        response::User::Birthday(res) => Reply::new(super::birthday(res)),
+        response::User::Fun { kind, content } => Reply::new(super::fun(kind, content)),
+        response::User::Away { streamer, message } => Reply::new(super::away(&streamer, &message)),
+        response::User::Trigger(Some(content)) | response::User::Faq(content) => {
+            Reply::new(content).silent()
+        }
+        response::User::Pronouns(res) => Reply::new(super::pronouns(res)),
+        response::User::Prefs(res) => Reply::new(super::prefs(res)),
+        response::User::Lurk(res) => Reply::new(super::lurk(res)),
+        response::User::Highlights(res) => Reply::new(highlights(res)),
+        response::User::NotEnoughPoints { cost, balance } => {
+            Reply::new(super::not_enough_points(cost, balance))
+        }
+        response::User::Today(content)
+        | response::User::FahrenheitToCelsius(content)
+        | response::User::CelsiusToFahrenheit(content) => Reply::new(content),
+        response::User::Custom(res) => match res {
+            Ok(content) => Reply::new(content),
+            Err(e) => {
+                error!(error = ?e, "failed finding custom command");
+                return Ok(None);
+            }
+        },
+        response::User::TimeoutMe
+        | response::User::FollowAge
+        | response::User::AccountAge
+        | response::User::TimeoutSpammer(_)
+        | response::User::Trigger(None)
+        | response::User::Unknown => return Ok(None),
+    }))
+}
+
+fn command_help(res: Result<Option<CommandHelp>>) -> String {
+    let help = match res {
+        Ok(Some(help)) => help,
+        Ok(None) => return "There is no command with that name".to_owned(),
+        Err(e) => {
+            error!(error = ?e, "failed getting command details");
+            return "Sorry, something went wrong looking up the command".to_owned();
+        }
+    };
+
+    let mut text = format!("**!{}**", help.name);
+    match help.info {
+        Some(info) => {
+            if !info.aliases.is_empty() {
+                let _ = write!(text, " (or `!{}`)", info.aliases.join("`, `!"));
+            }
+            let _ = write!(text, "\n{}\n\n**Usage**\n", info.description);
+            for (form, _) in info.forms() {
+                let _ = writeln!(text, "`{form}`");
+            }
+            if !info.examples.is_empty() {
+                text.push_str("\n**Examples**\n");
+                for example in info.examples {
+                    let _ = writeln!(text, "`{example}`");
+                }
+            }
+        }
+        None => text.push_str("\nCustom command\n"),
+    }
+
+    let _ = write!(
+        text,
+        "\n**Permission:** {}\n**Services:** {}",
+        super::permission(help.info),
+        super::sources(&help.sources)
+    );
+    if let Some(cost) = help.cost {
+        let _ = write!(text, "\n**Cost:** {cost} points");
+    }
+    if help.availability != Availability::Always {
+        let _ = write!(text, "\n**Availability:** {}", help.availability);
+    }
+    if let Some((uses, window)) = help.cooldown {
+        let _ = write!(
+            text,
+            "\n**Cooldown:** answered {uses} times per {} at most",
+            super::poll_duration(window)
+        );
+    }
+
+    text
+}
+
+/// Text of the command list that is kept pinned in a channel, from the response of `!commands`.
+#[must_use]
+pub fn command_list(resp: response::User) -> Option<String> {
+    match resp {
+        response::User::Commands { builtin, custom } => Some(commands(&builtin, custom)),
+        _ => None,
+    }
+}
+
+fn commands(builtin: &[&CommandInfo], custom: Result<Vec<String>>) -> String {
+    let custom = match custom {
+        Ok(custom) => custom,
+        Err(e) => {
+            error!(error = ?e, "failed listing commands");
+            return "Sorry, something went wrong fetching the list of commands".to_owned();
+        }
+    };
+
+    let mut list = String::from("Available commands:\n");
+    for cmd in builtin {
+        for (i, (form, description)) in cmd.forms().enumerate() {
+            let _ = write!(list, "`{form}`");
+            if i == 0 && !cmd.aliases.is_empty() {
+                let _ = write!(list, " (or `!{}`)", cmd.aliases.join("`, `!"));
+            }
+            let _ = writeln!(list, ": {description}");
+        }
+    }
+
+    list.push_str("\nFurther custom commands:\n");
+    for (i, name) in custom.into_iter().enumerate() {
+        if i > 0 {
+            list.push_str(", ");
+        }
+        let _ = write!(list, "`!{name}`");
+    }
+
+    list
+}
+
+/// Overview of all commands of an access level, with a block for each form.
+fn access_help(kind: &str, access: Access) -> String {
+    let mut message = format!("Hey there, I support the following {kind} commands:\n");
+    for cmd in commands::listed(access) {
+        for (form, description) in cmd.forms() {
+            let _ = write!(message, "\n```\n{form}\n```\n{description}\n");
+        }
+    }
+
+    message
+}
+
+pub(super) fn links_list(links: &response::Links) -> String {
+    super::links_list(links, "\n", |name, url| format!("{name}: <{url}>"))
+}
+
+fn crate_(res: Result<CrateSearch>) -> Result<Reply> {
+    const FORMAT: &[FormatItem<'static>] =
+        format_description!("[year]-[month]-[day] [hour]:[minute] UTC");
+
+    Ok(match res {
+        Ok(CrateSearch::Found(info)) => {
+            let mut embed = CreateEmbed::new()
+                .title(format!("{} (v{})", info.name, info.newest_version))
+                .description(&info.description)
+                .field(
+                    "Last update",
+                    info.updated_at.to_offset(UtcOffset::UTC).format(&FORMAT)?,
+                    true,
+                )
+                .field("Downloads", downloads(info.downloads), true)
+                .field("Documentation", info.docs_link(), true)
+                .field("Repository", &info.repository, true)
+                .field(
+                    "More information",
+                    format!("https://crates.io/crates/{0}", info.name),
+                    true,
+                );
+
+            if let Some(dependents) = &info.dependents {
+                embed = embed.field("Dependents", dependents.total.to_string(), true);
+                if !dependents.top.is_empty() {
+                    let top = dependents
+                        .top
+                        .iter()
+                        .map(|name| format!("[{name}](https://crates.io/crates/{name})"))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    embed = embed.field("Top dependents", top, false);
+                }
+            }
+
+            Reply::embed(embed)
+        }
+        Ok(CrateSearch::NotFound(message)) => Reply::new(message),
+        Ok(CrateSearch::Fallback { name, version }) => Reply::embed(
+            CreateEmbed::new()
+                .title(format!("{name} (v{version})"))
+                .description(
+                    "crates.io is unavailable right now, so only the latest version is known",
+                )
+                .field(
+                    "Documentation",
+                    format!("https://docs.rs/{name}/{version}"),
+                    true,
+                ),
+        ),
+        Err(e) => {
+            error!(error = ?e, "failed searching for crate");
+            Reply::new("Sorry, something went wrong looking up the crate")
+        }
+    })
+}
+
+fn crate_compare(res: Result<(CrateSearch, CrateSearch)>) -> Result<Reply> {
+    const FORMAT: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
+
+    Ok(match res {
+        Ok((CrateSearch::Found(first), CrateSearch::Found(second))) => {
+            let mut embed = CreateEmbed::new().title(format!("{} vs. {}", first.name, second.name));
+
+            for info in [first, second] {
+                embed = embed.field(
+                    format!("{} (v{})", info.name, info.newest_version),
+                    format!(
+                        "**Downloads:** {}\n**Last update:** {}\n**Versions:** {}\n**Docs:** {}",
+                        downloads(info.downloads),
+                        info.updated_at.to_offset(UtcOffset::UTC).format(&FORMAT)?,
+                        info.version_count,
+                        info.docs_link(),
+                    ),
+                    true,
+                );
+            }
+
+            Reply::embed(embed)
+        }
+        Ok((CrateSearch::NotFound(message), _) | (_, CrateSearch::NotFound(message))) => {
+            Reply::new(message)
+        }
+        Ok((CrateSearch::Fallback { .. }, _) | (_, CrateSearch::Fallback { .. })) => {
+            Reply::new(super::CRATES_IO_UNAVAILABLE)
+        }
+        Err(e) => {
+            error!(error = ?e, "failed searching for crates");
+            Reply::new("Sorry, something went wrong looking up the crates")
+        }
+    })
+}
+
+fn crate_version(res: Result<VersionSearch>) -> Result<Reply> {
+    const FORMAT: &[FormatItem<'static>] =
+        format_description!("[year]-[month]-[day] [hour]:[minute] UTC");
+
+    Ok(match res {
+        Ok(VersionSearch::Found(info)) => {
+            let mut embed = CreateEmbed::new()
+                .title(format!("{} (v{})", info.name, info.num))
+                .field(
+                    "Released",
+                    info.created_at.to_offset(UtcOffset::UTC).format(&FORMAT)?,
+                    true,
+                )
+                .field("Downloads", downloads(info.downloads), true);
+
+            if let Some(license) = &info.license {
+                embed = embed.field("License", license, true);
+            }
+            if let Some(rust_version) = &info.rust_version {
+                embed = embed.field("Rust version", rust_version, true);
+            }
+            if info.yanked {
+                embed = embed.description("⚠️ This version was yanked");
+            }
+
+            Reply::embed(embed.field("More information", info.link(), true))
+        }
+        Ok(VersionSearch::NotFound(message)) => Reply::new(message),
+        Err(e) => {
+            error!(error = ?e, "failed searching for crate version");
+            Reply::new("Sorry, something went wrong looking up the crate")
+        }
+    })
+}
+
+fn crate_features(res: Result<VersionSearch>) -> Reply {
+    match res {
+        Ok(VersionSearch::Found(info)) if info.features.keys().all(|name| name == "default") => {
+            Reply::new(format!("{} v{} has no feature flags", info.name, info.num))
+        }
+        Ok(VersionSearch::Found(info)) => {
+            let list = info
+                .features
+                .iter()
+                .fold(String::new(), |mut list, (name, enables)| {
+                    if enables.is_empty() {
+                        writeln!(list, "`{name}`").ok();
+                    } else {
+                        writeln!(list, "`{name}`: {}", enables.join(", ")).ok();
+                    }
+                    list
+                });
+
+            Reply::embed(
+                CreateEmbed::new()
+                    .title(format!("Features of {} (v{})", info.name, info.num))
+                    .url(info.features_link())
+                    .description(super::truncate(&list, 4000)),
+            )
+        }
+        Ok(VersionSearch::NotFound(message)) => Reply::new(message),
+        Err(e) => {
+            error!(error = ?e, "failed searching for crate features");
+            Reply::new("Sorry, something went wrong looking up the crate")
+        }
+    }
+}
+
+/// Shorten large download counts, to make them easier to read.
+fn downloads(count: u64) -> String {
+    if count > 1_000_000 {
+        format!("{}+M", count / 1_000_000)
+    } else if count > 1_000 {
+        format!("{}+k", count / 1_000)
+    } else {
+        count.to_string()
+    }
+}
+
+fn song_queue(res: Result<Vec<QueuedSong>>) -> String {
+    match res {
+        Ok(songs) if songs.is_empty() => {
+            "The song queue is empty, request a song with `!sr <link>`".to_owned()
+        }
+        Ok(songs) => {
+            let mut message = songs.iter().take(QUEUE_PREVIEW).enumerate().fold(
+                String::from("**Songs in the queue:**"),
+                |mut list, (i, song)| {
+                    write!(
+                        list,
+                        "\n{}. <{}> ({}, requested {})",
+                        i + 1,
+                        song.url,
+                        song.platform.as_ref(),
+                        requester(song)
+                    )
+                    .ok();
+                    list
+                },
+            );
+
+            if songs.len() > QUEUE_PREVIEW {
+                write!(message, "\n_...and {} more_", songs.len() - QUEUE_PREVIEW).ok();
+            }
+
+            message
+        }
+        Err(e) => {
+            error!(error = ?e, "failed listing song requests");
+            "Sorry, something went wrong fetching the song queue".to_owned()
+        }
+    }
+}
+
+/// Mention the requester of a song, if they're a Discord user, or name their service otherwise.
+fn requester(song: &QueuedSong) -> String {
+    match song.source {
+        Source::Discord => format!("by <@{}>", song.user_id),
+        source => format!("on {source}"),
+    }
+}
+
+fn status_(status: response::Status, streamer: &str) -> Result<String> {
+    const FORMAT: &[FormatItem<'static>] = format_description!("[hour]:[minute] UTC");
+
+    let mut message = match status.stream {
+        Ok(Some(info)) => format!(
+            "I'm up and running! **{streamer}** is live since {}, streaming _{}_ ({}).",
+            info.started_at.to_offset(UtcOffset::UTC).format(&FORMAT)?,
+            info.title,
+            info.category,
+        ),
+        Ok(None) => format!("I'm up and running! **{streamer}** is currently offline."),
+        Err(e) => {
+            error!(error = ?e, "failed getting stream status");
+            "Sorry, something went wrong fetching the status".to_owned()
+        }
+    };
+
+    for connector in status.connectors {
+        match (connector.stale, connector.silent_secs) {
+            (false, _) => write!(message, "\n{} {} is OK", emojis::OK_HAND, connector.source),
+            (true, Some(secs)) => write!(
+                message,
+                "\n{} {} is connected, but silent for {secs}s",
+                emojis::COLLISION,
+                connector.source
+            ),
+            (true, None) => write!(
+                message,
+                "\n{} {} didn't receive anything yet",
+                emojis::COLLISION,
+                connector.source
+            ),
+        }?;
+    }
+
+    Ok(message)
+}
+
+/// Render an admin response.
+///
+/// User notes are private, so they're never posted in a channel.
+#[must_use]
+pub fn admin(resp: response::Admin) -> Reply {
+    match resp {
+        response::Admin::Help => Reply::new(access_help("admin", Access::Admin)),
+        response::Admin::CustomCommands(response::CustomCommands::List(res)) => {
+            Reply::new(custom_commands(res))
+        }
+        response::Admin::CustomCommands(response::CustomCommands::Show(name, res)) => {
+            Reply::new(custom_command(&name, res))
+        }
+        response::Admin::CustomCommands(response::CustomCommands::History(name, res)) => {
+            Reply::new(command_history(&name, res))
+        }
+        response::Admin::CustomCommands(response::CustomCommands::Edit(res)) => {
+            Reply::new(custom_commands_edit(res))
+        }
+        response::Admin::Statistics(date, stats) => statistics(date, stats),
+        response::Admin::StreamTag(res) => Reply::new(done(res, "stream tagged")),
+        response::Admin::Timing(report) => Reply::new(timing(report)),
+        response::Admin::UserNotes(resp) => match resp {
+            response::UserNotes::List(user, Ok(notes)) => Reply {
+                delivery: Delivery::Direct,
+                ..Reply::new(user_notes(&user, notes)).silent()
+            },
+            response::UserNotes::List(_, Err(e)) => Reply::new(failed(&e)).private(),
+            response::UserNotes::Add(res) => match res {
+                Ok(id) => Reply::new(format!("{} note #{id} added", emojis::OK_HAND)).private(),
+                Err(e) => Reply::new(failed(&e)).private(),
+            },
+            response::UserNotes::Remove(res) => Reply::new(done(res, "note removed")).private(),
+        },
+        response::Admin::Away(res) => Reply::new(done(res, "the streamer is away now")),
+        response::Admin::Back(res) => Reply::new(match res {
+            Ok(Some(duration)) => format!(
+                "{} welcome back, you were away for {}",
+                emojis::OK_HAND,
+                super::uptime(duration)
+            ),
+            Ok(None) => "the streamer wasn't away".to_owned(),
+            Err(e) => failed(&e),
+        }),
+        response::Admin::NothingToConfirm => Reply::new(format!(
+            "{} there's nothing to confirm, or it took longer than {} seconds",
+            emojis::COLLISION,
+            CONFIRM_TIMEOUT.as_secs()
+        ))
+        .private(),
+        response::Admin::LinkPreview { source, links } => Reply::new(links_preview(source, &links)),
+        response::Admin::CheckLinks(res) => Reply::new(dead_links(res)),
+        response::Admin::Chapters(res) => chapters(res),
+        response::Admin::Suggestions(resp) => Reply::new(suggestions(resp)),
+        response::Admin::Issue(res) => Reply::new(issue(res)),
+        response::Admin::Topic(res) => Reply::new(topic_update(res)),
+        response::Admin::Shoutout(res) => Reply::new(match res {
+            Ok(true) => "raiding channels get a shoutout".to_owned(),
+            Ok(false) => "raiding channels don't get a shoutout".to_owned(),
+            Err(e) => failed(&e),
+        }),
+        response::Admin::SkipSong(res) => Reply::new(match res {
+            Ok(Some(song)) => format!("{} skipped <{}>", emojis::OK_HAND, song.url),
+            Ok(None) => "the song queue is empty".to_owned(),
+            Err(e) => failed(&e),
+        }),
+        response::Admin::CommandCosts(response::CommandCosts::List(res)) => {
+            Reply::new(command_costs(res))
+        }
+        response::Admin::CommandCosts(response::CommandCosts::Edit(res)) => {
+            Reply::new(done(res, "command cost updated"))
+        }
+        response::Admin::CommandAvailability(response::CommandAvailability::List(res)) => {
+            Reply::new(command_availability(res))
+        }
+        response::Admin::CommandAvailability(response::CommandAvailability::Edit(res)) => {
+            Reply::new(done(res, "command availability updated"))
+        }
+        response::Admin::UsageAlerts(response::UsageAlerts::List(res)) => {
+            Reply::new(usage_alerts(res))
+        }
+        response::Admin::UsageAlerts(response::UsageAlerts::Edit(res)) => {
+            Reply::new(done(res, "usage alert updated"))
+        }
+        response::Admin::Triggers(response::Triggers::List(res)) => Reply::new(triggers(res)),
+        response::Admin::Triggers(response::Triggers::Edit(res)) => {
+            Reply::new(done(res, "trigger word updated"))
+        }
+        response::Admin::Faqs(response::Faqs::List(res)) => Reply::new(faqs(res)),
+        response::Admin::Faqs(response::Faqs::Edit(res)) => {
+            Reply::new(done(res, "FAQ entry updated"))
+        }
+        response::Admin::WatchCrates(resp) => Reply::new(watch_crates(resp)),
+        response::Admin::Channels(resp) => Reply::new(channels(resp)),
+        response::Admin::Silence(res) => Reply::new(silence(res)),
+        response::Admin::CommandList(res) => Reply::new(command_list_moved(res)),
+        response::Admin::Pin(res) => Reply::new(pinned(res)).private(),
+    }
+}
+
+fn custom_commands(res: Result<BTreeMap<String, Vec<CustomCommandEntry>>>) -> String {
+    match res {
+        Ok(list) => list.into_iter().fold(
+            String::from("available custom commands:"),
+            |mut list, (name, entries)| {
+                let sources = entries
+                    .iter()
+                    .map(|entry| entry.source.as_ref())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(&mut list, "\n`!{name}` ({sources})").ok();
+                if let Some(creator) = super::command_creator(&entries) {
+                    write!(&mut list, " by {}", display_user(creator)).ok();
+                }
+                list
+            },
+        ),
+        Err(e) => failed(&e),
+    }
+}
+
+fn custom_command(name: &str, res: Result<Vec<CustomCommandEntry>>) -> String {
+    match res {
+        Ok(entries) if entries.is_empty() => {
+            format!("there is no custom command named `!{name}`")
+        }
+        Ok(entries) => {
+            entries
+                .into_iter()
+                .fold(format!("details of `!{name}`:"), |mut list, entry| {
+                    write!(
+                        &mut list,
+                        "\n**{}**: `{}`\n",
+                        entry.source.as_ref(),
+                        entry.content
+                    )
+                    .ok();
+                    match (entry.created_by, entry.created_at) {
+                        (Some(user), Some(at)) => write!(
+                            &mut list,
+                            "created by {} on <t:{}:f>",
+                            display_user(&user),
+                            at.unix_timestamp()
+                        ),
+                        _ => write!(&mut list, "created before creators were recorded"),
+                    }
+                    .ok();
+                    if let (Some(user), Some(at)) = (entry.updated_by, entry.updated_at) {
+                        write!(
+                            &mut list,
+                            ", last changed by {} on <t:{}:f>",
+                            display_user(&user),
+                            at.unix_timestamp()
+                        )
+                        .ok();
+                    }
+                    list
+                })
+        }
+        Err(e) => failed(&e),
+    }
+}
+
+fn command_history(name: &str, res: Result<Vec<CommandRevision>>) -> String {
+    match res {
+        Ok(revisions) if revisions.is_empty() => {
+            format!("there are no recorded changes for `!{name}`")
+        }
+        Ok(revisions) => {
+            revisions
+                .into_iter()
+                .fold(format!("recent changes to `!{name}`:"), |mut list, rev| {
+                    write!(
+                        &mut list,
+                        "\n**#{}** {} by {} on <t:{}:f>: ",
+                        rev.id,
+                        rev.source.as_ref(),
+                        display_user(&rev.author),
+                        rev.changed_at.unix_timestamp(),
+                    )
+                    .ok();
+                    match rev.content {
+                        Some(content) => write!(&mut list, "`{content}`").ok(),
+                        None => write!(&mut list, "_removed_").ok(),
+                    };
+                    list
+                })
+        }
+        Err(e) => failed(&e),
+    }
+}
+
+/// Output of a Rust snippet in a code block, which is cut off to stay within the message limit.
+fn eval(res: Result<Eval>) -> String {
+    super::eval(res, |success, output| {
+        let icon = if success { '✅' } else { '❌' };
+        if output.is_empty() {
+            return format!("{icon} (no output)");
+        }
+
+        // Keep the output from closing the code block early.
+        let output = output.replace("```", "`\u{200b}``");
+        format!(
+            "{icon}\n```\n{}\n```",
+            super::truncate(&output, MAX_CODE_LEN)
+        )
+    })
+}
+
+/// Current Rust versions, one release channel per line.
+fn rust_versions(res: Result<RustVersions>) -> String {
+    match res {
+        Ok(versions) => [
+            ("stable", versions.stable),
+            ("beta", versions.beta),
+            ("nightly", versions.nightly),
+        ]
+        .into_iter()
+        .fold(
+            String::from("Current Rust versions:"),
+            |mut list, (channel, release)| {
+                write!(
+                    &mut list,
+                    "\n**{channel}**: `{}` ({})",
+                    release.version, release.date
+                )
+                .ok();
+                list
+            },
+        ),
+        res => super::rust_versions(res),
+    }
+}
+
+/// Recently pinned highlights, one per line. The links are wrapped in angle brackets, so Discord
+/// doesn't add a preview for each of them.
+fn highlights(res: Result<Vec<Highlight>>) -> String {
+    match res {
+        Ok(highlights) if !highlights.is_empty() => highlights.into_iter().fold(
+            String::from("Recent highlights:"),
+            |mut list, highlight| {
+                write!(
+                    &mut list,
+                    "\n**{}**: {} <{}>",
+                    highlight.author,
+                    super::preview(&highlight.content),
+                    highlight.link()
+                )
+                .ok();
+                list
+            },
+        ),
+        res => super::highlights(res),
+    }
+}
+
+fn pinned(res: Result<Highlight>) -> String {
+    match res {
+        Ok(highlight) => format!(
+            "{} pinned the message of **{}** as highlight",
+            emojis::PUSHPIN,
+            highlight.author
+        ),
+        Err(e) => failed(&e),
+    }
+}
+
+fn watch_crates(resp: response::WatchCrates) -> String {
+    match resp {
+        response::WatchCrates::List(Ok(crates)) if crates.is_empty() => {
+            "there are no watched crates".to_owned()
+        }
+        response::WatchCrates::List(Ok(crates)) => crates.into_iter().fold(
+            String::from("watched crates:"),
+            |mut list, WatchedCrate { name, version, .. }| {
+                write!(&mut list, "\n`{name}` (latest {version})").ok();
+                list
+            },
+        ),
+        response::WatchCrates::Add(Ok(WatchedCrate { name, version, .. })) => done(
+            Ok(()),
+            &format!("watching `{name}` now, currently at {version}"),
+        ),
+        response::WatchCrates::Remove(Ok(())) => done(Ok(()), "crate no longer watched"),
+        response::WatchCrates::List(Err(e))
+        | response::WatchCrates::Add(Err(e))
+        | response::WatchCrates::Remove(Err(e)) => failed(&e),
+    }
+}
+
+fn channels(resp: response::Channels) -> String {
+    match resp {
+        response::Channels::List(Ok(rules)) if rules.is_empty() => {
+            "user commands work in all channels".to_owned()
+        }
+        response::Channels::List(Ok(rules)) => rules.into_iter().fold(
+            String::from("channel rules for user commands:"),
+            |mut list, (channel, rule)| {
+                write!(&mut list, "\n<#{channel}>: {rule}").ok();
+                list
+            },
+        ),
+        response::Channels::List(Err(e)) => failed(&e),
+        response::Channels::Edit(res) => done(res, "channel rules updated"),
+    }
+}
+
+fn silence(res: Result<Option<Duration>>) -> String {
+    match res {
+        Ok(Some(duration)) => format!(
+            "{} replies are silenced for {}",
+            emojis::OK_HAND,
+            super::uptime(duration)
+        ),
+        Ok(None) => format!("{} replies are back on", emojis::OK_HAND),
+        Err(e) => failed(&e),
+    }
+}
+
+/// The links of another service in a code block, so Discord doesn't turn them into embeds.
+fn links_preview(source: Source, links: &response::Links) -> String {
+    if links.links.is_empty() {
+        return format!("{} no links are shown on {source}", emojis::COLLISION);
+    }
+
+    format!(
+        "The links on {source} look like this:\n```\n{}\n```",
+        super::link_preview(source, links)
+    )
+}
+
+fn command_list_moved(res: Result<Option<NonZero<u64>>>) -> String {
+    match res {
+        Ok(Some(channel)) => format!(
+            "{} the command list is kept pinned in <#{channel}>",
+            emojis::OK_HAND
+        ),
+        Ok(None) => format!("{} the command list isn't updated anymore", emojis::OK_HAND),
+        Err(e) => failed(&e),
+    }
+}
+
+fn faqs(res: Result<Vec<Faq>>) -> String {
+    match res {
+        Ok(faqs) if faqs.is_empty() => "there are no FAQ entries".to_owned(),
+        Ok(faqs) => faqs
+            .into_iter()
+            .fold(String::from("FAQ entries:"), |mut list, faq| {
+                let kind = if faq.regex { "regex" } else { "keywords" };
+                write!(
+                    &mut list,
+                    "\n`{}` ({kind} `{}`): {}",
+                    faq.name, faq.pattern, faq.answer
+                )
+                .ok();
+                list
+            }),
+        Err(e) => failed(&e),
+    }
+}
+
+fn triggers(res: Result<Vec<Trigger>>) -> String {
+    match res {
+        Ok(triggers) if triggers.is_empty() => "there are no trigger words".to_owned(),
+        Ok(triggers) => {
+            triggers
+                .into_iter()
+                .fold(String::from("trigger words:"), |mut list, trigger| {
+                    write!(
+                        &mut list,
+                        "\n`{}` (cooldown {}s",
+                        trigger.word, trigger.cooldown
+                    )
+                    .ok();
+                    for source in trigger.disabled {
+                        write!(&mut list, ", off on {source}").ok();
+                    }
+                    write!(&mut list, "): {}", trigger.content).ok();
+                    list
+                })
+        }
+        Err(e) => failed(&e),
+    }
+}
+
+fn command_availability(res: Result<Vec<(String, Availability)>>) -> String {
+    match res {
+        Ok(list) if list.is_empty() => "all commands are always available".to_owned(),
+        Ok(list) => list.into_iter().fold(
+            String::from("commands that depend on the stream:"),
+            |mut list, (name, availability)| {
+                write!(&mut list, "\n`!{name}`: {availability}").ok();
+                list
+            },
+        ),
+        Err(e) => failed(&e),
+    }
+}
+
+fn command_costs(res: Result<Vec<(String, u64)>>) -> String {
+    match res {
+        Ok(costs) if costs.is_empty() => "all custom commands are free".to_owned(),
+        Ok(costs) => costs.into_iter().fold(
+            String::from("custom commands that cost points:"),
+            |mut list, (name, cost)| {
+                write!(&mut list, "\n`!{name}`: {cost}").ok();
+                list
+            },
+        ),
+        Err(e) => failed(&e),
+    }
+}
+
+fn dead_links(res: Result<Vec<DeadLink>>) -> String {
+    match res {
+        Ok(dead) if dead.is_empty() => format!("{} all links work", emojis::OK_HAND),
+        Ok(dead) => dead.into_iter().fold(
+            String::from("links that don't work anymore:"),
+            |mut list,
+             DeadLink {
+                 origin,
+                 url,
+                 reason,
+             }| {
+                let origin = match origin {
+                    LinkOrigin::Links(name) => format!("link `{name}`"),
+                    LinkOrigin::Command { name, source } => format!("`!{name}` on {source}"),
+                };
+                write!(&mut list, "\n{origin}: <{url}> ({reason})").ok();
+                list
+            },
+        ),
+        Err(e) => failed(&e),
+    }
+}
+
+fn timing(report: Vec<(Stage, Histogram)>) -> String {
+    report.into_iter().fold(
+        String::from("Here is the time spent in each stage of the message handling:"),
+        |mut message, (stage, histogram)| {
+            write!(
+                &mut message,
+                "\n**{stage}**: {}",
+                super::timing_summary(&histogram)
+            )
+            .ok();
+            message
+        },
+    )
+}
+
+fn chapters(res: Result<Vec<StreamChapters>>) -> Reply {
+    match res {
+        Ok(streams) if streams.is_empty() => Reply::new("no streams found"),
+        Ok(streams) => Reply::new(format!(
+            "chapters of {} {}, ready to paste into the VOD description",
+            streams.len(),
+            if streams.len() == 1 {
+                "stream"
+            } else {
+                "streams"
+            }
+        ))
+        .attachment("chapters.txt", super::chapter_list(&streams).into_bytes()),
+        Err(e) => Reply::new(failed(&e)),
+    }
+}
+
+fn suggestions(resp: response::Suggestions) -> String {
+    match resp {
+        response::Suggestions::List(Ok(suggestions)) if suggestions.is_empty() => {
+            "there are no suggestions waiting for a review".to_owned()
+        }
+        response::Suggestions::List(Ok(suggestions)) => suggestions.into_iter().fold(
+            String::from("suggestions waiting for a review:"),
+            |mut list, suggestion| {
+                write!(
+                    &mut list,
+                    "\n**#{}** by {} ({}) on <t:{}:d>: {}",
+                    suggestion.id,
+                    suggestion.name,
+                    suggestion.source,
+                    suggestion.created_at.unix_timestamp(),
+                    suggestion.content
+                )
+                .ok();
+                list
+            },
+        ),
+        response::Suggestions::Review(Ok(suggestion)) => format!(
+            "{} suggestion #{} {}, {} gets a message about it",
+            emojis::OK_HAND,
+            suggestion.id,
+            super::suggestion_status(suggestion.status),
+            suggestion.name
+        ),
+        response::Suggestions::List(Err(e)) | response::Suggestions::Review(Err(e)) => failed(&e),
+    }
+}
+
+fn topic_update(res: Result<response::TopicUpdate>) -> String {
+    match res {
+        Ok(update) => format!("{} {}", emojis::OK_HAND, super::topic_update(update)),
+        Err(e) => failed(&e),
+    }
+}
+
+fn issue(res: Result<response::Issue>) -> String {
+    match res {
+        Ok(response::Issue::Created(issue)) => format!(
+            "{} created issue [#{}]({}): {}",
+            emojis::OK_HAND,
+            issue.number,
+            issue.url,
+            issue.title
+        ),
+        Ok(response::Issue::Existing(issue)) => format!(
+            "the suggestion already is issue [#{}]({}): {}",
+            issue.number, issue.url, issue.title
+        ),
+        Err(e) => failed(&e),
+    }
+}
+
+fn usage_alerts(res: Result<Vec<(String, u64)>>) -> String {
+    match res {
+        Ok(alerts) if alerts.is_empty() => "there are no usage alerts".to_owned(),
+        Ok(alerts) => alerts.into_iter().fold(
+            String::from("commands with a daily usage alert:"),
+            |mut list, (name, limit)| {
+                write!(&mut list, "\n`!{name}`: {limit}").ok();
+                list
+            },
+        ),
+        Err(e) => failed(&e),
+    }
+}
+
+/// Statistics with the built-in and custom commands as bar chart, falling back to text if any of
+/// them failed loading or the chart can't be drawn.
+fn statistics(date: StatisticsDate, stats: Statistics) -> Reply {
+    let mut message = match date {
+        StatisticsDate::Total => "Here are the statistics of all time".to_owned(),
+        StatisticsDate::Current => "Here are the statistics of the current month".to_owned(),
+        StatisticsDate::Month { year, month } => {
+            format!("Here are the statistics of {month} {year}")
+        }
+        StatisticsDate::Tag(tag) => {
+            format!("Here are the statistics of all streams tagged `{tag}`")
+        }
+    };
+
+    let CommandUsage {
+        builtin,
+        custom,
+        unknown,
+        spam,
+    } = stats.command_usage;
+
+    let chart = match (&builtin, &custom) {
+        (Ok(builtin), Ok(custom)) => usage_chart(builtin, custom),
+        _ => None,
+    };
+
+    if chart.is_none() {
+        message.push_str("\n\n**Built-in**");
+        match builtin {
+            Ok(usage) => {
+                for (cmd, count) in usage {
+                    write!(&mut message, "\n`{}`: {count}", cmd.name()).ok();
+                }
+            }
+            Err(e) => write_section_error(&mut message, &e),
+        }
+    }
+
+    let mut sections = vec![("Unknown", unknown), ("Spam", spam)];
+    if chart.is_none() {
+        sections.insert(0, ("Custom", custom));
+    }
+
+    for (title, usage) in sections {
+        write!(&mut message, "\n\n**{title}**").ok();
+        match usage {
+            Ok(usage) => {
+                for (cmd, count) in usage {
+                    write!(&mut message, "\n`{cmd}`: {count}").ok();
+                }
+            }
+            Err(e) => write_section_error(&mut message, &e),
+        }
+    }
+
+    match stats.watch_time {
+        Ok(leaders) if leaders.is_empty() => {}
+        Ok(leaders) => {
+            message.push_str("\n\n**Watch time**");
+            for (name, seconds) in leaders {
+                let time = super::uptime(Duration::from_secs(seconds));
+                write!(&mut message, "\n`{name}`: {time}").ok();
+            }
+        }
+        Err(e) => {
+            message.push_str("\n\n**Watch time**");
+            write_section_error(&mut message, &e);
+        }
+    }
+
+    match chart {
+        Some(image) => Reply::new(message).attachment("statistics.png", image),
+        None => Reply::new(message),
+    }
+}
+
+/// Bar chart of the most used built-in and custom commands, if there are any and drawing the
+/// chart worked.
+fn usage_chart(
+    builtin: &IndexMap<BuiltinCommand, u64>,
+    custom: &IndexMap<String, u64>,
+) -> Option<Vec<u8>> {
+    let mut bars = builtin
+        .iter()
+        .map(|(cmd, count)| (format!("!{}", cmd.name()), *count))
+        .chain(
+            custom
+                .iter()
+                .map(|(name, count)| (format!("!{name}"), *count)),
+        )
+        .collect::<Vec<_>>();
+    if bars.is_empty() {
+        return None;
+    }
+
+    bars.sort_by(|(_, a), (_, b)| b.cmp(a));
+    bars.truncate(CHART_BARS);
+
+    chart::bars("Command usage", &bars)
+        .inspect_err(|e| warn!(error = ?e, "failed drawing the statistics chart"))
+        .ok()
+}
+
+fn write_section_error(message: &mut String, error: &anyhow::Error) {
+    write!(
+        message,
+        "\n_Sorry, this section couldn't be loaded: {error}_"
+    )
+    .ok();
+}
+
+fn user_notes(user: &UserRef, notes: Vec<UserNote>) -> String {
+    let user = display_user(user);
+    if notes.is_empty() {
+        return format!("There are no notes about {user}");
+    }
+
+    notes.into_iter().fold(
+        format!("Here are the notes about {user}:"),
+        |mut list, note| {
+            write!(
+                &mut list,
+                "\n**#{}** by <@{}> on <t:{}:d>: {}",
+                note.id,
+                note.author,
+                note.created_at.unix_timestamp(),
+                note.content
+            )
+            .ok();
+            list
+        },
+    )
+}
+
+fn display_user(user: &UserRef) -> String {
+    match user.source {
+        Source::Discord => format!("<@{}>", user.id),
+        _ => format!("`{user}`"),
+    }
+}
+
+/// Render an owner response.
+#[must_use]
+pub fn owner(resp: response::Owner) -> Reply {
+    match resp {
+        response::Owner::Help => Reply::new(access_help("owner", Access::Owner)),
+        response::Owner::Admins(response::Admins::List { users, roles }) => {
+            Reply::new(admins_list(users, roles)).silent()
+        }
+        response::Owner::Admins(response::Admins::Edit(res)) => Reply::new(match res {
+            Ok(action) => format!(
+                "{} {}",
+                emojis::OK_HAND,
+                match action {
+                    AdminAction::Added => "user added to admin list",
+                    AdminAction::Removed => "user removed from admin list",
+                    AdminAction::RoleAdded => "role added to admin roles",
+                    AdminAction::RoleRemoved => "role removed from admin roles",
+                },
+            ),
+            Err(e) => failed(&e),
+        }),
+        response::Owner::Plugins(response::Plugins::List(plugins)) => {
+            Reply::new(plugins_list(plugins))
+        }
+        response::Owner::Plugins(response::Plugins::Edit(res)) => Reply::new(match res {
+            Ok(action) => format!("{} plugin {action}", emojis::OK_HAND),
+            Err(e) => failed(&e),
+        }),
+        response::Owner::DebugParse(text) => Reply::new(debug_parse(text)),
+        response::Owner::Cleanup(res) => Reply::new(match res {
+            Ok(cleanup) => format!("{} {}", emojis::OK_HAND, super::cleanup(cleanup)),
+            Err(e) => failed(&e),
+        }),
+        response::Owner::ResyncCommands => Reply::new(format!(
+            "{} re-registering slash commands, changes show up once that's done",
+            emojis::OK_HAND
+        )),
+        response::Owner::Broadcast(_) => Reply::new(format!(
+            "{} broadcasting the message on all services",
+            emojis::OK_HAND
+        )),
+        #[cfg(feature = "chaos")]
+        response::Owner::Chaos(faults) => {
+            Reply::new(format!("{} injected faults: {faults}", emojis::OK_HAND))
+        }
+    }
+}
+
+/// Render the denial of a command, that only the author gets to see.
+#[must_use]
+pub fn denied(required: Access) -> Reply {
+    Reply::new(super::denied(required)).private()
+}
+
+/// Render the request to confirm a destructive command, with a button to do so.
+#[must_use]
+pub fn confirm(action: response::Confirm) -> Reply {
+    let action = match action {
+        response::Confirm::RemoveCustomCommand(name) => {
+            format!("remove the custom command `{name}` from all services")
+        }
+        response::Confirm::RemoveAdmin(id) => format!("remove <@{id}> from the admin list"),
+        response::Confirm::RemoveAdminRole(role) => {
+            format!("remove <@&{role}> from the admin roles")
+        }
+    };
+
+    Reply {
+        confirm: true,
+        ..Reply::new(format!(
+            "{} this will {action}, confirm it within {} seconds with the button below or \
+             `!confirm`",
+            emojis::WARNING,
+            CONFIRM_TIMEOUT.as_secs()
+        ))
+        .silent()
+    }
+}
+
+fn admins_list(users: Vec<AdminId>, roles: Vec<NonZero<u64>>) -> String {
+    let mut list = users
+        .into_iter()
+        .fold(String::from("current admins are:"), |mut list, id| {
+            write!(&mut list, "\n- <@{id}>").ok();
+            list
+        });
+
+    if !roles.is_empty() {
+        list.push_str("\n\nas well as all members of:");
+        for role in roles {
+            write!(&mut list, "\n- <@&{role}>").ok();
+        }
+    }
+
+    list
+}
+
+fn plugins_list(plugins: Vec<PluginInfo>) -> String {
+    plugins
+        .into_iter()
+        .fold(String::from("registered plugins:"), |mut list, plugin| {
+            let commands = plugin
+                .commands
+                .iter()
+                .map(|command| format!("`!{command}`"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            write!(
+                &mut list,
+                "\n- `{}` ({}): {commands}",
+                plugin.name,
+                if plugin.loaded { "loaded" } else { "unloaded" }
+            )
+            .ok();
+            list
+        })
+}
+
+fn debug_parse(mut text: String) -> String {
+    if text.len() > MAX_CODE_LEN {
+        let end = (0..=MAX_CODE_LEN)
+            .rev()
+            .find(|&i| text.is_char_boundary(i))
+            .unwrap_or_default();
+        text.truncate(end);
+        text.push_str("\n...");
+    }
+
+    format!("```rust\n{text}\n```")
+}
+
+fn custom_commands_edit(res: Result<Vec<MassMention>>) -> String {
+    let mentions = match res {
+        Ok(mentions) if mentions.is_empty() => return done(Ok(()), "custom commands updated"),
+        Ok(mentions) => mentions,
+        Err(e) => return failed(&e),
+    };
+
+    format!(
+        "{} custom commands updated\n{} the content mentions {}, which only notifies anyone if \
+         the mention settings allow it",
+        emojis::OK_HAND,
+        emojis::WARNING,
+        super::mass_mentions(&mentions, |mention| format!("`{mention}`")),
+    )
+}
+
+fn done(res: Result<()>, message: &str) -> String {
+    match res {
+        Ok(()) => format!("{} {message}", emojis::OK_HAND),
+        Err(e) => failed(&e),
+    }
+}
+
+fn failed(error: &anyhow::Error) -> String {
+    format!("{} some error happened: {error}", emojis::COLLISION)
+}
@@ -0,0 +1,2247 @@
+//! Rendering of responses into messages for the different chat services. Each service has its own
+//! flavor of formatting, but all renderers are pure functions without any access to the services,
+//! so connectors only take care of delivering the messages.
+
+use std::{fmt::Write, time::Duration};
+
+use anyhow::Result;
+use tracing::error;
+
+use crate::{
+    api::{
+        response::{
+            Birthday, BotStat, CommandHelp, CustomCommandEntry, Duel, Eval, Gamble, LastCategory,
+            Links, Lurk, MassMention, Prefs, Pronouns, RustVersions, SongRequest, StreamChapters,
+            StreamTitle, Suggest, Suggestion, Topic, TopicUpdate, VersionInfo, VersionSearch,
+            WatchTime,
+        },
+        Availability, Highlight, Poll, Source, SuggestionStatus, UserRef,
+    },
+    commands::CommandInfo,
+    fun::Kind,
+    handler::{Access, DUEL_TIMEOUT},
+    locale,
+    statistics::Cleanup,
+    timing::Histogram,
+};
+
+pub mod discord;
+pub mod plain;
+pub mod twitch;
+pub mod youtube;
+
+/// Amount of songs that are listed when showing the song request queue.
+const QUEUE_PREVIEW: usize = 5;
+/// Amount of characters of a highlight's content that are shown, before it's cut off.
+const HIGHLIGHT_PREVIEW: usize = 60;
+/// Amount of characters of a suggestion that are quoted, when telling its author about the review.
+const SUGGESTION_PREVIEW: usize = 60;
+
+/// Outcome of a song request, which reads the same on all services.
+fn song_request(res: Result<SongRequest>) -> String {
+    match res {
+        Ok(SongRequest::Added { position }) => {
+            format!("Added your song to the queue at position {position}")
+        }
+        Ok(SongRequest::Refused(message)) => message,
+        Err(e) => {
+            error!(error = ?e, "failed adding song request");
+            "Sorry, something went wrong adding the song".to_owned()
+        }
+    }
+}
+
+/// Details of a single command on one line, for services without formatting.
+fn command_help(res: Result<Option<CommandHelp>>) -> String {
+    let help = match res {
+        Ok(Some(help)) => help,
+        Ok(None) => return "There is no command with that name".to_owned(),
+        Err(e) => {
+            error!(error = ?e, "failed getting command details");
+            return "Sorry, something went wrong looking up the command".to_owned();
+        }
+    };
+
+    let mut text = format!("!{}", help.name);
+    match help.info {
+        Some(info) => {
+            if !info.aliases.is_empty() {
+                let _ = write!(text, " (or !{})", info.aliases.join(", !"));
+            }
+            let _ = write!(
+                text,
+                ": {} Usage: {}.",
+                info.description,
+                info.forms()
+                    .map(|(form, _)| form)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            if !info.examples.is_empty() {
+                let _ = write!(text, " Example: {}.", info.examples.join(", "));
+            }
+        }
+        None => text.push_str(": Custom command."),
+    }
+
+    let _ = write!(
+        text,
+        " For {}, on {}.",
+        permission(help.info),
+        sources(&help.sources)
+    );
+    if let Some(cost) = help.cost {
+        let _ = write!(text, " Costs {cost} points.");
+    }
+    if help.availability != Availability::Always {
+        let _ = write!(text, " Available {}.", help.availability);
+    }
+    if let Some((uses, window)) = help.cooldown {
+        let _ = write!(
+            text,
+            " Answered {uses} times per {} at most.",
+            poll_duration(window)
+        );
+    }
+
+    text
+}
+
+/// Names of all available commands on a single line, for services without formatting.
+fn command_names(builtin: &[&CommandInfo], custom: Result<Vec<String>>) -> String {
+    let custom = match custom {
+        Ok(custom) => custom,
+        Err(e) => {
+            error!(error = ?e, "failed listing commands");
+            return "Sorry, something went wrong fetching the list of commands".to_owned();
+        }
+    };
+
+    let names = builtin
+        .iter()
+        .map(|cmd| {
+            if cmd.aliases.is_empty() {
+                format!("!{}", cmd.name)
+            } else {
+                format!("!{} (or !{})", cmd.name, cmd.aliases.join(", !"))
+            }
+        })
+        .chain(custom.into_iter().map(|name| format!("!{name}")))
+        .collect::<Vec<_>>();
+
+    format!("Available commands: {}", names.join(", "))
+}
+
+/// Who may use a command. Custom commands are always available to everyone.
+fn permission(info: Option<&CommandInfo>) -> &'static str {
+    audience(info.map_or(Access::Standard, |info| info.access))
+}
+
+/// Group of users that an access level grants commands to.
+fn audience(access: Access) -> &'static str {
+    match access {
+        Access::Standard => "everyone",
+        Access::Admin => "admins",
+        Access::Owner => "owners",
+    }
+}
+
+/// Reply to a command that requires a higher access level. It reads the same on all services.
+#[must_use]
+pub fn denied(required: Access) -> String {
+    format!(
+        "Sorry, you don't have permission to use this command, it's only available to {}",
+        audience(required)
+    )
+}
+
+/// Comma separated list of service names.
+fn sources(sources: &[Source]) -> String {
+    sources
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Balance of a user's points, which reads the same on all services.
+fn points(res: Result<u64>) -> String {
+    match res {
+        Ok(1) => "You have 1 point".to_owned(),
+        Ok(balance) => format!("You have {balance} points"),
+        Err(e) => {
+            error!(error = ?e, "failed getting points");
+            "Sorry, something went wrong fetching your points".to_owned()
+        }
+    }
+}
+
+/// Refusal of a custom command that the user can't afford.
+fn not_enough_points(cost: u64, balance: u64) -> String {
+    format!("This command costs {cost} points, but you only have {balance}")
+}
+
+/// Outcome of a gamble, which reads the same on all services.
+fn gamble(res: Result<Gamble>) -> String {
+    match res {
+        Ok(Gamble::Won { amount, balance }) => {
+            format!("You won {amount} points and have {balance} now")
+        }
+        Ok(Gamble::Lost { amount, balance }) => {
+            format!("You lost {amount} points and have {balance} left")
+        }
+        Ok(Gamble::Refused(message)) => message,
+        Err(e) => {
+            error!(error = ?e, "failed gambling");
+            "Sorry, something went wrong with your bet".to_owned()
+        }
+    }
+}
+
+/// Outcome of a duel, which reads the same on all services.
+fn duel(res: Result<Duel>) -> String {
+    match res {
+        Ok(Duel::Challenged { opponent, amount }) => format!(
+            "{opponent}, you were challenged to a duel for {amount} points. Type !accept within {}s \
+             to take it on",
+            DUEL_TIMEOUT.as_secs()
+        ),
+        Ok(Duel::Won { opponent, amount }) => {
+            format!("You won the duel against {opponent} and took {amount} points")
+        }
+        Ok(Duel::Lost { opponent, amount }) => {
+            format!("{opponent} won the duel and took {amount} of your points")
+        }
+        Ok(Duel::Refused(message)) => message,
+        Err(e) => {
+            error!(error = ?e, "failed dueling");
+            "Sorry, something went wrong with the duel".to_owned()
+        }
+    }
+}
+
+/// Outcome of starting or stopping a lurk, which reads the same on all services.
+fn lurk(res: Result<Lurk>) -> String {
+    match res {
+        Ok(Lurk::Started {
+            name,
+            message: Some(message),
+        }) => format!("{name} is lurking now: {message}"),
+        Ok(Lurk::Started {
+            name,
+            message: None,
+        }) => format!("{name} is lurking now, enjoy the lurk!"),
+        Ok(Lurk::Ended { name, duration }) => {
+            format!(
+                "Welcome back {name}, you were lurking for {}",
+                uptime(duration)
+            )
+        }
+        Ok(Lurk::NotLurking(name)) => format!("{name}, you weren't lurking"),
+        Ok(Lurk::Refused(message)) => message,
+        Err(e) => {
+            error!(error = ?e, "failed handling lurk");
+            "Sorry, something went wrong with your lurk".to_owned()
+        }
+    }
+}
+
+/// Creator of a custom command, which is the one who added it to any of the services first.
+fn command_creator(entries: &[CustomCommandEntry]) -> Option<&UserRef> {
+    entries
+        .iter()
+        .filter(|entry| entry.created_by.is_some())
+        .min_by_key(|entry| entry.created_at)
+        .and_then(|entry| entry.created_by.as_ref())
+}
+
+/// Outcome of a pronoun lookup or change, which reads the same on all services.
+fn pronouns(res: Result<Pronouns>) -> String {
+    match res {
+        Ok(Pronouns::Found { user, pronouns }) => format!("{user} uses {pronouns} pronouns"),
+        Ok(Pronouns::NotSet(user)) => format!("{user} didn't share any pronouns yet"),
+        Ok(Pronouns::Saved) => "Your pronouns are saved".to_owned(),
+        Ok(Pronouns::Removed) => "Your pronouns are removed".to_owned(),
+        Ok(Pronouns::Refused(message)) => message,
+        Err(e) => {
+            error!(error = ?e, "failed handling pronouns");
+            "Sorry, something went wrong looking up the pronouns".to_owned()
+        }
+    }
+}
+
+/// Outcome of a lookup or change of personal settings, which reads the same on all services.
+fn prefs(res: Result<Prefs>) -> String {
+    match res {
+        Ok(Prefs::Current(prefs)) if prefs.is_empty() => {
+            "You're using the defaults for all settings, change them with !set <setting> <value>"
+                .to_owned()
+        }
+        Ok(Prefs::Current(prefs)) => {
+            let list = prefs
+                .iter()
+                .map(|pref| format!("{} {}", pref.key(), pref.value()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!("Your settings: {list}")
+        }
+        Ok(Prefs::Saved(pref)) => format!("Your {} is now {}", pref.key(), pref.value()),
+        Ok(Prefs::Reset(key)) => format!("Your {key} is back to the default"),
+        Ok(Prefs::NotSet(key)) => format!("You're already using the default {key}"),
+        Err(e) => {
+            error!(error = ?e, "failed handling settings");
+            "Sorry, something went wrong with your settings".to_owned()
+        }
+    }
+}
+
+/// The links joined by the profile's separator, or the service's default one. Each link uses the
+/// template if there is one, or the service's default format with the icon in front. Links
+/// without an icon don't keep the space around the empty placeholder.
+fn links_list(links: &Links, separator: &str, format: impl Fn(&str, &str) -> String) -> String {
+    links
+        .links
+        .iter()
+        .map(|link| match (&links.template, &link.icon) {
+            (Some(template), icon) => locale::fill(
+                template,
+                &[
+                    ("name", &link.name),
+                    ("url", &link.url),
+                    ("icon", &icon.as_deref().unwrap_or_default()),
+                ],
+            )
+            .trim()
+            .to_owned(),
+            (None, Some(icon)) => format!("{icon} {}", format(&link.name, &link.url)),
+            (None, None) => format(&link.name, &link.url),
+        })
+        .collect::<Vec<_>>()
+        .join(links.separator.as_deref().unwrap_or(separator))
+}
+
+/// The `links` command as it's shown on the given service.
+fn link_preview(source: Source, links: &Links) -> String {
+    match source {
+        Source::Discord => discord::links_list(links),
+        Source::Twitch | Source::YouTube => twitch::links_list(links),
+        Source::Matrix | Source::Console => plain::links_list(links),
+    }
+}
+
+/// Confirmation of a started poll, which reads the same on all services.
+/// List the kinds of mass mentions in a sentence, with `literal` formatting the ones that are
+/// written as-is in the content.
+fn mass_mentions(mentions: &[MassMention], literal: impl Fn(&str) -> String) -> String {
+    let mut names = mentions
+        .iter()
+        .map(|mention| match mention {
+            MassMention::Everyone => literal("@everyone"),
+            MassMention::Here => literal("@here"),
+            MassMention::Role => "roles".to_owned(),
+        })
+        .collect::<Vec<_>>();
+
+    match names.pop() {
+        Some(last) if names.is_empty() => last,
+        Some(last) => format!("{} and {last}", names.join(", ")),
+        None => String::new(),
+    }
+}
+
+fn poll(res: Result<Poll>) -> String {
+    match res {
+        Ok(poll) => format!(
+            "Started the {} \"{}\" with the options {} for {}",
+            poll.kind.name(),
+            poll.question,
+            poll.options.join(", "),
+            poll_duration(poll.duration),
+        ),
+        Err(e) => format!("Couldn't start the poll, {e}"),
+    }
+}
+
+/// Confirmation of opting in or out of live notifications, which reads the same on all services.
+fn notify_me(res: Result<bool>) -> String {
+    match res {
+        Ok(true) => "I'll send you a direct message whenever the stream goes live".to_owned(),
+        Ok(false) => "You won't get any more direct messages when the stream goes live".to_owned(),
+        Err(e) => {
+            error!(error = ?e, "failed changing live notification");
+            "Sorry, something went wrong changing your live notification".to_owned()
+        }
+    }
+}
+
+/// Outcome of a watch time lookup or change, which reads the same on all services.
+fn watch_time(res: Result<WatchTime>) -> String {
+    match res {
+        Ok(WatchTime::Tracked { month, total }) => format!(
+            "You watched for {} this month, and {} in total",
+            uptime(month),
+            uptime(total)
+        ),
+        Ok(WatchTime::OptedOut) => {
+            "Your watch time isn't tracked, use !watchtime on to change that".to_owned()
+        }
+        Ok(WatchTime::Changed(true)) => "Your watch time is tracked from now on".to_owned(),
+        Ok(WatchTime::Changed(false)) => {
+            "Your watch time is deleted and won't be tracked anymore".to_owned()
+        }
+        Err(e) => {
+            error!(error = ?e, "failed handling watch time");
+            "Sorry, something went wrong with your watch time".to_owned()
+        }
+    }
+}
+
+/// Titles and categories of yesterday's streams with the time they were set, which reads the same
+/// on all services.
+fn yesterday(res: Result<Vec<StreamTitle>>) -> String {
+    match res {
+        Ok(titles) if titles.is_empty() => "Nothing was streamed yesterday".to_owned(),
+        Ok(titles) => titles.into_iter().enumerate().fold(
+            String::from("Yesterday's streams:"),
+            |mut list, (i, title)| {
+                write!(
+                    &mut list,
+                    "{} {:02}:{:02} {} ({})",
+                    if i == 0 { "" } else { "," },
+                    title.changed_at.hour(),
+                    title.changed_at.minute(),
+                    title.title,
+                    title.category
+                )
+                .ok();
+                list
+            },
+        ),
+        Err(e) => {
+            error!(error = ?e, "failed listing yesterday's streams");
+            "Sorry, something went wrong looking up yesterday's streams".to_owned()
+        }
+    }
+}
+
+/// The last time that a category was streamed, which reads the same on all services.
+fn last_category(res: Result<LastCategory>) -> String {
+    match res {
+        Ok(LastCategory::Live(title)) => {
+            format!("{} is streamed right now: {}", title.category, title.title)
+        }
+        Ok(LastCategory::Past(title)) => format!(
+            "{} was last streamed on {}: {}",
+            title.category,
+            title.changed_at.date(),
+            title.title
+        ),
+        Ok(LastCategory::Never(category)) => format!("{category} wasn't streamed so far"),
+        Err(e) => {
+            error!(error = ?e, "failed looking up category");
+            "Sorry, something went wrong looking up the category".to_owned()
+        }
+    }
+}
+
+/// Outcome of sending in a suggestion, which reads the same on all services.
+fn suggest(res: Result<Suggest>) -> String {
+    match res {
+        Ok(Suggest::Added(id)) => {
+            format!("Thanks for your suggestion #{id}, the admins will have a look at it")
+        }
+        Ok(Suggest::Refused(message)) => message,
+        Err(e) => {
+            error!(error = ?e, "failed saving suggestion");
+            "Sorry, something went wrong saving your suggestion".to_owned()
+        }
+    }
+}
+
+/// Agenda of the current or next stream, which reads the same on all services.
+fn topic(res: Result<Option<Topic>>) -> String {
+    match res {
+        Ok(Some(topic)) if topic.live => format!("Today's agenda: {}", topic.content),
+        Ok(Some(topic)) => format!("Agenda of the next stream: {}", topic.content),
+        Ok(None) => "There's no agenda for the stream yet".to_owned(),
+        Err(e) => {
+            error!(error = ?e, "failed looking up the stream agenda");
+            "Sorry, something went wrong looking up the agenda".to_owned()
+        }
+    }
+}
+
+/// Confirmation of a changed agenda, for the admin that changed it.
+fn topic_update(update: TopicUpdate) -> &'static str {
+    match update {
+        TopicUpdate::Live => "agenda of the stream set",
+        TopicUpdate::Next => "the stream is offline, so the agenda is kept for the next one",
+        TopicUpdate::Cleared => "agenda cleared",
+    }
+}
+
+/// Message to the author of a suggestion once the admins reviewed it, which reads the same on all
+/// services.
+#[must_use]
+pub fn suggestion_reviewed(suggestion: &Suggestion) -> String {
+    let mut message = format!(
+        "Your suggestion #{} \"{}\" was {}",
+        suggestion.id,
+        truncate(&suggestion.content, SUGGESTION_PREVIEW),
+        suggestion_status(suggestion.status)
+    );
+    if let Some(reason) = &suggestion.reason {
+        write!(&mut message, ": {reason}").ok();
+    }
+    message
+}
+
+/// Review status of a suggestion, as it reads after "was".
+fn suggestion_status(status: SuggestionStatus) -> &'static str {
+    match status {
+        SuggestionStatus::Open => "reopened",
+        SuggestionStatus::Accepted => "accepted",
+        SuggestionStatus::Rejected => "rejected",
+    }
+}
+
+/// Chapter list of each stream, in the format that YouTube picks up from a video description.
+fn chapter_list(streams: &[StreamChapters]) -> String {
+    streams.iter().fold(String::new(), |mut list, stream| {
+        if !list.is_empty() {
+            list.push('\n');
+        }
+        writeln!(
+            &mut list,
+            "{} {:02}:{:02} UTC",
+            stream.started_at.date(),
+            stream.started_at.hour(),
+            stream.started_at.minute()
+        )
+        .ok();
+        for chapter in &stream.chapters {
+            writeln!(
+                &mut list,
+                "{} {} ({})",
+                chapter_offset(chapter.offset),
+                chapter.title,
+                chapter.category
+            )
+            .ok();
+        }
+        list
+    })
+}
+
+/// Offset of a chapter like `1:02:30`, leaving out the hours for the first hour of the stream.
+fn chapter_offset(offset: Duration) -> String {
+    let secs = offset.as_secs();
+    let (hours, minutes, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{secs:02}")
+    } else {
+        format!("{minutes}:{secs:02}")
+    }
+}
+
+/// Outcome of a birthday lookup or change, which reads the same on all services.
+fn birthday(res: Result<Birthday>) -> String {
+    match res {
+        Ok(Birthday::Registered {
+            month,
+            day,
+            announce,
+        }) => format!(
+            "Your birthday is on {month} {day}, and it's {}announced",
+            if announce { "" } else { "not " }
+        ),
+        Ok(Birthday::NotRegistered) => "You didn't register your birthday yet".to_owned(),
+        Ok(Birthday::Saved { month, day }) => format!("Your birthday on {month} {day} is saved"),
+        Ok(Birthday::Removed) => "Your birthday is removed".to_owned(),
+        Ok(Birthday::Announce(true)) => "Your birthday will be announced on the day".to_owned(),
+        Ok(Birthday::Announce(false)) => "Your birthday won't be announced anymore".to_owned(),
+        Err(e) => {
+            error!(error = ?e, "failed handling birthday");
+            "Sorry, something went wrong with your birthday".to_owned()
+        }
+    }
+}
+
+/// Automatic reply while the streamer is away, which reads the same on all services.
+fn away(streamer: &str, message: &str) -> String {
+    format!("{streamer} is away right now: {message}")
+}
+
+/// Current Rust versions of all release channels in a single line.
+fn rust_versions(res: Result<RustVersions>) -> String {
+    match res {
+        Ok(RustVersions {
+            stable,
+            beta,
+            nightly,
+        }) => format!(
+            "Rust stable {} ({}), beta {} ({}), nightly {} ({})",
+            stable.version, stable.date, beta.version, beta.date, nightly.version, nightly.date
+        ),
+        Err(e) => {
+            error!(error = ?e, "failed fetching Rust versions");
+            "Sorry, something went wrong looking up the Rust versions".to_owned()
+        }
+    }
+}
+
+/// Reply when crates can't be compared, because crates.io couldn't be reached.
+const CRATES_IO_UNAVAILABLE: &str = "Sorry, crates.io is unavailable right now, try again later";
+
+/// Crate that only docs.rs knew about, because crates.io couldn't be reached.
+fn crate_fallback(name: &str, version: &str) -> String {
+    format!(
+        "{name} v{version} (crates.io is unavailable right now) https://docs.rs/{name}/{version}"
+    )
+}
+
+/// Details about a single version of a crate in a single line.
+fn crate_version(res: Result<VersionSearch>) -> String {
+    match res {
+        Ok(VersionSearch::Found(info)) => format!(
+            "{} v{} ({}) {}",
+            info.name,
+            info.num,
+            version_details(&info).join(", "),
+            info.link()
+        ),
+        Ok(VersionSearch::NotFound(message)) => message,
+        Err(e) => {
+            error!(error = ?e, "failed searching for crate version");
+            "Sorry, something went wrong looking up the crate".to_owned()
+        }
+    }
+}
+
+/// Release date, downloads, license and other short facts about a crate version.
+fn version_details(info: &VersionInfo) -> Vec<String> {
+    let mut details = vec![
+        format!("released {}", info.created_at.date()),
+        format!("{} downloads", info.downloads),
+    ];
+    details.extend(info.license.clone());
+    details.extend(
+        info.rust_version
+            .as_ref()
+            .map(|version| format!("requires Rust {version}")),
+    );
+    if info.yanked {
+        details.push("yanked".to_owned());
+    }
+    details
+}
+
+/// Feature flags of a crate version in a single line, mentioning the default ones first.
+fn crate_features(res: Result<VersionSearch>) -> String {
+    match res {
+        Ok(VersionSearch::Found(info)) => {
+            let features = info
+                .features
+                .keys()
+                .filter(|name| *name != "default")
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+            if features.is_empty() {
+                return format!("{} v{} has no feature flags", info.name, info.num);
+            }
+
+            let default = info
+                .features
+                .get("default")
+                .filter(|default| !default.is_empty())
+                .map(|default| format!(" (default: {})", default.join(", ")))
+                .unwrap_or_default();
+
+            format!(
+                "Features of {} v{}{default}: {} {}",
+                info.name,
+                info.num,
+                features.join(", "),
+                info.features_link()
+            )
+        }
+        Ok(VersionSearch::NotFound(message)) => message,
+        Err(e) => {
+            error!(error = ?e, "failed searching for crate features");
+            "Sorry, something went wrong looking up the crate".to_owned()
+        }
+    }
+}
+
+/// Recently pinned highlights in a single line, with links that jump to the messages.
+fn highlights(res: Result<Vec<Highlight>>) -> String {
+    match res {
+        Ok(highlights) if highlights.is_empty() => "There are no highlights yet".to_owned(),
+        Ok(highlights) => {
+            let list = highlights
+                .iter()
+                .map(|highlight| {
+                    format!(
+                        "{}: \"{}\" {}",
+                        highlight.author,
+                        preview(&highlight.content),
+                        highlight.link()
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(" | ");
+            format!("Recent highlights: {list}")
+        }
+        Err(e) => {
+            error!(error = ?e, "failed listing highlights");
+            "Sorry, something went wrong fetching the highlights".to_owned()
+        }
+    }
+}
+
+/// Start of a highlight's content, cut off at the first line break or the preview length.
+fn preview(content: &str) -> String {
+    truncate(
+        content.lines().next().unwrap_or_default().trim(),
+        HIGHLIGHT_PREVIEW,
+    )
+}
+
+/// Cut off the text at the given amount of characters, marking the cut with an ellipsis.
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_owned()
+    } else {
+        let mut short = text.chars().take(max - 1).collect::<String>();
+        short.push('…');
+        short
+    }
+}
+
+/// Outcome of a Rust snippet, where the output of snippets that ran is formatted by `ran` with
+/// whether it succeeded. Refusals read the same on all services.
+fn eval(res: Result<Eval>, ran: impl FnOnce(bool, String) -> String) -> String {
+    match res {
+        Ok(Eval::Ran { success, output }) => ran(success, output),
+        Ok(Eval::TooLong { max }) => format!("That snippet is too long, the limit is {max} bytes"),
+        Ok(Eval::Cooldown(left)) => format!(
+            "Slow down, the next snippet can run in {}s",
+            left.as_secs().max(1)
+        ),
+        Err(e) => {
+            error!(error = ?e, "failed running snippet");
+            "Sorry, something went wrong running the snippet".to_owned()
+        }
+    }
+}
+
+/// Output of a Rust snippet in a single line, cut off at the given amount of characters.
+fn eval_line(success: bool, output: &str, max: usize) -> String {
+    let output = output
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ⏎ ");
+
+    format!(
+        "{} {}",
+        if success { '✅' } else { '❌' },
+        if output.is_empty() {
+            "(no output)".to_owned()
+        } else {
+            truncate(&output, max)
+        }
+    )
+}
+
+/// A piece of advice, fact or joke, which reads the same on all services.
+fn fun(kind: Kind, res: Result<String>) -> String {
+    res.unwrap_or_else(|e| {
+        error!(error = ?e, %kind, "failed fetching fun content");
+        "Sorry, I couldn't think of anything right now".to_owned()
+    })
+}
+
+/// Duration of a poll in minutes and seconds, leaving out units that are zero.
+fn poll_duration(duration: Duration) -> String {
+    let (minutes, seconds) = (duration.as_secs() / 60, duration.as_secs() % 60);
+
+    match (minutes, seconds) {
+        (0, seconds) => format!("{seconds}s"),
+        (minutes, 0) => format!("{minutes}m"),
+        (minutes, seconds) => format!("{minutes}m {seconds}s"),
+    }
+}
+
+/// Announcement of the winner of a poll, once it ended. It reads the same on all services.
+#[must_use]
+pub fn poll_results(question: &str, results: &[(String, u64)]) -> String {
+    let total = results.iter().map(|(_, votes)| votes).sum::<u64>();
+    let Some(max) = results
+        .iter()
+        .map(|(_, votes)| *votes)
+        .max()
+        .filter(|&max| max > 0)
+    else {
+        return format!("The poll \"{question}\" ended without any votes");
+    };
+
+    let winners = results
+        .iter()
+        .filter(|(_, votes)| *votes == max)
+        .map(|(option, _)| option.as_str())
+        .collect::<Vec<_>>();
+    let votes = results
+        .iter()
+        .map(|(option, votes)| format!("{option} {votes}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    if let [winner] = winners[..] {
+        format!("The poll \"{question}\" ended, {winner} won with {max} of {total} votes ({votes})")
+    } else {
+        format!(
+            "The poll \"{question}\" ended in a tie between {} ({votes})",
+            winners.join(" and ")
+        )
+    }
+}
+
+/// Details about the bot itself, which read the same on all services.
+fn botstat(stat: &BotStat) -> String {
+    let runtime = &stat.runtime;
+    let mut message = format!(
+        "ToggleBot v{} ({}), up for {}.",
+        runtime.version,
+        runtime.commit,
+        uptime(runtime.uptime)
+    );
+
+    if stat.connectors.is_empty() {
+        message.push_str(" Not connected to any service.");
+    } else {
+        let names = stat
+            .connectors
+            .iter()
+            .map(AsRef::as_ref)
+            .collect::<Vec<&str>>();
+        message.push_str(" Connected to ");
+        message.push_str(&names.join(", "));
+        message.push('.');
+    }
+
+    match &stat.db_size {
+        Ok(size) => {
+            write!(message, " Database size {}.", bytes(*size)).ok();
+        }
+        Err(e) => error!(error = ?e, "failed getting database size"),
+    }
+
+    write!(
+        message,
+        " Handled {} messages ({:.1} per minute).",
+        runtime.messages,
+        runtime.messages_per_minute()
+    )
+    .ok();
+
+    if stat.latency.count > 0 {
+        write!(
+            message,
+            " Replies take {} (p50) and {} (p95).",
+            millis(stat.latency.percentile(50)),
+            millis(stat.latency.percentile(95))
+        )
+        .ok();
+    }
+
+    message
+}
+
+/// Duration in days, hours and minutes, leaving out leading zero units.
+fn uptime(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    let (days, hours, minutes) = (minutes / 1440, minutes / 60 % 24, minutes % 60);
+
+    if days > 0 {
+        format!("{days}d {hours}h {minutes}m")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+/// Summary of a statistics cleanup.
+fn cleanup(cleanup: Cleanup) -> String {
+    format!(
+        "deleted {} outdated usage counters and reclaimed {}",
+        cleanup.rows,
+        bytes(cleanup.reclaimed)
+    )
+}
+
+/// Size in bytes, with a binary unit suffix.
+fn bytes(size: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if size < 1024 {
+        return format!("{size} B");
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let mut value = size as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next in &UNITS[1..] {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next;
+    }
+
+    format!("{value:.1} {unit}")
+}
+
+/// Summarize the durations of a single stage of the message handling, in a single line.
+fn timing_summary(histogram: &Histogram) -> String {
+    if histogram.count == 0 {
+        return "no messages yet".to_owned();
+    }
+
+    format!(
+        "{} messages, mean {}, p50 {}, p95 {}, max {}",
+        histogram.count,
+        millis(histogram.mean()),
+        millis(histogram.percentile(50)),
+        millis(histogram.percentile(95)),
+        millis(histogram.max),
+    )
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, fmt::Write, num::NonZero};
+
+    use anyhow::anyhow;
+    use indexmap::IndexMap;
+    use insta::{assert_snapshot, assert_yaml_snapshot, Settings};
+    use time::{
+        macros::{datetime, offset},
+        OffsetDateTime,
+    };
+
+    use super::*;
+    use crate::{
+        api::{
+            request::StatisticsDate,
+            response::{
+                self, AdminAction, CommandRevision, CrateInfo, CrateSearch, Dependents, QueuedSong,
+                SongPlatform, UserNote,
+            },
+            AdminId, Availability, ChannelRule, PollKind, Source, StreamInfo, TemperatureUnit,
+            UserPref, UserPrefs, UserRef,
+        },
+        commands,
+        health::ConnectorHealth,
+        locale::Language,
+        plugins::{PluginAction, PluginInfo},
+        runtime::RuntimeInfo,
+        statistics::{BuiltinCommand, CommandUsage, Statistics},
+        timing::{Stage, Timings},
+    };
+
+    /// Custom command on the service, with a known creator and a later change if it's `tracked`.
+    fn custom_entry(source: Source, tracked: bool) -> CustomCommandEntry {
+        let author = UserRef {
+            source: Source::Discord,
+            id: "42".to_owned(),
+        };
+
+        CustomCommandEntry {
+            name: "hello".to_owned(),
+            source,
+            content: "Hello there!".to_owned(),
+            created_by: tracked.then(|| author.clone()),
+            created_at: tracked.then_some(datetime!(2024-11-01 12:00 UTC)),
+            updated_by: tracked.then_some(author),
+            updated_at: tracked.then_some(datetime!(2024-11-02 12:00 UTC)),
+        }
+    }
+
+    fn stream_title(category: &str, changed_at: OffsetDateTime) -> StreamTitle {
+        StreamTitle {
+            title: format!("Playing {category}"),
+            category: category.to_owned(),
+            changed_at,
+        }
+    }
+
+    fn suggestion(id: i64, status: SuggestionStatus, reason: Option<&str>) -> Suggestion {
+        Suggestion {
+            id,
+            source: Source::Twitch,
+            user_id: "1".to_owned(),
+            name: "viewer".to_owned(),
+            content: "A Rust quiz night".to_owned(),
+            status,
+            reason: reason.map(ToOwned::to_owned),
+            created_at: datetime!(2025-03-01 18:00 UTC),
+        }
+    }
+
+    #[test]
+    fn suggestion_review_message() {
+        assert_eq!(
+            "Your suggestion #3 \"A Rust quiz night\" was accepted",
+            suggestion_reviewed(&suggestion(3, SuggestionStatus::Accepted, None))
+        );
+        assert_eq!(
+            "Your suggestion #4 \"A Rust quiz night\" was rejected: we did that last week",
+            suggestion_reviewed(&suggestion(
+                4,
+                SuggestionStatus::Rejected,
+                Some("we did that last week")
+            ))
+        );
+    }
+
+    fn crate_info(name: &str, downloads: u64) -> CrateInfo {
+        CrateInfo {
+            name: name.to_owned(),
+            updated_at: datetime!(2024-10-27 18:45 UTC),
+            downloads,
+            newest_version: "1.2.3".to_owned(),
+            description: format!("The {name} crate"),
+            documentation: None,
+            repository: format!("https://github.com/rust-lang/{name}"),
+            version_count: 42,
+            dependents: None,
+        }
+    }
+
+    fn version_info(features: BTreeMap<String, Vec<String>>) -> VersionInfo {
+        VersionInfo {
+            name: "tokio".to_owned(),
+            num: semver::Version::new(1, 38, 1),
+            created_at: datetime!(2024-07-16 09:30 UTC),
+            downloads: 1_234_567,
+            yanked: false,
+            license: Some("MIT".to_owned()),
+            rust_version: Some("1.63".to_owned()),
+            features,
+        }
+    }
+
+    fn status(stream: anyhow::Result<Option<StreamInfo>>) -> response::Status {
+        response::Status {
+            stream,
+            connectors: vec![
+                ConnectorHealth {
+                    source: Source::Discord,
+                    silent_secs: Some(5),
+                    stale: false,
+                },
+                ConnectorHealth {
+                    source: Source::Twitch,
+                    silent_secs: Some(900),
+                    stale: true,
+                },
+                ConnectorHealth {
+                    source: Source::YouTube,
+                    silent_secs: None,
+                    stale: true,
+                },
+            ],
+        }
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn users() -> Vec<(&'static str, response::User)> {
+        vec![
+            ("unknown", response::User::Unknown),
+            (
+                "help",
+                response::User::Help(Language::English.translate("help", &[])),
+            ),
+            (
+                "command_help",
+                response::User::CommandHelp(Ok(Some(response::CommandHelp {
+                    name: "crate".to_owned(),
+                    info: commands::find("crate"),
+                    sources: Source::ALL.to_vec(),
+                    cost: None,
+                    availability: Availability::Live,
+                    cooldown: Some((3, Duration::from_mins(1))),
+                }))),
+            ),
+            (
+                "command_help_custom",
+                response::User::CommandHelp(Ok(Some(response::CommandHelp {
+                    name: "hug".to_owned(),
+                    info: None,
+                    sources: vec![Source::Discord, Source::Twitch],
+                    cost: Some(50),
+                    availability: Availability::Always,
+                    cooldown: Some((3, Duration::from_mins(1))),
+                }))),
+            ),
+            (
+                "command_help_unknown",
+                response::User::CommandHelp(Ok(None)),
+            ),
+            (
+                "commands",
+                response::User::Commands {
+                    builtin: commands::listed(Access::Standard)
+                        .filter(|cmd| cmd.available_on(Source::Twitch))
+                        .collect(),
+                    custom: Ok(vec!["hello".to_owned(), "hug".to_owned()]),
+                },
+            ),
+            (
+                "commands_error",
+                response::User::Commands {
+                    builtin: Vec::new(),
+                    custom: Err(anyhow!("broken")),
+                },
+            ),
+            ("links", response::User::Links(links(None, None))),
+            (
+                "links_template",
+                response::User::Links(links(Some("{name} → {url}"), None)),
+            ),
+            (
+                "links_profile",
+                response::User::Links(links(Some("{icon} {name} {url}"), Some(" ~ "))),
+            ),
+            (
+                "ban",
+                response::User::Ban("tabs, YOU SHALL NOT PASS!!".to_owned()),
+            ),
+            (
+                "crate",
+                response::User::Crate(Ok(CrateSearch::Found(crate_info("serde", 250_000_000)))),
+            ),
+            (
+                "crate_dependents",
+                response::User::Crate(Ok(CrateSearch::Found(CrateInfo {
+                    dependents: Some(Dependents {
+                        total: 45_678,
+                        top: vec!["serde_json".to_owned(), "toml".to_owned()],
+                    }),
+                    ..crate_info("serde", 250_000_000)
+                }))),
+            ),
+            (
+                "crate_fallback",
+                response::User::Crate(Ok(CrateSearch::Fallback {
+                    name: "serde".to_owned(),
+                    version: "1.0.210".to_owned(),
+                })),
+            ),
+            (
+                "crate_not_found",
+                response::User::Crate(Ok(CrateSearch::NotFound(
+                    "Crate `nope` not found".to_owned(),
+                ))),
+            ),
+            ("crate_error", response::User::Crate(Err(anyhow!("broken")))),
+            (
+                "rust_versions",
+                response::User::RustVersions(Ok(response::RustVersions {
+                    stable: response::RustVersion {
+                        version: "1.82.0".to_owned(),
+                        date: "2024-10-17".to_owned(),
+                    },
+                    beta: response::RustVersion {
+                        version: "1.83.0-beta.3".to_owned(),
+                        date: "2024-10-29".to_owned(),
+                    },
+                    nightly: response::RustVersion {
+                        version: "1.84.0-nightly".to_owned(),
+                        date: "2024-11-01".to_owned(),
+                    },
+                })),
+            ),
+            (
+                "rust_versions_error",
+                response::User::RustVersions(Err(anyhow!("broken"))),
+            ),
+            (
+                "eval",
+                response::User::Eval(Ok(response::Eval::Ran {
+                    success: true,
+                    output: "Hello, world!\n[1, 2, 3]".to_owned(),
+                })),
+            ),
+            (
+                "eval_failed",
+                response::User::Eval(Ok(response::Eval::Ran {
+                    success: false,
+                    output: "error[E0308]: mismatched types".to_owned(),
+                })),
+            ),
+            (
+                "eval_no_output",
+                response::User::Eval(Ok(response::Eval::Ran {
+                    success: true,
+                    output: String::new(),
+                })),
+            ),
+            (
+                "eval_too_long",
+                response::User::Eval(Ok(response::Eval::TooLong { max: 1000 })),
+            ),
+            (
+                "eval_cooldown",
+                response::User::Eval(Ok(response::Eval::Cooldown(Duration::from_secs(42)))),
+            ),
+            (
+                "crate_compare",
+                response::User::CrateCompare(Ok((
+                    CrateSearch::Found(crate_info("tokio", 2_500)),
+                    CrateSearch::Found(crate_info("smol", 900)),
+                ))),
+            ),
+            (
+                "crate_compare_not_found",
+                response::User::CrateCompare(Ok((
+                    CrateSearch::Found(crate_info("tokio", 2_500)),
+                    CrateSearch::NotFound("Crate `nope` not found".to_owned()),
+                ))),
+            ),
+            (
+                "crate_version",
+                response::User::CrateVersion(Ok(VersionSearch::Found(version_info(
+                    BTreeMap::new(),
+                )))),
+            ),
+            (
+                "crate_version_not_found",
+                response::User::CrateVersion(Ok(VersionSearch::NotFound(
+                    "Crate `tokio` has no version `0.9`".to_owned(),
+                ))),
+            ),
+            (
+                "crate_features",
+                response::User::CrateFeatures(Ok(VersionSearch::Found(version_info(
+                    BTreeMap::from([
+                        ("default".to_owned(), vec!["rt".to_owned()]),
+                        ("macros".to_owned(), vec!["dep:tokio-macros".to_owned()]),
+                        ("rt".to_owned(), vec![]),
+                    ]),
+                )))),
+            ),
+            (
+                "crate_features_none",
+                response::User::CrateFeatures(Ok(VersionSearch::Found(version_info(
+                    BTreeMap::new(),
+                )))),
+            ),
+            (
+                "today",
+                response::User::Today("Today is Saturday, 2 November 2024".to_owned()),
+            ),
+            (
+                "status_live",
+                response::User::Status(status(Ok(Some(StreamInfo {
+                    id: "1".to_owned(),
+                    started_at: datetime!(2024-11-02 14:00 UTC),
+                    title: "Writing a bot".to_owned(),
+                    category: "Software and Game Development".to_owned(),
+                })))),
+            ),
+            ("status_offline", response::User::Status(status(Ok(None)))),
+            (
+                "botstat",
+                response::User::BotStat(response::BotStat {
+                    runtime: RuntimeInfo {
+                        version: "0.1.0",
+                        commit: "abc1234",
+                        uptime: Duration::from_mins(2 * 1_440 + 3 * 60 + 4),
+                        messages: 1_234,
+                    },
+                    connectors: vec![Source::Discord, Source::Twitch],
+                    db_size: Ok(1_258_291),
+                    latency: {
+                        let timings = Timings::default();
+                        timings.record_command("help", Duration::from_millis(4));
+                        timings.record_command("crate", Duration::from_millis(180));
+                        timings.latency()
+                    },
+                }),
+            ),
+            (
+                "song_request",
+                response::User::SongRequest(Ok(SongRequest::Added { position: 3 })),
+            ),
+            (
+                "song_request_refused",
+                response::User::SongRequest(Ok(SongRequest::Refused(
+                    "The queue is full, try again later".to_owned(),
+                ))),
+            ),
+            (
+                "song_queue",
+                response::User::SongQueue(Ok((1..=7).map(queued_song).collect())),
+            ),
+            (
+                "song_queue_empty",
+                response::User::SongQueue(Ok(Vec::new())),
+            ),
+            (
+                "status_error",
+                response::User::Status(status(Err(anyhow!("broken")))),
+            ),
+            (
+                "ftoc",
+                response::User::FahrenheitToCelsius("100.0°F = 37.8°C".to_owned()),
+            ),
+            (
+                "ctof",
+                response::User::CelsiusToFahrenheit("37.8°C = 100.0°F".to_owned()),
+            ),
+            ("points", response::User::Points(Ok(42))),
+            (
+                "points_error",
+                response::User::Points(Err(anyhow!("broken"))),
+            ),
+            (
+                "gamble_won",
+                response::User::Gamble(Ok(response::Gamble::Won {
+                    amount: 10,
+                    balance: 52,
+                })),
+            ),
+            (
+                "gamble_lost",
+                response::User::Gamble(Ok(response::Gamble::Lost {
+                    amount: 10,
+                    balance: 32,
+                })),
+            ),
+            (
+                "duel_challenged",
+                response::User::Duel(Ok(response::Duel::Challenged {
+                    opponent: "@somebody".to_owned(),
+                    amount: 20,
+                })),
+            ),
+            (
+                "duel_won",
+                response::User::Duel(Ok(response::Duel::Won {
+                    opponent: "@somebody".to_owned(),
+                    amount: 20,
+                })),
+            ),
+            (
+                "duel_lost",
+                response::User::Duel(Ok(response::Duel::Lost {
+                    opponent: "@somebody".to_owned(),
+                    amount: 20,
+                })),
+            ),
+            (
+                "duel_refused",
+                response::User::Duel(Ok(response::Duel::Refused(
+                    "You can't duel yourself".to_owned(),
+                ))),
+            ),
+            (
+                "poll_started",
+                response::User::Poll(Ok(Poll {
+                    kind: PollKind::Poll,
+                    question: "Best crate?".to_owned(),
+                    options: vec!["serde".to_owned(), "tokio".to_owned()],
+                    duration: Duration::from_secs(90),
+                })),
+            ),
+            (
+                "poll_failed",
+                response::User::Poll(Err(anyhow!("only moderators can start a poll"))),
+            ),
+            ("notify_me_on", response::User::NotifyMe(Ok(true))),
+            ("notify_me_off", response::User::NotifyMe(Ok(false))),
+            (
+                "watch_time",
+                response::User::WatchTime(Ok(WatchTime::Tracked {
+                    month: Duration::from_mins(150),
+                    total: Duration::from_hours(26),
+                })),
+            ),
+            (
+                "watch_time_opted_out",
+                response::User::WatchTime(Ok(WatchTime::OptedOut)),
+            ),
+            (
+                "watch_time_off",
+                response::User::WatchTime(Ok(WatchTime::Changed(false))),
+            ),
+            (
+                "yesterday",
+                response::User::Yesterday(Ok(vec![
+                    stream_title("Rust", datetime!(2025-03-01 18:00 UTC)),
+                    stream_title("Minecraft", datetime!(2025-03-01 20:30 UTC)),
+                ])),
+            ),
+            ("yesterday_none", response::User::Yesterday(Ok(Vec::new()))),
+            ("suggest", response::User::Suggest(Ok(Suggest::Added(3)))),
+            (
+                "suggest_refused",
+                response::User::Suggest(Ok(Suggest::Refused(
+                    "You already have 3 suggestions waiting for a review".to_owned(),
+                ))),
+            ),
+            (
+                "topic",
+                response::User::Topic(Ok(Some(Topic {
+                    content: "Rust quiz, then bug hunting".to_owned(),
+                    live: true,
+                }))),
+            ),
+            (
+                "topic_next",
+                response::User::Topic(Ok(Some(Topic {
+                    content: "Rust quiz, then bug hunting".to_owned(),
+                    live: false,
+                }))),
+            ),
+            ("topic_none", response::User::Topic(Ok(None))),
+            (
+                "last_category",
+                response::User::LastCategory(Ok(LastCategory::Past(stream_title(
+                    "Minecraft",
+                    datetime!(2025-03-01 20:30 UTC),
+                )))),
+            ),
+            (
+                "last_category_live",
+                response::User::LastCategory(Ok(LastCategory::Live(stream_title(
+                    "Rust",
+                    datetime!(2025-03-01 18:00 UTC),
+                )))),
+            ),
+            (
+                "last_category_never",
+                response::User::LastCategory(Ok(LastCategory::Never("Chess".to_owned()))),
+            ),
+            (
+                "away",
+                response::User::Away {
+                    streamer: "togglebit".to_owned(),
+                    message: "grabbing food, back in 20 minutes".to_owned(),
+                },
+            ),
+            (
+                "fun",
+                response::User::Fun {
+                    kind: Kind::Joke,
+                    content: Ok(
+                        "Why do programmers prefer dark mode? Because light attracts bugs."
+                            .to_owned(),
+                    ),
+                },
+            ),
+            (
+                "fun_failed",
+                response::User::Fun {
+                    kind: Kind::Fact,
+                    content: Err(anyhow!("service unavailable")),
+                },
+            ),
+            (
+                "birthday_registered",
+                response::User::Birthday(Ok(response::Birthday::Registered {
+                    month: time::Month::April,
+                    day: 2,
+                    announce: false,
+                })),
+            ),
+            (
+                "birthday_saved",
+                response::User::Birthday(Ok(response::Birthday::Saved {
+                    month: time::Month::February,
+                    day: 29,
+                })),
+            ),
+            (
+                "trigger",
+                response::User::Trigger(Some("Rust is awesome!".to_owned())),
+            ),
+            (
+                "faq",
+                response::User::Faq("It's a Keychron Q1 with brown switches.".to_owned()),
+            ),
+            (
+                "pronouns_found",
+                response::User::Pronouns(Ok(response::Pronouns::Found {
+                    user: "@somebody".to_owned(),
+                    pronouns: "she/they".to_owned(),
+                })),
+            ),
+            (
+                "pronouns_not_set",
+                response::User::Pronouns(Ok(response::Pronouns::NotSet("@somebody".to_owned()))),
+            ),
+            (
+                "prefs_current",
+                response::User::Prefs(Ok(response::Prefs::Current(UserPrefs {
+                    unit: Some(TemperatureUnit::Fahrenheit),
+                    timezone: Some(offset!(-5)),
+                    locale: None,
+                }))),
+            ),
+            (
+                "prefs_saved",
+                response::User::Prefs(Ok(response::Prefs::Saved(UserPref::Locale(
+                    Language::German,
+                )))),
+            ),
+            (
+                "lurk_started",
+                response::User::Lurk(Ok(response::Lurk::Started {
+                    name: "viewer".to_owned(),
+                    message: Some("making tea".to_owned()),
+                })),
+            ),
+            (
+                "lurk_ended",
+                response::User::Lurk(Ok(response::Lurk::Ended {
+                    name: "viewer".to_owned(),
+                    duration: Duration::from_mins(95),
+                })),
+            ),
+            (
+                "highlights",
+                response::User::Highlights(Ok(vec![
+                    highlight(),
+                    Highlight {
+                        guild: None,
+                        message: NonZero::new(30).unwrap(),
+                        author: "streamer".to_owned(),
+                        content: "A really long message, that goes on and on and is cut off at \
+                                  some point.\nWith a second line."
+                            .to_owned(),
+                        ..highlight()
+                    },
+                ])),
+            ),
+            (
+                "highlights_empty",
+                response::User::Highlights(Ok(Vec::new())),
+            ),
+            (
+                "not_enough_points",
+                response::User::NotEnoughPoints {
+                    cost: 50,
+                    balance: 12,
+                },
+            ),
+            (
+                "custom",
+                response::User::Custom(Ok("Hello there!".to_owned())),
+            ),
+            (
+                "custom_error",
+                response::User::Custom(Err(anyhow!("broken"))),
+            ),
+        ]
+    }
+
+    fn queued_song(id: i64) -> QueuedSong {
+        QueuedSong {
+            id,
+            platform: if id % 2 == 0 {
+                SongPlatform::Spotify
+            } else {
+                SongPlatform::YouTube
+            },
+            url: format!("https://youtu.be/song{id:0>7}"),
+            source: if id % 2 == 0 {
+                Source::Discord
+            } else {
+                Source::Twitch
+            },
+            user_id: "42".to_owned(),
+            requested_at: datetime!(2024-11-02 14:00 UTC),
+        }
+    }
+
+    fn highlight() -> Highlight {
+        Highlight {
+            guild: NonZero::new(10),
+            channel: NonZero::new(20).unwrap(),
+            message: NonZero::new(29).unwrap(),
+            author: "viewer".to_owned(),
+            content: "Ferris is the best crab".to_owned(),
+        }
+    }
+
+    fn timings() -> Vec<(Stage, Histogram)> {
+        let timings = Timings::default();
+        for ms in [1, 2, 2, 3, 40] {
+            timings.record(Stage::Queue, Duration::from_millis(ms));
+            timings.record(Stage::Handler, Duration::from_millis(ms * 10));
+        }
+        timings.report()
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn admins() -> Vec<(&'static str, response::Admin)> {
+        let stats = || Statistics {
+            command_usage: CommandUsage {
+                builtin: Ok(IndexMap::from([
+                    (BuiltinCommand::Crate, 12),
+                    (BuiltinCommand::Help, 3),
+                ])),
+                custom: Ok(IndexMap::from([("hello".to_owned(), 5)])),
+                unknown: Err(anyhow!("broken")),
+                spam: Ok(IndexMap::from([("links".to_owned(), 7)])),
+            },
+            watch_time: Ok(IndexMap::from([
+                ("Somebody".to_owned(), 18_300),
+                ("Viewer".to_owned(), 900),
+            ])),
+        };
+        let user = || UserRef {
+            source: Source::Twitch,
+            id: "somebody".to_owned(),
+        };
+
+        vec![
+            ("help", response::Admin::Help),
+            (
+                "custom_commands_list",
+                response::Admin::CustomCommands(response::CustomCommands::List(Ok(
+                    BTreeMap::from([
+                        (
+                            "hello".to_owned(),
+                            vec![custom_entry(Source::Discord, true)],
+                        ),
+                        (
+                            "hug".to_owned(),
+                            vec![
+                                custom_entry(Source::Twitch, false),
+                                custom_entry(Source::YouTube, false),
+                            ],
+                        ),
+                    ]),
+                ))),
+            ),
+            (
+                "custom_commands_show",
+                response::Admin::CustomCommands(response::CustomCommands::Show(
+                    "hello".to_owned(),
+                    Ok(vec![
+                        custom_entry(Source::Discord, true),
+                        custom_entry(Source::Twitch, false),
+                    ]),
+                )),
+            ),
+            (
+                "custom_commands_show_missing",
+                response::Admin::CustomCommands(response::CustomCommands::Show(
+                    "hello".to_owned(),
+                    Ok(Vec::new()),
+                )),
+            ),
+            (
+                "custom_commands_history",
+                response::Admin::CustomCommands(response::CustomCommands::History(
+                    "hello".to_owned(),
+                    Ok(vec![
+                        CommandRevision {
+                            id: 2,
+                            source: Source::Discord,
+                            content: None,
+                            author: UserRef {
+                                source: Source::Discord,
+                                id: "42".to_owned(),
+                            },
+                            changed_at: datetime!(2024-11-02 12:00 UTC),
+                        },
+                        CommandRevision {
+                            id: 1,
+                            source: Source::Discord,
+                            content: Some("Hello there!".to_owned()),
+                            author: user(),
+                            changed_at: datetime!(2024-11-01 12:00 UTC),
+                        },
+                    ]),
+                )),
+            ),
+            (
+                "custom_commands_history_empty",
+                response::Admin::CustomCommands(response::CustomCommands::History(
+                    "hello".to_owned(),
+                    Ok(Vec::new()),
+                )),
+            ),
+            (
+                "custom_commands_edit",
+                response::Admin::CustomCommands(response::CustomCommands::Edit(Ok(Vec::new()))),
+            ),
+            (
+                "custom_commands_edit_mentions",
+                response::Admin::CustomCommands(response::CustomCommands::Edit(Ok(vec![
+                    MassMention::Everyone,
+                    MassMention::Role,
+                ]))),
+            ),
+            (
+                "custom_commands_error",
+                response::Admin::CustomCommands(response::CustomCommands::Edit(Err(anyhow!(
+                    "broken"
+                )))),
+            ),
+            (
+                "statistics_total",
+                response::Admin::Statistics(StatisticsDate::Total, stats()),
+            ),
+            (
+                "statistics_current",
+                response::Admin::Statistics(StatisticsDate::Current, stats()),
+            ),
+            (
+                "statistics_tag",
+                response::Admin::Statistics(StatisticsDate::Tag("async".to_owned()), stats()),
+            ),
+            (
+                "statistics_builtin_error",
+                response::Admin::Statistics(StatisticsDate::Current, {
+                    let mut stats = stats();
+                    stats.command_usage.builtin = Err(anyhow!("broken"));
+                    stats
+                }),
+            ),
+            ("stream_tag", response::Admin::StreamTag(Ok(()))),
+            ("timing", response::Admin::Timing(timings())),
+            (
+                "user_notes_list",
+                response::Admin::UserNotes(response::UserNotes::List(
+                    user(),
+                    Ok(vec![UserNote {
+                        id: 7,
+                        content: "Asked nicely about unsafe".to_owned(),
+                        author: AdminId::new(42).unwrap(),
+                        created_at: datetime!(2024-11-01 12:00 UTC),
+                    }]),
+                )),
+            ),
+            (
+                "user_notes_list_empty",
+                response::Admin::UserNotes(response::UserNotes::List(user(), Ok(Vec::new()))),
+            ),
+            (
+                "user_notes_list_error",
+                response::Admin::UserNotes(response::UserNotes::List(
+                    user(),
+                    Err(anyhow!("broken")),
+                )),
+            ),
+            (
+                "user_notes_add",
+                response::Admin::UserNotes(response::UserNotes::Add(Ok(7))),
+            ),
+            (
+                "user_notes_remove",
+                response::Admin::UserNotes(response::UserNotes::Remove(Ok(()))),
+            ),
+            (
+                "skip_song",
+                response::Admin::SkipSong(Ok(Some(queued_song(1)))),
+            ),
+            ("skip_song_empty", response::Admin::SkipSong(Ok(None))),
+            ("shoutout_on", response::Admin::Shoutout(Ok(true))),
+            ("shoutout_off", response::Admin::Shoutout(Ok(false))),
+            ("away", response::Admin::Away(Ok(()))),
+            (
+                "back",
+                response::Admin::Back(Ok(Some(Duration::from_mins(42)))),
+            ),
+            ("back_not_away", response::Admin::Back(Ok(None))),
+            (
+                "command_costs_list",
+                response::Admin::CommandCosts(response::CommandCosts::List(Ok(vec![
+                    ("hug".to_owned(), 50),
+                    ("wave".to_owned(), 5),
+                ]))),
+            ),
+            (
+                "command_costs_list_empty",
+                response::Admin::CommandCosts(response::CommandCosts::List(Ok(Vec::new()))),
+            ),
+            (
+                "command_availability_list",
+                response::Admin::CommandAvailability(response::CommandAvailability::List(Ok(
+                    vec![
+                        ("hello".to_owned(), Availability::Offline),
+                        ("sr".to_owned(), Availability::Live),
+                    ],
+                ))),
+            ),
+            (
+                "command_availability_list_empty",
+                response::Admin::CommandAvailability(response::CommandAvailability::List(Ok(
+                    Vec::new(),
+                ))),
+            ),
+            (
+                "command_costs_edit",
+                response::Admin::CommandCosts(response::CommandCosts::Edit(Ok(()))),
+            ),
+            (
+                "usage_alerts_list",
+                response::Admin::UsageAlerts(response::UsageAlerts::List(Ok(vec![
+                    ("help".to_owned(), 20),
+                    ("unknown".to_owned(), 100),
+                ]))),
+            ),
+            (
+                "usage_alerts_list_empty",
+                response::Admin::UsageAlerts(response::UsageAlerts::List(Ok(Vec::new()))),
+            ),
+            (
+                "triggers_list",
+                response::Admin::Triggers(response::Triggers::List(Ok(vec![
+                    response::Trigger {
+                        word: "rust".to_owned(),
+                        content: "Rust is awesome!".to_owned(),
+                        cooldown: 60,
+                        disabled: vec![Source::Twitch],
+                    },
+                    response::Trigger {
+                        word: "o7".to_owned(),
+                        content: "o7".to_owned(),
+                        cooldown: 10,
+                        disabled: Vec::new(),
+                    },
+                ]))),
+            ),
+            (
+                "triggers_list_empty",
+                response::Admin::Triggers(response::Triggers::List(Ok(Vec::new()))),
+            ),
+            (
+                "triggers_edit",
+                response::Admin::Triggers(response::Triggers::Edit(Ok(()))),
+            ),
+            (
+                "faqs_list",
+                response::Admin::Faqs(response::Faqs::List(Ok(vec![
+                    response::Faq {
+                        name: "editor".to_owned(),
+                        pattern: r"what (editor|ide)".to_owned(),
+                        regex: true,
+                        answer: "Helix, of course.".to_owned(),
+                    },
+                    response::Faq {
+                        name: "keyboard".to_owned(),
+                        pattern: "which,keyboard".to_owned(),
+                        regex: false,
+                        answer: "It's a Keychron Q1 with brown switches.".to_owned(),
+                    },
+                ]))),
+            ),
+            (
+                "faqs_list_empty",
+                response::Admin::Faqs(response::Faqs::List(Ok(Vec::new()))),
+            ),
+            (
+                "faqs_edit",
+                response::Admin::Faqs(response::Faqs::Edit(Ok(()))),
+            ),
+            (
+                "watch_crates_list",
+                response::Admin::WatchCrates(response::WatchCrates::List(Ok(vec![
+                    response::WatchedCrate {
+                        name: "serde".to_owned(),
+                        version: "1.0.215".to_owned(),
+                        etag: None,
+                    },
+                    response::WatchedCrate {
+                        name: "tokio".to_owned(),
+                        version: "1.42.0".to_owned(),
+                        etag: Some("\"abc\"".to_owned()),
+                    },
+                ]))),
+            ),
+            (
+                "watch_crates_list_empty",
+                response::Admin::WatchCrates(response::WatchCrates::List(Ok(Vec::new()))),
+            ),
+            (
+                "watch_crates_add",
+                response::Admin::WatchCrates(response::WatchCrates::Add(Ok(
+                    response::WatchedCrate {
+                        name: "tokio".to_owned(),
+                        version: "1.42.0".to_owned(),
+                        etag: None,
+                    },
+                ))),
+            ),
+            (
+                "watch_crates_remove",
+                response::Admin::WatchCrates(response::WatchCrates::Remove(Ok(()))),
+            ),
+            (
+                "channels_list",
+                response::Admin::Channels(response::Channels::List(Ok(vec![
+                    (NonZero::new(10).unwrap(), ChannelRule::Allow),
+                    (NonZero::new(11).unwrap(), ChannelRule::Deny),
+                ]))),
+            ),
+            (
+                "channels_list_empty",
+                response::Admin::Channels(response::Channels::List(Ok(Vec::new()))),
+            ),
+            (
+                "channels_edit",
+                response::Admin::Channels(response::Channels::Edit(Ok(()))),
+            ),
+            (
+                "silence",
+                response::Admin::Silence(Ok(Some(Duration::from_mins(30)))),
+            ),
+            ("silence_off", response::Admin::Silence(Ok(None))),
+            (
+                "command_list",
+                response::Admin::CommandList(Ok(NonZero::new(123))),
+            ),
+            ("command_list_off", response::Admin::CommandList(Ok(None))),
+            ("pin", response::Admin::Pin(Ok(highlight()))),
+            ("nothing_to_confirm", response::Admin::NothingToConfirm),
+            (
+                "link_preview",
+                response::Admin::LinkPreview {
+                    source: Source::Twitch,
+                    links: links(None, None),
+                },
+            ),
+            (
+                "link_preview_empty",
+                response::Admin::LinkPreview {
+                    source: Source::Twitch,
+                    links: response::Links {
+                        links: Vec::new(),
+                        template: None,
+                        separator: None,
+                    },
+                },
+            ),
+            ("check_links", response::Admin::CheckLinks(Ok(dead_links()))),
+            (
+                "check_links_none",
+                response::Admin::CheckLinks(Ok(Vec::new())),
+            ),
+            ("chapters", response::Admin::Chapters(Ok(stream_chapters()))),
+            ("chapters_none", response::Admin::Chapters(Ok(Vec::new()))),
+            (
+                "suggestions_list",
+                response::Admin::Suggestions(response::Suggestions::List(Ok(vec![
+                    suggestion(3, SuggestionStatus::Open, None),
+                    suggestion(4, SuggestionStatus::Open, None),
+                ]))),
+            ),
+            (
+                "suggestions_list_none",
+                response::Admin::Suggestions(response::Suggestions::List(Ok(Vec::new()))),
+            ),
+            (
+                "suggestions_review",
+                response::Admin::Suggestions(response::Suggestions::Review(Ok(suggestion(
+                    3,
+                    SuggestionStatus::Accepted,
+                    None,
+                )))),
+            ),
+            ("topic_set", response::Admin::Topic(Ok(TopicUpdate::Live))),
+            (
+                "topic_set_next",
+                response::Admin::Topic(Ok(TopicUpdate::Next)),
+            ),
+            (
+                "topic_cleared",
+                response::Admin::Topic(Ok(TopicUpdate::Cleared)),
+            ),
+            (
+                "issue_created",
+                response::Admin::Issue(Ok(response::Issue::Created(github_issue()))),
+            ),
+            (
+                "issue_existing",
+                response::Admin::Issue(Ok(response::Issue::Existing(github_issue()))),
+            ),
+        ]
+    }
+
+    fn github_issue() -> response::GitHubIssue {
+        response::GitHubIssue {
+            number: 42,
+            url: "https://github.com/dnaka91/togglebot/issues/42".to_owned(),
+            title: "Suggestion: A Rust quiz night".to_owned(),
+        }
+    }
+
+    fn stream_chapters() -> Vec<response::StreamChapters> {
+        let chapter = |offset, title: &str, category: &str| response::Chapter {
+            offset: Duration::from_secs(offset),
+            title: title.to_owned(),
+            category: category.to_owned(),
+        };
+
+        vec![
+            response::StreamChapters {
+                started_at: datetime!(2025-03-01 18:00 UTC),
+                chapters: vec![
+                    chapter(0, "Building a bot", "Software and Game Development"),
+                    chapter(1_290, "Fixing the tests", "Software and Game Development"),
+                    chapter(5_430, "Games", "Minecraft"),
+                ],
+            },
+            response::StreamChapters {
+                started_at: datetime!(2025-03-01 23:30 UTC),
+                chapters: vec![chapter(0, "Late night art", "Art")],
+            },
+        ]
+    }
+
+    fn dead_links() -> Vec<response::DeadLink> {
+        vec![
+            response::DeadLink {
+                origin: response::LinkOrigin::Links("GitHub".to_owned()),
+                url: "https://github.com/dnaka91".to_owned(),
+                reason: "status 404 Not Found".to_owned(),
+            },
+            response::DeadLink {
+                origin: response::LinkOrigin::Command {
+                    name: "discord".to_owned(),
+                    source: Source::Twitch,
+                },
+                url: "https://discord.gg/abc".to_owned(),
+                reason: "timed out".to_owned(),
+            },
+        ]
+    }
+
+    fn links(template: Option<&str>, separator: Option<&str>) -> response::Links {
+        response::Links {
+            links: vec![
+                response::Link {
+                    name: "GitHub".to_owned(),
+                    url: "https://github.com/togglebyte".to_owned(),
+                    icon: Some("🐙".to_owned()),
+                },
+                response::Link {
+                    name: "Twitch".to_owned(),
+                    url: "https://twitch.tv/togglebit".to_owned(),
+                    icon: None,
+                },
+            ],
+            template: template.map(Into::into),
+            separator: separator.map(Into::into),
+        }
+    }
+
+    fn owners() -> Vec<(&'static str, response::Owner)> {
+        vec![
+            ("help", response::Owner::Help),
+            (
+                "admins_list",
+                response::Owner::Admins(response::Admins::List {
+                    users: vec![AdminId::new(42).unwrap(), AdminId::new(43).unwrap()],
+                    roles: Vec::new(),
+                }),
+            ),
+            (
+                "admins_list_roles",
+                response::Owner::Admins(response::Admins::List {
+                    users: vec![AdminId::new(42).unwrap()],
+                    roles: vec![NonZero::new(7).unwrap()],
+                }),
+            ),
+            (
+                "admin_role_added",
+                response::Owner::Admins(response::Admins::Edit(Ok(AdminAction::RoleAdded))),
+            ),
+            (
+                "admins_added",
+                response::Owner::Admins(response::Admins::Edit(Ok(AdminAction::Added))),
+            ),
+            (
+                "admins_removed",
+                response::Owner::Admins(response::Admins::Edit(Ok(AdminAction::Removed))),
+            ),
+            (
+                "admins_error",
+                response::Owner::Admins(response::Admins::Edit(Err(anyhow!("broken")))),
+            ),
+            (
+                "plugins_list",
+                response::Owner::Plugins(response::Plugins::List(vec![PluginInfo {
+                    name: "dice".to_owned(),
+                    commands: vec!["roll".to_owned(), "flip".to_owned()],
+                    loaded: true,
+                }])),
+            ),
+            (
+                "plugins_edit",
+                response::Owner::Plugins(response::Plugins::Edit(Ok(PluginAction::Loaded))),
+            ),
+            (
+                "debug_parse",
+                response::Owner::DebugParse("Ok(Some(User(Help)))".to_owned()),
+            ),
+            ("resync_commands", response::Owner::ResyncCommands),
+            (
+                "broadcast",
+                response::Owner::Broadcast("Stream starts in 5 minutes!".to_owned()),
+            ),
+            (
+                "cleanup",
+                response::Owner::Cleanup(Ok(Cleanup {
+                    rows: 42,
+                    reclaimed: 256 * 1024,
+                })),
+            ),
+            #[cfg(feature = "chaos")]
+            (
+                "chaos",
+                response::Owner::Chaos(crate::chaos::Faults {
+                    delay_rate: 10,
+                    ..crate::chaos::Faults::NONE
+                }),
+            ),
+        ]
+    }
+
+    /// Render all cases into a single document, with a heading for each case.
+    fn document<T>(cases: Vec<(&str, T)>, render: impl Fn(T) -> Option<String>) -> String {
+        cases
+            .into_iter()
+            .fold(String::new(), |mut doc, (name, resp)| {
+                let text = render(resp);
+                writeln!(
+                    &mut doc,
+                    "--- {name} ---\n{}\n",
+                    text.as_deref().unwrap_or("(no reply)")
+                )
+                .ok();
+                doc
+            })
+    }
+
+    /// Owner responses differ depending on the `chaos` feature, so they're stored separately.
+    fn owner_settings() -> Settings {
+        let mut settings = Settings::clone_current();
+        if cfg!(feature = "chaos") {
+            settings.set_snapshot_suffix("chaos");
+        }
+        settings
+    }
+
+    #[test]
+    fn plain_user() {
+        assert_snapshot!(document(users(), plain::user));
+    }
+
+    #[test]
+    fn plain_admin() {
+        assert_snapshot!(document(admins(), |resp| Some(plain::admin(resp))));
+    }
+
+    #[test]
+    fn plain_owner() {
+        owner_settings().bind(|| {
+            assert_snapshot!(document(owners(), |resp| Some(plain::owner(resp))));
+        });
+    }
+
+    #[test]
+    fn twitch_user() {
+        assert_snapshot!(document(users(), twitch::user));
+    }
+
+    #[test]
+    fn youtube_user() {
+        assert_snapshot!(document(users(), youtube::user));
+    }
+
+    #[test]
+    fn discord_user() {
+        let replies = users()
+            .into_iter()
+            .map(|(name, resp)| (name, discord::user(resp, "togglebit").unwrap()))
+            .collect::<IndexMap<_, _>>();
+        assert_yaml_snapshot!(replies);
+    }
+
+    #[test]
+    fn discord_admin() {
+        let replies = admins()
+            .into_iter()
+            .map(|(name, resp)| (name, discord::admin(resp)))
+            .collect::<IndexMap<_, _>>();
+        assert_yaml_snapshot!(replies);
+    }
+
+    #[test]
+    fn discord_owner() {
+        let replies = owners()
+            .into_iter()
+            .map(|(name, resp)| (name, discord::owner(resp)))
+            .collect::<IndexMap<_, _>>();
+        owner_settings().bind(|| assert_yaml_snapshot!(replies));
+    }
+
+    #[test]
+    fn confirm_prompt() {
+        assert_eq!(
+            "this will remove user 42 from the admin list, type !confirm within 30 seconds to go \
+             ahead",
+            plain::confirm(response::Confirm::RemoveAdmin(AdminId::new(42).unwrap()))
+        );
+
+        let reply = discord::confirm(response::Confirm::RemoveCustomCommand("hello".to_owned()));
+        assert!(reply.confirm);
+        assert!(!reply.notify);
+        assert_eq!(
+            "⚠ this will remove the custom command `hello` from all services, confirm it within \
+             30 seconds with the button below or `!confirm`",
+            reply.content
+        );
+    }
+
+    #[test]
+    fn poll_announcement() {
+        let results = |votes: [u64; 3]| {
+            ["serde", "tokio", "axum"]
+                .into_iter()
+                .zip(votes)
+                .map(|(option, votes)| (option.to_owned(), votes))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            "The poll \"Best crate?\" ended, tokio won with 5 of 8 votes (serde 2, tokio 5, axum \
+             1)",
+            poll_results("Best crate?", &results([2, 5, 1]))
+        );
+        assert_eq!(
+            "The poll \"Best crate?\" ended in a tie between serde and axum (serde 3, tokio 1, \
+             axum 3)",
+            poll_results("Best crate?", &results([3, 1, 3]))
+        );
+        assert_eq!(
+            "The poll \"Best crate?\" ended without any votes",
+            poll_results("Best crate?", &results([0, 0, 0]))
+        );
+    }
+}
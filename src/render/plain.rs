@@ -0,0 +1,900 @@
+//! Plain text rendering of responses, for connectors that don't support any rich formatting, like
+//! Matrix and the local console.
+
+use std::{collections::BTreeMap, fmt::Write, num::NonZero, time::Duration};
+
+use anyhow::Result;
+use tracing::error;
+
+use super::QUEUE_PREVIEW;
+use crate::{
+    api::{
+        request::StatisticsDate,
+        response::{
+            self, AdminAction, CommandRevision, CrateSearch, CustomCommandEntry, DeadLink, Eval,
+            Faq, LinkOrigin, MassMention, QueuedSong, StreamChapters, Trigger, WatchedCrate,
+        },
+        Highlight, Source,
+    },
+    commands,
+    handler::{Access, CONFIRM_TIMEOUT},
+    plugins::PluginInfo,
+    statistics::{CommandUsage, Statistics},
+    timing::{Histogram, Stage},
+};
+
+/// Characters of a snippet's output that are shown.
+const EVAL_OUTPUT: usize = 1500;
+
+/// Render any response as plain text message, or nothing if there is nothing to reply.
+#[must_use]
+pub fn response(resp: response::Response) -> Option<String> {
+    match resp {
+        response::Response::User(resp) => user(resp),
+        response::Response::Admin(resp) => Some(admin(resp)),
+        response::Response::Owner(resp) => Some(owner(resp)),
+        response::Response::Denied(required) => Some(super::denied(required)),
+        response::Response::Confirm(action) => Some(confirm(action)),
+    }
+}
+
+/// Render a user response as plain text message, or nothing if there is nothing to reply.
+#[must_use]
+pub fn user(resp: response::User) -> Option<String> {
+    Some(match resp {
+        response::User::Commands { builtin, custom } => super::command_names(&builtin, custom),
+        response::User::Links(links) => links_list(&links),
+        response::User::Crate(res) => crate_(res),
+        response::User::RustVersions(res) => super::rust_versions(res),
+        response::User::Eval(res) => eval(res),
+        response::User::CrateCompare(res) => crate_compare(res),
+        response::User::CrateVersion(res) => super::crate_version(res),
+        response::User::CrateFeatures(res) => super::crate_features(res),
+        response::User::Status(status) => status_(status),
+        response::User::BotStat(stat) => super::botstat(&stat),
+        response::User::SongRequest(res) => super::song_request(res),
+        response::User::SongQueue(res) => song_queue(res),
+        response::User::CommandHelp(res) => super::command_help(res),
+        response::User::Points(res) => super::points(res),
+        response::User::Gamble(res) => super::gamble(res),
+        response::User::Duel(res) => super::duel(res),
+        response::User::Poll(res) => super::poll(res),
+        response::User::NotifyMe(res) => super::notify_me(res),
+        response::User::WatchTime(res) => super::watch_time(res),
+        response::User::Yesterday(res) => super::yesterday(res),
+        response::User::LastCategory(res) => super::last_category(res),
+        response::User::Suggest(res) => super::suggest(res),
+        response::User::Topic(res) => super::topic(res),
+        response::User::Birthday(res) => super::birthday(res),
+        response::User::Fun { kind, content } => super::fun(kind, content),
+        response::User::Away { streamer, message } => super::away(&streamer, &message),
+        response::User::Trigger(Some(content)) | response::User::Faq(content) => content,
+        response::User::Pronouns(res) => super::pronouns(res),
+        response::User::Prefs(res) => super::prefs(res),
+        response::User::Lurk(res) => super::lurk(res),
+        response::User::Highlights(res) => super::highlights(res),
+        response::User::NotEnoughPoints { cost, balance } => {
+            super::not_enough_points(cost, balance)
+        }
+        response::User::Help(text)
+        | response::User::Ban(text)
+        | response::User::Today(text)
+        | response::User::FahrenheitToCelsius(text)
+        | response::User::CelsiusToFahrenheit(text) => text,
+        response::User::Custom(res) => match res {
+            Ok(content) => content,
+            Err(e) => {
+                error!(error = ?e, "failed finding custom command");
+                return None;
+            }
+        },
+        response::User::TimeoutMe
+        | response::User::FollowAge
+        | response::User::AccountAge
+        | response::User::TimeoutSpammer(_)
+        | response::User::Trigger(None)
+        | response::User::Unknown => return None,
+    })
+}
+
+pub(super) fn links_list(links: &response::Links) -> String {
+    super::links_list(links, "\n", |name, url| format!("{name}: {url}"))
+}
+
+fn eval(res: Result<Eval>) -> String {
+    super::eval(res, |success, output| {
+        format!(
+            "{}\n{}",
+            if success { "ok" } else { "failed" },
+            if output.is_empty() {
+                "(no output)".to_owned()
+            } else {
+                super::truncate(&output, EVAL_OUTPUT)
+            }
+        )
+    })
+}
+
+fn crate_(res: Result<CrateSearch>) -> String {
+    match res {
+        Ok(CrateSearch::Found(info)) => format!(
+            "{} v{}: {}\nhttps://crates.io/crates/{}",
+            info.name, info.newest_version, info.description, info.name
+        ),
+        Ok(CrateSearch::NotFound(message)) => message,
+        Ok(CrateSearch::Fallback { name, version }) => super::crate_fallback(&name, &version),
+        Err(e) => {
+            error!(error = ?e, "failed searching for crate");
+            "Sorry, something went wrong looking up the crate".to_owned()
+        }
+    }
+}
+
+fn crate_compare(res: Result<(CrateSearch, CrateSearch)>) -> String {
+    match res {
+        Ok((CrateSearch::Found(first), CrateSearch::Found(second))) => [first, second]
+            .iter()
+            .map(|info| {
+                format!(
+                    "{} v{}: {} downloads, {} versions, updated {}, docs {}",
+                    info.name,
+                    info.newest_version,
+                    info.downloads,
+                    info.version_count,
+                    info.updated_at.date(),
+                    info.docs_link(),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Ok((CrateSearch::NotFound(message), _) | (_, CrateSearch::NotFound(message))) => message,
+        Ok((CrateSearch::Fallback { .. }, _) | (_, CrateSearch::Fallback { .. })) => {
+            super::CRATES_IO_UNAVAILABLE.to_owned()
+        }
+        Err(e) => {
+            error!(error = ?e, "failed searching for crates");
+            "Sorry, something went wrong looking up the crates".to_owned()
+        }
+    }
+}
+
+fn song_queue(res: Result<Vec<QueuedSong>>) -> String {
+    match res {
+        Ok(songs) if songs.is_empty() => {
+            "The song queue is empty, request a song with !sr <link>".to_owned()
+        }
+        Ok(songs) => {
+            let mut message = songs.iter().take(QUEUE_PREVIEW).enumerate().fold(
+                String::from("Songs in the queue:"),
+                |mut list, (i, song)| {
+                    write!(
+                        list,
+                        "\n{}. {} ({})",
+                        i + 1,
+                        song.url,
+                        song.platform.as_ref()
+                    )
+                    .ok();
+                    list
+                },
+            );
+
+            if songs.len() > QUEUE_PREVIEW {
+                write!(message, "\n...and {} more", songs.len() - QUEUE_PREVIEW).ok();
+            }
+
+            message
+        }
+        Err(e) => {
+            error!(error = ?e, "failed listing song requests");
+            "Sorry, something went wrong fetching the song queue".to_owned()
+        }
+    }
+}
+
+fn status_(status: response::Status) -> String {
+    let mut message = match status.stream {
+        Ok(Some(info)) => format!(
+            "I'm up and running! The stream is live, streaming {} ({}).",
+            info.title, info.category
+        ),
+        Ok(None) => "I'm up and running! The stream is currently offline.".to_owned(),
+        Err(e) => {
+            error!(error = ?e, "failed getting stream status");
+            "Sorry, something went wrong fetching the status".to_owned()
+        }
+    };
+
+    for connector in status.connectors.iter().filter(|c| c.stale) {
+        message.push_str("\nConnection to ");
+        message.push_str(connector.source.as_ref());
+        message.push_str(" seems to be silent.");
+    }
+
+    message
+}
+
+/// Render an admin response as plain text message.
+///
+/// This includes private content like user notes, so it must only be used where nobody else
+/// can read along.
+#[must_use]
+pub fn admin(resp: response::Admin) -> String {
+    match resp {
+        response::Admin::Help => access_help("Admin", Access::Admin),
+        response::Admin::CustomCommands(response::CustomCommands::List(res)) => {
+            custom_commands(res)
+        }
+        response::Admin::CustomCommands(response::CustomCommands::Show(name, res)) => {
+            custom_command(&name, res)
+        }
+        response::Admin::CustomCommands(response::CustomCommands::History(name, res)) => {
+            command_history(&name, res)
+        }
+        response::Admin::CustomCommands(response::CustomCommands::Edit(res)) => {
+            custom_commands_edit(res)
+        }
+        response::Admin::Statistics(date, stats) => statistics(date, stats),
+        response::Admin::StreamTag(res) => done(res, "stream tagged"),
+        response::Admin::Timing(report) => timing(report),
+        response::Admin::UserNotes(resp) => user_notes(resp),
+        response::Admin::Away(res) => done(res, "the streamer is away now"),
+        response::Admin::Back(res) => match res {
+            Ok(Some(duration)) => {
+                format!(
+                    "welcome back, you were away for {}",
+                    super::uptime(duration)
+                )
+            }
+            Ok(None) => "the streamer wasn't away".to_owned(),
+            Err(e) => failed(&e),
+        },
+        response::Admin::NothingToConfirm => format!(
+            "there's nothing to confirm, or it took longer than {} seconds",
+            CONFIRM_TIMEOUT.as_secs()
+        ),
+        response::Admin::LinkPreview { source, links } => links_preview(source, &links),
+        response::Admin::CheckLinks(res) => dead_links(res),
+        response::Admin::Chapters(res) => chapters(res),
+        response::Admin::Suggestions(resp) => suggestions(resp),
+        response::Admin::Issue(res) => issue(res),
+        response::Admin::Topic(res) => match res {
+            Ok(update) => super::topic_update(update).to_owned(),
+            Err(e) => failed(&e),
+        },
+        response::Admin::Shoutout(res) => match res {
+            Ok(true) => "raiding channels get a shoutout".to_owned(),
+            Ok(false) => "raiding channels don't get a shoutout".to_owned(),
+            Err(e) => failed(&e),
+        },
+        response::Admin::SkipSong(res) => match res {
+            Ok(Some(song)) => format!("skipped {}", song.url),
+            Ok(None) => "the song queue is empty".to_owned(),
+            Err(e) => failed(&e),
+        },
+        response::Admin::CommandCosts(response::CommandCosts::List(res)) => match res {
+            Ok(costs) if costs.is_empty() => "all custom commands are free".to_owned(),
+            Ok(costs) => costs.into_iter().fold(
+                String::from("custom commands that cost points:"),
+                |mut list, (name, cost)| {
+                    write!(&mut list, "\n!{name}: {cost}").ok();
+                    list
+                },
+            ),
+            Err(e) => failed(&e),
+        },
+        response::Admin::CommandCosts(response::CommandCosts::Edit(res)) => {
+            done(res, "command cost updated")
+        }
+        response::Admin::UsageAlerts(resp) => usage_alerts(resp),
+        response::Admin::CommandAvailability(response::CommandAvailability::List(res)) => match res
+        {
+            Ok(list) if list.is_empty() => "all commands are always available".to_owned(),
+            Ok(list) => list.into_iter().fold(
+                String::from("commands that depend on the stream:"),
+                |mut list, (name, availability)| {
+                    write!(&mut list, "\n!{name}: {availability}").ok();
+                    list
+                },
+            ),
+            Err(e) => failed(&e),
+        },
+        response::Admin::CommandAvailability(response::CommandAvailability::Edit(res)) => {
+            done(res, "command availability updated")
+        }
+        response::Admin::Triggers(response::Triggers::List(res)) => triggers(res),
+        response::Admin::Triggers(response::Triggers::Edit(res)) => {
+            done(res, "trigger word updated")
+        }
+        response::Admin::Faqs(response::Faqs::List(res)) => faqs(res),
+        response::Admin::Faqs(response::Faqs::Edit(res)) => done(res, "FAQ entry updated"),
+        response::Admin::WatchCrates(resp) => watch_crates(resp),
+        response::Admin::Channels(resp) => channels(resp),
+        response::Admin::Silence(res) => silence(res),
+        response::Admin::CommandList(res) => command_list(res),
+        response::Admin::Pin(res) => pinned(res),
+    }
+}
+
+/// All forms of the commands of an access level on a single line.
+fn access_help(kind: &str, access: Access) -> String {
+    let forms = commands::listed(access)
+        .flat_map(|cmd| cmd.forms().map(|(form, _)| form))
+        .collect::<Vec<_>>();
+
+    format!("{kind} commands: {}", forms.join(", "))
+}
+
+fn user_notes(resp: response::UserNotes) -> String {
+    match resp {
+        response::UserNotes::List(user, Ok(notes)) if notes.is_empty() => {
+            format!("There are no notes about {user}")
+        }
+        response::UserNotes::List(user, Ok(notes)) => notes.into_iter().fold(
+            format!("Here are the notes about {user}:"),
+            |mut list, note| {
+                write!(
+                    &mut list,
+                    "\n#{} by {} on {}: {}",
+                    note.id,
+                    note.author,
+                    note.created_at.date(),
+                    note.content
+                )
+                .ok();
+                list
+            },
+        ),
+        response::UserNotes::List(_, Err(e)) => failed(&e),
+        response::UserNotes::Add(res) => match res {
+            Ok(id) => format!("note #{id} added"),
+            Err(e) => failed(&e),
+        },
+        response::UserNotes::Remove(res) => done(res, "note removed"),
+    }
+}
+
+fn custom_commands(res: Result<BTreeMap<String, Vec<CustomCommandEntry>>>) -> String {
+    match res {
+        Ok(list) => list.into_iter().fold(
+            String::from("available custom commands:"),
+            |mut list, (name, entries)| {
+                let sources = entries
+                    .iter()
+                    .map(|entry| entry.source.as_ref())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(&mut list, "\n!{name} ({sources})").ok();
+                if let Some(creator) = super::command_creator(&entries) {
+                    write!(&mut list, " by {creator}").ok();
+                }
+                list
+            },
+        ),
+        Err(e) => failed(&e),
+    }
+}
+
+fn custom_command(name: &str, res: Result<Vec<CustomCommandEntry>>) -> String {
+    match res {
+        Ok(entries) if entries.is_empty() => format!("there is no custom command named !{name}"),
+        Ok(entries) => {
+            entries
+                .into_iter()
+                .fold(format!("details of !{name}:"), |mut list, entry| {
+                    write!(
+                        &mut list,
+                        "\n{}: {}\n",
+                        entry.source.as_ref(),
+                        entry.content
+                    )
+                    .ok();
+                    match (entry.created_by, entry.created_at) {
+                        (Some(user), Some(at)) => {
+                            write!(&mut list, "created by {user} on {}", at.date())
+                        }
+                        _ => write!(&mut list, "created before creators were recorded"),
+                    }
+                    .ok();
+                    if let (Some(user), Some(at)) = (entry.updated_by, entry.updated_at) {
+                        write!(&mut list, ", last changed by {user} on {}", at.date()).ok();
+                    }
+                    list
+                })
+        }
+        Err(e) => failed(&e),
+    }
+}
+
+fn pinned(res: Result<Highlight>) -> String {
+    match res {
+        Ok(highlight) => format!(
+            "pinned the message of {} as highlight: {}",
+            highlight.author,
+            highlight.link()
+        ),
+        Err(e) => failed(&e),
+    }
+}
+
+fn channels(resp: response::Channels) -> String {
+    match resp {
+        response::Channels::List(Ok(rules)) if rules.is_empty() => {
+            "user commands work in all channels".to_owned()
+        }
+        response::Channels::List(Ok(rules)) => rules.into_iter().fold(
+            String::from("channel rules for user commands:"),
+            |mut list, (channel, rule)| {
+                write!(&mut list, "\n{channel}: {rule}").ok();
+                list
+            },
+        ),
+        response::Channels::List(Err(e)) => failed(&e),
+        response::Channels::Edit(res) => done(res, "channel rules updated"),
+    }
+}
+
+fn dead_links(res: Result<Vec<DeadLink>>) -> String {
+    match res {
+        Ok(dead) if dead.is_empty() => "all links work".to_owned(),
+        Ok(dead) => dead.into_iter().fold(
+            String::from("links that don't work anymore:"),
+            |mut list,
+             DeadLink {
+                 origin,
+                 url,
+                 reason,
+             }| {
+                let origin = match origin {
+                    LinkOrigin::Links(name) => format!("link {name}"),
+                    LinkOrigin::Command { name, source } => format!("!{name} on {source}"),
+                };
+                write!(&mut list, "\n{origin}: {url} ({reason})").ok();
+                list
+            },
+        ),
+        Err(e) => failed(&e),
+    }
+}
+
+fn chapters(res: Result<Vec<StreamChapters>>) -> String {
+    match res {
+        Ok(streams) if streams.is_empty() => "no streams found".to_owned(),
+        Ok(streams) => super::chapter_list(&streams).trim_end().to_owned(),
+        Err(e) => failed(&e),
+    }
+}
+
+fn suggestions(resp: response::Suggestions) -> String {
+    match resp {
+        response::Suggestions::List(Ok(suggestions)) if suggestions.is_empty() => {
+            "there are no suggestions waiting for a review".to_owned()
+        }
+        response::Suggestions::List(Ok(suggestions)) => suggestions.into_iter().fold(
+            String::from("suggestions waiting for a review:"),
+            |mut list, suggestion| {
+                write!(
+                    &mut list,
+                    "\n#{} by {} ({}) on {}: {}",
+                    suggestion.id,
+                    suggestion.name,
+                    suggestion.source,
+                    suggestion.created_at.date(),
+                    suggestion.content
+                )
+                .ok();
+                list
+            },
+        ),
+        response::Suggestions::Review(Ok(suggestion)) => format!(
+            "suggestion #{} {}, {} gets a message about it",
+            suggestion.id,
+            super::suggestion_status(suggestion.status),
+            suggestion.name
+        ),
+        response::Suggestions::List(Err(e)) | response::Suggestions::Review(Err(e)) => failed(&e),
+    }
+}
+
+fn timing(report: Vec<(Stage, Histogram)>) -> String {
+    report.into_iter().fold(
+        String::from("Here is the time spent in each stage of the message handling:"),
+        |mut message, (stage, histogram)| {
+            write!(
+                &mut message,
+                "\n{stage}: {}",
+                super::timing_summary(&histogram)
+            )
+            .ok();
+            message
+        },
+    )
+}
+
+fn issue(res: Result<response::Issue>) -> String {
+    match res {
+        Ok(response::Issue::Created(issue)) => {
+            format!("created issue #{}: {}", issue.number, issue.url)
+        }
+        Ok(response::Issue::Existing(issue)) => format!(
+            "the suggestion already is issue #{}: {}",
+            issue.number, issue.url
+        ),
+        Err(e) => failed(&e),
+    }
+}
+
+fn usage_alerts(resp: response::UsageAlerts) -> String {
+    match resp {
+        response::UsageAlerts::List(Ok(alerts)) if alerts.is_empty() => {
+            "there are no usage alerts".to_owned()
+        }
+        response::UsageAlerts::List(Ok(alerts)) => alerts.into_iter().fold(
+            String::from("commands with a daily usage alert:"),
+            |mut list, (name, limit)| {
+                write!(&mut list, "\n!{name}: {limit}").ok();
+                list
+            },
+        ),
+        response::UsageAlerts::List(Err(e)) => failed(&e),
+        response::UsageAlerts::Edit(res) => done(res, "usage alert updated"),
+    }
+}
+
+fn silence(res: Result<Option<Duration>>) -> String {
+    match res {
+        Ok(Some(duration)) => format!("replies are silenced for {}", super::uptime(duration)),
+        Ok(None) => "replies are back on".to_owned(),
+        Err(e) => failed(&e),
+    }
+}
+
+fn links_preview(source: Source, links: &response::Links) -> String {
+    if links.links.is_empty() {
+        return format!("no links are shown on {source}");
+    }
+
+    format!(
+        "the links on {source} look like this:\n{}",
+        super::link_preview(source, links)
+    )
+}
+
+fn command_list(res: Result<Option<NonZero<u64>>>) -> String {
+    match res {
+        Ok(Some(channel)) => format!("the command list is kept pinned in channel {channel}"),
+        Ok(None) => "the command list isn't updated anymore".to_owned(),
+        Err(e) => failed(&e),
+    }
+}
+
+fn watch_crates(resp: response::WatchCrates) -> String {
+    match resp {
+        response::WatchCrates::List(Ok(crates)) if crates.is_empty() => {
+            "there are no watched crates".to_owned()
+        }
+        response::WatchCrates::List(Ok(crates)) => crates.into_iter().fold(
+            String::from("watched crates:"),
+            |mut list, WatchedCrate { name, version, .. }| {
+                write!(&mut list, "\n{name} (latest {version})").ok();
+                list
+            },
+        ),
+        response::WatchCrates::Add(Ok(WatchedCrate { name, version, .. })) => done(
+            Ok(()),
+            &format!("watching {name} now, currently at {version}"),
+        ),
+        response::WatchCrates::Remove(Ok(())) => done(Ok(()), "crate no longer watched"),
+        response::WatchCrates::List(Err(e))
+        | response::WatchCrates::Add(Err(e))
+        | response::WatchCrates::Remove(Err(e)) => failed(&e),
+    }
+}
+
+fn faqs(res: Result<Vec<Faq>>) -> String {
+    match res {
+        Ok(faqs) if faqs.is_empty() => "there are no FAQ entries".to_owned(),
+        Ok(faqs) => faqs
+            .into_iter()
+            .fold(String::from("FAQ entries:"), |mut list, faq| {
+                let kind = if faq.regex { "regex" } else { "keywords" };
+                write!(
+                    &mut list,
+                    "\n{} ({kind} {}): {}",
+                    faq.name, faq.pattern, faq.answer
+                )
+                .ok();
+                list
+            }),
+        Err(e) => failed(&e),
+    }
+}
+
+fn triggers(res: Result<Vec<Trigger>>) -> String {
+    match res {
+        Ok(triggers) if triggers.is_empty() => "there are no trigger words".to_owned(),
+        Ok(triggers) => {
+            triggers
+                .into_iter()
+                .fold(String::from("trigger words:"), |mut list, trigger| {
+                    write!(
+                        &mut list,
+                        "\n{} (cooldown {}s",
+                        trigger.word, trigger.cooldown
+                    )
+                    .ok();
+                    for source in trigger.disabled {
+                        write!(&mut list, ", off on {source}").ok();
+                    }
+                    write!(&mut list, "): {}", trigger.content).ok();
+                    list
+                })
+        }
+        Err(e) => failed(&e),
+    }
+}
+
+fn command_history(name: &str, res: Result<Vec<CommandRevision>>) -> String {
+    match res {
+        Ok(revisions) if revisions.is_empty() => {
+            format!("there are no recorded changes for !{name}")
+        }
+        Ok(revisions) => {
+            revisions
+                .into_iter()
+                .fold(format!("recent changes to !{name}:"), |mut list, rev| {
+                    write!(
+                        &mut list,
+                        "\n#{} {} by {} on {}: {}",
+                        rev.id,
+                        rev.source.as_ref(),
+                        rev.author,
+                        rev.changed_at.date(),
+                        rev.content.as_deref().unwrap_or("(removed)"),
+                    )
+                    .ok();
+                    list
+                })
+        }
+        Err(e) => failed(&e),
+    }
+}
+
+fn statistics(date: StatisticsDate, stats: Statistics) -> String {
+    let CommandUsage {
+        builtin,
+        custom,
+        unknown,
+        spam,
+    } = stats.command_usage;
+
+    let mut message = match date {
+        StatisticsDate::Total => "Here are the statistics of all time".to_owned(),
+        StatisticsDate::Current => "Here are the statistics of the current month".to_owned(),
+        StatisticsDate::Month { year, month } => {
+            format!("Here are the statistics of {month} {year}")
+        }
+        StatisticsDate::Tag(tag) => format!("Here are the statistics of all streams tagged {tag}"),
+    };
+
+    let builtin = builtin.map(|usage| {
+        usage
+            .into_iter()
+            .map(|(cmd, count)| (cmd.name().to_owned(), count))
+            .collect()
+    });
+
+    for (title, usage) in [
+        ("Built-in", builtin),
+        ("Custom", custom),
+        ("Unknown", unknown),
+        ("Spam", spam),
+    ] {
+        write!(&mut message, "\n\n{title}").ok();
+        match usage {
+            Ok(usage) => {
+                for (cmd, count) in usage {
+                    write!(&mut message, "\n{cmd}: {count}").ok();
+                }
+            }
+            Err(e) => {
+                write!(
+                    &mut message,
+                    "\nSorry, this section couldn't be loaded: {e}"
+                )
+                .ok();
+            }
+        }
+    }
+
+    match stats.watch_time {
+        Ok(leaders) if leaders.is_empty() => {}
+        Ok(leaders) => {
+            message.push_str("\n\nWatch time");
+            for (name, seconds) in leaders {
+                let time = super::uptime(Duration::from_secs(seconds));
+                write!(&mut message, "\n{name}: {time}").ok();
+            }
+        }
+        Err(e) => {
+            write!(
+                &mut message,
+                "\n\nWatch time\nSorry, this section couldn't be loaded: {e}"
+            )
+            .ok();
+        }
+    }
+
+    message
+}
+
+/// Render an owner response as plain text message.
+#[must_use]
+pub fn owner(resp: response::Owner) -> String {
+    match resp {
+        response::Owner::Help => access_help("Owner", Access::Owner),
+        response::Owner::Admins(response::Admins::List { users, roles }) => {
+            let mut list =
+                users
+                    .into_iter()
+                    .fold(String::from("current admins are:"), |mut list, id| {
+                        write!(&mut list, "\n- {id}").ok();
+                        list
+                    });
+            for role in roles {
+                write!(&mut list, "\n- members of role {role}").ok();
+            }
+            list
+        }
+        response::Owner::Admins(response::Admins::Edit(res)) => match res {
+            Ok(AdminAction::Added) => "user added to admin list".to_owned(),
+            Ok(AdminAction::Removed) => "user removed from admin list".to_owned(),
+            Ok(AdminAction::RoleAdded) => "role added to admin roles".to_owned(),
+            Ok(AdminAction::RoleRemoved) => "role removed from admin roles".to_owned(),
+            Err(e) => failed(&e),
+        },
+        response::Owner::Plugins(response::Plugins::List(plugins)) => plugins_list(plugins),
+        response::Owner::Plugins(response::Plugins::Edit(res)) => match res {
+            Ok(action) => format!("plugin {action}"),
+            Err(e) => failed(&e),
+        },
+        response::Owner::DebugParse(text) => text,
+        response::Owner::ResyncCommands => "re-registering slash commands".to_owned(),
+        response::Owner::Broadcast(_) => "broadcasting the message on all services".to_owned(),
+        response::Owner::Cleanup(res) => match res {
+            Ok(cleanup) => super::cleanup(cleanup),
+            Err(e) => failed(&e),
+        },
+        #[cfg(feature = "chaos")]
+        response::Owner::Chaos(faults) => format!("injected faults: {faults}"),
+    }
+}
+
+fn plugins_list(plugins: Vec<PluginInfo>) -> String {
+    plugins
+        .into_iter()
+        .fold(String::from("registered plugins:"), |mut list, plugin| {
+            let commands = plugin
+                .commands
+                .iter()
+                .map(|command| format!("!{command}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            write!(
+                &mut list,
+                "\n- {} ({}): {commands}",
+                plugin.name,
+                if plugin.loaded { "loaded" } else { "unloaded" }
+            )
+            .ok();
+            list
+        })
+}
+
+fn custom_commands_edit(res: Result<Vec<MassMention>>) -> String {
+    match res {
+        Ok(mentions) if mentions.is_empty() => "custom commands updated".to_owned(),
+        Ok(mentions) => format!(
+            "custom commands updated, but the content mentions {}, which notifies many users on \
+             Discord if the mention settings allow it",
+            super::mass_mentions(&mentions, ToOwned::to_owned),
+        ),
+        Err(e) => failed(&e),
+    }
+}
+
+fn done(res: Result<()>, message: &str) -> String {
+    match res {
+        Ok(()) => message.to_owned(),
+        Err(e) => failed(&e),
+    }
+}
+
+/// Render the request to confirm a destructive command.
+#[must_use]
+pub fn confirm(action: response::Confirm) -> String {
+    let action = match action {
+        response::Confirm::RemoveCustomCommand(name) => {
+            format!("remove the custom command {name} from all services")
+        }
+        response::Confirm::RemoveAdmin(id) => format!("remove user {id} from the admin list"),
+        response::Confirm::RemoveAdminRole(role) => {
+            format!("remove role {role} from the admin roles")
+        }
+    };
+
+    format!(
+        "this will {action}, type !confirm within {} seconds to go ahead",
+        CONFIRM_TIMEOUT.as_secs()
+    )
+}
+
+fn failed(error: &anyhow::Error) -> String {
+    format!("some error happened: {error}")
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+    use indexmap::IndexMap;
+    use similar_asserts::assert_eq;
+
+    use super::*;
+    use crate::{api::Source, statistics::BuiltinCommand};
+
+    #[test]
+    fn render_user() {
+        assert_eq!(
+            Some("me, YOU SHALL NOT PASS!!".to_owned()),
+            user(response::User::Ban("me, YOU SHALL NOT PASS!!".to_owned()))
+        );
+        assert_eq!(
+            Some("Available commands: !help (or !bot), !crate (or !crates), !hello".to_owned()),
+            user(response::User::Commands {
+                builtin: ["help", "crate"]
+                    .into_iter()
+                    .filter_map(commands::find)
+                    .collect(),
+                custom: Ok(vec!["hello".to_owned()]),
+            })
+        );
+        assert_eq!(None, user(response::User::Unknown));
+    }
+
+    #[test]
+    fn render_admin() {
+        let stats = Statistics {
+            command_usage: CommandUsage {
+                builtin: Ok(IndexMap::from([(BuiltinCommand::Crate, 3)])),
+                custom: Err(anyhow!("broken")),
+                unknown: Ok(IndexMap::new()),
+                spam: Ok(IndexMap::from([("links".to_owned(), 7)])),
+            },
+            watch_time: Ok(IndexMap::new()),
+        };
+
+        assert_eq!(
+            "Here are the statistics of all time\n\nBuilt-in\ncrate: 3\n\nCustom\nSorry, this \
+             section couldn't be loaded: broken\n\nUnknown\n\nSpam\nlinks: 7",
+            admin(response::Admin::Statistics(StatisticsDate::Total, stats))
+        );
+        let entry = |source| CustomCommandEntry {
+            name: "hello".to_owned(),
+            source,
+            content: "Hello there!".to_owned(),
+            created_by: None,
+            created_at: None,
+            updated_by: None,
+            updated_at: None,
+        };
+        assert_eq!(
+            "available custom commands:\n!hello (Twitch, Console)",
+            admin(response::Admin::CustomCommands(
+                response::CustomCommands::List(Ok(BTreeMap::from([(
+                    "hello".to_owned(),
+                    vec![entry(Source::Twitch), entry(Source::Console)]
+                )])))
+            ))
+        );
+    }
+}
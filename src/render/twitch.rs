@@ -0,0 +1,166 @@
+//! Rendering of responses as Twitch chat messages, which are single lines without any formatting.
+
+use std::fmt::Write;
+
+use anyhow::Result;
+use tracing::error;
+
+use super::QUEUE_PREVIEW;
+use crate::api::response::{self, CrateSearch, QueuedSong};
+
+/// Characters of a snippet's output that are shown, leaving room for the rest of the message.
+const EVAL_OUTPUT: usize = 400;
+
+/// Render a user response as chat message, or nothing if there is nothing to reply.
+#[must_use]
+pub fn user(resp: response::User) -> Option<String> {
+    Some(match resp {
+        response::User::Commands { builtin, custom } => super::command_names(&builtin, custom),
+        response::User::Links(links) => links_list(&links),
+        response::User::Crate(res) => crate_(res),
+        response::User::RustVersions(res) => super::rust_versions(res),
+        response::User::Eval(res) => super::eval(res, |success, output| {
+            super::eval_line(success, &output, EVAL_OUTPUT)
+        }),
+        response::User::CrateCompare(res) => crate_compare(res),
+        response::User::CrateVersion(res) => super::crate_version(res),
+        response::User::CrateFeatures(res) => super::crate_features(res),
+        response::User::Status(status) => status_(status),
+        response::User::BotStat(stat) => super::botstat(&stat),
+        response::User::SongRequest(res) => super::song_request(res),
+        response::User::SongQueue(res) => song_queue(res),
+        response::User::CommandHelp(res) => super::command_help(res),
+        response::User::Points(res) => super::points(res),
+        response::User::Gamble(res) => super::gamble(res),
+        response::User::Duel(res) => super::duel(res),
+        response::User::Poll(res) => super::poll(res),
+        response::User::NotifyMe(res) => super::notify_me(res),
+        response::User::WatchTime(res) => super::watch_time(res),
+        response::User::Yesterday(res) => super::yesterday(res),
+        response::User::LastCategory(res) => super::last_category(res),
+        response::User::Suggest(res) => super::suggest(res),
+        response::User::Topic(res) => super::topic(res),
+        response::User::Birthday(res) => super::birthday(res),
+        response::User::Fun { kind, content } => super::fun(kind, content),
+        response::User::Away { streamer, message } => super::away(&streamer, &message),
+        response::User::Trigger(Some(content)) | response::User::Faq(content) => content,
+        response::User::Pronouns(res) => super::pronouns(res),
+        response::User::Prefs(res) => super::prefs(res),
+        response::User::Lurk(res) => super::lurk(res),
+        response::User::Highlights(res) => super::highlights(res),
+        response::User::NotEnoughPoints { cost, balance } => {
+            super::not_enough_points(cost, balance)
+        }
+        response::User::Help(text)
+        | response::User::Ban(text)
+        | response::User::Today(text)
+        | response::User::FahrenheitToCelsius(text)
+        | response::User::CelsiusToFahrenheit(text) => text,
+        response::User::Custom(res) => match res {
+            Ok(content) => content,
+            Err(e) => {
+                error!(error = ?e, "failed finding custom command");
+                return None;
+            }
+        },
+        response::User::TimeoutMe
+        | response::User::FollowAge
+        | response::User::AccountAge
+        | response::User::TimeoutSpammer(_)
+        | response::User::Trigger(None)
+        | response::User::Unknown => return None,
+    })
+}
+
+pub(super) fn links_list(links: &response::Links) -> String {
+    super::links_list(links, " | ", |name, url| format!("{name}: {url}"))
+}
+
+pub(super) fn crate_(res: Result<CrateSearch>) -> String {
+    match res {
+        Ok(CrateSearch::Found(info)) => format!("https://crates.io/crates/{}", info.name),
+        Ok(CrateSearch::NotFound(message)) => message,
+        Ok(CrateSearch::Fallback { name, version }) => super::crate_fallback(&name, &version),
+        Err(e) => {
+            error!(error = ?e, "failed searching for crate");
+            "Sorry, something went wrong looking up the crate".to_owned()
+        }
+    }
+}
+
+fn crate_compare(res: Result<(CrateSearch, CrateSearch)>) -> String {
+    match res {
+        Ok((CrateSearch::Found(first), CrateSearch::Found(second))) => [first, second]
+            .iter()
+            .map(|info| {
+                format!(
+                    "{} v{} ({} downloads, {} versions, updated {}, docs {})",
+                    info.name,
+                    info.newest_version,
+                    info.downloads,
+                    info.version_count,
+                    info.updated_at.date(),
+                    info.docs_link(),
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" vs. "),
+        Ok((CrateSearch::NotFound(message), _) | (_, CrateSearch::NotFound(message))) => message,
+        Ok((CrateSearch::Fallback { .. }, _) | (_, CrateSearch::Fallback { .. })) => {
+            super::CRATES_IO_UNAVAILABLE.to_owned()
+        }
+        Err(e) => {
+            error!(error = ?e, "failed searching for crates");
+            "Sorry, something went wrong looking up the crates".to_owned()
+        }
+    }
+}
+
+pub(super) fn song_queue(res: Result<Vec<QueuedSong>>) -> String {
+    match res {
+        Ok(songs) if songs.is_empty() => {
+            "The song queue is empty, request a song with !sr <link>".to_owned()
+        }
+        Ok(songs) => {
+            let mut message = songs
+                .iter()
+                .take(QUEUE_PREVIEW)
+                .enumerate()
+                .map(|(i, song)| format!("{}. {}", i + 1, song.url))
+                .collect::<Vec<_>>()
+                .join(" | ");
+
+            if songs.len() > QUEUE_PREVIEW {
+                write!(message, " | and {} more", songs.len() - QUEUE_PREVIEW).ok();
+            }
+
+            message
+        }
+        Err(e) => {
+            error!(error = ?e, "failed listing song requests");
+            "Sorry, something went wrong fetching the song queue".to_owned()
+        }
+    }
+}
+
+pub(super) fn status_(status: response::Status) -> String {
+    let mut message = match status.stream {
+        Ok(Some(info)) => format!(
+            "I'm up and running! The stream is live, streaming {} ({}).",
+            info.title, info.category
+        ),
+        Ok(None) => "I'm up and running! The stream is currently offline.".to_owned(),
+        Err(e) => {
+            error!(error = ?e, "failed getting stream status");
+            "Sorry, something went wrong fetching the status".to_owned()
+        }
+    };
+
+    for connector in status.connectors.iter().filter(|c| c.stale) {
+        message.push_str(" Connection to ");
+        message.push_str(connector.source.as_ref());
+        message.push_str(" seems to be silent.");
+    }
+
+    message
+}
@@ -0,0 +1,94 @@
+//! Rendering of responses as YouTube live chat messages, which are single lines of limited length.
+//! The connector cuts down messages that are still too long.
+
+use anyhow::Result;
+use tracing::error;
+
+use super::twitch::{crate_, links_list, song_queue, status_};
+use crate::api::response::{self, CrateSearch};
+
+/// Characters of a snippet's output that are shown, leaving room for the rest of the message.
+const EVAL_OUTPUT: usize = 180;
+
+/// Render a user response as single line chat message, or nothing if there is nothing to reply.
+#[must_use]
+pub fn user(resp: response::User) -> Option<String> {
+    Some(match resp {
+        response::User::Commands { builtin, custom } => super::command_names(&builtin, custom),
+        response::User::Links(links) => links_list(&links),
+        response::User::Crate(res) => crate_(res),
+        response::User::RustVersions(res) => super::rust_versions(res),
+        response::User::Eval(res) => super::eval(res, |success, output| {
+            super::eval_line(success, &output, EVAL_OUTPUT)
+        }),
+        response::User::CrateCompare(res) => crate_compare(res),
+        response::User::CrateVersion(res) => super::crate_version(res),
+        response::User::CrateFeatures(res) => super::crate_features(res),
+        response::User::Status(status) => status_(status),
+        response::User::BotStat(stat) => super::botstat(&stat),
+        response::User::SongRequest(res) => super::song_request(res),
+        response::User::SongQueue(res) => song_queue(res),
+        response::User::CommandHelp(res) => super::command_help(res),
+        response::User::Points(res) => super::points(res),
+        response::User::Gamble(res) => super::gamble(res),
+        response::User::Duel(res) => super::duel(res),
+        response::User::Poll(res) => super::poll(res),
+        response::User::NotifyMe(res) => super::notify_me(res),
+        response::User::WatchTime(res) => super::watch_time(res),
+        response::User::Yesterday(res) => super::yesterday(res),
+        response::User::LastCategory(res) => super::last_category(res),
+        response::User::Suggest(res) => super::suggest(res),
+        response::User::Topic(res) => super::topic(res),
+        response::User::Birthday(res) => super::birthday(res),
+        response::User::Fun { kind, content } => super::fun(kind, content),
+        response::User::Away { streamer, message } => super::away(&streamer, &message),
+        response::User::Trigger(Some(content)) | response::User::Faq(content) => content,
+        response::User::Pronouns(res) => super::pronouns(res),
+        response::User::Prefs(res) => super::prefs(res),
+        response::User::Lurk(res) => super::lurk(res),
+        response::User::Highlights(res) => super::highlights(res),
+        response::User::NotEnoughPoints { cost, balance } => {
+            super::not_enough_points(cost, balance)
+        }
+        response::User::Help(text)
+        | response::User::Ban(text)
+        | response::User::Today(text)
+        | response::User::FahrenheitToCelsius(text)
+        | response::User::CelsiusToFahrenheit(text) => text,
+        response::User::Custom(res) => match res {
+            Ok(content) => content,
+            Err(e) => {
+                error!(error = ?e, "failed finding custom command");
+                return None;
+            }
+        },
+        response::User::TimeoutMe
+        | response::User::FollowAge
+        | response::User::AccountAge
+        | response::User::TimeoutSpammer(_)
+        | response::User::Trigger(None)
+        | response::User::Unknown => return None,
+    })
+}
+
+fn crate_compare(res: Result<(CrateSearch, CrateSearch)>) -> String {
+    match res {
+        Ok((CrateSearch::Found(first), CrateSearch::Found(second))) => format!(
+            "{} v{} ({} downloads) vs. {} v{} ({} downloads)",
+            first.name,
+            first.newest_version,
+            first.downloads,
+            second.name,
+            second.newest_version,
+            second.downloads,
+        ),
+        Ok((CrateSearch::NotFound(message), _) | (_, CrateSearch::NotFound(message))) => message,
+        Ok((CrateSearch::Fallback { .. }, _) | (_, CrateSearch::Fallback { .. })) => {
+            super::CRATES_IO_UNAVAILABLE.to_owned()
+        }
+        Err(e) => {
+            error!(error = ?e, "failed searching for crates");
+            "Sorry, something went wrong looking up the crates".to_owned()
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Information about the running bot itself, like its version and how long it has been running.
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+/// Version of the bot, as defined in the crate manifest.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Short hash of the Git commit that the bot was built from, or `unknown`.
+pub const COMMIT: &str = env!("TOGGLEBOT_COMMIT");
+
+/// Shared handle to the runtime details, created once when the bot starts.
+#[derive(Clone)]
+pub struct Runtime {
+    started: Instant,
+    messages: Arc<AtomicU64>,
+}
+
+impl Runtime {
+    /// Start tracking the runtime, counting the uptime from now on.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            messages: Arc::default(),
+        }
+    }
+
+    /// Count a message that was handled by the bot.
+    pub fn record_message(&self) {
+        self.messages.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Create a report about the current runtime details.
+    #[must_use]
+    pub fn report(&self) -> RuntimeInfo {
+        RuntimeInfo {
+            version: VERSION,
+            commit: COMMIT,
+            uptime: self.started.elapsed(),
+            messages: self.messages.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Default for Runtime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Snapshot of the runtime details.
+#[derive(Clone, Debug)]
+pub struct RuntimeInfo {
+    /// Version of the bot.
+    pub version: &'static str,
+    /// Git commit of the build.
+    pub commit: &'static str,
+    /// Time since the bot started.
+    pub uptime: Duration,
+    /// Amount of messages handled since the start.
+    pub messages: u64,
+}
+
+impl RuntimeInfo {
+    /// Average amount of handled messages per minute.
+    #[must_use]
+    pub fn messages_per_minute(&self) -> f64 {
+        let minutes = self.uptime.as_secs_f64() / 60.0;
+        if minutes < 1.0 {
+            return 0.0;
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let messages = self.messages as f64;
+        messages / minutes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn throughput() {
+        let mut info = RuntimeInfo {
+            version: VERSION,
+            commit: COMMIT,
+            uptime: Duration::from_secs(30),
+            messages: 10,
+        };
+        assert!(info.messages_per_minute().abs() < f64::EPSILON);
+
+        info.uptime = Duration::from_mins(5);
+        assert!((info.messages_per_minute() - 2.0).abs() < f64::EPSILON);
+    }
+}
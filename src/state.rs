@@ -1,60 +1,135 @@
 //! State management and load/save logic for it.
 
-use std::sync::Arc;
+use std::{
+    num::NonZero,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use time::{Date, Month, OffsetDateTime};
 
-pub use self::migrate::run as migrate;
+pub use self::migrate::{pending as pending_import, run as migrate};
 use crate::{
-    api::{AdminId, Source},
+    api::{
+        response::{
+            CommandRevision, CustomCommandEntry, Faq, GitHubIssue, QueuedSong, SongPlatform,
+            StreamTitle, Suggestion, Topic, Trigger, UserNote, WatchedCrate,
+        },
+        AdminId, Availability, ChannelRule, GuildChannel, Highlight, Source, StreamInfo,
+        SuggestionStatus, UserRef,
+    },
+    crypto::Key,
     db::{self, connection::Connection},
 };
 
+/// Kind of tracked records for highlights, which follow the edits and deletion of the pinned
+/// Discord message.
+const TRACKED_HIGHLIGHT: &str = "highlight";
+
+/// Discord channel of the pinned command list, and its message once it was posted.
+pub type CommandList = (NonZero<u64>, Option<NonZero<u64>>);
+
 /// Main state structure holding all dynamic (runtime changeable) settings.
-pub struct State(Arc<Connection>);
+///
+/// The connection is guarded by a mutex, so the state can be shared with the HTTP server.
+#[derive(Clone)]
+pub struct State(Arc<Mutex<Connection>>);
 
 impl State {
     pub fn new(conn: Connection) -> Self {
-        Self(conn.into())
+        Self(Arc::new(Mutex::new(conn)))
     }
 
-    #[cfg(test)]
     pub fn in_memory() -> Result<Self> {
-        Connection::in_memory().map(Arc::new).map(Self)
+        Connection::in_memory().map(Self::new)
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
     }
 
     pub fn add_admin(&self, id: AdminId) -> Result<()> {
-        db::exec(&self.0, include_str!("../queries/admins/add.sql"), id)
+        db::exec(&self.conn(), include_str!("../queries/admins/add.sql"), id)
     }
 
     pub fn remove_admin(&self, id: AdminId) -> Result<()> {
-        db::exec(&self.0, include_str!("../queries/admins/remove.sql"), id)
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/admins/remove.sql"),
+            id,
+        )
     }
 
     pub fn is_admin(&self, id: AdminId) -> Result<bool> {
-        db::query_one(&self.0, include_str!("../queries/admins/exists.sql"), id)
-            .map(|exists| exists.unwrap_or(false))
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/admins/exists.sql"),
+            id,
+        )
+        .map(|exists| exists.unwrap_or(false))
     }
 
     pub fn list_admins(&self) -> Result<Vec<AdminId>> {
         db::query_vec(
-            &self.0,
+            &self.conn(),
             include_str!("../queries/admins/list.sql"),
             db::NO_PARAMS,
         )
     }
 
-    pub fn add_custom_command(&self, source: Source, name: &str, content: &str) -> Result<()> {
+    /// Let every member of the Discord role access the admin commands.
+    pub fn add_admin_role(&self, id: NonZero<u64>) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/admin_roles/add.sql"),
+            id,
+        )
+    }
+
+    pub fn remove_admin_role(&self, id: NonZero<u64>) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/admin_roles/remove.sql"),
+            id,
+        )
+    }
+
+    pub fn list_admin_roles(&self) -> Result<Vec<NonZero<u64>>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/admin_roles/list.sql"),
+            db::NO_PARAMS,
+        )
+    }
+
+    /// Save a custom command, recording the author as creator of a new command, or as the last
+    /// one to change an existing command.
+    pub fn add_custom_command(
+        &self,
+        source: Source,
+        name: &str,
+        content: &str,
+        author: &UserRef,
+        now: OffsetDateTime,
+    ) -> Result<()> {
         db::exec(
-            &self.0,
+            &self.conn(),
             include_str!("../queries/custom_cmds/add.sql"),
-            (source, name, content),
+            (
+                source,
+                name,
+                content,
+                author.to_string(),
+                now.unix_timestamp(),
+            ),
         )
     }
 
     pub fn remove_custom_command(&self, source: Source, name: &str) -> Result<()> {
         db::exec(
-            &self.0,
+            &self.conn(),
             include_str!("../queries/custom_cmds/remove.sql"),
             (source, name),
         )
@@ -62,7 +137,7 @@ impl State {
 
     pub fn remove_custom_command_by_name(&self, name: &str) -> Result<()> {
         db::exec(
-            &self.0,
+            &self.conn(),
             include_str!("../queries/custom_cmds/remove_name.sql"),
             name,
         )
@@ -70,7 +145,7 @@ impl State {
 
     pub fn get_custom_command(&self, source: Source, name: &str) -> Result<Option<String>> {
         db::query_one(
-            &self.0,
+            &self.conn(),
             include_str!("../queries/custom_cmds/get.sql"),
             (source, name),
         )
@@ -78,164 +153,2544 @@ impl State {
 
     pub fn list_custom_commands(&self) -> Result<Vec<(String, Source)>> {
         db::query_vec(
-            &self.0,
+            &self.conn(),
             include_str!("../queries/custom_cmds/list.sql"),
             db::NO_PARAMS,
         )
     }
 
+    /// List all custom commands with their content and provenance, ordered by name.
+    pub fn list_custom_command_details(&self) -> Result<Vec<CustomCommandEntry>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/custom_cmds/list_details.sql"),
+            db::NO_PARAMS,
+        )
+    }
+
+    /// Content and provenance of a custom command, for every service that it exists on.
+    pub fn custom_command_details(&self, name: &str) -> Result<Vec<CustomCommandEntry>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/custom_cmds/details.sql"),
+            name,
+        )
+    }
+
     pub fn list_custom_command_names(&self, source: Source) -> Result<Vec<String>> {
         db::query_vec(
-            &self.0,
+            &self.conn(),
             include_str!("../queries/custom_cmds/list_names.sql"),
             source,
         )
     }
-}
 
-mod migrate {
-    use std::{
-        collections::{HashMap, HashSet},
-        fs,
-        io::ErrorKind,
-        num::NonZeroU64,
-    };
+    /// Record a change to a custom command, with `None` as content if it was removed.
+    pub fn add_custom_command_revision(
+        &self,
+        source: Source,
+        name: &str,
+        content: Option<&str>,
+        author: &UserRef,
+        now: OffsetDateTime,
+    ) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/custom_cmd_history/add.sql"),
+            (
+                source,
+                name,
+                content,
+                author.to_string(),
+                now.unix_timestamp(),
+            ),
+        )
+    }
 
-    use anyhow::{Context, Result};
-    use serde::Deserialize;
+    /// List all changes to a custom command across all sources, newest first.
+    pub fn list_custom_command_revisions(&self, name: &str) -> Result<Vec<CommandRevision>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/custom_cmd_history/list.sql"),
+            name,
+        )
+    }
 
-    use super::Connection;
-    use crate::dirs::DIRS;
+    /// Record the start of a new stream session. Restarting a known session re-opens it.
+    pub fn start_stream(&self, info: &StreamInfo) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/streams/start.sql"),
+            (
+                &info.id,
+                info.started_at.unix_timestamp(),
+                &info.title,
+                &info.category,
+            ),
+        )
+    }
 
-    #[derive(Deserialize)]
-    struct State {
-        #[serde(default)]
-        custom_commands: HashMap<String, HashMap<Source, String>>,
-        #[serde(default)]
-        admins: HashSet<NonZeroU64>,
+    /// Close any stream session that is still active.
+    pub fn end_stream(&self, ended_at: OffsetDateTime) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/streams/end.sql"),
+            ended_at.unix_timestamp(),
+        )
     }
 
-    #[derive(Eq, Hash, PartialEq, Deserialize)]
-    enum Source {
-        Discord,
-        Twitch,
+    /// Add the title and category to the history of the active stream, unless they didn't change.
+    /// Without an active stream, nothing is recorded.
+    pub fn record_title(
+        &self,
+        title: &str,
+        category: &str,
+        changed_at: OffsetDateTime,
+    ) -> Result<()> {
+        if self
+            .current_title()?
+            .is_some_and(|current| current.title == title && current.category == category)
+        {
+            return Ok(());
+        }
+
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/stream_titles/add.sql"),
+            (title, category, changed_at.unix_timestamp()),
+        )
     }
 
-    fn load() -> Result<Option<State>> {
-        let state = match fs::read(DIRS.state_file()) {
-            Ok(buf) => buf,
-            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
-            Err(e) => return Err(e).context("failed reading state file"),
+    /// Latest title and category of the active stream, if any.
+    pub fn current_title(&self) -> Result<Option<StreamTitle>> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/stream_titles/current.sql"),
+            db::NO_PARAMS,
+        )
+    }
+
+    /// All titles and categories of the streams that were live at any time in the given range.
+    pub fn list_titles(
+        &self,
+        start: OffsetDateTime,
+        end: OffsetDateTime,
+    ) -> Result<Vec<StreamTitle>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/stream_titles/list_between.sql"),
+            (end.unix_timestamp(), start.unix_timestamp()),
+        )
+    }
+
+    /// Latest title that the category was streamed with, ignoring case.
+    pub fn last_category(&self, category: &str) -> Result<Option<StreamTitle>> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/stream_titles/last_category.sql"),
+            category,
+        )
+    }
+
+    /// All titles and categories of the streams that started in the given range, or of the latest
+    /// stream if there is no range, grouped by stream with the time it started.
+    pub fn stream_titles(
+        &self,
+        range: Option<(OffsetDateTime, OffsetDateTime)>,
+    ) -> Result<Vec<(OffsetDateTime, Vec<StreamTitle>)>> {
+        #[derive(Deserialize)]
+        struct Row {
+            stream: i64,
+            #[serde(with = "time::serde::timestamp")]
+            started_at: OffsetDateTime,
+            title: String,
+            category: String,
+            #[serde(with = "time::serde::timestamp")]
+            changed_at: OffsetDateTime,
+        }
+
+        let rows: Vec<Row> = match range {
+            Some((start, end)) => db::query_vec(
+                &self.conn(),
+                include_str!("../queries/stream_titles/list_started.sql"),
+                (start.unix_timestamp(), end.unix_timestamp()),
+            )?,
+            None => db::query_vec(
+                &self.conn(),
+                include_str!("../queries/stream_titles/list_latest.sql"),
+                db::NO_PARAMS,
+            )?,
         };
 
-        serde_json::from_slice(&state)
-            .context("failed parsing state data")
-            .map(Some)
+        let mut streams = Vec::<(i64, OffsetDateTime, Vec<StreamTitle>)>::new();
+        for row in rows {
+            let title = StreamTitle {
+                title: row.title,
+                category: row.category,
+                changed_at: row.changed_at,
+            };
+            match streams.last_mut() {
+                Some((stream, _, titles)) if *stream == row.stream => titles.push(title),
+                _ => streams.push((row.stream, row.started_at, vec![title])),
+            }
+        }
+
+        Ok(streams
+            .into_iter()
+            .map(|(_, started_at, titles)| (started_at, titles))
+            .collect())
     }
 
-    pub fn run(conn: &mut Connection) -> Result<()> {
-        let Some(state) = load()? else { return Ok(()) };
+    /// Size of the database, in bytes.
+    pub fn size(&self) -> Result<u64> {
+        self.conn().size()
+    }
+
+    /// Whether the stream is currently live, as tracked from the stream online and offline events.
+    pub fn is_live(&self) -> Result<bool> {
+        self.current_stream().map(|stream| stream.is_some())
+    }
+
+    /// Get the details of the currently active stream session, if any.
+    pub fn current_stream(&self) -> Result<Option<StreamInfo>> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/streams/current_info.sql"),
+            db::NO_PARAMS,
+        )
+    }
 
-        let tx = conn.transaction()?;
-        let mut stmt = tx.prepare(include_str!("../queries/admins/add.sql"))?;
+    /// Attach a topic tag to the currently active stream session.
+    pub fn tag_stream(&self, tag: &str) -> Result<()> {
+        let id = db::query_one::<_, i64>(
+            &self.conn(),
+            include_str!("../queries/streams/current.sql"),
+            db::NO_PARAMS,
+        )?
+        .context("no stream is currently live")?;
+
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/streams/set_tag.sql"),
+            (tag, id),
+        )
+    }
 
-        for admin in state.admins {
-            stmt.execute(serde_rusqlite::to_params(admin)?)?;
+    /// Agenda of the active stream session, or of the next one while the stream is offline.
+    pub fn topic(&self) -> Result<Option<Topic>> {
+        if let Some((_, topic)) = self.current_topic()? {
+            return Ok(topic.map(|content| Topic {
+                content,
+                live: true,
+            }));
         }
 
-        drop(stmt);
-        let mut stmt = tx.prepare(include_str!("../queries/custom_cmds/add.sql"))?;
+        Ok(db::query_one::<_, String>(
+            &self.conn(),
+            include_str!("../queries/upcoming_topic/get.sql"),
+            db::NO_PARAMS,
+        )?
+        .map(|content| Topic {
+            content,
+            live: false,
+        }))
+    }
 
-        for (name, contents) in state.custom_commands {
-            for (source, content) in contents {
-                let source = match source {
-                    Source::Discord => crate::api::Source::Discord,
-                    Source::Twitch => crate::api::Source::Twitch,
-                };
+    /// Set or clear the agenda of the active stream session. While the stream is offline, it's
+    /// kept for the next session instead. Returns whether the stream is live.
+    pub fn set_topic(&self, content: Option<&str>, now: OffsetDateTime) -> Result<bool> {
+        if let Some((id, _)) = self.current_topic()? {
+            db::exec(
+                &self.conn(),
+                include_str!("../queries/streams/set_topic.sql"),
+                (content, id),
+            )?;
+            return Ok(true);
+        }
 
-                stmt.execute(serde_rusqlite::to_params((source, &name, content))?)?;
-            }
+        match content {
+            Some(content) => db::exec(
+                &self.conn(),
+                include_str!("../queries/upcoming_topic/set.sql"),
+                (content, now.unix_timestamp()),
+            )?,
+            None => db::exec(
+                &self.conn(),
+                include_str!("../queries/upcoming_topic/clear.sql"),
+                db::NO_PARAMS,
+            )?,
         }
 
-        drop(stmt);
-        tx.commit()?;
+        Ok(false)
+    }
 
-        fs::remove_file(DIRS.state_file()).context("failed deleting obsolete state file")?;
+    /// ID and agenda of the active stream session, if any.
+    fn current_topic(&self) -> Result<Option<(i64, Option<String>)>> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/streams/current_topic.sql"),
+            db::NO_PARAMS,
+        )
+    }
 
-        Ok(())
+    /// Move the agenda that was set while offline to the active stream session, if there is any.
+    pub fn start_topic(&self) -> Result<()> {
+        let Some(content) = db::query_one::<_, String>(
+            &self.conn(),
+            include_str!("../queries/upcoming_topic/get.sql"),
+            db::NO_PARAMS,
+        )?
+        else {
+            return Ok(());
+        };
+
+        let Some((id, _)) = self.current_topic()? else {
+            return Ok(());
+        };
+
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/streams/set_topic.sql"),
+            (content, id),
+        )?;
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/upcoming_topic/clear.sql"),
+            db::NO_PARAMS,
+        )
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Attach a private note to a user, returning the ID of the new note.
+    pub fn add_user_note(
+        &self,
+        user: &UserRef,
+        content: &str,
+        author: AdminId,
+        now: OffsetDateTime,
+    ) -> Result<i64> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/user_notes/add.sql"),
+            (user.source, &user.id, content, author, now.unix_timestamp()),
+        )?
+        .context("no ID returned for new note")
+    }
 
-    #[test]
-    fn admin_roundtrip() {
-        let state = State::in_memory().unwrap();
-        let id = AdminId::new(1).unwrap();
+    /// List all notes of a user, oldest first.
+    pub fn list_user_notes(&self, user: &UserRef) -> Result<Vec<UserNote>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/user_notes/list.sql"),
+            (user.source, &user.id),
+        )
+    }
 
-        assert!(!state.is_admin(id).unwrap());
+    /// Delete a note, returning the user it belonged to, if the note existed.
+    pub fn remove_user_note(&self, id: i64) -> Result<Option<UserRef>> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/user_notes/remove.sql"),
+            id,
+        )
+    }
 
-        state.add_admin(id).unwrap();
-        assert!(state.is_admin(id).unwrap());
-        assert_eq!([id], state.list_admins().unwrap().as_slice());
+    /// Save a new suggestion of a user, returning its ID.
+    pub fn add_suggestion(
+        &self,
+        user: &UserRef,
+        name: &str,
+        content: &str,
+        created_at: OffsetDateTime,
+    ) -> Result<i64> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/suggestions/add.sql"),
+            (
+                user.source,
+                &user.id,
+                name,
+                content,
+                created_at.unix_timestamp(),
+            ),
+        )?
+        .context("no ID returned for new suggestion")
+    }
 
-        state.remove_admin(id).unwrap();
-        assert!(!state.is_admin(id).unwrap());
+    /// Count the suggestions of a user that still wait for a review.
+    pub fn count_open_suggestions(&self, user: &UserRef) -> Result<u64> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/suggestions/count_open.sql"),
+            (user.source, &user.id),
+        )
+        .map(Option::unwrap_or_default)
     }
 
-    #[test]
-    fn commands_roundtrip() {
-        let state = State::in_memory().unwrap();
+    /// List all suggestions that wait for a review, oldest first.
+    pub fn list_open_suggestions(&self) -> Result<Vec<Suggestion>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/suggestions/list_open.sql"),
+            db::NO_PARAMS,
+        )
+    }
 
-        for source in [Source::Discord, Source::Twitch] {
-            assert!(state.list_custom_command_names(source).unwrap().is_empty());
+    /// Accept or reject a suggestion, returning it if it existed and wasn't reviewed yet.
+    pub fn review_suggestion(
+        &self,
+        id: i64,
+        status: SuggestionStatus,
+        reason: Option<&str>,
+        reviewed_at: OffsetDateTime,
+    ) -> Result<Option<Suggestion>> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/suggestions/review.sql"),
+            (status, reason, reviewed_at.unix_timestamp(), id),
+        )
+    }
+
+    /// Get a single suggestion, regardless of its review.
+    pub fn suggestion(&self, id: i64) -> Result<Option<Suggestion>> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/suggestions/get.sql"),
+            id,
+        )
+    }
+
+    /// Remember a created GitHub issue, together with the suggestion or chat message it was made
+    /// from, if any.
+    pub fn add_github_issue(
+        &self,
+        repo: &str,
+        issue: &GitHubIssue,
+        suggestion: Option<i64>,
+        message: Option<&str>,
+        creator: &UserRef,
+        created_at: OffsetDateTime,
+    ) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/github_issues/add.sql"),
+            (
+                repo,
+                issue.number,
+                &issue.url,
+                &issue.title,
+                suggestion,
+                message,
+                creator.source,
+                &creator.id,
+                created_at.unix_timestamp(),
+            ),
+        )
+    }
+
+    /// The GitHub issue that a suggestion was turned into, if any.
+    pub fn suggestion_issue(&self, suggestion: i64) -> Result<Option<GitHubIssue>> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/github_issues/by_suggestion.sql"),
+            suggestion,
+        )
+    }
+
+    /// Append a song to the end of the request queue.
+    pub fn add_song_request(
+        &self,
+        platform: SongPlatform,
+        url: &str,
+        user: &UserRef,
+        now: OffsetDateTime,
+    ) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/song_requests/add.sql"),
+            (platform, url, user.source, &user.id, now.unix_timestamp()),
+        )
+    }
+
+    /// Count the songs in the request queue, either all of them or only those of a single user.
+    pub fn count_song_requests(&self, user: Option<&UserRef>) -> Result<u64> {
+        match user {
+            Some(user) => db::query_one(
+                &self.conn(),
+                include_str!("../queries/song_requests/count_user.sql"),
+                (user.source, &user.id),
+            ),
+            None => db::query_one(
+                &self.conn(),
+                include_str!("../queries/song_requests/count.sql"),
+                db::NO_PARAMS,
+            ),
         }
+        .map(Option::unwrap_or_default)
+    }
 
-        state
-            .add_custom_command(Source::Discord, "hi", "hello")
-            .unwrap();
-        assert_eq!(
-            Some("hello".to_owned()),
-            state.get_custom_command(Source::Discord, "hi").unwrap()
-        );
+    /// Check whether the song is already waiting in the request queue.
+    pub fn has_song_request(&self, url: &str) -> Result<bool> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/song_requests/exists.sql"),
+            url,
+        )
+        .map(|exists| exists.unwrap_or(false))
+    }
 
-        state.remove_custom_command(Source::Discord, "hi").unwrap();
-        assert_eq!(
-            None,
-            state.get_custom_command(Source::Discord, "hi").unwrap()
-        );
+    /// List all songs of the request queue, next one first.
+    pub fn list_song_requests(&self) -> Result<Vec<QueuedSong>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/song_requests/list.sql"),
+            db::NO_PARAMS,
+        )
+    }
 
-        state
-            .add_custom_command(Source::Twitch, "hi", "hello")
-            .unwrap();
-        assert_eq!(
-            Some("hello".to_owned()),
-            state.get_custom_command(Source::Twitch, "hi").unwrap()
-        );
+    /// Remove the next song from the request queue, returning it if the queue wasn't empty.
+    pub fn skip_song_request(&self) -> Result<Option<QueuedSong>> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/song_requests/skip.sql"),
+            db::NO_PARAMS,
+        )
+    }
 
-        state.remove_custom_command_by_name("hi").unwrap();
-        assert_eq!(
-            None,
-            state.get_custom_command(Source::Twitch, "hi").unwrap()
-        );
+    /// Give points to a user for chatting, unless they already earned some within the interval.
+    /// The user's current name is kept as well, to find them by it. Returns the new balance, if
+    /// any points were given.
+    pub fn earn_points(
+        &self,
+        user: &UserRef,
+        name: &str,
+        amount: u64,
+        now: OffsetDateTime,
+        interval: Duration,
+    ) -> Result<Option<u64>> {
+        let now = now.unix_timestamp();
+        let cutoff = now.saturating_sub(i64::try_from(interval.as_secs()).unwrap_or(i64::MAX));
 
-        assert!(state.list_custom_commands().unwrap().is_empty());
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/points/earn.sql"),
+            (user.source, &user.id, name, amount, now, cutoff),
+        )
     }
 
-    #[test]
-    fn overwrite_command() {
-        let state = State::in_memory().unwrap();
+    /// Get the current amount of points of a user.
+    pub fn points(&self, user: &UserRef) -> Result<u64> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/points/balance.sql"),
+            (user.source, &user.id),
+        )
+        .map(Option::unwrap_or_default)
+    }
 
-        state
-            .add_custom_command(Source::Discord, "test", "one")
-            .unwrap();
-        state
-            .add_custom_command(Source::Discord, "test", "two")
-            .unwrap();
+    /// Take points from a user, returning the remaining balance, or nothing if the user doesn't
+    /// have enough points.
+    pub fn spend_points(&self, user: &UserRef, amount: u64) -> Result<Option<u64>> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/points/spend.sql"),
+            (user.source, &user.id, amount),
+        )
+    }
 
-        let cmd = state.get_custom_command(Source::Discord, "test").unwrap();
-        assert_eq!(Some("two"), cmd.as_deref());
+    /// Find a user that collected points by their name.
+    pub fn find_points_user(&self, source: Source, name: &str) -> Result<Option<UserRef>> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/points/find.sql"),
+            (source, name),
+        )
+    }
+
+    /// Bet points, adding the amount to the user's balance if they won, or taking it otherwise.
+    /// Returns the new balance, or nothing if the user doesn't have enough points to bet.
+    pub fn wager_points(&self, user: &UserRef, amount: u64, won: bool) -> Result<Option<u64>> {
+        let change = i64::try_from(amount).context("amount too large")?;
+        let change = if won { change } else { -change };
+
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/points/wager.sql"),
+            (user.source, &user.id, change, amount),
+        )
+    }
+
+    /// Move points from the loser to the winner of a duel, if both of them have enough points at
+    /// stake. Both balances are checked and changed in a single transaction, so concurrent games
+    /// can't spend the same points twice.
+    pub fn duel_points(&self, winner: &UserRef, loser: &UserRef, amount: u64) -> Result<bool> {
+        self.conn().transaction(|tx| {
+            let stake = db::query_one::<_, u64>(
+                tx,
+                include_str!("../queries/points/balance.sql"),
+                (winner.source, &winner.id),
+            )?;
+            if stake.unwrap_or_default() < amount {
+                return Ok(false);
+            }
+
+            let spent = db::query_one::<_, u64>(
+                tx,
+                include_str!("../queries/points/spend.sql"),
+                (loser.source, &loser.id, amount),
+            )?;
+            if spent.is_none() {
+                return Ok(false);
+            }
+
+            db::exec(
+                tx,
+                include_str!("../queries/points/give.sql"),
+                (winner.source, &winner.id, amount),
+            )?;
+
+            Ok(true)
+        })
+    }
+
+    /// Set the points that a command costs, removing the cost if it's zero.
+    pub fn set_command_cost(&self, name: &str, cost: u64) -> Result<()> {
+        if cost == 0 {
+            db::exec(
+                &self.conn(),
+                include_str!("../queries/command_costs/remove.sql"),
+                name,
+            )
+        } else {
+            db::exec(
+                &self.conn(),
+                include_str!("../queries/command_costs/set.sql"),
+                (name, cost),
+            )
+        }
+    }
+
+    /// Get the points that a command costs, if it's not free.
+    pub fn command_cost(&self, name: &str) -> Result<Option<u64>> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/command_costs/get.sql"),
+            name,
+        )
+    }
+
+    /// List all commands that cost points, ordered by name.
+    pub fn list_command_costs(&self) -> Result<Vec<(String, u64)>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/command_costs/list.sql"),
+            db::NO_PARAMS,
+        )
+    }
+
+    /// Set the daily usage that trips the alert for a command, removing the alert if it's zero.
+    /// Changing the limit re-arms the alert for the current day.
+    pub fn set_usage_alert(&self, name: &str, limit: u64) -> Result<()> {
+        if limit == 0 {
+            db::exec(
+                &self.conn(),
+                include_str!("../queries/usage_alerts/remove.sql"),
+                name,
+            )
+        } else {
+            db::exec(
+                &self.conn(),
+                include_str!("../queries/usage_alerts/set.sql"),
+                (name, limit),
+            )
+        }
+    }
+
+    /// List all usage alerts with their daily limit, ordered by name.
+    pub fn list_usage_alerts(&self) -> Result<Vec<(String, u64)>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/usage_alerts/list.sql"),
+            db::NO_PARAMS,
+        )
+    }
+
+    /// Mark the alert of a command as tripped on the given day, returning whether it wasn't
+    /// already. Each alert thus fires at most once a day.
+    pub fn trip_usage_alert(&self, name: &str, day: Date) -> Result<bool> {
+        let day = day.to_julian_day();
+        db::query_one::<_, i64>(
+            &self.conn(),
+            include_str!("../queries/usage_alerts/trip.sql"),
+            (day, name, day),
+        )
+        .map(|id| id.is_some())
+    }
+
+    /// Set when a command can be used, removing the restriction if it's always available.
+    pub fn set_command_availability(&self, name: &str, availability: Availability) -> Result<()> {
+        if availability == Availability::Always {
+            db::exec(
+                &self.conn(),
+                include_str!("../queries/command_availability/remove.sql"),
+                name,
+            )
+        } else {
+            db::exec(
+                &self.conn(),
+                include_str!("../queries/command_availability/set.sql"),
+                (name, availability),
+            )
+        }
+    }
+
+    /// Get when a command can be used.
+    pub fn command_availability(&self, name: &str) -> Result<Availability> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/command_availability/get.sql"),
+            name,
+        )
+        .map(Option::unwrap_or_default)
+    }
+
+    /// List all commands that aren't always available, ordered by name.
+    pub fn list_command_availability(&self) -> Result<Vec<(String, Availability)>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/command_availability/list.sql"),
+            db::NO_PARAMS,
+        )
+    }
+
+    /// Set whether user commands are answered in a Discord channel, removing its rule if there is
+    /// none.
+    pub fn set_channel_rule(&self, channel: GuildChannel, rule: Option<ChannelRule>) -> Result<()> {
+        match rule {
+            Some(rule) => db::exec(
+                &self.conn(),
+                include_str!("../queries/command_channels/set.sql"),
+                (channel.guild, channel.channel, rule),
+            ),
+            None => db::exec(
+                &self.conn(),
+                include_str!("../queries/command_channels/remove.sql"),
+                channel.channel,
+            ),
+        }
+    }
+
+    /// List all channels of a Discord server that have a rule, ordered by their ID.
+    pub fn channel_rules(&self, guild: NonZero<u64>) -> Result<Vec<(NonZero<u64>, ChannelRule)>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/command_channels/list.sql"),
+            guild,
+        )
+    }
+
+    /// Suppress replies in a Discord channel until the given time, or lift the silence early.
+    pub fn set_silence(&self, channel: NonZero<u64>, until: Option<OffsetDateTime>) -> Result<()> {
+        match until {
+            Some(until) => db::exec(
+                &self.conn(),
+                include_str!("../queries/silenced_channels/set.sql"),
+                (channel, until.unix_timestamp()),
+            ),
+            None => db::exec(
+                &self.conn(),
+                include_str!("../queries/silenced_channels/remove.sql"),
+                channel,
+            ),
+        }
+    }
+
+    /// Time until which replies in a Discord channel are suppressed, if it was ever silenced. The
+    /// time may lie in the past already, once the silence expired.
+    pub fn silenced_until(&self, channel: NonZero<u64>) -> Result<Option<OffsetDateTime>> {
+        db::query_one::<_, i64>(
+            &self.conn(),
+            include_str!("../queries/silenced_channels/get.sql"),
+            channel,
+        )?
+        .map(OffsetDateTime::from_unix_timestamp)
+        .transpose()
+        .map_err(Into::into)
+    }
+
+    /// Remember that a custom command was featured as command of the day, only keeping the given
+    /// amount of the latest ones.
+    pub fn add_featured_command(&self, name: &str, at: OffsetDateTime, keep: u32) -> Result<()> {
+        let conn = self.conn();
+        db::exec(
+            &conn,
+            include_str!("../queries/featured_commands/add.sql"),
+            (name, at.unix_timestamp()),
+        )?;
+        db::exec(
+            &conn,
+            include_str!("../queries/featured_commands/trim.sql"),
+            keep,
+        )
+    }
+
+    /// Names of the latest featured custom commands, starting with the most recent one.
+    pub fn recent_featured_commands(&self, limit: u32) -> Result<Vec<String>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/featured_commands/recent.sql"),
+            limit,
+        )
+    }
+
+    /// Save the pronouns that a user shared about themselves, replacing any previous ones.
+    pub fn set_pronouns(&self, user: &UserRef, pronouns: &str) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/pronouns/set.sql"),
+            (user.source, &user.id, pronouns),
+        )
+    }
+
+    /// Get the pronouns of a user, if they shared any.
+    pub fn pronouns(&self, user: &UserRef) -> Result<Option<String>> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/pronouns/get.sql"),
+            (user.source, &user.id),
+        )
+    }
+
+    /// Remove the pronouns of a user, returning whether there were any.
+    pub fn remove_pronouns(&self, user: &UserRef) -> Result<bool> {
+        db::query_one::<_, String>(
+            &self.conn(),
+            include_str!("../queries/pronouns/remove.sql"),
+            (user.source, &user.id),
+        )
+        .map(|pronouns| pronouns.is_some())
+    }
+
+    /// Save a personal setting of a user, replacing any previous value.
+    pub fn set_user_pref(&self, user: &UserRef, key: &str, value: &str) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/user_prefs/set.sql"),
+            (user.source, &user.id, key, value),
+        )
+    }
+
+    /// All personal settings of a user as key and value, ordered by key.
+    pub fn user_prefs(&self, user: &UserRef) -> Result<Vec<(String, String)>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/user_prefs/list.sql"),
+            (user.source, &user.id),
+        )
+    }
+
+    /// Remove a personal setting of a user, returning whether it was set.
+    pub fn remove_user_pref(&self, user: &UserRef, key: &str) -> Result<bool> {
+        db::query_one::<_, String>(
+            &self.conn(),
+            include_str!("../queries/user_prefs/remove.sql"),
+            (user.source, &user.id, key),
+        )
+        .map(|value| value.is_some())
+    }
+
+    /// Record a user as lurking, keeping the original start if they were already lurking.
+    pub fn start_lurk(
+        &self,
+        user: &UserRef,
+        message: Option<&str>,
+        since: OffsetDateTime,
+    ) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/lurkers/start.sql"),
+            (user.source, &user.id, message, since.unix_timestamp()),
+        )
+    }
+
+    /// Stop a user's lurk, returning since when they were lurking, if at all.
+    pub fn end_lurk(&self, user: &UserRef) -> Result<Option<OffsetDateTime>> {
+        db::query_one::<_, i64>(
+            &self.conn(),
+            include_str!("../queries/lurkers/end.sql"),
+            (user.source, &user.id),
+        )?
+        .map(OffsetDateTime::from_unix_timestamp)
+        .transpose()
+        .map_err(Into::into)
+    }
+
+    /// Mark the streamer as away, with the message that mentions are answered with.
+    pub fn set_away(&self, message: &str, since: OffsetDateTime) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/away/set.sql"),
+            (message, since.unix_timestamp()),
+        )
+    }
+
+    /// Message and start of the streamer's absence, if they're away.
+    pub fn away(&self) -> Result<Option<(String, OffsetDateTime)>> {
+        db::query_one::<_, (String, i64)>(
+            &self.conn(),
+            include_str!("../queries/away/get.sql"),
+            db::NO_PARAMS,
+        )?
+        .map(|(message, since)| Ok((message, OffsetDateTime::from_unix_timestamp(since)?)))
+        .transpose()
+    }
+
+    /// Mark the streamer as back, returning since when they were away, if at all.
+    pub fn clear_away(&self) -> Result<Option<OffsetDateTime>> {
+        db::query_one::<_, i64>(
+            &self.conn(),
+            include_str!("../queries/away/clear.sql"),
+            db::NO_PARAMS,
+        )?
+        .map(OffsetDateTime::from_unix_timestamp)
+        .transpose()
+        .map_err(Into::into)
+    }
+
+    /// Keep the command list pinned in the Discord channel, or stop updating it. Moving the list to
+    /// another channel forgets the old message, so a new one is posted.
+    pub fn set_command_list(&self, channel: Option<NonZero<u64>>) -> Result<()> {
+        match channel {
+            Some(channel) => db::exec(
+                &self.conn(),
+                include_str!("../queries/command_list/set.sql"),
+                channel,
+            ),
+            None => db::exec(
+                &self.conn(),
+                include_str!("../queries/command_list/remove.sql"),
+                db::NO_PARAMS,
+            ),
+        }
+    }
+
+    /// Location of the pinned command list, if it's enabled.
+    pub fn command_list(&self) -> Result<Option<CommandList>> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/command_list/get.sql"),
+            db::NO_PARAMS,
+        )
+    }
+
+    /// Remember the message that holds the command list, as long as the list is still kept in the
+    /// same channel.
+    pub fn set_command_list_message(
+        &self,
+        channel: NonZero<u64>,
+        message: NonZero<u64>,
+    ) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/command_list/set_message.sql"),
+            (message, channel),
+        )
+    }
+
+    /// Load a secret and decrypt it with the key.
+    pub fn secret(&self, key: &Key, name: &str) -> Result<Option<String>> {
+        db::query_one::<_, String>(
+            &self.conn(),
+            include_str!("../queries/secrets/get.sql"),
+            name,
+        )?
+        .map(|value| key.decrypt(&value))
+        .transpose()
+        .with_context(|| format!("failed decrypting secret `{name}`"))
+    }
+
+    /// Encrypt a secret with the key and save it, replacing any previous value.
+    pub fn set_secret(&self, key: &Key, name: &str, value: &str) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/secrets/set.sql"),
+            (name, key.encrypt(value)?),
+        )
+    }
+
+    /// Encrypt all secrets with a new key. The `persist` callback is run before the changes are
+    /// committed, to save the new key, so a failure to do so keeps the old key usable.
+    pub fn rotate_secrets(
+        &self,
+        old: &Key,
+        new: &Key,
+        persist: impl FnOnce() -> Result<()>,
+    ) -> Result<usize> {
+        self.conn().transaction(|tx| {
+            let secrets = db::query_vec::<_, (String, String)>(
+                tx,
+                include_str!("../queries/secrets/list.sql"),
+                db::NO_PARAMS,
+            )?;
+
+            for (name, value) in &secrets {
+                let value = old
+                    .decrypt(value)
+                    .with_context(|| format!("failed decrypting secret `{name}`"))?;
+                db::exec(
+                    tx,
+                    include_str!("../queries/secrets/set.sql"),
+                    (name, new.encrypt(&value)?),
+                )?;
+            }
+
+            persist()?;
+            Ok(secrets.len())
+        })
+    }
+
+    /// Save the birthday of a user, keeping whether it's announced.
+    pub fn set_birthday(&self, user: &UserRef, month: Month, day: u8) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/birthdays/set.sql"),
+            (user.source, &user.id, u8::from(month), day),
+        )
+    }
+
+    /// Get the birthday of a user, if they registered it, and whether it's announced.
+    pub fn birthday(&self, user: &UserRef) -> Result<Option<(Month, u8, bool)>> {
+        db::query_one::<_, (u8, u8, bool)>(
+            &self.conn(),
+            include_str!("../queries/birthdays/get.sql"),
+            (user.source, &user.id),
+        )?
+        .map(|(month, day, announce)| Ok((Month::try_from(month)?, day, announce)))
+        .transpose()
+    }
+
+    /// Remove the birthday of a user, returning whether they registered one.
+    pub fn remove_birthday(&self, user: &UserRef) -> Result<bool> {
+        db::query_one::<_, i64>(
+            &self.conn(),
+            include_str!("../queries/birthdays/remove.sql"),
+            (user.source, &user.id),
+        )
+        .map(|id| id.is_some())
+    }
+
+    /// Change whether a user's birthday is announced, returning whether they registered one.
+    pub fn announce_birthday(&self, user: &UserRef, announce: bool) -> Result<bool> {
+        db::query_one::<_, i64>(
+            &self.conn(),
+            include_str!("../queries/birthdays/announce.sql"),
+            (announce, user.source, &user.id),
+        )
+        .map(|id| id.is_some())
+    }
+
+    /// IDs of all users of the service, whose birthday is on the given day and announced.
+    pub fn list_birthdays(&self, source: Source, month: Month, day: u8) -> Result<Vec<String>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/birthdays/list.sql"),
+            (source, u8::from(month), day),
+        )
+    }
+
+    /// Forget all lurkers, which happens once the stream ends.
+    pub fn clear_lurkers(&self) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/lurkers/clear.sql"),
+            db::NO_PARAMS,
+        )
+    }
+
+    /// Subscribe a user to a direct message whenever the stream goes live, or unsubscribe them.
+    pub fn set_live_notification(
+        &self,
+        user: &UserRef,
+        enabled: bool,
+        now: OffsetDateTime,
+    ) -> Result<()> {
+        if enabled {
+            db::exec(
+                &self.conn(),
+                include_str!("../queries/live_notifications/add.sql"),
+                (user.source, &user.id, now.unix_timestamp()),
+            )
+        } else {
+            self.remove_live_notification(user)
+        }
+    }
+
+    /// Stop sending a direct message to the user whenever the stream goes live.
+    pub fn remove_live_notification(&self, user: &UserRef) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/live_notifications/remove.sql"),
+            (user.source, &user.id),
+        )
+    }
+
+    /// Whether the user wants a direct message whenever the stream goes live.
+    pub fn live_notification(&self, user: &UserRef) -> Result<bool> {
+        db::query_one::<_, i64>(
+            &self.conn(),
+            include_str!("../queries/live_notifications/get.sql"),
+            (user.source, &user.id),
+        )
+        .map(|id| id.is_some())
+    }
+
+    /// IDs of all users of the service, that want a direct message when the stream goes live.
+    pub fn list_live_notifications(&self, source: Source) -> Result<Vec<String>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/live_notifications/list.sql"),
+            source,
+        )
+    }
+
+    /// Record whether the direct message to a user could be delivered. Failures add up until the
+    /// next successful delivery, and the amount of consecutive failures is returned.
+    pub fn record_live_notification(&self, user: &UserRef, delivered: bool) -> Result<u32> {
+        if delivered {
+            db::exec(
+                &self.conn(),
+                include_str!("../queries/live_notifications/reset.sql"),
+                (user.source, &user.id),
+            )
+            .map(|()| 0)
+        } else {
+            db::query_one(
+                &self.conn(),
+                include_str!("../queries/live_notifications/fail.sql"),
+                (user.source, &user.id),
+            )
+            .map(Option::unwrap_or_default)
+        }
+    }
+
+    /// Opt the user out of the watch time tracking, or back into it.
+    pub fn set_watch_time_opt_out(
+        &self,
+        user: &UserRef,
+        opt_out: bool,
+        now: OffsetDateTime,
+    ) -> Result<()> {
+        if opt_out {
+            db::exec(
+                &self.conn(),
+                include_str!("../queries/watch_time_opt_outs/add.sql"),
+                (user.source, &user.id, now.unix_timestamp()),
+            )
+        } else {
+            db::exec(
+                &self.conn(),
+                include_str!("../queries/watch_time_opt_outs/remove.sql"),
+                (user.source, &user.id),
+            )
+        }
+    }
+
+    /// Whether the user opted out of the watch time tracking.
+    pub fn watch_time_opt_out(&self, user: &UserRef) -> Result<bool> {
+        db::query_one::<_, i64>(
+            &self.conn(),
+            include_str!("../queries/watch_time_opt_outs/get.sql"),
+            (user.source, &user.id),
+        )
+        .map(|id| id.is_some())
+    }
+
+    /// IDs of all users of the service, that opted out of the watch time tracking.
+    pub fn list_watch_time_opt_outs(&self, source: Source) -> Result<Vec<String>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/watch_time_opt_outs/list.sql"),
+            source,
+        )
+    }
+
+    /// Add a new trigger word, or change the response of an existing one.
+    pub fn add_trigger(&self, word: &str, content: &str, cooldown: u64) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/triggers/add.sql"),
+            (word, content, cooldown),
+        )
+    }
+
+    /// Remove a trigger word, returning whether it existed.
+    pub fn remove_trigger(&self, word: &str) -> Result<bool> {
+        let conn = self.conn();
+        db::exec(
+            &conn,
+            include_str!("../queries/triggers/remove_disabled.sql"),
+            word,
+        )?;
+        db::query_one::<_, i64>(&conn, include_str!("../queries/triggers/remove.sql"), word)
+            .map(|id| id.is_some())
+    }
+
+    /// Change the cooldown of a trigger word, returning whether it exists.
+    pub fn set_trigger_cooldown(&self, word: &str, cooldown: u64) -> Result<bool> {
+        db::query_one::<_, i64>(
+            &self.conn(),
+            include_str!("../queries/triggers/cooldown.sql"),
+            (cooldown, word),
+        )
+        .map(|id| id.is_some())
+    }
+
+    /// Turn a trigger word on or off for a single service.
+    pub fn set_trigger_enabled(&self, word: &str, source: Source, enabled: bool) -> Result<()> {
+        let query = if enabled {
+            include_str!("../queries/triggers/enable.sql")
+        } else {
+            include_str!("../queries/triggers/disable.sql")
+        };
+
+        db::exec(&self.conn(), query, (word, source))
+    }
+
+    /// List all trigger words, together with the services they're turned off for.
+    pub fn list_triggers(&self) -> Result<Vec<Trigger>> {
+        let conn = self.conn();
+        let mut triggers = db::query_vec::<_, Trigger>(
+            &conn,
+            include_str!("../queries/triggers/list.sql"),
+            db::NO_PARAMS,
+        )?;
+        let disabled = db::query_vec::<_, (String, Source)>(
+            &conn,
+            include_str!("../queries/triggers/list_disabled.sql"),
+            db::NO_PARAMS,
+        )?;
+
+        for (word, source) in disabled {
+            if let Some(trigger) = triggers.iter_mut().find(|trigger| trigger.word == word) {
+                trigger.disabled.push(source);
+            }
+        }
+
+        Ok(triggers)
+    }
+
+    /// List the trigger words that are turned on for the service.
+    pub fn active_triggers(&self, source: Source) -> Result<Vec<Trigger>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/triggers/active.sql"),
+            source,
+        )
+    }
+
+    /// Add a new FAQ entry, or replace the pattern and answer of an existing one.
+    pub fn add_faq(&self, faq: &Faq) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/faqs/add.sql"),
+            (&faq.name, &faq.pattern, faq.regex, &faq.answer),
+        )
+    }
+
+    /// Remove a FAQ entry, returning whether it existed.
+    pub fn remove_faq(&self, name: &str) -> Result<bool> {
+        db::query_one::<_, i64>(
+            &self.conn(),
+            include_str!("../queries/faqs/remove.sql"),
+            name,
+        )
+        .map(|id| id.is_some())
+    }
+
+    /// List all FAQ entries, ordered by their name.
+    pub fn list_faqs(&self) -> Result<Vec<Faq>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/faqs/list.sql"),
+            db::NO_PARAMS,
+        )
+    }
+
+    /// Start watching a crate for new versions, returning whether it wasn't watched yet.
+    pub fn watch_crate(&self, watched: &WatchedCrate) -> Result<bool> {
+        db::query_one::<_, i64>(
+            &self.conn(),
+            include_str!("../queries/watched_crates/add.sql"),
+            (&watched.name, &watched.version, &watched.etag),
+        )
+        .map(|id| id.is_some())
+    }
+
+    /// Stop watching a crate, returning whether it was watched.
+    pub fn unwatch_crate(&self, name: &str) -> Result<bool> {
+        db::query_one::<_, i64>(
+            &self.conn(),
+            include_str!("../queries/watched_crates/remove.sql"),
+            name,
+        )
+        .map(|id| id.is_some())
+    }
+
+    /// List all watched crates, ordered by their name.
+    pub fn watched_crates(&self) -> Result<Vec<WatchedCrate>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/watched_crates/list.sql"),
+            db::NO_PARAMS,
+        )
+    }
+
+    /// Remember the latest version of a watched crate.
+    pub fn update_watched_crate(&self, watched: &WatchedCrate) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/watched_crates/update.sql"),
+            (&watched.version, &watched.etag, &watched.name),
+        )
+    }
+
+    /// Remember an event of a GitHub repository, like a release, returning whether it's new.
+    pub fn add_github_event(&self, repo: &str, kind: &str, item: i64) -> Result<bool> {
+        db::query_one::<_, i64>(
+            &self.conn(),
+            include_str!("../queries/github_events/add.sql"),
+            (repo, kind, item),
+        )
+        .map(|id| id.is_some())
+    }
+
+    /// Check whether any events of the kind were remembered for a GitHub repository yet.
+    pub fn has_github_events(&self, repo: &str, kind: &str) -> Result<bool> {
+        db::query_one::<_, i64>(
+            &self.conn(),
+            include_str!("../queries/github_events/any.sql"),
+            (repo, kind),
+        )
+        .map(|found| found.is_some())
+    }
+
+    /// Remember a stable Rust release, returning whether it's new.
+    pub fn add_rust_release(&self, version: &str) -> Result<bool> {
+        db::query_one::<_, i64>(
+            &self.conn(),
+            include_str!("../queries/rust_releases/add.sql"),
+            version,
+        )
+        .map(|id| id.is_some())
+    }
+
+    /// Check whether any stable Rust release was remembered yet.
+    pub fn has_rust_releases(&self) -> Result<bool> {
+        db::query_one::<_, i64>(
+            &self.conn(),
+            include_str!("../queries/rust_releases/any.sql"),
+            db::NO_PARAMS,
+        )
+        .map(|found| found.is_some())
+    }
+
+    /// Save a pinned message as highlight. Pinning it again only updates who pinned it and when.
+    pub fn add_highlight(
+        &self,
+        highlight: &Highlight,
+        admin: AdminId,
+        now: OffsetDateTime,
+    ) -> Result<()> {
+        self.conn().transaction(|tx| {
+            let id = db::query_one::<_, i64>(
+                tx,
+                include_str!("../queries/highlights/add.sql"),
+                (
+                    highlight.guild,
+                    highlight.channel,
+                    highlight.message,
+                    &highlight.author,
+                    &highlight.content,
+                    admin,
+                    now.unix_timestamp(),
+                ),
+            )?
+            .context("highlight wasn't saved")?;
+
+            db::exec(
+                tx,
+                include_str!("../queries/tracked_messages/add.sql"),
+                (highlight.message, TRACKED_HIGHLIGHT, id),
+            )
+        })
+    }
+
+    /// Update the records that were created from a Discord message, after it was edited. Returns
+    /// the amount of updated records.
+    pub fn edit_tracked_message(&self, message: NonZero<u64>, content: &str) -> Result<usize> {
+        self.conn().transaction(|tx| {
+            let records = db::query_vec::<_, (String, i64)>(
+                tx,
+                include_str!("../queries/tracked_messages/list.sql"),
+                message,
+            )?;
+
+            for (kind, record) in &records {
+                match kind.as_str() {
+                    TRACKED_HIGHLIGHT => db::exec(
+                        tx,
+                        include_str!("../queries/highlights/update.sql"),
+                        (content, record),
+                    )?,
+                    _ => bail!("unknown kind of tracked record `{kind}`"),
+                }
+            }
+
+            Ok(records.len())
+        })
+    }
+
+    /// Remove the records that were created from a Discord message, after it was deleted. Returns
+    /// the amount of removed records.
+    pub fn remove_tracked_message(&self, message: NonZero<u64>) -> Result<usize> {
+        self.conn().transaction(|tx| {
+            let records = db::query_vec::<_, (String, i64)>(
+                tx,
+                include_str!("../queries/tracked_messages/list.sql"),
+                message,
+            )?;
+
+            for (kind, record) in &records {
+                match kind.as_str() {
+                    TRACKED_HIGHLIGHT => {
+                        db::exec(tx, include_str!("../queries/highlights/remove.sql"), record)?;
+                    }
+                    _ => bail!("unknown kind of tracked record `{kind}`"),
+                }
+            }
+
+            db::exec(
+                tx,
+                include_str!("../queries/tracked_messages/remove.sql"),
+                message,
+            )?;
+
+            Ok(records.len())
+        })
+    }
+
+    /// List the most recently pinned highlights, newest first.
+    pub fn recent_highlights(&self, limit: usize) -> Result<Vec<Highlight>> {
+        db::query_vec(
+            &self.conn(),
+            include_str!("../queries/highlights/list.sql"),
+            limit,
+        )
+    }
+
+    /// Turn a feature on or off.
+    pub fn set_toggle(&self, name: &str, enabled: bool) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/toggles/set.sql"),
+            (name, enabled),
+        )
+    }
+
+    /// Whether a feature is turned on, or the default if it was never changed.
+    pub fn toggle(&self, name: &str, default: bool) -> Result<bool> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/toggles/get.sql"),
+            name,
+        )
+        .map(|enabled| enabled.unwrap_or(default))
+    }
+
+    /// Record an action of an admin in the audit log.
+    pub fn audit(
+        &self,
+        actor: AdminId,
+        action: &str,
+        details: &str,
+        now: OffsetDateTime,
+    ) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/audit_log/add.sql"),
+            (now.unix_timestamp(), actor, action, details),
+        )
+    }
+}
+
+mod migrate {
+    use std::{
+        collections::{HashMap, HashSet},
+        fs,
+        io::ErrorKind,
+        num::NonZeroU64,
+    };
+
+    use anyhow::{Context, Result};
+    use serde::Deserialize;
+
+    use super::Connection;
+    use crate::{db, dirs::DIRS};
+
+    #[derive(Deserialize)]
+    struct State {
+        #[serde(default)]
+        custom_commands: HashMap<String, HashMap<Source, String>>,
+        #[serde(default)]
+        admins: HashSet<NonZeroU64>,
+    }
+
+    #[derive(Eq, Hash, PartialEq, Deserialize)]
+    enum Source {
+        Discord,
+        Twitch,
+    }
+
+    fn load() -> Result<Option<State>> {
+        let state = match fs::read(DIRS.state_file()) {
+            Ok(buf) => buf,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).context("failed reading state file"),
+        };
+
+        serde_json::from_slice(&state)
+            .context("failed parsing state data")
+            .map(Some)
+    }
+
+    /// Amount of admins and custom commands in the former state file, that are imported on the
+    /// next start, if the file still exists.
+    pub fn pending() -> Result<Option<usize>> {
+        Ok(load()?.map(|state| {
+            state.admins.len()
+                + state
+                    .custom_commands
+                    .values()
+                    .map(HashMap::len)
+                    .sum::<usize>()
+        }))
+    }
+
+    pub fn run(conn: &Connection) -> Result<()> {
+        let Some(state) = load()? else { return Ok(()) };
+
+        conn.transaction(|tx| {
+            for admin in state.admins {
+                db::exec(tx, include_str!("../queries/admins/add.sql"), admin)?;
+            }
+
+            for (name, contents) in state.custom_commands {
+                for (source, content) in contents {
+                    let source = match source {
+                        Source::Discord => crate::api::Source::Discord,
+                        Source::Twitch => crate::api::Source::Twitch,
+                    };
+
+                    // The old state file didn't keep track of who created the commands.
+                    db::exec(
+                        tx,
+                        include_str!("../queries/custom_cmds/add.sql"),
+                        (source, &name, content, None::<String>, None::<i64>),
+                    )?;
+                }
+            }
+
+            Ok(())
+        })?;
+
+        fs::remove_file(DIRS.state_file()).context("failed deleting obsolete state file")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::{date, datetime};
+
+    use super::*;
+    use crate::api::AuthorId;
+
+    #[test]
+    fn admin_roundtrip() {
+        let state = State::in_memory().unwrap();
+        let id = AdminId::new(1).unwrap();
+
+        assert!(!state.is_admin(id).unwrap());
+
+        state.add_admin(id).unwrap();
+        assert!(state.is_admin(id).unwrap());
+        assert_eq!([id], state.list_admins().unwrap().as_slice());
+
+        state.remove_admin(id).unwrap();
+        assert!(!state.is_admin(id).unwrap());
+    }
+
+    #[test]
+    fn admin_roles() {
+        let state = State::in_memory().unwrap();
+        let id = NonZero::new(5).unwrap();
+
+        state.add_admin_role(id).unwrap();
+        state.add_admin_role(id).unwrap();
+        assert_eq!([id], state.list_admin_roles().unwrap().as_slice());
+
+        state.remove_admin_role(id).unwrap();
+        assert!(state.list_admin_roles().unwrap().is_empty());
+    }
+
+    #[test]
+    fn commands_roundtrip() {
+        let state = State::in_memory().unwrap();
+
+        for source in Source::ALL {
+            assert!(state.list_custom_command_names(source).unwrap().is_empty());
+        }
+
+        state
+            .add_custom_command(
+                Source::Discord,
+                "hi",
+                "hello",
+                &AuthorId::Console.user_ref(),
+                OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+        assert_eq!(
+            Some("hello".to_owned()),
+            state.get_custom_command(Source::Discord, "hi").unwrap()
+        );
+
+        state.remove_custom_command(Source::Discord, "hi").unwrap();
+        assert_eq!(
+            None,
+            state.get_custom_command(Source::Discord, "hi").unwrap()
+        );
+
+        state
+            .add_custom_command(
+                Source::Twitch,
+                "hi",
+                "hello",
+                &AuthorId::Console.user_ref(),
+                OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+        assert_eq!(
+            Some("hello".to_owned()),
+            state.get_custom_command(Source::Twitch, "hi").unwrap()
+        );
+
+        state
+            .add_custom_command(
+                Source::YouTube,
+                "hi",
+                "hello",
+                &AuthorId::Console.user_ref(),
+                OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+        assert_eq!(
+            vec![
+                ("hi".to_owned(), Source::Twitch),
+                ("hi".to_owned(), Source::YouTube)
+            ],
+            state.list_custom_commands().unwrap()
+        );
+
+        state.remove_custom_command_by_name("hi").unwrap();
+        assert_eq!(
+            None,
+            state.get_custom_command(Source::Twitch, "hi").unwrap()
+        );
+
+        assert!(state.list_custom_commands().unwrap().is_empty());
+    }
+
+    #[test]
+    fn user_notes_roundtrip() {
+        let state = State::in_memory().unwrap();
+        let author = AdminId::new(1).unwrap();
+        let user = UserRef {
+            source: Source::Twitch,
+            id: "someone".to_owned(),
+        };
+        let other = UserRef {
+            source: Source::Discord,
+            id: "someone".to_owned(),
+        };
+
+        let first = state
+            .add_user_note(&user, "one", author, OffsetDateTime::UNIX_EPOCH)
+            .unwrap();
+        let second = state
+            .add_user_note(&user, "two", author, OffsetDateTime::UNIX_EPOCH)
+            .unwrap();
+        state
+            .add_user_note(&other, "three", author, OffsetDateTime::UNIX_EPOCH)
+            .unwrap();
+
+        let notes = state.list_user_notes(&user).unwrap();
+        assert_eq!(
+            vec![(first, "one"), (second, "two")],
+            notes
+                .iter()
+                .map(|note| (note.id, note.content.as_str()))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(author, notes[0].author);
+
+        assert_eq!(Some(user.clone()), state.remove_user_note(first).unwrap());
+        assert_eq!(None, state.remove_user_note(first).unwrap());
+        assert_eq!(1, state.list_user_notes(&user).unwrap().len());
+
+        state
+            .audit(author, "usernote remove", "#1", OffsetDateTime::UNIX_EPOCH)
+            .unwrap();
+    }
+
+    #[test]
+    fn song_requests_roundtrip() {
+        let state = State::in_memory().unwrap();
+        let user = UserRef {
+            source: Source::Twitch,
+            id: "someone".to_owned(),
+        };
+        let other = UserRef {
+            source: Source::Discord,
+            id: "1".to_owned(),
+        };
+
+        assert_eq!(None, state.skip_song_request().unwrap().map(|song| song.id));
+
+        state
+            .add_song_request(
+                SongPlatform::YouTube,
+                "https://youtu.be/a",
+                &user,
+                OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+        state
+            .add_song_request(
+                SongPlatform::Spotify,
+                "https://spotify.com/b",
+                &other,
+                OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+        assert!(state
+            .add_song_request(
+                SongPlatform::YouTube,
+                "https://youtu.be/a",
+                &other,
+                OffsetDateTime::UNIX_EPOCH
+            )
+            .is_err());
+
+        assert!(state.has_song_request("https://youtu.be/a").unwrap());
+        assert_eq!(2, state.count_song_requests(None).unwrap());
+        assert_eq!(1, state.count_song_requests(Some(&user)).unwrap());
+
+        let next = state.skip_song_request().unwrap().unwrap();
+        assert_eq!(SongPlatform::YouTube, next.platform);
+        assert_eq!(user.id, next.user_id);
+
+        let queue = state.list_song_requests().unwrap();
+        assert_eq!(
+            vec!["https://spotify.com/b"],
+            queue
+                .iter()
+                .map(|song| song.url.as_str())
+                .collect::<Vec<_>>()
+        );
+        assert!(!state.has_song_request("https://youtu.be/a").unwrap());
+    }
+
+    #[test]
+    fn points_roundtrip() {
+        let state = State::in_memory().unwrap();
+        let user = UserRef {
+            source: Source::Twitch,
+            id: "1234".to_owned(),
+        };
+        let now = OffsetDateTime::now_utc();
+        let interval = Duration::from_mins(1);
+
+        assert_eq!(0, state.points(&user).unwrap());
+        assert_eq!(
+            Some(5),
+            state
+                .earn_points(&user, "someone", 5, now, interval)
+                .unwrap()
+        );
+        assert_eq!(
+            None,
+            state
+                .earn_points(&user, "someone", 5, now + Duration::from_secs(30), interval)
+                .unwrap()
+        );
+        assert_eq!(
+            Some(10),
+            state
+                .earn_points(&user, "someone", 5, now + Duration::from_mins(1), interval)
+                .unwrap()
+        );
+        assert_eq!(
+            Some(user.clone()),
+            state.find_points_user(Source::Twitch, "someone").unwrap()
+        );
+
+        assert_eq!(None, state.spend_points(&user, 11).unwrap());
+        assert_eq!(Some(3), state.spend_points(&user, 7).unwrap());
+        assert_eq!(3, state.points(&user).unwrap());
+
+        state.set_command_cost("hug", 50).unwrap();
+        state.set_command_cost("hi", 10).unwrap();
+        assert_eq!(Some(50), state.command_cost("hug").unwrap());
+        assert_eq!(
+            vec![("hi".to_owned(), 10), ("hug".to_owned(), 50)],
+            state.list_command_costs().unwrap()
+        );
+
+        state.set_command_cost("hug", 0).unwrap();
+        assert_eq!(None, state.command_cost("hug").unwrap());
+    }
+
+    #[test]
+    fn usage_alerts() {
+        let state = State::in_memory().unwrap();
+        let (today, tomorrow) = (date!(2025 - 03 - 01), date!(2025 - 03 - 02));
+
+        state.set_usage_alert("unknown", 50).unwrap();
+        state.set_usage_alert("help", 10).unwrap();
+        assert_eq!(
+            vec![("help".to_owned(), 10), ("unknown".to_owned(), 50)],
+            state.list_usage_alerts().unwrap()
+        );
+
+        assert!(state.trip_usage_alert("help", today).unwrap());
+        assert!(!state.trip_usage_alert("help", today).unwrap());
+        assert!(state.trip_usage_alert("help", tomorrow).unwrap());
+        assert!(!state.trip_usage_alert("other", today).unwrap());
+
+        state.set_usage_alert("help", 20).unwrap();
+        assert!(state.trip_usage_alert("help", tomorrow).unwrap());
+
+        state.set_usage_alert("help", 0).unwrap();
+        assert_eq!(
+            vec![("unknown".to_owned(), 50)],
+            state.list_usage_alerts().unwrap()
+        );
+    }
+
+    #[test]
+    fn command_availability() {
+        let state = State::in_memory().unwrap();
+
+        state
+            .set_command_availability("sr", Availability::Live)
+            .unwrap();
+        state
+            .set_command_availability("hi", Availability::Offline)
+            .unwrap();
+        assert_eq!(
+            Availability::Live,
+            state.command_availability("sr").unwrap()
+        );
+        assert_eq!(
+            vec![
+                ("hi".to_owned(), Availability::Offline),
+                ("sr".to_owned(), Availability::Live)
+            ],
+            state.list_command_availability().unwrap()
+        );
+
+        state
+            .set_command_availability("sr", Availability::Always)
+            .unwrap();
+        assert_eq!(
+            Availability::Always,
+            state.command_availability("sr").unwrap()
+        );
+        assert_eq!(1, state.list_command_availability().unwrap().len());
+    }
+
+    #[test]
+    fn channel_rules() {
+        let state = State::in_memory().unwrap();
+        let channel = |guild, channel| GuildChannel {
+            guild: NonZero::new(guild).unwrap(),
+            channel: NonZero::new(channel).unwrap(),
+        };
+
+        state
+            .set_channel_rule(channel(1, 11), Some(ChannelRule::Allow))
+            .unwrap();
+        state
+            .set_channel_rule(channel(1, 10), Some(ChannelRule::Allow))
+            .unwrap();
+        state
+            .set_channel_rule(channel(2, 20), Some(ChannelRule::Deny))
+            .unwrap();
+        state
+            .set_channel_rule(channel(1, 10), Some(ChannelRule::Deny))
+            .unwrap();
+        assert_eq!(
+            vec![
+                (NonZero::new(10).unwrap(), ChannelRule::Deny),
+                (NonZero::new(11).unwrap(), ChannelRule::Allow),
+            ],
+            state.channel_rules(NonZero::new(1).unwrap()).unwrap()
+        );
+
+        state.set_channel_rule(channel(1, 10), None).unwrap();
+        assert_eq!(
+            1,
+            state.channel_rules(NonZero::new(1).unwrap()).unwrap().len()
+        );
+        assert_eq!(
+            1,
+            state.channel_rules(NonZero::new(2).unwrap()).unwrap().len()
+        );
+    }
+
+    #[test]
+    fn silenced_channels() {
+        let state = State::in_memory().unwrap();
+        let channel = NonZero::new(10).unwrap();
+        let until = OffsetDateTime::from_unix_timestamp(1_700_000_000).unwrap();
+
+        assert_eq!(None, state.silenced_until(channel).unwrap());
+
+        state.set_silence(channel, Some(until)).unwrap();
+        state
+            .set_silence(channel, Some(until + Duration::from_hours(1)))
+            .unwrap();
+        assert_eq!(
+            Some(until + Duration::from_hours(1)),
+            state.silenced_until(channel).unwrap()
+        );
+
+        state.set_silence(channel, None).unwrap();
+        assert_eq!(None, state.silenced_until(channel).unwrap());
+    }
+
+    #[test]
+    fn featured_commands() {
+        let state = State::in_memory().unwrap();
+        let now = OffsetDateTime::now_utc();
+
+        for name in ["a", "b", "c"] {
+            state.add_featured_command(name, now, 2).unwrap();
+        }
+
+        assert_eq!(
+            vec!["c".to_owned(), "b".to_owned()],
+            state.recent_featured_commands(5).unwrap()
+        );
+        assert_eq!(
+            vec!["c".to_owned()],
+            state.recent_featured_commands(1).unwrap()
+        );
+    }
+
+    #[test]
+    fn points_games() {
+        let state = State::in_memory().unwrap();
+        let (first, second) = (
+            UserRef {
+                source: Source::Twitch,
+                id: "1".to_owned(),
+            },
+            UserRef {
+                source: Source::Twitch,
+                id: "2".to_owned(),
+            },
+        );
+        let now = OffsetDateTime::now_utc();
+        let interval = Duration::from_mins(1);
+
+        state
+            .earn_points(&first, "first", 10, now, interval)
+            .unwrap();
+        state
+            .earn_points(&second, "second", 4, now, interval)
+            .unwrap();
+
+        assert_eq!(None, state.wager_points(&first, 11, true).unwrap());
+        assert_eq!(Some(15), state.wager_points(&first, 5, true).unwrap());
+        assert_eq!(Some(12), state.wager_points(&first, 3, false).unwrap());
+
+        assert!(!state.duel_points(&second, &first, 5).unwrap());
+        assert!(!state.duel_points(&first, &second, 5).unwrap());
+        assert_eq!(
+            (12, 4),
+            (
+                state.points(&first).unwrap(),
+                state.points(&second).unwrap()
+            )
+        );
+
+        assert!(state.duel_points(&second, &first, 4).unwrap());
+        assert_eq!(
+            (8, 8),
+            (
+                state.points(&first).unwrap(),
+                state.points(&second).unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn pronouns_roundtrip() {
+        let state = State::in_memory().unwrap();
+        let user = UserRef {
+            source: Source::Discord,
+            id: "1".to_owned(),
+        };
+
+        assert_eq!(None, state.pronouns(&user).unwrap());
+        state.set_pronouns(&user, "she/her").unwrap();
+        state.set_pronouns(&user, "they/them").unwrap();
+        assert_eq!(Some("they/them".to_owned()), state.pronouns(&user).unwrap());
+
+        assert!(state.remove_pronouns(&user).unwrap());
+        assert!(!state.remove_pronouns(&user).unwrap());
+        assert_eq!(None, state.pronouns(&user).unwrap());
+    }
+
+    #[test]
+    fn user_prefs_roundtrip() {
+        let state = State::in_memory().unwrap();
+        let user = UserRef {
+            source: Source::Twitch,
+            id: "1".to_owned(),
+        };
+
+        state.set_user_pref(&user, "unit", "celsius").unwrap();
+        state.set_user_pref(&user, "unit", "fahrenheit").unwrap();
+        state.set_user_pref(&user, "locale", "de").unwrap();
+        assert_eq!(
+            vec![
+                ("locale".to_owned(), "de".to_owned()),
+                ("unit".to_owned(), "fahrenheit".to_owned())
+            ],
+            state.user_prefs(&user).unwrap()
+        );
+
+        assert!(state.remove_user_pref(&user, "locale").unwrap());
+        assert!(!state.remove_user_pref(&user, "locale").unwrap());
+        assert_eq!(1, state.user_prefs(&user).unwrap().len());
+    }
+
+    #[test]
+    fn toggles() {
+        let state = State::in_memory().unwrap();
+
+        assert!(state.toggle("shoutout", true).unwrap());
+        assert!(!state.toggle("shoutout", false).unwrap());
+
+        state.set_toggle("shoutout", false).unwrap();
+        assert!(!state.toggle("shoutout", true).unwrap());
+
+        state.set_toggle("shoutout", true).unwrap();
+        assert!(state.toggle("shoutout", false).unwrap());
+    }
+
+    #[test]
+    fn live_notifications() {
+        let state = State::in_memory().unwrap();
+        let user = UserRef {
+            source: Source::Discord,
+            id: "1".to_owned(),
+        };
+
+        assert!(!state.live_notification(&user).unwrap());
+        state
+            .set_live_notification(&user, true, OffsetDateTime::UNIX_EPOCH)
+            .unwrap();
+        state
+            .set_live_notification(&user, true, OffsetDateTime::UNIX_EPOCH)
+            .unwrap();
+        assert!(state.live_notification(&user).unwrap());
+        assert_eq!(
+            vec!["1".to_owned()],
+            state.list_live_notifications(Source::Discord).unwrap()
+        );
+        assert!(state
+            .list_live_notifications(Source::Twitch)
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(1, state.record_live_notification(&user, false).unwrap());
+        assert_eq!(2, state.record_live_notification(&user, false).unwrap());
+        assert_eq!(0, state.record_live_notification(&user, true).unwrap());
+        assert_eq!(1, state.record_live_notification(&user, false).unwrap());
+
+        state
+            .set_live_notification(&user, false, OffsetDateTime::UNIX_EPOCH)
+            .unwrap();
+        assert!(!state.live_notification(&user).unwrap());
+    }
+
+    #[test]
+    fn triggers() {
+        let state = State::in_memory().unwrap();
+
+        state.add_trigger("hello", "Hi there!", 60).unwrap();
+        state.add_trigger("hello", "Hey!", 30).unwrap();
+        state.add_trigger("rust", "Rust is great", 60).unwrap();
+        assert!(state.set_trigger_cooldown("rust", 10).unwrap());
+        assert!(!state.set_trigger_cooldown("missing", 10).unwrap());
+
+        state
+            .set_trigger_enabled("rust", Source::Twitch, false)
+            .unwrap();
+        let active = state.active_triggers(Source::Twitch).unwrap();
+        assert_eq!(1, active.len());
+        assert_eq!(
+            ("hello", "Hey!", 60),
+            (
+                active[0].word.as_str(),
+                active[0].content.as_str(),
+                active[0].cooldown
+            )
+        );
+
+        let triggers = state.list_triggers().unwrap();
+        assert_eq!(vec![Source::Twitch], triggers[1].disabled);
+        assert_eq!(10, triggers[1].cooldown);
+
+        state
+            .set_trigger_enabled("rust", Source::Twitch, true)
+            .unwrap();
+        assert_eq!(2, state.active_triggers(Source::Twitch).unwrap().len());
+
+        assert!(state.remove_trigger("rust").unwrap());
+        assert!(!state.remove_trigger("rust").unwrap());
+    }
+
+    #[test]
+    fn faqs() {
+        let state = State::in_memory().unwrap();
+        let faq = |name: &str, regex| Faq {
+            name: name.to_owned(),
+            pattern: "keyboard,keeb".to_owned(),
+            regex,
+            answer: format!("{name}!"),
+        };
+
+        state.add_faq(&faq("keyboard", false)).unwrap();
+        state.add_faq(&faq("keyboard", true)).unwrap();
+        state.add_faq(&faq("editor", false)).unwrap();
+
+        let faqs = state.list_faqs().unwrap();
+        assert_eq!(2, faqs.len());
+        assert_eq!(("editor", false), (faqs[0].name.as_str(), faqs[0].regex));
+        assert_eq!(("keyboard", true), (faqs[1].name.as_str(), faqs[1].regex));
+
+        assert!(state.remove_faq("editor").unwrap());
+        assert!(!state.remove_faq("editor").unwrap());
+    }
+
+    #[test]
+    fn highlights() {
+        let state = State::in_memory().unwrap();
+        let admin = AdminId::new(1).unwrap();
+        let now = OffsetDateTime::from_unix_timestamp(1_730_054_700).unwrap();
+        let highlight = |message| Highlight {
+            guild: NonZero::new(1),
+            channel: NonZero::new(2).unwrap(),
+            message: NonZero::new(message).unwrap(),
+            author: "viewer".to_owned(),
+            content: format!("gem #{message}"),
+        };
+
+        state.add_highlight(&highlight(10), admin, now).unwrap();
+        state
+            .add_highlight(&highlight(11), admin, now + Duration::from_secs(1))
+            .unwrap();
+        state
+            .add_highlight(&highlight(10), admin, now + Duration::from_secs(2))
+            .unwrap();
+
+        assert_eq!(
+            vec![highlight(10), highlight(11)],
+            state.recent_highlights(5).unwrap()
+        );
+        assert_eq!(vec![highlight(10)], state.recent_highlights(1).unwrap());
+
+        let message = NonZero::new(10).unwrap();
+        assert_eq!(1, state.edit_tracked_message(message, "edited").unwrap());
+        assert_eq!("edited", state.recent_highlights(1).unwrap()[0].content);
+
+        assert_eq!(1, state.remove_tracked_message(message).unwrap());
+        assert_eq!(vec![highlight(11)], state.recent_highlights(5).unwrap());
+        assert_eq!(0, state.remove_tracked_message(message).unwrap());
+        assert_eq!(
+            0,
+            state
+                .edit_tracked_message(NonZero::new(12).unwrap(), "edited")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn tracked_messages_roll_back() {
+        let state = State::in_memory().unwrap();
+        let highlight = Highlight {
+            guild: NonZero::new(1),
+            channel: NonZero::new(2).unwrap(),
+            message: NonZero::new(10).unwrap(),
+            author: "viewer".to_owned(),
+            content: "gem".to_owned(),
+        };
+
+        state
+            .add_highlight(
+                &highlight,
+                AdminId::new(1).unwrap(),
+                OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+        // A record of a kind that this version doesn't know about, which fails after the
+        // highlight was already changed.
+        db::exec(
+            &state.conn(),
+            include_str!("../queries/tracked_messages/add.sql"),
+            (highlight.message, "unknown", 1),
+        )
+        .unwrap();
+
+        assert!(state
+            .edit_tracked_message(highlight.message, "edited")
+            .is_err());
+        assert_eq!(vec![highlight.clone()], state.recent_highlights(5).unwrap());
+
+        assert!(state.remove_tracked_message(highlight.message).is_err());
+        assert_eq!(vec![highlight.clone()], state.recent_highlights(5).unwrap());
+        assert_eq!(
+            2,
+            db::query_vec::<_, (String, i64)>(
+                &state.conn(),
+                include_str!("../queries/tracked_messages/list.sql"),
+                highlight.message,
+            )
+            .unwrap()
+            .len()
+        );
+    }
+
+    #[test]
+    fn watched_crates() {
+        let state = State::in_memory().unwrap();
+        let watched = |name: &str, version: &str| WatchedCrate {
+            name: name.to_owned(),
+            version: version.to_owned(),
+            etag: None,
+        };
+
+        assert!(state.watch_crate(&watched("tokio", "1.41.0")).unwrap());
+        assert!(!state.watch_crate(&watched("tokio", "1.0.0")).unwrap());
+        assert!(state.watch_crate(&watched("anyhow", "1.0.91")).unwrap());
+
+        state
+            .update_watched_crate(&WatchedCrate {
+                etag: Some("\"abc\"".to_owned()),
+                ..watched("tokio", "1.42.0")
+            })
+            .unwrap();
+
+        let crates = state.watched_crates().unwrap();
+        assert_eq!(
+            vec![
+                ("anyhow", "1.0.91", None),
+                ("tokio", "1.42.0", Some("\"abc\""))
+            ],
+            crates
+                .iter()
+                .map(|c| (c.name.as_str(), c.version.as_str(), c.etag.as_deref()))
+                .collect::<Vec<_>>()
+        );
+
+        assert!(state.unwatch_crate("tokio").unwrap());
+        assert!(!state.unwatch_crate("tokio").unwrap());
+    }
+
+    #[test]
+    fn github_events() {
+        let state = State::in_memory().unwrap();
+        let repo = "togglebit/togglebot";
+
+        assert!(!state.has_github_events(repo, "release").unwrap());
+        assert!(state.add_github_event(repo, "release", 1).unwrap());
+        assert!(!state.add_github_event(repo, "release", 1).unwrap());
+        assert!(state.add_github_event(repo, "issue", 1).unwrap());
+        assert!(state.has_github_events(repo, "release").unwrap());
+        assert!(!state
+            .has_github_events("togglebit/other", "release")
+            .unwrap());
+    }
+
+    #[test]
+    fn rust_releases() {
+        let state = State::in_memory().unwrap();
+
+        assert!(!state.has_rust_releases().unwrap());
+        assert!(state.add_rust_release("1.82.0").unwrap());
+        assert!(!state.add_rust_release("1.82.0").unwrap());
+        assert!(state.has_rust_releases().unwrap());
+    }
+
+    #[test]
+    fn away_roundtrip() {
+        let state = State::in_memory().unwrap();
+        let since = OffsetDateTime::from_unix_timestamp(1_730_054_700).unwrap();
+
+        assert_eq!(None, state.away().unwrap());
+        assert_eq!(None, state.clear_away().unwrap());
+
+        state.set_away("grabbing food", since).unwrap();
+        state.set_away("at the doctor", since).unwrap();
+        assert_eq!(
+            Some(("at the doctor".to_owned(), since)),
+            state.away().unwrap()
+        );
+
+        assert_eq!(Some(since), state.clear_away().unwrap());
+        assert_eq!(None, state.away().unwrap());
+    }
+
+    #[test]
+    fn command_list_roundtrip() {
+        let state = State::in_memory().unwrap();
+        let (channel, other) = (NonZero::new(10).unwrap(), NonZero::new(20).unwrap());
+        let message = NonZero::new(100).unwrap();
+
+        assert_eq!(None, state.command_list().unwrap());
+
+        state.set_command_list(Some(channel)).unwrap();
+        state.set_command_list_message(channel, message).unwrap();
+        state.set_command_list(Some(channel)).unwrap();
+        assert_eq!(
+            Some((channel, Some(message))),
+            state.command_list().unwrap()
+        );
+
+        state.set_command_list_message(other, message).unwrap();
+        state.set_command_list(Some(other)).unwrap();
+        assert_eq!(Some((other, None)), state.command_list().unwrap());
+
+        state.set_command_list(None).unwrap();
+        assert_eq!(None, state.command_list().unwrap());
+    }
+
+    #[test]
+    fn secret_roundtrip() {
+        let state = State::in_memory().unwrap();
+        let (old, new) = (Key::generate(), Key::generate());
+
+        assert_eq!(None, state.secret(&old, "token").unwrap());
+
+        state.set_secret(&old, "token", "first").unwrap();
+        state.set_secret(&old, "token", "second").unwrap();
+        assert_eq!(
+            Some("second".to_owned()),
+            state.secret(&old, "token").unwrap()
+        );
+
+        assert!(state
+            .rotate_secrets(&old, &new, || anyhow::bail!("failed saving key"))
+            .is_err());
+        assert_eq!(
+            Some("second".to_owned()),
+            state.secret(&old, "token").unwrap()
+        );
+
+        assert_eq!(1, state.rotate_secrets(&old, &new, || Ok(())).unwrap());
+        assert!(state.secret(&old, "token").is_err());
+        assert_eq!(
+            Some("second".to_owned()),
+            state.secret(&new, "token").unwrap()
+        );
+    }
+
+    #[test]
+    fn birthday_roundtrip() {
+        let state = State::in_memory().unwrap();
+        let user = UserRef {
+            source: Source::Discord,
+            id: "1".to_owned(),
+        };
+
+        assert_eq!(None, state.birthday(&user).unwrap());
+        assert!(!state.announce_birthday(&user, false).unwrap());
+
+        state.set_birthday(&user, Month::April, 2).unwrap();
+        assert!(state.announce_birthday(&user, false).unwrap());
+        state.set_birthday(&user, Month::April, 3).unwrap();
+        assert_eq!(
+            Some((Month::April, 3, false)),
+            state.birthday(&user).unwrap()
+        );
+        assert!(state
+            .list_birthdays(Source::Discord, Month::April, 3)
+            .unwrap()
+            .is_empty());
+
+        state.announce_birthday(&user, true).unwrap();
+        assert_eq!(
+            vec!["1".to_owned()],
+            state
+                .list_birthdays(Source::Discord, Month::April, 3)
+                .unwrap()
+        );
+
+        assert!(state.remove_birthday(&user).unwrap());
+        assert!(!state.remove_birthday(&user).unwrap());
+    }
+
+    #[test]
+    fn lurk_roundtrip() {
+        let state = State::in_memory().unwrap();
+        let user = UserRef {
+            source: Source::Twitch,
+            id: "1".to_owned(),
+        };
+        let since = OffsetDateTime::from_unix_timestamp(1_730_054_700).unwrap();
+
+        assert_eq!(None, state.end_lurk(&user).unwrap());
+        state.start_lurk(&user, None, since).unwrap();
+        state
+            .start_lurk(&user, Some("brb"), since + Duration::from_mins(1))
+            .unwrap();
+        assert_eq!(Some(since), state.end_lurk(&user).unwrap());
+        assert_eq!(None, state.end_lurk(&user).unwrap());
+
+        state.start_lurk(&user, None, since).unwrap();
+        state.clear_lurkers().unwrap();
+        assert_eq!(None, state.end_lurk(&user).unwrap());
+    }
+
+    #[test]
+    fn tag_stream() {
+        let state = State::in_memory().unwrap();
+        assert!(state.tag_stream("rust").is_err());
+
+        state
+            .start_stream(&StreamInfo {
+                id: "1".to_owned(),
+                started_at: OffsetDateTime::UNIX_EPOCH,
+                title: "Test".to_owned(),
+                category: "Rust".to_owned(),
+            })
+            .unwrap();
+        state.tag_stream("rust").unwrap();
+
+        let current = state.current_stream().unwrap().unwrap();
+        assert_eq!("1", current.id);
+        assert_eq!(OffsetDateTime::UNIX_EPOCH, current.started_at);
+
+        state.end_stream(OffsetDateTime::UNIX_EPOCH).unwrap();
+        assert!(state.tag_stream("rust").is_err());
+        assert!(state.current_stream().unwrap().is_none());
+    }
+
+    #[test]
+    fn stream_topic() {
+        let state = State::in_memory().unwrap();
+        assert!(state.topic().unwrap().is_none());
+
+        assert!(!state
+            .set_topic(Some("Rust quiz"), OffsetDateTime::UNIX_EPOCH)
+            .unwrap());
+        let topic = state.topic().unwrap().unwrap();
+        assert_eq!("Rust quiz", topic.content);
+        assert!(!topic.live);
+
+        state
+            .start_stream(&StreamInfo {
+                id: "1".to_owned(),
+                started_at: OffsetDateTime::UNIX_EPOCH,
+                title: "Test".to_owned(),
+                category: "Rust".to_owned(),
+            })
+            .unwrap();
+        state.start_topic().unwrap();
+        let topic = state.topic().unwrap().unwrap();
+        assert_eq!("Rust quiz", topic.content);
+        assert!(topic.live);
+
+        assert!(state
+            .set_topic(Some("Bug hunting"), OffsetDateTime::UNIX_EPOCH)
+            .unwrap());
+        assert_eq!("Bug hunting", state.topic().unwrap().unwrap().content);
+
+        state.end_stream(OffsetDateTime::UNIX_EPOCH).unwrap();
+        assert!(state.topic().unwrap().is_none());
+    }
+
+    #[test]
+    fn overwrite_command() {
+        let state = State::in_memory().unwrap();
+
+        state
+            .add_custom_command(
+                Source::Discord,
+                "test",
+                "one",
+                &AuthorId::Console.user_ref(),
+                OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+        state
+            .add_custom_command(
+                Source::Discord,
+                "test",
+                "two",
+                &AuthorId::Console.user_ref(),
+                OffsetDateTime::UNIX_EPOCH,
+            )
+            .unwrap();
+
+        let cmd = state.get_custom_command(Source::Discord, "test").unwrap();
+        assert_eq!(Some("two"), cmd.as_deref());
+    }
+
+    #[test]
+    fn custom_command_provenance() {
+        let state = State::in_memory().unwrap();
+        let creator = AuthorId::Discord(NonZero::new(1).unwrap()).user_ref();
+        let editor = AuthorId::Discord(NonZero::new(2).unwrap()).user_ref();
+        let (created, updated) = (
+            datetime!(2025-03-01 18:00 UTC),
+            datetime!(2025-03-02 18:00 UTC),
+        );
+
+        state
+            .add_custom_command(Source::Twitch, "hi", "one", &creator, created)
+            .unwrap();
+
+        let details = state.custom_command_details("hi").unwrap();
+        assert_eq!(1, details.len());
+        assert_eq!(Some(&creator), details[0].created_by.as_ref());
+        assert_eq!(Some(created), details[0].created_at);
+        assert_eq!(None, details[0].updated_by);
+
+        state
+            .add_custom_command(Source::Twitch, "hi", "two", &editor, updated)
+            .unwrap();
+
+        let details = state.list_custom_command_details().unwrap();
+        assert_eq!("two", details[0].content);
+        assert_eq!(Some(&creator), details[0].created_by.as_ref());
+        assert_eq!(Some(&editor), details[0].updated_by.as_ref());
+        assert_eq!(Some(created), details[0].created_at);
+        assert_eq!(Some(updated), details[0].updated_at);
     }
 }
@@ -1,53 +1,218 @@
 //! Statistics management for the bot.
 
-use std::{hash::Hash, sync::Arc};
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    mem,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
-use time::OffsetDateTime;
-use tracing::error;
+use time::{Date, Month};
+use tracing::{error, info};
 
-pub use self::migrate::run as migrate;
-use crate::db::{self, connection::Connection};
+pub use self::migrate::{pending as pending_import, run as migrate};
+use crate::{
+    api::{response, UserRef, Viewer},
+    clock::{self, SharedClock},
+    commands::registry,
+    db::{self, connection::Connection},
+    fun::Kind,
+    settings::Retention,
+};
+
+/// Time after which accumulated usage counts should be written to the database.
+pub const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Time between automatic cleanups of old statistics.
+pub const CLEANUP_INTERVAL: Duration = Duration::from_hours(24);
+
+/// Days that the daily usage counts are kept. They only serve the usage alerts, which look at the
+/// current day, so there is no need to keep them as long as the monthly ones.
+const DAILY_RETENTION: i32 = 7;
+
+/// Amount of chatters in the watch time leaderboard.
+const WATCH_TIME_LEADERS: u32 = 10;
 
 /// Main structure that hold the statistics for different time frames.
-pub struct Stats(Arc<Connection>);
+///
+/// The connection is guarded by a mutex, so the statistics can be shared with the connectors.
+///
+/// Usage counts are accumulated in memory and only written to the database by
+/// [`flush`](Self::flush), which happens before reading any statistics and once the last clone is
+/// dropped. Busy chats thus cause a single write every few seconds, instead of one per command.
+#[derive(Clone)]
+pub struct Stats(Arc<Inner>);
+
+struct Inner {
+    conn: Mutex<Connection>,
+    pending: Mutex<Pending>,
+    stream: Mutex<Stream>,
+    clock: SharedClock,
+}
+
+/// Active stream that command uses are attributed to. It's looked up in the database once and then
+/// kept up to date by [`Stats::stream_online`] and [`Stats::stream_offline`].
+#[derive(Clone, Copy, Default)]
+enum Stream {
+    /// Not looked up yet.
+    #[default]
+    Unknown,
+    /// No stream is live.
+    Offline,
+    /// Live stream, by its row ID.
+    Online(i64),
+}
+
+impl From<Option<i64>> for Stream {
+    fn from(value: Option<i64>) -> Self {
+        value.map_or(Self::Offline, Self::Online)
+    }
+}
+
+/// Usage counts that weren't written to the database yet.
+#[derive(Default)]
+struct Pending {
+    /// Counts by year, month, kind and name.
+    monthly: HashMap<(i32, u8, CommandKind, String), u64>,
+    /// Counts by stream, kind and name. The stream is determined when the command is used, so
+    /// counts are attributed correctly even if the stream ends before the next flush.
+    streams: HashMap<(i64, CommandKind, String), u64>,
+    /// Counts by day (as Julian day number), kind and name.
+    daily: HashMap<(i32, CommandKind, String), u64>,
+}
+
+impl Pending {
+    fn merge(&mut self, other: Self) {
+        for (key, count) in other.monthly {
+            *self.monthly.entry(key).or_default() += count;
+        }
+        for (key, count) in other.streams {
+            *self.streams.entry(key).or_default() += count;
+        }
+        for (key, count) in other.daily {
+            *self.daily.entry(key).or_default() += count;
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.monthly.is_empty() && self.streams.is_empty() && self.daily.is_empty()
+    }
+}
 
 impl Stats {
     pub fn new(conn: Connection) -> Self {
-        Self(conn.into())
+        Self::with_clock(conn, clock::system())
+    }
+
+    /// Create the statistics with the given clock, which decides the month that command uses are
+    /// counted in.
+    pub fn with_clock(conn: Connection, clock: SharedClock) -> Self {
+        Self(Arc::new(Inner {
+            conn: Mutex::new(conn),
+            pending: Mutex::default(),
+            stream: Mutex::default(),
+            clock,
+        }))
     }
 
-    #[cfg(test)]
     pub fn in_memory() -> Result<Self> {
-        Connection::in_memory().map(Arc::new).map(Self)
+        Connection::in_memory().map(Self::new)
     }
 
-    /// Increment the usage counter for the given command by one.
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.0.conn()
+    }
+
+    /// Increment the usage counter for the given command by one. The change is only written to
+    /// the database with the next [`flush`](Self::flush).
     pub fn increment(&self, cmd: Command<'_>) -> Result<()> {
         // Don't track commands that are too long.
         if cmd.str_len() > 50 {
             return Ok(());
         }
 
-        let now = OffsetDateTime::now_utc();
+        let now = self.0.clock.now();
 
         let (kind, name) = match cmd {
             Command::Builtin(cmd) => (CommandKind::Builtin, cmd.name()),
             Command::Custom(cmd) => (CommandKind::Custom, cmd),
             Command::Unknown(cmd) => (CommandKind::Unknown, cmd),
+            Command::Spam(cmd) => (CommandKind::Spam, cmd),
         };
 
-        db::exec(
-            &self.0,
-            include_str!("../queries/cmd_usage/increment.sql"),
-            (now.year(), u8::from(now.month()), kind, name),
-        )?;
+        let stream = self.current_stream()?;
+
+        let mut pending = self.0.pending();
+        *pending
+            .monthly
+            .entry((now.year(), u8::from(now.month()), kind, name.to_owned()))
+            .or_default() += 1;
+        *pending
+            .daily
+            .entry((now.date().to_julian_day(), kind, name.to_owned()))
+            .or_default() += 1;
+        if let Some(stream) = stream {
+            *pending
+                .streams
+                .entry((stream, kind, name.to_owned()))
+                .or_default() += 1;
+        }
 
         Ok(())
     }
 
+    /// Attribute further command uses to the stream that just went online. The stream must already
+    /// be recorded in the [`State`](crate::state::State).
+    pub fn stream_online(&self) -> Result<()> {
+        let stream = self.load_stream()?;
+        *self.0.stream() = stream.into();
+        Ok(())
+    }
+
+    /// Stop attributing command uses to any stream, as it went offline.
+    pub fn stream_offline(&self) {
+        *self.0.stream() = Stream::Offline;
+    }
+
+    /// The active stream, only looked up in the database the first time.
+    fn current_stream(&self) -> Result<Option<i64>> {
+        let mut stream = self.0.stream();
+        match *stream {
+            Stream::Unknown => {
+                let id = self.load_stream()?;
+                *stream = id.into();
+                Ok(id)
+            }
+            Stream::Offline => Ok(None),
+            Stream::Online(id) => Ok(Some(id)),
+        }
+    }
+
+    fn load_stream(&self) -> Result<Option<i64>> {
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/streams/current.sql"),
+            db::NO_PARAMS,
+        )
+    }
+
+    /// Write all accumulated usage counts to the database. If that fails, the counts are kept for
+    /// the next attempt.
+    pub fn flush(&self) -> Result<()> {
+        self.0.flush()
+    }
+
+    /// Shorthand to flush the usage counts, but log an error instead of returning it.
+    pub fn try_flush(&self) {
+        if let Err(e) = self.flush() {
+            error!(error = ?e, "failed flushing statistics");
+        }
+    }
+
     /// Shorthand to increment the usage count, but log an error instead of returning it.
     pub fn try_increment(&self, cmd: Command<'_>) {
         if let Err(e) = self.increment(cmd) {
@@ -56,91 +221,429 @@ impl Stats {
     }
 
     /// Get the current or total statistics.
-    pub fn get(&self, total: bool) -> Result<Statistics> {
-        let now = OffsetDateTime::now_utc();
+    ///
+    /// Each section of the statistics is loaded separately, so a failure in one of them still
+    /// returns the others.
+    #[must_use]
+    pub fn get(&self, total: bool) -> Statistics {
+        self.try_flush();
 
-        let stats = if total {
-            db::query_vec::<_, Statistic>(
-                &self.0,
-                include_str!("../queries/cmd_usage/list_total.sql"),
-                db::NO_PARAMS,
+        if total {
+            Self::collect(
+                |kind| {
+                    db::query_vec(
+                        &self.conn(),
+                        include_str!("../queries/cmd_usage/list_total.sql"),
+                        [kind],
+                    )
+                },
+                || {
+                    db::query_vec(
+                        &self.conn(),
+                        include_str!("../queries/watch_time/list_total.sql"),
+                        [WATCH_TIME_LEADERS],
+                    )
+                },
             )
         } else {
-            db::query_vec::<_, Statistic>(
-                &self.0,
-                include_str!("../queries/cmd_usage/list_current.sql"),
-                (now.year(), u8::from(now.month())),
-            )
-        }?;
+            let now = self.0.clock.now();
+            self.get_month(now.year(), now.month())
+        }
+    }
+
+    /// Get the statistics of a single month.
+    #[must_use]
+    pub fn get_month(&self, year: i32, month: Month) -> Statistics {
+        self.try_flush();
+
+        Self::collect(
+            |kind| {
+                db::query_vec(
+                    &self.conn(),
+                    include_str!("../queries/cmd_usage/list_current.sql"),
+                    (year, u8::from(month), kind),
+                )
+            },
+            || {
+                db::query_vec(
+                    &self.conn(),
+                    include_str!("../queries/watch_time/list_month.sql"),
+                    (year, u8::from(month), WATCH_TIME_LEADERS),
+                )
+            },
+        )
+    }
+
+    /// List all months that have any statistics, latest first.
+    pub fn list_months(&self) -> Result<Vec<(i32, Month)>> {
+        self.flush()?;
+
+        db::query_vec::<_, (i32, u8)>(
+            &self.conn(),
+            include_str!("../queries/cmd_usage/list_months.sql"),
+            db::NO_PARAMS,
+        )?
+        .into_iter()
+        .map(|(year, month)| Ok((year, Month::try_from(month)?)))
+        .collect()
+    }
+
+    /// Get the total statistics of all streams that were tagged with the given topic. The watch
+    /// time isn't tracked per stream, so it's always empty.
+    #[must_use]
+    pub fn get_tag(&self, tag: &str) -> Statistics {
+        self.try_flush();
+
+        Self::collect(
+            |kind| {
+                db::query_vec(
+                    &self.conn(),
+                    include_str!("../queries/cmd_usage/list_tag.sql"),
+                    (tag, kind),
+                )
+            },
+            || Ok(Vec::new()),
+        )
+    }
+
+    /// Get how often a built-in or custom command was used on the given day.
+    pub fn daily_usage(&self, day: Date, name: &str) -> Result<u64> {
+        self.flush()?;
+
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/cmd_usage/count_daily.sql"),
+            (day.to_julian_day(), name),
+        )
+        .map(Option::unwrap_or_default)
+    }
+
+    /// Get how often unrecognized commands were used on the given day, all of them combined.
+    pub fn daily_unknown(&self, day: Date) -> Result<u64> {
+        self.flush()?;
+
+        db::query_one(
+            &self.conn(),
+            include_str!("../queries/cmd_usage/count_daily_kind.sql"),
+            (day.to_julian_day(), CommandKind::Unknown),
+        )
+        .map(Option::unwrap_or_default)
+    }
 
-        Ok(stats
+    fn collect(
+        query: impl Fn(CommandKind) -> Result<Vec<Statistic>>,
+        watch_time: impl FnOnce() -> Result<Vec<Statistic>>,
+    ) -> Statistics {
+        let section = |kind| {
+            query(kind)
+                .with_context(|| format!("failed loading {} statistics", kind.name()))
+                .inspect_err(|e| error!(error = ?e, "failed loading statistics section"))
+        };
+
+        Statistics {
+            command_usage: CommandUsage {
+                builtin: section(CommandKind::Builtin).map(|stats| {
+                    stats
+                        .into_iter()
+                        .filter_map(|stat| {
+                            Some((BuiltinCommand::from_str(&stat.name)?, stat.count))
+                        })
+                        .collect()
+                }),
+                custom: section(CommandKind::Custom).map(Self::counts),
+                unknown: section(CommandKind::Unknown).map(Self::counts),
+                spam: section(CommandKind::Spam).map(Self::counts),
+            },
+            watch_time: watch_time()
+                .context("failed loading watch time statistics")
+                .inspect_err(|e| error!(error = ?e, "failed loading statistics section"))
+                .map(Self::counts),
+        }
+    }
+
+    fn counts(stats: Vec<Statistic>) -> IndexMap<String, u64> {
+        stats
             .into_iter()
-            .fold(Statistics::default(), |mut acc, stat| {
-                match stat.kind {
-                    CommandKind::Builtin => {
-                        if let Some(cmd) = BuiltinCommand::from_str(&stat.name) {
-                            acc.command_usage.builtin.insert(cmd, stat.count);
-                        }
-                    }
-                    CommandKind::Custom => {
-                        acc.command_usage.custom.insert(stat.name, stat.count);
-                    }
-                    CommandKind::Unknown => {
-                        acc.command_usage.unknown.insert(stat.name, stat.count);
-                    }
-                };
-                acc
-            }))
+            .map(|stat| (stat.name, stat.count))
+            .collect()
     }
 
     /// Erase the usage counter for a custom command. This is usually done when a custom command
     /// is deleted.
     pub fn erase_custom(&self, name: &str) -> Result<()> {
+        self.flush()?;
+
         db::exec(
-            &self.0,
+            &self.conn(),
             include_str!("../queries/cmd_usage/delete.sql"),
             name,
         )?;
 
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/cmd_usage/delete_stream.sql"),
+            name,
+        )?;
+
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/cmd_usage/delete_daily.sql"),
+            name,
+        )?;
+
         Ok(())
     }
+
+    /// Credit each viewer with the given time in the current month. Samples only come in every few
+    /// minutes, so they are written right away instead of being accumulated like command uses.
+    pub fn add_watch_time(&self, viewers: &[Viewer], duration: Duration) -> Result<()> {
+        let now = self.0.clock.now();
+        let (year, month) = (now.year(), u8::from(now.month()));
+        let seconds = duration.as_secs();
+
+        self.conn().transaction(|tx| {
+            for viewer in viewers {
+                db::exec(
+                    tx,
+                    include_str!("../queries/watch_time/add.sql"),
+                    (
+                        year,
+                        month,
+                        viewer.user.source,
+                        &viewer.user.id,
+                        &viewer.name,
+                        seconds,
+                    ),
+                )?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Watch time of the user in the current month, and of all time that is still kept.
+    pub fn watch_time(&self, user: &UserRef) -> Result<(Duration, Duration)> {
+        let now = self.0.clock.now();
+        let conn = self.conn();
+
+        let month = db::query_one::<_, u64>(
+            &conn,
+            include_str!("../queries/watch_time/get_month.sql"),
+            (now.year(), u8::from(now.month()), user.source, &user.id),
+        )?;
+        let total = db::query_one::<_, u64>(
+            &conn,
+            include_str!("../queries/watch_time/get_total.sql"),
+            (user.source, &user.id),
+        )?;
+
+        Ok((
+            Duration::from_secs(month.unwrap_or_default()),
+            Duration::from_secs(total.unwrap_or_default()),
+        ))
+    }
+
+    /// Erase all watch time of the user. This is done when they opt out of the tracking.
+    pub fn erase_watch_time(&self, user: &UserRef) -> Result<()> {
+        db::exec(
+            &self.conn(),
+            include_str!("../queries/watch_time/delete.sql"),
+            (user.source, &user.id),
+        )
+    }
+
+    /// Delete statistics that are older than the retention allows, and drop the least used
+    /// unknown commands of each month beyond the limit. The watch time follows the same retention,
+    /// while daily counts are always kept for a week only. Afterwards, the database is compacted to
+    /// give the freed space back.
+    pub fn cleanup(&self, retention: Retention) -> Result<Cleanup> {
+        self.flush()?;
+
+        let now = self.0.clock.now();
+        let cutoff = i64::from(now.year()) * 12 + i64::from(u8::from(now.month()))
+            - i64::from(retention.months.max(1))
+            + 1;
+
+        let conn = self.conn();
+        let count = || -> Result<u64> {
+            let count = |query| {
+                db::query_one::<_, u64>(&conn, query, db::NO_PARAMS).map(Option::unwrap_or_default)
+            };
+            Ok(count(include_str!("../queries/cmd_usage/count.sql"))?
+                + count(include_str!("../queries/watch_time/count.sql"))?)
+        };
+
+        let (rows, size) = (count()?, conn.size()?);
+
+        conn.transaction(|tx| {
+            db::exec(
+                tx,
+                include_str!("../queries/cmd_usage/delete_before.sql"),
+                cutoff,
+            )?;
+            db::exec(
+                tx,
+                include_str!("../queries/watch_time/delete_before.sql"),
+                cutoff,
+            )?;
+            db::exec(
+                tx,
+                include_str!("../queries/cmd_usage/delete_daily_before.sql"),
+                now.date().to_julian_day() - DAILY_RETENTION + 1,
+            )?;
+            db::exec(
+                tx,
+                include_str!("../queries/cmd_usage/cap_unknown.sql"),
+                retention.unknown,
+            )
+        })?;
+
+        db::exec(&conn, "VACUUM;", db::NO_PARAMS).context("failed compacting database")?;
+
+        Ok(Cleanup {
+            rows: rows.saturating_sub(count()?),
+            reclaimed: size.saturating_sub(conn.size()?),
+        })
+    }
+
+    /// Shorthand to clean up the statistics, but log the outcome instead of returning it.
+    pub fn try_cleanup(&self, retention: Retention) {
+        match self.cleanup(retention) {
+            Ok(cleanup) => info!(cleanup.rows, cleanup.reclaimed, "cleaned up statistics"),
+            Err(e) => error!(error = ?e, "failed cleaning up statistics"),
+        }
+    }
+}
+
+/// Outcome of a statistics cleanup.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct Cleanup {
+    /// Amount of deleted usage counters, including the monthly watch time.
+    pub rows: u64,
+    /// Bytes that the database shrunk by.
+    pub reclaimed: u64,
+}
+
+impl Inner {
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn pending(&self) -> MutexGuard<'_, Pending> {
+        self.pending.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn stream(&self) -> MutexGuard<'_, Stream> {
+        self.stream.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn flush(&self) -> Result<()> {
+        let pending = mem::take(&mut *self.pending());
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        let res = self.conn().transaction(|tx| {
+            for ((year, month, kind, name), count) in &pending.monthly {
+                db::exec(
+                    tx,
+                    include_str!("../queries/cmd_usage/increment.sql"),
+                    (year, month, kind, name, count),
+                )?;
+            }
+
+            for ((stream, kind, name), count) in &pending.streams {
+                db::exec(
+                    tx,
+                    include_str!("../queries/cmd_usage/increment_stream.sql"),
+                    (stream, kind, name, count),
+                )?;
+            }
+
+            for ((day, kind, name), count) in &pending.daily {
+                db::exec(
+                    tx,
+                    include_str!("../queries/cmd_usage/increment_daily.sql"),
+                    (day, kind, name, count),
+                )?;
+            }
+
+            Ok(())
+        });
+
+        if res.is_err() {
+            self.pending().merge(pending);
+        }
+
+        res
+    }
+}
+
+impl Drop for Inner {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            error!(error = ?e, "failed flushing statistics on shutdown");
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize)]
 struct Statistic {
-    kind: CommandKind,
     name: String,
     count: u64,
 }
 
-#[derive(Clone, Copy, Deserialize, Serialize)]
+#[derive(Clone, Copy, Eq, Hash, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
 enum CommandKind {
     Builtin,
     Custom,
     Unknown,
+    Spam,
 }
 
-/// Statistics for various details about `togglebot` (well, currently only command usage counters).
-#[derive(Default)]
+impl CommandKind {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Builtin => "built-in",
+            Self::Custom => "custom",
+            Self::Unknown => "unknown",
+            Self::Spam => "spam",
+        }
+    }
+}
+
+/// Statistics for various details about `togglebot`, like command usage counters and the watch
+/// time of chatters.
+#[derive(Serialize)]
 #[cfg_attr(test, derive(Debug))]
 pub struct Statistics {
     /// Usage counters for commands.
     pub command_usage: CommandUsage,
+    /// Chatters that watched the stream the longest, by display name and seconds.
+    #[serde(serialize_with = "response::result")]
+    pub watch_time: Result<IndexMap<String, u64>>,
 }
 
 /// Counters for all available **user** commands. These are split between builtin, custom and
-/// unknown to allow better visualization and categorization.
-#[derive(Default)]
+/// unknown to allow better visualization and categorization. Each category is loaded on its own,
+/// and may have failed independently of the others.
+#[derive(Serialize)]
 #[cfg_attr(test, derive(Debug))]
 pub struct CommandUsage {
     /// Standard, built-in commands. Helps to find out which built in commands might be removed
     /// in the future due to low usage.
-    pub builtin: IndexMap<BuiltinCommand, u64>,
+    #[serde(serialize_with = "response::result")]
+    pub builtin: Result<IndexMap<BuiltinCommand, u64>>,
     /// Custom defined commands. Allows admins to see what commands might be retired.
-    pub custom: IndexMap<String, u64>,
+    #[serde(serialize_with = "response::result")]
+    pub custom: Result<IndexMap<String, u64>>,
     /// Unrecognized commands. Can give insight about common misspells or wished-for commands.
-    pub unknown: IndexMap<String, u64>,
+    #[serde(serialize_with = "response::result")]
+    pub unknown: Result<IndexMap<String, u64>>,
+    /// Commands that were dropped, because they were repeated too often.
+    #[serde(serialize_with = "response::result")]
+    pub spam: Result<IndexMap<String, u64>>,
 }
 
 /// A command that belongs in one of the defined categories.
@@ -152,14 +655,16 @@ pub enum Command<'a> {
     Custom(&'a str),
     /// Unrecognized command.
     Unknown(&'a str),
+    /// Command that was repeated too often and dropped.
+    Spam(&'a str),
 }
 
-impl<'a> Command<'a> {
+impl Command<'_> {
     /// Get the string length of the command.
     fn str_len(&self) -> usize {
         match self {
             Self::Builtin(_) => 0,
-            Self::Custom(v) | Self::Unknown(v) => v.len(),
+            Self::Custom(v) | Self::Unknown(v) | Self::Spam(v) => v.len(),
         }
     }
 }
@@ -183,12 +688,72 @@ pub enum BuiltinCommand {
     Crate,
     /// Fake ban for fun.
     Ban,
-    /// Get the current date (in UTC).
+    /// Get the current date (in UTC, or the user's own time zone).
     Today,
     /// Convert Fahrenheit degrees to Celsius degrees.
     FahrenheitToCelsius,
     /// Convert Celsius degrees to Fahrenheit degrees.
     CelsiusToFahrenheit,
+    /// Current status of the bot and stream.
+    Status,
+    /// Version and uptime of the bot.
+    BotStat,
+    /// Request a song for the song queue.
+    SongRequest,
+    /// List of requested songs.
+    SongQueue,
+    /// Chance of getting timed out on Twitch.
+    TimeoutMe,
+    /// Balance of collected points.
+    Points,
+    /// Betting points.
+    Gamble,
+    /// Betting points against another user.
+    Duel,
+    /// Looking up or sharing pronouns.
+    Pronouns,
+    /// Time since following the Twitch channel.
+    FollowAge,
+    /// Age of the Twitch account.
+    AccountAge,
+    /// Starting a poll or prediction.
+    Poll,
+    /// Opting in or out of direct messages when the stream goes live.
+    NotifyMe,
+    /// Registering or looking up a birthday.
+    Birthday,
+    /// A random piece of advice.
+    Advice,
+    /// A random fact.
+    Fact,
+    /// A random joke.
+    Joke,
+    /// A trigger word that fired in a chat message.
+    Trigger,
+    /// A frequently asked question that was answered.
+    Faq,
+    /// Starting to lurk.
+    Lurk,
+    /// Coming back from lurking.
+    Unlurk,
+    /// Listing the recently pinned highlights.
+    Highlights,
+    /// Current versions of the Rust toolchain.
+    RustVersions,
+    /// Running a Rust snippet on the playground.
+    Eval,
+    /// Showing or changing personal settings.
+    Settings,
+    /// Looking up the watch time, or opting in or out of its tracking.
+    WatchTime,
+    /// Titles and categories of yesterday's streams.
+    Yesterday,
+    /// The last time that a category was streamed.
+    LastCategory,
+    /// Sending in a suggestion.
+    Suggest,
+    /// Looking up the agenda of the stream.
+    Topic,
     /// Any other command that may have existed in the past.
     ///
     /// This uses the `#[serde(other)]` configuration, so that commands can be deleted and then
@@ -200,35 +765,43 @@ pub enum BuiltinCommand {
 
 impl BuiltinCommand {
     /// Get the display name for this command. It does **not** include the command prefix.
+    ///
+    /// Commands take their name from the [`registry`], only the counters of automatic replies
+    /// have names of their own.
     #[must_use]
-    pub const fn name(&self) -> &'static str {
+    pub fn name(&self) -> &'static str {
         match self {
-            Self::Help => "help",
-            Self::Commands => "commands",
-            Self::Links => "links",
-            Self::Crate => "crate",
-            Self::Ban => "ban",
-            Self::Today => "today",
-            Self::FahrenheitToCelsius => "ftoc",
-            Self::CelsiusToFahrenheit => "ctof",
+            Self::Trigger => "trigger",
+            Self::Faq => "faq",
             Self::Deprecated => "deprecated",
+            cmd => registry::COMMANDS
+                .iter()
+                .find(|info| info.statistic == Some(*cmd))
+                .map_or("deprecated", |info| info.name),
         }
     }
 
     #[must_use]
     fn from_str(s: &str) -> Option<Self> {
-        Some(match s {
-            "help" => Self::Help,
-            "commands" => Self::Commands,
-            "links" => Self::Links,
-            "crate" => Self::Crate,
-            "ban" => Self::Ban,
-            "today" => Self::Today,
-            "ftoc" => Self::FahrenheitToCelsius,
-            "ctof" => Self::CelsiusToFahrenheit,
-            "deprecated" => Self::Deprecated,
-            _ => return None,
-        })
+        match s {
+            "trigger" => Some(Self::Trigger),
+            "faq" => Some(Self::Faq),
+            "deprecated" => Some(Self::Deprecated),
+            s => registry::COMMANDS
+                .iter()
+                .find(|info| info.name == s)
+                .and_then(|info| info.statistic),
+        }
+    }
+}
+
+impl From<Kind> for BuiltinCommand {
+    fn from(value: Kind) -> Self {
+        match value {
+            Kind::Advice => Self::Advice,
+            Kind::Fact => Self::Fact,
+            Kind::Joke => Self::Joke,
+        }
     }
 }
 
@@ -241,7 +814,7 @@ mod migrate {
     use time::{Month, OffsetDateTime};
 
     use super::Connection;
-    use crate::dirs::DIRS;
+    use crate::{db, dirs::DIRS};
 
     #[derive(Deserialize)]
     struct Stats {
@@ -311,11 +884,22 @@ mod migrate {
         map.into_iter().map(move |(k, &v)| (kind, k.as_ref(), v))
     }
 
-    pub fn run(conn: &mut Connection) -> Result<()> {
-        let Some(stats) = load()? else { return Ok(()) };
+    /// Amount of usage counters in the former statistics file, that are imported on the next
+    /// start, if the file still exists.
+    pub fn pending() -> Result<Option<usize>> {
+        Ok(load()?.map(|stats| {
+            [stats.current.1, stats.total]
+                .iter()
+                .map(|stats| {
+                    let usage = &stats.command_usage;
+                    usage.builtin.len() + usage.custom.len() + usage.unknown.len()
+                })
+                .sum()
+        }))
+    }
 
-        let tx = conn.transaction()?;
-        let mut stmt = tx.prepare(include_str!("../queries/cmd_usage/add.sql"))?;
+    pub fn run(conn: &Connection) -> Result<()> {
+        let Some(stats) = load()? else { return Ok(()) };
 
         let stats = [
             (
@@ -326,30 +910,30 @@ mod migrate {
             (0, Month::January, stats.total),
         ];
 
-        for (year, month, stats) in stats {
-            let usages = transform_map(super::CommandKind::Builtin, &stats.command_usage.builtin)
-                .chain(transform_map(
-                    super::CommandKind::Custom,
-                    &stats.command_usage.custom,
-                ))
-                .chain(transform_map(
-                    super::CommandKind::Unknown,
-                    &stats.command_usage.unknown,
-                ));
-
-            for (kind, name, count) in usages {
-                stmt.execute(serde_rusqlite::to_params((
-                    year,
-                    u8::from(month),
-                    kind,
-                    name,
-                    count,
-                ))?)?;
+        conn.transaction(|tx| {
+            for (year, month, stats) in stats {
+                let usages =
+                    transform_map(super::CommandKind::Builtin, &stats.command_usage.builtin)
+                        .chain(transform_map(
+                            super::CommandKind::Custom,
+                            &stats.command_usage.custom,
+                        ))
+                        .chain(transform_map(
+                            super::CommandKind::Unknown,
+                            &stats.command_usage.unknown,
+                        ));
+
+                for (kind, name, count) in usages {
+                    db::exec(
+                        tx,
+                        include_str!("../queries/cmd_usage/add.sql"),
+                        (year, u8::from(month), kind, name, count),
+                    )?;
+                }
             }
-        }
 
-        drop(stmt);
-        tx.commit()?;
+            Ok(())
+        })?;
 
         fs::remove_file(DIRS.statistics_file())
             .context("failed deleting obsolete statistics file")?;
@@ -361,8 +945,21 @@ mod migrate {
 #[cfg(test)]
 mod tests {
     use similar_asserts::assert_eq;
+    use time::macros::{date, datetime};
 
     use super::*;
+    use crate::clock::MockClock;
+
+    #[test]
+    fn builtin_names() {
+        for cmd in registry::COMMANDS {
+            if let Some(statistic) = cmd.statistic {
+                assert_eq!(Some(statistic), BuiltinCommand::from_str(statistic.name()));
+            }
+        }
+        assert_eq!("rustversion", BuiltinCommand::RustVersions.name());
+        assert_eq!("poll", BuiltinCommand::Poll.name());
+    }
 
     #[test]
     fn increment() {
@@ -379,10 +976,139 @@ mod tests {
             stats.increment(Command::Unknown("who")).unwrap();
         }
 
-        let usage = &stats.get(false).unwrap().command_usage;
-        assert_eq!(2, usage.builtin[&BuiltinCommand::Help]);
-        assert_eq!(3, usage.custom["me"]);
-        assert_eq!(4, usage.unknown["who"]);
+        let usage = &stats.get(false).command_usage;
+        assert_eq!(2, usage.builtin.as_ref().unwrap()[&BuiltinCommand::Help]);
+        assert_eq!(3, usage.custom.as_ref().unwrap()["me"]);
+        assert_eq!(4, usage.unknown.as_ref().unwrap()["who"]);
+    }
+
+    #[test]
+    fn batched() {
+        let stats = Stats::in_memory().unwrap();
+        let rows = |stats: &Stats| {
+            db::query_one::<_, u64>(
+                &stats.conn(),
+                "SELECT COUNT(*) FROM command_usage;",
+                db::NO_PARAMS,
+            )
+            .unwrap()
+        };
+
+        for _ in 0..3 {
+            stats.increment(Command::Custom("me")).unwrap();
+        }
+        assert_eq!(Some(0), rows(&stats));
+
+        stats.flush().unwrap();
+        assert_eq!(Some(1), rows(&stats));
+        assert_eq!(3, stats.get(false).command_usage.custom.unwrap()["me"]);
+
+        stats.increment(Command::Custom("me")).unwrap();
+        assert_eq!(4, stats.get(true).command_usage.custom.unwrap()["me"]);
+    }
+
+    #[test]
+    fn tagged() {
+        let stats = Stats::in_memory().unwrap();
+        stats.increment(Command::Custom("before")).unwrap();
+
+        db::exec(
+            &stats.conn(),
+            include_str!("../queries/streams/start.sql"),
+            ("1", 0, "Test", "Rust"),
+        )
+        .unwrap();
+        db::exec(
+            &stats.conn(),
+            include_str!("../queries/streams/set_tag.sql"),
+            ("rust", 1),
+        )
+        .unwrap();
+        stats.stream_online().unwrap();
+        stats.increment(Command::Custom("during")).unwrap();
+
+        stats.stream_offline();
+        stats.increment(Command::Custom("after")).unwrap();
+
+        let custom = stats.get_tag("rust").command_usage.custom.unwrap();
+        assert_eq!(1, custom["during"]);
+        assert!(custom.get("before").is_none());
+        assert!(custom.get("after").is_none());
+        assert!(stats
+            .get_tag("web")
+            .command_usage
+            .custom
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn months() {
+        let now = datetime!(2025-03-15 12:00 UTC);
+        let stats = Stats::with_clock(
+            Connection::in_memory().unwrap(),
+            Arc::new(MockClock::new(now)),
+        );
+        assert!(stats.list_months().unwrap().is_empty());
+
+        stats.increment(Command::Custom("me")).unwrap();
+
+        assert_eq!(
+            vec![(now.year(), now.month())],
+            stats.list_months().unwrap()
+        );
+        let custom = stats
+            .get_month(now.year(), now.month())
+            .command_usage
+            .custom
+            .unwrap();
+        assert_eq!(Some(&1), custom.get("me"));
+    }
+
+    #[test]
+    fn month_buckets() {
+        let clock = Arc::new(MockClock::new(datetime!(2025-01-31 23:59 UTC)));
+        let stats = Stats::with_clock(Connection::in_memory().unwrap(), clock.clone());
+
+        stats.increment(Command::Custom("me")).unwrap();
+        clock.advance(time::Duration::minutes(2));
+        stats.increment(Command::Custom("me")).unwrap();
+        stats.increment(Command::Custom("me")).unwrap();
+
+        assert_eq!(
+            vec![(2025, Month::February), (2025, Month::January)],
+            stats.list_months().unwrap()
+        );
+        assert_eq!(
+            Some(&2),
+            stats.get(false).command_usage.custom.unwrap().get("me")
+        );
+    }
+
+    #[test]
+    fn day_buckets() {
+        let clock = Arc::new(MockClock::new(datetime!(2025-01-31 23:59 UTC)));
+        let stats = Stats::with_clock(Connection::in_memory().unwrap(), clock.clone());
+
+        stats.increment(BuiltinCommand::Help.into()).unwrap();
+        stats.increment(Command::Unknown("halp")).unwrap();
+        clock.advance(time::Duration::minutes(2));
+        stats.increment(BuiltinCommand::Help.into()).unwrap();
+        stats.increment(BuiltinCommand::Help.into()).unwrap();
+        stats.increment(Command::Unknown("hlep")).unwrap();
+        stats.increment(Command::Unknown("hepl")).unwrap();
+
+        let (before, after) = (date!(2025 - 01 - 31), date!(2025 - 02 - 01));
+        assert_eq!(1, stats.daily_usage(before, "help").unwrap());
+        assert_eq!(2, stats.daily_usage(after, "help").unwrap());
+        assert_eq!(0, stats.daily_usage(after, "halp").unwrap());
+        assert_eq!(1, stats.daily_unknown(before).unwrap());
+        assert_eq!(2, stats.daily_unknown(after).unwrap());
+
+        clock.advance(time::Duration::days(6));
+        stats.cleanup(Retention::default()).unwrap();
+        assert_eq!(0, stats.daily_usage(before, "help").unwrap());
+        assert_eq!(2, stats.daily_usage(after, "help").unwrap());
     }
 
     #[test]
@@ -392,8 +1118,77 @@ mod tests {
         stats.increment(Command::Custom("you")).unwrap();
         stats.erase_custom("you").unwrap();
 
-        let usage = &stats.get(false).unwrap().command_usage;
-        assert_eq!(1, usage.custom["me"]);
-        assert!(usage.custom.get("you").is_none());
+        let custom = stats.get(false).command_usage.custom.unwrap();
+        assert_eq!(1, custom["me"]);
+        assert!(custom.get("you").is_none());
+    }
+
+    #[test]
+    fn cleanup() {
+        let now = datetime!(2025-03-15 12:00 UTC);
+        let stats = Stats::with_clock(
+            Connection::in_memory().unwrap(),
+            Arc::new(MockClock::new(now)),
+        );
+        stats.increment(Command::Custom("me")).unwrap();
+        for name in ["a", "b", "c", "a", "b", "a"] {
+            stats.increment(Command::Unknown(name)).unwrap();
+        }
+
+        for (year, month) in [(now.year() - 3, 1), (0, 1)] {
+            db::exec(
+                &stats.conn(),
+                include_str!("../queries/cmd_usage/add.sql"),
+                (year, month, CommandKind::Custom, "old", 1),
+            )
+            .unwrap();
+        }
+
+        let cleanup = stats
+            .cleanup(Retention {
+                months: 24,
+                unknown: 2,
+            })
+            .unwrap();
+        assert_eq!(3, cleanup.rows);
+
+        let usage = stats.get(true).command_usage;
+        assert_eq!(vec!["me"], usage.custom.unwrap().keys().collect::<Vec<_>>());
+        assert_eq!(
+            vec!["a", "b"],
+            usage.unknown.unwrap().keys().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn partial_failure() {
+        let now = datetime!(2025-03-15 12:00 UTC);
+        let stats = Stats::with_clock(
+            Connection::in_memory().unwrap(),
+            Arc::new(MockClock::new(now)),
+        );
+        stats.increment(BuiltinCommand::Help.into()).unwrap();
+        stats.increment(Command::Unknown("who")).unwrap();
+
+        // A negative count can't be decoded into the unsigned counter.
+        db::exec(
+            &stats.conn(),
+            "INSERT INTO command_usage (year, month, kind, name, count) VALUES (?, ?, 'custom', \
+             'broken', -1);",
+            (now.year(), u8::from(now.month())),
+        )
+        .unwrap();
+
+        for usage in [
+            stats.get(false).command_usage,
+            stats.get(true).command_usage,
+        ] {
+            assert_eq!(1, usage.builtin.unwrap()[&BuiltinCommand::Help]);
+            assert_eq!(1, usage.unknown.unwrap()["who"]);
+            assert_eq!(
+                "failed loading custom statistics",
+                usage.custom.unwrap_err().to_string()
+            );
+        }
     }
 }
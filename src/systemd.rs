@@ -0,0 +1,173 @@
+//! Notifications to systemd about the state of the bot, when it runs as a service with
+//! `Type=notify`.
+//!
+//! Readiness is signaled once all connectors started, and if the unit has a `WatchdogSec` set, the
+//! main loop pings the watchdog regularly. A hung main loop stops pinging, and systemd restarts
+//! the bot. Outside of systemd, the `NOTIFY_SOCKET` variable isn't set and all of this does nothing.
+
+use std::{env, future, io, os::unix::net::UnixDatagram, path::Path, time::Duration};
+
+use tokio::time::{Interval, MissedTickBehavior};
+use tracing::{info, warn};
+
+/// Connection to the notification socket of systemd.
+pub struct Systemd {
+    socket: Option<(UnixDatagram, String)>,
+    watchdog: Option<Interval>,
+}
+
+impl Systemd {
+    /// Connect to the notification socket that systemd passed through the environment, if any.
+    #[must_use]
+    pub fn from_env() -> Self {
+        let Some(path) = env::var_os("NOTIFY_SOCKET") else {
+            return Self::disabled();
+        };
+        let path = path.to_string_lossy().into_owned();
+
+        let socket = match UnixDatagram::unbound() {
+            Ok(socket) => socket,
+            Err(e) => {
+                warn!(error = ?e, "failed creating systemd notification socket");
+                return Self::disabled();
+            }
+        };
+
+        let watchdog = watchdog_interval(
+            env::var("WATCHDOG_USEC").ok().as_deref(),
+            env::var("WATCHDOG_PID").ok().as_deref(),
+            std::process::id(),
+        );
+        info!(?watchdog, "running under systemd");
+
+        Self {
+            socket: Some((socket, path)),
+            watchdog: watchdog.map(|period| {
+                let mut interval = tokio::time::interval(period);
+                interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                interval
+            }),
+        }
+    }
+
+    fn disabled() -> Self {
+        Self {
+            socket: None,
+            watchdog: None,
+        }
+    }
+
+    /// Tell systemd that the bot finished starting up.
+    pub fn ready(&self) {
+        self.notify("READY=1");
+    }
+
+    /// Tell systemd that the bot is shutting down.
+    pub fn stopping(&self) {
+        self.notify("STOPPING=1");
+    }
+
+    /// Tell systemd that the bot is still alive, resetting the watchdog timer.
+    pub fn ping(&self) {
+        self.notify("WATCHDOG=1");
+    }
+
+    /// Wait until the watchdog should be pinged again. Without a watchdog, this never completes.
+    pub async fn watchdog(&mut self) {
+        match &mut self.watchdog {
+            Some(interval) => {
+                interval.tick().await;
+            }
+            None => future::pending().await,
+        }
+    }
+
+    fn notify(&self, state: &str) {
+        let Some((socket, path)) = &self.socket else {
+            return;
+        };
+
+        if let Err(e) = send(socket, path, state) {
+            warn!(error = ?e, state, "failed notifying systemd");
+        }
+    }
+}
+
+fn send(socket: &UnixDatagram, path: &str, state: &str) -> io::Result<()> {
+    // Abstract sockets are written with a leading `@` instead of the null byte.
+    #[cfg(target_os = "linux")]
+    if let Some(name) = path.strip_prefix('@') {
+        use std::os::{linux::net::SocketAddrExt, unix::net::SocketAddr};
+
+        let addr = SocketAddr::from_abstract_name(name)?;
+        return socket.send_to_addr(state.as_bytes(), &addr).map(|_| ());
+    }
+
+    socket
+        .send_to(state.as_bytes(), Path::new(path))
+        .map(|_| ())
+}
+
+/// Period at which to ping the watchdog, which is half of the timeout that systemd configured, so
+/// a single late ping doesn't cause a restart. If systemd set a specific process for the watchdog,
+/// it must be this one.
+fn watchdog_interval(usec: Option<&str>, pid: Option<&str>, own_pid: u32) -> Option<Duration> {
+    if let Some(pid) = pid {
+        if pid.parse::<u32>().ok()? != own_pid {
+            return None;
+        }
+    }
+
+    match usec?.parse::<u64>().ok()? {
+        0 => None,
+        usec => Some(Duration::from_micros(usec / 2)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn watchdog_period() {
+        assert_eq!(
+            Some(Duration::from_secs(15)),
+            watchdog_interval(Some("30000000"), None, 7)
+        );
+        assert_eq!(
+            Some(Duration::from_secs(15)),
+            watchdog_interval(Some("30000000"), Some("7"), 7)
+        );
+        assert_eq!(None, watchdog_interval(Some("30000000"), Some("8"), 7));
+        assert_eq!(None, watchdog_interval(Some("0"), None, 7));
+        assert_eq!(None, watchdog_interval(Some("soon"), None, 7));
+        assert_eq!(None, watchdog_interval(None, None, 7));
+    }
+
+    #[test]
+    fn notify_socket() {
+        let path = env::temp_dir().join(format!("togglebot-notify-{}", std::process::id()));
+        std::fs::remove_file(&path).ok();
+        let server = UnixDatagram::bind(&path).unwrap();
+
+        let systemd = Systemd {
+            socket: Some((
+                UnixDatagram::unbound().unwrap(),
+                path.to_string_lossy().into_owned(),
+            )),
+            watchdog: None,
+        };
+        systemd.ready();
+        systemd.ping();
+
+        let mut buf = [0; 16];
+        let len = server.recv(&mut buf).unwrap();
+        assert_eq!(b"READY=1", &buf[..len]);
+        let len = server.recv(&mut buf).unwrap();
+        assert_eq!(b"WATCHDOG=1", &buf[..len]);
+
+        std::fs::remove_file(&path).ok();
+    }
+}
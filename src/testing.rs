@@ -0,0 +1,160 @@
+//! Support for integration tests, that drive whole request/response flows without connecting to
+//! any real service. Only available with the `testing` feature.
+//!
+//! A [`Harness`] runs the message handling on top of in-memory databases, and messages can be sent
+//! to it as if they came from any of the chat services:
+//!
+//! ```
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() -> anyhow::Result<()> {
+//! use togglebot::{
+//!     api::{AuthorId, Source},
+//!     testing::Harness,
+//! };
+//!
+//! let harness = Harness::new()?;
+//! let reply = harness
+//!     .send(
+//!         Source::Twitch,
+//!         AuthorId::Twitch("viewer".to_owned()),
+//!         "!ban everyone",
+//!     )
+//!     .await?;
+//! assert_eq!(Some("everyone, YOU SHALL NOT PASS!!".to_owned()), reply);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Replies are rendered as plain text, regardless of the service. Messages that the bot posts on
+//! its own can be captured with a [`MockConnector`].
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+use anyhow::Result;
+use futures_util::future::{self, BoxFuture};
+
+use crate::{
+    api::{AuthorId, Event, Post, Source},
+    bot::Bot,
+    clock::SharedClock,
+    connector::{Connector, Context as ConnectorContext},
+    render,
+    settings::Commands as CommandSettings,
+    state::State,
+    statistics::Stats,
+};
+
+/// The message handling of the bot, running on in-memory databases.
+///
+/// Owners are only known for the [`Source::Console`], while admins can be added through the
+/// [`state`](Self::state) as usual.
+pub struct Harness {
+    bot: Bot,
+}
+
+impl Harness {
+    /// Set up the message handling with default settings.
+    pub fn new() -> Result<Self> {
+        Self::with_settings(CommandSettings::default())
+    }
+
+    /// Set up the message handling with the given settings for built-in commands.
+    pub fn with_settings(settings: CommandSettings) -> Result<Self> {
+        Ok(Self {
+            bot: Bot::builder(settings).in_memory().build()?,
+        })
+    }
+
+    /// Set up the message handling with default settings and the given clock, usually a
+    /// [`MockClock`](crate::clock::MockClock), to test time dependent commands.
+    pub fn with_clock(clock: SharedClock) -> Result<Self> {
+        Ok(Self {
+            bot: Bot::builder(CommandSettings::default())
+                .clock(clock)
+                .in_memory()
+                .build()?,
+        })
+    }
+
+    /// Send a chat message as the given author through the given service, returning the rendered
+    /// reply. Nothing is returned, if the message isn't a command or the bot doesn't reply to it.
+    pub async fn send(
+        &self,
+        source: Source,
+        author: AuthorId,
+        text: &str,
+    ) -> Result<Option<String>> {
+        let resp = self.bot.handle_text(source, author.into(), text).await?;
+
+        Ok(resp.and_then(render::plain::response))
+    }
+
+    /// Handle an event that isn't related to any chat message, like the stream going online.
+    pub fn event(&self, event: Event) {
+        self.bot.event(event);
+    }
+
+    /// Access to the state, for example to set up admins or custom commands.
+    #[must_use]
+    pub fn state(&self) -> &State {
+        self.bot.state()
+    }
+
+    /// Access to the collected statistics.
+    #[must_use]
+    pub fn statistics(&self) -> &Stats {
+        self.bot.statistics()
+    }
+}
+
+/// In-memory connector, that records all messages posted through it, to be checked with
+/// [`posts`](Self::posts). Clones share the same record, so one clone can be added to the
+/// [`Connectors`](crate::connector::Connectors) while another is kept for inspection.
+#[derive(Clone)]
+pub struct MockConnector {
+    source: Source,
+    posts: Arc<Mutex<Vec<String>>>,
+}
+
+impl MockConnector {
+    /// Create a new connector that stands in for the given service.
+    #[must_use]
+    pub fn new(source: Source) -> Self {
+        Self {
+            source,
+            posts: Arc::default(),
+        }
+    }
+
+    /// All messages that were posted through this connector so far.
+    #[must_use]
+    pub fn posts(&self) -> Vec<String> {
+        self.posts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl Connector for MockConnector {
+    fn source(&self) -> Source {
+        self.source
+    }
+
+    fn start(&self, _: ConnectorContext) -> BoxFuture<'_, Result<()>> {
+        Box::pin(future::ready(Ok(())))
+    }
+
+    fn send_reply(&self, post: Post) -> BoxFuture<'_, Result<()>> {
+        self.posts
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(post.content);
+
+        Box::pin(future::ready(Ok(())))
+    }
+
+    fn shutdown(&self) -> BoxFuture<'_, ()> {
+        Box::pin(future::ready(()))
+    }
+}
@@ -1,12 +1,22 @@
-use std::num::NonZero;
+//! Parsing of chat messages into requests, shared by all services.
 
-use anyhow::Result;
+use std::{num::NonZero, time::Duration};
 
-use crate::api::{
-    request::{self, Request, StatisticsDate},
-    Source,
+use anyhow::{anyhow, ensure, Context, Result};
+use time::{format_description::FormatItem, macros::format_description, Date};
+
+use crate::{
+    api::{
+        request::{self, Request, StatisticsDate},
+        Poll, PollKind, Source, SuggestionStatus, UserPref,
+    },
+    commands,
+    fun::Kind,
 };
 
+/// Longest duration that polls and silences can last, so they always end at a valid date.
+const MAX_DURATION: Duration = Duration::from_hours(24 * 365);
+
 macro_rules! bail {
     ($e:expr $(,)?) => {
         return Some(Err(anyhow::anyhow!($e)))
@@ -23,7 +33,7 @@ macro_rules! err {
 }
 
 pub fn parse(text: &str, source: Source, mention: Option<NonZero<u64>>) -> Result<Option<Request>> {
-    owner_message(text, mention)
+    owner_message(text, source, mention)
         .map(|r| r.map(Request::Owner))
         .or_else(|| admin_message(text).map(|r| r.map(Request::Admin)))
         .or_else(|| user_message(text, source).map(|r| r.map(Request::User)))
@@ -35,106 +45,804 @@ fn user_message(content: &str, source: Source) -> Option<Result<request::User>>
     let mut parts = content.splitn(2, char::is_whitespace);
     let command = parts.next()?.strip_prefix('!')?;
 
-    Some(Ok(match (command.to_lowercase().as_ref(), parts.next()) {
-        ("help" | "bot", None) => request::User::Help,
+    let command = command.to_lowercase();
+    let args = parts.next();
+
+    // Only built-in commands reach the parsing below, anything else is a custom command or
+    // handled by a plugin.
+    let Some(info) = commands::find(&command) else {
+        return Some(Ok(custom(command, args)));
+    };
+
+    Some(Ok(match (info.name, args) {
+        ("help", args) => match args.map(str::trim).filter(|name| !name.is_empty()) {
+            Some(name) => request::User::CommandHelp(name.to_owned()),
+            None => request::User::Help,
+        },
         ("commands", None) => request::User::Commands(source),
         ("links", None) => request::User::Links,
-        ("crate" | "crates", Some(name)) => request::User::Crate(name.to_owned()),
+        ("crate", Some(args)) => match args.split_whitespace().collect::<Vec<_>>()[..] {
+            ["compare", first, second] => {
+                request::User::CrateCompare(first.to_owned(), second.to_owned())
+            }
+            [name, "features"] => {
+                let (name, version) = err!(crate_version(name));
+                request::User::CrateFeatures { name, version }
+            }
+            [name] => match err!(crate_version(name)) {
+                (name, Some(version)) => request::User::CrateVersion { name, version },
+                (name, None) => request::User::Crate(name),
+            },
+            _ => request::User::Crate(args.to_owned()),
+        },
+        ("rustversion", None) => request::User::RustVersions,
+        ("eval", Some(code)) => request::User::Eval(code.trim().to_owned()),
+        ("eval", None) => bail!(commands::usage("eval")),
         ("ban", Some(target)) => request::User::Ban(target.to_owned()),
         ("today", None) => request::User::Today,
+        ("status", None) => request::User::Status,
+        ("botstat", None) => request::User::BotStat,
         ("ftoc", Some(fahrenheit)) => request::User::Ftoc(err!(fahrenheit.parse())),
         ("ctof", Some(celsius)) => request::User::Ctof(err!(celsius.parse())),
-        (name, None) => request::User::Custom(name.to_string()),
-        _ => return None,
+        ("convert", Some(degrees)) => request::User::Convert(err!(degrees.trim().parse())),
+        ("sr", Some(link)) => request::User::SongRequest(link.trim().to_owned()),
+        ("queue", None) => request::User::SongQueue,
+        ("points", None) => request::User::Points,
+        ("gamble", Some(amount)) => request::User::Gamble(err!(amount.trim().parse())),
+        ("duel", Some(args)) => err!(duel(args)),
+        ("accept", None) => request::User::Accept,
+        ("pronouns", Some(args)) => request::User::Pronouns(match args.trim() {
+            "unset" => request::Pronouns::Unset,
+            args => match args.split_once(char::is_whitespace) {
+                Some(("set", pronouns)) => request::Pronouns::Set(pronouns.trim().to_owned()),
+                _ => request::Pronouns::Show(args.to_owned()),
+            },
+        }),
+        ("pronouns", None) => bail!(commands::usage("pronouns")),
+        ("set", args) => request::User::Prefs(err!(prefs(args.unwrap_or_default()))),
+        ("poll", Some(args)) => request::User::Poll(err!(poll(PollKind::Poll, args))),
+        ("prediction", Some(args)) => request::User::Poll(err!(poll(PollKind::Prediction, args))),
+        ("notifyme", args) if source == Source::Discord => {
+            request::User::NotifyMe(err!(switch("notifyme", args)))
+        }
+        ("birthday", args) if source == Source::Discord => {
+            let args = args.unwrap_or_default().split_whitespace();
+            request::User::Birthday(match args.collect::<Vec<_>>()[..] {
+                [] => request::Birthday::Show,
+                ["set", date] => err!(birthday(date)),
+                ["remove"] => request::Birthday::Remove,
+                ["announce", "on"] => request::Birthday::Announce(true),
+                ["announce", "off"] => request::Birthday::Announce(false),
+                _ => bail!(commands::usage("birthday")),
+            })
+        }
+        ("advice", None) => request::User::Fun(Kind::Advice),
+        ("fact", None) => request::User::Fun(Kind::Fact),
+        ("joke", None) => request::User::Fun(Kind::Joke),
+        ("lurk", message) => request::User::Lurk(message.map(|message| message.trim().to_owned())),
+        ("unlurk", None) => request::User::Unlurk,
+        ("highlights", None) => request::User::Highlights,
+        ("yesterday", None) => request::User::Yesterday,
+        ("lastcategory", Some(category)) => request::User::LastCategory(category.trim().to_owned()),
+        ("suggest", Some(text)) if !text.trim().is_empty() => {
+            request::User::Suggest(text.trim().to_owned())
+        }
+        ("topic", None) => request::User::Topic,
+        (name @ ("lastcategory" | "suggest" | "topic"), _) => bail!(commands::usage(name)),
+        ("followage", None) if source == Source::Twitch => request::User::FollowAge,
+        ("accountage", None) if source == Source::Twitch => request::User::AccountAge,
+        ("watchtime", args) if source == Source::Twitch => {
+            request::User::WatchTime(err!(switch("watchtime", args)))
+        }
+        ("timeout", Some(target))
+            if source == Source::Twitch && target.eq_ignore_ascii_case("me") =>
+        {
+            request::User::TimeoutMe
+        }
+        (name, Some(_)) if commands::without_args(name) => return None,
+        (name, args) => custom(name.to_owned(), args),
     }))
 }
 
+/// Request for a command that isn't built-in, which is a custom command if it has no arguments, or
+/// otherwise might be handled by a plugin.
+fn custom(name: String, args: Option<&str>) -> request::User {
+    match args {
+        None => request::User::Custom(name),
+        Some(args) => request::User::Plugin {
+            name,
+            args: args.to_owned(),
+        },
+    }
+}
+
 /// Handle admin facing messages to control the bot and prepare a response.
 fn admin_message(content: &str) -> Option<Result<request::Admin>> {
     let mut parts = content.split_whitespace();
     let command = parts.next()?.strip_prefix('!')?;
 
+    let command = commands::find(command)?.name;
+
+    // Commands with free text arguments, or with their own usage message.
+    match command {
+        "custom_commands" => {
+            return Some(custom_commands(content).map(request::Admin::CustomCommands));
+        }
+        "usernote" => return Some(user_notes(content).map(request::Admin::UserNotes)),
+        "suggestions" => return Some(suggestions(content).map(request::Admin::Suggestions)),
+        "issue" => return Some(issue(content).map(request::Admin::Issue)),
+        "topic" => {
+            if let Some(res) = topic(content) {
+                return Some(res.map(request::Admin::Topic));
+            }
+        }
+        "trigger" => return Some(triggers(content).map(request::Admin::Triggers)),
+        "faq" => return Some(faqs(content).map(request::Admin::Faqs)),
+        "watchcrate" => return Some(watch_crates(content).map(request::Admin::WatchCrates)),
+        "channels" => return Some(channels(content).map(request::Admin::Channels)),
+        "silence" => return Some(silence(content)),
+        "commandlist" => return Some(command_list(content).map(request::Admin::CommandList)),
+        "away" => return Some(away(content).map(request::Admin::Away)),
+        _ => {}
+    }
+
     Some(Ok(
         match (
-            command.to_lowercase().as_ref(),
+            command,
             parts.next(),
             parts.next(),
             parts.next(),
             parts.next(),
         ) {
-            ("admin_help" | "admin-help" | "adminhelp" | "ahelp", None, None, None, None) => {
-                request::Admin::Help
+            ("ahelp", None, None, None, None) => request::Admin::Help,
+            ("stats", Some("tag"), Some(tag), None, None) => {
+                request::Admin::StreamTag(tag.to_owned())
             }
-            ("custom_commands" | "custom_command", Some("list"), None, None, None) => {
-                request::Admin::CustomCommands(request::CustomCommands::List)
+            ("stats", Some("tag"), Some(tag), Some("total"), None) => {
+                request::Admin::Statistics(StatisticsDate::Tag(tag.to_owned()))
             }
-            (
-                "custom_commands" | "custom_command",
-                Some(action),
-                Some(source),
-                Some(name),
-                content,
-            ) => request::Admin::CustomCommands(match action {
-                "add" => request::CustomCommands::Add {
-                    source: match source {
-                        "all" => None,
-                        "discord" => Some(Source::Discord),
-                        "twitch" => Some(Source::Twitch),
-                        s => bail!("unknown source `{s}`"),
-                    },
+            ("stats", Some("timing"), None, None, None) => request::Admin::Timing,
+            ("skip", None, None, None, None) => request::Admin::SkipSong,
+            ("back", None, None, None, None) => request::Admin::Back,
+            ("confirm", None, None, None, None) => request::Admin::Confirm,
+            ("linkpreview", Some(source), None, None, None) => {
+                request::Admin::LinkPreview(err!(source.parse()))
+            }
+            ("checklinks", None, None, None, None) => request::Admin::CheckLinks,
+            ("chapters", day_arg, None, None, None) => request::Admin::Chapters(match day_arg {
+                Some(value) => Some(err!(day(value))),
+                None => None,
+            }),
+            ("shoutout", None, None, None, None) => request::Admin::Shoutout(None),
+            ("shoutout", Some(enabled), None, None, None) => {
+                request::Admin::Shoutout(Some(match enabled {
+                    "on" => true,
+                    "off" => false,
+                    s => bail!("unknown shoutout state `{s}`"),
+                }))
+            }
+            ("cost", Some("list"), None, None, None) => {
+                request::Admin::CommandCosts(request::CommandCosts::List)
+            }
+            ("cost", Some(name), Some(cost), None, None) => {
+                request::Admin::CommandCosts(request::CommandCosts::Set {
                     name: name.to_owned(),
-                    content: content.map(ToOwned::to_owned)?,
-                },
-                "remove" => request::CustomCommands::Remove {
-                    source: match source {
-                        "all" => None,
-                        "discord" => Some(Source::Discord),
-                        "twitch" => Some(Source::Twitch),
-                        s => bail!("unknown source `{s}`"),
-                    },
+                    cost: err!(cost.parse()),
+                })
+            }
+            ("availability", Some("list"), None, None, None) => {
+                request::Admin::CommandAvailability(request::CommandAvailability::List)
+            }
+            ("availability", Some(name), Some(availability), None, None) => {
+                request::Admin::CommandAvailability(request::CommandAvailability::Set {
                     name: name.to_owned(),
-                },
-                s => bail!("unknown action `{s}`"),
-            }),
+                    availability: err!(availability.parse()),
+                })
+            }
+            ("alert", Some("list"), None, None, None) => {
+                request::Admin::UsageAlerts(request::UsageAlerts::List)
+            }
+            ("alert", Some(name), Some(limit), None, None) => {
+                request::Admin::UsageAlerts(request::UsageAlerts::Set {
+                    name: name.to_owned(),
+                    limit: err!(limit.parse()),
+                })
+            }
             ("stats", date, None, None, None) => request::Admin::Statistics(match date {
                 Some("total") => StatisticsDate::Total,
                 Some("current") | None => StatisticsDate::Current,
-                Some(s) => bail!("unknown statistics time `{s}`"),
+                Some(s) => err!(month(s).with_context(|| format!("unknown statistics time `{s}`"))),
             }),
             _ => return None,
         },
     ))
 }
 
+/// Parse an admin role change like `add <@&123>`, where the role is either mentioned or given by
+/// its ID.
+fn admin_role(args: &str) -> Result<request::Admins> {
+    let Some((action, role)) = args.split_once(char::is_whitespace) else {
+        anyhow::bail!("usage: !admins role [add|remove] <role>");
+    };
+    let role = role.trim();
+    let role = role
+        .strip_prefix("<@&")
+        .and_then(|role| role.strip_suffix('>'))
+        .unwrap_or(role)
+        .parse::<NonZero<u64>>()
+        .with_context(|| format!("invalid role `{role}`"))?;
+
+    Ok(match action {
+        "add" => request::Admins::AddRole(role),
+        "remove" => request::Admins::RemoveRole(role),
+        s => anyhow::bail!("unknown action `{s}`"),
+    })
+}
+
+/// Parse a month like `2024-09`, to get the statistics of that month.
+pub fn month(value: &str) -> Result<StatisticsDate> {
+    let (year, month) = value
+        .split_once('-')
+        .context("expected a month like `2024-09`")?;
+    Ok(StatisticsDate::Month {
+        year: year.parse()?,
+        month: month.parse::<u8>()?.try_into()?,
+    })
+}
+
+/// Parse a day like `2024-09-21`.
+pub fn day(value: &str) -> Result<Date> {
+    const FORMAT: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
+    Date::parse(value, FORMAT)
+        .with_context(|| format!("expected a day like `2024-09-21`, not `{value}`"))
+}
+
+/// Parse a crate name with an optional version, like `tokio@1.38`.
+fn crate_version(value: &str) -> Result<(String, Option<String>)> {
+    Ok(match value.split_once('@') {
+        Some((name, version)) if !name.is_empty() && !version.is_empty() => {
+            (name.to_owned(), Some(version.to_owned()))
+        }
+        Some(_) => anyhow::bail!(commands::usage("crate")),
+        None => (value.to_owned(), None),
+    })
+}
+
+/// Parse the arguments of `!set`, to show, change or reset the author's personal settings.
+fn prefs(args: &str) -> Result<request::Prefs> {
+    let args = args.to_lowercase();
+    Ok(match args.split_whitespace().collect::<Vec<_>>()[..] {
+        [] => request::Prefs::Show,
+        ["reset", key] => {
+            ensure!(
+                UserPref::KEYS.contains(&key),
+                "unknown setting `{key}`, must be one of `{}`",
+                UserPref::KEYS.join("`, `")
+            );
+            request::Prefs::Reset(key.to_owned())
+        }
+        [key, value] => request::Prefs::Set(UserPref::parse(key, value)?),
+        _ => anyhow::bail!(commands::usage("set")),
+    })
+}
+
+/// Parse the optional `on` or `off` argument of the named command, where nothing means to show
+/// the current state.
+fn switch(name: &str, args: Option<&str>) -> Result<Option<bool>> {
+    match args.map(str::trim) {
+        None => Ok(None),
+        Some("on") => Ok(Some(true)),
+        Some("off") => Ok(Some(false)),
+        Some(_) => Err(anyhow!(commands::usage(name))),
+    }
+}
+
+/// Parse the opponent and the points of a duel, like `@somebody 100`.
+fn duel(args: &str) -> Result<request::User> {
+    match args.split_whitespace().collect::<Vec<_>>()[..] {
+        [target, amount] => Ok(request::User::Duel {
+            target: target.to_owned(),
+            amount: amount.parse()?,
+        }),
+        _ => Err(anyhow!(commands::usage("duel"))),
+    }
+}
+
+/// Parse a birthday like `04-02`, without the year, so it can be kept private.
+pub fn birthday(value: &str) -> Result<request::Birthday> {
+    let (month, day) = value
+        .split_once('-')
+        .context("expected a birthday like `04-02`")?;
+    let (month, day) = (month.parse::<u8>()?.try_into()?, day.parse()?);
+
+    // Any leap year, so February 29 is a valid birthday.
+    Date::from_calendar_date(2000, month, day)?;
+
+    Ok(request::Birthday::Set { month, day })
+}
+
+/// Arguments of a command, that are split at whitespace. Double quotes group several words into a
+/// single argument, like `"Best crate?"`, and a backslash escapes a quote, backslash or whitespace
+/// that follows it. Any other backslash is kept as is, so regular expressions like `what\s+editor`
+/// can be written without escaping.
+pub struct Args<'a>(&'a str);
+
+impl<'a> Args<'a> {
+    /// Split the given text into arguments.
+    #[must_use]
+    pub fn new(args: &'a str) -> Self {
+        Self(args)
+    }
+
+    /// Next argument, if there is any left.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the argument has a quote that is never closed.
+    pub fn arg(&mut self) -> Result<Option<String>> {
+        self.next().transpose()
+    }
+
+    /// All text after the arguments that were taken so far, as is but without surrounding
+    /// whitespace.
+    #[must_use]
+    pub fn rest(&self) -> &'a str {
+        self.0.trim()
+    }
+}
+
+impl Iterator for Args<'_> {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let args = self.0.trim_start();
+        if args.is_empty() {
+            self.0 = args;
+            return None;
+        }
+
+        let mut arg = String::new();
+        let mut quoted = false;
+        let mut end = args.len();
+        let mut chars = args.char_indices().peekable();
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next_if(|&(_, c)| c == '"' || c == '\\' || c.is_whitespace()) {
+                    Some((_, escaped)) => arg.push(escaped),
+                    None => arg.push(c),
+                },
+                '"' => quoted = !quoted,
+                c if c.is_whitespace() && !quoted => {
+                    end = i;
+                    break;
+                }
+                c => arg.push(c),
+            }
+        }
+
+        if quoted {
+            self.0 = "";
+            return Some(Err(anyhow!("missing closing quote in `{args}`")));
+        }
+
+        self.0 = &args[end..];
+        Some(Ok(arg))
+    }
+}
+
+/// Parse a poll like `"Best crate?" serde "async-std" 3m`, where the question is quoted and the
+/// last argument is the duration in seconds or minutes.
+fn poll(kind: PollKind, args: &str) -> Result<Poll> {
+    let usage = || anyhow!(commands::usage(kind.name()));
+
+    anyhow::ensure!(args.trim_start().starts_with('"'), usage());
+
+    let mut args = Args::new(args);
+    let question = args.arg()?.ok_or_else(usage)?;
+    let mut options = args.collect::<Result<Vec<_>>>()?;
+    let duration = options.pop().ok_or_else(usage)?;
+
+    Ok(Poll {
+        kind,
+        question: question.trim().to_owned(),
+        options,
+        duration: duration_arg(&duration)?,
+    })
+}
+
+/// Parse a duration in seconds, minutes or hours, like `90s`, `3m` or `2h`.
+fn duration_arg(value: &str) -> Result<Duration> {
+    let duration = value
+        .strip_suffix('h')
+        .map(|hours| (hours, 3600))
+        .or_else(|| value.strip_suffix('m').map(|minutes| (minutes, 60)))
+        .or_else(|| value.strip_suffix('s').map(|seconds| (seconds, 1)))
+        .and_then(|(value, unit)| value.parse::<u64>().ok()?.checked_mul(unit))
+        .map(Duration::from_secs)
+        .ok_or_else(|| anyhow!("invalid duration `{value}`, use for example 90s, 3m or 2h"))?;
+
+    anyhow::ensure!(
+        duration <= MAX_DURATION,
+        "duration `{value}` is too long, it can be a year at most"
+    );
+    Ok(duration)
+}
+
+/// Parse the target of a custom command, where `all` means every source.
+fn target(source: &str) -> Result<Option<Source>> {
+    Ok(match source {
+        "all" => None,
+        "discord" => Some(Source::Discord),
+        "twitch" => Some(Source::Twitch),
+        "matrix" => Some(Source::Matrix),
+        "youtube" => Some(Source::YouTube),
+        "console" => Some(Source::Console),
+        s => anyhow::bail!("unknown source `{s}`"),
+    })
+}
+
+/// Parse the arguments of custom command management, where the content may contain whitespace.
+fn custom_commands(content: &str) -> Result<request::CustomCommands> {
+    let mut parts = content.trim_end().splitn(5, char::is_whitespace).skip(1);
+
+    Ok(
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some("list"), None, None, None) => request::CustomCommands::List,
+            (Some("show"), Some(name), None, None) => {
+                request::CustomCommands::Show(name.to_owned())
+            }
+            (Some("history"), Some(name), None, None) => {
+                request::CustomCommands::History(name.to_owned())
+            }
+            (Some("revert"), Some(name), None, None) => {
+                request::CustomCommands::Revert(name.to_owned())
+            }
+            (Some("add"), Some(source), Some(name), Some(content))
+                if !content.trim().is_empty() =>
+            {
+                request::CustomCommands::Add {
+                    source: target(source)?,
+                    name: name.to_owned(),
+                    content: content.trim().to_owned(),
+                }
+            }
+            (Some("remove"), Some(source), Some(name), None) => request::CustomCommands::Remove {
+                source: target(source)?,
+                name: name.to_owned(),
+            },
+            _ => anyhow::bail!(commands::usage("custom_commands")),
+        },
+    )
+}
+
+/// Parse the message of the away command, which is the whole remaining text.
+fn away(content: &str) -> Result<String> {
+    content
+        .split_once(char::is_whitespace)
+        .map(|(_, message)| message.trim())
+        .filter(|message| !message.is_empty())
+        .map(ToOwned::to_owned)
+        .with_context(|| commands::usage("away"))
+}
+
+/// Parse the arguments of user note commands, where the note content may contain whitespace.
+fn user_notes(content: &str) -> Result<request::UserNotes> {
+    let mut parts = content.splitn(4, char::is_whitespace).skip(1);
+
+    Ok(match (parts.next(), parts.next(), parts.next()) {
+        (Some("list"), Some(user), None) => request::UserNotes::List(user.parse()?),
+        (Some("add"), Some(user), Some(text)) if !text.trim().is_empty() => {
+            request::UserNotes::Add {
+                user: user.parse()?,
+                content: text.trim().to_owned(),
+            }
+        }
+        (Some("remove" | "delete"), Some(id), None) => request::UserNotes::Remove(
+            id.trim_start_matches('#')
+                .parse()
+                .map_err(|_| anyhow::anyhow!("invalid note ID `{id}`"))?,
+        ),
+        _ => anyhow::bail!(commands::usage("usernote")),
+    })
+}
+
+/// Parse the arguments of suggestion commands, where the reason of a review may contain whitespace.
+fn suggestions(content: &str) -> Result<request::Suggestions> {
+    let mut parts = content.splitn(4, char::is_whitespace).skip(1);
+
+    Ok(match (parts.next(), parts.next(), parts.next()) {
+        (Some("list") | None, None, None) => request::Suggestions::List,
+        (Some(action @ ("accept" | "reject")), Some(id), reason) => request::Suggestions::Review {
+            id: id
+                .trim_start_matches('#')
+                .parse()
+                .map_err(|_| anyhow!("invalid suggestion ID `{id}`"))?,
+            status: if action == "accept" {
+                SuggestionStatus::Accepted
+            } else {
+                SuggestionStatus::Rejected
+            },
+            reason: reason
+                .map(str::trim)
+                .filter(|reason| !reason.is_empty())
+                .map(ToOwned::to_owned),
+        },
+        _ => anyhow::bail!(commands::usage("suggestions")),
+    })
+}
+
+/// Parse the admin forms of the topic command, or nothing if it's the user form.
+fn topic(content: &str) -> Option<Result<Option<String>>> {
+    let mut parts = content.splitn(3, char::is_whitespace).skip(1);
+
+    match (parts.next(), parts.next()) {
+        (Some("set"), Some(text)) if !text.trim().is_empty() => {
+            Some(Ok(Some(text.trim().to_owned())))
+        }
+        (Some("set"), _) => Some(Err(anyhow!(commands::usage("topic")))),
+        (Some("clear"), None) => Some(Ok(None)),
+        _ => None,
+    }
+}
+
+/// Parse the arguments of the issue command, which either takes a title with an optional body after
+/// a `|`, or refers to a suggestion.
+fn issue(content: &str) -> Result<request::Issue> {
+    let args = content
+        .split_once(char::is_whitespace)
+        .map_or("", |(_, args)| args.trim());
+
+    if let Some(id) = args.strip_prefix("suggestion ") {
+        let id = id.trim();
+        return Ok(request::Issue::Suggestion(
+            id.trim_start_matches('#')
+                .parse()
+                .map_err(|_| anyhow!("invalid suggestion ID `{id}`"))?,
+        ));
+    }
+
+    let (title, body) = match args.split_once('|') {
+        Some((title, body)) => (title.trim(), Some(body.trim())),
+        None => (args, None),
+    };
+    ensure!(!title.is_empty(), commands::usage("issue"));
+
+    Ok(request::Issue::New {
+        title: title.to_owned(),
+        body: body.filter(|body| !body.is_empty()).map(ToOwned::to_owned),
+    })
+}
+
+/// Parse the arguments of trigger word commands, where the response may contain whitespace.
+fn triggers(content: &str) -> Result<request::Triggers> {
+    let mut parts = content.splitn(4, char::is_whitespace).skip(1);
+
+    Ok(match (parts.next(), parts.next(), parts.next()) {
+        (Some("list"), None, None) => request::Triggers::List,
+        (Some("add"), Some(word), Some(text)) if !text.trim().is_empty() => {
+            request::Triggers::Add {
+                word: word.to_owned(),
+                content: text.trim().to_owned(),
+            }
+        }
+        (Some("remove"), Some(word), None) => request::Triggers::Remove(word.to_owned()),
+        (Some("cooldown"), Some(word), Some(cooldown)) => request::Triggers::Cooldown {
+            word: word.to_owned(),
+            cooldown: cooldown.trim().parse()?,
+        },
+        (Some(state @ ("on" | "off")), Some(source), Some(word)) => request::Triggers::Enable {
+            word: word.trim().to_owned(),
+            source: source.parse()?,
+            enabled: state == "on",
+        },
+        _ => anyhow::bail!(commands::usage("trigger")),
+    })
+}
+
+/// Parse the arguments of FAQ commands, where the name and pattern may be quoted and the answer
+/// may contain whitespace.
+fn faqs(content: &str) -> Result<request::Faqs> {
+    let usage = || anyhow!(commands::usage("faq"));
+
+    let mut args = Args::new(content);
+    args.arg()?;
+
+    Ok(match args.arg()?.as_deref() {
+        Some("list") if args.rest().is_empty() => request::Faqs::List,
+        Some(kind @ ("add" | "regex")) => {
+            let name = args.arg()?.ok_or_else(usage)?;
+            let pattern = args.arg()?.ok_or_else(usage)?;
+            let answer = args.rest();
+            anyhow::ensure!(!answer.is_empty(), usage());
+
+            request::Faqs::Add {
+                name,
+                pattern,
+                regex: kind == "regex",
+                answer: answer.to_owned(),
+            }
+        }
+        Some("remove") => match (args.arg()?, args.rest()) {
+            (Some(name), "") => request::Faqs::Remove(name),
+            _ => return Err(usage()),
+        },
+        _ => return Err(usage()),
+    })
+}
+
+/// Parse the arguments of the commands for watched crates.
+fn watch_crates(content: &str) -> Result<request::WatchCrates> {
+    let mut parts = content.split_whitespace().skip(1);
+
+    Ok(match (parts.next(), parts.next(), parts.next()) {
+        (Some("list"), None, None) => request::WatchCrates::List,
+        (Some("add"), Some(name), None) => request::WatchCrates::Add(name.to_owned()),
+        (Some("remove"), Some(name), None) => request::WatchCrates::Remove(name.to_owned()),
+        _ => anyhow::bail!(commands::usage("watchcrate")),
+    })
+}
+
+/// Parse a channel rule change like `allow <#123>`.
+fn channels(content: &str) -> Result<request::Channels> {
+    let mut parts = content.split_whitespace().skip(1);
+    let (action, channel) = match (parts.next(), parts.next(), parts.next()) {
+        (Some("list"), None, None) => return Ok(request::Channels::List),
+        (Some(action), Some(channel), None) => (action, channel),
+        _ => anyhow::bail!(commands::usage("channels")),
+    };
+
+    let channel = channel_arg(channel)?;
+    let rule = match action {
+        "reset" => None,
+        rule => Some(rule.parse()?),
+    };
+
+    Ok(request::Channels::Set { channel, rule })
+}
+
+/// Parse a silenced channel like `<#123> 30m`, where `here` means the current channel and `off`
+/// ends the silence.
+fn silence(content: &str) -> Result<request::Admin> {
+    let mut parts = content.split_whitespace().skip(1);
+    let (Some(channel), Some(duration), None) = (parts.next(), parts.next(), parts.next()) else {
+        anyhow::bail!(commands::usage("silence"));
+    };
+
+    Ok(request::Admin::Silence {
+        channel: match channel {
+            "here" => None,
+            channel => Some(channel_arg(channel)?),
+        },
+        duration: match duration {
+            "off" => None,
+            duration => Some(duration_arg(duration)?),
+        },
+    })
+}
+
+/// Parse the channel to keep the command list in, where `here` means the current channel and `off`
+/// stops updating it.
+fn command_list(content: &str) -> Result<request::CommandList> {
+    let mut parts = content.split_whitespace().skip(1);
+
+    Ok(match (parts.next(), parts.next()) {
+        (Some("here"), None) => request::CommandList::Pin(None),
+        (Some("off"), None) => request::CommandList::Off,
+        (Some(channel), None) => request::CommandList::Pin(Some(channel_arg(channel)?)),
+        _ => anyhow::bail!(commands::usage("commandlist")),
+    })
+}
+
+/// Parse a Discord channel, that is either mentioned or given by its ID.
+fn channel_arg(value: &str) -> Result<NonZero<u64>> {
+    value
+        .strip_prefix("<#")
+        .and_then(|channel| channel.strip_suffix('>'))
+        .unwrap_or(value)
+        .parse()
+        .with_context(|| format!("invalid channel `{value}`"))
+}
+
 /// Handle messages only accessible to owners defined in the settings and prepare a response.
-fn owner_message(content: &str, mention: Option<NonZero<u64>>) -> Option<Result<request::Owner>> {
+fn owner_message(
+    content: &str,
+    source: Source,
+    mention: Option<NonZero<u64>>,
+) -> Option<Result<request::Owner>> {
     let mut parts = content.splitn(3, char::is_whitespace);
     let command = parts.next()?.strip_prefix('!')?;
 
+    // The broadcast text is kept as is, including its whitespace.
+    if command.eq_ignore_ascii_case("broadcast") {
+        let text = content
+            .split_once(char::is_whitespace)
+            .map(|(_, text)| text.trim())
+            .filter(|text| !text.is_empty());
+        return Some(
+            text.map(|text| request::Owner::Broadcast(text.to_owned()))
+                .ok_or_else(|| anyhow!(commands::usage("broadcast"))),
+        );
+    }
+
     Some(Ok(
-        match (command.to_lowercase().as_ref(), parts.next(), parts.next()) {
-            ("owner_help" | "owner-help" | "ownerhelp" | "ohelp", None, None) => {
-                request::Owner::Help
-            }
-            ("admins" | "admin", Some("list"), None) => {
-                request::Owner::Admins(request::Admins::List)
-            }
-            ("admins" | "admin", Some(action), _) => request::Owner::Admins(match action {
+        match (commands::find(command)?.name, parts.next(), parts.next()) {
+            ("ohelp", None, None) => request::Owner::Help,
+            ("admins", Some("list"), None) => request::Owner::Admins(request::Admins::List),
+            ("admins", Some("role"), Some(args)) => request::Owner::Admins(err!(admin_role(args))),
+            ("admins", Some(action), _) => request::Owner::Admins(match action {
                 "add" => request::Admins::Add(mention?.into()),
                 "remove" => request::Admins::Remove(mention?.into()),
                 s => bail!("unknown action `{s}`"),
             }),
+            ("plugins", Some("list"), None) => request::Owner::Plugins(request::Plugins::List),
+            ("plugins", Some(action), Some(name)) => request::Owner::Plugins(match action {
+                "load" => request::Plugins::Load(name.trim().to_owned()),
+                "unload" => request::Plugins::Unload(name.trim().to_owned()),
+                s => bail!("unknown action `{s}`"),
+            }),
+            ("debug", Some("parse"), Some(text)) => request::Owner::DebugParse {
+                text: text.to_owned(),
+                source,
+                mention,
+            },
+            ("resync_commands", None, None) => request::Owner::ResyncCommands,
+            ("cleanup", None, None) => request::Owner::Cleanup,
+            #[cfg(feature = "chaos")]
+            ("chaos", None | Some("status"), None) => request::Owner::Chaos(request::Chaos::Status),
+            #[cfg(feature = "chaos")]
+            ("chaos", Some("off"), None) => request::Owner::Chaos(request::Chaos::Off),
+            #[cfg(feature = "chaos")]
+            ("chaos", Some(fault), Some(args)) => request::Owner::Chaos(err!(chaos(fault, args))),
             _ => return None,
         },
     ))
 }
 
+#[cfg(feature = "chaos")]
+fn chaos(fault: &str, args: &str) -> Result<request::Chaos> {
+    use std::time::Duration;
+
+    use anyhow::Context;
+
+    fn rate(value: &str) -> Result<u8> {
+        let rate = value
+            .trim_end_matches('%')
+            .parse::<u8>()
+            .with_context(|| format!("invalid rate `{value}`"))?;
+        anyhow::ensure!(rate <= 100, "rate must be between 0 and 100 percent");
+        Ok(rate)
+    }
+
+    let args = args.split_whitespace().collect::<Vec<_>>();
+
+    Ok(match (fault, &args[..]) {
+        ("delay", [value]) => request::Chaos::Delay {
+            rate: rate(value)?,
+            max: Duration::from_secs(5),
+        },
+        ("delay", [value, max]) => request::Chaos::Delay {
+            rate: rate(value)?,
+            max: Duration::from_millis(
+                max.parse()
+                    .with_context(|| format!("invalid maximum delay `{max}`"))?,
+            ),
+        },
+        ("send", [value]) => request::Chaos::SendFailure(rate(value)?),
+        ("events", [value]) => request::Chaos::EventDrop(rate(value)?),
+        _ => anyhow::bail!(commands::usage("chaos")),
+    })
+}
+
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
     use similar_asserts::assert_eq;
     use test_case::test_matrix;
+    use time::{macros::date, UtcOffset};
 
     use super::*;
+    use crate::api::{Availability, ChannelRule, TemperatureUnit, UserRef};
 
     fn parse_ok(value: impl AsRef<str>) -> Request {
         parse_simple(value).unwrap().unwrap()
@@ -163,6 +871,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn owner_admin_roles() {
+        let role = NonZero::new(123).unwrap();
+        assert_eq!(
+            Request::Owner(request::Owner::Admins(request::Admins::AddRole(role))),
+            parse_ok("!admins role add <@&123>")
+        );
+        assert_eq!(
+            Request::Owner(request::Owner::Admins(request::Admins::RemoveRole(role))),
+            parse_ok("!admin role remove 123")
+        );
+        assert!(parse_simple("!admins role add everyone").is_err());
+        assert!(parse_simple("!admins role add").is_err());
+    }
+
     #[test_matrix(["admins", "admin"])]
     fn owner_admins_add(name: &str) {
         let req = parse_ok(format!("!{name} add x"));
@@ -191,6 +914,95 @@ mod tests {
         assert!(req.is_err());
     }
 
+    #[test]
+    fn owner_plugins_list() {
+        let req = parse_ok("!plugins list");
+        assert_eq!(
+            Request::Owner(request::Owner::Plugins(request::Plugins::List)),
+            req
+        );
+    }
+
+    #[test]
+    fn owner_plugins_load() {
+        let req = parse_ok("!plugins load weather");
+        assert_eq!(
+            Request::Owner(request::Owner::Plugins(request::Plugins::Load(
+                "weather".to_owned()
+            ))),
+            req
+        );
+    }
+
+    #[test]
+    fn owner_plugins_unknown_action() {
+        let req = parse_simple("!plugins meep weather");
+        assert!(req.is_err());
+    }
+
+    #[test]
+    fn owner_debug_parse() {
+        let req = parse_ok("!debug parse !crate anyhow");
+        assert_eq!(
+            Request::Owner(request::Owner::DebugParse {
+                text: "!crate anyhow".to_owned(),
+                source: Source::Discord,
+                mention: Some(NonZero::new(1).unwrap()),
+            }),
+            req
+        );
+    }
+
+    #[test]
+    fn owner_resync_commands() {
+        let req = parse_ok("!resync_commands");
+        assert_eq!(Request::Owner(request::Owner::ResyncCommands), req);
+    }
+
+    #[test]
+    fn owner_cleanup() {
+        let req = parse_ok("!cleanup");
+        assert_eq!(Request::Owner(request::Owner::Cleanup), req);
+    }
+
+    #[test]
+    fn owner_broadcast() {
+        let req = parse_ok("!broadcast Stream starts  in 5 minutes!");
+        assert_eq!(
+            Request::Owner(request::Owner::Broadcast(
+                "Stream starts  in 5 minutes!".to_owned()
+            )),
+            req
+        );
+
+        assert!(parse_simple("!broadcast").is_err());
+        assert!(parse_simple("!broadcast   ").is_err());
+    }
+
+    #[cfg(feature = "chaos")]
+    #[test]
+    fn owner_chaos() {
+        use std::time::Duration;
+
+        assert_eq!(
+            Request::Owner(request::Owner::Chaos(request::Chaos::Status)),
+            parse_ok("!chaos")
+        );
+        assert_eq!(
+            Request::Owner(request::Owner::Chaos(request::Chaos::Delay {
+                rate: 20,
+                max: Duration::from_millis(1500)
+            })),
+            parse_ok("!chaos delay 20% 1500")
+        );
+        assert_eq!(
+            Request::Owner(request::Owner::Chaos(request::Chaos::EventDrop(5))),
+            parse_ok("!chaos events 5")
+        );
+        assert!(parse_simple("!chaos send 101").is_err());
+        assert!(parse_simple("!chaos meep 10").is_err());
+    }
+
     #[test_matrix(["admin_help", "adminhelp", "ahelp"])]
     fn admin_ahelp(name: &str) {
         let req = parse_ok(format!("!{name}"));
@@ -210,12 +1022,22 @@ mod tests {
 
     #[test_matrix(
         ["custom_command", "custom_commands"],
-        [None, Some(Source::Discord), Some(Source::Twitch)]
+        [
+            None,
+            Some(Source::Discord),
+            Some(Source::Twitch),
+            Some(Source::Matrix),
+            Some(Source::YouTube),
+            Some(Source::Console)
+        ]
     )]
     fn admin_custom_cmd_add(name: &str, target: Option<Source>) {
         let t = match target {
             Some(Source::Discord) => "discord",
             Some(Source::Twitch) => "twitch",
+            Some(Source::Matrix) => "matrix",
+            Some(Source::YouTube) => "youtube",
+            Some(Source::Console) => "console",
             None => "all",
         };
 
@@ -240,12 +1062,22 @@ mod tests {
 
     #[test_matrix(
         ["custom_command", "custom_commands"],
-        [None, Some(Source::Discord), Some(Source::Twitch)]
+        [
+            None,
+            Some(Source::Discord),
+            Some(Source::Twitch),
+            Some(Source::Matrix),
+            Some(Source::YouTube),
+            Some(Source::Console)
+        ]
     )]
     fn admin_custom_cmd_remove(name: &str, target: Option<Source>) {
         let t = match target {
             Some(Source::Discord) => "discord",
             Some(Source::Twitch) => "twitch",
+            Some(Source::Matrix) => "matrix",
+            Some(Source::YouTube) => "youtube",
+            Some(Source::Console) => "console",
             None => "all",
         };
 
@@ -267,6 +1099,28 @@ mod tests {
         assert!(req.is_err());
     }
 
+    #[test_matrix(["custom_command", "custom_commands"])]
+    fn admin_custom_cmd_history(name: &str) {
+        assert_eq!(
+            Request::Admin(request::Admin::CustomCommands(
+                request::CustomCommands::History("key".to_owned())
+            )),
+            parse_ok(format!("!{name} history key"))
+        );
+        assert_eq!(
+            Request::Admin(request::Admin::CustomCommands(
+                request::CustomCommands::Show("key".to_owned())
+            )),
+            parse_ok(format!("!{name} show key"))
+        );
+        assert_eq!(
+            Request::Admin(request::Admin::CustomCommands(
+                request::CustomCommands::Revert("key".to_owned())
+            )),
+            parse_ok(format!("!{name} revert key"))
+        );
+    }
+
     #[test]
     fn admin_custom_cmd_invalid() {
         let req = parse_simple("!custom_command meep all key");
@@ -278,6 +1132,7 @@ mod tests {
         let d = match date {
             StatisticsDate::Total => "total",
             StatisticsDate::Current => "current",
+            StatisticsDate::Month { .. } | StatisticsDate::Tag(_) => unreachable!(),
         };
 
         let req = parse_ok(format!("!stats {d}"));
@@ -285,42 +1140,926 @@ mod tests {
     }
 
     #[test]
-    fn admin_stats_invalid() {
-        let req = parse_simple("!stats meep");
-        assert!(req.is_err());
+    fn admin_stats_tag() {
+        let req = parse_ok("!stats tag rust-web");
+        assert_eq!(
+            Request::Admin(request::Admin::StreamTag("rust-web".to_owned())),
+            req
+        );
     }
 
-    #[test_matrix(["help", "bot"])]
-    fn user_help(name: &str) {
+    #[test]
+    fn admin_stats_tag_total() {
+        let req = parse_ok("!stats tag rust-web total");
+        assert_eq!(
+            Request::Admin(request::Admin::Statistics(StatisticsDate::Tag(
+                "rust-web".to_owned()
+            ))),
+            req
+        );
+    }
+
+    #[test]
+    fn admin_stats_timing() {
+        let req = parse_ok("!stats timing");
+        assert_eq!(Request::Admin(request::Admin::Timing), req);
+    }
+
+    #[test]
+    fn admin_skip() {
+        let req = parse_ok("!skip");
+        assert_eq!(Request::Admin(request::Admin::SkipSong), req);
+    }
+
+    #[test]
+    fn admin_shoutout() {
+        assert_eq!(
+            Request::Admin(request::Admin::Shoutout(None)),
+            parse_ok("!shoutout")
+        );
+        assert_eq!(
+            Request::Admin(request::Admin::Shoutout(Some(true))),
+            parse_ok("!shoutout on")
+        );
+        assert_eq!(
+            Request::Admin(request::Admin::Shoutout(Some(false))),
+            parse_ok("!shoutout off")
+        );
+        assert!(parse_simple("!shoutout maybe").is_err());
+    }
+
+    #[test]
+    fn admin_away() {
+        assert_eq!(
+            Request::Admin(request::Admin::Away("grabbing food, back in 20".to_owned())),
+            parse_ok("!away  grabbing food, back in 20 ")
+        );
+        assert_eq!(Request::Admin(request::Admin::Back), parse_ok("!back"));
+        assert!(parse_simple("!away").is_err());
+    }
+
+    #[test]
+    fn admin_confirm() {
+        assert_eq!(
+            Request::Admin(request::Admin::Confirm),
+            parse_ok("!confirm")
+        );
+    }
+
+    #[test]
+    fn admin_link_preview() {
+        assert_eq!(
+            Request::Admin(request::Admin::LinkPreview(Source::Twitch)),
+            parse_ok("!linkpreview twitch")
+        );
+        assert!(parse_simple("!linkpreview irc").is_err());
+    }
+
+    #[test]
+    fn admin_check_links() {
+        assert_eq!(
+            Request::Admin(request::Admin::CheckLinks),
+            parse_ok("!checklinks")
+        );
+    }
+
+    #[test]
+    fn admin_suggestions() {
+        assert_eq!(
+            Request::Admin(request::Admin::Suggestions(request::Suggestions::List)),
+            parse_ok("!suggestions")
+        );
+        assert_eq!(
+            Request::Admin(request::Admin::Suggestions(request::Suggestions::List)),
+            parse_ok("!suggestions list")
+        );
+        assert_eq!(
+            Request::Admin(request::Admin::Suggestions(request::Suggestions::Review {
+                id: 3,
+                status: SuggestionStatus::Accepted,
+                reason: None,
+            })),
+            parse_ok("!suggestions accept #3")
+        );
+        assert_eq!(
+            Request::Admin(request::Admin::Suggestions(request::Suggestions::Review {
+                id: 4,
+                status: SuggestionStatus::Rejected,
+                reason: Some("we had that last week".to_owned()),
+            })),
+            parse_ok("!suggestions reject 4 we had that last week")
+        );
+        assert!(parse_simple("!suggestions accept").is_err());
+        assert!(parse_simple("!suggestions reject abc").is_err());
+    }
+
+    #[test]
+    fn admin_issue() {
+        assert_eq!(
+            Request::Admin(request::Admin::Issue(request::Issue::New {
+                title: "Add a quote command".to_owned(),
+                body: Some("Viewers asked for it.".to_owned()),
+            })),
+            parse_ok("!issue Add a quote command | Viewers asked for it.")
+        );
+        assert_eq!(
+            Request::Admin(request::Admin::Issue(request::Issue::New {
+                title: "Fix the clock".to_owned(),
+                body: None,
+            })),
+            parse_ok("!issue Fix the clock")
+        );
+        assert_eq!(
+            Request::Admin(request::Admin::Issue(request::Issue::Suggestion(3))),
+            parse_ok("!issue suggestion #3")
+        );
+        assert!(parse_simple("!issue").is_err());
+        assert!(parse_simple("!issue | only a body").is_err());
+        assert!(parse_simple("!issue suggestion abc").is_err());
+    }
+
+    #[test]
+    fn admin_chapters() {
+        assert_eq!(
+            Request::Admin(request::Admin::Chapters(None)),
+            parse_ok("!chapters")
+        );
+        assert_eq!(
+            Request::Admin(request::Admin::Chapters(Some(date!(2025 - 03 - 01)))),
+            parse_ok("!chapters 2025-03-01")
+        );
+        assert!(parse_simple("!chapters yesterday").is_err());
+    }
+
+    #[test]
+    fn admin_cost() {
+        let req = parse_ok("!cost list");
+        assert_eq!(
+            Request::Admin(request::Admin::CommandCosts(request::CommandCosts::List)),
+            req
+        );
+
+        let req = parse_ok("!cost hug 50");
+        assert_eq!(
+            Request::Admin(request::Admin::CommandCosts(request::CommandCosts::Set {
+                name: "hug".to_owned(),
+                cost: 50
+            })),
+            req
+        );
+
+        assert!(parse_simple("!cost hug many").is_err());
+    }
+
+    #[test]
+    fn admin_alert() {
+        let req = parse_ok("!alert list");
+        assert_eq!(
+            Request::Admin(request::Admin::UsageAlerts(request::UsageAlerts::List)),
+            req
+        );
+
+        let req = parse_ok("!alert unknown 100");
+        assert_eq!(
+            Request::Admin(request::Admin::UsageAlerts(request::UsageAlerts::Set {
+                name: "unknown".to_owned(),
+                limit: 100
+            })),
+            req
+        );
+
+        assert!(parse_simple("!alert help often").is_err());
+    }
+
+    #[test]
+    fn admin_availability() {
+        let req = parse_ok("!availability list");
+        assert_eq!(
+            Request::Admin(request::Admin::CommandAvailability(
+                request::CommandAvailability::List
+            )),
+            req
+        );
+
+        let req = parse_ok("!availability sr live");
+        assert_eq!(
+            Request::Admin(request::Admin::CommandAvailability(
+                request::CommandAvailability::Set {
+                    name: "sr".to_owned(),
+                    availability: Availability::Live
+                }
+            )),
+            req
+        );
+
+        assert!(parse_simple("!availability sr sometimes").is_err());
+    }
+
+    #[test]
+    fn admin_stats_month() {
+        let req = parse_ok("!stats 2024-09");
+        assert_eq!(
+            Request::Admin(request::Admin::Statistics(StatisticsDate::Month {
+                year: 2024,
+                month: time::Month::September
+            })),
+            req
+        );
+        assert!(parse_simple("!stats 2024-13").is_err());
+    }
+
+    #[test]
+    fn admin_stats_invalid() {
+        let req = parse_simple("!stats meep");
+        assert!(req.is_err());
+    }
+
+    #[test]
+    fn admin_triggers() {
+        let req = parse_ok("!trigger list");
+        assert_eq!(
+            Request::Admin(request::Admin::Triggers(request::Triggers::List)),
+            req
+        );
+
+        let req = parse_ok("!trigger add rust Rust is the best!");
+        assert_eq!(
+            Request::Admin(request::Admin::Triggers(request::Triggers::Add {
+                word: "rust".to_owned(),
+                content: "Rust is the best!".to_owned(),
+            })),
+            req
+        );
+
+        let req = parse_ok("!trigger cooldown rust 120");
+        assert_eq!(
+            Request::Admin(request::Admin::Triggers(request::Triggers::Cooldown {
+                word: "rust".to_owned(),
+                cooldown: 120,
+            })),
+            req
+        );
+
+        let req = parse_ok("!trigger off twitch rust");
+        assert_eq!(
+            Request::Admin(request::Admin::Triggers(request::Triggers::Enable {
+                word: "rust".to_owned(),
+                source: Source::Twitch,
+                enabled: false,
+            })),
+            req
+        );
+
+        assert!(parse_simple("!trigger add rust").is_err());
+        assert!(parse_simple("!trigger off nowhere rust").is_err());
+        assert!(parse_simple("!trigger cooldown rust soon").is_err());
+    }
+
+    #[test]
+    fn admin_watch_crates() {
+        let req = parse_ok("!watchcrate add tokio");
+        assert_eq!(
+            Request::Admin(request::Admin::WatchCrates(request::WatchCrates::Add(
+                "tokio".to_owned()
+            ))),
+            req
+        );
+
+        let req = parse_ok("!watchcrates list");
+        assert_eq!(
+            Request::Admin(request::Admin::WatchCrates(request::WatchCrates::List)),
+            req
+        );
+
+        assert!(parse_simple("!watchcrate add tokio serde").is_err());
+        assert!(parse_simple("!watchcrate follow tokio").is_err());
+    }
+
+    #[test]
+    fn admin_channels() {
+        assert_eq!(
+            Request::Admin(request::Admin::Channels(request::Channels::List)),
+            parse_ok("!channels list")
+        );
+        assert_eq!(
+            Request::Admin(request::Admin::Channels(request::Channels::Set {
+                channel: NonZero::new(123).unwrap(),
+                rule: Some(ChannelRule::Allow),
+            })),
+            parse_ok("!channels allow <#123>")
+        );
+        assert_eq!(
+            Request::Admin(request::Admin::Channels(request::Channels::Set {
+                channel: NonZero::new(123).unwrap(),
+                rule: None,
+            })),
+            parse_ok("!channel reset 123")
+        );
+
+        assert!(parse_simple("!channels").is_err());
+        assert!(parse_simple("!channels ignore <#123>").is_err());
+        assert!(parse_simple("!channels deny general").is_err());
+    }
+
+    #[test]
+    fn admin_silence() {
+        assert_eq!(
+            Request::Admin(request::Admin::Silence {
+                channel: NonZero::new(123),
+                duration: Some(Duration::from_mins(30)),
+            }),
+            parse_ok("!silence <#123> 30m")
+        );
+        assert_eq!(
+            Request::Admin(request::Admin::Silence {
+                channel: None,
+                duration: Some(Duration::from_hours(2)),
+            }),
+            parse_ok("!silence here 2h")
+        );
+        assert_eq!(
+            Request::Admin(request::Admin::Silence {
+                channel: None,
+                duration: None,
+            }),
+            parse_ok("!silence here off")
+        );
+
+        assert!(parse_simple("!silence here").is_err());
+        assert!(parse_simple("!silence here soon").is_err());
+        assert!(parse_simple("!silence general 30m").is_err());
+    }
+
+    #[test]
+    fn admin_command_list() {
+        assert_eq!(
+            Request::Admin(request::Admin::CommandList(request::CommandList::Pin(
+                NonZero::new(123)
+            ))),
+            parse_ok("!commandlist <#123>")
+        );
+        assert_eq!(
+            Request::Admin(request::Admin::CommandList(request::CommandList::Pin(None))),
+            parse_ok("!commandlist here")
+        );
+        assert_eq!(
+            Request::Admin(request::Admin::CommandList(request::CommandList::Off)),
+            parse_ok("!commandlist off")
+        );
+
+        assert!(parse_simple("!commandlist").is_err());
+        assert!(parse_simple("!commandlist general").is_err());
+    }
+
+    #[test]
+    fn admin_faqs() {
+        let req = parse_ok("!faq list");
+        assert_eq!(
+            Request::Admin(request::Admin::Faqs(request::Faqs::List)),
+            req
+        );
+
+        let req = parse_ok("!faq add keyboard which,keyboard It's a Keychron Q1!");
+        assert_eq!(
+            Request::Admin(request::Admin::Faqs(request::Faqs::Add {
+                name: "keyboard".to_owned(),
+                pattern: "which,keyboard".to_owned(),
+                regex: false,
+                answer: "It's a Keychron Q1!".to_owned(),
+            })),
+            req
+        );
+
+        let req = parse_ok(r"!faq regex editor what\s+editor Helix, of course.");
+        assert_eq!(
+            Request::Admin(request::Admin::Faqs(request::Faqs::Add {
+                name: "editor".to_owned(),
+                pattern: r"what\s+editor".to_owned(),
+                regex: true,
+                answer: "Helix, of course.".to_owned(),
+            })),
+            req
+        );
+
+        let req =
+            parse_ok(r#"!faq regex "rust book" "the (rust )?book" https://doc.rust-lang.org/book"#);
+        assert_eq!(
+            Request::Admin(request::Admin::Faqs(request::Faqs::Add {
+                name: "rust book".to_owned(),
+                pattern: "the (rust )?book".to_owned(),
+                regex: true,
+                answer: "https://doc.rust-lang.org/book".to_owned(),
+            })),
+            req
+        );
+
+        assert!(parse_simple("!faq add keyboard keyboard").is_err());
+        assert!(parse_simple("!faq add keyboard \"which keyboard").is_err());
+        assert!(parse_simple("!faq remove").is_err());
+    }
+
+    #[test]
+    fn admin_user_notes() {
+        let req = parse_ok("!usernote add <@!5> talks a lot about  C++");
+        assert_eq!(
+            Request::Admin(request::Admin::UserNotes(request::UserNotes::Add {
+                user: UserRef {
+                    source: Source::Discord,
+                    id: "5".to_owned(),
+                },
+                content: "talks a lot about  C++".to_owned(),
+            })),
+            req
+        );
+
+        let req = parse_ok("!usernotes list twitch:@SomeOne");
+        assert_eq!(
+            Request::Admin(request::Admin::UserNotes(request::UserNotes::List(
+                UserRef {
+                    source: Source::Twitch,
+                    id: "someone".to_owned(),
+                }
+            ))),
+            req
+        );
+
+        let req = parse_ok("!usernote remove #3");
+        assert_eq!(
+            Request::Admin(request::Admin::UserNotes(request::UserNotes::Remove(3))),
+            req
+        );
+    }
+
+    #[test_matrix([
+        "!usernote add twitch:someone",
+        "!usernote list irc:someone",
+        "!usernote list discord:abc",
+        "!usernote remove three",
+        "!usernote",
+    ])]
+    fn admin_user_notes_invalid(input: &str) {
+        assert!(parse_simple(input).is_err());
+    }
+
+    #[test_matrix(["help", "bot"])]
+    fn user_help(name: &str) {
         let req = parse_ok(format!("!{name}"));
         assert_eq!(Request::User(request::User::Help), req);
     }
 
+    #[test_matrix(["help", "bot"])]
+    fn user_command_help(name: &str) {
+        let req = parse_ok(format!("!{name} crate"));
+        assert_eq!(
+            Request::User(request::User::CommandHelp("crate".to_owned())),
+            req
+        );
+    }
+
+    #[test]
+    fn user_commands() {
+        let req = parse_ok("!commands");
+        assert_eq!(Request::User(request::User::Commands(Source::Discord)), req);
+    }
+
+    #[test]
+    fn user_links() {
+        let req = parse_ok("!links");
+        assert_eq!(Request::User(request::User::Links), req);
+    }
+
+    #[test_matrix(["crate", "crates"])]
+    fn user_crates(name: &str) {
+        let req = parse_ok(format!("!{name} anyhow"));
+        assert_eq!(
+            Request::User(request::User::Crate("anyhow".to_owned())),
+            req
+        );
+    }
+
+    #[test]
+    fn user_crate_version() {
+        let req = parse_ok("!crate tokio@1.38");
+        assert_eq!(
+            Request::User(request::User::CrateVersion {
+                name: "tokio".to_owned(),
+                version: "1.38".to_owned(),
+            }),
+            req
+        );
+
+        assert!(parse_simple("!crate tokio@").is_err());
+        assert!(parse_simple("!crate @1.38").is_err());
+    }
+
+    #[test]
+    fn user_crate_features() {
+        let req = parse_ok("!crate tokio features");
+        assert_eq!(
+            Request::User(request::User::CrateFeatures {
+                name: "tokio".to_owned(),
+                version: None,
+            }),
+            req
+        );
+
+        let req = parse_ok("!crate tokio@1.38 features");
+        assert_eq!(
+            Request::User(request::User::CrateFeatures {
+                name: "tokio".to_owned(),
+                version: Some("1.38".to_owned()),
+            }),
+            req
+        );
+    }
+
+    #[test]
+    fn user_ban() {
+        let req = parse_ok("!ban me");
+        assert_eq!(Request::User(request::User::Ban("me".to_owned())), req);
+    }
+
+    #[test_matrix(["crate", "crates"])]
+    fn user_crate_compare(name: &str) {
+        let req = parse_ok(format!("!{name} compare anyhow thiserror"));
+        assert_eq!(
+            Request::User(request::User::CrateCompare(
+                "anyhow".to_owned(),
+                "thiserror".to_owned()
+            )),
+            req
+        );
+
+        let req = parse_ok(format!("!{name} compare"));
+        assert_eq!(
+            Request::User(request::User::Crate("compare".to_owned())),
+            req
+        );
+    }
+
     #[test]
-    fn user_commands() {
-        let req = parse_ok("!commands");
-        assert_eq!(Request::User(request::User::Commands(Source::Discord)), req);
+    fn user_song_request() {
+        let req = parse_ok("!sr https://youtu.be/dQw4w9WgXcQ ");
+        assert_eq!(
+            Request::User(request::User::SongRequest(
+                "https://youtu.be/dQw4w9WgXcQ".to_owned()
+            )),
+            req
+        );
+
+        let req = parse_ok("!queue");
+        assert_eq!(Request::User(request::User::SongQueue), req);
+        assert!(parse_simple("!queue all").unwrap().is_none());
     }
 
     #[test]
-    fn user_links() {
-        let req = parse_ok("!links");
-        assert_eq!(Request::User(request::User::Links), req);
+    fn user_points() {
+        let req = parse_ok("!points");
+        assert_eq!(Request::User(request::User::Points), req);
+        assert!(parse_simple("!points all").unwrap().is_none());
     }
 
-    #[test_matrix(["crate", "crates"])]
-    fn user_crates(name: &str) {
-        let req = parse_ok(format!("!{name} anyhow"));
+    #[test]
+    fn user_games() {
+        let req = parse_ok("!gamble 25");
+        assert_eq!(Request::User(request::User::Gamble(25)), req);
+        assert!(parse_simple("!gamble lots").is_err());
+
+        let req = parse_ok("!duel @someone 10");
         assert_eq!(
-            Request::User(request::User::Crate("anyhow".to_owned())),
+            Request::User(request::User::Duel {
+                target: "@someone".to_owned(),
+                amount: 10
+            }),
             req
         );
+        assert!(parse_simple("!duel @someone").is_err());
+        assert_eq!(Request::User(request::User::Accept), parse_ok("!accept"));
     }
 
     #[test]
-    fn user_ban() {
-        let req = parse_ok("!ban me");
-        assert_eq!(Request::User(request::User::Ban("me".to_owned())), req);
+    fn user_polls() {
+        let req = parse_ok("!poll \"Best crate?\" serde tokio 3m");
+        assert_eq!(
+            Request::User(request::User::Poll(Poll {
+                kind: PollKind::Poll,
+                question: "Best crate?".to_owned(),
+                options: vec!["serde".to_owned(), "tokio".to_owned()],
+                duration: Duration::from_mins(3),
+            })),
+            req
+        );
+
+        let req = parse_ok("!prediction \"Will it compile?\" yes no 90s");
+        assert!(matches!(
+            req,
+            Request::User(request::User::Poll(Poll {
+                kind: PollKind::Prediction,
+                duration,
+                ..
+            })) if duration == Duration::from_secs(90)
+        ));
+
+        assert!(parse_simple("!poll Best crate? serde tokio 3m").is_err());
+        assert!(parse_simple("!poll \"Best crate?\" serde tokio").is_err());
+        assert!(parse_simple("!poll \"Best crate?\"").is_err());
+        assert!(parse_simple("!poll \"Best crate? serde tokio 3m").is_err());
+    }
+
+    #[test]
+    fn user_polls_quoted_options() {
+        let req = parse_ok(r#"!poll "Best \"async\" runtime?" tokio "async std" smol 3m"#);
+        assert_eq!(
+            Request::User(request::User::Poll(Poll {
+                kind: PollKind::Poll,
+                question: "Best \"async\" runtime?".to_owned(),
+                options: vec![
+                    "tokio".to_owned(),
+                    "async std".to_owned(),
+                    "smol".to_owned()
+                ],
+                duration: Duration::from_mins(3),
+            })),
+            req
+        );
+    }
+
+    #[test]
+    fn args_quotes_and_escapes() {
+        let args = |text| Args::new(text).collect::<Result<Vec<_>>>().unwrap();
+
+        assert_eq!(vec!["a", "b"], args("  a \tb  "));
+        assert_eq!(vec!["a b", "c"], args(r#""a b" c"#));
+        assert_eq!(vec!["ab c"], args(r#"a"b c""#));
+        assert_eq!(vec!["", "a"], args(r#""" a"#));
+        assert_eq!(vec![r#"say "hi""#], args(r#""say \"hi\"""#));
+        assert_eq!(vec!["a b", r"c\d", r"\s+"], args(r"a\ b c\\d \s+"));
+        assert!(args("").is_empty());
+
+        assert!(Args::new(r#"a "b c"#).nth(1).unwrap().is_err());
+    }
+
+    #[test]
+    fn args_rest() {
+        let mut args = Args::new(r#"!faq add "rust book" rust,book  It's "free" "#);
+        assert_eq!(Some("!faq".to_owned()), args.arg().unwrap());
+        args.nth(2);
+        assert_eq!(r#"It's "free""#, args.rest());
+    }
+
+    #[test]
+    fn user_notify_me() {
+        let req = parse_ok("!notifyme");
+        assert_eq!(Request::User(request::User::NotifyMe(None)), req);
+
+        let req = parse_ok("!notifyme off");
+        assert_eq!(Request::User(request::User::NotifyMe(Some(false))), req);
+
+        assert!(parse_simple("!notifyme maybe").is_err());
+        assert_eq!(
+            Some(Request::User(request::User::Custom("notifyme".to_owned()))),
+            super::parse("!notifyme", Source::Twitch, None).unwrap()
+        );
+    }
+
+    #[test]
+    fn user_birthday() {
+        let req = parse_ok("!birthday");
+        assert_eq!(
+            Request::User(request::User::Birthday(request::Birthday::Show)),
+            req
+        );
+
+        let req = parse_ok("!birthday set 02-29");
+        assert_eq!(
+            Request::User(request::User::Birthday(request::Birthday::Set {
+                month: time::Month::February,
+                day: 29
+            })),
+            req
+        );
+
+        let req = parse_ok("!birthday announce off");
+        assert_eq!(
+            Request::User(request::User::Birthday(request::Birthday::Announce(false))),
+            req
+        );
+
+        assert!(parse_simple("!birthday set 02-30").is_err());
+        assert!(parse_simple("!birthday set 13-01").is_err());
+        assert!(parse_simple("!birthday later").is_err());
+    }
+
+    #[test]
+    fn user_fun() {
+        let req = parse_ok("!joke");
+        assert_eq!(Request::User(request::User::Fun(Kind::Joke)), req);
+
+        let req = parse_ok("!ADVICE");
+        assert_eq!(Request::User(request::User::Fun(Kind::Advice)), req);
+
+        assert_eq!(
+            None,
+            super::parse("!fact check", Source::Twitch, None).unwrap()
+        );
+    }
+
+    #[test]
+    fn user_pronouns() {
+        let req = parse_ok("!pronouns @someone");
+        assert_eq!(
+            Request::User(request::User::Pronouns(request::Pronouns::Show(
+                "@someone".to_owned()
+            ))),
+            req
+        );
+
+        let req = parse_ok("!pronouns set  they/them ");
+        assert_eq!(
+            Request::User(request::User::Pronouns(request::Pronouns::Set(
+                "they/them".to_owned()
+            ))),
+            req
+        );
+
+        let req = parse_ok("!pronouns unset");
+        assert_eq!(
+            Request::User(request::User::Pronouns(request::Pronouns::Unset)),
+            req
+        );
+        assert!(parse_simple("!pronouns").is_err());
+    }
+
+    #[test]
+    fn user_prefs() {
+        let req = parse_ok("!set");
+        assert_eq!(
+            Request::User(request::User::Prefs(request::Prefs::Show)),
+            req
+        );
+
+        let req = parse_ok("!set unit Fahrenheit");
+        assert_eq!(
+            Request::User(request::User::Prefs(request::Prefs::Set(UserPref::Unit(
+                TemperatureUnit::Fahrenheit
+            )))),
+            req
+        );
+
+        let req = parse_ok("!set timezone UTC-5:30");
+        assert_eq!(
+            Request::User(request::User::Prefs(request::Prefs::Set(
+                UserPref::Timezone(UtcOffset::from_hms(-5, -30, 0).unwrap())
+            ))),
+            req
+        );
+
+        let req = parse_ok("!set reset locale");
+        assert_eq!(
+            Request::User(request::User::Prefs(request::Prefs::Reset(
+                "locale".to_owned()
+            ))),
+            req
+        );
+
+        assert!(parse_simple("!set locale fr").is_err());
+        assert!(parse_simple("!set timezone +15").is_err());
+        assert!(parse_simple("!set reset color").is_err());
+        assert!(parse_simple("!set unit").is_err());
+    }
+
+    #[test]
+    fn user_convert() {
+        let req = parse_ok("!convert -40");
+        assert_eq!(Request::User(request::User::Convert(-40.0)), req);
+        assert!(parse_simple("!convert warm").is_err());
+    }
+
+    #[test]
+    fn user_ages() {
+        let req = parse("!followage", Source::Twitch, None).unwrap().unwrap();
+        assert_eq!(Request::User(request::User::FollowAge), req);
+
+        let req = parse("!accountage", Source::Twitch, None).unwrap().unwrap();
+        assert_eq!(Request::User(request::User::AccountAge), req);
+
+        let req = parse_ok("!followage");
+        assert_eq!(
+            Request::User(request::User::Custom("followage".to_owned())),
+            req
+        );
+    }
+
+    #[test]
+    fn user_watch_time() {
+        let twitch = |text| parse(text, Source::Twitch, None);
+
+        let req = twitch("!watchtime").unwrap().unwrap();
+        assert_eq!(Request::User(request::User::WatchTime(None)), req);
+
+        let req = twitch("!watchtime off").unwrap().unwrap();
+        assert_eq!(Request::User(request::User::WatchTime(Some(false))), req);
+
+        assert!(twitch("!watchtime later").is_err());
+        assert_eq!(
+            Request::User(request::User::Custom("watchtime".to_owned())),
+            parse_ok("!watchtime")
+        );
+    }
+
+    #[test]
+    fn user_suggest() {
+        assert_eq!(
+            Request::User(request::User::Suggest("Play more Factorio".to_owned())),
+            parse_ok("!suggest  Play more Factorio ")
+        );
+        assert!(parse_simple("!suggest").is_err());
+    }
+
+    #[test]
+    fn user_topic() {
+        assert_eq!(Request::User(request::User::Topic), parse_ok("!topic"));
+        assert_eq!(Request::User(request::User::Topic), parse_ok("!agenda"));
+        assert_eq!(
+            Request::Admin(request::Admin::Topic(Some("Rust quiz".to_owned()))),
+            parse_ok("!topic set Rust quiz")
+        );
+        assert_eq!(
+            Request::Admin(request::Admin::Topic(None)),
+            parse_ok("!topic clear")
+        );
+        assert!(parse_simple("!topic set").is_err());
+        assert!(parse_simple("!topic something").is_err());
+    }
+
+    #[test]
+    fn user_stream_history() {
+        let req = parse_ok("!yesterday");
+        assert_eq!(Request::User(request::User::Yesterday), req);
+
+        let req = parse_ok("!lastcategory  Just Chatting ");
+        assert_eq!(
+            Request::User(request::User::LastCategory("Just Chatting".to_owned())),
+            req
+        );
+        assert!(parse_simple("!lastcategory").is_err());
+    }
+
+    #[test]
+    fn user_lurk() {
+        let req = parse_ok("!lurk");
+        assert_eq!(Request::User(request::User::Lurk(None)), req);
+
+        let req = parse_ok("!lurk  making tea ");
+        assert_eq!(
+            Request::User(request::User::Lurk(Some("making tea".to_owned()))),
+            req
+        );
+
+        let req = parse_ok("!unlurk");
+        assert_eq!(Request::User(request::User::Unlurk), req);
+        assert_eq!(None, parse_simple("!unlurk now").unwrap());
+    }
+
+    #[test]
+    fn user_highlights() {
+        let req = parse_ok("!highlights");
+        assert_eq!(Request::User(request::User::Highlights), req);
+        assert_eq!(None, parse_simple("!highlights all").unwrap());
+    }
+
+    #[test]
+    fn user_eval() {
+        let req = parse_ok("!eval ```rust\nlet x = 2;\nx * 21\n```");
+        assert_eq!(
+            Request::User(request::User::Eval(
+                "```rust\nlet x = 2;\nx * 21\n```".to_owned()
+            )),
+            req
+        );
+        assert!(parse_simple("!eval").is_err());
+    }
+
+    #[test]
+    fn user_rust_versions() {
+        let req = parse_ok("!rustversion");
+        assert_eq!(Request::User(request::User::RustVersions), req);
+        assert_eq!(None, parse_simple("!rustversion nightly").unwrap());
+    }
+
+    #[test]
+    fn user_timeout_me() {
+        let req = parse("!timeout ME", Source::Twitch, None).unwrap().unwrap();
+        assert_eq!(Request::User(request::User::TimeoutMe), req);
+
+        let req = parse_ok("!timeout me");
+        assert_eq!(
+            Request::User(request::User::Plugin {
+                name: "timeout".to_owned(),
+                args: "me".to_owned()
+            }),
+            req
+        );
     }
 
     #[test]
@@ -329,6 +2068,18 @@ mod tests {
         assert_eq!(Request::User(request::User::Today), req);
     }
 
+    #[test]
+    fn user_status() {
+        let req = parse_ok("!status");
+        assert_eq!(Request::User(request::User::Status), req);
+    }
+
+    #[test]
+    fn user_botstat() {
+        let req = parse_ok("!botstat");
+        assert_eq!(Request::User(request::User::BotStat), req);
+    }
+
     #[test]
     fn user_ftoc() {
         let req = parse_ok("!ftoc 1.0");
@@ -359,9 +2110,301 @@ mod tests {
         assert_eq!(Request::User(request::User::Custom("meep".to_owned())), req);
     }
 
+    #[test]
+    fn user_plugin() {
+        let req = parse_ok("!aaa bbb ccc");
+        assert_eq!(
+            Request::User(request::User::Plugin {
+                name: "aaa".to_owned(),
+                args: "bbb ccc".to_owned()
+            }),
+            req
+        );
+    }
+
     #[test]
     fn unknown() {
-        let req = parse("!aaa bbb", Source::Discord, None).unwrap();
+        let req = parse("!links bbb", Source::Discord, None).unwrap();
+        assert!(req.is_none());
+    }
+
+    #[test]
+    fn registered_commands_parse() {
+        let mut failed = Vec::new();
+
+        for info in commands::registry::COMMANDS {
+            if !info.usage.is_empty() && info.examples.is_empty() {
+                failed.push(format!("`{}` has no examples", info.name));
+            }
+
+            let source = info.sources.first().copied().unwrap_or(Source::Discord);
+            let names = std::iter::once(info.name).chain(info.aliases.iter().copied());
+
+            for (name, example) in
+                names.flat_map(|name| info.examples.iter().map(move |e| (name, e)))
+            {
+                // Discord shows channel mentions by name, but sends them as `<#id>`.
+                let text = example
+                    .replacen(&format!("!{}", info.name), &format!("!{name}"), 1)
+                    .replace("#bot-commands", "<#1>");
+                match parse(&text, source, NonZero::new(1)) {
+                    Ok(Some(Request::User(
+                        request::User::Custom(_) | request::User::Plugin { .. },
+                    ))) => failed.push(format!("`{text}` isn't parsed as built-in command")),
+                    Ok(Some(req)) if req.name() != info.name => {
+                        failed.push(format!("`{text}` is parsed as `{}`", req.name()));
+                    }
+                    Ok(Some(_)) => {}
+                    Ok(None) => failed.push(format!("`{text}` isn't parsed")),
+                    Err(e) => failed.push(format!("`{text}` failed: {e}")),
+                }
+            }
+        }
+
+        assert!(failed.is_empty(), "{failed:#?}");
+    }
+
+    #[test]
+    fn unregistered_commands_are_custom() {
+        assert_eq!(
+            Request::User(request::User::Custom("nope".to_owned())),
+            parse_ok("!nope")
+        );
+        assert_eq!(
+            Request::User(request::User::Plugin {
+                name: "nope".to_owned(),
+                args: "list".to_owned(),
+            }),
+            parse_ok("!nope list")
+        );
+    }
+
+    #[test]
+    fn no_prefix() {
+        let req = parse("aaa bbb", Source::Discord, None).unwrap();
         assert!(req.is_none());
     }
+
+    /// Random argument, mostly made of values that commands look for.
+    fn argument() -> impl Strategy<Value = String> {
+        prop_oneof![
+            1 => Just("all".to_owned()),
+            1 => Just("<@1>".to_owned()),
+            1 => Just("\"".to_owned()),
+            2 => "-?[0-9]{1,20}",
+            4 => "[0-9]{1,20}[smh]",
+            1 => "[0-9]{1,2}-[0-9]{1,2}",
+            2 => "\\PC{0,8}",
+        ]
+    }
+
+    /// Chat messages for any form of the built-in commands, like `!silence here 90s`. Alternatives
+    /// like `[add|remove]` are picked randomly, and placeholders like `<name>` are replaced with
+    /// random arguments.
+    fn command_messages() -> impl Strategy<Value = String> {
+        let forms = commands::registry::COMMANDS
+            .iter()
+            .flat_map(|cmd| {
+                let names = std::iter::once(cmd.name).chain(cmd.aliases.iter().copied());
+                names.flat_map(|name| {
+                    cmd.usage
+                        .iter()
+                        .map(move |usage| (name, usage.args))
+                        .chain(std::iter::once((name, "")))
+                })
+            })
+            .collect::<Vec<_>>();
+
+        (
+            proptest::sample::select(forms),
+            proptest::collection::vec((any::<prop::sample::Index>(), argument()), 12),
+            proptest::collection::vec(argument(), 0..2),
+        )
+            .prop_map(|((name, args), picks, extra)| {
+                let args = args
+                    .split_whitespace()
+                    .zip(picks)
+                    .map(|(token, (index, arg))| {
+                        let alternatives = token
+                            .trim_matches(['[', ']'])
+                            .split('|')
+                            .collect::<Vec<_>>();
+                        let alternative = alternatives[index.index(alternatives.len())];
+                        if alternative.starts_with('"') {
+                            format!("\"{arg}\"")
+                        } else if alternative.contains('<') {
+                            arg
+                        } else {
+                            alternative.to_owned()
+                        }
+                    });
+
+                std::iter::once(format!("!{name}"))
+                    .chain(args)
+                    .chain(extra)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+    }
+
+    /// Name that isn't taken by any built-in command.
+    fn custom_name() -> impl Strategy<Value = String> {
+        "[a-z][a-z0-9_]{0,11}".prop_filter("reserved name", |name| !commands::is_reserved(name))
+    }
+
+    /// Free text of a few words, separated by single spaces.
+    fn free_text() -> impl Strategy<Value = String> {
+        proptest::collection::vec("[a-zA-Z0-9.,?!'-]{1,8}", 1..5).prop_map(|words| words.join(" "))
+    }
+
+    /// Structured requests together with the chat message that creates them.
+    fn requests() -> impl Strategy<Value = (Request, String)> {
+        let word = "[a-zA-Z0-9_.-]{1,12}";
+        let source = proptest::option::of(proptest::sample::select(Source::ALL.to_vec()));
+        let seconds = 1..=MAX_DURATION.as_secs();
+
+        prop_oneof![
+            custom_name().prop_map(|name| (
+                Request::User(request::User::Custom(name.clone())),
+                format!("!{name}")
+            )),
+            (word, word).prop_map(|(first, second)| (
+                Request::User(request::User::CrateCompare(first.clone(), second.clone())),
+                format!("!crate compare {first} {second}")
+            )),
+            free_text().prop_map(|target| (
+                Request::User(request::User::Ban(target.clone())),
+                format!("!ban {target}")
+            )),
+            (word, any::<u64>()).prop_map(|(target, amount)| (
+                Request::User(request::User::Duel {
+                    target: target.clone(),
+                    amount
+                }),
+                format!("!duel {target} {amount}")
+            )),
+            any::<f64>()
+                .prop_filter("comparable", |value| value.is_finite())
+                .prop_map(|value| (
+                    Request::User(request::User::Ftoc(value)),
+                    format!("!ftoc {value}")
+                )),
+            (
+                proptest::sample::select(vec![PollKind::Poll, PollKind::Prediction]),
+                free_text().prop_filter("unquoted", |text| !text.contains('"')),
+                proptest::collection::vec(word, 0..4),
+                seconds.clone(),
+            )
+                .prop_map(|(kind, question, options, secs)| (
+                    Request::User(request::User::Poll(Poll {
+                        kind,
+                        question: question.clone(),
+                        options: options.clone(),
+                        duration: Duration::from_secs(secs),
+                    })),
+                    format!(
+                        "!{} \"{question}\" {} {secs}s",
+                        kind.name(),
+                        options.join(" ")
+                    )
+                )),
+            (source, custom_name(), free_text()).prop_map(|(source, name, content)| (
+                Request::Admin(request::Admin::CustomCommands(
+                    request::CustomCommands::Add {
+                        source,
+                        name: name.clone(),
+                        content: content.clone(),
+                    }
+                )),
+                format!(
+                    "!custom_commands add {} {name} {content}",
+                    source.map_or("all".to_owned(), |source| source.to_string().to_lowercase())
+                )
+            )),
+            (
+                proptest::option::of(1..=u64::MAX),
+                proptest::option::of(seconds)
+            )
+                .prop_map(|(channel, secs)| (
+                    Request::Admin(request::Admin::Silence {
+                        channel: channel.and_then(NonZero::new),
+                        duration: secs.map(Duration::from_secs),
+                    }),
+                    format!(
+                        "!silence {} {}",
+                        channel.map_or("here".to_owned(), |channel| format!("<#{channel}>")),
+                        secs.map_or("off".to_owned(), |secs| format!("{secs}s"))
+                    )
+                )),
+            (word, free_text()).prop_map(|(word, content)| (
+                Request::Admin(request::Admin::Triggers(request::Triggers::Add {
+                    word: word.clone(),
+                    content: content.clone(),
+                })),
+                format!("!trigger add {word} {content}")
+            )),
+        ]
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig {
+            cases: 1024,
+            timeout: 1000,
+            ..ProptestConfig::default()
+        })]
+
+        #[test]
+        fn never_panics_on_any_text(text in "\\PC*", mention: Option<u64>) {
+            let _ = parse(&text, Source::Discord, mention.and_then(NonZero::new));
+        }
+
+        #[test]
+        fn never_panics_on_commands(text in command_messages()) {
+            for source in Source::ALL {
+                let _ = parse(&text, source, NonZero::new(1));
+            }
+        }
+
+        #[test]
+        fn args_round_trip(args in proptest::collection::vec("\\PC*", 0..5)) {
+            let text = args
+                .iter()
+                .map(|arg| format!("\"{}\"", arg.replace('\\', r"\\").replace('"', "\\\"")))
+                .collect::<Vec<_>>()
+                .join(" ");
+            prop_assert_eq!(args, Args::new(&text).collect::<Result<Vec<_>>>().unwrap());
+        }
+
+        #[test]
+        fn round_trip((request, text) in requests()) {
+            prop_assert_eq!(request, parse(&text, Source::Discord, None).unwrap().unwrap());
+        }
+    }
+
+    #[test]
+    fn durations_are_bounded() {
+        assert!(parse_simple("!silence here 99999999999999999h").is_err());
+        assert!(parse_simple("!silence here 9000h").is_err());
+        assert_eq!(
+            Request::Admin(request::Admin::Silence {
+                channel: None,
+                duration: Some(MAX_DURATION),
+            }),
+            parse_ok("!silence here 8760h")
+        );
+    }
+
+    #[test]
+    fn custom_cmd_content_with_whitespace() {
+        assert_eq!(
+            Request::Admin(request::Admin::CustomCommands(
+                request::CustomCommands::Add {
+                    source: None,
+                    name: "hi".to_owned(),
+                    content: "hello there, friend".to_owned(),
+                }
+            )),
+            parse_ok("!custom_commands add all hi hello there, friend")
+        );
+    }
 }
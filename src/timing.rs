@@ -0,0 +1,336 @@
+//! Timing of the message handling, to find out where messages spend their time. Every handled
+//! message passes through the same stages, and each stage collects its durations in a histogram.
+//! The whole time from receiving a message until its reply was sent is collected per command, and
+//! the outcome of sending replies to Discord is counted.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display},
+    future::Future,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+/// Upper bounds of the histogram buckets, in milliseconds. Durations above the last bound are
+/// counted in an extra overflow bucket.
+pub const BUCKETS: [u64; 10] = [1, 5, 10, 25, 50, 100, 250, 500, 1000, 5000];
+
+/// The stages that a message passes through, in order.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    /// Waiting in the queue, until the handler picks it up.
+    Queue,
+    /// Checking the access level of the author.
+    Access,
+    /// Running the command itself.
+    Handler,
+    /// Rendering the response and sending it back to the service.
+    Reply,
+}
+
+impl Stage {
+    /// All stages, in the order a message passes through them.
+    pub const ALL: [Self; 4] = [Self::Queue, Self::Access, Self::Handler, Self::Reply];
+
+    /// Short lowercase name of the stage.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Queue => "queue",
+            Self::Access => "access",
+            Self::Handler => "handler",
+            Self::Reply => "reply",
+        }
+    }
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Outcomes of a single attempt to send a reply to Discord.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplyOutcome {
+    /// The reply was sent.
+    Sent,
+    /// Sending failed for a temporary reason, like a rate limit, and is tried again.
+    Retried,
+    /// Sending failed for a reason that won't go away, like missing permissions, and the reply was
+    /// dropped.
+    Dropped,
+    /// Sending failed for a temporary reason too many times, and the reply was given up.
+    Failed,
+}
+
+impl ReplyOutcome {
+    /// All outcomes, from best to worst.
+    pub const ALL: [Self; 4] = [Self::Sent, Self::Retried, Self::Dropped, Self::Failed];
+
+    /// Short lowercase name of the outcome.
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sent => "sent",
+            Self::Retried => "retried",
+            Self::Dropped => "dropped",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+impl Display for ReplyOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Distribution of the durations of a single stage.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct Histogram {
+    /// Amount of durations within each of the [`BUCKETS`], plus the overflow bucket at the end.
+    /// The counts are **not** cumulative.
+    pub buckets: [u64; BUCKETS.len() + 1],
+    /// Amount of recorded durations.
+    pub count: u64,
+    /// Sum of all recorded durations.
+    pub sum: Duration,
+    /// Longest recorded duration.
+    pub max: Duration,
+}
+
+impl Histogram {
+    fn record(&mut self, duration: Duration) {
+        let index = BUCKETS
+            .iter()
+            .position(|&bound| duration <= Duration::from_millis(bound))
+            .unwrap_or(BUCKETS.len());
+
+        self.buckets[index] += 1;
+        self.count += 1;
+        self.sum += duration;
+        self.max = self.max.max(duration);
+    }
+
+    fn merge(&mut self, other: &Self) {
+        for (bucket, count) in self.buckets.iter_mut().zip(other.buckets) {
+            *bucket += count;
+        }
+        self.count += other.count;
+        self.sum += other.sum;
+        self.max = self.max.max(other.max);
+    }
+
+    /// Average of all recorded durations.
+    #[must_use]
+    pub fn mean(&self) -> Duration {
+        self.sum
+            .checked_div(u32::try_from(self.count).unwrap_or(u32::MAX))
+            .unwrap_or_default()
+    }
+
+    /// Approximation of the given percentile, being the upper bound of the bucket it falls in.
+    /// The longest recorded duration is used instead, if it's lower than that bound.
+    #[must_use]
+    pub fn percentile(&self, percent: u64) -> Duration {
+        let rank = (self.count * percent.min(100)).div_ceil(100).max(1);
+        let mut seen = 0;
+
+        for (count, bound) in self.buckets.iter().zip(BUCKETS) {
+            seen += count;
+            if seen >= rank {
+                return Duration::from_millis(bound).min(self.max);
+            }
+        }
+
+        self.max
+    }
+}
+
+/// Shared collection of the histograms for all stages and commands.
+#[derive(Clone, Default)]
+pub struct Timings(Arc<Mutex<Inner>>);
+
+#[derive(Default)]
+struct Inner {
+    stages: [Histogram; Stage::ALL.len()],
+    commands: BTreeMap<String, Histogram>,
+    replies: [u64; ReplyOutcome::ALL.len()],
+}
+
+impl Timings {
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Record how long a single message spent in the given stage.
+    pub fn record(&self, stage: Stage, duration: Duration) {
+        self.inner().stages[stage as usize].record(duration);
+    }
+
+    /// Record how long it took from receiving a message until its reply was sent.
+    pub fn record_command(&self, command: &str, duration: Duration) {
+        let mut inner = self.inner();
+        if let Some(histogram) = inner.commands.get_mut(command) {
+            histogram.record(duration);
+        } else {
+            let mut histogram = Histogram::default();
+            histogram.record(duration);
+            inner.commands.insert(command.to_owned(), histogram);
+        }
+    }
+
+    /// Run the future and record how long it took to complete.
+    pub async fn measure<F: Future>(&self, stage: Stage, future: F) -> F::Output {
+        let start = Instant::now();
+        let output = future.await;
+        self.record(stage, start.elapsed());
+        output
+    }
+
+    /// Run the future that sends the reply for a command, recording how long it took as
+    /// [`Stage::Reply`], and the whole time since the message was received for the command.
+    pub async fn reply<F: Future>(&self, command: &str, received: Instant, future: F) -> F::Output {
+        let start = Instant::now();
+        let output = future.await;
+        self.record(Stage::Reply, start.elapsed());
+        self.record_command(command, received.elapsed());
+        output
+    }
+
+    /// Count a single attempt to send a reply to Discord.
+    pub fn record_reply(&self, outcome: ReplyOutcome) {
+        self.inner().replies[outcome as usize] += 1;
+    }
+
+    /// Get the amount of reply attempts for each outcome.
+    #[must_use]
+    pub fn replies(&self) -> Vec<(ReplyOutcome, u64)> {
+        ReplyOutcome::ALL
+            .into_iter()
+            .zip(self.inner().replies)
+            .collect()
+    }
+
+    /// Get the current histograms of all stages, in the order a message passes through them.
+    #[must_use]
+    pub fn report(&self) -> Vec<(Stage, Histogram)> {
+        Stage::ALL
+            .into_iter()
+            .zip(self.inner().stages.iter().cloned())
+            .collect()
+    }
+
+    /// Get the current histograms of all commands that were replied to, sorted by name.
+    #[must_use]
+    pub fn commands(&self) -> Vec<(String, Histogram)> {
+        self.inner()
+            .commands
+            .iter()
+            .map(|(name, histogram)| (name.clone(), histogram.clone()))
+            .collect()
+    }
+
+    /// Combined histogram of all commands, for the overall latency of replies.
+    #[must_use]
+    pub fn latency(&self) -> Histogram {
+        self.inner()
+            .commands
+            .values()
+            .fold(Histogram::default(), |mut total, histogram| {
+                total.merge(histogram);
+                total
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn record_stages() {
+        let timings = Timings::default();
+        timings.record(Stage::Queue, Duration::from_micros(200));
+        timings.record(Stage::Handler, Duration::from_millis(30));
+        timings.record(Stage::Handler, Duration::from_secs(10));
+
+        let report = timings.report();
+        assert_eq!(
+            vec![Stage::Queue, Stage::Access, Stage::Handler, Stage::Reply],
+            report.iter().map(|(stage, _)| *stage).collect::<Vec<_>>()
+        );
+
+        let queue = &report[0].1;
+        assert_eq!(1, queue.count);
+        assert_eq!(1, queue.buckets[0]);
+
+        let handler = &report[2].1;
+        assert_eq!(2, handler.count);
+        assert_eq!(1, handler.buckets[4]);
+        assert_eq!(1, handler.buckets[BUCKETS.len()]);
+        assert_eq!(Duration::from_millis(5015), handler.mean());
+        assert_eq!(Duration::from_secs(10), handler.max);
+
+        assert_eq!(0, report[1].1.count);
+        assert_eq!(Duration::ZERO, report[1].1.mean());
+    }
+
+    #[test]
+    fn record_commands() {
+        let timings = Timings::default();
+        timings.record_command("help", Duration::from_millis(3));
+        timings.record_command("help", Duration::from_millis(30));
+        timings.record_command("crate", Duration::from_millis(700));
+
+        let commands = timings.commands();
+        assert_eq!(
+            vec!["crate", "help"],
+            commands.iter().map(|(name, _)| name).collect::<Vec<_>>()
+        );
+        assert_eq!(2, commands[1].1.count);
+
+        let latency = timings.latency();
+        assert_eq!(3, latency.count);
+        assert_eq!(Duration::from_millis(50), latency.percentile(50));
+        assert_eq!(Duration::from_millis(700), latency.percentile(95));
+    }
+
+    #[test]
+    fn count_replies() {
+        let timings = Timings::default();
+        timings.record_reply(ReplyOutcome::Retried);
+        timings.record_reply(ReplyOutcome::Sent);
+        timings.record_reply(ReplyOutcome::Sent);
+
+        assert_eq!(
+            vec![
+                (ReplyOutcome::Sent, 2),
+                (ReplyOutcome::Retried, 1),
+                (ReplyOutcome::Dropped, 0),
+                (ReplyOutcome::Failed, 0),
+            ],
+            timings.replies()
+        );
+    }
+
+    #[test]
+    fn percentiles() {
+        let mut histogram = Histogram::default();
+        for ms in [1, 2, 3, 4, 20, 20, 20, 20, 20, 400] {
+            histogram.record(Duration::from_millis(ms));
+        }
+
+        assert_eq!(Duration::from_millis(5), histogram.percentile(30));
+        assert_eq!(Duration::from_millis(25), histogram.percentile(50));
+        assert_eq!(Duration::from_millis(400), histogram.percentile(95));
+        assert_eq!(Duration::ZERO, Histogram::default().percentile(50));
+    }
+}
@@ -0,0 +1,133 @@
+//! The `!followage` and `!accountage` commands, that tell users how long they've been around.
+
+use time::{
+    format_description::FormatItem, macros::format_description, util, Month, OffsetDateTime,
+};
+use tracing::error;
+use twitch_api::eventsub::channel::ChannelChatMessageV1Payload;
+
+use super::eventsub::Replier;
+use crate::clock::SharedClock;
+
+pub struct Ages {
+    replier: Replier,
+    clock: SharedClock,
+}
+
+impl Ages {
+    pub fn new(replier: Replier, clock: SharedClock) -> Self {
+        Self { replier, clock }
+    }
+
+    /// Create the reply about how long the author of the message has been following the channel.
+    pub async fn follow_age(&self, msg: &ChannelChatMessageV1Payload) -> String {
+        let name = &msg.chatter_user_name;
+        let streamer = &msg.broadcaster_user_name;
+
+        if msg.chatter_user_id == msg.broadcaster_user_id {
+            return format!("{name}, you can't follow yourself.");
+        }
+
+        match self.replier.followed_at(&msg.chatter_user_id).await {
+            Ok(Some(since)) => format!(
+                "{name} has been following {streamer} for {}.",
+                describe(since, self.clock.now())
+            ),
+            Ok(None) => format!("{name} isn't following {streamer} yet."),
+            Err(e) => {
+                error!(error = ?e, "failed getting follow age");
+                "Sorry, something went wrong looking up your follow age".to_owned()
+            }
+        }
+    }
+
+    /// Create the reply about how old the account of the message's author is.
+    pub async fn account_age(&self, msg: &ChannelChatMessageV1Payload) -> String {
+        let name = &msg.chatter_user_name;
+
+        match self.replier.created_at(&msg.chatter_user_id).await {
+            Ok(Some(since)) => format!(
+                "{name} created their account {} ago.",
+                describe(since, self.clock.now())
+            ),
+            Ok(None) => format!("{name}, I couldn't find your account."),
+            Err(e) => {
+                error!(error = ?e, "failed getting account age");
+                "Sorry, something went wrong looking up your account age".to_owned()
+            }
+        }
+    }
+}
+
+/// Describe the time between the two points in calendar years, months and days, together with the
+/// date of the first one.
+fn describe(since: OffsetDateTime, now: OffsetDateTime) -> String {
+    const FORMAT: &[FormatItem<'static>] = format_description!("[year]-[month]-[day]");
+
+    let (from, to) = (since.date(), now.date());
+    let mut months = (to.year() - from.year()) * 12 + i32::from(u8::from(to.month()))
+        - i32::from(u8::from(from.month()));
+    let days = if to.day() >= from.day() {
+        to.day() - from.day()
+    } else {
+        months -= 1;
+        let previous = to.month().previous();
+        let year = if previous == Month::December {
+            to.year() - 1
+        } else {
+            to.year()
+        };
+        util::days_in_year_month(year, previous).saturating_sub(from.day()) + to.day()
+    };
+
+    let parts = [
+        (months.max(0) / 12, "year"),
+        (months.max(0) % 12, "month"),
+        (i32::from(days), "day"),
+    ]
+    .into_iter()
+    .filter(|(value, _)| *value > 0)
+    .map(|(value, unit)| format!("{value} {unit}{}", if value == 1 { "" } else { "s" }))
+    .collect::<Vec<_>>();
+
+    let age = if parts.is_empty() || since > now {
+        "less than a day".to_owned()
+    } else {
+        parts.join(", ")
+    };
+
+    match from.format(&FORMAT) {
+        Ok(date) => format!("{age} (since {date})"),
+        Err(_) => age,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[test]
+    fn describe_ages() {
+        let now = datetime!(2024-11-02 12:00 UTC);
+
+        assert_eq!(
+            "less than a day (since 2024-11-02)",
+            describe(datetime!(2024-11-02 08:00 UTC), now)
+        );
+        assert_eq!(
+            "1 day (since 2024-11-01)",
+            describe(datetime!(2024-11-01 08:00 UTC), now)
+        );
+        assert_eq!(
+            "2 years, 1 month, 5 days (since 2022-09-28)",
+            describe(datetime!(2022-09-28 12:00 UTC), now)
+        );
+        assert_eq!(
+            "1 year (since 2023-11-02)",
+            describe(datetime!(2023-11-02 20:00 UTC), now)
+        );
+    }
+}
@@ -0,0 +1,74 @@
+//! Interactive OAuth flow, to create the tokens of the bot account.
+
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+};
+
+use anyhow::{bail, Context, Result};
+use reqwest::Url;
+use twitch_api::twitch_oauth2::{Scope, UserToken};
+
+/// Scopes that the bot account needs, to read and write chat messages and to time out users that
+/// lost the `!timeout me` roulette. Subscriptions and cheers can only be read, and polls and
+/// predictions only be started, if the bot uses the streamer's account.
+pub const SCOPES: [Scope; 10] = [
+    Scope::ChannelBot,
+    Scope::UserReadChat,
+    Scope::UserWriteChat,
+    Scope::ModeratorManageBannedUsers,
+    Scope::ModeratorReadFollowers,
+    Scope::ChannelReadSubscriptions,
+    Scope::BitsRead,
+    Scope::ChannelManagePolls,
+    Scope::ChannelManagePredictions,
+    Scope::ModeratorReadChatters,
+];
+
+/// URL that the Twitch application must have registered for redirects.
+pub const REDIRECT_URL: &str = "http://localhost";
+
+/// Run the authorization code flow on the terminal.
+///
+/// The user visits the printed page, authorizes the application and then pastes the URL that
+/// Twitch redirected to, which contains the code to get the tokens.
+pub async fn authorize(client_id: String, client_secret: String) -> Result<UserToken> {
+    let mut builder = UserToken::builder(
+        client_id.into(),
+        client_secret.into(),
+        REDIRECT_URL.parse()?,
+    )
+    .force_verify(true)
+    .set_scopes(SCOPES.to_vec());
+
+    let (url, _) = builder.generate_url();
+    println!("visit this page and log in with the bot account: {url}\n");
+
+    let url = prompt("paste the URL you got redirected to:")?;
+    let url = Url::parse(&url).context("invalid URL")?;
+    let pairs = url.query_pairs().collect::<HashMap<_, _>>();
+
+    if let Some((state, code)) = pairs.get("state").zip(pairs.get("code")) {
+        builder
+            .get_user_token(&reqwest::Client::new(), state, code)
+            .await
+            .context("failed getting user token")
+    } else if let Some((error, description)) =
+        pairs.get("error").zip(pairs.get("error_description"))
+    {
+        bail!("got error from twitch:\n{error}: {description}");
+    } else {
+        bail!("invalid URL, it neither contains a code nor an error");
+    }
+}
+
+/// Ask the user for a single line of input on the terminal.
+pub fn prompt(question: &str) -> Result<String> {
+    println!("{question}");
+    io::stdout().flush()?;
+
+    let mut line = String::new();
+    io::stdin().read_line(&mut line)?;
+
+    Ok(line.trim().to_owned())
+}
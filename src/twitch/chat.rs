@@ -0,0 +1,285 @@
+//! Tracking of the Twitch chat settings, and delivery of replies that adapts to restricted chat
+//! modes like slow mode or emote-only mode.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::{anyhow, Result};
+use tokio::{
+    select,
+    sync::{mpsc, watch},
+    time::{self, Instant},
+};
+use tokio_shutdown::Shutdown;
+use tracing::{debug, error, info, warn};
+use twitch_api::types::MsgId;
+
+use super::eventsub::Replier;
+
+/// Interval in which the chat settings are refreshed.
+const POLL_INTERVAL: Duration = Duration::from_mins(1);
+/// Maximum length of a single chat message in Twitch.
+const MAX_MESSAGE_LEN: usize = 500;
+/// Separator between individual answers, when several of them are combined into one message.
+const SEPARATOR: &str = " | ";
+
+/// Restrictions of the Twitch chat that influence how the bot replies.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ChatMode {
+    /// Minimum time between two messages of the same user, if slow mode is active.
+    pub slow_mode: Option<Duration>,
+    /// Whether chat messages may only contain emotes.
+    pub emote_only: bool,
+}
+
+impl ChatMode {
+    /// Whether any restriction is active, in which case non-essential replies are suppressed.
+    pub fn is_restricted(self) -> bool {
+        self.slow_mode.is_some() || self.emote_only
+    }
+
+    /// Short explanation of the current restrictions and how the bot adapts to them.
+    pub fn note(self) -> Option<String> {
+        match (self.slow_mode, self.emote_only) {
+            (Some(wait), true) => Some(format!(
+                "Chat is in emote-only and slow mode ({}s), so I only answer important commands \
+                 and combine my replies.",
+                wait.as_secs()
+            )),
+            (Some(wait), false) => Some(format!(
+                "Chat is in slow mode ({}s), so I only answer important commands and combine my \
+                 replies.",
+                wait.as_secs()
+            )),
+            (None, true) => {
+                Some("Chat is in emote-only mode, so I only answer important commands.".to_owned())
+            }
+            (None, false) => None,
+        }
+    }
+}
+
+struct Reply {
+    /// Message of the command that this replies to, with the name of the command.
+    reply_to: Option<(MsgId, String)>,
+    content: String,
+}
+
+/// Queue for outgoing chat replies, that are delivered in the background according to the current
+/// chat mode.
+pub struct Outbox {
+    tx: mpsc::Sender<Reply>,
+    mode: watch::Receiver<ChatMode>,
+}
+
+impl Outbox {
+    /// Start tracking the chat settings and delivering replies in the background.
+    pub fn spawn(replier: Replier, shutdown: Shutdown) -> Self {
+        let replier = Arc::new(replier);
+        let (mode_tx, mode_rx) = watch::channel(ChatMode::default());
+        let (tx, rx) = mpsc::channel(32);
+
+        tokio::spawn(poll(Arc::clone(&replier), mode_tx, shutdown));
+        tokio::spawn(deliver(replier, rx, mode_rx.clone()));
+
+        Self { tx, mode: mode_rx }
+    }
+
+    /// The last known chat mode.
+    pub fn mode(&self) -> ChatMode {
+        *self.mode.borrow()
+    }
+
+    /// Queue a reply to the given chat message, which contained the named command.
+    pub async fn send(&self, msg_id: &MsgId, command: &str, content: String) -> Result<()> {
+        self.tx
+            .send(Reply {
+                reply_to: Some((msg_id.clone(), command.to_owned())),
+                content,
+            })
+            .await
+            .map_err(|_| anyhow!("reply delivery stopped"))
+    }
+
+    /// Queue a message that isn't a reply to any other chat message.
+    pub async fn post(&self, content: String) -> Result<()> {
+        self.tx
+            .send(Reply {
+                reply_to: None,
+                content,
+            })
+            .await
+            .map_err(|_| anyhow!("reply delivery stopped"))
+    }
+}
+
+async fn poll(replier: Arc<Replier>, mode: watch::Sender<ChatMode>, shutdown: Shutdown) {
+    let mut interval = time::interval(POLL_INTERVAL);
+
+    loop {
+        select! {
+            () = shutdown.handle() => break,
+            _ = interval.tick() => {}
+        }
+
+        match replier.chat_mode().await {
+            Ok(new) => {
+                mode.send_if_modified(|current| {
+                    if *current == new {
+                        return false;
+                    }
+
+                    info!(?new, "chat mode changed");
+                    *current = new;
+                    true
+                });
+            }
+            Err(e) => warn!(error = ?e, "failed getting chat settings"),
+        }
+    }
+}
+
+async fn deliver(
+    client: Arc<Replier>,
+    mut rx: mpsc::Receiver<Reply>,
+    mode: watch::Receiver<ChatMode>,
+) {
+    let mut last_sent = None::<Instant>;
+
+    while let Some(reply) = rx.recv().await {
+        let mut replies = vec![reply];
+        let slow_mode = mode.borrow().slow_mode;
+
+        if let Some(wait) = slow_mode {
+            if let Some(last_sent) = last_sent {
+                time::sleep_until(last_sent + wait).await;
+            }
+
+            // Collect everything that queued up in the meantime, to answer it all at once.
+            while let Ok(reply) = rx.try_recv() {
+                replies.push(reply);
+            }
+
+            if replies.len() > 1 {
+                debug!(count = replies.len(), "combining replies due to slow mode");
+            }
+        }
+
+        for (i, reply) in combine(replies).into_iter().enumerate() {
+            if let (Some(wait), true) = (slow_mode, i > 0) {
+                time::sleep(wait).await;
+            }
+
+            if let Err(e) = client
+                .send_chat_message(
+                    reply
+                        .reply_to
+                        .as_ref()
+                        .map(|(msg_id, command)| (msg_id, command.as_str())),
+                    reply.content,
+                )
+                .await
+            {
+                error!(error = ?e, "failed sending reply");
+            }
+
+            last_sent = Some(Instant::now());
+        }
+    }
+}
+
+/// Merge several replies into as few messages as possible, without exceeding the maximum message
+/// length. Only consecutive replies to the same chat message (or posts that don't reply to any) are
+/// merged, so that every answer stays threaded under the message that asked for it.
+fn combine(replies: Vec<Reply>) -> Vec<Reply> {
+    let mut combined = Vec::<Reply>::new();
+
+    for reply in replies {
+        match combined.last_mut() {
+            Some(last)
+                if last.reply_to == reply.reply_to
+                    && last.content.len() + SEPARATOR.len() + reply.content.len()
+                        <= MAX_MESSAGE_LEN =>
+            {
+                last.content.push_str(SEPARATOR);
+                last.content.push_str(&reply.content);
+            }
+            _ => combined.push(reply),
+        }
+    }
+
+    combined
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use super::*;
+
+    fn reply(id: &str, content: &str) -> Reply {
+        Reply {
+            reply_to: Some((id.into(), "help".to_owned())),
+            content: content.to_owned(),
+        }
+    }
+
+    #[test]
+    fn combine_replies() {
+        let combined = combine(vec![
+            reply("1", "a"),
+            reply("1", "b"),
+            reply("1", &"c".repeat(MAX_MESSAGE_LEN - 2)),
+        ]);
+
+        assert_eq!(2, combined.len());
+        assert_eq!("1", combined[0].reply_to.as_ref().unwrap().0.as_str());
+        assert_eq!("a | b", combined[0].content);
+        assert_eq!("1", combined[1].reply_to.as_ref().unwrap().0.as_str());
+    }
+
+    #[test]
+    fn combine_per_target() {
+        let post = |content: &str| Reply {
+            reply_to: None,
+            content: content.to_owned(),
+        };
+        let combined = combine(vec![
+            reply("1", "a"),
+            reply("2", "b"),
+            reply("2", "c"),
+            post("d"),
+            post("e"),
+        ]);
+
+        let combined = combined
+            .iter()
+            .map(|reply| {
+                (
+                    reply.reply_to.as_ref().map(|(id, _)| id.as_str()),
+                    reply.content.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![(Some("1"), "a"), (Some("2"), "b | c"), (None, "d | e")],
+            combined
+        );
+    }
+
+    #[test]
+    fn restricted() {
+        assert!(!ChatMode::default().is_restricted());
+        assert!(ChatMode::default().note().is_none());
+
+        let mode = ChatMode {
+            slow_mode: Some(Duration::from_secs(30)),
+            emote_only: false,
+        };
+        assert!(mode.is_restricted());
+        assert_eq!(
+            "Chat is in slow mode (30s), so I only answer important commands and combine my \
+             replies.",
+            mode.note().unwrap()
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use std::{sync::Arc, time::Duration};
 
+use ::time::{format_description::well_known::Rfc3339, OffsetDateTime};
 use anyhow::{bail, ensure, Context, Result};
 use futures_util::{SinkExt, StreamExt, TryStreamExt};
 use tokio::{
@@ -14,18 +15,33 @@ use tokio_tungstenite::{
 use tracing::{error, info, trace, warn};
 use twitch_api::{
     eventsub::{
-        channel::{ChannelChatMessageV1, ChannelChatMessageV1Payload},
+        channel::{
+            ChannelChatMessageV1, ChannelChatMessageV1Payload, ChannelCheerV1, ChannelFollowV2,
+            ChannelPollEndV1, ChannelRaidV1, ChannelSubscribeV1, ChannelUpdateV2,
+        },
         stream::{StreamOfflineV1, StreamOnlineV1},
         Event, EventType, EventsubWebsocketData, Message, Payload, ReconnectPayload, SessionData,
         Transport, WelcomePayload,
     },
-    helix::chat::{SendChatMessageBody, SendChatMessageRequest},
+    helix::{
+        channels::GetChannelFollowersRequest,
+        chat::{SendChatMessageBody, SendChatMessageRequest},
+        polls::{CreatePollBody, CreatePollRequest, NewPollChoice},
+        predictions::create_prediction::{
+            CreatePredictionBody, CreatePredictionRequest, NewPredictionOutcome,
+        },
+    },
     twitch_oauth2::{client::Client as Oauth2Client, TwitchToken, UserToken},
-    types::{MsgId, UserId},
+    types::{MsgId, PollStatus, SubscriptionTier, UserId},
     HelixClient,
 };
 
-use crate::twitch::StreamInfo;
+use crate::{
+    api::{Event as BotEvent, Events, Poll, PollKind, Source, StreamInfo, UserRef, Viewer},
+    health::Health,
+    settings::Replies as RepliesSettings,
+    twitch::{chat::ChatMode, stream_info, TokenStore},
+};
 
 type WebSocketStream = tokio_tungstenite::WebSocketStream<MaybeTlsStream<TcpStream>>;
 
@@ -37,13 +53,20 @@ pub struct EventSubClient {
     token: Token,
     connect_url: Uri,
     connection: WebSocketStream,
+    events: Events,
+    health: Health,
+    replies: Arc<RepliesSettings>,
 }
 
 impl EventSubClient {
     pub async fn new(
         client: HelixClient<'static, reqwest::Client>,
         token: UserToken,
+        store: Option<TokenStore>,
         streamer_id: UserId,
+        events: Events,
+        health: Health,
+        replies: RepliesSettings,
     ) -> Result<Self> {
         let url = Uri::from_static(twitch_api::TWITCH_EVENTSUB_WEBSOCKET_URL.as_str());
         let connection = Self::connect(&url).await?;
@@ -53,9 +76,12 @@ impl EventSubClient {
             streamer_id,
             user_id: token.user_id.clone(),
             client,
-            token: Token::new(token),
+            token: Token::new(token, store),
             connect_url: url,
             connection,
+            events,
+            health,
+            replies: Arc::new(replies),
         })
     }
 
@@ -65,6 +91,7 @@ impl EventSubClient {
             user_id: self.user_id.clone(),
             client: self.client.clone(),
             token: self.token.clone(),
+            replies: Arc::clone(&self.replies),
         }
     }
 
@@ -118,6 +145,8 @@ impl EventSubClient {
                 Ok(message) => message,
             };
 
+            self.health.beat(Source::Twitch);
+
             if let Err(err) = self.process_websocket_message(message, tx.clone()).await {
                 error!(?err, "failed processing message");
             }
@@ -132,10 +161,10 @@ impl EventSubClient {
         tx: mpsc::Sender<ChannelChatMessageV1Payload>,
     ) -> Result<()> {
         match msg {
-            tungstenite::Message::Text(text) => self
-                .process_eventsub_message(Event::parse_websocket(&text)?, tx)
-                .await
-                .map_err(Into::into),
+            tungstenite::Message::Text(text) => {
+                self.process_eventsub_message(Event::parse_websocket(&text)?, tx)
+                    .await
+            }
             tungstenite::Message::Ping(msg) => self
                 .connection
                 .send(tungstenite::Message::Pong(msg))
@@ -165,14 +194,12 @@ impl EventSubClient {
             | EventsubWebsocketData::Reconnect {
                 payload: ReconnectPayload { session },
                 ..
-            } => self
-                .process_welcome_message(session)
-                .await
-                .map_err(Into::into),
-            EventsubWebsocketData::Notification { payload, .. } => self
-                .process_notification_message(payload, tx)
-                .await
-                .map_err(Into::into),
+            } => self.process_welcome_message(session).await,
+            #[cfg(feature = "chaos")]
+            EventsubWebsocketData::Notification { .. } if crate::chaos::drop_event() => Ok(()),
+            EventsubWebsocketData::Notification { payload, .. } => {
+                self.process_notification_message(payload, tx).await
+            }
             EventsubWebsocketData::Revocation { metadata, payload } => {
                 warn!(?metadata, ?payload, "received revocation");
                 Ok(())
@@ -201,10 +228,10 @@ impl EventSubClient {
                         .await?
                         .ok()?;
 
-                    StreamInfo::try_from(stream).ok()
+                    stream_info(stream).ok()
                 };
 
-                if let Some(info) = get_info().await {
+                let info = if let Some(info) = get_info().await {
                     info!(
                         info.id,
                         %info.started_at,
@@ -212,33 +239,154 @@ impl EventSubClient {
                         info.category,
                         "streamer started streaming",
                     );
+                    info
                 } else {
                     info!(
                         info.id = message.id,
                         info.started_at = %message.started_at,
                         "streamer started streaming",
                     );
-                }
+                    StreamInfo {
+                        id: message.id.clone(),
+                        started_at: OffsetDateTime::parse(message.started_at.as_str(), &Rfc3339)
+                            .unwrap_or_else(|_| OffsetDateTime::now_utc()),
+                        title: String::new(),
+                        category: String::new(),
+                    }
+                };
+
+                self.events.send(BotEvent::StreamOnline(info)).await.ok();
             }
             Event::StreamOfflineV1(Payload {
                 message: Message::Notification(_),
                 ..
             }) => {
                 info!("streamer stopped streaming");
+                self.events.send(BotEvent::StreamOffline).await.ok();
+            }
+            Event::ChannelUpdateV2(Payload {
+                message: Message::Notification(message),
+                ..
+            }) => {
+                info!(
+                    message.title,
+                    message.category_name, "streamer changed the title"
+                );
+                self.events
+                    .send(BotEvent::ChannelUpdate {
+                        title: message.title,
+                        category: message.category_name,
+                    })
+                    .await
+                    .ok();
             }
             Event::ChannelChatMessageV1(Payload {
                 message: Message::Notification(message),
                 ..
+            }) if message.chatter_user_id != self.user_id => {
+                tx.send(message).await.ok();
+            }
+            Event::ChannelFollowV2(Payload {
+                message: Message::Notification(message),
+                ..
             }) => {
-                if message.chatter_user_id != self.user_id {
-                    tx.send(message).await.ok();
-                }
+                info!(user = %message.user_login, "new follower");
+                self.events
+                    .send(BotEvent::Follow {
+                        source: Source::Twitch,
+                        user: message.user_name.to_string(),
+                    })
+                    .await
+                    .ok();
             }
-            _ => {}
+            event => self.process_community_event(event).await,
         }
         Ok(())
     }
 
+    /// Handle events where other users support the channel, like raids, subscriptions and cheers.
+    async fn process_community_event(&self, event: Event) {
+        match event {
+            Event::ChannelRaidV1(Payload {
+                message: Message::Notification(message),
+                ..
+            }) => {
+                self.events
+                    .send(BotEvent::Raid {
+                        source: Source::Twitch,
+                        user: message.from_broadcaster_user_name.to_string(),
+                        login: message.from_broadcaster_user_login.to_string(),
+                        viewers: message.viewers.try_into().unwrap_or_default(),
+                        category: self.last_category(&message.from_broadcaster_user_id).await,
+                    })
+                    .await
+                    .ok();
+            }
+            Event::ChannelSubscribeV1(Payload {
+                message: Message::Notification(message),
+                ..
+            }) => {
+                info!(user = %message.user_login, gift = message.is_gift, "new subscriber");
+                self.events
+                    .send(BotEvent::Subscribe {
+                        source: Source::Twitch,
+                        user: message.user_name.to_string(),
+                        tier: tier_name(&message.tier).to_owned(),
+                    })
+                    .await
+                    .ok();
+            }
+            Event::ChannelCheerV1(Payload {
+                message: Message::Notification(message),
+                ..
+            }) => {
+                info!(user = ?message.user_login, message.bits, "new cheer");
+                self.events
+                    .send(BotEvent::Cheer {
+                        source: Source::Twitch,
+                        user: message.user_name.map(|name| name.to_string()),
+                        bits: message.bits.try_into().unwrap_or_default(),
+                    })
+                    .await
+                    .ok();
+            }
+            Event::ChannelPollEndV1(Payload {
+                message: Message::Notification(message),
+                ..
+            }) if message.status == PollStatus::Completed => {
+                info!(poll = %message.id, "poll ended");
+                self.events
+                    .send(BotEvent::PollEnded {
+                        source: Source::Twitch,
+                        question: message.title,
+                        results: message
+                            .choices
+                            .into_iter()
+                            .map(|choice| {
+                                let votes = choice.votes.unwrap_or_default();
+                                (choice.title, votes.try_into().unwrap_or_default())
+                            })
+                            .collect(),
+                    })
+                    .await
+                    .ok();
+            }
+            _ => {}
+        }
+    }
+
+    /// Category that the channel streamed last, if it's known.
+    async fn last_category(&self, channel: &UserId) -> Option<String> {
+        let token = self.token.get(&self.client).await.ok()?;
+        let channel = self
+            .client
+            .get_channel_from_id(channel, &*token)
+            .await
+            .ok()??;
+
+        Some(channel.game_name.take()).filter(|name| !name.is_empty())
+    }
+
     async fn process_welcome_message(&mut self, data: SessionData<'_>) -> Result<()> {
         self.session_id = Some(data.id.to_string());
 
@@ -290,18 +438,120 @@ impl EventSubClient {
                 .await?;
         }
 
+        if !subs.contains(&EventType::ChannelUpdate) {
+            self.client
+                .create_eventsub_subscription(
+                    ChannelUpdateV2::broadcaster_user_id(self.streamer_id.clone()),
+                    transport.clone(),
+                    &*token,
+                )
+                .await?;
+        }
+
         if !subs.contains(&EventType::ChannelChatMessage) {
             self.client
                 .create_eventsub_subscription(
                     ChannelChatMessageV1::new(self.streamer_id.clone(), self.user_id.clone()),
-                    transport,
+                    transport.clone(),
                     &*token,
                 )
                 .await?;
         }
 
+        // Follows are only visible to moderators, so the bot still works without them in case it
+        // isn't a moderator of the channel.
+        if !subs.contains(&EventType::ChannelFollow) {
+            if let Err(e) = self
+                .client
+                .create_eventsub_subscription(
+                    ChannelFollowV2::new(self.streamer_id.clone(), self.user_id.clone()),
+                    transport.clone(),
+                    &*token,
+                )
+                .await
+            {
+                warn!(error = ?e, "failed subscribing to follows, is the bot a moderator?");
+            }
+        }
+
+        if !subs.contains(&EventType::ChannelRaid) {
+            self.client
+                .create_eventsub_subscription(
+                    ChannelRaidV1::to_broadcaster_user_id(self.streamer_id.clone()),
+                    transport.clone(),
+                    &*token,
+                )
+                .await?;
+        }
+
+        // Subscriptions, cheers and polls are only visible to the broadcaster.
+        self.subscribe_broadcaster_events(&subs, transport, &token)
+            .await;
+
         Ok(())
     }
+
+    /// Subscribe to the events that are only visible to the broadcaster, which only succeeds if
+    /// the bot runs with the streamer's account. The bot keeps working without them.
+    async fn subscribe_broadcaster_events(
+        &self,
+        subs: &[EventType],
+        transport: Transport,
+        token: &UserToken,
+    ) {
+        if !subs.contains(&EventType::ChannelSubscribe) {
+            if let Err(e) = self
+                .client
+                .create_eventsub_subscription(
+                    ChannelSubscribeV1::broadcaster_user_id(self.streamer_id.clone()),
+                    transport.clone(),
+                    token,
+                )
+                .await
+            {
+                warn!(error = ?e, "failed subscribing to subscriptions, is the bot the streamer?");
+            }
+        }
+
+        if !subs.contains(&EventType::ChannelCheer) {
+            if let Err(e) = self
+                .client
+                .create_eventsub_subscription(
+                    ChannelCheerV1::broadcaster_user_id(self.streamer_id.clone()),
+                    transport.clone(),
+                    token,
+                )
+                .await
+            {
+                warn!(error = ?e, "failed subscribing to cheers, is the bot the streamer?");
+            }
+        }
+
+        if !subs.contains(&EventType::ChannelPollEnd) {
+            if let Err(e) = self
+                .client
+                .create_eventsub_subscription(
+                    ChannelPollEndV1::broadcaster_user_id(self.streamer_id.clone()),
+                    transport,
+                    token,
+                )
+                .await
+            {
+                warn!(error = ?e, "failed subscribing to polls, is the bot the streamer?");
+            }
+        }
+    }
+}
+
+/// Short name of a subscription tier, as shown in chat.
+fn tier_name(tier: &SubscriptionTier) -> &str {
+    match tier {
+        SubscriptionTier::Tier1 => "1",
+        SubscriptionTier::Tier2 => "2",
+        SubscriptionTier::Tier3 => "3",
+        SubscriptionTier::Prime => "Prime",
+        SubscriptionTier::Other(other) => other,
+    }
 }
 
 pub struct Replier {
@@ -309,42 +559,192 @@ pub struct Replier {
     user_id: UserId,
     client: HelixClient<'static, reqwest::Client>,
     token: Token,
+    replies: Arc<RepliesSettings>,
 }
 
 impl Replier {
-    pub async fn send_chat_message(&self, msg_id: &MsgId, content: String) -> Result<()> {
+    /// Send a message to the chat. Replies to a command, given by the message ID and the name of
+    /// the command, are threaded under that message, unless the settings turn it off.
+    pub async fn send_chat_message(
+        &self,
+        reply_to: Option<(&MsgId, &str)>,
+        content: String,
+    ) -> Result<()> {
+        #[cfg(feature = "chaos")]
+        crate::chaos::send()?;
+
+        let mut body = SendChatMessageBody::new(&self.streamer_id, &self.user_id, content);
+        if let Some(msg_id) = self.parent(reply_to) {
+            body = body.reply_parent_message_id(msg_id);
+        }
+
         let token = self.token.get(&self.client).await?;
         let resp = self
             .client
-            .req_post(
-                SendChatMessageRequest::new(),
-                SendChatMessageBody::new(&self.streamer_id, &self.user_id, content)
-                    .reply_parent_message_id(msg_id),
+            .req_post(SendChatMessageRequest::new(), body, &*token)
+            .await?;
+
+        ensure!(resp.data.is_sent, "message wasn't sent");
+
+        Ok(())
+    }
+
+    /// Message that a reply is threaded under, if any.
+    fn parent<'a>(&self, reply_to: Option<(&'a MsgId, &str)>) -> Option<&'a MsgId> {
+        reply_to
+            .filter(|(_, command)| self.replies.threaded(command))
+            .map(|(msg_id, _)| msg_id)
+    }
+
+    pub async fn timeout(&self, target: &UserId, duration: u32, reason: &str) -> Result<()> {
+        let token = self.token.get(&self.client).await?;
+        self.client
+            .ban_user(
+                target,
+                reason,
+                duration,
+                &self.streamer_id,
+                &self.user_id,
                 &*token,
             )
             .await?;
 
-        ensure!(resp.data.is_sent, "message wasn't sent");
+        Ok(())
+    }
+
+    /// Time when the user started following the channel, if they follow it at all.
+    pub async fn followed_at(&self, user: &UserId) -> Result<Option<OffsetDateTime>> {
+        let token = self.token.get(&self.client).await?;
+        let resp = self
+            .client
+            .req_get(
+                GetChannelFollowersRequest::broadcaster_id(&self.streamer_id).user_id(user),
+                &*token,
+            )
+            .await?;
+
+        resp.data
+            .into_iter()
+            .next()
+            .map(|follower| OffsetDateTime::parse(follower.followed_at.as_str(), &Rfc3339))
+            .transpose()
+            .context("invalid follow time")
+    }
+
+    /// Time when the user created their account.
+    pub async fn created_at(&self, user: &UserId) -> Result<Option<OffsetDateTime>> {
+        let token = self.token.get(&self.client).await?;
+
+        self.client
+            .get_user_from_id(user, &*token)
+            .await?
+            .map(|user| OffsetDateTime::parse(user.created_at.as_str(), &Rfc3339))
+            .transpose()
+            .context("invalid account creation time")
+    }
+
+    /// Start a poll or prediction in the streamer's channel.
+    pub async fn create_poll(&self, poll: &Poll) -> Result<()> {
+        let token = self.token.get(&self.client).await?;
+        let duration = poll.duration.as_secs().try_into()?;
+
+        match poll.kind {
+            PollKind::Poll => {
+                let choices = poll
+                    .options
+                    .iter()
+                    .map(|option| NewPollChoice::new(option.as_str()))
+                    .collect::<Vec<_>>();
+                let body = CreatePollBody::new(
+                    &self.streamer_id,
+                    poll.question.as_str(),
+                    duration,
+                    choices,
+                );
+                self.client
+                    .req_post(CreatePollRequest::new(), body, &*token)
+                    .await?;
+            }
+            PollKind::Prediction => {
+                let outcomes = poll
+                    .options
+                    .iter()
+                    .map(|option| NewPredictionOutcome::new(option.as_str()))
+                    .collect::<Vec<_>>();
+                let body = CreatePredictionBody::new(
+                    &self.streamer_id,
+                    poll.question.as_str(),
+                    &outcomes,
+                    duration,
+                );
+                self.client
+                    .req_post(CreatePredictionRequest::new(), body, &*token)
+                    .await?;
+            }
+        }
 
         Ok(())
     }
+
+    /// Users that are currently in the chat, without the streamer and the bot itself.
+    pub async fn chatters(&self) -> Result<Vec<Viewer>> {
+        let token = self.token.get(&self.client).await?;
+        let chatters = self
+            .client
+            .get_chatters(&self.streamer_id, &self.user_id, 1000, &*token)
+            .try_collect::<Vec<_>>()
+            .await?;
+
+        Ok(chatters
+            .into_iter()
+            .filter(|chatter| {
+                chatter.user_id != self.streamer_id && chatter.user_id != self.user_id
+            })
+            .map(|chatter| Viewer {
+                user: UserRef {
+                    source: Source::Twitch,
+                    id: chatter.user_id.take(),
+                },
+                name: chatter.user_name.take(),
+            })
+            .collect())
+    }
+
+    pub async fn chat_mode(&self) -> Result<ChatMode> {
+        let token = self.token.get(&self.client).await?;
+        let settings = self
+            .client
+            .get_chat_settings(&self.streamer_id, None, &*token)
+            .await?;
+
+        Ok(ChatMode {
+            slow_mode: settings
+                .slow_mode
+                .then(|| Duration::from_secs(settings.slow_mode_wait_time.unwrap_or_default())),
+            emote_only: settings.emote_mode,
+        })
+    }
 }
 
 #[derive(Clone)]
-struct Token(Arc<Mutex<UserToken>>);
+struct Token(Arc<Mutex<UserToken>>, Option<TokenStore>);
 
 impl Token {
-    fn new(token: UserToken) -> Self {
-        Self(Arc::new(Mutex::new(token)))
+    fn new(token: UserToken, store: Option<TokenStore>) -> Self {
+        Self(Arc::new(Mutex::new(token)), store)
     }
 
     async fn get(&self, client: &impl Oauth2Client) -> Result<MutexGuard<'_, UserToken>> {
         let mut token = self.0.lock().await;
-        if token.expires_in() < Duration::from_secs(120) {
+        if token.expires_in() < Duration::from_mins(2) {
             token
                 .refresh_token(client)
                 .await
                 .context("failed refreshing expired user token")?;
+
+            if let Some(store) = &self.1 {
+                store.save(&token);
+            }
         }
 
         Ok(token)
@@ -1,319 +1,461 @@
 //! Twitch service connector that allows to receive commands from Twitch channels.
 
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+    time::Instant,
+};
 
-use anyhow::{Context, Result};
-use futures_util::StreamExt;
+use anyhow::{anyhow, Context, Result};
+use futures_util::{future::BoxFuture, StreamExt};
 use time::{format_description::well_known::Rfc3339, OffsetDateTime};
-use tokio::{select, sync::oneshot};
-use tokio_shutdown::Shutdown;
-use tracing::{error, info, info_span, instrument, Instrument, Span};
+use tokio::{select, sync::mpsc, task::JoinHandle};
+use tracing::{debug, error, info, info_span, instrument, Instrument, Span};
 use twitch_api::{
     eventsub::channel::ChannelChatMessageV1Payload,
     helix,
     twitch_oauth2::{
         client::Client as Oauth2Client, tokens::errors::ValidationError, RefreshToken, UserToken,
     },
-    types::MsgId,
     HelixClient,
 };
 
-use self::eventsub::{EventSubClient, Replier};
+pub use self::tokens::TokenStore;
+use self::{
+    age::Ages,
+    chat::Outbox,
+    eventsub::{EventSubClient, Replier},
+    moderation::Moderation,
+    roulette::Roulette,
+};
 use crate::{
     api::{
-        response::{self, CrateSearch, Response},
-        AuthorId, Message, Queue, Source,
+        request::{self, Request},
+        response::{self, Response},
+        Author, AuthorId, Event, Events, Message, Poll, Post, Queue, Source, StreamInfo,
     },
-    settings::{Commands as CommandSettings, Twitch as TwitchSettings},
+    connector::{self, Connector, Context as ConnectorContext},
+    render,
+    settings::Twitch as TwitchSettings,
     textparse,
+    timing::Timings,
 };
 
+mod age;
+pub mod auth;
+mod chat;
 mod eventsub;
-
-#[derive(Debug)]
-struct StreamInfo {
-    id: String,
-    started_at: OffsetDateTime,
-    title: String,
-    category: String,
+mod moderation;
+mod roulette;
+mod tokens;
+mod watch_time;
+
+fn stream_info(value: helix::streams::Stream) -> Result<StreamInfo> {
+    Ok(StreamInfo {
+        id: value.id.take(),
+        started_at: OffsetDateTime::parse(value.started_at.as_str(), &Rfc3339)
+            .context("invalid stream start time")?,
+        title: value.title,
+        category: value.game_name,
+    })
 }
 
-impl TryFrom<helix::streams::Stream> for StreamInfo {
-    type Error = anyhow::Error;
+/// Connector for Twitch, that receives chat messages through `EventSub` and replies in the
+/// streamer's chat.
+pub struct Twitch {
+    config: TwitchSettings,
+    tokens: Option<TokenStore>,
+    outbox: OnceLock<Arc<Outbox>>,
+    polls: OnceLock<Replier>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
 
-    fn try_from(value: helix::streams::Stream) -> std::result::Result<Self, Self::Error> {
-        Ok(Self {
-            id: value.id.take(),
-            started_at: OffsetDateTime::parse(value.started_at.as_str(), &Rfc3339)
-                .context("invalid stream start time")?,
-            title: value.title,
-            category: value.game_name,
-        })
+impl Twitch {
+    /// Create a new, not yet started, Twitch connector. Refreshed tokens are kept in the store, if
+    /// there is one.
+    #[must_use]
+    pub fn new(config: TwitchSettings, tokens: Option<TokenStore>) -> Self {
+        Self {
+            config,
+            tokens,
+            outbox: OnceLock::new(),
+            polls: OnceLock::new(),
+            tasks: Mutex::default(),
+        }
     }
-}
 
-/// Initialize and run the Twitch connection in a background task.
-///
-/// The given queue is used to transfer received messages for further processing, combined with a
-/// oneshot channel to listen for any possible replies to a message. The shutdown handle is used
-/// to gracefully disconnect from Twitch, before fully quitting the application.
-#[allow(clippy::missing_panics_doc)]
-pub async fn start(
-    config: &TwitchSettings,
-    settings: Arc<CommandSettings>,
-    queue: Queue,
-    shutdown: Shutdown,
-) -> Result<()> {
-    let client = HelixClient::with_client(reqwest::Client::new());
-    let token = create_token(&client, config).await?;
-
-    let streamer_id = client
-        .get_channel_from_login(&settings.streamer, &token)
-        .await?
-        .context("streamer doesn't exist")?
-        .broadcaster_id;
-
-    let stream_info = client
-        .get_streams_from_ids(&[&streamer_id][..].into(), &token)
-        .next()
-        .await
-        .transpose()
-        .context("failed getting stream info")?
-        .map(StreamInfo::try_from)
-        .transpose()
-        .context("failed parsing stream info")?;
-
-    info!(?stream_info);
-
-    let mut sub = EventSubClient::new(client, token, streamer_id).await?;
-    let replier = sub.create_replier();
-
-    let (tx, mut rx) = tokio::sync::mpsc::channel(32);
-    let shutdown2 = shutdown.clone();
-
-    tokio::spawn(async move {
-        loop {
-            select! {
-                () = shutdown.handle() => break,
-                res = sub.start(tx.clone()) => {
-                    if let Err(e) = res {
-                        error!(error = ?e, "failed running twitch client");
+    /// Initialize and run the Twitch connection in a background task.
+    ///
+    /// The context's queue is used to transfer received messages for further processing, combined
+    /// with a oneshot channel to listen for any possible replies to a message. Stream state
+    /// changes are reported through the events queue. Every message received from Twitch is
+    /// reported as heartbeat to the health state.
+    async fn run(&self, ctx: ConnectorContext) -> Result<()> {
+        let ConnectorContext {
+            settings,
+            queue,
+            events,
+            health,
+            timings,
+            clock,
+            shutdown,
+            ..
+        } = ctx;
+
+        let client = HelixClient::with_client(reqwest::Client::new());
+        let token = create_token(&client, &self.config, self.tokens.as_ref()).await?;
+
+        let streamer_id = client
+            .get_channel_from_login(&settings.streamer, &token)
+            .await?
+            .context("streamer doesn't exist")?
+            .broadcaster_id;
+
+        let stream_info = client
+            .get_streams_from_ids(&[&streamer_id][..].into(), &token)
+            .next()
+            .await
+            .transpose()
+            .context("failed getting stream info")?
+            .map(stream_info)
+            .transpose()
+            .context("failed parsing stream info")?;
+
+        info!(?stream_info);
+
+        if let Some(info) = stream_info {
+            events.send(Event::StreamOnline(info)).await.ok();
+        }
+
+        let (chats, samples) = (events.clone(), events.clone());
+        let mut sub = EventSubClient::new(
+            client,
+            token,
+            self.tokens.clone(),
+            streamer_id,
+            events,
+            health,
+            self.config.replies.clone(),
+        )
+        .await?;
+        let outbox = Arc::new(Outbox::spawn(sub.create_replier(), shutdown.clone()));
+        let roulette = self
+            .config
+            .roulette
+            .clone()
+            .map(|config| Roulette::new(config, sub.create_replier()));
+        let ages = Ages::new(sub.create_replier(), clock);
+        let moderation = Moderation::new(sub.create_replier());
+        let sampler_task = self.config.watch_time.as_ref().map(|config| {
+            watch_time::spawn(sub.create_replier(), config, samples, shutdown.clone())
+        });
+
+        self.outbox
+            .set(Arc::clone(&outbox))
+            .map_err(|_| anyhow!("twitch connector already started"))?;
+        self.polls
+            .set(sub.create_replier())
+            .map_err(|_| anyhow!("twitch connector already started"))?;
+
+        let (tx, mut rx) = mpsc::channel(32);
+        let shutdown2 = shutdown.clone();
+
+        let client_task = tokio::spawn(async move {
+            loop {
+                select! {
+                    () = shutdown.handle() => break,
+                    res = sub.start(tx.clone()) => {
+                        if let Err(e) = res {
+                            error!(error = ?e, "failed running twitch client");
+                        }
                     }
                 }
             }
-        }
-    });
-
-    tokio::spawn(async move {
-        loop {
-            select! {
-                () = shutdown2.handle() => break,
-                message = rx.recv() => {
-                    if let Some(message) = message {
-                        handle_message(queue.clone(), message, &replier).await.expect("success");
-                    } else {
-                        break;
+        });
+
+        let message_task = tokio::spawn(async move {
+            loop {
+                select! {
+                    () = shutdown2.handle() => break,
+                    message = rx.recv() => {
+                        if let Some(message) = message {
+                            handle_message(&queue, &chats, &timings, message, &outbox, roulette.as_ref(), &ages, &moderation).await.expect("success");
+                        } else {
+                            break;
+                        }
                     }
                 }
             }
-        }
-    });
+        });
 
-    info!("twitch connection ready, listening for events");
+        self.tasks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .extend([client_task, message_task].into_iter().chain(sampler_task));
 
-    Ok(())
+        info!("twitch connection ready, listening for events");
+
+        Ok(())
+    }
+
+    async fn post(&self, post: Post) -> Result<()> {
+        self.outbox
+            .get()
+            .context("twitch connector not started")?
+            .post(post.content)
+            .await
+    }
+
+    async fn create_poll(&self, poll: Poll) -> Result<()> {
+        self.polls
+            .get()
+            .context("twitch connector not started")?
+            .create_poll(&poll)
+            .await
+    }
 }
 
-async fn create_token(client: &impl Oauth2Client, config: &TwitchSettings) -> Result<UserToken> {
+impl Connector for Twitch {
+    fn source(&self) -> Source {
+        Source::Twitch
+    }
+
+    fn start(&self, ctx: ConnectorContext) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.run(ctx))
+    }
+
+    fn send_reply(&self, post: Post) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.post(post))
+    }
+
+    fn start_poll(&self, poll: Poll) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.create_poll(poll))
+    }
+
+    fn shutdown(&self) -> BoxFuture<'_, ()> {
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap_or_else(PoisonError::into_inner));
+
+        Box::pin(async move {
+            for task in tasks {
+                task.abort();
+            }
+            info!("twitch connection shutting down");
+        })
+    }
+}
+
+/// Validate the latest known tokens, or refresh them if they expired. Stored tokens are preferred
+/// over the ones in the config, as those may have been refreshed already.
+async fn create_token(
+    client: &impl Oauth2Client,
+    config: &TwitchSettings,
+    tokens: Option<&TokenStore>,
+) -> Result<UserToken> {
+    let (access_token, refresh_token) = tokens
+        .and_then(TokenStore::load)
+        .unwrap_or_else(|| (config.access_token.clone(), config.refresh_token.clone()));
+
     let result = UserToken::from_existing(
         client,
-        config.access_token.clone().into(),
-        Some(config.refresh_token.clone().into()),
+        access_token.into(),
+        Some(refresh_token.clone().into()),
         Some(config.client_secret.clone().into()),
     )
     .await;
 
-    match result {
-        Ok(token) => Ok(token),
+    let token = match result {
+        Ok(token) => token,
         Err(ValidationError::NotAuthorized) => {
             // Token expired, use refresh token and try again
             let client_secret = config.client_secret.clone().into();
-            let (access_token, _, refresh_token) = RefreshToken::from(config.refresh_token.clone())
+            let (access_token, _, refresh_token) = RefreshToken::from(refresh_token)
                 .refresh_token(client, &config.client_id.clone().into(), &client_secret)
                 .await?;
 
             UserToken::from_existing(client, access_token, refresh_token, Some(client_secret))
-                .await
-                .map_err(Into::into)
+                .await?
         }
-        Err(err) => Err(err.into()),
+        Err(err) => return Err(err.into()),
+    };
+
+    if let Some(tokens) = tokens {
+        tokens.save(&token);
     }
+
+    Ok(token)
 }
 
+#[allow(clippy::too_many_arguments)]
 #[instrument(skip_all, name = "twitch message", fields(source = %Source::Twitch))]
 async fn handle_message(
-    queue: Queue,
+    queue: &Queue,
+    events: &Events,
+    timings: &Timings,
     msg: ChannelChatMessageV1Payload,
-    client: &Replier,
+    client: &Outbox,
+    roulette: Option<&Roulette>,
+    ages: &Ages,
+    moderation: &Moderation,
 ) -> Result<()> {
-    let Ok(Some(content)) = textparse::parse(&msg.message.text, Source::Twitch, None) else {
-        return Ok(());
+    let received = Instant::now();
+    let author = author(&msg);
+    events
+        .send(Event::Chat {
+            user: author.id.user_ref(),
+            name: msg.chatter_user_login.to_string(),
+        })
+        .await
+        .ok();
+
+    // Messages without any command may still contain trigger words.
+    let content = match textparse::parse(&msg.message.text, Source::Twitch, None) {
+        Ok(Some(content)) => content,
+        Ok(None) => Request::User(request::User::Trigger(msg.message.text.clone())),
+        Err(_) => return Ok(()),
     };
 
-    let response = async {
-        let message = Message {
-            span: Span::current(),
-            source: Source::Twitch,
-            content,
-            author: AuthorId::Twitch(msg.message_id.as_str().to_owned()),
-            mention: None,
-        };
-        let (tx, rx) = oneshot::channel();
-
-        if queue.send((message, tx)).await.is_ok() {
-            Some(rx.await)
-        } else {
-            None
-        }
-    }
-    .instrument(info_span!("handle"))
-    .await;
+    let message = Message {
+        span: Span::current(),
+        source: Source::Twitch,
+        content,
+        author: author.clone(),
+        mention: None,
+        channel: None,
+    };
 
-    if let Some(Ok(resp)) = response {
-        async {
+    let (command, name) = (
+        message.content.metric_name().to_owned(),
+        message.content.name().to_owned(),
+    );
+    if let Some(resp) = connector::dispatch(queue, message).await {
+        let reply = async {
             match resp {
                 Response::User(user_resp) => {
-                    handle_user_message(user_resp, &msg.message_id, client).await
+                    handle_user_message(
+                        user_resp, &msg, &name, &author, client, roulette, ages, moderation,
+                    )
+                    .await
+                }
+                Response::Denied(required) if !client.mode().is_restricted() => {
+                    client
+                        .send(&msg.message_id, &name, render::denied(required))
+                        .await
                 }
-                Response::Admin(_) | Response::Owner(_) => Ok(()),
+                Response::Admin(_)
+                | Response::Owner(_)
+                | Response::Denied(_)
+                | Response::Confirm(_) => Ok(()),
             }
         }
-        .instrument(info_span!("reply"))
-        .await?;
+        .instrument(info_span!("reply"));
+
+        timings.reply(&command, received, reply).await?;
     }
 
     Ok(())
 }
 
-async fn handle_user_message(resp: response::User, msg_id: &MsgId, client: &Replier) -> Result<()> {
-    match resp {
-        response::User::Help => handle_help(msg_id, client).await,
-        response::User::Commands(res) => handle_commands(msg_id, client, res).await,
-        response::User::Links(links) => handle_links(msg_id, client, links).await,
-        response::User::Ban(target) => handle_ban(msg_id, client, target).await,
-        response::User::Crate(res) => handle_crate(msg_id, client, res).await,
-        response::User::Today(text)
-        | response::User::FahrenheitToCelsius(text)
-        | response::User::CelsiusToFahrenheit(text) => {
-            handle_string_reply(msg_id, client, text).await
-        }
-        response::User::Custom(res) => handle_custom_reply(msg_id, client, res).await,
-        response::User::Unknown => Ok(()),
+/// Author of a chat message, with the badges as roles.
+fn author(msg: &ChannelChatMessageV1Payload) -> Author {
+    let mut roles = msg
+        .badges
+        .iter()
+        .map(|badge| badge.set_id.as_str().to_owned())
+        .collect::<Vec<_>>();
+    if msg.chatter_user_id == msg.broadcaster_user_id && !roles.iter().any(|r| r == "broadcaster") {
+        roles.push("broadcaster".to_owned());
     }
-}
-
-async fn handle_help(msg_id: &MsgId, client: &Replier) -> Result<()> {
-    client
-        .send_chat_message(
-            msg_id,
-            "Thanks for asking, I'm a bot to help answer some typical questions. \
-            Try out `!commands` command to see what I can do. \
-            My source code is at https://github.com/dnaka91/togglebot"
-                .to_owned(),
-        )
-        .await?;
 
-    Ok(())
+    Author {
+        id: AuthorId::Twitch(msg.chatter_user_id.as_str().to_owned()),
+        name: msg.chatter_user_name.as_str().to_owned(),
+        subscriber: roles.iter().any(|r| r == "subscriber" || r == "founder"),
+        roles,
+    }
 }
 
-async fn handle_commands(msg_id: &MsgId, client: &Replier, res: Result<Vec<String>>) -> Result<()> {
-    let message = match res {
-        Ok(names) => names.into_iter().fold(
-            String::from(
-                "Available commands: !help (or !bot), !links, !ban, !crate(s), !today, !ftoc, \
-                 !ctof",
-            ),
-            |mut list, name| {
-                list.push_str(", !");
-                list.push_str(&name);
-                list
-            },
-        ),
-        Err(e) => {
-            error!(error = ?e, "failed listing commands");
-            "Sorry, something went wrong fetching the list of commands".to_owned()
-        }
-    };
-
-    client.send_chat_message(msg_id, message).await?;
-
-    Ok(())
+/// Whether the response is important enough to be sent, even if the chat is in a restricted mode.
+fn is_essential(resp: &response::User) -> bool {
+    !matches!(
+        resp,
+        response::User::Ban(_)
+            | response::User::Today(_)
+            | response::User::BotStat(_)
+            | response::User::FahrenheitToCelsius(_)
+            | response::User::CelsiusToFahrenheit(_)
+            | response::User::TimeoutMe
+            | response::User::Gamble(_)
+            | response::User::Duel(_)
+            | response::User::Pronouns(_)
+            | response::User::FollowAge
+            | response::User::AccountAge
+            | response::User::Trigger(_)
+            | response::User::Away { .. }
+            | response::User::Faq(_)
+    )
 }
 
-async fn handle_links(
-    msg_id: &MsgId,
-    client: &Replier,
-    links: Arc<HashMap<String, String>>,
+#[allow(clippy::too_many_arguments)]
+async fn handle_user_message(
+    resp: response::User,
+    msg: &ChannelChatMessageV1Payload,
+    command: &str,
+    author: &Author,
+    client: &Outbox,
+    roulette: Option<&Roulette>,
+    ages: &Ages,
+    moderation: &Moderation,
 ) -> Result<()> {
-    client
-        .send_chat_message(
-            msg_id,
-            links
-                .iter()
-                .enumerate()
-                .fold(String::new(), |mut list, (i, (name, url))| {
-                    if i > 0 {
-                        list.push_str(" | ");
-                    }
-
-                    list.push_str(name);
-                    list.push_str(": ");
-                    list.push_str(url);
-                    list
-                }),
-        )
-        .await?;
-
-    Ok(())
-}
-
-async fn handle_ban(msg_id: &MsgId, client: &Replier, target: String) -> Result<()> {
-    client
-        .send_chat_message(msg_id, format!("{target}, YOU SHALL NOT PASS!!"))
-        .await?;
-
-    Ok(())
-}
+    if client.mode().is_restricted() && !is_essential(&resp) {
+        debug!("suppressing non-essential reply in restricted chat mode");
+        return Ok(());
+    }
 
-async fn handle_crate(msg_id: &MsgId, client: &Replier, res: Result<CrateSearch>) -> Result<()> {
-    let message = match res {
-        Ok(search) => match search {
-            CrateSearch::Found(info) => format!("https://crates.io/crates/{}", info.name),
-            CrateSearch::NotFound(message) => message,
-        },
-        Err(e) => {
-            error!(error = ?e, "failed searching for crate");
-            "Sorry, something went wrong looking up the crate".to_owned()
+    match resp {
+        response::User::TimeoutMe => {
+            let Some(roulette) = roulette else {
+                return Ok(());
+            };
+
+            return client
+                .send(
+                    &msg.message_id,
+                    command,
+                    roulette.play(msg, author.is_moderator()).await,
+                )
+                .await;
         }
-    };
+        response::User::FollowAge => {
+            return client
+                .send(&msg.message_id, command, ages.follow_age(msg).await)
+                .await;
+        }
+        response::User::AccountAge => {
+            return client
+                .send(&msg.message_id, command, ages.account_age(msg).await)
+                .await;
+        }
+        response::User::TimeoutSpammer(duration) => {
+            return match moderation.timeout_spammer(msg, duration).await {
+                Some(message) => client.send(&msg.message_id, command, message).await,
+                None => Ok(()),
+            };
+        }
+        _ => {}
+    }
 
-    client.send_chat_message(msg_id, message).await?;
+    let status = matches!(resp, response::User::Status(_));
+    let Some(mut message) = render::twitch::user(resp) else {
+        return Ok(());
+    };
 
-    Ok(())
-}
+    if status {
+        if let Some(note) = client.mode().note() {
+            message.push(' ');
+            message.push_str(&note);
+        }
+    }
 
-async fn handle_string_reply(msg_id: &MsgId, client: &Replier, content: String) -> Result<()> {
-    client.send_chat_message(msg_id, content).await?;
+    client.send(&msg.message_id, command, message).await?;
 
     Ok(())
 }
-
-async fn handle_custom_reply(msg_id: &MsgId, client: &Replier, res: Result<String>) -> Result<()> {
-    match res {
-        Ok(content) => handle_string_reply(msg_id, client, content).await,
-        Err(e) => {
-            error!(error = ?e, "failed finding custom command");
-            Ok(())
-        }
-    }
-}
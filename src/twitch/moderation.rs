@@ -0,0 +1,40 @@
+//! Moderation actions that the bot takes on its own, like timing out users that spam commands.
+
+use tracing::{error, info};
+use twitch_api::eventsub::channel::ChannelChatMessageV1Payload;
+
+use super::eventsub::Replier;
+
+pub struct Moderation {
+    replier: Replier,
+}
+
+impl Moderation {
+    pub fn new(replier: Replier) -> Self {
+        Self { replier }
+    }
+
+    /// Time out the author of the message for repeating a command too often, and create the reply
+    /// for the chat.
+    pub async fn timeout_spammer(
+        &self,
+        msg: &ChannelChatMessageV1Payload,
+        duration: u32,
+    ) -> Option<String> {
+        info!(user = %msg.chatter_user_login, "timing out spammer");
+
+        if let Err(e) = self
+            .replier
+            .timeout(&msg.chatter_user_id, duration, "repeated the same command")
+            .await
+        {
+            error!(error = ?e, "failed timing out spammer");
+            return None;
+        }
+
+        Some(format!(
+            "{}, please stop repeating the same command. Take a break for {duration}s.",
+            msg.chatter_user_name
+        ))
+    }
+}
@@ -0,0 +1,188 @@
+//! The `!timeout me` roulette, where users can take a 1-in-6 chance of being timed out for a short
+//! time. Moderators and the broadcaster can't play, as a timeout would remove their role.
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
+
+use rand::Rng;
+use tokio::time::Instant;
+use tracing::{error, info};
+use twitch_api::{eventsub::channel::ChannelChatMessageV1Payload, types::UserId};
+
+use super::eventsub::Replier;
+use crate::settings::Roulette as RouletteSettings;
+
+/// Amount of chambers in the revolver, of which only one is loaded.
+const CHAMBERS: u32 = 6;
+
+/// Result of a single round of the roulette.
+#[derive(Debug, Eq, PartialEq)]
+enum Outcome {
+    /// The user is a moderator or the broadcaster, and not allowed to play.
+    Protected,
+    /// The user played recently and has to wait for the remaining time.
+    Cooldown(Duration),
+    /// The chamber was empty.
+    Survived,
+    /// The chamber was loaded, and the user is timed out.
+    Hit,
+}
+
+pub struct Roulette {
+    config: RouletteSettings,
+    replier: Replier,
+    cooldowns: Cooldowns,
+}
+
+impl Roulette {
+    pub fn new(config: RouletteSettings, replier: Replier) -> Self {
+        Self {
+            cooldowns: Cooldowns::new(Duration::from_secs(config.cooldown)),
+            config,
+            replier,
+        }
+    }
+
+    /// Let the author of the message play a round, time them out if they lose, and create the
+    /// reply for the chat. Protected users, like moderators, are never timed out.
+    pub async fn play(&self, msg: &ChannelChatMessageV1Payload, protected: bool) -> String {
+        let outcome = spin(
+            &self.cooldowns,
+            &msg.chatter_user_id,
+            protected,
+            Instant::now(),
+            || rand::thread_rng().gen_range(0..CHAMBERS) == 0,
+        );
+        let name = &msg.chatter_user_name;
+
+        match outcome {
+            Outcome::Protected => format!("{name}, moderators are immune to the revolver."),
+            Outcome::Cooldown(left) => format!(
+                "{name}, the revolver is still cooling down. Try again in {}s.",
+                left.as_secs().max(1)
+            ),
+            Outcome::Survived => format!("*click* {name} survived, lucky you!"),
+            Outcome::Hit => {
+                info!(user = %msg.chatter_user_login, "timing out roulette loser");
+
+                if let Err(e) = self
+                    .replier
+                    .timeout(
+                        &msg.chatter_user_id,
+                        self.config.timeout,
+                        "lost the !timeout me roulette",
+                    )
+                    .await
+                {
+                    error!(error = ?e, "failed timing out user");
+                    return format!("*BANG* ...but the bullet was a dud. {name} got away.");
+                }
+
+                format!(
+                    "*BANG* {name} is taking a break for {}s.",
+                    self.config.timeout
+                )
+            }
+        }
+    }
+}
+
+/// Last time that each user played, to enforce a break between two rounds of the same user.
+struct Cooldowns {
+    duration: Duration,
+    last_played: Mutex<HashMap<UserId, Instant>>,
+}
+
+impl Cooldowns {
+    fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            last_played: Mutex::default(),
+        }
+    }
+
+    /// Start the cooldown for the user, or get the remaining time if it's still active.
+    fn start(&self, user: &UserId, now: Instant) -> Result<(), Duration> {
+        let mut last_played = self
+            .last_played
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        last_played.retain(|_, played| now.duration_since(*played) < self.duration);
+
+        if let Some(played) = last_played.get(user) {
+            return Err(self.duration.saturating_sub(now.duration_since(*played)));
+        }
+
+        last_played.insert(user.clone(), now);
+        Ok(())
+    }
+}
+
+/// Decide the outcome of a round for the user, with `fire` rolling whether the current chamber is
+/// loaded. Only rounds that are actually played start the cooldown.
+fn spin(
+    cooldowns: &Cooldowns,
+    user: &UserId,
+    protected: bool,
+    now: Instant,
+    fire: impl FnOnce() -> bool,
+) -> Outcome {
+    if protected {
+        return Outcome::Protected;
+    }
+
+    if let Err(left) = cooldowns.start(user, now) {
+        return Outcome::Cooldown(left);
+    }
+
+    if fire() {
+        Outcome::Hit
+    } else {
+        Outcome::Survived
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn never_hits_protected() {
+        let cooldowns = Cooldowns::new(Duration::from_mins(1));
+        let user = UserId::from("1");
+
+        let outcome = spin(&cooldowns, &user, true, Instant::now(), || true);
+        assert_eq!(Outcome::Protected, outcome);
+    }
+
+    #[test]
+    fn enforces_cooldown() {
+        let cooldowns = Cooldowns::new(Duration::from_mins(1));
+        let (first, second) = (UserId::from("1"), UserId::from("2"));
+        let now = Instant::now();
+
+        assert_eq!(Outcome::Hit, spin(&cooldowns, &first, false, now, || true));
+        assert_eq!(
+            Outcome::Survived,
+            spin(&cooldowns, &second, false, now, || false)
+        );
+
+        let later = now + Duration::from_secs(20);
+        assert_eq!(
+            Outcome::Cooldown(Duration::from_secs(40)),
+            spin(&cooldowns, &first, false, later, || true)
+        );
+
+        let after = now + Duration::from_mins(1);
+        assert_eq!(
+            Outcome::Survived,
+            spin(&cooldowns, &first, false, after, || false)
+        );
+    }
+}
@@ -0,0 +1,78 @@
+//! Storage of refreshed tokens in the database, encrypted with the configured key, so a restart
+//! continues with the latest tokens instead of the ones from the config.
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use twitch_api::twitch_oauth2::UserToken;
+
+use crate::{crypto::Key, settings::Twitch as TwitchSettings, state::State};
+
+/// Name of the secret in the database.
+const SECRET: &str = "twitch_tokens";
+
+/// Tokens of the bot account, with the refresh token from the config they descend from. New
+/// tokens in the config take precedence over stored ones again.
+#[derive(Serialize, Deserialize)]
+struct Stored {
+    origin: String,
+    access_token: String,
+    refresh_token: String,
+}
+
+/// Encrypted storage of the Twitch tokens.
+#[derive(Clone)]
+pub struct TokenStore {
+    state: State,
+    key: Key,
+    origin: String,
+}
+
+impl TokenStore {
+    /// Create a store for the tokens that descend from the ones in the config.
+    #[must_use]
+    pub fn new(state: State, key: Key, config: &TwitchSettings) -> Self {
+        Self {
+            state,
+            key,
+            origin: config.refresh_token.clone(),
+        }
+    }
+
+    /// Access and refresh token, if they were refreshed since the config was last updated.
+    #[must_use]
+    pub fn load(&self) -> Option<(String, String)> {
+        let stored = self
+            .state
+            .secret(&self.key, SECRET)
+            .and_then(|value| {
+                value
+                    .map(|value| serde_json::from_str::<Stored>(&value))
+                    .transpose()
+                    .context("invalid stored tokens")
+            })
+            .inspect_err(|e| warn!(error = ?e, "failed loading stored tokens, using the config"))
+            .ok()??;
+
+        (stored.origin == self.origin).then_some((stored.access_token, stored.refresh_token))
+    }
+
+    /// Keep the current tokens, logging any failure as the bot can continue without them.
+    pub fn save(&self, token: &UserToken) {
+        let Some(refresh_token) = &token.refresh_token else {
+            return;
+        };
+
+        let result = serde_json::to_string(&Stored {
+            origin: self.origin.clone(),
+            access_token: token.access_token.secret().to_owned(),
+            refresh_token: refresh_token.secret().to_owned(),
+        })
+        .map_err(Into::into)
+        .and_then(|value| self.state.set_secret(&self.key, SECRET, &value));
+
+        if let Err(e) = result {
+            warn!(error = ?e, "failed saving refreshed tokens");
+        }
+    }
+}
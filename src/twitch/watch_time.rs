@@ -0,0 +1,56 @@
+//! Regular samples of the users in the chat, that add up to their watch time.
+//!
+//! Whether the stream is live is decided by the handler, so the samples are taken all the time.
+
+use std::time::Duration;
+
+use tokio::{
+    select,
+    task::JoinHandle,
+    time::{self, Instant, MissedTickBehavior},
+};
+use tokio_shutdown::Shutdown;
+use tracing::{debug, error};
+
+use super::eventsub::Replier;
+use crate::{
+    api::{Event, Events},
+    settings::WatchTime as WatchTimeSettings,
+};
+
+/// Start sampling the chat in the interval of the settings, until shutdown.
+pub fn spawn(
+    replier: Replier,
+    settings: &WatchTimeSettings,
+    events: Events,
+    shutdown: Shutdown,
+) -> JoinHandle<()> {
+    let period = Duration::from_secs(settings.interval);
+
+    tokio::spawn(async move {
+        // Nobody watched for a whole interval yet, so skip the tick that fires right away.
+        let mut samples = time::interval_at(Instant::now() + period, period);
+        samples.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            select! {
+                () = shutdown.handle() => break,
+                _ = samples.tick() => {}
+            }
+
+            match replier.chatters().await {
+                Ok(viewers) => {
+                    debug!(count = viewers.len(), "sampled chatters");
+                    let event = Event::Watching {
+                        viewers,
+                        duration: period,
+                    };
+                    if events.send(event).await.is_err() {
+                        break;
+                    }
+                }
+                Err(e) => error!(error = ?e, "failed sampling chatters"),
+            }
+        }
+    })
+}
@@ -0,0 +1,291 @@
+//! Outbound webhook notifications, that inform external services about events in the bot.
+//!
+//! Each event is sent as JSON payload in a `POST` request to all configured webhooks that are
+//! interested in it. If a secret is configured, the payload is signed with HMAC-SHA256 and the
+//! signature is sent in the `X-Togglebot-Signature` header as `sha256=<hex>`.
+
+use std::{fmt::Write, sync::Arc, time::Duration};
+
+use anyhow::{ensure, Result};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use time::OffsetDateTime;
+use tracing::{debug, warn};
+
+use crate::{
+    api::{
+        request::{self, Request},
+        Source, StreamInfo,
+    },
+    settings::Webhook,
+};
+
+/// Name of the header that carries the payload signature.
+const SIGNATURE_HEADER: &str = "X-Togglebot-Signature";
+/// Maximum attempts to deliver a single event to a webhook.
+const MAX_ATTEMPTS: u32 = 3;
+/// Time that a single delivery may take, before it counts as failed attempt.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Dispatcher that delivers events to the configured webhooks in the background.
+#[derive(Clone)]
+pub struct Webhooks {
+    client: reqwest::Client,
+    hooks: Arc<[Webhook]>,
+}
+
+impl Webhooks {
+    /// Create a new dispatcher for the given webhooks.
+    pub fn new(hooks: Vec<Webhook>) -> Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::builder().timeout(TIMEOUT).build()?,
+            hooks: hooks.into(),
+        })
+    }
+
+    /// Send the event to all interested webhooks. Delivery happens in the background, including
+    /// retries with an increasing backoff.
+    pub fn send(&self, event: Event) {
+        let hooks = self
+            .hooks
+            .iter()
+            .filter(|hook| hook.events.is_empty() || hook.events.contains(&event.kind()))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if hooks.is_empty() {
+            return;
+        }
+
+        let payload = match serde_json::to_vec(&Payload {
+            timestamp: OffsetDateTime::now_utc(),
+            event,
+        }) {
+            Ok(payload) => Arc::<[u8]>::from(payload),
+            Err(e) => {
+                warn!(error = ?e, "failed serializing webhook payload");
+                return;
+            }
+        };
+
+        for hook in hooks {
+            let client = self.client.clone();
+            let payload = Arc::clone(&payload);
+
+            tokio::spawn(async move {
+                let mut delay = Duration::from_secs(1);
+
+                for attempt in 1..=MAX_ATTEMPTS {
+                    match deliver(&client, &hook, &payload).await {
+                        Ok(()) => {
+                            debug!(url = hook.url, "delivered webhook");
+                            return;
+                        }
+                        Err(e) => {
+                            warn!(error = ?e, url = hook.url, attempt, "failed delivering webhook");
+                        }
+                    }
+
+                    if attempt < MAX_ATTEMPTS {
+                        tokio::time::sleep(delay).await;
+                        delay *= 2;
+                    }
+                }
+            });
+        }
+    }
+}
+
+async fn deliver(client: &reqwest::Client, hook: &Webhook, payload: &[u8]) -> Result<()> {
+    let mut req = client
+        .post(&hook.url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(payload.to_vec());
+
+    if let Some(secret) = &hook.secret {
+        req = req.header(SIGNATURE_HEADER, sign(secret, payload));
+    }
+
+    let resp = req.send().await?;
+    ensure!(
+        resp.status().is_success(),
+        "unexpected status code {}",
+        resp.status()
+    );
+
+    Ok(())
+}
+
+/// Create the signature header value for the given payload.
+fn sign(secret: &str, payload: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(payload);
+
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .fold(String::from("sha256="), |mut sig, b| {
+            write!(sig, "{b:02x}").ok();
+            sig
+        })
+}
+
+#[derive(Serialize)]
+struct Payload {
+    #[serde(with = "time::serde::rfc3339")]
+    timestamp: OffsetDateTime,
+    #[serde(flatten)]
+    event: Event,
+}
+
+/// Kinds of events that webhooks can subscribe to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// A user, admin or owner command was executed.
+    CommandExecuted,
+    /// A custom command was added, changed or removed.
+    CustomCommandChanged,
+    /// The stream went online.
+    StreamOnline,
+    /// The stream went offline.
+    StreamOffline,
+}
+
+/// An event that is sent to the webhooks.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum Event {
+    /// A command was executed.
+    CommandExecuted {
+        /// Service the command came from.
+        source: Source,
+        /// Name of the command, without the `!` prefix.
+        command: String,
+    },
+    /// A custom command was added, changed or removed.
+    CustomCommandChanged {
+        /// Service the change applies to, or all services if missing.
+        source: Option<Source>,
+        /// Name of the custom command.
+        name: String,
+        /// Whether the command was removed (or added/changed otherwise).
+        removed: bool,
+    },
+    /// The stream went online.
+    StreamOnline {
+        /// Title of the stream.
+        title: String,
+        /// Category (game) of the stream.
+        category: String,
+    },
+    /// The stream went offline.
+    StreamOffline,
+}
+
+impl Event {
+    fn kind(&self) -> EventKind {
+        match self {
+            Self::CommandExecuted { .. } => EventKind::CommandExecuted,
+            Self::CustomCommandChanged { .. } => EventKind::CustomCommandChanged,
+            Self::StreamOnline { .. } => EventKind::StreamOnline,
+            Self::StreamOffline => EventKind::StreamOffline,
+        }
+    }
+
+    /// Create the event that describes a request.
+    #[must_use]
+    pub fn from_request(source: Source, request: &Request) -> Self {
+        match request {
+            Request::Admin(request::Admin::CustomCommands(request::CustomCommands::Add {
+                source,
+                name,
+                ..
+            })) => Self::CustomCommandChanged {
+                source: *source,
+                name: name.clone(),
+                removed: false,
+            },
+            Request::Admin(request::Admin::CustomCommands(request::CustomCommands::Remove {
+                source,
+                name,
+            })) => Self::CustomCommandChanged {
+                source: *source,
+                name: name.clone(),
+                removed: true,
+            },
+            _ => Self::CommandExecuted {
+                source,
+                command: request.name().to_owned(),
+            },
+        }
+    }
+
+    /// Create the event for a started stream.
+    #[must_use]
+    pub fn stream_online(info: &StreamInfo) -> Self {
+        Self::StreamOnline {
+            title: info.title.clone(),
+            category: info.category.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn signature() {
+        assert_eq!(
+            "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8",
+            sign("key", b"The quick brown fox jumps over the lazy dog")
+        );
+    }
+
+    #[test]
+    fn payload() {
+        let payload = serde_json::to_value(Payload {
+            timestamp: OffsetDateTime::UNIX_EPOCH,
+            event: Event::CommandExecuted {
+                source: Source::Twitch,
+                command: "help".to_owned(),
+            },
+        })
+        .unwrap();
+
+        assert_eq!(
+            serde_json::json!({
+                "timestamp": "1970-01-01T00:00:00Z",
+                "event": "command_executed",
+                "data": {
+                    "source": "twitch",
+                    "command": "help",
+                },
+            }),
+            payload
+        );
+    }
+
+    #[test]
+    fn custom_command_change() {
+        let event = Event::from_request(
+            Source::Discord,
+            &Request::Admin(request::Admin::CustomCommands(
+                request::CustomCommands::Remove {
+                    source: None,
+                    name: "hi".to_owned(),
+                },
+            )),
+        );
+
+        assert!(matches!(
+            event,
+            Event::CustomCommandChanged { removed: true, .. }
+        ));
+    }
+}
@@ -0,0 +1,211 @@
+//! Minimal client for the YouTube Data API, covering only the endpoints that are needed to find
+//! the current live chat of a channel, read it and reply in it.
+
+use std::{
+    collections::HashSet,
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::json;
+
+const BASE_URL: &str = "https://www.googleapis.com/youtube/v3";
+
+pub struct Client {
+    http: reqwest::Client,
+    api_key: String,
+    access_token: Option<String>,
+    /// IDs of messages that the bot posted itself, so they can be skipped when they show up in
+    /// the chat.
+    sent: Mutex<HashSet<String>>,
+}
+
+impl Client {
+    pub fn new(api_key: String, access_token: Option<String>) -> Result<Self> {
+        Ok(Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()?,
+            api_key,
+            access_token,
+            sent: Mutex::default(),
+        })
+    }
+
+    async fn get<T: DeserializeOwned>(&self, path: &str, query: &[(&str, &str)]) -> Result<T> {
+        self.http
+            .get(format!("{BASE_URL}/{path}"))
+            .query(query)
+            .query(&[("key", &self.api_key)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .map_err(Into::into)
+    }
+
+    /// Find the live chat of the channel's current live stream, if it's live right now.
+    pub async fn live_chat_id(&self, channel_id: &str) -> Result<Option<String>> {
+        let search = self
+            .get::<List<SearchResult>>(
+                "search",
+                &[
+                    ("part", "id"),
+                    ("channelId", channel_id),
+                    ("eventType", "live"),
+                    ("type", "video"),
+                ],
+            )
+            .await
+            .context("failed searching for live stream")?;
+
+        let Some(video_id) = search.items.into_iter().find_map(|item| item.id.video_id) else {
+            return Ok(None);
+        };
+
+        let videos = self
+            .get::<List<Video>>(
+                "videos",
+                &[("part", "liveStreamingDetails"), ("id", &video_id)],
+            )
+            .await
+            .context("failed getting live stream details")?;
+
+        Ok(videos
+            .items
+            .into_iter()
+            .find_map(|video| video.live_streaming_details?.active_live_chat_id))
+    }
+
+    /// Get the next page of chat messages, starting after the given page token.
+    pub async fn messages(&self, chat_id: &str, page_token: Option<&str>) -> Result<Messages> {
+        let mut query = vec![("liveChatId", chat_id), ("part", "snippet,authorDetails")];
+        if let Some(page_token) = page_token {
+            query.push(("pageToken", page_token));
+        }
+
+        self.get("liveChat/messages", &query).await
+    }
+
+    /// Post a message in the live chat.
+    pub async fn send(&self, chat_id: &str, text: &str) -> Result<()> {
+        #[derive(Deserialize)]
+        struct Inserted {
+            id: String,
+        }
+
+        let access_token = self
+            .access_token
+            .as_ref()
+            .context("posting messages requires an access token")?;
+
+        #[cfg(feature = "chaos")]
+        crate::chaos::send()?;
+
+        let inserted = self
+            .http
+            .post(format!("{BASE_URL}/liveChat/messages"))
+            .query(&[("part", "snippet")])
+            .bearer_auth(access_token)
+            .json(&json!({
+                "snippet": {
+                    "liveChatId": chat_id,
+                    "type": "textMessageEvent",
+                    "textMessageDetails": { "messageText": text },
+                }
+            }))
+            .send()
+            .await?
+            .error_for_status()?
+            .json::<Inserted>()
+            .await?;
+
+        self.sent
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(inserted.id);
+
+        Ok(())
+    }
+
+    /// Whether the message was posted by the bot itself.
+    pub fn is_own(&self, message_id: &str) -> bool {
+        self.sent
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(message_id)
+    }
+}
+
+#[derive(Deserialize)]
+struct List<T> {
+    #[serde(default = "Vec::new")]
+    items: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct SearchResult {
+    id: SearchResultId,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SearchResultId {
+    video_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Video {
+    live_streaming_details: Option<LiveStreamingDetails>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LiveStreamingDetails {
+    active_live_chat_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Messages {
+    #[serde(default)]
+    pub items: Vec<ChatMessage>,
+    pub next_page_token: Option<String>,
+    pub polling_interval_millis: u64,
+    /// Set once the live chat ended.
+    pub offline_at: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatMessage {
+    pub id: String,
+    pub snippet: Snippet,
+    pub author_details: AuthorDetails,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub display_message: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthorDetails {
+    pub channel_id: String,
+    #[serde(default)]
+    pub display_name: String,
+    #[serde(default)]
+    pub is_chat_owner: bool,
+    #[serde(default)]
+    pub is_chat_moderator: bool,
+    #[serde(default)]
+    pub is_chat_sponsor: bool,
+}
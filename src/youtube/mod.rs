@@ -0,0 +1,342 @@
+//! YouTube service connector that allows to receive commands from the live chat of a channel.
+//!
+//! The YouTube Data API doesn't offer any push notifications, so the connector polls for the
+//! channel's live stream and afterwards its chat messages. Finding the live stream is rather
+//! expensive in terms of API quota and therefore only done every few minutes.
+
+use std::{
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Context, Result};
+use futures_util::future::{self, BoxFuture};
+use tokio::{select, task::JoinHandle, time};
+use tracing::{error, info, info_span, instrument, warn, Instrument, Span};
+
+use self::client::{AuthorDetails, ChatMessage, Client};
+use crate::{
+    api::{response::Response, Author, AuthorId, Message, Post, Queue, Source},
+    connector::{self, Connector, Context as ConnectorContext},
+    health::Health,
+    render,
+    settings::YouTube as YouTubeSettings,
+    textparse,
+    timing::Timings,
+};
+
+mod client;
+
+/// Interval to check whether the channel went live.
+const OFFLINE_INTERVAL: Duration = Duration::from_mins(15);
+/// Minimum interval between two chat polls, even if YouTube suggests a shorter one.
+const MIN_POLL_INTERVAL: Duration = Duration::from_secs(5);
+/// Delay before retrying a failed API request.
+const RETRY_DELAY: Duration = Duration::from_mins(1);
+/// Maximum length of a single chat message in YouTube.
+const MAX_MESSAGE_LEN: usize = 200;
+
+/// Connector for YouTube, that follows the live chat of a channel while it's streaming.
+pub struct YouTube {
+    config: YouTubeSettings,
+    client: OnceLock<Arc<Client>>,
+    chat_id: Arc<Mutex<Option<String>>>,
+    tasks: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl YouTube {
+    /// Create a new, not yet started, YouTube connector.
+    #[must_use]
+    pub fn new(config: YouTubeSettings) -> Self {
+        Self {
+            config,
+            client: OnceLock::new(),
+            chat_id: Arc::default(),
+            tasks: Mutex::default(),
+        }
+    }
+
+    /// Start watching for the channel's live stream in a background task, and follow its chat
+    /// while it's live.
+    ///
+    /// Only messages that arrive after the connector started following a chat are processed.
+    /// Every successful API response is reported as heartbeat to the health state.
+    fn run(&self, ctx: ConnectorContext) -> Result<()> {
+        let ConnectorContext {
+            queue,
+            health,
+            timings,
+            shutdown,
+            ..
+        } = ctx;
+
+        if self.config.access_token.is_none() {
+            warn!("no youtube access token configured, only listening to the chat");
+        }
+
+        let client = Arc::new(Client::new(
+            self.config.api_key.clone(),
+            self.config.access_token.clone(),
+        )?);
+
+        self.client
+            .set(Arc::clone(&client))
+            .map_err(|_| anyhow!("youtube connector already started"))?;
+
+        let channel_id = self.config.channel_id.clone();
+        let chat_id = Arc::clone(&self.chat_id);
+
+        let task = tokio::spawn(async move {
+            select! {
+                () = shutdown.handle() => {}
+                () = watch(&client, &channel_id, &chat_id, &queue, &health, &timings) => {}
+            }
+        });
+
+        self.tasks
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(task);
+
+        info!("youtube connection ready, watching for live streams");
+
+        Ok(())
+    }
+
+    /// Post the message in the current live chat.
+    async fn post(&self, post: Post) -> Result<()> {
+        let client = self.client.get().context("youtube connector not started")?;
+        let chat_id = self
+            .chat_id
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+            .context("channel is currently not live")?;
+
+        client.send(&chat_id, &truncate(post.content)).await
+    }
+}
+
+impl Connector for YouTube {
+    fn source(&self) -> Source {
+        Source::YouTube
+    }
+
+    fn start(&self, ctx: ConnectorContext) -> BoxFuture<'_, Result<()>> {
+        Box::pin(future::ready(self.run(ctx)))
+    }
+
+    fn send_reply(&self, post: Post) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.post(post))
+    }
+
+    fn shutdown(&self) -> BoxFuture<'_, ()> {
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap_or_else(PoisonError::into_inner));
+
+        Box::pin(async move {
+            for task in tasks {
+                task.abort();
+            }
+            info!("youtube connection shutting down");
+        })
+    }
+}
+
+/// Wait for the channel to go live, and follow each live chat until it ends.
+async fn watch(
+    client: &Client,
+    channel_id: &str,
+    current: &Mutex<Option<String>>,
+    queue: &Queue,
+    health: &Health,
+    timings: &Timings,
+) {
+    loop {
+        match client.live_chat_id(channel_id).await {
+            Ok(Some(chat_id)) => {
+                health.beat(Source::YouTube);
+                info!(%chat_id, "channel is live, following chat");
+
+                *current.lock().unwrap_or_else(PoisonError::into_inner) = Some(chat_id.clone());
+                follow(client, &chat_id, queue, health, timings).await;
+                *current.lock().unwrap_or_else(PoisonError::into_inner) = None;
+
+                info!(%chat_id, "stopped following chat");
+            }
+            Ok(None) => {
+                health.beat(Source::YouTube);
+                time::sleep(OFFLINE_INTERVAL).await;
+            }
+            Err(e) => {
+                error!(error = ?e, "failed checking for youtube live stream");
+                time::sleep(RETRY_DELAY).await;
+            }
+        }
+    }
+}
+
+/// Poll the live chat for new messages and handle them, until the chat ends.
+async fn follow(client: &Client, chat_id: &str, queue: &Queue, health: &Health, timings: &Timings) {
+    let mut page_token = None;
+    let mut initial = true;
+
+    loop {
+        let messages = match client.messages(chat_id, page_token.as_deref()).await {
+            Ok(messages) => messages,
+            Err(e) => {
+                warn!(error = ?e, "failed polling youtube chat, assuming it ended");
+                return;
+            }
+        };
+
+        health.beat(Source::YouTube);
+
+        // Skip over any history, so old commands aren't answered again.
+        if !initial {
+            for message in &messages.items {
+                let Some(text) = text(message).filter(|_| !client.is_own(&message.id)) else {
+                    continue;
+                };
+
+                if let Err(e) = handle_message(queue, timings, client, chat_id, message, text).await
+                {
+                    error!(error = ?e, "failed handling youtube message");
+                }
+            }
+        }
+
+        if messages.offline_at.is_some() {
+            return;
+        }
+
+        initial = false;
+        page_token = messages.next_page_token;
+
+        time::sleep(Duration::from_millis(messages.polling_interval_millis).max(MIN_POLL_INTERVAL))
+            .await;
+    }
+}
+
+/// Get the text of a regular chat message, ignoring any other events like super chats.
+fn text(message: &ChatMessage) -> Option<&str> {
+    (message.snippet.kind == "textMessageEvent")
+        .then_some(message.snippet.display_message.as_deref())
+        .flatten()
+}
+
+/// Author of a chat message, where the channel owner counts as broadcaster and members as
+/// subscribers.
+fn author(details: &AuthorDetails) -> Author {
+    let roles = [
+        (details.is_chat_owner, "broadcaster"),
+        (details.is_chat_moderator, "moderator"),
+    ];
+
+    Author {
+        id: AuthorId::YouTube(details.channel_id.clone()),
+        name: details.display_name.clone(),
+        roles: roles
+            .into_iter()
+            .filter(|(active, _)| *active)
+            .map(|(_, role)| role.to_owned())
+            .collect(),
+        subscriber: details.is_chat_sponsor,
+    }
+}
+
+#[instrument(skip_all, name = "youtube message", fields(source = %Source::YouTube))]
+async fn handle_message(
+    queue: &Queue,
+    timings: &Timings,
+    client: &Client,
+    chat_id: &str,
+    msg: &ChatMessage,
+    text: &str,
+) -> Result<()> {
+    let received = Instant::now();
+    let Ok(Some(content)) = textparse::parse(text, Source::YouTube, None) else {
+        return Ok(());
+    };
+
+    let message = Message {
+        span: Span::current(),
+        source: Source::YouTube,
+        content,
+        author: author(&msg.author_details),
+        mention: None,
+        channel: None,
+    };
+
+    let command = message.content.metric_name().to_owned();
+    if let Some(resp) = connector::dispatch(queue, message).await {
+        let reply = async {
+            match resp {
+                Response::User(user_resp) => match render::youtube::user(user_resp) {
+                    Some(text) => client.send(chat_id, &truncate(text)).await,
+                    None => Ok(()),
+                },
+                Response::Denied(required) => client.send(chat_id, &render::denied(required)).await,
+                Response::Admin(_) | Response::Owner(_) | Response::Confirm(_) => Ok(()),
+            }
+        }
+        .instrument(info_span!("reply"));
+
+        timings.reply(&command, received, reply).await?;
+    }
+
+    Ok(())
+}
+
+/// Cut the message down to the maximum message length, if needed.
+fn truncate(mut message: String) -> String {
+    if message.chars().count() > MAX_MESSAGE_LEN {
+        message = message.chars().take(MAX_MESSAGE_LEN - 1).collect();
+        message.push('…');
+    }
+
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use similar_asserts::assert_eq;
+
+    use super::{client::Messages, *};
+
+    #[test]
+    fn chat_messages() {
+        let messages = serde_json::from_value::<Messages>(serde_json::json!({
+            "nextPageToken": "abc",
+            "pollingIntervalMillis": 2000,
+            "items": [
+                {
+                    "id": "1",
+                    "snippet": { "type": "textMessageEvent", "displayMessage": "!help" },
+                    "authorDetails": { "channelId": "UC1" }
+                },
+                {
+                    "id": "2",
+                    "snippet": { "type": "superChatEvent", "displayMessage": "$5.00" },
+                    "authorDetails": { "channelId": "UC2" }
+                }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(Some("abc"), messages.next_page_token.as_deref());
+        assert!(messages.offline_at.is_none());
+        assert_eq!(
+            vec![Some("!help"), None],
+            messages.items.iter().map(text).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn truncate_long() {
+        assert_eq!("short", truncate("short".to_owned()));
+
+        let message = truncate("ä".repeat(300));
+        assert_eq!(MAX_MESSAGE_LEN, message.chars().count());
+        assert!(message.ends_with('…'));
+    }
+}
@@ -0,0 +1,195 @@
+use std::{num::NonZero, sync::Arc};
+
+use similar_asserts::assert_eq;
+use time::macros::datetime;
+use togglebot::{
+    api::{AdminId, AuthorId, Event, Post, Source, StreamInfo},
+    clock::MockClock,
+    connector::Connectors,
+    statistics::BuiltinCommand,
+    testing::{Harness, MockConnector},
+};
+
+fn viewer() -> AuthorId {
+    AuthorId::Twitch("viewer".to_owned())
+}
+
+fn discord_user(id: u64) -> AuthorId {
+    AuthorId::Discord(NonZero::new(id).unwrap())
+}
+
+#[tokio::test]
+async fn help_from_twitch() {
+    let harness = Harness::new().unwrap();
+
+    let reply = harness
+        .send(Source::Twitch, viewer(), "!help")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(reply.starts_with("Thanks for asking"), "{reply}");
+}
+
+#[tokio::test]
+async fn ignore_non_commands() {
+    let harness = Harness::new().unwrap();
+
+    assert_eq!(
+        None,
+        harness
+            .send(Source::Twitch, viewer(), "hello there")
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        None,
+        harness
+            .send(Source::Twitch, viewer(), "!nope")
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn custom_command_across_services() {
+    let harness = Harness::new().unwrap();
+
+    // Only admins may add commands.
+    assert_eq!(
+        Some(
+            "Sorry, you don't have permission to use this command, it's only available to admins"
+                .to_owned()
+        ),
+        harness
+            .send(
+                Source::Discord,
+                discord_user(5),
+                "!custom_commands add all hi hello"
+            )
+            .await
+            .unwrap()
+    );
+
+    harness.state().add_admin(AdminId::new(5).unwrap()).unwrap();
+    assert_eq!(
+        Some("custom commands updated".to_owned()),
+        harness
+            .send(
+                Source::Discord,
+                discord_user(5),
+                "!custom_commands add all hi hello"
+            )
+            .await
+            .unwrap()
+    );
+
+    assert_eq!(
+        Some("hello".to_owned()),
+        harness.send(Source::Twitch, viewer(), "!hi").await.unwrap()
+    );
+}
+
+#[tokio::test]
+async fn today_follows_clock() {
+    let clock = Arc::new(MockClock::new(datetime!(2025-12-31 23:59 UTC)));
+    let harness = Harness::with_clock(clock.clone()).unwrap();
+
+    let today = || async {
+        harness
+            .send(Source::Twitch, viewer(), "!today")
+            .await
+            .unwrap()
+            .unwrap()
+    };
+
+    let reply = today().await;
+    assert!(
+        reply.starts_with("Today is Wednesday, December the 31st"),
+        "{reply}"
+    );
+
+    clock.advance(time::Duration::minutes(1));
+    let reply = today().await;
+    assert!(
+        reply.starts_with("Today is Thursday, January the 1st"),
+        "{reply}"
+    );
+}
+
+#[tokio::test]
+async fn console_is_owner() {
+    let harness = Harness::new().unwrap();
+
+    let reply = harness
+        .send(Source::Console, AuthorId::Console, "!plugins list")
+        .await
+        .unwrap();
+    assert_eq!(Some("registered plugins:".to_owned()), reply);
+}
+
+#[tokio::test]
+async fn stream_statistics() {
+    let harness = Harness::new().unwrap();
+
+    harness.event(Event::StreamOnline(StreamInfo {
+        id: "1".to_owned(),
+        started_at: datetime!(2025-03-01 18:00 UTC),
+        title: "Rust".to_owned(),
+        category: "Software and Game Development".to_owned(),
+    }));
+    harness.state().tag_stream("rust").unwrap();
+
+    harness
+        .send(Source::Twitch, viewer(), "!help")
+        .await
+        .unwrap()
+        .unwrap();
+
+    let usage = harness.statistics().get_tag("rust").command_usage;
+    assert_eq!(1, usage.builtin.unwrap()[&BuiltinCommand::Help]);
+}
+
+#[tokio::test]
+async fn bots_keep_own_memory() {
+    let first = Harness::new().unwrap();
+    let second = Harness::new().unwrap();
+
+    let reply = first
+        .send(
+            Source::Console,
+            AuthorId::Console,
+            "!custom_commands remove all hi",
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(reply.contains("type !confirm"), "{reply}");
+
+    // The pending confirmation only exists in the bot that asked for it.
+    let reply = second
+        .send(Source::Console, AuthorId::Console, "!confirm")
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(reply.starts_with("there's nothing to confirm"), "{reply}");
+}
+
+#[tokio::test]
+async fn record_posts() {
+    let twitch = MockConnector::new(Source::Twitch);
+
+    let mut connectors = Connectors::default();
+    connectors.add(twitch.clone());
+    connectors
+        .send_reply(
+            Source::Twitch,
+            Post {
+                channel: None,
+                content: "we're live".to_owned(),
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(vec!["we're live".to_owned()], twitch.posts());
+}